@@ -0,0 +1,77 @@
+use anyhow::{anyhow, Result};
+use std::net::SocketAddr;
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+const DEFAULT_PORT: u16 = 7879;
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(45);
+
+// A release build of the backend with the testbot endpoints, owned by the benchmark run.
+pub struct Host {
+    child: Child,
+    pub base_url: String,
+}
+
+impl Host {
+    pub async fn start() -> Result<Self> {
+        let address = format!("127.0.0.1:{DEFAULT_PORT}");
+        log::info!("Starting hosting application at {}", address);
+        let child = Command::new("cargo")
+            .args([
+                "run",
+                "--release",
+                "--bin",
+                "nimble-photos",
+                "--features",
+                "testbot",
+            ])
+            .current_dir("..")
+            .env("Nimble_Photo_Url", &address)
+            .env("RUST_LOG", "off")
+            .spawn()?;
+
+        let host = Self {
+            child,
+            base_url: format!("http://{address}"),
+        };
+        if let Err(err) = wait_for(address.parse()?).await {
+            host.shutdown();
+            return Err(err);
+        }
+        Ok(host)
+    }
+
+    pub fn shutdown(mut self) {
+        #[cfg(unix)]
+        let _ = Command::new("kill")
+            .args(["-TERM", &self.child.id().to_string()])
+            .status();
+
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) | Err(_) = self.child.try_wait() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+        log::warn!("Host did not exit after termination signal; killing it");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+async fn wait_for(socket: SocketAddr) -> Result<()> {
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    loop {
+        match tokio::net::TcpStream::connect(socket).await {
+            Ok(_) => return Ok(()),
+            Err(err) if Instant::now() < deadline => {
+                log::debug!("waiting for host at {}: {}", socket, err);
+                sleep(Duration::from_millis(500)).await;
+            }
+            Err(err) => return Err(anyhow!("timed out waiting for host at {}: {}", socket, err)),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use tokio::task::{self, LocalSet};
+
+use nimble_web::testbot::TestBot;
+
+const PHOTOS_PAGE_SIZE: u32 = 50;
+const TIMELINE_PAGE_SIZE: u32 = 10;
+const THUMBNAIL_SAMPLE: u32 = 200;
+
+pub struct Target {
+    pub name: &'static str,
+    paths: Vec<String>,
+}
+
+impl Target {
+    pub async fn discover(bot: &mut TestBot) -> Result<Vec<Self>> {
+        let mut targets = vec![
+            Self {
+                name: "photos",
+                paths: vec![format!("/api/photos/1/{PHOTOS_PAGE_SIZE}")],
+            },
+            Self {
+                name: "timeline",
+                paths: vec![format!("/api/timeline/1/{TIMELINE_PAGE_SIZE}")],
+            },
+        ];
+
+        let hashes = Self::thumbnail_hashes(bot).await?;
+        if hashes.is_empty() {
+            log::warn!("No photos with hashes found; skipping the thumbnail endpoint");
+        } else {
+            targets.push(Self {
+                name: "thumbnail",
+                paths: hashes
+                    .iter()
+                    .map(|hash| format!("/api/photos/thumbnail/{hash}"))
+                    .collect(),
+            });
+        }
+        Ok(targets)
+    }
+
+    async fn thumbnail_hashes(bot: &mut TestBot) -> Result<Vec<String>> {
+        let response = bot
+            .get_auth(&format!("/api/photos/1/{THUMBNAIL_SAMPLE}"))
+            .await?;
+        if response.status != 200 {
+            return Err(anyhow!(
+                "listing photos returned status {}",
+                response.status
+            ));
+        }
+        let page: Value = response.json()?;
+        let hashes = page
+            .get("items")
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.get("hash").and_then(Value::as_str))
+                    .filter(|hash| !hash.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(hashes)
+    }
+}
+
+pub struct EndpointStats {
+    pub name: &'static str,
+    pub latencies: Vec<Duration>,
+    pub errors: usize,
+}
+
+impl EndpointStats {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            latencies: Vec::new(),
+            errors: 0,
+        }
+    }
+
+    pub fn requests(&self) -> usize {
+        self.latencies.len()
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        if self.latencies.is_empty() {
+            return 0.0;
+        }
+        self.errors as f64 / self.latencies.len() as f64
+    }
+
+    // Nearest-rank percentile; latencies must already be sorted.
+    pub fn percentile(&self, percent: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = ((percent / 100.0) * self.latencies.len() as f64).ceil() as usize;
+        self.latencies[rank.clamp(1, self.latencies.len()) - 1]
+    }
+
+    fn merge(&mut self, other: EndpointStats) {
+        self.latencies.extend(other.latencies);
+        self.errors += other.errors;
+    }
+}
+
+// TestBot is not Send, so workers share one thread and interleave on I/O.
+pub async fn run(
+    base_url: &str,
+    token: &str,
+    targets: Vec<Target>,
+    concurrency: usize,
+    duration: Duration,
+) -> Result<Vec<EndpointStats>> {
+    let targets = Rc::new(targets);
+    let deadline = Instant::now() + duration;
+    let local = LocalSet::new();
+    let workers: Vec<_> = (0..concurrency)
+        .map(|worker| {
+            let targets = targets.clone();
+            let base_url = base_url.to_string();
+            let token = token.to_string();
+            local.spawn_local(async move {
+                let mut bot = TestBot::connect(base_url).await?;
+                bot.context.access_token = Some(token);
+                drive(&mut bot, worker, &targets, deadline).await
+            })
+        })
+        .collect();
+    local.await;
+
+    let mut totals: Vec<EndpointStats> = targets
+        .iter()
+        .map(|target| EndpointStats::new(target.name))
+        .collect();
+    for worker in workers {
+        let stats = worker
+            .await
+            .map_err(|err| anyhow!("load worker panicked: {}", err))??;
+        for (total, stats) in totals.iter_mut().zip(stats) {
+            total.merge(stats);
+        }
+    }
+    for total in &mut totals {
+        total.latencies.sort();
+    }
+    Ok(totals)
+}
+
+async fn drive(
+    bot: &mut TestBot,
+    worker: usize,
+    targets: &[Target],
+    deadline: Instant,
+) -> Result<Vec<EndpointStats>> {
+    let mut stats: Vec<EndpointStats> = targets
+        .iter()
+        .map(|target| EndpointStats::new(target.name))
+        .collect();
+    let mut iteration = worker;
+    while Instant::now() < deadline {
+        let index = iteration % targets.len();
+        let target = &targets[index];
+        let path = &target.paths[(iteration / targets.len()) % target.paths.len()];
+
+        let started = Instant::now();
+        let ok = matches!(bot.get_auth(path).await, Ok(response) if response.status < 400);
+        stats[index].latencies.push(started.elapsed());
+        if !ok {
+            stats[index].errors += 1;
+        }
+
+        iteration += 1;
+        task::yield_now().await;
+    }
+    Ok(stats)
+}
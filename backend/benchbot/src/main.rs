@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use std::env;
+use std::time::Instant;
+
+use nimble_web::testbot::TestBot;
+
+mod host;
+mod load;
+mod options;
+mod report;
+mod seed;
+use host::Host;
+use load::Target;
+use options::BenchOptions;
+use seed::SeededLibrary;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    init_logging();
+    let options = BenchOptions::parse(env::args().skip(1))?;
+
+    let host = match &options.url {
+        Some(_) => None,
+        None => Some(Host::start().await?),
+    };
+    let base_url = options
+        .url
+        .clone()
+        .or_else(|| host.as_ref().map(|host| host.base_url.clone()))
+        .ok_or_else(|| anyhow!("no target url"))?;
+
+    let result = execute(&base_url, &options).await;
+    if let Some(host) = host {
+        host.shutdown();
+    }
+
+    let violations = result?;
+    if !violations.is_empty() {
+        for violation in &violations {
+            log::error!("{}", violation);
+        }
+        return Err(anyhow!("{} p95 threshold(s) exceeded", violations.len()));
+    }
+    Ok(())
+}
+
+async fn execute(base_url: &str, options: &BenchOptions) -> Result<Vec<String>> {
+    log::info!("Benchmarking {}", base_url);
+    let mut bot = TestBot::connect(base_url.to_string()).await?;
+    let token = seed::authenticate(&mut bot, options).await?;
+
+    let library = if options.photos > 0 {
+        Some(SeededLibrary::create(&mut bot, options.photos).await?)
+    } else {
+        None
+    };
+
+    let targets = Target::discover(&mut bot).await?;
+    log::info!(
+        "Driving {} endpoint(s) with {} worker(s) for {:?}",
+        targets.len(),
+        options.concurrency,
+        options.duration
+    );
+    let started = Instant::now();
+    let stats = load::run(
+        base_url,
+        &token,
+        targets,
+        options.concurrency,
+        options.duration,
+    )
+    .await;
+    let elapsed = started.elapsed();
+
+    if let Some(library) = &library {
+        if let Err(err) = library.remove(&mut bot).await {
+            log::warn!(
+                "Failed to remove seeded storage {}: {}",
+                library.storage_id,
+                err
+            );
+        }
+    }
+
+    let stats = stats?;
+    report::print(&stats, elapsed);
+    Ok(report::violations(&stats, options))
+}
+
+fn init_logging() {
+    let mut builder = env_logger::Builder::from_default_env();
+    builder
+        .filter(None, log::LevelFilter::Off)
+        .filter_module("benchbot", log::LevelFilter::Info);
+
+    let _ = builder.try_init();
+}
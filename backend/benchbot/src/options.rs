@@ -0,0 +1,90 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub const ALL_ENDPOINTS: &str = "all";
+
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub url: Option<String>,
+    pub photos: usize,
+    pub concurrency: usize,
+    pub duration: Duration,
+    pub email: Option<String>,
+    pub password: Option<String>,
+    pub max_p95: HashMap<String, Duration>,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            url: None,
+            photos: 200,
+            concurrency: 8,
+            duration: Duration::from_secs(30),
+            email: None,
+            password: None,
+            max_p95: HashMap::new(),
+        }
+    }
+}
+
+impl BenchOptions {
+    pub const USAGE: &'static str = "usage: benchbot [--url http://host:port] [--photos N] \
+        [--concurrency N] [--duration SECONDS] [--email ADMIN_EMAIL --password ADMIN_PASSWORD] \
+        [--max-p95 [ENDPOINT=]MILLISECONDS]...";
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow!("missing value for {}\n{}", flag, Self::USAGE))
+            };
+            match flag.as_str() {
+                "--url" => options.url = Some(value()?.trim_end_matches('/').to_string()),
+                "--photos" => options.photos = Self::number(&flag, &value()?)?,
+                "--concurrency" => options.concurrency = Self::number(&flag, &value()?)?.max(1),
+                "--duration" => {
+                    options.duration = Duration::from_secs(Self::number(&flag, &value()?)? as u64)
+                }
+                "--email" => options.email = Some(value()?),
+                "--password" => options.password = Some(value()?),
+                "--max-p95" => {
+                    let (endpoint, limit) = Self::threshold(&value()?)?;
+                    options.max_p95.insert(endpoint, limit);
+                }
+                "--help" | "-h" => return Err(anyhow!(Self::USAGE)),
+                _ => return Err(anyhow!("unknown argument '{}'\n{}", flag, Self::USAGE)),
+            }
+        }
+
+        if options.email.is_some() != options.password.is_some() {
+            return Err(anyhow!("--email and --password must be given together"));
+        }
+        Ok(options)
+    }
+
+    pub fn p95_limit(&self, endpoint: &str) -> Option<Duration> {
+        self.max_p95
+            .get(endpoint)
+            .or_else(|| self.max_p95.get(ALL_ENDPOINTS))
+            .copied()
+    }
+
+    fn number(flag: &str, value: &str) -> Result<usize> {
+        value
+            .parse()
+            .map_err(|_| anyhow!("{} expects a number, got '{}'", flag, value))
+    }
+
+    fn threshold(value: &str) -> Result<(String, Duration)> {
+        let (endpoint, millis) = match value.split_once('=') {
+            Some((endpoint, millis)) => (endpoint.trim().to_lowercase(), millis),
+            None => (ALL_ENDPOINTS.to_string(), value),
+        };
+        let millis = Self::number("--max-p95", millis.trim())?;
+        Ok((endpoint, Duration::from_millis(millis as u64)))
+    }
+}
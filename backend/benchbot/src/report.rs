@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use crate::load::EndpointStats;
+use crate::options::BenchOptions;
+
+pub fn print(stats: &[EndpointStats], elapsed: Duration) {
+    println!(
+        "{:<10} {:>9} {:>8} {:>10} {:>10} {:>10} {:>9}",
+        "endpoint", "requests", "req/s", "p50 ms", "p95 ms", "p99 ms", "errors"
+    );
+    for endpoint in stats {
+        println!(
+            "{:<10} {:>9} {:>8.1} {:>10.1} {:>10.1} {:>10.1} {:>8.2}%",
+            endpoint.name,
+            endpoint.requests(),
+            endpoint.requests() as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            millis(endpoint.percentile(50.0)),
+            millis(endpoint.percentile(95.0)),
+            millis(endpoint.percentile(99.0)),
+            endpoint.error_rate() * 100.0
+        );
+    }
+}
+
+pub fn violations(stats: &[EndpointStats], options: &BenchOptions) -> Vec<String> {
+    stats
+        .iter()
+        .filter_map(|endpoint| {
+            let limit = options.p95_limit(endpoint.name)?;
+            let p95 = endpoint.percentile(95.0);
+            (p95 > limit).then(|| {
+                format!(
+                    "{} p95 {:.1} ms exceeds {:.1} ms",
+                    endpoint.name,
+                    millis(p95),
+                    millis(limit)
+                )
+            })
+        })
+        .collect()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
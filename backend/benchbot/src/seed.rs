@@ -0,0 +1,177 @@
+use anyhow::{anyhow, Result};
+use image::{ImageFormat, Rgb, RgbImage};
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use uuid::Uuid;
+
+use nimble_photos::dtos::auth_dtos::{LoginRequest, LoginResponse, RegisterRequest};
+use nimble_photos::dtos::{FolderImportRequest, FolderImportResponse, ImportBatchStatus};
+use nimble_web::testbot::{AssertResponse, TestBot};
+
+use crate::options::BenchOptions;
+
+const IMPORT_FOLDER: &str = "bench";
+const IMAGE_SIZE: u32 = 32;
+const IMPORT_TIMEOUT: Duration = Duration::from_secs(600);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub async fn authenticate(bot: &mut TestBot, options: &BenchOptions) -> Result<String> {
+    let tokens = match (&options.email, &options.password) {
+        (Some(email), Some(password)) => login(bot, email, password).await?,
+        _ => promote_admin(bot).await?,
+    };
+    bot.context.access_token = Some(tokens.access_token.clone());
+    Ok(tokens.access_token)
+}
+
+async fn login(bot: &mut TestBot, email: &str, password: &str) -> Result<LoginResponse> {
+    let request = LoginRequest {
+        email: email.to_string(),
+        password: password.to_string(),
+    };
+    let response = bot.post("/api/auth/login", &request).await?;
+    response.assert_status(200)?;
+    Ok(response.json()?)
+}
+
+// Only hosts built with the testbot feature expose the promote endpoint.
+async fn promote_admin(bot: &mut TestBot) -> Result<LoginResponse> {
+    let email = format!("bench+{}@example.com", Uuid::new_v4().simple());
+    let password = "BenchBotPass#1".to_string();
+    let register = RegisterRequest {
+        email: email.clone(),
+        password: password.clone(),
+        confirm_password: password.clone(),
+        display_name: "BenchBot Admin".to_string(),
+    };
+    bot.post("/api/auth/register", &register)
+        .await?
+        .assert_status(200)?;
+    login(bot, &email, &password).await?;
+
+    let response = bot
+        .post("/api/test/auth/promote-admin", &json!({ "email": email }))
+        .await?;
+    response.assert_status(200)?;
+    Ok(response.json()?)
+}
+
+pub struct SeededLibrary {
+    pub storage_id: String,
+    pub root: PathBuf,
+}
+
+impl SeededLibrary {
+    // Files are written locally, so the target host must share this machine's filesystem.
+    pub async fn create(bot: &mut TestBot, count: usize) -> Result<Self> {
+        let nonce = Uuid::new_v4().simple().to_string();
+        let mount_point = env::temp_dir();
+        let folder = format!("nimble-benchbot-{}", &nonce[..8]);
+        let root = mount_point.join(&folder);
+        let images = root.join(IMPORT_FOLDER);
+        fs::create_dir_all(&images)?;
+
+        let payload = json!({
+            "label": "BenchBot Storage",
+            "mountPoint": mount_point.to_string_lossy(),
+            "path": folder,
+            "isDefault": false,
+        });
+        let response = bot.post_auth("/api/storage/locations", &payload).await?;
+        response.assert_status(200)?;
+        let created: Value = response.json()?;
+        let storage_id = created
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("create storage response missing id"))?
+            .to_string();
+        let library = Self { storage_id, root };
+
+        for index in 0..count {
+            write_image(&images, &nonce, index)?;
+        }
+        library.import(bot, count).await?;
+        Ok(library)
+    }
+
+    pub async fn remove(&self, bot: &mut TestBot) -> Result<()> {
+        let path = format!("/api/storage/locations/{}", self.storage_id);
+        bot.delete_auth(&path).await?.assert_status(200)?;
+        fs::remove_dir_all(&self.root)?;
+        Ok(())
+    }
+
+    async fn import(&self, bot: &mut TestBot, count: usize) -> Result<()> {
+        let request = FolderImportRequest {
+            path: IMPORT_FOLDER.to_string(),
+            recursive: true,
+        };
+        let path = format!("/api/storage/{}/import", self.storage_id);
+        let response = bot.post_auth(&path, &request).await?;
+        response.assert_status(200)?;
+        let batch: FolderImportResponse = response.json()?;
+        log::info!(
+            "Queued {} of {} generated photos (batch {})",
+            batch.queued,
+            count,
+            batch.batch_id
+        );
+
+        let started = Instant::now();
+        let path = format!("/api/storage/imports/{}", batch.batch_id);
+        loop {
+            let response = bot.get_auth(&path).await?;
+            response.assert_status(200)?;
+            let status: ImportBatchStatus = response.json()?;
+            if status.completed + status.failed >= status.total {
+                if status.failed > 0 {
+                    log::warn!("{} generated photos failed to import", status.failed);
+                }
+                log::info!(
+                    "Imported {} photos in {:?}",
+                    status.completed,
+                    started.elapsed()
+                );
+                return Ok(());
+            }
+            if started.elapsed() > IMPORT_TIMEOUT {
+                return Err(anyhow!(
+                    "import batch {} did not finish within {:?}",
+                    batch.batch_id,
+                    IMPORT_TIMEOUT
+                ));
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for SeededLibrary {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+// Distinct colours per index so every file hashes differently.
+fn write_image(folder: &Path, nonce: &str, index: usize) -> Result<()> {
+    let seed = index as u32;
+    let colour = Rgb([
+        (seed & 0xff) as u8,
+        ((seed >> 8) & 0xff) as u8,
+        ((seed * 37) & 0xff) as u8,
+    ]);
+    let image = RgbImage::from_fn(IMAGE_SIZE, IMAGE_SIZE, |x, y| {
+        if (x + y) % 2 == 0 {
+            colour
+        } else {
+            Rgb([colour[2], colour[0], colour[1]])
+        }
+    });
+    let path = folder.join(format!("bench-{}-{:05}.jpg", &nonce[..8], index));
+    image.save_with_format(&path, ImageFormat::Jpeg)?;
+    Ok(())
+}
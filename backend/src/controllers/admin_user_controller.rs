@@ -12,6 +12,30 @@ impl Controller for AdminUserController {
 
 struct ListAdminUsersHandler;
 
+impl ListAdminUsersHandler {
+    const DEFAULT_PAGE: u32 = 1;
+    const DEFAULT_PAGE_SIZE: u32 = 50;
+    const MAX_PAGE_SIZE: u32 = 200;
+
+    fn parse_number(context: &HttpContext, key: &str, fallback: u32) -> Result<u32, PipelineError> {
+        match context.request().query_params().get(key) {
+            Some(raw) => raw
+                .parse::<u32>()
+                .ok()
+                .filter(|value| *value > 0)
+                .ok_or_else(|| PipelineError::message(&format!("invalid {}", key))),
+            None => Ok(fallback),
+        }
+    }
+
+    fn parse_filter(context: &HttpContext) -> AdminUserFilter {
+        let params = context.request().query_params();
+        let text = |key: &str| params.get(key).map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+
+        AdminUserFilter { search: text("q"), role: text("role") }
+    }
+}
+
 #[async_trait]
 #[get("/api/admin/users", policy = Policy::Authenticated)]
 impl HttpHandler for ListAdminUsersHandler {
@@ -21,12 +45,39 @@ impl HttpHandler for ListAdminUsersHandler {
             return Ok(ResponseValue::empty());
         }
 
+        let page = Self::parse_number(context, "page", Self::DEFAULT_PAGE)?;
+        let page_size = Self::parse_number(context, "pageSize", Self::DEFAULT_PAGE_SIZE)?.min(Self::MAX_PAGE_SIZE);
+        let filter = Self::parse_filter(context);
+
         let service = context.service::<AdminUserService>()?;
-        let users = service.list_users().await?;
+        let users = service.list_users(filter, page, page_size).await?;
         Ok(ResponseValue::json(users))
     }
 }
 
+struct GetAdminUserHandler;
+
+#[async_trait]
+#[get("/api/admin/users/{id}", policy = Policy::Authenticated)]
+impl HttpHandler for GetAdminUserHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let user_id = context.entity_id().or_fail(context)?;
+        let service = context.service::<AdminUserService>()?;
+        match service.get_user(user_id).await? {
+            Some(user) => Ok(ResponseValue::json(user)),
+            None => {
+                context.response_mut().set_status(404);
+                Err(PipelineError::message("user not found"))
+            }
+        }
+    }
+}
+
 struct UpdateUserRolesHandler;
 
 impl UpdateUserRolesHandler {
@@ -44,10 +95,9 @@ impl HttpHandler for UpdateUserRolesHandler {
             return Ok(ResponseValue::empty());
         }
 
-        let payload =
-            context.read_json::<UpdateUserRolesRequest>().map_err(|err| PipelineError::message(err.message()))?;
+        let payload = context.read_payload::<UpdateUserRolesRequest>()?;
 
-        let user_id = context.entity_id()?;
+        let user_id = context.entity_id().or_fail(context)?;
         let current_user_id = context.current_user_id()?;
 
         if user_id == current_user_id && !self.contains_admin_role(&payload.roles) {
@@ -56,6 +106,94 @@ impl HttpHandler for UpdateUserRolesHandler {
 
         let service = context.service::<AdminUserService>()?;
         let updated = service.update_roles(user_id, payload.roles).await?;
+
+        context
+            .audit(
+                AuditActions::USER_ROLES_UPDATE,
+                AuditTargets::USER,
+                &updated.id.to_string(),
+                json!({ "roles": updated.roles }),
+            )
+            .await;
         Ok(ResponseValue::json(updated))
     }
 }
+
+struct DeleteUserHandler;
+
+impl DeleteUserHandler {
+    fn delete_comments(context: &HttpContext) -> bool {
+        context
+            .request()
+            .query_params()
+            .get("deleteComments")
+            .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "1"))
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait]
+#[delete("/api/admin/users/{id}", policy = Policy::Authenticated)]
+impl HttpHandler for DeleteUserHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let user_id = context.entity_id().or_fail(context)?;
+        let current_user_id = context.current_user_id()?;
+        if user_id == current_user_id {
+            return Err(PipelineError::message("Admin cannot delete their own account"));
+        }
+
+        let delete_comments = Self::delete_comments(context);
+        let service = context.service::<AdminUserService>()?;
+        let result = service.delete_user(user_id, delete_comments).await?;
+        if let Err(err) = context.service::<AuthService>()?.revoke_other_sessions(user_id, None).await {
+            log::warn!("Failed to revoke sessions for deleted user {}: {:?}", user_id, err);
+        }
+        if let Err(err) = context.service::<TwoFactorService>()?.disable(user_id).await {
+            log::warn!("Failed to remove two-factor settings for deleted user {}: {:?}", user_id, err);
+        }
+        context.service::<RevokedSubjectRegistry>()?.revoke(user_id);
+
+        context
+            .audit(
+                AuditActions::USER_DELETE,
+                AuditTargets::USER,
+                &user_id.to_string(),
+                json!({
+                    "deleteComments": delete_comments,
+                    "commentsDeleted": result.comments_deleted,
+                    "commentsAnonymized": result.comments_anonymized,
+                }),
+            )
+            .await;
+        Ok(ResponseValue::json(result))
+    }
+}
+
+// For users who lost their authenticator and their backup codes. They sign in with just a password
+// afterwards and can set two-factor up again.
+struct DisableUserTwoFactorHandler;
+
+#[async_trait]
+#[delete("/api/admin/users/{id}/2fa", policy = Policy::Authenticated)]
+impl HttpHandler for DisableUserTwoFactorHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let user_id = context.entity_id().or_fail(context)?;
+        if !context.service::<TwoFactorService>()?.disable(user_id).await? {
+            return Err(context.fail(ApiError::not_found("Two-factor sign-in is not enabled for this user")));
+        }
+
+        context.audit(AuditActions::USER_TWO_FACTOR_DISABLE, AuditTargets::USER, &user_id.to_string(), json!({})).await;
+        context.response_mut().set_status(204);
+        Ok(ResponseValue::empty())
+    }
+}
@@ -21,11 +21,20 @@ struct AlbumPhotosHandler;
 #[get("/api/albums/{id}/photos/{page}/{pageSize}")]
 impl HttpHandler for AlbumPhotosHandler {
     async fn invoke(&self, context: &mut HttpContext) -> std::result::Result<ResponseValue, PipelineError> {
-        let id = context.entity_id()?;
+        let id = context.entity_id().or_fail(context)?;
         let page: u32 = context.page().unwrap_or(1);
         let page_size: u32 = context.page_size().unwrap_or(20);
         let repository = context.service::<Repository<Photo>>()?;
-        let paged_photos = repository.photos_in_album(id, page, page_size).await?;
+        // The cached pages are unfiltered, so only admins may read from them.
+        let paged_photos = if context.is_admin() {
+            let albums = context.service::<Repository<Album>>()?;
+            let cache = context.service::<AlbumMembershipCache>()?;
+            cache.photos_page(&albums, &repository, id, page, page_size).await?
+        } else {
+            let mut hidden_tags = context.viewer_hidden_tags().await?;
+            hidden_tags.extend(context.service::<Repository<Tag>>()?.admin_only_tag_names().await.or_fail(context)?);
+            repository.photos_in_album(id, page, page_size, &hidden_tags).await?
+        };
 
         Ok(ResponseValue::json(paged_photos))
     }
@@ -33,28 +42,119 @@ impl HttpHandler for AlbumPhotosHandler {
 
 struct ListAlbumsHandler;
 
+impl ListAlbumsHandler {
+    fn exclude_hidden(context: &HttpContext) -> bool {
+        context
+            .request()
+            .query_params()
+            .get("excludeHidden")
+            .map(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "1"))
+            .unwrap_or(false)
+    }
+}
+
 #[async_trait]
 #[get("/api/albums/{page}/{pageSize}")]
 impl HttpHandler for ListAlbumsHandler {
     async fn invoke(&self, context: &mut HttpContext) -> std::result::Result<ResponseValue, PipelineError> {
         let page: u32 = context.page().unwrap_or(1);
         let page_size: u32 = context.page_size().unwrap_or(20);
-        let repository = context.service::<Repository<Album>>()?;
-
-        let query = QueryBuilder::<Album>::new().page(page, page_size).build();
+        let params = context.request().query_params();
+        let query = AlbumListQuery::parse(
+            params.get("q").map(String::as_str),
+            params.get("sort").map(String::as_str),
+            params.get("dir").map(String::as_str),
+        );
+        let query = query.map_err(ApiError::bad_request).or_fail(context)?;
 
-        let albums = repository.query(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        let repository = context.service::<Repository<Album>>()?;
+        let albums = repository.search_albums(&query, page, page_size).await?;
+
+        let is_viewer = context.is_viewer();
+        let hidden_tags = if is_viewer { context.viewer_hidden_tags().await? } else { HashSet::new() };
+        let album_ids: Vec<Uuid> = albums.items.iter().map(|album| album.id).collect();
+        let counts = context.service::<Repository<AlbumPhoto>>()?.photo_counts(&album_ids, &hidden_tags).await?;
+        let exclude_hidden = is_viewer && Self::exclude_hidden(context);
+
+        let mut excluded = 0;
+        let mut items = Vec::with_capacity(albums.items.len());
+        for mut album in albums.items {
+            let album_counts = counts.get(&album.id).copied().unwrap_or_default();
+            if exclude_hidden && album_counts.total > 0 && album_counts.visible == 0 {
+                excluded += 1;
+                continue;
+            }
+            album.image_count = Some(album_counts.visible);
+            items.push(album);
+        }
 
-        Ok(ResponseValue::json(albums))
+        Ok(ResponseValue::json(Page {
+            items,
+            total: albums.total.saturating_sub(excluded),
+            page: albums.page,
+            page_size: albums.page_size,
+        }))
     }
 }
 
 struct AlbumCommentsHandler;
 
+impl AlbumCommentsHandler {
+    const DEFAULT_PAGE_SIZE: u32 = 50;
+    const MAX_PAGE_SIZE: u32 = 200;
+
+    fn parse_number(context: &HttpContext, key: &str, fallback: u32) -> Result<u32, ApiError> {
+        match context.request().query_params().get(key) {
+            Some(raw) => raw
+                .parse::<u32>()
+                .ok()
+                .filter(|value| *value > 0)
+                .ok_or_else(|| ApiError::bad_request(format!("invalid {}", key))),
+            None => Ok(fallback),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct AlbumPhotoIdsPayload {
     #[serde(rename = "photoIds")]
     photo_ids: Vec<Uuid>,
+    version: i32,
+}
+
+async fn album_version_matches(context: &HttpContext, album_id: Uuid, expected: i32) -> Result<bool, PipelineError> {
+    let albums = context.service::<Repository<Album>>()?;
+    let album = albums.get(&album_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+    Ok(album.is_some_and(|album| album.version == expected))
+}
+
+// Bumped only once the write has landed, so a reader never caches the old rows under the new stamp.
+async fn claim_album_version(
+    context: &HttpContext,
+    album_id: Uuid,
+    expected: i32,
+) -> Result<Option<i32>, PipelineError> {
+    let albums = context.service::<Repository<Album>>()?;
+    albums.bump_album_version(album_id, expected).await
+}
+
+fn publish_album_updated(context: &HttpContext, album_id: Uuid) {
+    if let Ok(live) = context.service::<LiveUpdateService>() {
+        live.publish(LiveEvent::AlbumUpdated { album_id });
+    }
+}
+
+async fn album_version_conflict(context: &mut HttpContext, album_id: Uuid) -> Result<ResponseValue, PipelineError> {
+    let albums = context.service::<Repository<Album>>()?;
+    let current = albums.get(&album_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+
+    match current {
+        Some(album) => {
+            context.response_mut().set_status(409);
+            Ok(ResponseValue::new(Json(album)))
+        }
+        None => Err(context.fail(ApiError::not_found("Album not found"))),
+    }
 }
 
 struct AddAlbumPhotosHandler;
@@ -63,8 +163,12 @@ struct AddAlbumPhotosHandler;
 #[post("/api/albums/{id}/photos", policy = Policy::Authenticated)]
 impl HttpHandler for AddAlbumPhotosHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let album_id = context.entity_id()?;
-        let payload = context.read_json::<AlbumPhotoIdsPayload>().map_err(|e| PipelineError::message(e.message()))?;
+        let album_id = context.entity_id().or_fail(context)?;
+        let payload = context.read_payload::<AlbumPhotoIdsPayload>()?;
+
+        if !album_version_matches(context, album_id, payload.version).await? {
+            return album_version_conflict(context, album_id).await;
+        }
 
         let photo_ids = payload.photo_ids;
         let repository = context.service::<Repository<AlbumPhoto>>()?;
@@ -72,8 +176,48 @@ impl HttpHandler for AddAlbumPhotosHandler {
             .add_photos_to_album(album_id, &photo_ids)
             .await
             .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        context.service::<Repository<Album>>()?.refresh_album_dates(album_id).await?;
+        let Some(version) = claim_album_version(context, album_id, payload.version).await? else {
+            return album_version_conflict(context, album_id).await;
+        };
+        publish_album_updated(context, album_id);
 
-        Ok(ResponseValue::new(Json(json!({ "updated": added }))))
+        Ok(ResponseValue::new(Json(json!({ "updated": added, "version": version }))))
+    }
+}
+
+struct DeleteAlbumHandler;
+
+// Replaces the generic entity delete so the audit entry names the admin and is only written once the row is gone.
+#[async_trait]
+#[delete("/api/albums/{id}", policy = Policy::InRole("admin".to_string()))]
+impl HttpHandler for DeleteAlbumHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let album_id = context.entity_id().or_fail(context)?;
+        let repository = context.service::<Repository<Album>>()?;
+        let album = repository
+            .get(&album_id)
+            .await
+            .or_repository_error("get_album")
+            .or_fail(context)?
+            .ok_or_else(|| ApiError::not_found("Album not found"))
+            .or_fail(context)?;
+
+        repository.delete(&album_id).await.or_repository_error("delete_album").or_fail(context)?;
+        if let Ok(cache) = context.service::<AlbumMembershipCache>() {
+            cache.invalidate(album_id);
+        }
+
+        context
+            .audit(
+                AuditActions::ALBUM_DELETE,
+                AuditTargets::ALBUM,
+                &album_id.to_string(),
+                json!({ "name": album.name }),
+            )
+            .await;
+        context.response_mut().set_status(204);
+        Ok(ResponseValue::empty())
     }
 }
 
@@ -83,15 +227,86 @@ struct RemoveAlbumPhotosHandler;
 #[delete("/api/albums/{id}/photos", policy = Policy::Authenticated)]
 impl HttpHandler for RemoveAlbumPhotosHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let album_id = context.entity_id()?;
-        let payload = context.read_json::<AlbumPhotoIdsPayload>().map_err(|e| PipelineError::message(e.message()))?;
+        let album_id = context.entity_id().or_fail(context)?;
+        let payload = context.read_payload::<AlbumPhotoIdsPayload>()?;
+        if !album_version_matches(context, album_id, payload.version).await? {
+            return album_version_conflict(context, album_id).await;
+        }
+
         let photo_ids = payload.photo_ids;
         let repository = context.service::<Repository<AlbumPhoto>>()?;
         let removed = repository
             .remove_photos_from_album(album_id, &photo_ids)
             .await
             .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
-        Ok(ResponseValue::new(Json(json!({ "updated": removed }))))
+        context.service::<Repository<Album>>()?.refresh_album_dates(album_id).await?;
+        let Some(version) = claim_album_version(context, album_id, payload.version).await? else {
+            return album_version_conflict(context, album_id).await;
+        };
+        publish_album_updated(context, album_id);
+        Ok(ResponseValue::new(Json(json!({ "updated": removed, "version": version }))))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MoveAlbumPhotoPayload {
+    photo_id: Uuid,
+    #[serde(default)]
+    before_photo_id: Option<Uuid>,
+    version: i32,
+}
+
+struct ReorderAlbumPhotosHandler;
+
+#[async_trait]
+#[put("/api/albums/{id}/photos/order", policy = Policy::Authenticated)]
+impl HttpHandler for ReorderAlbumPhotosHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let album_id = context.entity_id().or_fail(context)?;
+        let payload = context.read_payload::<AlbumPhotoIdsPayload>()?;
+        let repository = context.service::<Repository<AlbumPhoto>>()?;
+        let current = repository.album_photo_order(album_id).await?;
+
+        AlbumPhotoOrder::validate(&current, &payload.photo_ids).map_err(ApiError::bad_request).or_fail(context)?;
+        if !album_version_matches(context, album_id, payload.version).await? {
+            return album_version_conflict(context, album_id).await;
+        }
+
+        let updated = repository.set_album_photo_order(album_id, &payload.photo_ids).await?;
+        let Some(version) = claim_album_version(context, album_id, payload.version).await? else {
+            return album_version_conflict(context, album_id).await;
+        };
+        publish_album_updated(context, album_id);
+        Ok(ResponseValue::new(Json(json!({ "updated": updated, "version": version }))))
+    }
+}
+
+struct MoveAlbumPhotoHandler;
+
+#[async_trait]
+#[patch("/api/albums/{id}/photos/order", policy = Policy::Authenticated)]
+impl HttpHandler for MoveAlbumPhotoHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let album_id = context.entity_id().or_fail(context)?;
+        let payload = context.read_payload::<MoveAlbumPhotoPayload>()?;
+        let repository = context.service::<Repository<AlbumPhoto>>()?;
+        let current = repository.album_photo_order(album_id).await?;
+
+        let order = AlbumPhotoOrder::move_before(&current, payload.photo_id, payload.before_photo_id)
+            .map_err(ApiError::bad_request)
+            .or_fail(context)?;
+
+        if !album_version_matches(context, album_id, payload.version).await? {
+            return album_version_conflict(context, album_id).await;
+        }
+
+        let updated = repository.set_album_photo_order(album_id, &order).await?;
+        let Some(version) = claim_album_version(context, album_id, payload.version).await? else {
+            return album_version_conflict(context, album_id).await;
+        };
+        publish_album_updated(context, album_id);
+        Ok(ResponseValue::new(Json(json!({ "updated": updated, "version": version }))))
     }
 }
 
@@ -99,22 +314,34 @@ impl HttpHandler for RemoveAlbumPhotosHandler {
 #[get("/api/album/comments/{id}")]
 impl HttpHandler for AlbumCommentsHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let album_id = context.entity_id()?;
-        let is_admin = context.is_admin();
+        let album_id = context.entity_id().or_fail(context)?;
+        let page = Self::parse_number(context, "page", 1).or_fail(context)?;
+        let page_size =
+            Self::parse_number(context, "pageSize", Self::DEFAULT_PAGE_SIZE).or_fail(context)?.min(Self::MAX_PAGE_SIZE);
 
         log::info!("Fetching comments for album {}", album_id);
 
-        let repository = context.service::<Repository<AlbumComment>>()?;
-        let allow_hidden = is_admin;
-
-        let query = QueryBuilder::<AlbumComment>::new()
-            .filter("album_id", FilterOperator::Eq, Value::Uuid(album_id))
-            .filter("hidden", FilterOperator::Eq, Value::Bool(allow_hidden))
-            .sort_desc("created_at")
-            .build();
-        let comments = repository.query(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
-
-        Ok(ResponseValue::json(comments))
+        let viewer = context.current_user_id().ok();
+        let comments = context
+            .service::<Repository<AlbumComment>>()?
+            .visible_album_comments(album_id, viewer, context.is_admin(), page, page_size)
+            .await?;
+
+        let author_ids: Vec<Uuid> = comments.items.iter().filter_map(|comment| comment.user_id).collect();
+        let authors = context.service::<Repository<UserSettings>>()?.get_by_user_ids(&author_ids).await?;
+
+        let dtos = Page {
+            items: comments
+                .items
+                .into_iter()
+                .map(|comment| AlbumCommentDto::from(comment).with_author(&authors))
+                .collect(),
+            total: comments.total,
+            page: comments.page,
+            page_size: comments.page_size,
+        };
+
+        Ok(ResponseValue::json(dtos))
     }
 }
 
@@ -123,43 +350,37 @@ struct CreateAlbumCommentPayload {
     comment: String,
 }
 
-struct CreateAlbumCommentHandler;
-
-impl CreateAlbumCommentHandler {
-    fn validate_comment(&self, comment: &str) -> Result<String, PipelineError> {
-        let trimmed = comment.trim();
-        if trimmed.is_empty() {
-            return Err(PipelineError::message("Comment cannot be empty"));
-        }
-        if trimmed.chars().count() > MAX_COMMENT_LENGTH {
-            return Err(PipelineError::message(&format!("Comment must be {} characters or fewer", MAX_COMMENT_LENGTH)));
-        }
-        Ok(trimmed.to_string())
+impl Validate for CreateAlbumCommentPayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Validator::new()
+            .required("comment", "Comment", &self.comment)
+            .max_chars("comment", "Comment", &self.comment, MAX_COMMENT_LENGTH)
+            .finish()
     }
 }
 
+struct CreateAlbumCommentHandler;
+
 #[async_trait]
 #[post("/api/album/comments/{id}", policy = Policy::Authenticated)]
 impl HttpHandler for CreateAlbumCommentHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload =
-            context.read_json::<CreateAlbumCommentPayload>().map_err(|e| PipelineError::message(e.message()))?;
+        let payload = context.read_valid_json::<CreateAlbumCommentPayload>()?;
 
-        let comment = self.validate_comment(&payload.comment)?;
-        let album_id = context.entity_id()?;
+        let comment = payload.comment.trim().to_string();
+        let album_id = context.entity_id().or_fail(context)?;
         let user_id = context.current_user_id()?;
-
-        let settings_repo = context.service::<Repository<UserSettings>>()?;
-        let display_name = settings_repo
-            .get(&user_id)
-            .await
-            .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
-            .map(|settings| settings.display_name)
-            .unwrap_or_else(|| "Anonymous".to_string());
+        let display_name = context.current_user_display_name().await?;
 
         let new_comment = AlbumComment::new(album_id, user_id, display_name, comment);
         let repository = context.service::<Repository<AlbumComment>>()?;
         let saved = repository.insert(new_comment).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        if let Ok(notifications) = context.service::<NotificationService>() {
+            notifications.notify_album_comment(&saved);
+        }
+        if let Ok(live) = context.service::<LiveUpdateService>() {
+            live.publish(LiveEvent::CommentAdded { comment_id: saved.id, photo_id: None, album_id: Some(album_id) });
+        }
 
         Ok(ResponseValue::json(AlbumCommentDto::from(saved)))
     }
@@ -176,27 +397,68 @@ struct UpdateAlbumCommentVisibilityHandler;
 #[put("/api/album/comments/visibility/{albumId}/{commentId}", policy = Policy::InRole("admin".to_string()))]
 impl HttpHandler for UpdateAlbumCommentVisibilityHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let album_id = context.id("albumId")?;
-        let comment_id = context.id("commentId")?;
-        let payload = context
-            .read_json::<UpdateAlbumCommentVisibilityPayload>()
-            .map_err(|e| PipelineError::message(e.message()))?;
+        let album_id = context.id("albumId").or_fail(context)?;
+        let comment_id = context.id("commentId").or_fail(context)?;
+        let payload = context.read_payload::<UpdateAlbumCommentVisibilityPayload>()?;
 
         let repository = context.service::<Repository<AlbumComment>>()?;
         let mut comment = repository
             .get(&comment_id)
             .await
             .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
-            .ok_or_else(|| PipelineError::message("Comment not found"))?;
+            .ok_or_else(|| ApiError::not_found("Comment not found"))
+            .or_fail(context)?;
 
         if comment.album_id != album_id {
-            return Err(PipelineError::message("Comment does not belong to the supplied album"));
+            return Err(context.fail(ApiError::bad_request("Comment does not belong to the supplied album")));
         }
 
+        let newly_hidden = payload.hidden && !comment.hidden;
         comment.hidden = payload.hidden;
 
         let saved = repository.update(comment).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        if newly_hidden {
+            if let Ok(notifications) = context.service::<NotificationService>() {
+                notifications.notify_comment_hidden(&saved, context.current_user_id().ok());
+            }
+        }
+
+        context
+            .audit(
+                AuditActions::COMMENT_VISIBILITY_UPDATE,
+                AuditTargets::ALBUM_COMMENT,
+                &saved.id.to_string(),
+                json!({ "albumId": saved.album_id, "hidden": saved.hidden }),
+            )
+            .await;
 
         Ok(ResponseValue::new(Json(AlbumCommentDto::from(saved))))
     }
 }
+
+struct AutoGenerateAlbumsHandler;
+
+#[async_trait]
+#[post("/api/albums/auto-generate", policy = Policy::InRole("admin".to_string()))]
+impl HttpHandler for AutoGenerateAlbumsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let request = context.read_payload::<AutoAlbumRequest>()?;
+        let service = context.service::<EventAlbumService>()?;
+        let response = service.generate(&request).await.map_err(|e| PipelineError::message(&e.to_string()))?;
+
+        for album in &response.albums {
+            if let Some(album_id) = album.album_id {
+                context
+                    .audit(
+                        AuditActions::ALBUM_AUTO_GENERATE,
+                        AuditTargets::ALBUM,
+                        &album_id.to_string(),
+                        json!({ "title": album.title, "photoCount": album.photo_count }),
+                    )
+                    .await;
+            }
+        }
+
+        Ok(ResponseValue::new(Json(response)))
+    }
+}
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use nimble_web::ResponseBody;
 use std::path::Path;
 
 use crate::prelude::*;
@@ -11,13 +12,192 @@ impl Controller for AssetsController {
     }
 }
 
+impl AssetsController {
+    const INDEX_FILE: &'static str = "index.html";
+    const NO_CACHE: &'static str = "no-cache";
+    const IMMUTABLE_CACHE: &'static str = "public, max-age=31536000, immutable";
+    const DEFAULT_CACHE: &'static str = "public, max-age=3600";
+    const MIN_HASH_LENGTH: usize = 8;
+    const SHARE_PREFIX: &'static str = "/p/";
+    const HTML_CONTENT_TYPE: &'static str = "text/html; charset=utf-8";
+
+    pub fn resolve_web_root(configured: &str) -> Option<PathBuf> {
+        let configured = configured.trim();
+        if configured.is_empty() {
+            return None;
+        }
+
+        let root = PathBuf::from(configured);
+        if root.is_absolute() {
+            return Some(root);
+        }
+
+        let mut candidates = vec![root.clone(), Path::new("backend").join(&root)];
+        if let Ok(exe) = std::env::current_exe() {
+            if let Some(parent) = exe.parent() {
+                candidates.push(parent.join(&root));
+            }
+        }
+
+        Some(candidates.into_iter().find(|candidate| candidate.join(Self::INDEX_FILE).is_file()).unwrap_or(root))
+    }
+
+    pub fn web_asset_path(root: &Path, request_path: &str) -> Result<Option<PathBuf>, ApiError> {
+        if Self::is_api_path(request_path) {
+            return Ok(None);
+        }
+
+        let relative = Self::normalize_request_path(request_path)
+            .ok_or_else(|| ApiError::bad_request(format!("Invalid asset path: {}", request_path)))?;
+
+        let mut file_path = root.join(relative);
+        if file_path.is_dir() {
+            file_path = file_path.join(Self::INDEX_FILE);
+        }
+
+        if !file_path.is_file() {
+            if !Self::is_client_route(request_path) {
+                return Ok(None);
+            }
+            file_path = root.join(Self::INDEX_FILE);
+            if !file_path.is_file() {
+                return Ok(None);
+            }
+        }
+
+        if !Self::is_within_root(root, &file_path) {
+            return Err(ApiError::bad_request(format!("Invalid asset path: {}", request_path)));
+        }
+
+        Ok(Some(file_path))
+    }
+
+    pub fn serve_web_asset(context: &mut HttpContext, path: PathBuf) {
+        let cache_control = Self::cache_control(&path);
+        let content_type = Self::content_type(&path);
+
+        FileResponse::from_path(path).into_response(context);
+        let headers = context.response_mut().headers_mut();
+        headers.insert("content-type", content_type);
+        headers.insert("cache-control", cache_control);
+    }
+
+    pub fn share_hash(path: &str) -> Option<String> {
+        path.strip_prefix(Self::SHARE_PREFIX).and_then(HashService::normalize)
+    }
+
+    // Hidden and missing photos get the same bare 404, so the page cannot be used to probe for them.
+    pub async fn serve_share_page(context: &mut HttpContext, hash: &str) -> Result<(), PipelineError> {
+        let embeds = context.service::<EmbedService>()?;
+        let embed = if embeds.is_enabled().await? {
+            embeds.photo(&EmbedTarget::SharedPhoto(hash.to_string()), &context.public_base_url()).await?
+        } else {
+            None
+        };
+        let Some(embed) = embed else {
+            context.response_mut().set_status(404);
+            return Ok(());
+        };
+
+        let html = embed.render_page(&context.derivative_profile().await);
+        let response = context.response_mut();
+        response.set_status(200);
+        response.headers_mut().insert("content-type", Self::HTML_CONTENT_TYPE);
+        response.headers_mut().insert("cache-control", Self::NO_CACHE);
+        response.set_body(ResponseBody::Text(html));
+        Ok(())
+    }
+
+    pub fn cache_control(path: &Path) -> &'static str {
+        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_ascii_lowercase();
+        if file_name.ends_with(".html") {
+            Self::NO_CACHE
+        } else if Self::is_hashed_file_name(&file_name) {
+            Self::IMMUTABLE_CACHE
+        } else {
+            Self::DEFAULT_CACHE
+        }
+    }
+
+    pub fn content_type(path: &Path) -> &'static str {
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_ascii_lowercase();
+        match extension.as_str() {
+            "html" | "htm" => Self::HTML_CONTENT_TYPE,
+            "js" | "mjs" => "text/javascript; charset=utf-8",
+            "css" => "text/css; charset=utf-8",
+            "json" | "map" => "application/json",
+            "webmanifest" => "application/manifest+json",
+            "txt" => "text/plain; charset=utf-8",
+            "xml" => "application/xml",
+            "svg" => "image/svg+xml",
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "webp" => "image/webp",
+            "avif" => "image/avif",
+            "ico" => "image/x-icon",
+            "woff" => "font/woff",
+            "woff2" => "font/woff2",
+            "ttf" => "font/ttf",
+            "wasm" => "application/wasm",
+            _ => "application/octet-stream",
+        }
+    }
+
+    pub fn is_api_path(path: &str) -> bool {
+        path == "/api" || path.starts_with("/api/")
+    }
+
+    fn normalize_request_path(path: &str) -> Option<PathBuf> {
+        let trimmed = path.trim_start_matches('/');
+        if trimmed.is_empty() {
+            return Some(PathBuf::from(Self::INDEX_FILE));
+        }
+        if trimmed.contains('\\') {
+            return None;
+        }
+
+        let mut normalized = PathBuf::new();
+        for component in Path::new(trimmed).components() {
+            match component {
+                Component::Normal(segment) => normalized.push(segment),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+
+        Some(normalized)
+    }
+
+    fn is_client_route(path: &str) -> bool {
+        let leaf = path.rsplit('/').next().unwrap_or_default();
+        !leaf.contains('.')
+    }
+
+    fn is_within_root(root: &Path, path: &Path) -> bool {
+        match (root.canonicalize(), path.canonicalize()) {
+            (Ok(root), Ok(path)) => path.starts_with(root),
+            _ => false,
+        }
+    }
+
+    fn is_hashed_file_name(file_name: &str) -> bool {
+        let stem = file_name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(file_name);
+        stem.split(['.', '-', '_']).skip(1).any(|part| {
+            part.len() >= Self::MIN_HASH_LENGTH
+                && part.chars().all(|ch| ch.is_ascii_alphanumeric())
+                && part.chars().any(|ch| ch.is_ascii_digit())
+        })
+    }
+}
+
 struct LogoHandler;
 
 #[async_trait]
 #[get("/api/assets/logo/{filename}")]
 impl HttpHandler for LogoHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let filename = context.param("filename")?;
+        let filename = context.param("filename").or_fail(context)?;
 
         if filename.contains("..") || filename.contains('/') || filename.contains('\\') {
             return Err(PipelineError::message("invalid filename"));
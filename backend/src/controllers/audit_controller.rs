@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+
+use crate::prelude::*;
+
+pub struct AuditController;
+
+impl Controller for AuditController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct ListAuditLogsHandler;
+
+impl ListAuditLogsHandler {
+    const DEFAULT_PAGE: u32 = 1;
+    const DEFAULT_PAGE_SIZE: u32 = 50;
+    const MAX_PAGE_SIZE: u32 = 200;
+
+    fn parse_number(context: &HttpContext, key: &str, fallback: u32) -> Result<u32, PipelineError> {
+        match context.request().query_params().get(key) {
+            Some(raw) => raw
+                .parse::<u32>()
+                .ok()
+                .filter(|value| *value > 0)
+                .ok_or_else(|| PipelineError::message(&format!("invalid {}", key))),
+            None => Ok(fallback),
+        }
+    }
+
+    fn parse_filter(context: &HttpContext) -> Result<AuditLogFilter, PipelineError> {
+        let params = context.request().query_params();
+        let action = params.get("action").map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+        let actor_user_id = params
+            .get("userId")
+            .map(|value| Uuid::parse_str(value.trim()).map_err(|_| PipelineError::message("invalid userId")))
+            .transpose()?;
+
+        Ok(AuditLogFilter { action, actor_user_id })
+    }
+}
+
+#[async_trait]
+#[get("/api/admin/audit", policy = Policy::Authenticated)]
+impl HttpHandler for ListAuditLogsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let page = Self::parse_number(context, "page", Self::DEFAULT_PAGE)?;
+        let page_size = Self::parse_number(context, "pageSize", Self::DEFAULT_PAGE_SIZE)?.min(Self::MAX_PAGE_SIZE);
+        let filter = Self::parse_filter(context)?;
+
+        let service = context.service::<AuditService>()?;
+        let entries = service.list(filter, page, page_size).await?;
+        Ok(ResponseValue::json(entries))
+    }
+}
@@ -14,12 +14,31 @@ impl Controller for AuthController {
             EndpointRoute::post("/api/auth/login", LoginHandler).build(),
             EndpointRoute::post("/api/auth/refresh", RefreshHandler).build(),
             EndpointRoute::post("/api/auth/logout", LogoutHandler).build(),
+            EndpointRoute::post("/api/auth/verify-email", VerifyEmailHandler).build(),
+            EndpointRoute::post("/api/auth/resend-verification", ResendVerificationHandler).build(),
+            EndpointRoute::post("/api/auth/2fa/setup", TwoFactorSetupHandler)
+                .with_policy(Policy::Authenticated)
+                .build(),
+            EndpointRoute::post("/api/auth/2fa/enable", TwoFactorEnableHandler)
+                .with_policy(Policy::Authenticated)
+                .build(),
+            EndpointRoute::post("/api/auth/2fa/verify", TwoFactorVerifyHandler).build(),
             EndpointRoute::get("/api/auth/registration-status", RegistrationStatusHandler).build(),
             EndpointRoute::get("/api/auth/me", MeHandler).with_policy(Policy::Authenticated).build(),
+            EndpointRoute::delete("/api/auth/me", DeleteAccountHandler).with_policy(Policy::Authenticated).build(),
+            EndpointRoute::get("/api/auth/sessions", ListSessionsHandler).with_policy(Policy::Authenticated).build(),
+            EndpointRoute::delete("/api/auth/sessions", RevokeOtherSessionsHandler)
+                .with_policy(Policy::Authenticated)
+                .build(),
+            EndpointRoute::delete("/api/auth/sessions/{id}", RevokeSessionHandler)
+                .with_policy(Policy::Authenticated)
+                .build(),
             #[cfg(feature = "testbot")]
             EndpointRoute::post("/api/test/auth/reset-token", TestResetTokenHandler).build(),
             #[cfg(feature = "testbot")]
             EndpointRoute::post("/api/test/auth/verify-token", TestVerifyTokenHandler).build(),
+            #[cfg(feature = "testbot")]
+            EndpointRoute::post("/api/test/auth/promote-admin", TestPromoteAdminHandler).build(),
         ]
     }
 }
@@ -29,10 +48,20 @@ struct LoginHandler;
 #[async_trait]
 impl HttpHandler for LoginHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload: LoginRequest = context.json()?;
+        let payload: LoginRequest = context.read_valid_json()?;
 
         let auth_service = context.service::<AuthService>()?;
-        let response = auth_service.login(&payload.email, &payload.password).await?;
+        let require_verified = context.service::<SettingService>()?.is_email_verification_required().await?;
+        let response = match auth_service
+            .login(&payload.email, &payload.password, &context.session_client(), require_verified)
+            .await
+        {
+            Ok(response) => response,
+            Err(err) if err.message() == AuthService::EMAIL_NOT_VERIFIED => {
+                return Err(context.fail(ApiError::email_not_verified("Verify your email address before signing in")));
+            }
+            Err(err) => return Err(err),
+        };
 
         Ok(ResponseValue::json(response))
     }
@@ -43,17 +72,24 @@ struct RegisterHandler;
 #[async_trait]
 impl HttpHandler for RegisterHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload: RegisterRequest = context.json()?;
-
-        if payload.password != payload.confirm_password {
-            return Err(PipelineError::message("Passwords do not match"));
-        }
+        let payload: RegisterRequest = context.read_valid_json()?;
 
         let auth_service = context.service::<AuthService>()?;
         let setting_service = context.service::<SettingService>()?;
-        let response = auth_service.register(&payload.email, &payload.password, &payload.display_name).await?;
+        let user_id = auth_service.create_account(&payload.email, &payload.password, &payload.display_name).await?;
         setting_service.update("site.initialized", json!(true)).await?;
 
+        let pending = if setting_service.is_email_verification_required().await? {
+            context.service::<EmailVerificationService>()?.start(&payload.email, &context.public_base_url()).await?
+        } else {
+            false
+        };
+        if pending && !setting_service.issues_tokens_before_verification().await? {
+            context.response_mut().set_status(202);
+            return Ok(ResponseValue::json(RegistrationPendingResponse { user_id, verification_required: true }));
+        }
+
+        let response = auth_service.issue_tokens(user_id, &context.session_client()).await?;
         Ok(ResponseValue::json(response))
     }
 }
@@ -92,7 +128,8 @@ impl HttpHandler for MeHandler {
             .get(&user_id)
             .await
             .map_err(|_| PipelineError::message("data error"))?
-            .ok_or_else(|| PipelineError::message("user not found"))?;
+            .ok_or_else(|| ApiError::not_found("user not found"))
+            .or_fail(context)?;
 
         let settings = settings_repo.get(&user_id).await.map_err(|_| PipelineError::message("data error"))?.unwrap_or(
             UserSettings {
@@ -102,6 +139,7 @@ impl HttpHandler for MeHandler {
                 theme: "light".to_string(),
                 language: "en".to_string(),
                 timezone: "UTC".to_string(),
+                email_digest: false,
                 created_at: Utc::now(),
             },
         );
@@ -112,14 +150,75 @@ impl HttpHandler for MeHandler {
     }
 }
 
+struct DeleteAccountHandler;
+
+#[async_trait]
+impl HttpHandler for DeleteAccountHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload: DeleteAccountRequest = context.read_payload()?;
+        let user_id = context.current_user_id()?;
+
+        let auth_service = context.service::<AuthService>()?;
+        if !auth_service.verify_password(user_id, &payload.password).await? {
+            return Err(context.fail(ApiError::forbidden("invalid credentials")));
+        }
+
+        let delete_comments = payload.content_action == AccountContentAction::Delete;
+        let admin_user_service = context.service::<AdminUserService>()?;
+        let result = match admin_user_service.delete_user(user_id, delete_comments).await {
+            Ok(result) => result,
+            Err(err) => return Err(context.fail(ApiError::bad_request(err.message()))),
+        };
+
+        if let Some(refresh_token) = payload.refresh_token.as_deref() {
+            if let Err(err) = auth_service.logout(refresh_token).await {
+                log::warn!("Failed to revoke refresh token for deleted account {}: {:?}", user_id, err);
+            }
+        }
+        if let Err(err) = auth_service.revoke_other_sessions(user_id, None).await {
+            log::warn!("Failed to revoke sessions for deleted account {}: {:?}", user_id, err);
+        }
+        if let Err(err) = context.service::<TwoFactorService>()?.disable(user_id).await {
+            log::warn!("Failed to remove two-factor settings for deleted account {}: {:?}", user_id, err);
+        }
+        context.service::<RevokedSubjectRegistry>()?.revoke(user_id);
+
+        context
+            .audit(
+                AuditActions::USER_DELETE,
+                AuditTargets::USER,
+                &user_id.to_string(),
+                json!({
+                    "selfService": true,
+                    "contentAction": payload.content_action,
+                    "commentsDeleted": result.comments_deleted,
+                    "commentsAnonymized": result.comments_anonymized,
+                }),
+            )
+            .await;
+
+        context.response_mut().set_status(204);
+        Ok(ResponseValue::empty())
+    }
+}
+
 struct RefreshHandler;
 
 #[async_trait]
 impl HttpHandler for RefreshHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload: RefreshTokenRequest = context.json()?;
+        let payload: RefreshTokenRequest = context.read_payload()?;
         let auth_service = context.service::<AuthService>()?;
-        let response = auth_service.refresh(&payload.refresh_token).await?;
+        let settings = context.service::<SettingService>()?;
+        let require_verified = settings.is_email_verification_required().await?;
+        let allow_grace = settings.issues_tokens_before_verification().await?;
+        let response = match auth_service.refresh(&payload.refresh_token, require_verified, allow_grace).await {
+            Ok(response) => response,
+            Err(err) if err.message() == AuthService::EMAIL_NOT_VERIFIED => {
+                return Err(context.fail(ApiError::email_not_verified("Verify your email address to stay signed in")));
+            }
+            Err(err) => return Err(err),
+        };
 
         Ok(ResponseValue::json(response))
     }
@@ -130,14 +229,171 @@ struct LogoutHandler;
 #[async_trait]
 impl HttpHandler for LogoutHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload: LogoutRequest = context.json()?;
+        let payload: LogoutRequest = context.read_payload()?;
+        let auth_service = context.service::<AuthService>()?;
+        auth_service.logout(&payload.refresh_token).await?;
+
+        Ok(ResponseValue::empty())
+    }
+}
+
+struct VerifyEmailHandler;
+
+#[async_trait]
+impl HttpHandler for VerifyEmailHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload: VerifyEmailRequest = context.read_payload()?;
         let auth_service = context.service::<AuthService>()?;
-        auth_service.logout(&payload.refresh_token)?;
+        if auth_service.verify_email(payload.token.trim()).await.is_err() {
+            return Err(context.fail(ApiError::bad_request("Verification link is invalid or has already been used")));
+        }
+
+        context.response_mut().set_status(204);
+        Ok(ResponseValue::empty())
+    }
+}
+
+// Answers the same whether or not the address has an account, so it cannot be used to probe for users.
+struct ResendVerificationHandler;
+
+#[async_trait]
+impl HttpHandler for ResendVerificationHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload: ResendVerificationRequest = context.read_valid_json()?;
+        let verification = context.service::<EmailVerificationService>()?;
+        if !verification.is_available() {
+            return Err(context.fail(ApiError::unavailable("Email delivery is not configured")));
+        }
+
+        if !verification.resend(payload.email.trim(), &context.public_base_url()).await? {
+            return Err(context.fail(ApiError::too_many_requests(format!(
+                "Wait {} seconds before requesting another verification email",
+                EmailVerificationService::RESEND_COOLDOWN_SECONDS
+            ))));
+        }
+
+        context.response_mut().set_status(202);
+        Ok(ResponseValue::empty())
+    }
+}
+
+fn two_factor_failure(context: &mut HttpContext, error: TwoFactorError) -> PipelineError {
+    let error = match error {
+        TwoFactorError::AlreadyEnabled => ApiError::conflict("Two-factor sign-in is already enabled"),
+        TwoFactorError::NotStarted => ApiError::bad_request("Start two-factor setup first"),
+        TwoFactorError::InvalidCode => ApiError::bad_request("Invalid authentication code"),
+        TwoFactorError::InvalidChallenge => ApiError::forbidden("Sign-in challenge has expired; sign in again"),
+        TwoFactorError::LockedOut(locked_until) => ApiError::too_many_requests(format!(
+            "Too many wrong codes; try again after {}",
+            locked_until.format("%H:%M UTC")
+        )),
+        TwoFactorError::Failed(err) => return err,
+    };
+    context.fail(error)
+}
+
+struct TwoFactorSetupHandler;
+
+impl TwoFactorSetupHandler {
+    const DEFAULT_ISSUER: &'static str = "Nimble Photos";
+}
+
+#[async_trait]
+impl HttpHandler for TwoFactorSetupHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let user_id = context.current_user_id()?;
+        let user = context.service::<AuthService>()?.me(&user_id.to_string()).await?;
+        let site_title = context.service::<SettingService>()?.site_title().await?;
+        let issuer = if site_title.is_empty() { Self::DEFAULT_ISSUER } else { site_title.as_str() };
+
+        match context.service::<TwoFactorService>()?.setup(user_id, issuer, &user.email).await {
+            Ok(response) => Ok(ResponseValue::json(response)),
+            Err(err) => Err(two_factor_failure(context, err)),
+        }
+    }
+}
+
+struct TwoFactorEnableHandler;
+
+#[async_trait]
+impl HttpHandler for TwoFactorEnableHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload: TwoFactorCodeRequest = context.read_valid_json()?;
+        let user_id = context.current_user_id()?;
+
+        match context.service::<TwoFactorService>()?.enable(user_id, &payload.code).await {
+            Ok(backup_codes) => Ok(ResponseValue::json(TwoFactorEnabledResponse { backup_codes })),
+            Err(err) => Err(two_factor_failure(context, err)),
+        }
+    }
+}
+
+// Second half of a sign-in: trades the challenge from /api/auth/login and a code for real tokens.
+struct TwoFactorVerifyHandler;
+
+#[async_trait]
+impl HttpHandler for TwoFactorVerifyHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload: TwoFactorVerifyRequest = context.read_valid_json()?;
+
+        let user_id =
+            match context.service::<TwoFactorService>()?.complete(&payload.challenge_token, &payload.code).await {
+                Ok(user_id) => user_id,
+                Err(err) => return Err(two_factor_failure(context, err)),
+            };
+        let response = context.service::<AuthService>()?.issue_tokens(user_id, &context.session_client()).await?;
+        Ok(ResponseValue::json(response))
+    }
+}
+
+struct ListSessionsHandler;
+
+#[async_trait]
+impl HttpHandler for ListSessionsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let user_id = context.current_user_id()?;
+        let current = context.current_session_id();
+        let sessions = context.service::<AuthService>()?.sessions(user_id).await?;
+
+        let sessions: Vec<SessionDto> =
+            sessions.into_iter().map(|session| SessionDto::from_session(session, current)).collect();
+        Ok(ResponseValue::json(sessions))
+    }
+}
+
+struct RevokeSessionHandler;
+
+#[async_trait]
+impl HttpHandler for RevokeSessionHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let user_id = context.current_user_id()?;
+        let session_id = context.entity_id().or_fail(context)?;
 
+        if !context.service::<AuthService>()?.revoke_session(user_id, session_id).await? {
+            return Err(context.fail(ApiError::not_found("Session not found")));
+        }
+
+        context.response_mut().set_status(204);
         Ok(ResponseValue::empty())
     }
 }
 
+// Signs out every other device; the caller's own session is found through the X-Session-Id header.
+struct RevokeOtherSessionsHandler;
+
+#[async_trait]
+impl HttpHandler for RevokeOtherSessionsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let user_id = context.current_user_id()?;
+        let Some(current) = context.current_session_id() else {
+            return Err(context.fail(ApiError::bad_request("X-Session-Id header is required")));
+        };
+
+        let revoked = context.service::<AuthService>()?.revoke_other_sessions(user_id, Some(current)).await?;
+        Ok(ResponseValue::json(RevokeSessionsResponse { revoked }))
+    }
+}
+
 #[cfg(feature = "testbot")]
 #[derive(Deserialize)]
 struct TokenRequest {
@@ -157,7 +413,7 @@ struct TestResetTokenHandler;
 #[async_trait]
 impl HttpHandler for TestResetTokenHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload: TokenRequest = context.json()?;
+        let payload: TokenRequest = context.read_payload()?;
         let auth_service = context.service::<AuthService>()?;
         let token = auth_service.issue_reset_token(&payload.email).await?;
         Ok(ResponseValue::json(TokenResponse { token }))
@@ -171,9 +427,24 @@ struct TestVerifyTokenHandler;
 #[async_trait]
 impl HttpHandler for TestVerifyTokenHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload: TokenRequest = context.json()?;
+        let payload: TokenRequest = context.read_payload()?;
         let auth_service = context.service::<AuthService>()?;
         let token = auth_service.issue_verification_token(&payload.email).await?;
         Ok(ResponseValue::json(TokenResponse { token }))
     }
 }
+
+#[cfg(feature = "testbot")]
+struct TestPromoteAdminHandler;
+
+#[cfg(feature = "testbot")]
+#[async_trait]
+impl HttpHandler for TestPromoteAdminHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload: TokenRequest = context.read_payload()?;
+        let auth_service = context.service::<AuthService>()?;
+        let user_id = auth_service.grant_role(&payload.email, "admin").await?;
+        let response = auth_service.issue_tokens(user_id, &context.session_client()).await?;
+        Ok(ResponseValue::json(response))
+    }
+}
@@ -73,7 +73,7 @@ impl HttpHandler for ApproveClientHandler {
             return Err(PipelineError::message("Client approval is only available when approval policy is manual"));
         }
 
-        let client_id = context.id("id")?;
+        let client_id = context.id("id").or_fail(context)?;
         let repo = context.service::<Repository<Client>>()?;
         let mut client = repo
             .get(&client_id)
@@ -100,7 +100,7 @@ impl HttpHandler for RevokeClientHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
         context.require_admin()?;
 
-        let client_id = context.id("id")?;
+        let client_id = context.id("id").or_fail(context)?;
         let repo = context.service::<Repository<Client>>()?;
         let mut client = repo
             .get(&client_id)
@@ -124,7 +124,7 @@ impl HttpHandler for DeleteClientHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
         context.require_admin()?;
 
-        let client_id = context.id("id")?;
+        let client_id = context.id("id").or_fail(context)?;
         let repo = context.service::<Repository<Client>>()?;
         let deleted = repo.delete(&client_id).await.map_err(|_| PipelineError::message("failed to delete client"))?;
         if !deleted {
@@ -183,8 +183,7 @@ impl RegisterClientHandler {
 #[post("/api/clients/register", policy = Policy::Authenticated)]
 impl HttpHandler for RegisterClientHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let request =
-            context.read_json::<RegisterClientRequest>().map_err(|err| PipelineError::message(err.message()))?;
+        let request = context.read_payload::<RegisterClientRequest>()?;
 
         let device_name = Self::normalized(&request.device_name, "deviceName")?;
         let device_type = Self::normalized(&request.device_type, "deviceType")?;
@@ -243,9 +242,7 @@ struct UpdateClientStorageSettingsHandler;
 
 impl UpdateClientStorageSettingsHandler {
     async fn invoke_inner(context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let request = context
-            .read_json::<UpdateClientStorageSettingsPayload>()
-            .map_err(|err| PipelineError::message(err.message()))?;
+        let request = context.read_payload::<UpdateClientStorageSettingsPayload>()?;
 
         let client_id = context.current_client_id().await?;
         let storage_repo = context.service::<Repository<StorageLocation>>()?;
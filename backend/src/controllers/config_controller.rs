@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+
+use crate::prelude::*;
+
+pub struct ConfigController;
+
+impl Controller for ConfigController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct ReloadConfigHandler;
+
+#[async_trait]
+#[post("/api/admin/config/reload", policy = Policy::Authenticated)]
+impl HttpHandler for ReloadConfigHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let service = context.service::<ConfigReloadService>()?;
+        let result = service.reload().await;
+        context
+            .audit(
+                AuditActions::CONFIG_RELOAD,
+                AuditTargets::CONFIG,
+                &service.config_file().display().to_string(),
+                json!({
+                    "success": result.success,
+                    "applied": result.applied,
+                    "restartRequired": result.restart_required,
+                    "error": result.error,
+                }),
+            )
+            .await;
+
+        if let Some(error) = &result.error {
+            context.response_mut().set_status(400);
+            return Err(PipelineError::message(error));
+        }
+        Ok(ResponseValue::json(result))
+    }
+}
+
+struct GetConfigReloadStatusHandler;
+
+#[async_trait]
+#[get("/api/admin/config/reload", policy = Policy::Authenticated)]
+impl HttpHandler for GetConfigReloadStatusHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let service = context.service::<ConfigReloadService>()?;
+        Ok(ResponseValue::json(ConfigReloadStatusDto {
+            config_file: service.config_file().display().to_string(),
+            last_reload: service.last_result(),
+        }))
+    }
+}
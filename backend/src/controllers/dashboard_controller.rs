@@ -34,8 +34,7 @@ struct UpdateSettingHandler;
 #[put("/api/dashboard/settings/{key}", policy = Policy::Authenticated)]
 impl HttpHandler for UpdateSettingHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload =
-            context.read_json::<UpdateSettingPayload>().map_err(|err| PipelineError::message(err.message()))?;
+        let payload = context.read_payload::<UpdateSettingPayload>()?;
 
         let key = context
             .route()
@@ -48,6 +47,21 @@ impl HttpHandler for UpdateSettingHandler {
             return Ok(ResponseValue::empty());
         }
         let updated = service.update(key, payload.value).await?;
+        if updated.key.starts_with(CorsPolicy::SETTING_PREFIX) {
+            context.invalidate_cors_policy();
+        }
+        if DerivativeProfile::is_setting_key(&updated.key) {
+            context.invalidate_derivative_profile();
+        }
+        if updated.key == SettingKeys::PHOTO_MANAGE_STACK_REPRESENTATIVE {
+            let preference = service.stack_representative().await?;
+            let moved = context.service::<Repository<Photo>>()?.restack_representatives(preference).await?;
+            log::info!("Moved {} photo stacks to a {} representative", moved, preference.as_str());
+        }
+
+        context
+            .audit(AuditActions::SETTING_UPDATE, AuditTargets::SETTING, &updated.key, json!({ "value": updated.value }))
+            .await;
 
         Ok(ResponseValue::json(updated))
     }
@@ -100,7 +114,7 @@ impl UploadLogoHandler {
 #[post("/api/dashboard/settings/logo/upload", policy = Policy::Authenticated)]
 impl HttpHandler for UploadLogoHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload = context.read_json::<LogoUploadRequest>().map_err(|err| PipelineError::message(err.message()))?;
+        let payload = context.read_payload::<LogoUploadRequest>()?;
 
         let (mime, encoded) = Self::parse_data_url(&payload.data_url)?;
         let extension = match mime {
@@ -133,3 +147,33 @@ impl HttpHandler for UploadLogoHandler {
         Ok(ResponseValue::json(updated))
     }
 }
+
+struct DashboardStatsHandler;
+
+#[async_trait]
+#[get("/api/dashboard/stats", policy = Policy::Authenticated)]
+impl HttpHandler for DashboardStatsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_access_dashboard().await? {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+        let previews = context.service::<PreviewWarmerService>()?.status();
+        Ok(ResponseValue::json(json!({ "previews": previews })))
+    }
+}
+
+struct DashboardOverviewHandler;
+
+#[async_trait]
+#[get("/api/dashboard/overview", policy = Policy::Authenticated)]
+impl HttpHandler for DashboardOverviewHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_access_dashboard().await? {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+        let overview = context.service::<DashboardOverviewService>()?.overview().await?;
+        Ok(ResponseValue::json(overview.as_ref().clone()))
+    }
+}
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use urlencoding::decode;
+
+use crate::prelude::*;
+
+pub struct EmbedController;
+
+impl Controller for EmbedController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct OEmbedHandler;
+
+impl OEmbedHandler {
+    const NOT_FOUND: &'static str = "No embeddable content at this URL";
+
+    fn parse_limit(context: &HttpContext, key: &str) -> Result<Option<u32>, ApiError> {
+        match context.request().query_params().get(key) {
+            Some(raw) => raw
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|value| *value > 0)
+                .map(Some)
+                .ok_or_else(|| ApiError::bad_request(format!("invalid {}", key))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+#[get("/api/oembed")]
+impl HttpHandler for OEmbedHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        // The spec asks for 501 when a consumer wants a format the provider does not speak.
+        let format = context.request().query_params().get("format").map(|format| format.trim().to_ascii_lowercase());
+        if format.is_some_and(|format| format != "json") {
+            context.response_mut().set_status(501);
+            return Ok(ResponseValue::empty());
+        }
+
+        let embeds = context.service::<EmbedService>()?;
+        if !embeds.is_enabled().await? {
+            return Err(context.fail(ApiError::not_found(Self::NOT_FOUND)));
+        }
+
+        let url = context
+            .request()
+            .query_params()
+            .get("url")
+            .and_then(|url| decode(url).ok().map(|url| url.into_owned()))
+            .filter(|url| !url.trim().is_empty())
+            .ok_or_else(|| ApiError::bad_request("url parameter missing"))
+            .or_fail(context)?;
+        let max_width = Self::parse_limit(context, "maxwidth").or_fail(context)?;
+        let max_height = Self::parse_limit(context, "maxheight").or_fail(context)?;
+
+        let base_url = context.public_base_url();
+        let host = base_url.split_once("://").map(|(_, host)| host).unwrap_or_default();
+        let profile = context.derivative_profile().await;
+        let response = match EmbedTarget::parse(&url, host) {
+            Some(EmbedTarget::Album(album_id)) => {
+                embeds.album(album_id, &base_url).await?.map(|album| album.oembed(&profile))
+            }
+            Some(target) => {
+                embeds.photo(&target, &base_url).await?.map(|photo| photo.oembed(&profile, max_width, max_height))
+            }
+            None => None,
+        };
+
+        match response {
+            Some(response) => Ok(ResponseValue::json(response)),
+            None => Err(context.fail(ApiError::not_found(Self::NOT_FOUND))),
+        }
+    }
+}
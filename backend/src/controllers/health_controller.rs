@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+
+use crate::prelude::*;
+
+pub struct HealthController;
+
+impl Controller for HealthController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct HealthHandler;
+
+#[async_trait]
+#[get("/api/health")]
+impl HttpHandler for HealthHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            return Ok(ResponseValue::json(json!({ "status": "ok" })));
+        }
+
+        let secrets = context.service::<StartupSecrets>()?;
+        Ok(ResponseValue::json(json!({
+            "status": "ok",
+            "environment": secrets.environment,
+            "jwtSecretSource": secrets.jwt_source,
+            "encryptionKeySource": secrets.encryption_source,
+        })))
+    }
+}
+
+struct ReadinessHandler;
+
+#[async_trait]
+#[get("/api/health/ready")]
+impl HttpHandler for ReadinessHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let database = context.service::<DatabaseHealthService>()?.readiness().await;
+        if !database.ready {
+            context.response_mut().set_status(503);
+        }
+
+        let status = if database.ready { "ready" } else { "unavailable" };
+        Ok(ResponseValue::json(json!({ "status": status, "database": database })))
+    }
+}
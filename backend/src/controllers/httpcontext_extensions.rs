@@ -1,4 +1,4 @@
-use chrono::Utc;
+use serde::de::DeserializeOwned;
 use urlencoding::decode;
 
 use crate::prelude::*;
@@ -8,24 +8,36 @@ pub trait HttpContextExtensions {
     fn require(&self, permission: Permission) -> Result<(), PipelineError>;
     fn require_admin(&self) -> Result<(), PipelineError>;
     fn current_user_id(&self) -> Result<Uuid, PipelineError>;
+    fn request_id(&self) -> Option<String>;
+    fn public_base_url(&self) -> String;
+    fn session_client(&self) -> SessionClient;
+    fn current_session_id(&self) -> Option<Uuid>;
     fn extract_api_key(&self) -> Result<String, PipelineError>;
-    fn parse_browse_request(&self) -> Result<BrowseRequest, PipelineError>;
-    fn route_storage_id(&self) -> Result<Uuid, PipelineError>;
-    fn hash(&self) -> Result<String, PipelineError>;
-    fn default_preview_root(&self) -> PathBuf;
+    fn parse_browse_request(&self) -> Result<BrowseRequest, ApiError>;
+    fn route_storage_id(&self) -> Result<Uuid, ApiError>;
+    fn hash(&self) -> Result<String, ApiError>;
     fn is_admin(&self) -> bool;
+    fn app_config(&self) -> Arc<AppConfig>;
     fn is_viewer(&self) -> bool;
-    fn entity_id(&self) -> Result<Uuid, PipelineError>;
+    fn is_anonymous(&self) -> bool;
+    fn entity_id(&self) -> Result<Uuid, ApiError>;
     fn page(&self) -> Result<u32, PipelineError>;
     fn page_size(&self) -> Result<u32, PipelineError>;
-    fn param(&self, key: &str) -> Result<String, PipelineError>;
-    fn id(&self, key: &str) -> Result<Uuid, PipelineError>;
+    fn param(&self, key: &str) -> Result<String, ApiError>;
+    fn id(&self, key: &str) -> Result<Uuid, ApiError>;
+    fn fail(&mut self, error: ApiError) -> PipelineError;
+    fn read_payload<T: DeserializeOwned>(&mut self) -> Result<T, PipelineError>;
+    fn read_valid_json<T: DeserializeOwned + Validate>(&mut self) -> Result<T, PipelineError>;
     fn body_bytes(&self) -> Result<Vec<u8>, PipelineError>;
     async fn current_user_display_name(&self) -> Result<String, PipelineError>;
     async fn can_upload_photos(&self) -> Result<bool, PipelineError>;
     async fn can_access_dashboard(&self) -> Result<bool, PipelineError>;
+    async fn can_tag_photos(&self) -> Result<bool, PipelineError>;
+    async fn can_edit_photos(&self) -> Result<bool, PipelineError>;
     async fn can_update_setting(&self, key: &str) -> Result<bool, PipelineError>;
     async fn viewer_hidden_tags(&self) -> Result<HashSet<String>, PipelineError>;
+    async fn coordinate_precision(&self) -> Result<CoordinatePrecision, PipelineError>;
+    async fn timeline_zone(&self) -> TimelineZone;
     async fn current_client_id(&self) -> Result<Uuid, PipelineError>;
     async fn is_preview_exists(&self, hash: &str) -> bool;
     async fn load_client_storage_settings(
@@ -34,12 +46,39 @@ pub trait HttpContextExtensions {
         storage_id: Uuid,
     ) -> Result<BrowseOptions, PipelineError>;
     async fn validate_api_key(&mut self, api_key: &str) -> Result<Client, PipelineError>;
+    async fn visible_photos(&mut self, hash: &str, missing: &str) -> Result<Vec<Photo>, PipelineError>;
+    fn invalidate_storage_paths(&self);
+    fn invalidate_cors_policy(&self);
+    async fn derivative_profile(&self) -> Arc<DerivativeProfile>;
+    fn invalidate_derivative_profile(&self);
+    async fn storage_root(&self, storage_id: Uuid) -> Result<Option<PathBuf>, PipelineError>;
     async fn get_preview_root(&self, hash: &str) -> Result<PathBuf, PipelineError>;
     async fn get_preview_path(&self, hash: &str) -> Result<PathBuf, PipelineError>;
     async fn get_preview_root_by_storage(&self, storage_id: Uuid) -> Result<PathBuf, PipelineError>;
     async fn get_preview_path_by_storage(&self, storage_id: Uuid, hash: &str) -> Result<PathBuf, PipelineError>;
     async fn get_thumbnail_root_by_storage(&self, storage_id: Uuid) -> Result<PathBuf, PipelineError>;
-    async fn get_thumbnail_roots(&self) -> Result<Vec<PathBuf>, PipelineError>;
+    async fn audit(&self, action: &str, target_type: &str, target_id: &str, details: JsonValue);
+}
+
+pub trait ApiResultExtensions<T> {
+    fn or_fail(self, context: &mut HttpContext) -> Result<T, PipelineError>;
+}
+
+impl<T> ApiResultExtensions<T> for Result<T, ApiError> {
+    fn or_fail(self, context: &mut HttpContext) -> Result<T, PipelineError> {
+        self.map_err(|error| context.fail(error))
+    }
+}
+
+impl<T> ApiResultExtensions<T> for Result<T, RepositoryError> {
+    fn or_fail(self, context: &mut HttpContext) -> Result<T, PipelineError> {
+        self.map_err(|error| {
+            if let (Some(kind), Ok(metrics)) = (error.metric_label(), context.service::<MetricsService>()) {
+                metrics.increment(MetricNames::DB_QUERY_ERRORS_TOTAL, &[("kind", kind)]);
+            }
+            context.fail(error.api_error())
+        })
+    }
 }
 
 #[async_trait]
@@ -63,21 +102,21 @@ impl HttpContextExtensions for HttpContext {
         Ok(())
     }
 
-    fn parse_browse_request(&self) -> Result<BrowseRequest, PipelineError> {
+    fn parse_browse_request(&self) -> Result<BrowseRequest, ApiError> {
         let params = self.request().query_params();
 
         let page_size = params
             .get("pageSize")
             .map(|value| value.parse::<i64>())
             .transpose()
-            .map_err(|_| PipelineError::message("invalid pageSize"))?;
+            .map_err(|_| ApiError::bad_request("invalid pageSize"))?;
 
         let path = params
             .get("path")
             .map(|value| {
                 decode(value)
                     .map(|decoded| decoded.into_owned())
-                    .map_err(|_| PipelineError::message("invalid path encoding"))
+                    .map_err(|_| ApiError::bad_request("invalid path encoding"))
             })
             .transpose()?;
 
@@ -86,50 +125,70 @@ impl HttpContextExtensions for HttpContext {
             .map(|value| {
                 decode(value)
                     .map(|decoded| decoded.into_owned())
-                    .map_err(|_| PipelineError::message("invalid cursor encoding"))
+                    .map_err(|_| ApiError::bad_request("invalid cursor encoding"))
             })
             .transpose()?;
 
-        Ok(BrowseRequest { path, page_size, cursor })
+        let decode_text = |key: &str| {
+            params
+                .get(key)
+                .map(|value| {
+                    decode(value)
+                        .map(|decoded| decoded.into_owned())
+                        .map_err(|_| ApiError::bad_request(format!("invalid {} encoding", key)))
+                })
+                .transpose()
+        };
+        let filter = decode_text("filter")?;
+        let search = decode_text("search")?;
+
+        let sort = params
+            .get("sort")
+            .map(|value| BrowseSort::parse(value).ok_or_else(|| ApiError::bad_request("invalid sort")))
+            .transpose()?;
+
+        let sort_direction = params
+            .get("sortDirection")
+            .map(|value| SortDirection::parse(value).ok_or_else(|| ApiError::bad_request("invalid sortDirection")))
+            .transpose()?;
+
+        Ok(BrowseRequest { path, page_size, cursor, filter, search, sort, sort_direction })
     }
 
-    fn route_storage_id(&self) -> Result<Uuid, PipelineError> {
+    fn route_storage_id(&self) -> Result<Uuid, ApiError> {
         let raw = self
             .route()
             .and_then(|route| route.params().get("storageId"))
             .cloned()
-            .ok_or_else(|| PipelineError::message("storageId parameter missing"))?;
-        Uuid::parse_str(&raw).map_err(|_| PipelineError::message("invalid storageId"))
+            .ok_or_else(|| ApiError::bad_request("storageId parameter missing"))?;
+        Uuid::parse_str(&raw).map_err(|_| ApiError::bad_request("invalid storageId"))
     }
 
-    fn hash(&self) -> Result<String, PipelineError> {
+    fn hash(&self) -> Result<String, ApiError> {
         let hash = self
             .route()
             .and_then(|route| route.params().get("hash"))
             .cloned()
-            .ok_or_else(|| PipelineError::message("hash parameter missing"))?;
-
-        if hash.len() < 4 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
-            return Err(PipelineError::message("invalid thumbnail hash"));
-        }
+            .ok_or_else(|| ApiError::bad_request("hash parameter missing"))?;
 
-        Ok(hash)
+        HashService::normalize(&hash).ok_or_else(|| ApiError::bad_request("invalid thumbnail hash"))
     }
 
-    fn default_preview_root(&self) -> PathBuf {
-        if cfg!(windows) {
-            if let Ok(user_profile) = std::env::var("USERPROFILE") {
-                return Path::new(&user_profile).join("AppData").join("Local").join("photon");
-            }
-        }
-
-        PathBuf::from("./previews")
+    fn app_config(&self) -> Arc<AppConfig> {
+        self.service::<ConfigReloadService>()
+            .map(|reload| reload.current())
+            .or_else(|_| self.service::<AppConfig>())
+            .unwrap_or_else(|_| Arc::new(AppConfig::from_configuration(self.config()).unwrap_or_default()))
     }
 
     fn is_admin(&self) -> bool {
         self.get::<IdentityContext>().map(|ctx| ctx.identity().claims().roles().contains("admin")).unwrap_or(false)
     }
 
+    fn is_anonymous(&self) -> bool {
+        !self.get::<IdentityContext>().map(|identity| identity.is_authenticated()).unwrap_or(false)
+    }
+
     fn is_viewer(&self) -> bool {
         self.get::<IdentityContext>()
             .map(|ctx| {
@@ -140,29 +199,46 @@ impl HttpContextExtensions for HttpContext {
             .unwrap_or(false)
     }
 
-    fn entity_id(&self) -> Result<Uuid, PipelineError> {
+    fn entity_id(&self) -> Result<Uuid, ApiError> {
         let id = self
             .route()
             .and_then(|route| route.params().get("id"))
-            .ok_or_else(|| PipelineError::message("id parameter missing"))?;
-        Uuid::parse_str(id).map_err(|_| PipelineError::message("invalid album id"))
+            .ok_or_else(|| ApiError::bad_request("id parameter missing"))?;
+        Uuid::parse_str(id).map_err(|_| ApiError::bad_request(format!("Invalid uuid: {}", id)))
     }
 
-    fn param(&self, key: &str) -> Result<String, PipelineError> {
+    fn param(&self, key: &str) -> Result<String, ApiError> {
         self.route()
             .and_then(|route| route.params().get(key))
             .cloned()
-            .ok_or_else(|| PipelineError::message(&format!("{} parameter missing", key)))
+            .ok_or_else(|| ApiError::bad_request(format!("{} parameter missing", key)))
     }
 
-    fn id(&self, key: &str) -> Result<Uuid, PipelineError> {
+    fn id(&self, key: &str) -> Result<Uuid, ApiError> {
         let id = self
             .route()
             .and_then(|route| route.params().get(key).cloned())
             .or_else(|| self.request().query_params().get(key).cloned())
-            .ok_or_else(|| PipelineError::message(&format!("{} parameter missing", key)))?;
+            .ok_or_else(|| ApiError::bad_request(format!("{} parameter missing", key)))?;
+
+        Uuid::parse_str(&id).map_err(|_| ApiError::bad_request(format!("Invalid uuid: {}", id)))
+    }
 
-        Uuid::parse_str(&id).map_err(|_| PipelineError::message(&format!("Invalid uuid: {}", id)))
+    fn fail(&mut self, error: ApiError) -> PipelineError {
+        self.response_mut().set_status(error.status());
+        let pipeline_error = PipelineError::from(error.clone());
+        self.insert(error);
+        pipeline_error
+    }
+
+    fn read_payload<T: DeserializeOwned>(&mut self) -> Result<T, PipelineError> {
+        self.read_json::<T>().map_err(|err| ApiError::bad_request(err.message())).or_fail(self)
+    }
+
+    fn read_valid_json<T: DeserializeOwned + Validate>(&mut self) -> Result<T, PipelineError> {
+        let payload = self.read_payload::<T>()?;
+        payload.validate().map_err(ApiError::invalid).or_fail(self)?;
+        Ok(payload)
     }
 
     fn page(&self) -> Result<u32, PipelineError> {
@@ -187,6 +263,27 @@ impl HttpContextExtensions for HttpContext {
         Uuid::parse_str(&subject).map_err(|_| PipelineError::message("Invalid identity: user ID is not valid"))
     }
 
+    fn request_id(&self) -> Option<String> {
+        self.get::<RequestId>().map(|request_id| request_id.as_str().to_string())
+    }
+
+    fn session_client(&self) -> SessionClient {
+        let headers = self.request().headers();
+        SessionClient::from_headers(headers.get("user-agent"), headers.get("x-forwarded-for"), headers.get("x-real-ip"))
+    }
+
+    fn current_session_id(&self) -> Option<Uuid> {
+        self.request().headers().get(SessionClient::SESSION_HEADER).and_then(|value| Uuid::parse_str(value.trim()).ok())
+    }
+
+    fn public_base_url(&self) -> String {
+        let headers = self.request().headers();
+        FeedMiddleware::base_url(
+            headers.get("x-forwarded-proto"),
+            headers.get("x-forwarded-host").or_else(|| headers.get("host")),
+        )
+    }
+
     async fn current_user_display_name(&self) -> Result<String, PipelineError> {
         let user_id = self.current_user_id()?;
         let settings_repo = self.service::<Repository<UserSettings>>()?;
@@ -242,6 +339,18 @@ impl HttpContextExtensions for HttpContext {
         self.service::<SettingService>()?.can_access_dashboard(&roles).await
     }
 
+    async fn can_tag_photos(&self) -> Result<bool, PipelineError> {
+        let roles =
+            self.get::<IdentityContext>().map(|ctx| ctx.identity().claims().roles().clone()).unwrap_or_default();
+        self.service::<SettingService>()?.can_tag_photos(&roles).await
+    }
+
+    async fn can_edit_photos(&self) -> Result<bool, PipelineError> {
+        let roles =
+            self.get::<IdentityContext>().map(|ctx| ctx.identity().claims().roles().clone()).unwrap_or_default();
+        self.service::<SettingService>()?.can_edit_photos(&roles).await
+    }
+
     async fn can_update_setting(&self, key: &str) -> Result<bool, PipelineError> {
         let roles =
             self.get::<IdentityContext>().map(|ctx| ctx.identity().claims().roles().clone()).unwrap_or_default();
@@ -256,6 +365,35 @@ impl HttpContextExtensions for HttpContext {
         settings.viewer_hidden_tags().await
     }
 
+    async fn coordinate_precision(&self) -> Result<CoordinatePrecision, PipelineError> {
+        if self.is_admin() {
+            return Ok(CoordinatePrecision::Exact);
+        }
+        self.service::<SettingService>()?.coordinate_precision_for_viewers().await
+    }
+
+    async fn timeline_zone(&self) -> TimelineZone {
+        if let Some(requested) = self.request().query_params().get("tz") {
+            let decoded = decode(requested).map(|value| value.into_owned()).unwrap_or_default();
+            return TimelineZone::from_name(Some(&decoded));
+        }
+
+        let Ok(user_id) = self.current_user_id() else {
+            return TimelineZone::utc();
+        };
+        let Ok(settings_repo) = self.service::<Repository<UserSettings>>() else {
+            return TimelineZone::utc();
+        };
+        match settings_repo.get(&user_id).await {
+            Ok(Some(settings)) => TimelineZone::from_name(Some(&settings.timezone)),
+            Ok(None) => TimelineZone::utc(),
+            Err(err) => {
+                log::warn!("Failed to load timezone for user {}: {:?}", user_id, err);
+                TimelineZone::utc()
+            }
+        }
+    }
+
     async fn current_client_id(&self) -> Result<Uuid, PipelineError> {
         if let Some(identity) = self.get::<IdentityContext>() {
             let subject = identity.identity().subject().to_string();
@@ -328,116 +466,84 @@ impl HttpContextExtensions for HttpContext {
         }
     }
 
-    async fn get_preview_root(&self, hash: &str) -> Result<PathBuf, PipelineError> {
-        let preview_storage_id = SettingConsts::DEFAULT_STORAGE_ID;
-        let preview_root = self.default_preview_root();
-
-        if let Ok(storage_repo) = self.service::<Repository<StorageLocation>>() {
-            match storage_repo.get(&preview_storage_id).await {
-                Ok(Some(_)) => {}
-                Ok(None) => {
-                    if let Err(err) = std::fs::create_dir_all(&preview_root) {
-                        log::warn!("Failed to create default preview root '{}': {:?}", preview_root.display(), err);
-                    }
+    fn invalidate_storage_paths(&self) {
+        if let Ok(cache) = self.service::<StoragePathCache>() {
+            cache.invalidate();
+        }
+    }
 
-                    let preview_storage = StorageLocation {
-                        id: preview_storage_id,
-                        label: "Preview Cache".to_string(),
-                        path: preview_root.to_string_lossy().to_string(),
-                        is_default: false,
-                        is_readonly: preview_storage_id == SettingConsts::DEFAULT_STORAGE_ID,
-                        created_at: Utc::now().to_rfc3339(),
-                        category_template: "{year}/{date:%Y-%m-%d}/{fileName}".to_string(),
-                    };
-
-                    if let Err(err) = storage_repo.insert(preview_storage).await {
-                        log::warn!("Failed to create preview storage {}: {:?}", preview_storage_id, err);
-                    }
-                }
-                Err(err) => {
-                    log::warn!("Failed to load preview storage {}: {:?}", preview_storage_id, err);
-                }
-            }
+    fn invalidate_cors_policy(&self) {
+        if let Ok(cache) = self.service::<CorsPolicyCache>() {
+            cache.invalidate();
         }
+    }
 
-        let photo_repo = self.service::<Repository<Photo>>()?;
-        let photo = photo_repo.find_by_hash(&hash).await?.ok_or_else(|| PipelineError::message("preview not found"))?;
+    async fn derivative_profile(&self) -> Arc<DerivativeProfile> {
+        let (Ok(cache), Ok(settings)) = (self.service::<DerivativeProfileCache>(), self.service::<SettingService>())
+        else {
+            return Arc::new(DerivativeProfile::default());
+        };
+        cache.profile(&settings).await.unwrap_or_else(|error| {
+            log::warn!("Using default derivative profile: {:?}", error);
+            Arc::new(DerivativeProfile::default())
+        })
+    }
 
-        let storage_id = photo.storage_id;
-        if let Ok(storage_repo) = self.service::<Repository<StorageLocation>>() {
-            match storage_repo.get(&storage_id).await {
-                Ok(Some(storage)) => {
-                    return Ok(storage.normalized_path().join(".previews"));
-                }
-                Ok(None) => {
-                    log::warn!("Storage {} not found while resolving preview for hash {}", storage_id, hash);
-                }
-                Err(err) => {
-                    log::warn!("Failed to load storage {} for preview hash {}: {:?}", storage_id, hash, err);
-                }
-            }
-        } else {
-            log::warn!("Storage repository unavailable while resolving preview for hash {}", hash);
+    fn invalidate_derivative_profile(&self) {
+        if let Ok(cache) = self.service::<DerivativeProfileCache>() {
+            cache.invalidate();
         }
+    }
 
-        Ok(preview_root)
+    async fn storage_root(&self, storage_id: Uuid) -> Result<Option<PathBuf>, PipelineError> {
+        let storage_repo = self.service::<Repository<StorageLocation>>()?;
+        match self.service::<StoragePathCache>() {
+            Ok(cache) => cache.root(&storage_repo, storage_id).await,
+            Err(_) => Ok(storage_repo
+                .get(&storage_id)
+                .await
+                .map_err(|_| PipelineError::message("failed to load storage location"))?
+                .map(|storage| storage.normalized_path())),
+        }
+    }
+
+    async fn get_preview_root(&self, hash: &str) -> Result<PathBuf, PipelineError> {
+        let photo_repo = self.service::<Repository<Photo>>()?;
+        let photo = Photo::select_by_hash(photo_repo.find_by_hash_all(hash).await?, None)
+            .ok_or_else(|| PipelineError::message("preview not found"))?;
+        self.get_preview_root_by_storage(photo.storage_id).await
     }
 
     async fn get_preview_path(&self, hash: &str) -> Result<PathBuf, PipelineError> {
         let preview_root = self.get_preview_root(hash).await?;
-        Ok(preview_root.join(&hash[0..2]).join(&hash[2..4]).join(format!("{hash}.jpg")))
+        Ok(FileService::hash_path(preview_root, hash, SettingConsts::PREVIEW_FORMAT))
     }
 
     async fn get_preview_root_by_storage(&self, storage_id: Uuid) -> Result<PathBuf, PipelineError> {
-        let storage_repo = self.service::<Repository<StorageLocation>>()?;
-        let storage = storage_repo
-            .get(&storage_id)
-            .await
-            .map_err(|_| PipelineError::message("failed to load storage location"))?
-            .ok_or_else(|| PipelineError::message("storage location not found"))?;
-        Ok(storage.normalized_path().join(".previews"))
+        let root =
+            self.storage_root(storage_id).await?.ok_or_else(|| PipelineError::message("storage location not found"))?;
+        Ok(root.join(SettingConsts::PREVIEW_FOLDER))
     }
 
     async fn get_preview_path_by_storage(&self, storage_id: Uuid, hash: &str) -> Result<PathBuf, PipelineError> {
         let preview_root = self.get_preview_root_by_storage(storage_id).await?;
-        Ok(preview_root.join(&hash[0..2]).join(&hash[2..4]).join(format!("{hash}.jpg")))
+        Ok(FileService::hash_path(preview_root, hash, SettingConsts::PREVIEW_FORMAT))
     }
 
     async fn get_thumbnail_root_by_storage(&self, storage_id: Uuid) -> Result<PathBuf, PipelineError> {
-        let storage_repo = self.service::<Repository<StorageLocation>>()?;
-        let storage = storage_repo
-            .get(&storage_id)
-            .await
-            .map_err(|_| PipelineError::message("failed to load storage location"))?
-            .ok_or_else(|| PipelineError::message("storage location not found"))?;
-        Ok(storage.normalized_path().join(SettingConsts::THUMBNAIL_FOLDER))
-    }
-
-    async fn get_thumbnail_roots(&self) -> Result<Vec<PathBuf>, PipelineError> {
-        let mut roots = Vec::<PathBuf>::new();
-        if let Ok(storage_repo) = self.service::<Repository<StorageLocation>>() {
-            if let Ok(page) = storage_repo.query(Query::<StorageLocation>::new()).await {
-                for location in page.items {
-                    let path = location.normalized_path().join(SettingConsts::THUMBNAIL_FOLDER);
-                    if !roots.contains(&path) {
-                        roots.push(path);
-                    }
-                }
-            }
-        }
+        let root =
+            self.storage_root(storage_id).await?.ok_or_else(|| PipelineError::message("storage location not found"))?;
+        Ok(root.join(SettingConsts::THUMBNAIL_FOLDER))
+    }
 
-        let config = self.config();
-        let default_legacy_base = format!("./{}", SettingConsts::THUMBNAIL_FOLDER);
-        let legacy_base = config
-            .get("thumbnail.base.path")
-            .or_else(|| config.get("thumbnail.basepath"))
-            .unwrap_or(default_legacy_base.as_str());
-        let legacy_path = Path::new(legacy_base).to_path_buf();
-        if !roots.contains(&legacy_path) {
-            roots.push(legacy_path);
+    // Hidden photos come back as no rows for non-admins, so they look exactly like missing ones.
+    async fn visible_photos(&mut self, hash: &str, missing: &str) -> Result<Vec<Photo>, PipelineError> {
+        let photo_repo = self.service::<Repository<Photo>>()?;
+        let photos = photo_repo.find_visible_by_hash(hash, self.is_admin()).await?;
+        if photos.is_empty() {
+            return Err(self.fail(ApiError::not_found(missing)));
         }
-
-        Ok(roots)
+        Ok(photos)
     }
 
     async fn is_preview_exists(&self, hash: &str) -> bool {
@@ -446,4 +552,17 @@ impl HttpContextExtensions for HttpContext {
             Err(_) => false,
         }
     }
+
+    async fn audit(&self, action: &str, target_type: &str, target_id: &str, details: JsonValue) {
+        let entry = AuditLog::new(self.current_user_id().ok(), action, target_type, target_id).with_details(details);
+        match self.service::<AuditService>() {
+            Ok(service) => service.record(entry).await,
+            Err(_) => log::warn!(
+                "AuditService unavailable, audit entry '{}' on {} {} not recorded",
+                action,
+                target_type,
+                target_id
+            ),
+        }
+    }
 }
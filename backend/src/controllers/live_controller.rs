@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::prelude::*;
+
+pub struct LiveController;
+
+impl Controller for LiveController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct IssueLiveTicketHandler;
+
+#[async_trait]
+#[post("/api/ws/ticket", policy = Policy::Authenticated)]
+impl HttpHandler for IssueLiveTicketHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let service = context.service::<LiveUpdateService>()?;
+        let Some(address) = service.address().map(str::to_string) else {
+            return Err(context.fail(ApiError::conflict("Live updates are disabled")));
+        };
+
+        let user_id = context.current_user_id()?;
+        let audience = LiveAudience::new(context.is_admin(), context.is_viewer());
+        let (ticket, expires_at) = service.issue_ticket(user_id, audience);
+        let port = address.rsplit_once(':').and_then(|(_, port)| port.parse::<u16>().ok());
+        Ok(ResponseValue::json(LiveTicketDto { ticket, expires_at, path: LiveUpdateService::PATH.to_string(), port }))
+    }
+}
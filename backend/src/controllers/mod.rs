@@ -1,39 +1,99 @@
 pub mod admin_user_controller;
 pub mod album_controller;
 pub mod assets_controller;
+pub mod audit_controller;
 pub mod auth_controller;
 pub mod client_controller;
+pub mod config_controller;
 pub mod dashboard_controller;
+pub mod embed_controller;
+pub mod health_controller;
 pub mod httpcontext_extensions;
+pub mod live_controller;
+pub mod notification_controller;
+pub mod people_controller;
 pub mod photo_controller;
+pub mod setup_controller;
 pub mod storage_controller;
 pub mod tag_controller;
+pub mod task_controller;
 pub mod timeline_controller;
+pub mod upload_session_controller;
+pub mod user_settings_controller;
 
-use nimble_web::AppBuilder;
+use nimble_web::{AppBuilder, Controller, EndpointRegistry};
 
 pub use admin_user_controller::AdminUserController;
 pub use album_controller::AlbumController;
 pub use assets_controller::AssetsController;
+pub use audit_controller::AuditController;
 pub use auth_controller::AuthController;
 pub use client_controller::ClientHandlers;
+pub use config_controller::ConfigController;
 pub use dashboard_controller::DashboardController;
-pub use httpcontext_extensions::HttpContextExtensions;
+pub use embed_controller::EmbedController;
+pub use health_controller::HealthController;
+pub use httpcontext_extensions::{ApiResultExtensions, HttpContextExtensions};
+pub use live_controller::LiveController;
+pub use notification_controller::NotificationController;
+pub use people_controller::PeopleController;
 pub use photo_controller::PhotoController;
+pub use setup_controller::SetupController;
 pub use storage_controller::StorageController;
 pub use tag_controller::TagController;
+pub use task_controller::TaskController;
+pub use upload_session_controller::UploadSessionController;
+pub use user_settings_controller::UserSettingsController;
+
+pub trait ControllerRegistrar {
+    fn add<C: Controller + Send + Sync + 'static>(&mut self) -> &mut Self;
+}
+
+impl ControllerRegistrar for AppBuilder {
+    fn add<C: Controller + Send + Sync + 'static>(&mut self) -> &mut Self {
+        self.use_controller::<C>()
+    }
+}
+
+impl ControllerRegistrar for EndpointRegistry {
+    fn add<C: Controller + Send + Sync + 'static>(&mut self) -> &mut Self {
+        self.register::<C>();
+        self
+    }
+}
 
 pub fn register_controllers(builder: &mut AppBuilder) -> &mut AppBuilder {
+    add_controllers(builder);
     builder
-        .use_controller::<AdminUserController>()
-        .use_controller::<AuthController>()
-        .use_controller::<ClientHandlers>()
-        .use_controller::<PhotoController>()
-        .use_controller::<TagController>()
-        .use_controller::<DashboardController>()
-        .use_controller::<AlbumController>()
-        .use_controller::<AssetsController>()
-        .use_controller::<StorageController>();
+}
 
-    builder
+// The API document is built from the same list, so new controllers only need adding here.
+pub fn endpoint_registry() -> EndpointRegistry {
+    let mut registry = EndpointRegistry::new();
+    add_controllers(&mut registry);
+    registry
+}
+
+fn add_controllers<R: ControllerRegistrar>(registrar: &mut R) {
+    registrar
+        .add::<AdminUserController>()
+        .add::<AuditController>()
+        .add::<TaskController>()
+        .add::<AuthController>()
+        .add::<SetupController>()
+        .add::<HealthController>()
+        .add::<ConfigController>()
+        .add::<LiveController>()
+        .add::<EmbedController>()
+        .add::<NotificationController>()
+        .add::<ClientHandlers>()
+        .add::<PhotoController>()
+        .add::<UploadSessionController>()
+        .add::<TagController>()
+        .add::<PeopleController>()
+        .add::<DashboardController>()
+        .add::<AlbumController>()
+        .add::<AssetsController>()
+        .add::<StorageController>()
+        .add::<UserSettingsController>();
 }
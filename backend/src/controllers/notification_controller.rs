@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+
+use crate::prelude::*;
+
+pub struct NotificationController;
+
+impl Controller for NotificationController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct SendTestDigestHandler;
+
+#[async_trait]
+#[post("/api/admin/notifications/test-digest", policy = Policy::Authenticated)]
+impl HttpHandler for SendTestDigestHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            return Err(context.fail(ApiError::forbidden("Only administrators can send a test digest")));
+        }
+        if !context.service::<EmailService>()?.is_configured() {
+            return Err(context.fail(ApiError::conflict("Email is not configured; set email.smtpHost and email.from")));
+        }
+
+        let user_id = context.current_user_id()?;
+        let user = context.service::<Repository<User>>()?.get(&user_id).await.map_err(|e| {
+            let msg = format!("Failed to load user {}: {:?}", user_id, e);
+            PipelineError::message(&msg)
+        })?;
+        let Some(user) = user else {
+            return Err(context.fail(ApiError::not_found("User not found")));
+        };
+
+        let service = context.service::<NotificationDigestService>()?;
+        let run = match service.send_test(&user.email).await {
+            Ok(run) => run,
+            Err(error) => {
+                log::warn!("Test digest to {} failed: {:?}", user.email, error);
+                return Err(context.fail(ApiError::internal("Failed to send the test digest")));
+            }
+        };
+        context
+            .audit(
+                AuditActions::NOTIFICATION_TEST_DIGEST,
+                AuditTargets::DIGEST_RUN,
+                &run.id.to_string(),
+                json!({ "to": user.email, "photos": run.photos, "comments": run.comments, "albums": run.albums }),
+            )
+            .await;
+        Ok(ResponseValue::json(run))
+    }
+}
+
+struct ListNotificationsHandler;
+
+impl ListNotificationsHandler {
+    const DEFAULT_PAGE_SIZE: u32 = 20;
+    const MAX_PAGE_SIZE: u32 = 100;
+
+    fn parse_number(context: &HttpContext, key: &str, fallback: u32) -> Result<u32, ApiError> {
+        match context.request().query_params().get(key) {
+            Some(raw) => raw
+                .parse::<u32>()
+                .ok()
+                .filter(|value| *value > 0)
+                .ok_or_else(|| ApiError::bad_request(format!("invalid {}", key))),
+            None => Ok(fallback),
+        }
+    }
+}
+
+#[async_trait]
+#[get("/api/notifications", policy = Policy::Authenticated)]
+impl HttpHandler for ListNotificationsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let user_id = context.current_user_id()?;
+        let page = Self::parse_number(context, "page", 1).or_fail(context)?;
+        let page_size =
+            Self::parse_number(context, "pageSize", Self::DEFAULT_PAGE_SIZE).or_fail(context)?.min(Self::MAX_PAGE_SIZE);
+        let unread_only = context
+            .request()
+            .query_params()
+            .get("unreadOnly")
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("true"));
+
+        let service = context.service::<NotificationService>()?;
+        Ok(ResponseValue::json(service.list(user_id, unread_only, page, page_size).await?))
+    }
+}
+
+struct MarkNotificationReadHandler;
+
+#[async_trait]
+#[post("/api/notifications/{id}/read", policy = Policy::Authenticated)]
+impl HttpHandler for MarkNotificationReadHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let user_id = context.current_user_id()?;
+        let notification_id = context.id("id").or_fail(context)?;
+
+        let service = context.service::<NotificationService>()?;
+        match service.mark_read(user_id, notification_id).await? {
+            Some(notification) => Ok(ResponseValue::json(notification)),
+            None => Err(context.fail(ApiError::not_found("Notification not found"))),
+        }
+    }
+}
+
+struct MarkAllNotificationsReadHandler;
+
+#[async_trait]
+#[post("/api/notifications/read-all", policy = Policy::Authenticated)]
+impl HttpHandler for MarkAllNotificationsReadHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let user_id = context.current_user_id()?;
+        let service = context.service::<NotificationService>()?;
+        let updated = service.mark_all_read(user_id).await?;
+        Ok(ResponseValue::json(NotificationsReadDto { updated }))
+    }
+}
@@ -0,0 +1,50 @@
+use crate::prelude::*;
+
+pub struct PeopleController;
+
+impl Controller for PeopleController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct ListPeopleHandler;
+
+#[async_trait]
+#[get("/api/people")]
+impl HttpHandler for ListPeopleHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let mut hidden_tags = if context.is_viewer() { context.viewer_hidden_tags().await? } else { HashSet::new() };
+        if !context.is_admin() {
+            hidden_tags.extend(context.service::<Repository<Tag>>()?.admin_only_tag_names().await.or_fail(context)?);
+        }
+
+        let people = context.service::<Repository<Photo>>()?.list_people(&hidden_tags).await?;
+        Ok(ResponseValue::json(people))
+    }
+}
+
+struct RenamePersonHandler;
+
+#[async_trait]
+#[post("/api/people/rename", policy = Policy::Authenticated)]
+impl HttpHandler for RenamePersonHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_tag_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to rename people")));
+        }
+
+        let payload = context.read_valid_json::<RenamePersonPayload>()?;
+        let updated = context.service::<Repository<Photo>>()?.rename_person(&payload.from, &payload.to).await?;
+        context
+            .audit(
+                AuditActions::PERSON_RENAME,
+                AuditTargets::PERSON,
+                payload.from.trim(),
+                json!({ "from": payload.from.trim(), "to": payload.to.trim(), "photos": updated }),
+            )
+            .await;
+
+        Ok(ResponseValue::json(json!({ "updated": updated })))
+    }
+}
@@ -1,6 +1,5 @@
 use serde::Deserialize;
 use std::result::Result;
-use tokio::task;
 
 use crate::prelude::*;
 
@@ -10,7 +9,10 @@ pub struct PhotoController;
 
 impl Controller for PhotoController {
     fn routes() -> Vec<EndpointRoute> {
-        vec![]
+        vec![
+            #[cfg(feature = "testbot")]
+            EndpointRoute::post("/api/test/photos/seed", TestSeedPhotoHandler).build(),
+        ]
     }
 }
 
@@ -22,64 +24,104 @@ impl HttpHandler for UploadPhotosHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
         let settings = context.service::<SettingService>()?;
         if !context.can_upload_photos().await? {
-            context.response_mut().set_status(403);
-            return Ok(ResponseValue::empty());
+            return Err(context.fail(ApiError::forbidden("Photo upload is not allowed")));
         }
 
         let uploads_enabled = settings.is_photo_upload_enabled().await?;
         if !uploads_enabled {
-            context.response_mut().set_status(403);
-            return Ok(ResponseValue::empty());
+            return Err(context.fail(ApiError::forbidden("Photo upload is disabled")));
         }
         log::info!("Processing photo upload request");
 
         let upload_service = context.service::<PhotoUploadService>()?;
         let content_type_header = upload_service
             .require_content_type(context.request().headers().get("content-type"))
-            .map_err(|error| PipelineError::message(&error.to_string()))?;
+            .map(str::to_string)
+            .map_err(|error| ApiError::bad_request(error.to_string()))
+            .or_fail(context)?;
         let request_body = context.body_bytes()?;
 
-        let storage_id = context.id("storageId")?;
+        let storage_id = context.id("storageId").or_fail(context)?;
         let storage_repo = context.service::<Repository<StorageLocation>>()?;
         let storage = storage_repo
             .get(&storage_id)
             .await
             .map_err(|_| PipelineError::message("Storage location not found"))?
-            .ok_or_else(|| PipelineError::message("Storage is not found"))?;
+            .ok_or_else(|| ApiError::not_found("Storage is not found"))
+            .or_fail(context)?;
         if storage.is_readonly {
-            context.response_mut().set_status(403);
-            return Err(PipelineError::message("Storage is readonly"));
+            return Err(context.fail(ApiError::forbidden("Storage is readonly")));
         }
 
-        let saved_files = upload_service
-            .persist_multipart_to_storage_temp(content_type_header, request_body, Path::new(&storage.path))
+        let uploads = upload_service
+            .persist_multipart_to_storage_temp(&content_type_header, request_body, Path::new(&storage.path))
             .await
             .map_err(|error| PipelineError::message(&error.to_string()))?;
 
-        if saved_files.is_empty() {
-            return Err(PipelineError::message("No files found in upload request"));
+        if uploads.is_empty() {
+            return Err(context.fail(ApiError::bad_request("No files found in upload request")));
         }
 
-        if !saved_files.is_empty() {
-            let pipeline = context.service::<ImageProcessPipeline>()?;
-            pipeline.enqueue_files(storage.clone(), saved_files.clone()).map_err(|error| {
-                log::error!("Failed to enqueue image pipeline: {:?}", error);
-                PipelineError::message("Failed to schedule image processing tasks")
-            })?;
-        }
+        let PersistedUploads { saved: saved_files, rejected } = uploads;
+        let pipeline = context.service::<ImageProcessPipeline>()?;
+        let batch_id = Uuid::new_v4();
+        let synchronous = !saved_files.is_empty()
+            && Self::wants_sync(context)
+            && saved_files.len() <= settings.sync_upload_max_files().await?;
+        let processed = if saved_files.is_empty() {
+            Vec::new()
+        } else if synchronous {
+            let timeout = settings.sync_upload_timeout().await?;
+            pipeline
+                .process_now(storage.clone(), saved_files.clone(), batch_id, context.request_id(), timeout)
+                .await
+                .map_err(|error| {
+                    log::error!("Failed to process uploaded photos: {:?}", error);
+                    PipelineError::message("Failed to process uploaded photos")
+                })?
+        } else {
+            pipeline
+                .enqueue_files_for_request(storage.clone(), saved_files.clone(), Some(batch_id), context.request_id())
+                .await
+                .map_err(|error| {
+                    log::error!("Failed to enqueue image pipeline: {:?}", error);
+                    PipelineError::message("Failed to schedule image processing tasks")
+                })?;
+            Vec::new()
+        };
+        let mut processed = processed.into_iter();
 
         let response = UploadPhotosResponse {
             storage_id: storage.id.to_string(),
             storage_path: storage.path,
             uploaded_count: saved_files.len(),
+            rejected_count: rejected.len(),
+            batch_id,
+            synchronous,
             files: saved_files
                 .into_iter()
-                .map(|item| UploadFileResponse {
-                    file_name: item.file_name,
-                    relative_path: item.relative_path,
-                    byte_size: item.byte_size,
-                    content_type: item.content_type,
+                .map(|item| {
+                    let outcome = processed.next();
+                    let mut file = UploadFileResponse::accepted(
+                        item.file_name,
+                        item.relative_path,
+                        item.byte_size,
+                        item.content_type,
+                    );
+                    file.status = outcome.as_ref().map(|outcome| outcome.status);
+                    file.photo = outcome.and_then(|outcome| {
+                        let has_thumbnail = outcome.has_thumbnail;
+                        outcome.photo.map(|photo| UploadedPhotoResponse {
+                            id: photo.id,
+                            hash: photo.hash,
+                            width: photo.width,
+                            height: photo.height,
+                            has_thumbnail,
+                        })
+                    });
+                    file
                 })
+                .chain(rejected.into_iter().map(UploadFileResponse::rejected))
                 .collect(),
         };
 
@@ -87,26 +129,34 @@ impl HttpHandler for UploadPhotosHandler {
     }
 }
 
+impl UploadPhotosHandler {
+    fn wants_sync(context: &HttpContext) -> bool {
+        context.request().query_params().get("sync").is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    }
+}
+
 struct DeletePhotosHandler;
 
 #[async_trait]
 #[delete("/api/photos", policy = Policy::Authenticated)]
 impl HttpHandler for DeletePhotosHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload = context.read_json::<DeletePhotosPayload>().map_err(|e| PipelineError::message(e.message()))?;
+        let payload = context.read_payload::<DeletePhotosPayload>()?;
 
         if payload.photo_ids.is_empty() {
-            return Err(PipelineError::message("photoIds cannot be empty"));
+            return Err(context.fail(ApiError::bad_request("photoIds cannot be empty")));
         }
 
         let photo_repo = context.service::<Repository<Photo>>()?;
         let timeline_repo = context.service::<Repository<TimelineDay>>()?;
+        let live = context.service::<LiveUpdateService>().ok();
 
         let mut deleted = 0u32;
 
         for raw_photo_id in payload.photo_ids {
             let photo_id = Uuid::parse_str(raw_photo_id.trim())
-                .map_err(|e| PipelineError::message(&format!("invalid photo id: {}", e)))?;
+                .map_err(|e| ApiError::bad_request(format!("invalid photo id: {}", e)))
+                .or_fail(context)?;
 
             let Some(photo) =
                 photo_repo.get(&photo_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
@@ -114,10 +164,26 @@ impl HttpHandler for DeletePhotosHandler {
                 continue;
             };
 
-            deleted += photo_repo
+            let removed = photo_repo
                 .delete_photo(context, &photo)
                 .await
                 .map_err(|e| PipelineError::message(&format!("failed to delete photo: {:?}", e)))?;
+
+            if removed > 0 {
+                context
+                    .audit(
+                        AuditActions::PHOTO_DELETE,
+                        AuditTargets::PHOTO,
+                        &photo.id.to_string(),
+                        json!({ "name": photo.name, "hash": photo.hash, "storageId": photo.storage_id }),
+                    )
+                    .await;
+                if let Some(live) = &live {
+                    live.publish(LiveEvent::PhotoDeleted { photo_id: photo.id });
+                }
+            }
+
+            deleted += removed;
         }
 
         if deleted > 0 {
@@ -131,66 +197,218 @@ impl HttpHandler for DeletePhotosHandler {
     }
 }
 
+struct PhotoExistsHandler;
+
+#[async_trait]
+#[post("/api/photos/exists")]
+impl HttpHandler for PhotoExistsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        // Hash probing reveals library contents, so only signed-in users and registered clients may ask.
+        let authenticated = context.get::<IdentityContext>().is_some_and(|identity| identity.is_authenticated());
+        if !authenticated {
+            let Ok(api_key) = context.extract_api_key() else {
+                context.response_mut().set_status(401);
+                return Err(PipelineError::message("Authentication or a client api key is required"));
+            };
+            context.validate_api_key(&api_key).await?;
+        }
+
+        let payload = context.read_valid_json::<PhotoExistsRequest>()?;
+        let hashes = payload.normalized_hashes();
+        let photo_repo = context.service::<Repository<Photo>>()?;
+        // Upload clients dedupe against everything stored, including photos hidden from viewers.
+        let locations = photo_repo.find_hash_locations(&hashes, true).await?;
+
+        Ok(ResponseValue::json(PhotoExistsResponse::from_locations(hashes, locations)))
+    }
+}
+
+struct RepairPhotoPathsHandler;
+
+#[async_trait]
+#[post("/api/admin/photos/repair-paths", policy = Policy::Authenticated)]
+impl HttpHandler for RepairPhotoPathsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            return Err(context.fail(ApiError::forbidden("Admin access required")));
+        }
+
+        let payload = context.read_payload::<PhotoPathRepairRequest>()?;
+        PhotoPathRepairService::validate(&payload).or_fail(context)?;
+
+        let service = context.service::<PhotoPathRepairService>()?;
+        let result = service.repair(&payload).await?;
+
+        if !payload.dry_run && result.missing > 0 {
+            context.response_mut().set_status(409);
+        }
+        if result.updated > 0 {
+            context
+                .audit(
+                    AuditActions::PHOTO_PATHS_REPAIR,
+                    AuditTargets::STORAGE,
+                    &payload.storage_id.to_string(),
+                    json!({ "oldPrefix": payload.old_prefix, "newPrefix": payload.new_prefix, "updated": result.updated }),
+                )
+                .await;
+        }
+        Ok(ResponseValue::json(result))
+    }
+}
+
 struct ThumbnailByStorageHandler;
 
 #[async_trait]
 #[get("/api/photos/thumbnail/{storage_id}/{hash}")]
 impl HttpHandler for ThumbnailByStorageHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let storage_id = context.id("storage_id")?;
-        let hash = context.hash()?;
+        let storage_id = context.id("storage_id").or_fail(context)?;
+        let hash = context.hash().or_fail(context)?;
+        let photos = context.visible_photos(&hash, "thumbnail not found").await?;
 
-        let file_service = context.service::<FileService>()?;
+        let profile = context.derivative_profile().await;
         let root = context.get_thumbnail_root_by_storage(storage_id).await?;
-        let thumb_path = file_service.path_for_hash(root, &hash, SettingConsts::THUMBNAIL_FORMAT);
-
-        if !thumb_path.exists() {
-            return Err(PipelineError::message("thumbnail not found"));
+        if let Some((existing, format)) = ThumbnailHandler::existing_thumbnail(context, &root, &hash, &profile)? {
+            return Ok(ThumbnailHandler::file_response(existing, format, &profile));
         }
+        let thumb_path = FileService::hash_path(&root, &hash, profile.thumbnail_format.extension());
+
+        let Some(photo) = Photo::select_by_hash(photos, Some(storage_id)) else {
+            return Err(context.fail(ApiError::not_found("thumbnail not found")));
+        };
 
-        Ok(ResponseValue::new(
-            FileResponse::from_path(thumb_path)
-                .with_content_type(SettingConsts::THUMBNAIL_CONTENT_TYPE)
-                .with_header("Cache-Control", SettingConsts::DEFAULT_HTTP_IMAGE_CACHE_HEADER),
-        ))
+        match ThumbnailHandler::generate_thumbnail(context, &photo, thumb_path, &hash, &profile).await? {
+            Ok(Some(path)) => Ok(ThumbnailHandler::file_response(path, profile.thumbnail_format, &profile)),
+            Ok(None) => Err(context.fail(ApiError::not_found("thumbnail not found"))),
+            Err(PreviewBusy) => Ok(PreviewHandler::busy_response(context)),
+        }
     }
 }
 
 struct ThumbnailHandler;
 
+impl ThumbnailHandler {
+    fn file_response(path: PathBuf, format: ThumbnailFormat, profile: &DerivativeProfile) -> ResponseValue {
+        let response = FileResponse::from_path(path)
+            .with_content_type(format.content_type())
+            .with_header("Cache-Control", SettingConsts::DEFAULT_HTTP_IMAGE_CACHE_HEADER);
+        // Once AVIF can be negotiated, caches must key thumbnails on the Accept header.
+        let response = if profile.thumbnail_avif { response.with_header("Vary", "Accept") } else { response };
+        ResponseValue::new(response)
+    }
+
+    // Thumbnails built before the format setting changed keep being served until they are rebuilt.
+    fn existing_thumbnail(
+        context: &HttpContext,
+        root: &Path,
+        hash: &str,
+        profile: &DerivativeProfile,
+    ) -> Result<Option<(PathBuf, ThumbnailFormat)>, PipelineError> {
+        let file_service = context.service::<FileService>()?;
+        let candidates = profile.thumbnail_candidates(context.request().headers().get("accept"));
+        let existing = candidates.into_iter().find_map(|format| {
+            let path = file_service.path_for_hash(root, hash, format.extension());
+            path.exists().then_some((path, format))
+        });
+
+        if let Some((path, format)) = &existing {
+            if *format != ThumbnailFormat::Avif {
+                Self::schedule_avif(context, path, profile);
+            }
+        }
+        Ok(existing)
+    }
+
+    // Never encodes on the request thread; the sibling is served from the next request on.
+    fn schedule_avif(context: &HttpContext, source: &Path, profile: &DerivativeProfile) {
+        if !profile.thumbnail_avif {
+            return;
+        }
+        let avif_path = source.with_extension(ThumbnailFormat::Avif.extension());
+        if avif_path.exists() {
+            return;
+        }
+        if let Ok(avif) = context.service::<AvifThumbnailService>() {
+            avif.schedule(source.to_path_buf(), avif_path);
+        }
+    }
+
+    async fn generate_thumbnail(
+        context: &HttpContext,
+        photo: &Photo,
+        output_path: PathBuf,
+        hash: &str,
+        profile: &DerivativeProfile,
+    ) -> Result<Result<Option<PathBuf>, PreviewBusy>, PipelineError> {
+        let source_path = PathBuf::from(&photo.path);
+        if !source_path.exists() {
+            log::warn!("Thumbnail source file missing for hash {} at {}", hash, source_path.display());
+            return Ok(Ok(None));
+        }
+
+        let coordinator = context.service::<PreviewCoordinator>()?;
+        let extractor = ThumbnailExtractor::from_profile(profile);
+        let output_path_clone = output_path.clone();
+        let owned_hash = hash.to_string();
+
+        let generated = coordinator
+            .generate(&output_path, move || {
+                let started_at = Instant::now();
+                let result = extractor.extract_to(source_path, &output_path_clone);
+                log::debug!("Thumbnail generated on demand for hash {} in {:?}", owned_hash, started_at.elapsed());
+                result.ok()
+            })
+            .await;
+
+        if let Ok(Some(path)) = &generated {
+            Self::schedule_avif(context, path, profile);
+            PreviewHandler::record_profile(
+                context,
+                photo.storage_id,
+                hash,
+                DerivativeProfile::KIND_THUMBNAIL,
+                &profile.thumbnail_signature(),
+            )
+            .await;
+        }
+
+        Ok(generated)
+    }
+}
+
 #[async_trait]
 #[get("/api/photos/thumbnail/{hash}")]
 impl HttpHandler for ThumbnailHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let hash = context.hash()?;
-        let photo_repo = context.service::<Repository<Photo>>()?;
-        let photo =
-            photo_repo.find_by_hash(&hash).await?.ok_or_else(|| PipelineError::message("thumbnail not found"))?;
-
-        let storage_repo = context.service::<Repository<StorageLocation>>()?;
-        let storage = storage_repo
-            .get(&photo.storage_id)
-            .await
-            .map_err(|_| PipelineError::message("Storage location not found"))?
-            .ok_or_else(|| PipelineError::message(&format!("Storage is not found: {}", photo.storage_id)))?;
+        let hash = context.hash().or_fail(context)?;
+        let photos = context.visible_photos(&hash, "thumbnail not found").await?;
+        let Some(photo) = Photo::select_by_hash(photos, None) else {
+            return Err(context.fail(ApiError::not_found("thumbnail not found")));
+        };
 
-        let file_service = context.service::<FileService>()?;
-        let root = Path::new(&storage.path).join(SettingConsts::THUMBNAIL_FOLDER);
+        let storage_root = context
+            .storage_root(photo.storage_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Storage is not found: {}", photo.storage_id)))
+            .or_fail(context)?;
 
-        let thumb_path = file_service.path_for_hash(root, &hash, SettingConsts::THUMBNAIL_FORMAT);
+        let profile = context.derivative_profile().await;
+        let root = storage_root.join(SettingConsts::THUMBNAIL_FOLDER);
+        if let Some((existing, format)) = Self::existing_thumbnail(context, &root, &hash, &profile)? {
+            return Ok(Self::file_response(existing, format, &profile));
+        }
 
-        let full_path = if thumb_path.exists() {
-            thumb_path
-        } else {
-            log::debug!("Thumbnail file not found at {}, falling back to original image", thumb_path.display());
-            PathBuf::from(&photo.path)
+        let thumb_path = FileService::hash_path(&root, &hash, profile.thumbnail_format.extension());
+        let full_path = match Self::generate_thumbnail(context, &photo, thumb_path.clone(), &hash, &profile).await? {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                log::debug!("Thumbnail file not found at {}, falling back to original image", thumb_path.display());
+                PathBuf::from(&photo.path)
+            }
+            Err(PreviewBusy) => return Ok(PreviewHandler::busy_response(context)),
         };
 
-        Ok(ResponseValue::new(
-            FileResponse::from_path(full_path)
-                .with_content_type(SettingConsts::THUMBNAIL_CONTENT_TYPE)
-                .with_header("Cache-Control", SettingConsts::DEFAULT_HTTP_IMAGE_CACHE_HEADER),
-        ))
+        Ok(Self::file_response(full_path, profile.thumbnail_format, &profile))
     }
 }
 
@@ -211,39 +429,128 @@ impl PreviewHandler {
         }
 
         let output_path = context.get_preview_path(hash).await?;
-        let extractor = context.service::<PreviewExtractor>()?;
+        let generated = Self::generate_preview(context, photo.storage_id, source_path, output_path, hash, "Preview")
+            .await?
+            .map_err(|_| PipelineError::message("Preview extraction is busy"))?;
+
+        Ok(generated.map(|path| (path, "image/jpeg")))
+    }
+
+    // Anonymous visitors get a lazily generated watermarked copy; signed-in users keep the clean preview.
+    // Both variants share a URL while watermarking is on, so neither may land in a shared cache.
+    async fn serve(
+        context: &mut HttpContext,
+        preview_path: PathBuf,
+        hash: &str,
+    ) -> Result<ResponseValue, PipelineError> {
+        // Marks the preview as in use so cache pruning leaves it alone while it streams.
+        let cache = context.service::<PreviewCacheService>().ok();
+        if let Some(cache) = &cache {
+            cache.record_access(&preview_path);
+        }
+        let watermark =
+            if preview_path.exists() { context.service::<SettingService>()?.preview_watermark().await? } else { None };
+        let Some(watermark) = watermark else {
+            return Ok(ResponseValue::new(
+                FileResponse::from_path(preview_path)
+                    .with_content_type(SettingConsts::PREVIEW_CONTENT_TYPE)
+                    .with_header("Cache-Control", SettingConsts::DEFAULT_HTTP_IMAGE_CACHE_HEADER),
+            ));
+        };
+        if !context.is_anonymous() {
+            return Ok(ResponseValue::new(
+                FileResponse::from_path(preview_path)
+                    .with_content_type(SettingConsts::PREVIEW_CONTENT_TYPE)
+                    .with_header("Cache-Control", SettingConsts::PRIVATE_HTTP_IMAGE_CACHE_HEADER)
+                    .with_header("Vary", "Authorization"),
+            ));
+        }
+
+        let variant_path = watermark.variant_path(&preview_path, hash);
+        if let Some(cache) = &cache {
+            cache.record_access(&variant_path);
+        }
+        let output_path = variant_path.clone();
+        let generated = context
+            .service::<PreviewCoordinator>()?
+            .generate(&variant_path, move || {
+                PreviewExtractor::apply_watermark(&preview_path, &watermark, &output_path)
+                    .map_err(|error| log::warn!("Failed to watermark preview {}: {:?}", preview_path.display(), error))
+                    .ok()
+            })
+            .await;
+
+        match generated {
+            Ok(Some(path)) => Ok(ResponseValue::new(
+                FileResponse::from_path(path)
+                    .with_content_type(SettingConsts::PREVIEW_CONTENT_TYPE)
+                    .with_header("Cache-Control", SettingConsts::WATERMARKED_HTTP_IMAGE_CACHE_HEADER)
+                    .with_header("Vary", "Authorization"),
+            )),
+            Ok(None) => Err(context.fail(ApiError::internal("Failed to watermark preview"))),
+            Err(PreviewBusy) => Ok(Self::busy_response(context)),
+        }
+    }
+
+    fn busy_response(context: &mut HttpContext) -> ResponseValue {
+        let response = context.response_mut();
+        response.set_status(503);
+        response.headers_mut().insert("retry-after", &PreviewCoordinator::RETRY_AFTER_SECONDS.to_string());
+        ResponseValue::empty()
+    }
+
+    async fn record_profile(context: &HttpContext, storage_id: Uuid, hash: &str, kind: &str, signature: &str) {
+        let Ok(photo_repo) = context.service::<Repository<Photo>>() else {
+            return;
+        };
+        if let Err(error) = photo_repo.record_derivative_profile(storage_id, hash, kind, signature).await {
+            log::warn!("Failed to record {} profile for {}: {:?}", kind, hash, error);
+        }
+    }
+
+    async fn generate_preview(
+        context: &HttpContext,
+        storage_id: Uuid,
+        source_path: PathBuf,
+        output_path: PathBuf,
+        hash: &str,
+        label: &'static str,
+    ) -> Result<Result<Option<PathBuf>, PreviewBusy>, PipelineError> {
+        let coordinator = context.service::<PreviewCoordinator>()?;
+        let profile = context.derivative_profile().await;
+        let extractor = PreviewExtractor::from_profile(&profile);
+        let metrics = context.service::<MetricsService>().ok();
         let output_path_clone = output_path.clone();
-        let source_path_clone = source_path.clone();
+        let owned_hash = hash.to_string();
         let enqueue_at = Instant::now();
 
-        let generated = task::spawn_blocking(move || {
-            let started_at = Instant::now();
-            let queue_wait = started_at.duration_since(enqueue_at);
-            let extract_started = Instant::now();
-            let result = extractor.extract_to(source_path_clone, &output_path_clone);
-            let extract_elapsed = extract_started.elapsed();
-
-            (result, queue_wait, extract_elapsed)
-        })
-        .await
-        .ok()
-        .and_then(|(result, queue_wait, extract_elapsed)| {
-            log::debug!(
-                "Preview blocking task timing for hash {}: queue_wait={:?}, extract={:?}",
-                hash,
-                queue_wait,
-                extract_elapsed
-            );
-            result.ok()
-        });
-
-        if let Some(path) = generated {
-            if path.exists() {
-                return Ok(Some((path, "image/jpeg")));
-            }
+        let generated = coordinator
+            .generate(&output_path, move || {
+                let started_at = Instant::now();
+                let queue_wait = started_at.duration_since(enqueue_at);
+                let result = extractor.extract_to(source_path, &output_path_clone);
+                let extract_elapsed = started_at.elapsed();
+
+                log::debug!(
+                    "{} timing for hash {}: queue_wait={:?}, extract={:?}",
+                    label,
+                    owned_hash,
+                    queue_wait,
+                    extract_elapsed
+                );
+                if let Some(metrics) = &metrics {
+                    metrics.observe(MetricNames::PREVIEW_GENERATION_SECONDS, &[], extract_elapsed.as_secs_f64());
+                }
+                result.ok()
+            })
+            .await;
+
+        if let Ok(Some(_)) = &generated {
+            let signature = profile.preview_signature();
+            Self::record_profile(context, storage_id, hash, DerivativeProfile::KIND_PREVIEW, &signature).await;
         }
 
-        Ok(None)
+        Ok(generated)
     }
 }
 
@@ -252,74 +559,41 @@ struct PreviewByStorageHandler;
 #[get("/api/photos/preview/{storage_id}/{hash}")]
 impl HttpHandler for PreviewByStorageHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let storage_id = context.id("storage_id")?;
-        let hash = context.hash()?;
+        let storage_id = context.id("storage_id").or_fail(context)?;
+        let hash = context.hash().or_fail(context)?;
+        let photos = context.visible_photos(&hash, "preview not found").await?;
 
         let preview_path = context.get_preview_path_by_storage(storage_id, &hash).await?;
         if preview_path.exists() {
-            return Ok(ResponseValue::new(
-                FileResponse::from_path(preview_path)
-                    .with_content_type(SettingConsts::PREVIEW_CONTENT_TYPE)
-                    .with_header("Cache-Control", SettingConsts::DEFAULT_HTTP_IMAGE_CACHE_HEADER),
-            ));
+            return PreviewHandler::serve(context, preview_path, &hash).await;
         }
 
-        let photo_repo = context.service::<Repository<Photo>>()?;
-        let query = QueryBuilder::<Photo>::new()
-            .filter("storage_id", FilterOperator::Eq, Value::Uuid(storage_id))
-            .filter("hash", FilterOperator::Eq, Value::String(hash.clone()))
-            .page(1, 1)
-            .build();
-
-        let photo = photo_repo
-            .query(query)
-            .await
-            .map_err(|_| PipelineError::message("failed to load photo"))?
-            .items
-            .into_iter()
-            .next()
-            .ok_or_else(|| PipelineError::message("preview not found"))?;
+        let photo = Photo::select_by_hash(photos, Some(storage_id))
+            .ok_or_else(|| ApiError::not_found("preview not found"))
+            .or_fail(context)?;
 
         let source_path = PathBuf::from(&photo.path);
         if !source_path.exists() {
-            return Err(PipelineError::message("preview source not found"));
+            return Err(context.fail(ApiError::not_found("preview source not found")));
         }
 
         let output_path = context.get_preview_path_by_storage(storage_id, &hash).await?;
-        let extractor = context.service::<PreviewExtractor>()?;
-        let output_path_clone = output_path.clone();
-        let source_path_clone = source_path.clone();
-        let enqueue_at = Instant::now();
-
-        let generated = task::spawn_blocking(move || {
-            let started_at = Instant::now();
-            let queue_wait = started_at.duration_since(enqueue_at);
-            let extract_started = Instant::now();
-            let result = extractor.extract_to(source_path_clone, &output_path_clone);
-            let extract_elapsed = extract_started.elapsed();
-
-            (result, queue_wait, extract_elapsed)
-        })
-        .await
-        .ok()
-        .and_then(|(result, queue_wait, extract_elapsed)| {
-            log::debug!(
-                "Preview (storage-specific) timing for hash {}: queue_wait={:?}, extract={:?}",
-                hash,
-                queue_wait,
-                extract_elapsed
-            );
-            result.ok()
-        });
-
-        let resolved_path =
-            generated.filter(|path| path.exists()).ok_or_else(|| PipelineError::message("preview not found"))?;
+        let generated = PreviewHandler::generate_preview(
+            context,
+            storage_id,
+            source_path,
+            output_path,
+            &hash,
+            "Preview (storage-specific)",
+        )
+        .await?;
+
+        let resolved_path = match generated {
+            Ok(path) => path.ok_or_else(|| ApiError::not_found("preview not found")).or_fail(context)?,
+            Err(PreviewBusy) => return Ok(PreviewHandler::busy_response(context)),
+        };
 
-        Ok(ResponseValue::new(
-            FileResponse::from_path(resolved_path)
-                .with_content_type(SettingConsts::PREVIEW_CONTENT_TYPE)
-                .with_header("Cache-Control", SettingConsts::DEFAULT_HTTP_IMAGE_CACHE_HEADER),
-        ))
+        PreviewHandler::serve(context, resolved_path, &hash).await
     }
 }
 
@@ -327,27 +601,199 @@ impl HttpHandler for PreviewByStorageHandler {
 #[get("/api/photos/preview/{hash}")]
 impl HttpHandler for PreviewHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let hash = context.hash()?;
-        let photo_repo = context.service::<Repository<Photo>>()?;
-        let photo = photo_repo.find_by_hash(&hash).await?.ok_or_else(|| PipelineError::message("Preview not found"))?;
-
-        let storage_repo = context.service::<Repository<StorageLocation>>()?;
-        let storage = storage_repo
-            .get(&photo.storage_id)
-            .await
-            .map_err(|_| PipelineError::message("Storage location not found"))?
-            .ok_or_else(|| PipelineError::message("Storage is not found"))?;
+        let hash = context.hash().or_fail(context)?;
+        let photos = context.visible_photos(&hash, "Preview not found").await?;
+        let photo = Photo::select_by_hash(photos, None)
+            .ok_or_else(|| ApiError::not_found("Preview not found"))
+            .or_fail(context)?;
+
+        let storage_root = context
+            .storage_root(photo.storage_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Storage is not found"))
+            .or_fail(context)?;
 
         let file_service = context.service::<FileService>()?;
-        let root = Path::new(&storage.path).join(SettingConsts::PREVIEW_FOLDER);
+        let root = storage_root.join(SettingConsts::PREVIEW_FOLDER);
 
         let full_path = file_service.path_for_hash(root, &hash, SettingConsts::PREVIEW_FORMAT);
 
-        Ok(ResponseValue::new(
-            FileResponse::from_path(full_path)
-                .with_content_type(SettingConsts::PREVIEW_CONTENT_TYPE)
-                .with_header("Cache-Control", SettingConsts::DEFAULT_HTTP_IMAGE_CACHE_HEADER),
-        ))
+        Self::serve(context, full_path, &hash).await
+    }
+}
+
+struct HasPreviewHandler;
+
+impl HasPreviewHandler {
+    // Stats on cold network shares can take a while, so they run off the event loop a chunk at a time.
+    const EXISTS_CHUNK_SIZE: usize = 32;
+
+    async fn preview_flags(context: &HttpContext, hashes: &[String]) -> Result<HashMap<String, bool>, PipelineError> {
+        let locations = context.service::<Repository<Photo>>()?.find_hash_locations(hashes, context.is_admin()).await?;
+
+        let mut roots = HashMap::<Uuid, Option<PathBuf>>::new();
+        let mut candidates = Vec::with_capacity(locations.len());
+        for location in locations {
+            if !roots.contains_key(&location.storage_id) {
+                let root = context.storage_root(location.storage_id).await?;
+                roots.insert(location.storage_id, root.map(|root| root.join(SettingConsts::PREVIEW_FOLDER)));
+            }
+            if let Some(Some(root)) = roots.get(&location.storage_id) {
+                let path = FileService::hash_path(root, &location.hash, SettingConsts::PREVIEW_FORMAT);
+                candidates.push((location.hash, path));
+            }
+        }
+
+        let mut flags = hashes.iter().map(|hash| (hash.clone(), false)).collect::<HashMap<_, _>>();
+        for chunk in candidates.chunks(Self::EXISTS_CHUNK_SIZE) {
+            let chunk = chunk.to_vec();
+            let found = tokio::task::spawn_blocking(move || {
+                chunk.into_iter().filter(|(_, path)| path.exists()).map(|(hash, _)| hash).collect::<Vec<_>>()
+            })
+            .await
+            .map_err(|e| PipelineError::message(&format!("preview check failed: {:?}", e)))?;
+            for hash in found {
+                flags.insert(hash, true);
+            }
+        }
+
+        Ok(flags)
+    }
+}
+
+#[async_trait]
+#[get("/api/photos/haspreview/{hash}")]
+impl HttpHandler for HasPreviewHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let hash = context.hash().or_fail(context)?;
+        let flags = Self::preview_flags(context, std::slice::from_ref(&hash)).await?;
+        Ok(ResponseValue::json(flags.get(&hash).copied().unwrap_or(false)))
+    }
+}
+
+struct BatchHasPreviewHandler;
+
+#[async_trait]
+#[post("/api/photos/haspreview")]
+impl HttpHandler for BatchHasPreviewHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload = context.read_valid_json::<HasPreviewRequest>()?;
+        let flags = HasPreviewHandler::preview_flags(context, &payload.normalized_hashes()).await?;
+        Ok(ResponseValue::json(flags))
+    }
+}
+
+struct RecentPhotosHandler;
+
+impl RecentPhotosHandler {
+    const MAX_PAGE_SIZE: u32 = 500;
+
+    fn query_u32(context: &HttpContext, key: &str) -> Result<Option<u32>, ApiError> {
+        context
+            .request()
+            .query_params()
+            .get(key)
+            .map(|value| value.trim().parse::<u32>().map_err(|_| ApiError::bad_request(format!("invalid {}", key))))
+            .transpose()
+    }
+
+    fn paging(context: &HttpContext) -> Result<(u32, u32, Option<u32>), ApiError> {
+        let page = Self::query_u32(context, "page")?.unwrap_or(1).max(1);
+        let page_size = Self::query_u32(context, "pageSize")?.unwrap_or(50).clamp(1, Self::MAX_PAGE_SIZE);
+        let days = Self::query_u32(context, "days")?.filter(|days| *days > 0);
+        Ok((page, page_size, days))
+    }
+}
+
+#[async_trait]
+#[get("/api/photos/recent")]
+impl HttpHandler for RecentPhotosHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let (page, page_size, days) = Self::paging(context).or_fail(context)?;
+
+        let mut hidden_tags = context.viewer_hidden_tags().await?;
+        if !context.is_admin() {
+            hidden_tags.extend(context.service::<Repository<Tag>>()?.admin_only_tag_names().await.or_fail(context)?);
+        }
+        let include_hidden_tags = context.is_admin();
+        let zone = context.timeline_zone().await;
+        let repository = context.service::<Repository<Photo>>()?;
+        let photos = repository.recently_added(page, page_size, days, &hidden_tags, include_hidden_tags, &zone).await?;
+
+        Ok(ResponseValue::json(photos))
+    }
+}
+
+struct RandomPhotosHandler;
+
+impl RandomPhotosHandler {
+    const MAX_COUNT: u32 = 50;
+
+    fn count(context: &HttpContext) -> Result<u32, ApiError> {
+        match context.request().query_params().get("count") {
+            Some(value) => value
+                .trim()
+                .parse::<u32>()
+                .map(|count| count.clamp(1, Self::MAX_COUNT))
+                .map_err(|_| ApiError::bad_request("invalid count")),
+            None => Ok(1),
+        }
+    }
+}
+
+#[async_trait]
+#[get("/api/photos/random")]
+impl HttpHandler for RandomPhotosHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let count = Self::count(context).or_fail(context)?;
+
+        let tags = PhotosQueryHandler::tags(context);
+        let hidden_tags = context.viewer_hidden_tags().await?;
+        let repository = context.service::<Repository<Photo>>()?;
+        let photos = repository.random_photos(count, &tags, &hidden_tags, context.is_admin()).await?;
+
+        Ok(ResponseValue::json(photos))
+    }
+}
+
+struct PhotosQueryHandler;
+
+impl PhotosQueryHandler {
+    fn tags(context: &HttpContext) -> Vec<String> {
+        context
+            .request()
+            .query_params()
+            .get("tags")
+            .map(|value| value.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+#[get("/api/photos/{page}/{pageSize}")]
+impl HttpHandler for PhotosQueryHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let page: u32 = context.page().unwrap_or(1);
+        let page_size: u32 = context.page_size().unwrap_or(50);
+
+        let params = context.request().query_params();
+        let sort = PhotoSort::parse(params.get("sort").map(String::as_str), params.get("dir").map(String::as_str));
+        let sort = sort.map_err(ApiError::bad_request).or_fail(context)?;
+        let matching = TagMatch::parse(params.get("match").map(String::as_str));
+        let matching = matching.map_err(ApiError::bad_request).or_fail(context)?;
+        let min_rating = PhotoRating::parse_min(params.get("minRating").map(String::as_str));
+        let min_rating = min_rating.map_err(ApiError::bad_request).or_fail(context)?;
+        let person = params.get("person").map(String::as_str);
+
+        let filter = PhotoFilter::new(&Self::tags(context), matching).with_min_rating(min_rating).with_person(person);
+        let mut hidden_tags = if context.is_viewer() { context.viewer_hidden_tags().await? } else { HashSet::new() };
+        if !context.is_admin() {
+            hidden_tags.extend(context.service::<Repository<Tag>>()?.admin_only_tag_names().await.or_fail(context)?);
+        }
+        let repository = context.service::<Repository<Photo>>()?;
+        let photos = repository.query_photos(&filter, page, page_size, sort, &hidden_tags).await?;
+
+        Ok(ResponseValue::json(photos))
     }
 }
 
@@ -365,8 +811,16 @@ impl HttpHandler for MapPhotosHandler {
         let limit = page_size;
         let offset = if page > 0 { (page - 1) * limit } else { 0 };
 
-        let photos =
-            repository.photos_with_gps(limit, offset).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        let mut hidden_tags = context.viewer_hidden_tags().await?;
+        if !context.is_admin() {
+            hidden_tags.extend(context.service::<Repository<Tag>>()?.admin_only_tag_names().await.or_fail(context)?);
+        }
+        let include_hidden_tags = context.is_admin();
+        let precision = context.coordinate_precision().await?;
+        let photos = repository
+            .photos_with_gps_with_tags(limit, offset, &hidden_tags, include_hidden_tags, precision)
+            .await
+            .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
 
         let response = serde_json::json!({
             "page": page,
@@ -383,13 +837,22 @@ struct CreatePhotoCommentPayload {
     comment: String,
 }
 
+impl Validate for CreatePhotoCommentPayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Validator::new()
+            .required("comment", "Comment", &self.comment)
+            .max_chars("comment", "Comment", &self.comment, MAX_COMMENT_LENGTH)
+            .finish()
+    }
+}
+
 struct PhotoCommentsHandler;
 
 #[async_trait]
 #[get("/api/photos/comments/{id}/{page}/{pageSize}")]
 impl HttpHandler for PhotoCommentsHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let photo_id = context.id("id")?;
+        let photo_id = context.id("id").or_fail(context)?;
         let page: u32 = context.page().unwrap_or(1);
         let page_size: u32 = context.page_size().unwrap_or(50);
 
@@ -402,8 +865,15 @@ impl HttpHandler for PhotoCommentsHandler {
 
         let comments = repository.query(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
 
+        let author_ids: Vec<Uuid> = comments.items.iter().filter_map(|comment| comment.user_id).collect();
+        let authors = context.service::<Repository<UserSettings>>()?.get_by_user_ids(&author_ids).await?;
+
         let dtos = Page {
-            items: comments.items.into_iter().map(PhotoCommentDto::from).collect(),
+            items: comments
+                .items
+                .into_iter()
+                .map(|comment| PhotoCommentDto::from(comment).with_author(&authors))
+                .collect(),
             total: comments.total,
             page: comments.page,
             page_size: comments.page_size,
@@ -420,7 +890,7 @@ struct CreatePhotoCommentHandler;
 impl HttpHandler for CreatePhotoCommentHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
         let user_id = context.current_user_id()?;
-        let photo_id = context.id("id")?;
+        let photo_id = context.id("id").or_fail(context)?;
         let display_name = context.current_user_display_name().await?;
 
         let identity =
@@ -429,24 +899,30 @@ impl HttpHandler for CreatePhotoCommentHandler {
         let settings = context.service::<SettingService>()?;
         let can_comment = settings.can_create_comments(identity.identity().claims().roles()).await?;
         if !can_comment {
-            context.response_mut().set_status(403);
-            return Ok(ResponseValue::empty());
+            return Err(context.fail(ApiError::forbidden("Comments are not allowed")));
         }
 
-        let payload =
-            context.read_json::<CreatePhotoCommentPayload>().map_err(|e| PipelineError::message(e.message()))?;
-
+        let payload = context.read_valid_json::<CreatePhotoCommentPayload>()?;
         let body = payload.comment.trim();
-        if body.is_empty() {
-            return Err(PipelineError::message("Comment cannot be empty"));
-        }
-        if body.chars().count() > MAX_COMMENT_LENGTH {
-            return Err(PipelineError::message(&format!("Comment must be {} characters or fewer", MAX_COMMENT_LENGTH)));
+
+        let photo = context
+            .service::<Repository<Photo>>()?
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        if photo.is_none() {
+            return Err(context.fail(ApiError::not_found("Photo not found")));
         }
 
         let comment = PhotoComment::new(photo_id, user_id, Some(display_name), Some(body.to_string()));
         let repository = context.service::<Repository<PhotoComment>>()?;
         let saved = repository.insert(comment).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        if let Ok(notifications) = context.service::<NotificationService>() {
+            notifications.notify_photo_comment(&saved);
+        }
+        if let Ok(live) = context.service::<LiveUpdateService>() {
+            live.publish(LiveEvent::CommentAdded { comment_id: saved.id, photo_id: Some(photo_id), album_id: None });
+        }
 
         Ok(ResponseValue::json(PhotoCommentDto::from(saved)))
     }
@@ -468,40 +944,474 @@ impl HttpHandler for PhotoTagsHandler {
     }
 }
 
+struct PhotoTagsByIdHandler;
+
+#[async_trait]
+#[get("/api/photos/{id}/tags")]
+impl HttpHandler for PhotoTagsByIdHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let photo_id = context.id("id").or_fail(context)?;
+        let include_hidden = context.is_admin();
+
+        let tags = context.service::<Repository<Tag>>()?.get_photo_tags(photo_id).await.or_fail(context)?;
+        let names = tags
+            .into_iter()
+            .filter(|tag| include_hidden || tag.visibility == Tag::VISIBILITY_PUBLIC)
+            .map(|tag| tag.name)
+            .collect::<Vec<_>>();
+        Ok(ResponseValue::json(names))
+    }
+}
+
 struct UpdatePhotoTagsHandler;
 
 #[async_trait]
 #[put("/api/photos/tags")]
 impl HttpHandler for UpdatePhotoTagsHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload = context.read_json::<UpdatePhotoTagsPayload>().map_err(|e| PipelineError::message(e.message()))?;
+        if !context.can_tag_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to modify photo tags")));
+        }
 
-        if payload.photo_ids.is_empty() {
-            return Err(PipelineError::message("photoIds cannot be empty"));
+        let payload = context.read_valid_json::<UpdatePhotoTagsPayload>()?;
+
+        let refs = payload.tags.iter().map(|raw| TagRef::parse(raw)).collect::<Vec<_>>();
+        let photo_repo = context.service::<Repository<Photo>>()?;
+        let tag_repo = context.service::<Repository<Tag>>()?;
+
+        let requested_ids =
+            payload.photo_ids.iter().filter_map(|raw_photo_id| raw_photo_id.to_uuid()).collect::<Vec<_>>();
+        let photo_ids =
+            photo_repo.get_by_ids(&requested_ids).await?.into_iter().map(|photo| photo.id).collect::<Vec<_>>();
+
+        let tag_ids = match payload.mode {
+            TagUpdateMode::Remove => tag_repo.find_tag_ids(&refs).await.or_fail(context)?,
+            TagUpdateMode::Add | TagUpdateMode::Replace => {
+                tag_repo.resolve_tag_ids(&refs, Tag::VISIBILITY_PUBLIC).await.or_fail(context)?
+            }
+        };
+
+        let is_admin = context.is_admin();
+        if !is_admin && tag_repo.includes_hidden_tags(&tag_ids).await.or_fail(context)? {
+            return Err(context.fail(ApiError::forbidden(Tag::HIDDEN_TAG_FORBIDDEN)));
+        }
+
+        match payload.mode {
+            TagUpdateMode::Add => photo_repo.add_photo_tags_bulk(&photo_ids, &tag_ids).await?,
+            TagUpdateMode::Remove => photo_repo.remove_photo_tags_bulk(&photo_ids, &tag_ids).await?,
+            TagUpdateMode::Replace => photo_repo.replace_photo_tags_bulk(&photo_ids, &tag_ids, !is_admin).await?,
+        }
+
+        let updated = photo_ids.len() as u32;
+        context.service::<XmpSidecarService>()?.schedule_write_back(photo_ids);
+        Ok(ResponseValue::new(Json(serde_json::json!({ "updated": updated }))))
+    }
+}
+
+struct UpdatePhotoCaptionHandler;
+
+#[async_trait]
+#[put("/api/photos/{id}/caption", policy = Policy::Authenticated)]
+impl HttpHandler for UpdatePhotoCaptionHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_edit_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to edit photos")));
+        }
+
+        let photo_id = context.id("id").or_fail(context)?;
+        let payload = context.read_valid_json::<UpdatePhotoCaptionPayload>()?;
+        let repository = context.service::<Repository<Photo>>()?;
+        let mut photo = repository
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?
+            .ok_or_else(|| ApiError::not_found("Photo not found"))
+            .or_fail(context)?;
+
+        payload.apply(&mut photo);
+        let saved = repository
+            .update(photo)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to update photo: {:?}", e)))?;
+        context.service::<XmpSidecarService>()?.schedule_write_back(vec![saved.id]);
+
+        Ok(ResponseValue::json(saved))
+    }
+}
+
+struct UpdatePhotoRatingHandler;
+
+#[async_trait]
+#[put("/api/photos/{id}/rating", policy = Policy::Authenticated)]
+impl HttpHandler for UpdatePhotoRatingHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_edit_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to edit photos")));
+        }
+
+        let photo_id = context.id("id").or_fail(context)?;
+        let payload = context.read_valid_json::<UpdatePhotoRatingPayload>()?;
+        let repository = context.service::<Repository<Photo>>()?;
+        let mut photo = repository
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?
+            .ok_or_else(|| ApiError::not_found("Photo not found"))
+            .or_fail(context)?;
+
+        photo.rating = Some(payload.rating);
+        photo.updated_at = Some(Utc::now());
+        let saved = repository
+            .update(photo)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to update photo: {:?}", e)))?;
+
+        Ok(ResponseValue::json(saved))
+    }
+}
+
+struct PhotoPeopleHandler;
+
+#[async_trait]
+#[get("/api/photos/{id}/people")]
+impl HttpHandler for PhotoPeopleHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let photo_id = context.id("id").or_fail(context)?;
+        let repository = context.service::<Repository<Photo>>()?;
+        let photo = repository
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?
+            .ok_or_else(|| ApiError::not_found("Photo not found"))
+            .or_fail(context)?;
+        if DownloadOriginalHandler::is_hidden(context, &photo).await? {
+            return Err(context.fail(ApiError::not_found("Photo not found")));
+        }
+
+        let people = repository.photo_people(photo.id).await?;
+        Ok(ResponseValue::json(people.into_iter().map(PhotoPersonDto::from).collect::<Vec<_>>()))
+    }
+}
+
+struct UpdatePhotoPeopleHandler;
+
+#[async_trait]
+#[put("/api/photos/{id}/people", policy = Policy::Authenticated)]
+impl HttpHandler for UpdatePhotoPeopleHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_tag_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to modify photo people")));
         }
 
-        let refs = payload.tags.iter().map(|name| TagRef::Name(name.clone())).collect::<Vec<_>>();
+        let photo_id = context.id("id").or_fail(context)?;
+        let payload = context.read_valid_json::<UpdatePhotoPeoplePayload>()?;
+        let repository = context.service::<Repository<Photo>>()?;
+        let photo = repository
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?
+            .ok_or_else(|| ApiError::not_found("Photo not found"))
+            .or_fail(context)?;
+
+        repository.replace_photo_people(photo.id, &payload.mentions()).await?;
+        let people = repository.photo_people(photo.id).await?;
+        Ok(ResponseValue::json(people.into_iter().map(PhotoPersonDto::from).collect::<Vec<_>>()))
+    }
+}
+
+struct PhotoTagSuggestionsHandler;
+
+#[async_trait]
+#[get("/api/photos/{id}/tag-suggestions", policy = Policy::Authenticated)]
+impl HttpHandler for PhotoTagSuggestionsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let photo_id = context.id("id").or_fail(context)?;
+        let repository = context.service::<Repository<Photo>>()?;
+        let photo = repository
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?
+            .ok_or_else(|| ApiError::not_found("Photo not found"))
+            .or_fail(context)?;
+        if DownloadOriginalHandler::is_hidden(context, &photo).await? {
+            return Err(context.fail(ApiError::not_found("Photo not found")));
+        }
+
+        let suggestions = repository.tag_suggestions(photo.id).await?;
+        Ok(ResponseValue::json(suggestions.into_iter().map(TagSuggestionDto::from).collect::<Vec<_>>()))
+    }
+}
+
+struct AcceptTagSuggestionsHandler;
+
+#[async_trait]
+#[post("/api/photos/{id}/tag-suggestions/accept", policy = Policy::Authenticated)]
+impl HttpHandler for AcceptTagSuggestionsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_tag_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to modify photo tags")));
+        }
+
+        let photo_id = context.id("id").or_fail(context)?;
+        let payload = context.read_valid_json::<AcceptTagSuggestionsPayload>()?;
         let photo_repo = context.service::<Repository<Photo>>()?;
         let tag_repo = context.service::<Repository<Tag>>()?;
+        let photo = photo_repo
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?
+            .ok_or_else(|| ApiError::not_found("Photo not found"))
+            .or_fail(context)?;
+
+        // Only pending suggestions can be accepted; the stored spelling becomes the tag name.
+        let requested = payload.tags.iter().map(|name| name.trim().to_lowercase()).collect::<HashSet<_>>();
+        let accepted = photo_repo
+            .tag_suggestions(photo.id)
+            .await?
+            .into_iter()
+            .filter(|suggestion| requested.contains(&suggestion.name_norm))
+            .map(|suggestion| suggestion.name)
+            .collect::<Vec<_>>();
+        if accepted.is_empty() {
+            return Err(context.fail(ApiError::not_found("No matching tag suggestions")));
+        }
 
-        let mut updated = 0u32;
-        for raw_photo_id in payload.photo_ids {
-            let photo_id = raw_photo_id
-                .to_uuid()
-                .ok_or_else(|| PipelineError::message(&format!("invalid photo id: {}", raw_photo_id)))?;
+        let refs = accepted.iter().map(|name| TagRef::Name(name.clone())).collect::<Vec<_>>();
+        let tag_ids = tag_repo.resolve_tag_ids(&refs, Tag::VISIBILITY_PUBLIC).await.or_fail(context)?;
+        if !context.is_admin() && tag_repo.includes_hidden_tags(&tag_ids).await.or_fail(context)? {
+            return Err(context.fail(ApiError::forbidden(Tag::HIDDEN_TAG_FORBIDDEN)));
+        }
 
-            let exists =
-                photo_repo.get(&photo_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?.is_some();
+        photo_repo.add_photo_tags_bulk(&[photo.id], &tag_ids).await?;
+        photo_repo.remove_tag_suggestions(photo.id, &accepted).await?;
+        context.service::<XmpSidecarService>()?.schedule_write_back(vec![photo.id]);
 
-            if !exists {
-                continue;
+        let remaining = photo_repo.tag_suggestions(photo.id).await?;
+        Ok(ResponseValue::json(json!({
+            "accepted": accepted,
+            "suggestions": remaining.into_iter().map(TagSuggestionDto::from).collect::<Vec<_>>(),
+        })))
+    }
+}
+
+struct PhotoStackHandler;
+
+#[async_trait]
+#[get("/api/photos/{id}/stack")]
+impl HttpHandler for PhotoStackHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let photo_id = context.id("id").or_fail(context)?;
+        let members = context.service::<Repository<Photo>>()?.stack_members(photo_id).await?;
+
+        let mut visible = Vec::with_capacity(members.len());
+        for member in members {
+            if !DownloadOriginalHandler::is_hidden(context, &member).await? {
+                visible.push(member);
             }
+        }
+        if !visible.iter().any(|member| member.id == photo_id) {
+            return Err(context.fail(ApiError::not_found("Photo not found")));
+        }
+
+        Ok(ResponseValue::json(visible))
+    }
+}
 
-            tag_repo.set_photo_tags(photo_id, &refs).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
-            updated += 1;
+struct UnstackPhotoHandler;
+
+#[async_trait]
+#[delete("/api/photos/{id}/stack", policy = Policy::Authenticated)]
+impl HttpHandler for UnstackPhotoHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_edit_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to edit photos")));
         }
 
-        Ok(ResponseValue::new(Json(serde_json::json!({ "updated": updated }))))
+        let photo_id = context.id("id").or_fail(context)?;
+        let unstacked = context.service::<Repository<Photo>>()?.unstack(photo_id).await?;
+        if unstacked > 0 {
+            context
+                .audit(
+                    AuditActions::PHOTO_UNSTACK,
+                    AuditTargets::PHOTO,
+                    &photo_id.to_string(),
+                    json!({ "unstacked": unstacked }),
+                )
+                .await;
+        }
+
+        Ok(ResponseValue::json(json!({ "unstacked": unstacked })))
+    }
+}
+
+struct BatchEditPhotosHandler;
+
+#[async_trait]
+#[post("/api/photos/batch-edit", policy = Policy::Authenticated)]
+impl HttpHandler for BatchEditPhotosHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload = context.read_valid_json::<BatchEditPhotosRequest>()?;
+        let changes = payload.set;
+        if changes.has_field_changes() && !context.can_edit_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to edit photos")));
+        }
+        if changes.has_tag_changes() && !context.can_tag_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to modify photo tags")));
+        }
+
+        let photo_repo = context.service::<Repository<Photo>>()?;
+        let tag_repo = context.service::<Repository<Tag>>()?;
+
+        let mut results = Vec::with_capacity(payload.photo_ids.len());
+        let mut requested_ids = Vec::new();
+        for raw in &payload.photo_ids {
+            match raw.to_uuid() {
+                Some(id) => requested_ids.push(id),
+                None => results.push(BatchEditPhotoResult::failed(raw.trim(), format!("invalid photo id: {}", raw))),
+            }
+        }
+        let existing =
+            photo_repo.get_by_ids(&requested_ids).await?.into_iter().map(|photo| photo.id).collect::<HashSet<_>>();
+        let photo_ids = requested_ids.into_iter().filter(|id| existing.contains(id)).collect::<Vec<_>>();
+        for raw in &payload.photo_ids {
+            if raw.to_uuid().is_some_and(|id| !existing.contains(&id)) {
+                results.push(BatchEditPhotoResult::failed(raw.trim(), "Photo not found"));
+            }
+        }
+
+        let add_refs = changes.add_tags.iter().map(|raw| TagRef::parse(raw)).collect::<Vec<_>>();
+        let remove_refs = changes.remove_tags.iter().map(|raw| TagRef::parse(raw)).collect::<Vec<_>>();
+        let add_ids = tag_repo.resolve_tag_ids(&add_refs, Tag::VISIBILITY_PUBLIC).await.or_fail(context)?;
+        let remove_ids = tag_repo.find_tag_ids(&remove_refs).await.or_fail(context)?;
+        if changes.has_tag_changes()
+            && !context.is_admin()
+            && (tag_repo.includes_hidden_tags(&add_ids).await.or_fail(context)?
+                || tag_repo.includes_hidden_tags(&remove_ids).await.or_fail(context)?)
+        {
+            return Err(context.fail(ApiError::forbidden(Tag::HIDDEN_TAG_FORBIDDEN)));
+        }
+
+        // Scalar fields go first so a failed UPDATE leaves tags untouched for the whole selection.
+        let outcome = async {
+            photo_repo.update_photo_fields_bulk(&photo_ids, &changes).await?;
+            photo_repo.remove_photo_tags_bulk(&photo_ids, &remove_ids).await?;
+            photo_repo.add_photo_tags_bulk(&photo_ids, &add_ids).await
+        }
+        .await;
+
+        let updated = match outcome {
+            Ok(()) => {
+                results.extend(photo_ids.iter().map(|id| BatchEditPhotoResult::succeeded(id.to_string())));
+                photo_ids.len() as u32
+            }
+            Err(error) => {
+                log::error!("Batch photo edit failed: {:?}", error);
+                let message = "Failed to apply changes";
+                results.extend(photo_ids.iter().map(|id| BatchEditPhotoResult::failed(id.to_string(), message)));
+                0
+            }
+        };
+
+        if updated > 0 && (changes.caption.is_some() || changes.has_tag_changes()) {
+            context.service::<XmpSidecarService>()?.schedule_write_back(photo_ids);
+        }
+
+        if updated > 0 && changes.date_taken.is_some() {
+            context
+                .service::<Repository<TimelineDay>>()?
+                .sync()
+                .await
+                .map_err(|e| PipelineError::message(&format!("failed to sync timeline days: {:?}", e)))?;
+        }
+
+        Ok(ResponseValue::json(BatchEditPhotosResponse { updated, results }))
+    }
+}
+
+struct DownloadOriginalHandler;
+
+impl DownloadOriginalHandler {
+    fn requested_strip(context: &HttpContext) -> Result<Option<bool>, ApiError> {
+        context
+            .request()
+            .query_params()
+            .get("stripGps")
+            .map(|value| value.trim().parse::<bool>().map_err(|_| ApiError::bad_request("invalid stripGps")))
+            .transpose()
+    }
+
+    async fn is_hidden(context: &HttpContext, photo: &Photo) -> Result<bool, PipelineError> {
+        if context.is_admin() {
+            return Ok(false);
+        }
+        let hidden_tags = if context.is_viewer() { context.viewer_hidden_tags().await? } else { HashSet::new() };
+        let tags = context.service::<Repository<Tag>>()?.get_photo_tags(photo.id).await?;
+        Ok(tags
+            .iter()
+            .any(|tag| tag.visibility != Tag::VISIBILITY_PUBLIC || hidden_tags.contains(&tag.name.to_lowercase())))
+    }
+
+    fn file_response(path: PathBuf) -> ResponseValue {
+        let content_type = AssetsController::content_type(&path);
+        ResponseValue::new(FileResponse::from_path(path).with_content_type(content_type))
+    }
+}
+
+#[async_trait]
+#[get("/api/photos/{id}/original", policy = Policy::Authenticated)]
+impl HttpHandler for DownloadOriginalHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let photo_id = context.id("id").or_fail(context)?;
+        let requested = Self::requested_strip(context).or_fail(context)?;
+        let photo = context
+            .service::<Repository<Photo>>()?
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?
+            .ok_or_else(|| ApiError::not_found("Photo not found"))
+            .or_fail(context)?;
+        if Self::is_hidden(context, &photo).await? {
+            return Err(context.fail(ApiError::not_found("Photo not found")));
+        }
+
+        let source_path = PathBuf::from(&photo.path);
+        if !source_path.is_file() {
+            return Err(context.fail(ApiError::not_found("Original file not found")));
+        }
+
+        let setting_enabled = context.service::<SettingService>()?.strip_gps_on_download().await?;
+        if !GpsScrubService::should_strip(setting_enabled, context.is_admin(), requested) {
+            return Ok(Self::file_response(source_path));
+        }
+
+        let storage_root = context
+            .storage_root(photo.storage_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Storage is not found"))
+            .or_fail(context)?;
+        let key = photo.hash.clone().unwrap_or_else(|| photo.id.simple().to_string());
+        let scrubbed_path = context.service::<FileService>()?.path_for_hash(
+            storage_root.join(SettingConsts::SCRUBBED_FOLDER),
+            &key,
+            GpsScrubService::output_extension(&source_path),
+        );
+        if GpsScrubService::is_fresh(&source_path, &scrubbed_path) {
+            return Ok(Self::file_response(scrubbed_path));
+        }
+
+        let scrubber = context.service::<GpsScrubService>()?;
+        let output_path = scrubbed_path.clone();
+        let scrubbed = tokio::task::spawn_blocking(move || scrubber.scrub_to(&source_path, &output_path))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+        match scrubbed {
+            Ok(path) => Ok(Self::file_response(path)),
+            Err(error) => {
+                log::warn!("Failed to remove GPS data from {}: {:?}", photo.path, error);
+                Err(context.fail(ApiError::bad_request("Location data cannot be removed from this file format")))
+            }
+        }
     }
 }
 
@@ -511,30 +1421,233 @@ struct GetMetadataHandler;
 #[get("/api/photos/metadata/{id}")]
 impl HttpHandler for GetMetadataHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let photo_id = context.id("id")?;
+        let photo_id = context.id("id").or_fail(context)?;
+        let photo = context
+            .service::<Repository<Photo>>()?
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?;
+        if photo.is_none() {
+            return Err(context.fail(ApiError::not_found("Photo not found")));
+        }
+
         let exif_repo = context.service::<Repository<ExifModel>>()?;
-        let metadata = exif_repo
+        let mut metadata = exif_repo
             .get_by("image_id", Value::Uuid(photo_id))
             .await
             .map_err(|e| PipelineError::message(&format!("failed to get exif record: {:?}", e)))?;
+        let precision = context.coordinate_precision().await?;
+        if let Some(metadata) = metadata.as_mut() {
+            precision.apply(metadata);
+        }
 
         Ok(ResponseValue::json(metadata))
     }
 }
 
+struct UpdateMetadataHandler;
+
+impl UpdateMetadataHandler {
+    fn wants_file_write(context: &HttpContext) -> bool {
+        context.request().query_params().get("writeToFile").is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    }
+
+    async fn write_to_file(context: &HttpContext, photo: &Photo, tags: Vec<(String, String)>) -> bool {
+        let Some(exe_path) = context.app_config().exiftool_path.clone() else {
+            log::warn!("Skipping metadata write-back for {}: metadata.exiftoolPath is not configured", photo.id);
+            return false;
+        };
+
+        let path = photo.path.clone();
+        let written = tokio::task::spawn_blocking(move || ExifTool::with_exe_path(exe_path).write_tags(&path, &tags))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| result);
+        match written {
+            Ok(()) => true,
+            Err(error) => {
+                log::warn!("Failed to write metadata back to {}: {:?}", photo.path, error);
+                false
+            }
+        }
+    }
+}
+
+#[async_trait]
+#[put("/api/photos/{id}/metadata", policy = Policy::Authenticated)]
+impl HttpHandler for UpdateMetadataHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            return Err(context.fail(ApiError::forbidden("Only administrators can edit photo metadata")));
+        }
+
+        let photo_id = context.id("id").or_fail(context)?;
+        let payload = context.read_valid_json::<UpdatePhotoMetadataRequest>()?;
+        let photo_repo = context.service::<Repository<Photo>>()?;
+        let mut photo = photo_repo
+            .get(&photo_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get photo: {:?}", e)))?
+            .ok_or_else(|| ApiError::not_found("Photo not found"))
+            .or_fail(context)?;
+
+        let exif_repo = context.service::<Repository<ExifModel>>()?;
+        let existing = exif_repo
+            .get_by("image_id", Value::Uuid(photo_id))
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to get exif record: {:?}", e)))?;
+        let is_new = existing.is_none();
+        let mut exif = existing.unwrap_or_else(|| ExifModel {
+            id: Uuid::new_v4(),
+            image_id: photo.id,
+            hash: photo.hash.clone().unwrap_or_default(),
+            ..ExifModel::default()
+        });
+        payload.apply(&mut exif);
+
+        let saved = if is_new { exif_repo.insert(exif).await } else { exif_repo.update(exif).await };
+        let saved = saved.map_err(|e| PipelineError::message(&format!("failed to save exif record: {:?}", e)))?;
+
+        // The photo row carries denormalized camera fields used by listings; the map reads GPS from exifs directly.
+        photo.make = saved.make.clone();
+        photo.model = saved.model.clone();
+        photo.lens_model = saved.lens_model.clone();
+        let photo = photo_repo
+            .update(photo)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to update photo: {:?}", e)))?;
+
+        let written_to_file =
+            Self::wants_file_write(context) && Self::write_to_file(context, &photo, payload.exiftool_tags()).await;
+
+        context
+            .audit(
+                AuditActions::PHOTO_METADATA_UPDATE,
+                AuditTargets::PHOTO,
+                &photo.id.to_string(),
+                json!({ "editedFields": saved.edited_fields, "writtenToFile": written_to_file }),
+            )
+            .await;
+
+        let mut metadata = saved;
+        context.coordinate_precision().await?.apply(&mut metadata);
+        Ok(ResponseValue::json(PhotoMetadataUpdateResponse { metadata, written_to_file }))
+    }
+}
+
 struct GetMetadataByHashHandler;
 
 #[async_trait]
 #[get("/api/photos/metadata/hash/{hash}")]
 impl HttpHandler for GetMetadataByHashHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let hash = context.param("hash")?;
+        let hash = context.param("hash").or_fail(context)?;
         let exif_repo = context.service::<Repository<ExifModel>>()?;
-        let metadata = exif_repo
+        let mut metadata = exif_repo
             .get_by("hash", Value::String(hash))
             .await
             .map_err(|e| PipelineError::message(&format!("failed to get exif record: {:?}", e)))?;
+        let precision = context.coordinate_precision().await?;
+        if let Some(metadata) = metadata.as_mut() {
+            precision.apply(metadata);
+        }
 
         Ok(ResponseValue::json(metadata))
     }
 }
+
+#[cfg(feature = "testbot")]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SeedPhotosRequest {
+    #[serde(default)]
+    storage_id: Option<Uuid>,
+    #[serde(default)]
+    count: Option<u32>,
+}
+
+#[cfg(feature = "testbot")]
+struct TestSeedPhotoHandler;
+
+#[cfg(feature = "testbot")]
+impl TestSeedPhotoHandler {
+    const MAX_COUNT: u32 = 100;
+
+    async fn target_storage(
+        context: &mut HttpContext,
+        storage_id: Option<Uuid>,
+    ) -> Result<StorageLocation, PipelineError> {
+        let storage_repo = context.service::<Repository<StorageLocation>>()?;
+        if let Some(storage_id) = storage_id {
+            return storage_repo
+                .get(&storage_id)
+                .await
+                .map_err(|_| PipelineError::message("failed to load storage settings"))?
+                .ok_or_else(|| ApiError::not_found("storage not found"))
+                .or_fail(context);
+        }
+
+        let root = std::env::temp_dir().join("nimble-testbot").join(Uuid::new_v4().to_string());
+        fs::create_dir_all(&root).map_err(|e| PipelineError::message(&format!("failed to create storage: {}", e)))?;
+
+        let storage = StorageLocation {
+            id: Uuid::new_v4(),
+            label: "TestBot".to_string(),
+            path: root.to_string_lossy().to_string(),
+            is_default: false,
+            is_readonly: false,
+            created_at: Utc::now().to_rfc3339(),
+            category_template: StorageLocation::default_category_template(),
+        };
+        let storage = storage_repo
+            .insert(storage)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to insert storage: {:?}", e)))?;
+        context.invalidate_storage_paths();
+        Ok(storage)
+    }
+}
+
+#[cfg(feature = "testbot")]
+#[async_trait]
+impl HttpHandler for TestSeedPhotoHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload: SeedPhotosRequest = context.read_payload()?;
+        let count = payload.count.unwrap_or(1).clamp(1, Self::MAX_COUNT);
+        let storage = Self::target_storage(context, payload.storage_id).await?;
+        let root = storage.normalized_path();
+
+        let photo_repo = context.service::<Repository<Photo>>()?;
+        let hash_service = HashService::new();
+        let now = Utc::now();
+        let mut seeded = Vec::with_capacity(count as usize);
+
+        for index in 0..count {
+            let content = format!("testbot-{}", Uuid::new_v4()).into_bytes();
+            let hash = hash_service.compute(&content, content.len());
+            let file_path = root.join(format!("{}.jpg", hash));
+            fs::write(&file_path, &content)
+                .map_err(|e| PipelineError::message(&format!("failed to write photo: {}", e)))?;
+
+            let mut photo = Photo {
+                storage_id: storage.id,
+                path: file_path.to_string_lossy().to_string(),
+                name: format!("testbot-{}.jpg", index + 1),
+                format: Some("jpg".to_string()),
+                hash: Some(hash),
+                size: Some(content.len() as i64),
+                date_taken: Some(now - Duration::minutes(index as i64)),
+                ..Photo::default()
+            };
+            photo.refresh_timeline_dates();
+
+            let photo = photo_repo
+                .insert(photo)
+                .await
+                .map_err(|e| PipelineError::message(&format!("failed to insert photo: {:?}", e)))?;
+            seeded.push(photo);
+        }
+
+        Ok(ResponseValue::json(seeded))
+    }
+}
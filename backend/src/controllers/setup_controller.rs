@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+
+use crate::prelude::*;
+
+pub struct SetupController;
+
+impl Controller for SetupController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct SetupStatusHandler;
+
+#[async_trait]
+#[get("/api/setup/status")]
+impl HttpHandler for SetupStatusHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let service = context.service::<SetupService>()?;
+        let status = service.status().await?;
+        Ok(ResponseValue::json(status))
+    }
+}
+
+struct InitializeSetupHandler;
+
+#[async_trait]
+#[post("/api/setup/initialize")]
+impl HttpHandler for InitializeSetupHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let service = context.service::<SetupService>()?;
+        if service.status().await?.has_admin {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let payload = match context.read_json::<SetupInitializeRequest>() {
+            Ok(payload) => payload,
+            Err(err) => {
+                context.response_mut().set_status(400);
+                return Err(PipelineError::message(err.message()));
+            }
+        };
+
+        let client = context.session_client();
+        let response = match service.initialize(payload, &client).await {
+            Ok(response) => response,
+            Err(SetupError::AlreadyInitialized) => {
+                context.response_mut().set_status(403);
+                return Ok(ResponseValue::empty());
+            }
+            Err(SetupError::Invalid(err)) => {
+                context.response_mut().set_status(400);
+                return Err(err);
+            }
+            Err(SetupError::Failed(err)) => return Err(err),
+        };
+        context.invalidate_storage_paths();
+
+        context
+            .audit(
+                AuditActions::STORAGE_CREATE,
+                AuditTargets::STORAGE,
+                &response.storage_id.to_string(),
+                json!({ "setup": true, "adminUserId": response.user_id }),
+            )
+            .await;
+        Ok(ResponseValue::json(response))
+    }
+}
@@ -14,8 +14,19 @@ struct DisksHandler;
 #[get("/api/storage/disks", policy = Policy::InRole("admin".to_string()))]
 impl HttpHandler for DisksHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let storage_repo = context.service::<Repository<StorageLocation>>()?;
-        Ok(ResponseValue::json(storage_repo.list_disks()))
+        let disk_info = context.service::<DiskInfoService>()?;
+        Ok(ResponseValue::json(disk_info.disks().await))
+    }
+}
+
+struct RefreshDisksHandler;
+
+#[async_trait]
+#[post("/api/storage/disks/refresh", policy = Policy::InRole("admin".to_string()))]
+impl HttpHandler for RefreshDisksHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let disk_info = context.service::<DiskInfoService>()?;
+        Ok(ResponseValue::json(disk_info.refresh().await))
     }
 }
 
@@ -28,8 +39,10 @@ impl HttpHandler for ListStorageHandler {
         let repo = context.service::<Repository<StorageLocation>>()?;
         let locations = repo.load_storages().await?;
 
+        let disks = context.service::<DiskInfoService>()?.disks().await;
+
         let response = repo
-            .to_storage_responses(locations)
+            .to_storage_responses(locations, &disks)
             .map_err(|_| PipelineError::message("failed to load storage settings"))?;
 
         Ok(ResponseValue::json(response))
@@ -45,8 +58,10 @@ impl HttpHandler for ListStorageLegacyHandler {
         let repo = context.service::<Repository<StorageLocation>>()?;
         let locations = repo.load_storages().await?;
 
+        let disks = context.service::<DiskInfoService>()?.disks().await;
+
         let response = repo
-            .to_storage_responses(locations)
+            .to_storage_responses(locations, &disks)
             .map_err(|_| PipelineError::message("failed to load storage settings"))?;
 
         Ok(ResponseValue::json(response))
@@ -59,12 +74,11 @@ struct CreateStorageHandler;
 #[post("/api/storage/locations", policy = Policy::InRole("admin".to_string()))]
 impl HttpHandler for CreateStorageHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let payload =
-            context.read_json::<CreateStoragePayload>().map_err(|err| PipelineError::message(err.message()))?;
+        let payload = context.read_valid_json::<CreateStoragePayload>()?;
 
-        let label_value = payload.label.trim().should_not_empty("Storage label")?;
-        let mount_point = payload.mount_point.trim().should_not_empty("Mount point")?;
-        let path_value = payload.path.trim().should_not_empty("Storage path")?;
+        let label_value = payload.label.trim();
+        let mount_point = payload.mount_point.trim();
+        let path_value = payload.path.trim();
         let full_path = Path::new(mount_point).join(path_value);
         let full_path_value = full_path.to_string_lossy().to_string();
 
@@ -78,7 +92,7 @@ impl HttpHandler for CreateStorageHandler {
 
         let repository = context.service::<Repository<StorageLocation>>()?;
         if repository.exists_by_path(&full_path_value).await? {
-            return Err(PipelineError::message("Storage path already registered"));
+            return Err(context.fail(ApiError::conflict("Storage path already registered")));
         }
 
         let mut is_default = payload.is_default.unwrap_or(false);
@@ -110,8 +124,19 @@ impl HttpHandler for CreateStorageHandler {
             .insert(new_location.clone())
             .await
             .map_err(|_| PipelineError::message("failed to save storage settings"))?;
+        context.invalidate_storage_paths();
+
+        context
+            .audit(
+                AuditActions::STORAGE_CREATE,
+                AuditTargets::STORAGE,
+                &new_location.id.to_string(),
+                json!({ "label": new_location.label, "path": new_location.path, "isDefault": new_location.is_default }),
+            )
+            .await;
 
-        let disk = repository.find_disk(&new_location.path, &repository.list_disks());
+        let disks = context.service::<DiskInfoService>()?.disks().await;
+        let disk = DiskInfoService::find_disk(&new_location.path, &disks);
 
         Ok(ResponseValue::json(StorageLocationResponse {
             id: new_location.id.to_string(),
@@ -132,35 +157,30 @@ struct UpdateStorageHandler;
 #[put("/api/storage/locations/{id}", policy = Policy::InRole("admin".to_string()))]
 impl HttpHandler for UpdateStorageHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let id = context
-            .route()
-            .and_then(|route| route.params().get("id"))
-            .ok_or_else(|| PipelineError::message("id parameter missing"))
-            .and_then(|value| Uuid::parse_str(value).map_err(|_| PipelineError::message("invalid id parameter")))?;
+        let id = context.entity_id().or_fail(context)?;
 
-        let payload =
-            context.read_json::<UpdateStoragePayload>().map_err(|err| PipelineError::message(err.message()))?;
+        let payload = context.read_valid_json::<UpdateStoragePayload>()?;
 
         let repository = context.service::<Repository<StorageLocation>>()?;
         let mut location = repository
             .get(&id)
             .await
             .map_err(|_| PipelineError::message("failed to load storage settings"))?
-            .ok_or_else(|| PipelineError::message("Storage location not found"))?;
+            .ok_or_else(|| ApiError::not_found("Storage location not found"))
+            .or_fail(context)?;
 
         if let Some(label) = &payload.label {
-            let label_value = label.trim().should_not_empty("Storage label");
-            location.label = label_value?.to_string();
+            location.label = label.trim().to_string();
         }
 
         if let Some(path) = &payload.path {
-            let path_value = path.trim().should_not_empty("Storage path")?.to_string();
+            let path_value = path.trim().to_string();
             if !Path::new(&path_value).exists() {
-                return Err(PipelineError::message("Storage path does not exist"));
+                return Err(context.fail(ApiError::bad_request("Storage path does not exist")));
             }
             if let Some(existing) = repository.find_storage_by_path(&path_value).await? {
                 if existing.id != location.id {
-                    return Err(PipelineError::message("Storage path already registered"));
+                    return Err(context.fail(ApiError::conflict("Storage path already registered")));
                 }
             }
 
@@ -168,8 +188,7 @@ impl HttpHandler for UpdateStorageHandler {
         }
 
         if let Some(category_template) = &payload.category_template {
-            let value = category_template.trim().should_not_empty("Category template");
-            location.category_template = value?.to_string();
+            location.category_template = category_template.trim().to_string();
         }
 
         if let Some(is_default) = payload.is_default {
@@ -194,11 +213,16 @@ impl HttpHandler for UpdateStorageHandler {
             }
         }
 
+        let audit_details = json!({ "label": location.label, "path": location.path, "isDefault": location.is_default });
         repository.update(location).await.map_err(|_| PipelineError::message("failed to save storage settings"))?;
+        context.invalidate_storage_paths();
+
+        context.audit(AuditActions::STORAGE_UPDATE, AuditTargets::STORAGE, &id.to_string(), audit_details).await;
 
         let locations = repository.load_storages().await?;
+        let disks = context.service::<DiskInfoService>()?.disks().await;
         let response = repository
-            .to_storage_responses(locations)
+            .to_storage_responses(locations, &disks)
             .map_err(|_| PipelineError::message("failed to load storage settings"))?;
 
         Ok(ResponseValue::json(response))
@@ -211,26 +235,27 @@ struct DefaultStorageHandler;
 #[put("/api/storage/locations/{id}/default", policy = Policy::InRole("admin".to_string()))]
 impl HttpHandler for DefaultStorageHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let id = context
-            .route()
-            .and_then(|route| route.params().get("id"))
-            .ok_or_else(|| PipelineError::message("id parameter missing"))
-            .and_then(|value| Uuid::parse_str(value).map_err(|_| PipelineError::message("invalid id parameter")))?;
+        let id = context.entity_id().or_fail(context)?;
 
         let storage_repo = context.service::<Repository<StorageLocation>>()?;
         let mut location = storage_repo
             .get(&id)
             .await
             .map_err(|_| PipelineError::message("failed to load storage settings"))?
-            .ok_or_else(|| PipelineError::message("Storage location not found"))?;
+            .ok_or_else(|| ApiError::not_found("Storage location not found"))
+            .or_fail(context)?;
 
         storage_repo.reset_default().await?;
         location.is_default = true;
         storage_repo.update(location).await.map_err(|_| PipelineError::message("failed to save storage settings"))?;
+        context.invalidate_storage_paths();
+
+        context.audit(AuditActions::STORAGE_SET_DEFAULT, AuditTargets::STORAGE, &id.to_string(), json!({})).await;
 
         let locations = storage_repo.load_storages().await?;
+        let disks = context.service::<DiskInfoService>()?.disks().await;
         let response = storage_repo
-            .to_storage_responses(locations)
+            .to_storage_responses(locations, &disks)
             .map_err(|_| PipelineError::message("failed to load storage settings"))?;
 
         Ok(ResponseValue::json(response))
@@ -243,20 +268,27 @@ struct DeleteStorageHandler;
 #[delete("/api/storage/locations/{id}", policy = Policy::InRole("admin".to_string()))]
 impl HttpHandler for DeleteStorageHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let id = context
-            .route()
-            .and_then(|route| route.params().get("id"))
-            .ok_or_else(|| PipelineError::message("id parameter missing"))
-            .and_then(|value| Uuid::parse_str(value).map_err(|_| PipelineError::message("invalid id parameter")))?;
+        let id = context.entity_id().or_fail(context)?;
 
         let repository = context.service::<Repository<StorageLocation>>()?;
-        let deleted_location =
-            repository.get(&id).await.map_err(|_| PipelineError::message("failed to load storage settings"))?;
-        if deleted_location.is_none() {
-            return Err(PipelineError::message("Storage location not found"));
-        }
+        let deleted_location = repository
+            .get(&id)
+            .await
+            .map_err(|_| PipelineError::message("failed to load storage settings"))?
+            .ok_or_else(|| ApiError::not_found("Storage location not found"))
+            .or_fail(context)?;
 
         repository.delete(&id).await.map_err(|_| PipelineError::message("failed to save storage settings"))?;
+        context.invalidate_storage_paths();
+
+        context
+            .audit(
+                AuditActions::STORAGE_DELETE,
+                AuditTargets::STORAGE,
+                &id.to_string(),
+                json!({ "label": deleted_location.label, "path": deleted_location.path }),
+            )
+            .await;
 
         let mut locations = repository.load_storages().await?;
         if !locations.iter().any(|location| location.is_default) {
@@ -269,26 +301,70 @@ impl HttpHandler for DeleteStorageHandler {
                 locations = repository.load_storages().await?;
             }
         }
+        let disks = context.service::<DiskInfoService>()?.disks().await;
         let response = repository
-            .to_storage_responses(locations)
+            .to_storage_responses(locations, &disks)
             .map_err(|_| PipelineError::message("failed to load storage settings"))?;
 
         Ok(ResponseValue::json(response))
     }
 }
 
+struct PreviewCacheReportHandler;
+
+#[async_trait]
+#[get("/api/storage/locations/{id}/cache", policy = Policy::InRole("admin".to_string()))]
+impl HttpHandler for PreviewCacheReportHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let id = context.entity_id().or_fail(context)?;
+        let report = context
+            .service::<PreviewCacheService>()?
+            .report(id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Storage location not found"))
+            .or_fail(context)?;
+
+        Ok(ResponseValue::json(report))
+    }
+}
+
+struct PrunePreviewCacheHandler;
+
+#[async_trait]
+#[post("/api/storage/locations/{id}/cache/prune", policy = Policy::InRole("admin".to_string()))]
+impl HttpHandler for PrunePreviewCacheHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let id = context.entity_id().or_fail(context)?;
+        let report = context
+            .service::<PreviewCacheService>()?
+            .prune(id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("Storage location not found"))
+            .or_fail(context)?;
+
+        context
+            .audit(
+                AuditActions::STORAGE_CACHE_PRUNE,
+                AuditTargets::STORAGE,
+                &id.to_string(),
+                json!({ "evictedFiles": report.evicted_files, "evictedBytes": report.evicted_bytes }),
+            )
+            .await;
+
+        Ok(ResponseValue::json(report))
+    }
+}
+
 struct BrowseStorageHandler;
 
 #[async_trait]
 #[get("/api/storage/browse/{storageId}")]
 impl HttpHandler for BrowseStorageHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let storage_id = context.route_storage_id()?;
-        let request = context.parse_browse_request()?;
-        let path_segments = request.path_segments().map_err(|_| {
-            context.response_mut().set_status(400);
-            PipelineError::message("invalid browse path")
-        })?;
+        let storage_id = context.route_storage_id().or_fail(context)?;
+        let request = context.parse_browse_request().or_fail(context)?;
+        let path_segments =
+            request.path_segments().map_err(|err| ApiError::bad_request(err.to_string())).or_fail(context)?;
         let start = std::time::Instant::now();
 
         let repository = context.service::<Repository<StorageLocation>>()?;
@@ -296,39 +372,38 @@ impl HttpHandler for BrowseStorageHandler {
             .get(&storage_id)
             .await
             .map_err(|_| PipelineError::message("failed to load storage settings"))?
-            .ok_or_else(|| {
-                context.response_mut().set_status(404);
-                PipelineError::message("storage not found")
-            })?;
+            .ok_or_else(|| ApiError::not_found("storage not found"))
+            .or_fail(context)?;
 
         let client_id = context.current_client_id().await?;
-        let browse_options = context.load_client_storage_settings(client_id, storage.id).await?;
+        let browse_options = context.load_client_storage_settings(client_id, storage.id).await?.with_request(&request);
 
+        let browse_service = context.service::<BrowseService>()?;
         let cursor = match request.cursor.as_deref() {
-            Some(raw) if !raw.trim().is_empty() => {
-                Some(PhotoCursor::decode(raw).map_err(|_| PipelineError::message("invalid cursor"))?)
-            }
+            Some(raw) if !raw.trim().is_empty() => Some(
+                browse_service
+                    .decode_cursor(raw)
+                    .map_err(|err| ApiError::bad_request(err.to_string()))
+                    .or_fail(context)?,
+            ),
             _ => None,
         };
 
         let page_size = request.page_size.unwrap_or(50);
-        let browse_service = context.service::<BrowseService>()?;
         let response: BrowseResponse = browse_service
             .browse(&storage.id, &path_segments, &browse_options, page_size, cursor)
             .await
             .map_err(|err| {
+                if let Some(path_error) = err.downcast_ref::<BrowsePathError>() {
+                    return ApiError::bad_request(path_error.to_string());
+                }
                 let message = err.to_string();
-                if message.contains("invalid browse path depth")
-                    || message.contains("invalid digit found in string")
-                    || message.contains("input contains invalid characters")
-                    || message.contains("trailing input")
-                    || message.contains("input is out of range")
-                {
-                    context.response_mut().set_status(400);
-                    return PipelineError::message("invalid browse path");
+                if message == BrowseService::CURSOR_MISMATCH || message == BrowseOptions::INVALID_FILTER {
+                    return ApiError::bad_request(message);
                 }
-                PipelineError::message(&message)
-            })?;
+                ApiError::internal(message)
+            })
+            .or_fail(context)?;
 
         log::info!("Browse storage completed - elapsed: {:?}", start.elapsed());
         Ok(ResponseValue::json(response))
@@ -341,7 +416,7 @@ struct SyncStorageCheckHandler;
 #[post("/api/storage/sync/check")]
 impl HttpHandler for SyncStorageCheckHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let request = context.read_json::<CheckFileRequest>().map_err(|err| PipelineError::message(err.message()))?;
+        let request = context.read_payload::<CheckFileRequest>()?;
         let sync_service = context.service::<SyncService>()?;
         let response = sync_service.check_missing_files(request).await?;
 
@@ -355,8 +430,7 @@ struct SyncStorageMetadataHandler;
 #[post("/api/storage/sync/metadata")]
 impl HttpHandler for SyncStorageMetadataHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let request =
-            context.read_json::<SyncMetadataRequest>().map_err(|err| PipelineError::message(err.message()))?;
+        let request = context.read_payload::<SyncMetadataRequest>()?;
         let sync_service = context.service::<SyncService>()?;
         let metadata = sync_service.sync_metadata(request.clone()).await?;
 
@@ -377,10 +451,12 @@ impl HttpHandler for SyncStorageFileHandler {
             .request()
             .headers()
             .get("content-type")
-            .ok_or_else(|| PipelineError::message("Missing content-type header"))?;
+            .map(str::to_string)
+            .ok_or_else(|| ApiError::bad_request("Missing content-type header"))
+            .or_fail(context)?;
         let request_body = context.body_bytes()?;
         let sync_service = context.service::<SyncService>()?;
-        let response = sync_service.sync_file(content_type_header, request_body).await?;
+        let response = sync_service.sync_file(&content_type_header, request_body).await?;
         Ok(ResponseValue::json(response))
     }
 }
@@ -391,15 +467,70 @@ struct ScanStorageHandler;
 #[serde(rename_all = "camelCase")]
 struct ScanStoragePayload {
     storage_id: Uuid,
+    #[serde(default)]
+    rebuild_stale: bool,
 }
 
 #[async_trait]
 #[post("/api/storage/scan")]
 impl HttpHandler for ScanStorageHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let request = context.read_json::<ScanStoragePayload>().map_err(|err| PipelineError::message(err.message()))?;
+        let request = context.read_payload::<ScanStoragePayload>()?;
         let storage_service = context.service::<StorageService>()?;
-        let response = storage_service.scan(request.storage_id).await?;
+        let response = storage_service.scan(request.storage_id, request.rebuild_stale).await?;
         Ok(ResponseValue::json(response))
     }
 }
+
+struct ImportStorageFolderHandler;
+
+#[async_trait]
+#[post("/api/storage/{storageId}/import", policy = Policy::InRole("admin".to_string()))]
+impl HttpHandler for ImportStorageFolderHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let storage_id = context.route_storage_id().or_fail(context)?;
+        let request = context.read_payload::<FolderImportRequest>()?;
+
+        let repository = context.service::<Repository<StorageLocation>>()?;
+        let storage = repository
+            .get(&storage_id)
+            .await
+            .map_err(|_| PipelineError::message("failed to load storage settings"))?
+            .ok_or_else(|| ApiError::not_found("storage not found"))
+            .or_fail(context)?;
+
+        let import_service = context.service::<FolderImportService>()?;
+        let response = import_service
+            .import_folder(&storage, &request)
+            .await
+            .map_err(|err| {
+                if let Some(path_error) = err.downcast_ref::<BrowsePathError>() {
+                    return ApiError::bad_request(path_error.to_string());
+                }
+                log::error!("Folder import failed for storage {}: {:?}", storage.id, err);
+                ApiError::internal("failed to import folder")
+            })
+            .or_fail(context)?;
+
+        Ok(ResponseValue::json(response))
+    }
+}
+
+struct ImportBatchStatusHandler;
+
+#[async_trait]
+#[get("/api/storage/imports/{batchId}", policy = Policy::InRole("admin".to_string()))]
+impl HttpHandler for ImportBatchStatusHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let batch_id = context.id("batchId").or_fail(context)?;
+        let import_service = context.service::<FolderImportService>()?;
+        let status = import_service
+            .batch_status(batch_id)
+            .await
+            .map_err(|err| PipelineError::message(&err.to_string()))?
+            .ok_or_else(|| ApiError::not_found("import batch not found"))
+            .or_fail(context)?;
+
+        Ok(ResponseValue::json(status))
+    }
+}
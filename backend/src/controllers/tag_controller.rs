@@ -4,6 +4,165 @@ pub struct TagController;
 
 impl Controller for TagController {
     fn routes() -> Vec<EndpointRoute> {
-        vec![]
+        vec![
+            #[cfg(feature = "testbot")]
+            EndpointRoute::post("/api/test/tags/visibility", TestTagVisibilityHandler).build(),
+        ]
+    }
+}
+
+#[cfg(feature = "testbot")]
+#[derive(Deserialize)]
+struct TagVisibilityRequest {
+    name: String,
+    visibility: i16,
+}
+
+#[cfg(feature = "testbot")]
+struct TestTagVisibilityHandler;
+
+#[cfg(feature = "testbot")]
+#[async_trait]
+impl HttpHandler for TestTagVisibilityHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let payload: TagVisibilityRequest = context.read_payload()?;
+        let tag_repo = context.service::<Repository<Tag>>()?;
+        let updated =
+            tag_repo.set_tag_visibility(&[TagRef::parse(&payload.name)], payload.visibility).await.or_fail(context)?;
+        if updated.is_empty() {
+            return Err(context.fail(ApiError::not_found(format!("Tag not found: {}", payload.name))));
+        }
+
+        Ok(ResponseValue::json(json!({ "updated": updated.len() })))
+    }
+}
+
+struct ListTagRulesHandler;
+
+#[async_trait]
+#[get("/api/tags/rules", policy = Policy::Authenticated)]
+impl HttpHandler for ListTagRulesHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_tag_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to manage tag rules")));
+        }
+
+        let rules = context.service::<TagRuleService>()?.list().await?;
+        Ok(ResponseValue::json(rules))
+    }
+}
+
+struct CreateTagRuleHandler;
+
+#[async_trait]
+#[post("/api/tags/rules", policy = Policy::Authenticated)]
+impl HttpHandler for CreateTagRuleHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_tag_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to manage tag rules")));
+        }
+
+        let payload = context.read_valid_json::<CreateTagRuleRequest>()?;
+        let service = context.service::<TagRuleService>()?;
+        let is_admin = context.is_admin();
+        if !is_admin && service.is_admin_only_tag(&payload.tag).await? {
+            return Err(context.fail(ApiError::forbidden(Tag::HIDDEN_TAG_FORBIDDEN)));
+        }
+
+        let author = context.current_user_id().ok();
+        let rule = service.create(&payload, author, is_admin).await?;
+        context
+            .audit(
+                AuditActions::TAG_RULE_CREATE,
+                AuditTargets::TAG_RULE,
+                &rule.id.to_string(),
+                json!({ "field": rule.field, "operator": rule.operator, "value": rule.value, "tag": rule.tag_name }),
+            )
+            .await;
+
+        context.response_mut().set_status(201);
+        Ok(ResponseValue::json(rule))
+    }
+}
+
+struct DeleteTagRuleHandler;
+
+#[async_trait]
+#[delete("/api/tags/rules/{id}", policy = Policy::Authenticated)]
+impl HttpHandler for DeleteTagRuleHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.can_tag_photos().await? {
+            return Err(context.fail(ApiError::forbidden("You are not allowed to manage tag rules")));
+        }
+
+        let rule_id = context.id("id").or_fail(context)?;
+        let service = context.service::<TagRuleService>()?;
+        let rule =
+            service.get(rule_id).await?.ok_or_else(|| ApiError::not_found("Tag rule not found")).or_fail(context)?;
+        if rule.author_is_admin && !context.is_admin() {
+            return Err(
+                context.fail(ApiError::forbidden("Only administrators can remove rules written by an administrator"))
+            );
+        }
+
+        service.delete(rule_id).await?;
+        context
+            .audit(
+                AuditActions::TAG_RULE_DELETE,
+                AuditTargets::TAG_RULE,
+                &rule_id.to_string(),
+                json!({ "tag": rule.tag_name }),
+            )
+            .await;
+
+        Ok(ResponseValue::json(json!({ "deleted": 1 })))
+    }
+}
+
+struct ApplyTagRulesHandler;
+
+#[async_trait]
+#[post("/api/tags/rules/apply", policy = Policy::Authenticated)]
+impl HttpHandler for ApplyTagRulesHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            return Err(context.fail(ApiError::forbidden("Only administrators can apply tag rules")));
+        }
+
+        let service = context.service::<TagRuleService>()?;
+        let worker = Arc::clone(&service);
+        let task = TaskDescriptor::new(TagRuleService::TASK_NAME, async move {
+            let progress = worker.apply_all().await.map_err(|error| anyhow::anyhow!("{:?}", error))?;
+            log::info!("Tag rules applied: {} of {} photos tagged", progress.tagged, progress.processed);
+            Ok(())
+        });
+        let task_id = task.id;
+        if !service.begin_apply(task_id) {
+            return Err(context.fail(ApiError::conflict("Tag rules are already being applied")));
+        }
+
+        let runner = context.service::<BackgroundTaskRunner>()?;
+        if let Err(error) = runner.enqueue(task) {
+            service.finish_unstarted(&error.to_string());
+            return Err(PipelineError::message(&error.to_string()));
+        }
+        context.audit(AuditActions::TAG_RULES_APPLY, AuditTargets::TASK, &task_id.to_string(), json!({})).await;
+
+        context.response_mut().set_status(202);
+        Ok(ResponseValue::json(service.progress()))
+    }
+}
+
+struct TagRulesProgressHandler;
+
+#[async_trait]
+#[get("/api/tags/rules/apply", policy = Policy::Authenticated)]
+impl HttpHandler for TagRulesProgressHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            return Err(context.fail(ApiError::forbidden("Only administrators can apply tag rules")));
+        }
+
+        Ok(ResponseValue::json(context.service::<TagRuleService>()?.progress()))
     }
 }
@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use std::str::FromStr;
+
+use crate::prelude::*;
+
+pub struct TaskController;
+
+impl Controller for TaskController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+struct ListTasksHandler;
+
+#[async_trait]
+#[get("/api/admin/tasks", policy = Policy::Authenticated)]
+impl HttpHandler for ListTasksHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let state = context
+            .request()
+            .query_params()
+            .get("state")
+            .map(|value| TaskState::from_str(value).map_err(|err| PipelineError::message(&err)))
+            .transpose()?;
+
+        let runner = context.service::<BackgroundTaskRunner>()?;
+        Ok(ResponseValue::json(runner.tasks(state)))
+    }
+}
+
+struct CancelTaskHandler;
+
+#[async_trait]
+#[delete("/api/admin/tasks/{id}", policy = Policy::Authenticated)]
+impl HttpHandler for CancelTaskHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let task_id = context.id("id").or_fail(context)?;
+        let runner = context.service::<BackgroundTaskRunner>()?;
+        let cancelled = runner.cancel(task_id).map_err(|err| PipelineError::message(&err.to_string()))?;
+
+        if !cancelled {
+            let message = match runner.task(task_id) {
+                Some(task) => format!("Task cannot be cancelled in state {:?}", task.state),
+                None => "Task not found".to_string(),
+            };
+            return Err(PipelineError::message(&message));
+        }
+
+        Ok(ResponseValue::json(runner.task(task_id)))
+    }
+}
+
+struct RelocateLegacyAssetsHandler;
+
+#[async_trait]
+#[post("/api/admin/tasks/relocate-assets", policy = Policy::Authenticated)]
+impl HttpHandler for RelocateLegacyAssetsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let service = context.service::<AssetRelocationService>()?;
+        let task = TaskDescriptor::new(AssetRelocationService::TASK_NAME, async move {
+            let summary = service.relocate().await?;
+            log::info!("Legacy asset relocation finished: {:?}", summary);
+            Ok(())
+        });
+        let task_id = task.id;
+
+        let runner = context.service::<BackgroundTaskRunner>()?;
+        runner.enqueue(task).map_err(|err| PipelineError::message(&err.to_string()))?;
+        context.audit(AuditActions::ASSETS_RELOCATE, AuditTargets::TASK, &task_id.to_string(), json!({})).await;
+
+        context.response_mut().set_status(202);
+        Ok(ResponseValue::json(runner.task(task_id)))
+    }
+}
+
+struct BackfillBlurhashHandler;
+
+#[async_trait]
+#[post("/api/admin/tasks/backfill-blurhash", policy = Policy::Authenticated)]
+impl HttpHandler for BackfillBlurhashHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        if !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(ResponseValue::empty());
+        }
+
+        let service = context.service::<BlurhashService>()?;
+        let task = TaskDescriptor::new(BlurhashService::TASK_NAME, async move {
+            let summary = service.backfill().await?;
+            log::info!("Blurhash backfill finished: {:?}", summary);
+            Ok(())
+        });
+        let task_id = task.id;
+
+        let runner = context.service::<BackgroundTaskRunner>()?;
+        runner.enqueue(task).map_err(|err| PipelineError::message(&err.to_string()))?;
+        context.audit(AuditActions::BLURHASH_BACKFILL, AuditTargets::TASK, &task_id.to_string(), json!({})).await;
+
+        context.response_mut().set_status(202);
+        Ok(ResponseValue::json(runner.task(task_id)))
+    }
+}
@@ -2,27 +2,75 @@ use crate::prelude::*;
 
 struct TimelineYearsHandler;
 
+impl TimelineYearsHandler {
+    fn detailed(context: &HttpContext) -> bool {
+        context
+            .request()
+            .query_params()
+            .get("detailed")
+            .is_some_and(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "1"))
+    }
+}
+
 #[async_trait]
 #[get("/api/timeline/years")]
 impl HttpHandler for TimelineYearsHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let repository = context.service::<Repository<TimelineDay>>()?;
+        let zone = context.timeline_zone().await;
+        if Self::detailed(context) {
+            let mut hidden_tags =
+                if context.is_viewer() { context.viewer_hidden_tags().await? } else { HashSet::new() };
+            if !context.is_admin() {
+                hidden_tags
+                    .extend(context.service::<Repository<Tag>>()?.admin_only_tag_names().await.or_fail(context)?);
+            }
+            let counts = context.service::<Repository<Photo>>()?.get_year_counts(&zone, &hidden_tags).await?;
+            return Ok(ResponseValue::json(counts));
+        }
 
-        let years = repository.get_years().await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        let years = if zone.is_utc() {
+            let repository = context.service::<Repository<TimelineDay>>()?;
+            repository.get_years().await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+        } else {
+            context.service::<Repository<Photo>>()?.get_years(&zone).await?
+        };
 
         Ok(ResponseValue::json(years))
     }
 }
 
+struct TimelineYearOffsetHandler;
+
+#[async_trait]
+#[get("/api/timeline/years/{year}/offset")]
+impl HttpHandler for TimelineYearOffsetHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let year = context.param("year").or_fail(context)?;
+        let zone = context.timeline_zone().await;
+        let raw_granularity = context.request().query_params().get("granularity").cloned();
+        let granularity =
+            TimelineGranularity::parse(raw_granularity.as_deref()).map_err(ApiError::bad_request).or_fail(context)?;
+
+        let repository = context.service::<Repository<Photo>>()?;
+        let offset = repository.get_year_offset(&year, &zone, granularity).await?;
+
+        Ok(ResponseValue::json(serde_json::json!({ "year": year, "offset": offset, "timezone": zone.as_str() })))
+    }
+}
+
 struct TimelineYearDaysHandler;
 
 #[async_trait]
 #[get("/api/timeline/yeardays")]
 impl HttpHandler for TimelineYearDaysHandler {
     async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
-        let repository = context.service::<Repository<TimelineDay>>()?;
-
-        let years = repository.get_yeardays().await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        let zone = context.timeline_zone().await;
+        let years = if zone.is_utc() {
+            let repository = context.service::<Repository<TimelineDay>>()?;
+            repository.get_yeardays().await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+        } else {
+            context.service::<Repository<Photo>>()?.get_yeardays(&zone).await?
+        };
 
         Ok(ResponseValue::json(years))
     }
@@ -30,6 +78,13 @@ impl HttpHandler for TimelineYearDaysHandler {
 
 struct TimelineHandler;
 
+impl TimelineHandler {
+    fn min_rating(context: &HttpContext) -> Result<Option<u8>, ApiError> {
+        let raw = context.request().query_params().get("minRating").cloned();
+        PhotoRating::parse_min(raw.as_deref()).map_err(ApiError::bad_request)
+    }
+}
+
 #[async_trait]
 #[get("/api/timeline/{page}/{pageSize}")]
 impl HttpHandler for TimelineHandler {
@@ -38,19 +93,57 @@ impl HttpHandler for TimelineHandler {
         let photo_repository = context.service::<Repository<Photo>>()?;
         let page: u32 = context.page().unwrap_or(1);
         let page_size: u32 = context.page_size().unwrap_or(10);
+        let per_day_limit = context
+            .request()
+            .query_params()
+            .get("perDayLimit")
+            .and_then(|value| value.parse::<u32>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(SettingConsts::DEFAULT_TIMELINE_PER_DAY_LIMIT)
+            .min(SettingConsts::MAX_TIMELINE_PER_DAY_LIMIT);
+        let min_rating = Self::min_rating(context).or_fail(context)?;
+
+        let zone = context.timeline_zone().await;
 
-        let days: Vec<String> = repository
-            .get_days(page, page_size)
-            .await?
-            .into_iter()
-            .map(|d| d.day_date.format("%Y-%m-%d").to_string())
-            .collect();
+        // The precomputed day table counts every photo, so a rating filter has to scan photos instead.
+        let days: Vec<String> = if zone.is_utc() && min_rating.is_none() {
+            repository
+                .get_days(page, page_size)
+                .await?
+                .into_iter()
+                .map(|d| d.day_date.format("%Y-%m-%d").to_string())
+                .collect()
+        } else {
+            photo_repository.get_days(page, page_size, &zone, min_rating).await?
+        };
 
         let groups = photo_repository
-            .photos_for_days(days)
+            .photos_for_days(days, per_day_limit, &zone, min_rating)
             .await
             .map_err(|e| PipelineError::message(&format!("failed to load photos for days: {:?}", e)))?;
 
         Ok(ResponseValue::json(groups))
     }
 }
+
+struct TimelineDayPhotosHandler;
+
+#[async_trait]
+#[get("/api/photos/day/{date}/{page}/{pageSize}")]
+impl HttpHandler for TimelineDayPhotosHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let raw_date = context.param("date").or_fail(context)?;
+        let day = NaiveDate::parse_from_str(&raw_date, "%Y-%m-%d")
+            .map_err(|e| PipelineError::message(&format!("invalid date '{}': {}", raw_date, e)))?;
+        let page: u32 = context.page().unwrap_or(1);
+        let page_size: u32 = context.page_size().unwrap_or(SettingConsts::DEFAULT_TIMELINE_PER_DAY_LIMIT);
+        let min_rating = TimelineHandler::min_rating(context).or_fail(context)?;
+
+        let zone = context.timeline_zone().await;
+
+        let repository = context.service::<Repository<Photo>>()?;
+        let photos = repository.get_photos_for_day(day, page, page_size, &zone, min_rating).await?;
+
+        Ok(ResponseValue::json(photos))
+    }
+}
@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+
+use crate::prelude::*;
+
+pub struct UploadSessionController;
+
+impl Controller for UploadSessionController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+const CHUNK_CHECKSUM_HEADER: &str = "x-chunk-checksum";
+
+fn session_error(error: UploadSessionError) -> ApiError {
+    match error {
+        UploadSessionError::NotFound => ApiError::not_found("Upload session not found"),
+        UploadSessionError::Invalid(message) => ApiError::bad_request(message),
+        UploadSessionError::Incomplete(missing) => {
+            ApiError::conflict(format!("Upload is missing {} chunk(s), starting at {}", missing.len(), missing[0]))
+        }
+        UploadSessionError::Failed(error) => {
+            log::error!("Upload session failed: {:?}", error);
+            ApiError::internal("Upload session failed")
+        }
+    }
+}
+
+struct CreateUploadSessionHandler;
+
+#[async_trait]
+#[post("/api/photos/uploads", policy = Policy::Authenticated)]
+impl HttpHandler for CreateUploadSessionHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let settings = context.service::<SettingService>()?;
+        if !context.can_upload_photos().await? {
+            return Err(context.fail(ApiError::forbidden("Photo upload is not allowed")));
+        }
+        if !settings.is_photo_upload_enabled().await? {
+            return Err(context.fail(ApiError::forbidden("Photo upload is disabled")));
+        }
+
+        let payload = context.read_valid_json::<CreateUploadSessionRequest>()?;
+        let storage = context
+            .service::<Repository<StorageLocation>>()?
+            .get(&payload.storage_id)
+            .await
+            .map_err(|_| PipelineError::message("Storage location not found"))?
+            .ok_or_else(|| ApiError::not_found("Storage is not found"))
+            .or_fail(context)?;
+        if storage.is_readonly {
+            return Err(context.fail(ApiError::forbidden("Storage is readonly")));
+        }
+
+        let owner_id = context.current_user_id()?;
+        let max_file_size = context.service::<PhotoUploadService>()?.max_file_size();
+        let sessions = context.service::<UploadSessionService>()?;
+        let session = sessions
+            .create(owner_id, &storage, &payload.file_name, payload.total_size, payload.content_type, max_file_size)
+            .await
+            .map_err(session_error)
+            .or_fail(context)?;
+
+        log::info!("Created upload session {} for '{}' ({} bytes)", session.id, session.file_name, session.total_size);
+        Ok(ResponseValue::json(UploadSessionResponse::from_session(&session, sessions.ttl())))
+    }
+}
+
+struct UploadSessionStatusHandler;
+
+#[async_trait]
+#[get("/api/photos/uploads/{id}", policy = Policy::Authenticated)]
+impl HttpHandler for UploadSessionStatusHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let id = context.entity_id().or_fail(context)?;
+        let owner_id = context.current_user_id()?;
+        let sessions = context.service::<UploadSessionService>()?;
+        let session = sessions.get(id, owner_id).map_err(session_error).or_fail(context)?;
+
+        Ok(ResponseValue::json(UploadSessionResponse::from_session(&session, sessions.ttl())))
+    }
+}
+
+struct UploadSessionChunkHandler;
+
+#[async_trait]
+#[put("/api/photos/uploads/{id}/chunks/{index}", policy = Policy::Authenticated)]
+impl HttpHandler for UploadSessionChunkHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let id = context.entity_id().or_fail(context)?;
+        let index = context
+            .param("index")
+            .and_then(|raw| raw.parse::<u32>().map_err(|_| ApiError::bad_request("invalid chunk index")))
+            .or_fail(context)?;
+        let checksum = context.request().headers().get(CHUNK_CHECKSUM_HEADER).map(str::to_string);
+        let body = context.body_bytes()?;
+        let owner_id = context.current_user_id()?;
+
+        let sessions = context.service::<UploadSessionService>()?;
+        let session = sessions
+            .write_chunk(id, owner_id, index, &body, checksum.as_deref())
+            .await
+            .map_err(session_error)
+            .or_fail(context)?;
+
+        Ok(ResponseValue::json(UploadSessionResponse::from_session(&session, sessions.ttl())))
+    }
+}
+
+struct CompleteUploadSessionHandler;
+
+#[async_trait]
+#[post("/api/photos/uploads/{id}/complete", policy = Policy::Authenticated)]
+impl HttpHandler for CompleteUploadSessionHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let id = context.entity_id().or_fail(context)?;
+        let body = context.body_bytes()?;
+        let payload = if body.is_empty() {
+            CompleteUploadSessionRequest::default()
+        } else {
+            serde_json::from_slice::<CompleteUploadSessionRequest>(&body)
+                .map_err(|error| ApiError::bad_request(format!("invalid complete request: {}", error)))
+                .or_fail(context)?
+        };
+        let owner_id = context.current_user_id()?;
+
+        let sessions = context.service::<UploadSessionService>()?;
+        let (session, assembled_path) =
+            sessions.complete(id, owner_id, payload.hash.as_deref()).await.map_err(session_error).or_fail(context)?;
+
+        let storage = context
+            .service::<Repository<StorageLocation>>()?
+            .get(&session.storage_id)
+            .await
+            .map_err(|_| PipelineError::message("Storage location not found"))?
+            .ok_or_else(|| ApiError::not_found("Storage is not found"))
+            .or_fail(context)?;
+
+        let upload_service = context.service::<PhotoUploadService>()?;
+        let saved = upload_service
+            .adopt_temp_file(&assembled_path, &session.file_name, session.content_type.clone(), &session.storage_path)
+            .await
+            .map_err(|error| PipelineError::message(&error.to_string()))?;
+
+        let batch_id = Uuid::new_v4();
+        context
+            .service::<ImageProcessPipeline>()?
+            .enqueue_files_for_request(storage.clone(), vec![saved.clone()], Some(batch_id), context.request_id())
+            .await
+            .map_err(|error| {
+                log::error!("Failed to enqueue image pipeline: {:?}", error);
+                PipelineError::message("Failed to schedule image processing tasks")
+            })?;
+
+        log::info!("Completed upload session {} as '{}'", session.id, saved.relative_path);
+        Ok(ResponseValue::json(UploadPhotosResponse {
+            storage_id: storage.id.to_string(),
+            storage_path: storage.path,
+            uploaded_count: 1,
+            rejected_count: 0,
+            batch_id,
+            synchronous: false,
+            files: vec![UploadFileResponse::accepted(
+                saved.file_name,
+                saved.relative_path,
+                saved.byte_size,
+                saved.content_type,
+            )],
+        }))
+    }
+}
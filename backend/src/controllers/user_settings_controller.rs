@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+
+use crate::prelude::*;
+
+pub struct UserSettingsController;
+
+impl Controller for UserSettingsController {
+    fn routes() -> Vec<EndpointRoute> {
+        vec![]
+    }
+}
+
+impl UserSettingsController {
+    fn target_user(context: &mut HttpContext) -> Result<Option<Uuid>, PipelineError> {
+        let current_user_id = context.current_user_id()?;
+        let requested = context.param("id").or_fail(context)?;
+        let target = if requested.eq_ignore_ascii_case("me") {
+            current_user_id
+        } else {
+            context.entity_id().or_fail(context)?
+        };
+
+        if target != current_user_id && !context.is_admin() {
+            context.response_mut().set_status(403);
+            return Ok(None);
+        }
+        Ok(Some(target))
+    }
+
+    async fn load(context: &HttpContext, user_id: Uuid) -> Result<(UserSettings, bool), PipelineError> {
+        let repository = context.service::<Repository<UserSettings>>()?;
+        if let Some(settings) =
+            repository.get(&user_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+        {
+            return Ok((settings, true));
+        }
+
+        let user = context
+            .service::<Repository<User>>()?
+            .get(&user_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+            .ok_or_else(|| PipelineError::message("User not found"))?;
+        Ok((UserSettings::new(user_id, user.display_name), false))
+    }
+}
+
+struct GetUserSettingsHandler;
+
+#[async_trait]
+#[get("/api/users/{id}/settings", policy = Policy::Authenticated)]
+impl HttpHandler for GetUserSettingsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let Some(user_id) = UserSettingsController::target_user(context)? else {
+            return Ok(ResponseValue::empty());
+        };
+
+        let (settings, _) = UserSettingsController::load(context, user_id).await?;
+        Ok(ResponseValue::json(UserSettingsDto::from(settings)))
+    }
+}
+
+struct UpdateUserSettingsHandler;
+
+#[async_trait]
+#[put("/api/users/{id}/settings", policy = Policy::Authenticated)]
+impl HttpHandler for UpdateUserSettingsHandler {
+    async fn invoke(&self, context: &mut HttpContext) -> Result<ResponseValue, PipelineError> {
+        let Some(user_id) = UserSettingsController::target_user(context)? else {
+            return Ok(ResponseValue::empty());
+        };
+        let payload = context.read_payload::<UpdateUserSettingsRequest>()?;
+
+        let (mut settings, exists) = UserSettingsController::load(context, user_id).await?;
+
+        if let Err(message) = payload.apply(&mut settings) {
+            context.response_mut().set_status(400);
+            return Err(PipelineError::message(&message));
+        }
+
+        let repository = context.service::<Repository<UserSettings>>()?;
+        let saved = if exists { repository.update(settings).await } else { repository.insert(settings).await };
+        let saved = saved.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        Ok(ResponseValue::json(UserSettingsDto::from(saved)))
+    }
+}
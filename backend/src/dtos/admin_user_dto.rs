@@ -11,6 +11,12 @@ pub struct AdminUserDto {
     pub created_at: DateTime<Utc>,
     pub email_verified: bool,
     pub roles: Vec<String>,
+    #[serde(default)]
+    pub photo_comment_count: u64,
+    #[serde(default)]
+    pub album_comment_count: u64,
+    #[serde(default)]
+    pub last_comment_at: Option<DateTime<Utc>>,
 }
 
 impl From<User> for AdminUserDto {
@@ -22,6 +28,9 @@ impl From<User> for AdminUserDto {
             created_at: user.created_at,
             email_verified: user.email_verified,
             roles: parse_roles(user.roles.as_deref()),
+            photo_comment_count: 0,
+            album_comment_count: 0,
+            last_comment_at: None,
         }
     }
 }
@@ -32,6 +41,14 @@ pub struct UpdateUserRolesRequest {
     pub roles: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminUserDeletionDto {
+    pub user_id: Uuid,
+    pub comments_deleted: u32,
+    pub comments_anonymized: u32,
+}
+
 fn parse_roles(raw: Option<&str>) -> Vec<String> {
     raw.unwrap_or_default()
         .split(',')
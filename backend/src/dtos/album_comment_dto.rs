@@ -1,14 +1,15 @@
 use crate::prelude::*;
 
-use crate::entities::AlbumComment;
+use crate::entities::{AlbumComment, UserSettings};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AlbumCommentDto {
     pub id: Uuid,
     pub album_id: Uuid,
-    pub user_id: Uuid,
+    pub user_id: Option<Uuid>,
     pub user_display_name: Option<String>,
+    pub avatar_url: Option<String>,
     pub body: String,
     pub created_at: DateTime<Utc>,
     pub hidden: bool,
@@ -21,9 +22,20 @@ impl From<AlbumComment> for AlbumCommentDto {
             album_id: comment.album_id,
             user_id: comment.user_id,
             user_display_name: comment.user_display_name,
+            avatar_url: None,
             body: comment.body.unwrap_or_default(),
             created_at: comment.created_at.unwrap_or_else(Utc::now),
             hidden: comment.hidden,
         }
     }
 }
+
+impl AlbumCommentDto {
+    pub fn with_author(mut self, authors: &HashMap<Uuid, UserSettings>) -> Self {
+        if let Some(settings) = self.user_id.and_then(|user_id| authors.get(&user_id)) {
+            self.user_display_name = Some(settings.display_name.clone());
+            self.avatar_url = settings.avatar_url.clone();
+        }
+        self
+    }
+}
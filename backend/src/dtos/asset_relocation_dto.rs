@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetRelocationReport {
+    pub scanned: usize,
+    pub moved: usize,
+    pub already_present: usize,
+    pub orphaned: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetRelocationSummary {
+    pub thumbnails: AssetRelocationReport,
+    pub previews: AssetRelocationReport,
+}
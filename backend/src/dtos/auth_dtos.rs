@@ -5,6 +5,10 @@ use crate::prelude::*;
 pub struct LoginResponse {
     pub access_token: String,
     pub refresh_token: String,
+    // Sent back in the X-Session-Id header so the session list can tell which device is asking.
+    pub session_id: Uuid,
+    // Saves clients a call to /api/auth/me right after signing in or refreshing.
+    pub user: UserProfileDto,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -16,6 +20,18 @@ pub struct RegisterRequest {
     pub display_name: String,
 }
 
+impl Validate for RegisterRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Validator::new()
+            .required("email", "Email", &self.email)
+            .check(self.email.trim().is_empty() || self.email.contains('@'), "email", "Email is not valid")
+            .required("password", "Password", &self.password)
+            .check(self.password == self.confirm_password, "confirmPassword", "Passwords do not match")
+            .required("displayName", "Display name", &self.display_name)
+            .finish()
+    }
+}
+
 #[derive(Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginRequest {
@@ -23,6 +39,15 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+impl Validate for LoginRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Validator::new()
+            .required("email", "Email", &self.email)
+            .required("password", "Password", &self.password)
+            .finish()
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RefreshTokenRequest {
@@ -42,6 +67,24 @@ pub struct ChangePasswordRequest {
     pub new_password: String,
 }
 
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountContentAction {
+    #[default]
+    Anonymize,
+    Delete,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAccountRequest {
+    pub password: String,
+    #[serde(default)]
+    pub content_action: AccountContentAction,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResetPasswordRequest {
@@ -55,6 +98,26 @@ pub struct VerifyEmailRequest {
     pub token: String,
 }
 
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResendVerificationRequest {
+    pub email: String,
+}
+
+impl Validate for ResendVerificationRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Validator::new().required("email", "Email", &self.email).finish()
+    }
+}
+
+// Returned instead of tokens when new accounts have to verify before their first sign-in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationPendingResponse {
+    pub user_id: Uuid,
+    pub verification_required: bool,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RegistrationStatusResponse {
@@ -62,3 +125,103 @@ pub struct RegistrationStatusResponse {
     pub allow_registration: bool,
     pub initialized: bool,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDto {
+    pub id: Uuid,
+    pub device: Option<String>,
+    pub ip_address: Option<String>,
+    // Only the last few characters of the refresh token, enough to tell sessions apart.
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+    pub current: bool,
+}
+
+impl SessionDto {
+    pub fn from_session(session: RefreshSession, current: Option<Uuid>) -> Self {
+        Self {
+            id: session.id,
+            device: session.user_agent,
+            ip_address: session.ip_address,
+            token: format!("…{}", session.token_hint),
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+            current: current == Some(session.id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeSessionsResponse {
+    pub revoked: u64,
+}
+
+// What a password sign-in answers with: tokens, or a challenge when the account has two-factor sign-in on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum LoginOutcome {
+    Tokens(LoginResponse),
+    TwoFactorRequired(TwoFactorChallengeResponse),
+}
+
+impl LoginOutcome {
+    pub fn into_tokens(self) -> Option<LoginResponse> {
+        match self {
+            Self::Tokens(response) => Some(response),
+            Self::TwoFactorRequired(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorSetupResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorCodeRequest {
+    pub code: String,
+}
+
+impl Validate for TwoFactorCodeRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Validator::new().required("code", "Code", &self.code).finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorEnabledResponse {
+    // Shown once; only hashes are stored.
+    pub backup_codes: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactorVerifyRequest {
+    pub challenge_token: String,
+    pub code: String,
+}
+
+impl Validate for TwoFactorVerifyRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        Validator::new()
+            .required("challengeToken", "Challenge token", &self.challenge_token)
+            .required("code", "Code", &self.code)
+            .finish()
+    }
+}
@@ -0,0 +1,34 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoAlbumRequest {
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub gap_hours: Option<f64>,
+    #[serde(default)]
+    pub max_distance_km: Option<f64>,
+    #[serde(default)]
+    pub min_photos: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoAlbumProposal {
+    pub album_id: Option<Uuid>,
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub photo_count: usize,
+    pub thumbnail_hash: Option<String>,
+    pub photo_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoAlbumResponse {
+    pub dry_run: bool,
+    pub scanned: usize,
+    pub albums: Vec<AutoAlbumProposal>,
+}
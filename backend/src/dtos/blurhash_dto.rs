@@ -0,0 +1,10 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlurhashBackfillSummary {
+    pub scanned: usize,
+    pub updated: usize,
+    pub missing: usize,
+    pub failed: usize,
+}
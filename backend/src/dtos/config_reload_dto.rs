@@ -0,0 +1,33 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloadResult {
+    pub reloaded_at: DateTime<Utc>,
+    pub success: bool,
+    pub applied: Vec<String>,
+    pub restart_required: Vec<String>,
+    pub warnings: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ConfigReloadResult {
+    pub fn failed(error: String) -> Self {
+        Self {
+            reloaded_at: Utc::now(),
+            success: false,
+            applied: Vec::new(),
+            restart_required: Vec::new(),
+            warnings: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloadStatusDto {
+    pub config_file: String,
+    pub last_reload: Option<ConfigReloadResult>,
+}
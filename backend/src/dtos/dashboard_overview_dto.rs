@@ -0,0 +1,42 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardTotals {
+    #[serde(alias = "total_photos")]
+    pub total_photos: i64,
+    #[serde(alias = "total_albums")]
+    pub total_albums: i64,
+    #[serde(alias = "total_users")]
+    pub total_users: i64,
+    #[serde(alias = "total_tags")]
+    pub total_tags: i64,
+    #[serde(alias = "added_last_7_days")]
+    pub added_last_7_days: i64,
+    #[serde(alias = "added_last_30_days")]
+    pub added_last_30_days: i64,
+    #[serde(alias = "pending_jobs")]
+    pub pending_jobs: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsage {
+    #[serde(alias = "storage_id")]
+    pub storage_id: Uuid,
+    pub label: String,
+    #[serde(alias = "photo_count")]
+    pub photo_count: i64,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardOverview {
+    #[serde(flatten)]
+    pub totals: DashboardTotals,
+    pub storage: Vec<StorageUsage>,
+    // No backup job exists yet; the field is kept so the dashboard does not change shape when one lands.
+    pub last_backup_at: Option<DateTime<Utc>>,
+    pub generated_at: DateTime<Utc>,
+}
@@ -0,0 +1,30 @@
+use crate::prelude::*;
+
+// oEmbed consumers expect the spec's snake_case field names.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OEmbedResponse {
+    pub version: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub title: String,
+    pub provider_name: String,
+    pub provider_url: String,
+    pub cache_age: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail_height: Option<u32>,
+}
+
+impl OEmbedResponse {
+    pub const TYPE_PHOTO: &'static str = "photo";
+    pub const TYPE_LINK: &'static str = "link";
+}
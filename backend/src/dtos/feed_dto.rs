@@ -0,0 +1,28 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedPhoto {
+    pub id: Uuid,
+    pub hash: String,
+    pub name: String,
+    pub title: Option<String>,
+    pub caption: Option<String>,
+    #[serde(alias = "date_taken")]
+    pub date_taken: Option<DateTime<Utc>>,
+    pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedAlbum {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(alias = "create_date")]
+    pub create_date: DateTime<Utc>,
+    #[serde(alias = "thumbnail_hash")]
+    pub thumbnail_hash: Option<String>,
+    #[serde(alias = "photo_count")]
+    pub photo_count: i64,
+}
@@ -0,0 +1,54 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImportRequest {
+    #[serde(default)]
+    pub path: String,
+    #[serde(default = "FolderImportRequest::default_recursive")]
+    pub recursive: bool,
+    #[serde(default)]
+    pub format: ImportFormat,
+}
+
+impl FolderImportRequest {
+    fn default_recursive() -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImportResponse {
+    pub batch_id: Uuid,
+    pub scanned: usize,
+    pub already_imported: usize,
+    pub queued: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportBatchStatus {
+    pub batch_id: Uuid,
+    pub total: usize,
+    pub queued: usize,
+    pub running: usize,
+    pub completed: usize,
+    pub failed: usize,
+}
+
+impl ImportBatchStatus {
+    pub fn from_jobs(batch_id: Uuid, jobs: &[PipelineJob]) -> Self {
+        let mut status = Self { batch_id, total: jobs.len(), ..Default::default() };
+        for job in jobs {
+            match job.state.as_str() {
+                PipelineJob::STATE_QUEUED => status.queued += 1,
+                PipelineJob::STATE_RUNNING => status.running += 1,
+                PipelineJob::STATE_COMPLETED => status.completed += 1,
+                PipelineJob::STATE_FAILED => status.failed += 1,
+                _ => {}
+            }
+        }
+        status
+    }
+}
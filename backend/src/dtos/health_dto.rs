@@ -0,0 +1,13 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseReadinessDto {
+    pub ready: bool,
+    pub migrated: bool,
+    pub reachable: bool,
+    pub pool_size: u32,
+    pub idle_connections: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
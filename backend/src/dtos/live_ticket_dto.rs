@@ -0,0 +1,10 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveTicketDto {
+    pub ticket: String,
+    pub expires_at: DateTime<Utc>,
+    pub path: String,
+    pub port: Option<u16>,
+}
@@ -1,32 +1,79 @@
 pub mod admin_user_dto;
+pub mod api_error_dto;
+pub mod asset_relocation_dto;
 pub mod album_comment_dto;
+pub mod auto_album_dto;
 pub mod auth_dtos;
+pub mod blurhash_dto;
 pub mod client_dto;
+pub mod config_reload_dto;
+pub mod dashboard_overview_dto;
 pub mod dashboard_settings_dto;
+pub mod embed_dto;
+pub mod feed_dto;
+pub mod folder_import_dto;
+pub mod health_dto;
+pub mod live_ticket_dto;
+pub mod notification_dto;
+pub mod person_dto;
 pub mod photo_comment_dto;
 pub mod photo_dtos;
+pub mod photo_metadata_dto;
+pub mod photo_path_repair_dto;
+pub mod preview_cache_dto;
+pub mod setup_dto;
 pub mod sync_dto;
+pub mod tag_rule_dto;
+pub mod tag_suggestion_dto;
 pub mod timeline_dtos;
+pub mod upload_session_dto;
 pub mod user_profile_dto;
 
-pub use admin_user_dto::{AdminUserDto, UpdateUserRolesRequest};
+pub use admin_user_dto::{AdminUserDeletionDto, AdminUserDto, UpdateUserRolesRequest};
+pub use api_error_dto::{ApiErrorBody, FieldError};
+pub use asset_relocation_dto::{AssetRelocationReport, AssetRelocationSummary};
 pub use album_comment_dto::AlbumCommentDto;
+pub use auto_album_dto::{AutoAlbumProposal, AutoAlbumRequest, AutoAlbumResponse};
 pub use auth_dtos::{
-    ChangePasswordRequest, LoginRequest, LoginResponse, LogoutRequest, RefreshTokenRequest, RegisterRequest,
-    RegistrationStatusResponse, ResetPasswordRequest, VerifyEmailRequest,
+    AccountContentAction, ChangePasswordRequest, DeleteAccountRequest, LoginOutcome, LoginRequest, LoginResponse,
+    LogoutRequest, RefreshTokenRequest, RegisterRequest, RegistrationPendingResponse, RegistrationStatusResponse,
+    ResendVerificationRequest, ResetPasswordRequest, RevokeSessionsResponse, SessionDto, TwoFactorChallengeResponse,
+    TwoFactorCodeRequest, TwoFactorEnabledResponse, TwoFactorSetupResponse, TwoFactorVerifyRequest, VerifyEmailRequest,
 };
+pub use blurhash_dto::BlurhashBackfillSummary;
 pub use client_dto::{RegisterClientRequest, RegisterClientResponse};
+pub use config_reload_dto::{ConfigReloadResult, ConfigReloadStatusDto};
+pub use dashboard_overview_dto::{DashboardOverview, DashboardTotals, StorageUsage};
 pub use dashboard_settings_dto::{
     LogoUploadRequest, SettingDto, SettingOptionDto, SettingSection, UpdateSettingPayload,
 };
+pub use embed_dto::OEmbedResponse;
+pub use feed_dto::{FeedAlbum, FeedPhoto};
+pub use folder_import_dto::{FolderImportRequest, FolderImportResponse, ImportBatchStatus};
+pub use health_dto::DatabaseReadinessDto;
+pub use live_ticket_dto::LiveTicketDto;
+pub use notification_dto::{NotificationPageDto, NotificationsReadDto};
+pub use person_dto::{PersonSummary, PhotoPersonDto, RenamePersonPayload, UpdatePhotoPeoplePayload};
 pub use photo_comment_dto::PhotoCommentDto;
 pub use photo_dtos::{
-    DeletePhotosPayload, PhotoGroup, PhotoLoc, PhotoLocWithTags, PhotoWithTags, TagRef, TimelineGroup,
-    UpdatePhotoTagsPayload, UploadFileResponse, UploadPhotosResponse,
+    BatchEditPhotoResult, BatchEditPhotosRequest, BatchEditPhotosResponse, BatchPhotoChanges, DeletePhotosPayload,
+    HasPreviewRequest, PhotoExistsRequest, PhotoExistsResponse, PhotoGroup, PhotoHashExists, PhotoHashLocation,
+    PhotoLoc, PhotoLocWithTags, PhotoWithTags, RandomPhoto, TagRef, TagUpdateMode, TimelineGroup,
+    UpdatePhotoCaptionPayload, UpdatePhotoTagsPayload, UploadFileResponse, UploadPhotosResponse,
 };
+pub use photo_metadata_dto::{PhotoMetadataUpdateResponse, UpdatePhotoMetadataRequest};
+pub use photo_path_repair_dto::{PhotoPathRepairRequest, PhotoPathRepairResult};
+pub use preview_cache_dto::PreviewCacheReport;
 pub use sync_dto::{
     CheckFileItem, CheckFileRequest, CheckFileResponse, SyncAssetKind, SyncFileItem, SyncFileResponse, SyncFileStream,
     SyncMetadataRequest,
 };
-pub use timeline_dtos::TimelineYearDays;
-pub use user_profile_dto::UserProfileDto;
+pub use setup_dto::{
+    SetupAdminRequest, SetupInitializeRequest, SetupInitializeResponse, SetupSettingsRequest, SetupStatusDto,
+    SetupStorageRequest,
+};
+pub use tag_rule_dto::{CreateTagRuleRequest, TagRuleApplyProgress};
+pub use tag_suggestion_dto::{AcceptTagSuggestionsPayload, TagSuggestionDto};
+pub use timeline_dtos::{TimelineYearCount, TimelineYearDays};
+pub use upload_session_dto::{CompleteUploadSessionRequest, CreateUploadSessionRequest, UploadSessionResponse};
+pub use user_profile_dto::{UpdateUserSettingsRequest, UserProfileDto, UserSettingsDto};
@@ -0,0 +1,17 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPageDto {
+    pub items: Vec<Notification>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub unread: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationsReadDto {
+    pub updated: u64,
+}
@@ -0,0 +1,71 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonSummary {
+    pub name: String,
+    #[serde(alias = "photo_count")]
+    pub photo_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoPersonDto {
+    pub name: String,
+    pub region: Option<PersonRegion>,
+    pub source: String,
+}
+
+impl From<PhotoPerson> for PhotoPersonDto {
+    fn from(person: PhotoPerson) -> Self {
+        Self { region: person.region(), name: person.name, source: person.source }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePhotoPeoplePayload {
+    #[serde(default)]
+    pub people: Vec<PersonMention>,
+}
+
+impl UpdatePhotoPeoplePayload {
+    pub const MAX_PEOPLE: usize = 100;
+
+    pub fn mentions(&self) -> Vec<PersonMention> {
+        PersonMention::dedup(self.people.clone())
+    }
+}
+
+impl Validate for UpdatePhotoPeoplePayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.check(
+            self.people.len() <= Self::MAX_PEOPLE,
+            "people",
+            format!("At most {} people can be named on a photo", Self::MAX_PEOPLE),
+        );
+        for person in &self.people {
+            validator.required("people.name", "Name", &person.name);
+            validator.max_chars("people.name", "Name", &person.name, PersonMention::MAX_NAME_LENGTH);
+        }
+        validator.finish()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamePersonPayload {
+    pub from: String,
+    pub to: String,
+}
+
+impl Validate for RenamePersonPayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.required("from", "Name", &self.from);
+        validator.required("to", "Name", &self.to);
+        validator.max_chars("to", "Name", &self.to, PersonMention::MAX_NAME_LENGTH);
+        validator.finish()
+    }
+}
@@ -1,14 +1,15 @@
 use crate::prelude::*;
 
-use crate::entities::PhotoComment;
+use crate::entities::{PhotoComment, UserSettings};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PhotoCommentDto {
     pub id: Uuid,
     pub photo_id: Uuid,
-    pub user_id: Uuid,
+    pub user_id: Option<Uuid>,
     pub user_display_name: Option<String>,
+    pub avatar_url: Option<String>,
     pub body: String,
     pub created_at: DateTime<Utc>,
 }
@@ -20,8 +21,19 @@ impl From<PhotoComment> for PhotoCommentDto {
             photo_id: comment.photo_id,
             user_id: comment.user_id,
             user_display_name: comment.user_display_name,
+            avatar_url: None,
             body: comment.body.unwrap_or_default(),
             created_at: comment.created_at.unwrap_or_else(Utc::now),
         }
     }
 }
+
+impl PhotoCommentDto {
+    pub fn with_author(mut self, authors: &HashMap<Uuid, UserSettings>) -> Self {
+        if let Some(settings) = self.user_id.and_then(|user_id| authors.get(&user_id)) {
+            self.user_display_name = Some(settings.display_name.clone());
+            self.avatar_url = settings.avatar_url.clone();
+        }
+        self
+    }
+}
@@ -7,6 +7,16 @@ pub enum TagRef {
     Name(String),
 }
 
+impl TagRef {
+    pub fn parse(raw: &str) -> Self {
+        let trimmed = raw.trim();
+        match Uuid::parse_str(trimmed) {
+            Ok(id) => TagRef::Id(id),
+            Err(_) => TagRef::Name(trimmed.to_string()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TimelineGroup {
@@ -39,6 +49,24 @@ pub struct PhotoWithTags {
     #[serde(flatten)]
     pub photo: Photo,
     pub tags: Vec<String>,
+    #[serde(default, alias = "imported_on", skip_serializing_if = "Option::is_none")]
+    pub imported_on: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomPhoto {
+    pub id: Uuid,
+    #[serde(alias = "storage_id")]
+    pub storage_id: Uuid,
+    pub hash: Option<String>,
+    pub name: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    #[serde(alias = "date_taken")]
+    pub date_taken: Option<DateTime<Utc>>,
+    #[serde(alias = "sort_date")]
+    pub sort_date: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -56,6 +84,51 @@ pub struct UploadFileResponse {
     pub relative_path: String,
     pub byte_size: usize,
     pub content_type: Option<String>,
+    pub accepted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<SyncProcessStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub photo: Option<UploadedPhotoResponse>,
+}
+
+impl UploadFileResponse {
+    pub fn accepted(file_name: String, relative_path: String, byte_size: usize, content_type: Option<String>) -> Self {
+        Self {
+            file_name,
+            relative_path,
+            byte_size,
+            content_type,
+            accepted: true,
+            reject_reason: None,
+            status: None,
+            photo: None,
+        }
+    }
+
+    pub fn rejected(file: RejectedUploadFile) -> Self {
+        Self {
+            file_name: file.file_name,
+            relative_path: String::new(),
+            byte_size: file.byte_size,
+            content_type: file.content_type,
+            accepted: false,
+            reject_reason: Some(file.reason),
+            status: None,
+            photo: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedPhotoResponse {
+    pub id: Uuid,
+    pub hash: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub has_thumbnail: bool,
 }
 
 #[derive(Serialize)]
@@ -64,9 +137,126 @@ pub struct UploadPhotosResponse {
     pub storage_id: String,
     pub storage_path: String,
     pub uploaded_count: usize,
+    pub rejected_count: usize,
+    pub batch_id: Uuid,
+    pub synchronous: bool,
     pub files: Vec<UploadFileResponse>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoExistsRequest {
+    pub hashes: Vec<String>,
+}
+
+impl PhotoExistsRequest {
+    pub const MAX_HASHES: usize = 1000;
+
+    pub fn normalized_hashes(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.hashes
+            .iter()
+            .filter_map(|hash| HashService::normalize(hash))
+            .filter(|hash| seen.insert(hash.clone()))
+            .collect()
+    }
+}
+
+impl Validate for PhotoExistsRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.check(!self.hashes.is_empty(), "hashes", "hashes cannot be empty");
+        validator.check(
+            self.hashes.len() <= Self::MAX_HASHES,
+            "hashes",
+            format!("at most {} hashes can be checked per request", Self::MAX_HASHES),
+        );
+        for raw in &self.hashes {
+            validator.check(HashService::normalize(raw).is_some(), "hashes", format!("invalid hash: {}", raw));
+        }
+        validator.finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HasPreviewRequest {
+    pub hashes: Vec<String>,
+}
+
+impl HasPreviewRequest {
+    // A lightbox page prefetches a few dozen photos; the cap keeps one request from stat-ing a whole library.
+    pub const MAX_HASHES: usize = 200;
+
+    pub fn normalized_hashes(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.hashes
+            .iter()
+            .filter_map(|hash| HashService::normalize(hash))
+            .filter(|hash| seen.insert(hash.clone()))
+            .collect()
+    }
+}
+
+impl Validate for HasPreviewRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.check(!self.hashes.is_empty(), "hashes", "hashes cannot be empty");
+        validator.check(
+            self.hashes.len() <= Self::MAX_HASHES,
+            "hashes",
+            format!("at most {} hashes can be checked per request", Self::MAX_HASHES),
+        );
+        for raw in &self.hashes {
+            validator.check(HashService::normalize(raw).is_some(), "hashes", format!("invalid hash: {}", raw));
+        }
+        validator.finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoHashLocation {
+    pub hash: String,
+    #[serde(alias = "storage_id")]
+    pub storage_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoHashExists {
+    pub hash: String,
+    pub exists: bool,
+    pub storage_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoExistsResponse {
+    pub results: Vec<PhotoHashExists>,
+}
+
+impl PhotoExistsResponse {
+    pub fn from_locations(hashes: Vec<String>, locations: Vec<PhotoHashLocation>) -> Self {
+        let mut storages = HashMap::<String, Vec<Uuid>>::new();
+        for location in locations {
+            let ids = storages.entry(location.hash).or_default();
+            if !ids.contains(&location.storage_id) {
+                ids.push(location.storage_id);
+            }
+        }
+
+        let results = hashes
+            .into_iter()
+            .map(|hash| {
+                let storage_ids = storages.remove(&hash).unwrap_or_default();
+                PhotoHashExists { exists: !storage_ids.is_empty(), hash, storage_ids }
+            })
+            .collect();
+        Self { results }
+    }
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeletePhotosPayload {
@@ -78,4 +268,174 @@ pub struct DeletePhotosPayload {
 pub struct UpdatePhotoTagsPayload {
     pub photo_ids: Vec<String>,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub mode: TagUpdateMode,
+}
+
+impl Validate for UpdatePhotoTagsPayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.check(!self.photo_ids.is_empty(), "photoIds", "photoIds cannot be empty");
+        for raw in &self.photo_ids {
+            validator.check(raw.to_uuid().is_some(), "photoIds", format!("invalid photo id: {}", raw));
+        }
+        validator.check(self.tags.iter().all(|tag| !tag.trim().is_empty()), "tags", "Tags should not be blank");
+        validator.finish()
+    }
+}
+
+// A missing field is left alone and an empty string clears the stored value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePhotoCaptionPayload {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+impl UpdatePhotoCaptionPayload {
+    pub const MAX_TITLE_LENGTH: usize = 200;
+    pub const MAX_CAPTION_LENGTH: usize = 5_000;
+
+    pub fn apply(&self, photo: &mut Photo) {
+        if let Some(title) = &self.title {
+            photo.title = Self::normalize(title);
+        }
+        if let Some(caption) = &self.caption {
+            photo.caption = Self::normalize(caption);
+        }
+        photo.updated_at = Some(Utc::now());
+    }
+
+    fn normalize(value: &str) -> Option<String> {
+        let trimmed = value.trim();
+        (!trimmed.is_empty()).then(|| trimmed.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePhotoRatingPayload {
+    pub rating: u8,
+}
+
+impl Validate for UpdatePhotoRatingPayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.check(PhotoRating::is_valid(self.rating), "rating", PhotoRating::INVALID_RATING);
+        validator.finish()
+    }
+}
+
+impl Validate for UpdatePhotoCaptionPayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.check(self.title.is_some() || self.caption.is_some(), "title", "Provide a title or a caption");
+        if let Some(title) = &self.title {
+            validator.max_chars("title", "Title", title, Self::MAX_TITLE_LENGTH);
+        }
+        if let Some(caption) = &self.caption {
+            validator.max_chars("caption", "Caption", caption, Self::MAX_CAPTION_LENGTH);
+        }
+        validator.finish()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchPhotoChanges {
+    #[serde(default)]
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub date_taken: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub rating: Option<u8>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+}
+
+impl BatchPhotoChanges {
+    pub fn has_field_changes(&self) -> bool {
+        self.caption.is_some() || self.date_taken.is_some() || self.rating.is_some()
+    }
+
+    pub fn has_tag_changes(&self) -> bool {
+        !self.add_tags.is_empty() || !self.remove_tags.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEditPhotosRequest {
+    pub photo_ids: Vec<String>,
+    #[serde(default)]
+    pub set: BatchPhotoChanges,
+}
+
+impl BatchEditPhotosRequest {
+    pub const MAX_PHOTOS: usize = 1000;
+}
+
+impl Validate for BatchEditPhotosRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.check(!self.photo_ids.is_empty(), "photoIds", "photoIds cannot be empty");
+        validator.check(
+            self.photo_ids.len() <= Self::MAX_PHOTOS,
+            "photoIds",
+            format!("At most {} photos can be edited at once", Self::MAX_PHOTOS),
+        );
+        validator.check(
+            self.set.has_field_changes() || self.set.has_tag_changes(),
+            "set",
+            "Provide at least one change to apply",
+        );
+        if let Some(caption) = &self.set.caption {
+            validator.max_chars("set.caption", "Caption", caption, UpdatePhotoCaptionPayload::MAX_CAPTION_LENGTH);
+        }
+        if let Some(rating) = self.set.rating {
+            validator.check(PhotoRating::is_valid(rating), "set.rating", PhotoRating::INVALID_RATING);
+        }
+        let tags = self.set.add_tags.iter().chain(&self.set.remove_tags);
+        validator.check(tags.clone().all(|tag| !tag.trim().is_empty()), "set.tags", "Tags should not be blank");
+        validator.finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEditPhotoResult {
+    pub photo_id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchEditPhotoResult {
+    pub fn succeeded(photo_id: impl Into<String>) -> Self {
+        Self { photo_id: photo_id.into(), success: true, error: None }
+    }
+
+    pub fn failed(photo_id: impl Into<String>, error: impl Into<String>) -> Self {
+        Self { photo_id: photo_id.into(), success: false, error: Some(error.into()) }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEditPhotosResponse {
+    pub updated: u32,
+    pub results: Vec<BatchEditPhotoResult>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TagUpdateMode {
+    #[default]
+    Add,
+    Remove,
+    Replace,
 }
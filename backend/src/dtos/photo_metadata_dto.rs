@@ -0,0 +1,111 @@
+use crate::prelude::*;
+
+// Text fields use an empty string to clear the value; GPS must be sent as a latitude/longitude pair.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePhotoMetadataRequest {
+    #[serde(default)]
+    pub make: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub lens_model: Option<String>,
+    #[serde(default)]
+    pub gps_latitude: Option<f64>,
+    #[serde(default)]
+    pub gps_longitude: Option<f64>,
+}
+
+impl UpdatePhotoMetadataRequest {
+    pub const MAX_TEXT_LENGTH: usize = 200;
+
+    pub fn apply(&self, exif: &mut ExifModel) {
+        if let Some(make) = &self.make {
+            exif.make = Self::normalize(make);
+            exif.mark_edited(ExifModel::FIELD_MAKE);
+        }
+        if let Some(model) = &self.model {
+            exif.model = Self::normalize(model);
+            exif.mark_edited(ExifModel::FIELD_MODEL);
+        }
+        if let Some(lens_model) = &self.lens_model {
+            exif.lens_model = Self::normalize(lens_model);
+            exif.mark_edited(ExifModel::FIELD_LENS_MODEL);
+        }
+        if let (Some(latitude), Some(longitude)) = (self.gps_latitude, self.gps_longitude) {
+            exif.gps_latitude = Some(latitude);
+            exif.gps_longitude = Some(longitude);
+            exif.gps_latitude_ref = Some(if latitude < 0.0 { "S" } else { "N" }.to_string());
+            exif.gps_longitude_ref = Some(if longitude < 0.0 { "W" } else { "E" }.to_string());
+            exif.mark_edited(ExifModel::FIELD_GPS);
+        }
+    }
+
+    pub fn exiftool_tags(&self) -> Vec<(String, String)> {
+        let mut tags = Vec::new();
+        let mut text = |tag: &str, value: &Option<String>| {
+            if let Some(value) = value {
+                tags.push((tag.to_string(), value.trim().to_string()));
+            }
+        };
+        text("Make", &self.make);
+        text("Model", &self.model);
+        text("LensModel", &self.lens_model);
+
+        if let (Some(latitude), Some(longitude)) = (self.gps_latitude, self.gps_longitude) {
+            tags.push(("GPSLatitude".to_string(), latitude.abs().to_string()));
+            tags.push(("GPSLatitudeRef".to_string(), if latitude < 0.0 { "S" } else { "N" }.to_string()));
+            tags.push(("GPSLongitude".to_string(), longitude.abs().to_string()));
+            tags.push(("GPSLongitudeRef".to_string(), if longitude < 0.0 { "W" } else { "E" }.to_string()));
+        }
+        tags
+    }
+
+    fn normalize(value: &str) -> Option<String> {
+        let trimmed = value.trim();
+        (!trimmed.is_empty()).then(|| trimmed.to_string())
+    }
+}
+
+impl Validate for UpdatePhotoMetadataRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        let has_text = self.make.is_some() || self.model.is_some() || self.lens_model.is_some();
+        let has_gps = self.gps_latitude.is_some() || self.gps_longitude.is_some();
+        validator.check(has_text || has_gps, "metadata", "Provide at least one field to change");
+        for (field, label, value) in [
+            ("make", "Make", &self.make),
+            ("model", "Model", &self.model),
+            ("lensModel", "Lens model", &self.lens_model),
+        ] {
+            if let Some(value) = value {
+                validator.max_chars(field, label, value, Self::MAX_TEXT_LENGTH);
+            }
+        }
+        if has_gps {
+            validator.check(
+                self.gps_latitude.is_some() && self.gps_longitude.is_some(),
+                "gps",
+                "gpsLatitude and gpsLongitude must be provided together",
+            );
+        }
+        if let Some(latitude) = self.gps_latitude {
+            validator.check((-90.0..=90.0).contains(&latitude), "gpsLatitude", "Latitude must be between -90 and 90");
+        }
+        if let Some(longitude) = self.gps_longitude {
+            validator.check(
+                (-180.0..=180.0).contains(&longitude),
+                "gpsLongitude",
+                "Longitude must be between -180 and 180",
+            );
+        }
+        validator.finish()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoMetadataUpdateResponse {
+    pub metadata: ExifModel,
+    pub written_to_file: bool,
+}
@@ -0,0 +1,28 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoPathRepairRequest {
+    pub storage_id: Uuid,
+    pub old_prefix: String,
+    pub new_prefix: String,
+    #[serde(default = "PhotoPathRepairRequest::default_dry_run")]
+    pub dry_run: bool,
+}
+
+impl PhotoPathRepairRequest {
+    fn default_dry_run() -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoPathRepairResult {
+    pub dry_run: bool,
+    pub matched: u64,
+    pub updated: u64,
+    pub sampled: usize,
+    pub missing: usize,
+    pub missing_samples: Vec<String>,
+}
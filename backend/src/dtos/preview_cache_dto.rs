@@ -0,0 +1,13 @@
+use crate::prelude::*;
+
+// Size of one storage's preview cache; the evicted counts stay zero for a plain report.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewCacheReport {
+    pub storage_id: Uuid,
+    pub max_bytes: u64,
+    pub files: u64,
+    pub bytes: u64,
+    pub evicted_files: u64,
+    pub evicted_bytes: u64,
+}
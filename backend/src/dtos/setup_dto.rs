@@ -0,0 +1,78 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupStatusDto {
+    pub has_admin: bool,
+    pub has_storage: bool,
+    pub jwt_secret_configured: bool,
+    pub complete: bool,
+    pub incomplete_steps: Vec<String>,
+}
+
+impl SetupStatusDto {
+    pub const STEP_ADMIN: &'static str = "admin";
+    pub const STEP_STORAGE: &'static str = "storage";
+    pub const STEP_JWT_SECRET: &'static str = "jwtSecret";
+
+    pub fn new(has_admin: bool, has_storage: bool, jwt_secret_configured: bool) -> Self {
+        let incomplete_steps: Vec<String> = [
+            (has_admin, Self::STEP_ADMIN),
+            (has_storage, Self::STEP_STORAGE),
+            (jwt_secret_configured, Self::STEP_JWT_SECRET),
+        ]
+        .into_iter()
+        .filter(|(done, _)| !done)
+        .map(|(_, step)| step.to_string())
+        .collect();
+
+        Self { has_admin, has_storage, jwt_secret_configured, complete: incomplete_steps.is_empty(), incomplete_steps }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupAdminRequest {
+    pub email: String,
+    pub password: String,
+    pub confirm_password: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupStorageRequest {
+    pub label: String,
+    pub path: String,
+    #[serde(default)]
+    pub category_template: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupSettingsRequest {
+    #[serde(default)]
+    pub site_title: Option<String>,
+    #[serde(default)]
+    pub site_public: Option<bool>,
+    #[serde(default)]
+    pub allow_registration: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupInitializeRequest {
+    pub admin: SetupAdminRequest,
+    pub storage: SetupStorageRequest,
+    #[serde(default)]
+    pub settings: SetupSettingsRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupInitializeResponse {
+    pub user_id: Uuid,
+    pub storage_id: Uuid,
+    #[serde(flatten)]
+    pub tokens: LoginResponse,
+}
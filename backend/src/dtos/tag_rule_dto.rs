@@ -0,0 +1,65 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateTagRuleRequest {
+    pub field: String,
+    pub operator: String,
+    pub value: String,
+    pub tag: String,
+}
+
+impl CreateTagRuleRequest {
+    pub const MAX_VALUE_LENGTH: usize = 200;
+
+    pub fn condition(&self) -> Option<(TagRuleField, TagRuleOperator)> {
+        Some((TagRuleField::parse(&self.field)?, TagRuleOperator::parse(&self.operator)?))
+    }
+}
+
+impl Validate for CreateTagRuleRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        let field = TagRuleField::parse(&self.field);
+        let operator = TagRuleOperator::parse(&self.operator);
+        validator.check(field.is_some(), "field", format!("unsupported field: {}", self.field));
+        validator.check(operator.is_some(), "operator", format!("unsupported operator: {}", self.operator));
+        validator.required("value", "Value", &self.value);
+        validator.max_chars("value", "Value", &self.value, Self::MAX_VALUE_LENGTH);
+        validator.check(!self.tag.trim().is_empty(), "tag", "Tag should not be blank");
+
+        if let (Some(field), Some(operator)) = (field, operator) {
+            if field.is_numeric() {
+                validator.check(!operator.is_text_only(), "operator", "Numeric fields cannot use text operators");
+                validator.check(self.value.trim().parse::<f64>().is_ok(), "value", "Value should be a number");
+            } else if field == TagRuleField::IsRaw {
+                validator.check(
+                    !operator.is_text_only() && !operator.is_ordering(),
+                    "operator",
+                    "isRaw only supports eq and ne",
+                );
+                validator.check(
+                    self.value.trim().to_lowercase().parse::<bool>().is_ok(),
+                    "value",
+                    "Value should be true or false",
+                );
+            } else {
+                validator.check(!operator.is_ordering(), "operator", "Text fields cannot use numeric operators");
+            }
+        }
+        validator.finish()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRuleApplyProgress {
+    pub task_id: Option<Uuid>,
+    pub running: bool,
+    pub total: u64,
+    pub processed: u64,
+    pub tagged: u64,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
@@ -0,0 +1,34 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSuggestionDto {
+    pub name: String,
+    pub confidence: f32,
+    pub analyzer: String,
+}
+
+impl From<PhotoTagSuggestion> for TagSuggestionDto {
+    fn from(suggestion: PhotoTagSuggestion) -> Self {
+        Self { name: suggestion.name, confidence: suggestion.confidence, analyzer: suggestion.analyzer }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AcceptTagSuggestionsPayload {
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Validate for AcceptTagSuggestionsPayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.check(
+            self.tags.iter().any(|tag| !tag.trim().is_empty()),
+            "tags",
+            "At least one suggestion must be accepted",
+        );
+        validator.finish()
+    }
+}
@@ -5,3 +5,13 @@ pub struct TimelineYearDays {
     pub year: i32,
     pub days: Vec<NaiveDate>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineYearCount {
+    pub year: i32,
+    #[serde(alias = "day_count")]
+    pub day_count: i64,
+    #[serde(alias = "photo_count")]
+    pub photo_count: i64,
+}
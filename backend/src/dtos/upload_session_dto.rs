@@ -0,0 +1,58 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUploadSessionRequest {
+    pub storage_id: Uuid,
+    pub file_name: String,
+    pub total_size: u64,
+    #[serde(default)]
+    pub content_type: Option<String>,
+}
+
+impl Validate for CreateUploadSessionRequest {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.required("fileName", "File name", &self.file_name);
+        validator.max_chars("fileName", "File name", &self.file_name, 255);
+        validator.check(self.total_size > 0, "totalSize", "totalSize must be greater than zero");
+        validator.finish()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompleteUploadSessionRequest {
+    #[serde(default)]
+    pub hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSessionResponse {
+    pub id: Uuid,
+    pub storage_id: Uuid,
+    pub file_name: String,
+    pub total_size: u64,
+    pub chunk_size: u64,
+    pub chunk_count: u32,
+    pub received_chunks: Vec<u32>,
+    pub missing_chunks: Vec<u32>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl UploadSessionResponse {
+    pub fn from_session(session: &UploadSession, ttl: std::time::Duration) -> Self {
+        Self {
+            id: session.id,
+            storage_id: session.storage_id,
+            file_name: session.file_name.clone(),
+            total_size: session.total_size,
+            chunk_size: session.chunk_size,
+            chunk_count: session.chunk_count(),
+            received_chunks: session.received.iter().copied().collect(),
+            missing_chunks: session.missing_chunks(),
+            expires_at: session.expires_at(ttl),
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use crate::entities::{user::User, user_settings::UserSettings};
 use crate::prelude::*;
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UserProfileDto {
     pub id: Uuid,
     pub email: String,
     pub display_name: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
     pub avatar_url: Option<String>,
     pub theme: String,
     pub language: String,
@@ -18,6 +20,15 @@ impl From<(User, UserSettings)> for UserProfileDto {
             id: user.id,
             email: user.email,
             display_name: settings.display_name,
+            roles: user
+                .roles
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|role| !role.is_empty())
+                .map(str::to_string)
+                .collect(),
             avatar_url: settings.avatar_url,
             theme: settings.theme,
             language: settings.language,
@@ -25,3 +36,103 @@ impl From<(User, UserSettings)> for UserProfileDto {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSettingsDto {
+    pub user_id: Uuid,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub theme: String,
+    pub language: String,
+    pub timezone: String,
+    pub email_digest: bool,
+}
+
+impl From<UserSettings> for UserSettingsDto {
+    fn from(settings: UserSettings) -> Self {
+        Self {
+            user_id: settings.user_id,
+            display_name: settings.display_name,
+            avatar_url: settings.avatar_url,
+            theme: settings.theme,
+            language: settings.language,
+            timezone: settings.timezone,
+            email_digest: settings.email_digest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserSettingsRequest {
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub email_digest: Option<bool>,
+}
+
+impl UpdateUserSettingsRequest {
+    pub const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+    pub const MAX_AVATAR_URL_LENGTH: usize = 2048;
+
+    pub fn apply(self, settings: &mut UserSettings) -> Result<(), String> {
+        if let Some(display_name) = self.display_name {
+            let display_name = display_name.trim();
+            if display_name.is_empty() {
+                return Err("Display name cannot be empty".to_string());
+            }
+            if display_name.chars().count() > Self::MAX_DISPLAY_NAME_LENGTH {
+                return Err(format!("Display name must be {} characters or fewer", Self::MAX_DISPLAY_NAME_LENGTH));
+            }
+            settings.display_name = display_name.to_string();
+        }
+
+        if let Some(avatar_url) = self.avatar_url {
+            let avatar_url = avatar_url.trim();
+            if avatar_url.len() > Self::MAX_AVATAR_URL_LENGTH {
+                return Err(format!("Avatar URL must be {} characters or fewer", Self::MAX_AVATAR_URL_LENGTH));
+            }
+            settings.avatar_url = (!avatar_url.is_empty()).then(|| avatar_url.to_string());
+        }
+
+        if let Some(theme) = self.theme {
+            settings.theme = Self::allowed(&theme, UserSettings::THEMES, "theme")?;
+        }
+
+        if let Some(language) = self.language {
+            settings.language = Self::allowed(&language, UserSettings::LANGUAGES, "language")?;
+        }
+
+        if let Some(timezone) = self.timezone {
+            let timezone = timezone.trim();
+            if !TimelineZone::is_valid(timezone) {
+                return Err(format!("Unsupported timezone '{}'", timezone));
+            }
+            settings.timezone = timezone.to_string();
+        }
+
+        if let Some(email_digest) = self.email_digest {
+            settings.email_digest = email_digest;
+        }
+
+        Ok(())
+    }
+
+    fn allowed(value: &str, options: &[&str], field: &str) -> Result<String, String> {
+        let value = value.trim();
+        options
+            .iter()
+            .find(|option| option.eq_ignore_ascii_case(value))
+            .map(|option| option.to_string())
+            .ok_or_else(|| format!("Unsupported {} '{}'. Allowed values: {}", field, value, options.join(", ")))
+    }
+}
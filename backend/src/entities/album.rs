@@ -46,6 +46,43 @@ pub struct Album {
     pub sort_order: i32,
     #[serde(alias = "image_count")]
     pub image_count: Option<i64>,
+    #[serde(default)]
+    pub version: i32,
+    #[serde(default, alias = "start_date")]
+    pub start_date: Option<DateTime<Utc>>,
+    #[serde(default, alias = "end_date")]
+    pub end_date: Option<DateTime<Utc>>,
+    #[serde(default, alias = "dates_overridden")]
+    pub dates_overridden: bool,
+    #[serde(default, alias = "location_name")]
+    pub location_name: Option<String>,
+    #[serde(default, skip_serializing)]
+    #[cfg_attr(feature = "postgres", sqlx(skip))]
+    pub reset_dates: bool,
+}
+
+impl Album {
+    pub const MAX_NAME_LENGTH: usize = 200;
+    pub const MAX_DESCRIPTION_LENGTH: usize = 10_000;
+    pub const MAX_LOCATION_NAME_LENGTH: usize = 200;
+}
+
+impl Validate for Album {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator.required("name", "Album name", &self.name);
+        validator.max_chars("name", "Album name", &self.name, Self::MAX_NAME_LENGTH);
+        if let Some(description) = &self.description {
+            validator.max_chars("description", "Description", description, Self::MAX_DESCRIPTION_LENGTH);
+        }
+        if let Some(location_name) = &self.location_name {
+            validator.max_chars("locationName", "Location name", location_name, Self::MAX_LOCATION_NAME_LENGTH);
+        }
+        if let (Some(start), Some(end)) = (self.start_date, self.end_date) {
+            validator.check(start <= end, "endDate", "End date must not be before start date");
+        }
+        validator.finish()
+    }
 }
 
 #[cfg(feature = "postgres")]
@@ -105,6 +142,11 @@ impl PostgresEntity for Album {
             "thumbnail_hash",
             "sort_order",
             "image_count",
+            "version",
+            "start_date",
+            "end_date",
+            "dates_overridden",
+            "location_name",
         ]
     }
 
@@ -120,6 +162,11 @@ impl PostgresEntity for Album {
             PostgresValueBuilder::optional_string(&self.thumbnail_hash),
             Value::Int(self.sort_order as i64),
             PostgresValueBuilder::optional_i64(self.image_count),
+            Value::Int(self.version as i64),
+            PostgresValueBuilder::optional_datetime(&self.start_date),
+            PostgresValueBuilder::optional_datetime(&self.end_date),
+            Value::Bool(self.dates_overridden),
+            PostgresValueBuilder::optional_string(&self.location_name),
         ]
     }
 
@@ -134,6 +181,11 @@ impl PostgresEntity for Album {
             "thumbnail_hash",
             "sort_order",
             "image_count",
+            "version",
+            "start_date",
+            "end_date",
+            "dates_overridden",
+            "location_name",
         ]
     }
 
@@ -148,6 +200,11 @@ impl PostgresEntity for Album {
             PostgresValueBuilder::optional_string(&self.thumbnail_hash),
             Value::Int(self.sort_order as i64),
             PostgresValueBuilder::optional_i64(self.image_count),
+            Value::Int(self.version as i64),
+            PostgresValueBuilder::optional_datetime(&self.start_date),
+            PostgresValueBuilder::optional_datetime(&self.end_date),
+            Value::Bool(self.dates_overridden),
+            PostgresValueBuilder::optional_string(&self.location_name),
         ]
     }
 
@@ -163,6 +220,11 @@ impl PostgresEntity for Album {
             ColumnDef::new("thumbnail_hash", ColumnType::Text),
             ColumnDef::new("sort_order", ColumnType::Integer).not_null(),
             ColumnDef::new("image_count", ColumnType::BigInt),
+            ColumnDef::new("version", ColumnType::Integer).not_null().default("1"),
+            ColumnDef::new("start_date", ColumnType::Timestamp),
+            ColumnDef::new("end_date", ColumnType::Timestamp),
+            ColumnDef::new("dates_overridden", ColumnType::Boolean).not_null().default("false"),
+            ColumnDef::new("location_name", ColumnType::Text),
         ]
     }
 }
@@ -18,7 +18,7 @@ pub struct AlbumComment {
     #[serde(default)]
     pub album_id: Uuid,
     #[serde(default)]
-    pub user_id: Uuid,
+    pub user_id: Option<Uuid>,
     pub user_display_name: Option<String>,
     pub body: Option<String>,
     pub created_at: Option<DateTime<Utc>>,
@@ -30,7 +30,7 @@ impl AlbumComment {
         Self {
             id: Uuid::nil(),
             album_id,
-            user_id,
+            user_id: Some(user_id),
             user_display_name: Some(display_name),
             body: Some(body),
             created_at: Some(Utc::now()),
@@ -94,7 +94,7 @@ impl PostgresEntity for AlbumComment {
         vec![
             nimble_web::data::query::Value::Uuid(self.id),
             nimble_web::data::query::Value::Uuid(self.album_id),
-            nimble_web::data::query::Value::Uuid(self.user_id),
+            PostgresValueBuilder::optional_uuid(self.user_id),
             PostgresValueBuilder::optional_string(&self.user_display_name),
             PostgresValueBuilder::optional_string(&self.body),
             PostgresValueBuilder::optional_datetime(&self.created_at),
@@ -103,11 +103,12 @@ impl PostgresEntity for AlbumComment {
     }
 
     fn update_columns() -> &'static [&'static str] {
-        &["user_display_name", "body", "hidden"]
+        &["user_id", "user_display_name", "body", "hidden"]
     }
 
     fn update_values(&self) -> Vec<nimble_web::data::query::Value> {
         vec![
+            PostgresValueBuilder::optional_uuid(self.user_id),
             PostgresValueBuilder::optional_string(&self.user_display_name),
             PostgresValueBuilder::optional_string(&self.body),
             nimble_web::data::query::Value::Bool(self.hidden),
@@ -118,7 +119,7 @@ impl PostgresEntity for AlbumComment {
         vec![
             ColumnDef::new("id", ColumnType::Uuid).primary_key().default("gen_random_uuid()"),
             ColumnDef::new("album_id", ColumnType::Uuid).not_null(),
-            ColumnDef::new("user_id", ColumnType::Uuid).not_null(),
+            ColumnDef::new("user_id", ColumnType::Uuid),
             ColumnDef::new("user_display_name", ColumnType::Text),
             ColumnDef::new("body", ColumnType::Text).not_null(),
             ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
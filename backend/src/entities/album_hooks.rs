@@ -7,6 +7,34 @@ impl AlbumHooks {
     pub fn new() -> Self {
         Self
     }
+
+    fn validate(entity: &Album) -> HttpResult<()> {
+        entity.validate().map_err(|errors| {
+            let message = errors.first().map(|error| error.message.clone()).unwrap_or_default();
+            HttpError::new(400, &message)
+        })
+    }
+
+    fn invalidate_membership(context: &RequestContext, album_id: Uuid) {
+        if let Some(cache) = context.services().resolve::<AlbumMembershipCache>() {
+            cache.invalidate(album_id);
+        }
+    }
+
+    // Explicit dates in the payload pin the range; resetDates hands it back to membership-based derivation.
+    fn apply_date_override(entity: &mut Album, current: Option<&Album>) {
+        if entity.reset_dates {
+            entity.dates_overridden = false;
+            entity.reset_dates = false;
+            return;
+        }
+
+        let changed = match current {
+            Some(current) => current.start_date != entity.start_date || current.end_date != entity.end_date,
+            None => entity.start_date.is_some() || entity.end_date.is_some(),
+        };
+        entity.dates_overridden = changed || current.is_some_and(|current| current.dates_overridden);
+    }
 }
 
 #[async_trait]
@@ -24,6 +52,44 @@ impl EntityHooks<Album> for AlbumHooks {
         if entity.create_date.is_none() {
             entity.create_date = Some(Utc::now());
         }
+        entity.version = entity.version.max(1);
+        Self::validate(entity)?;
+        Self::apply_date_override(entity, None);
         Ok(())
     }
+
+    async fn before_update(&self, context: &RequestContext, entity: &mut Album) -> HttpResult<()> {
+        let repository = context
+            .services()
+            .resolve::<Repository<Album>>()
+            .ok_or_else(|| HttpError::new(500, "Album repository is not registered"))?;
+        Self::validate(entity)?;
+
+        if entity.reset_dates {
+            let (start_date, end_date) = repository
+                .album_date_range(entity.id)
+                .await
+                .map_err(|err| HttpError::new(500, &format!("{:?}", err)))?;
+            entity.start_date = start_date;
+            entity.end_date = end_date;
+        }
+        let stored = repository.get(&entity.id).await.map_err(|err| HttpError::new(500, &format!("{:?}", err)))?;
+        Self::apply_date_override(entity, stored.as_ref());
+
+        let bumped = repository
+            .bump_album_version(entity.id, entity.version)
+            .await
+            .map_err(|err| HttpError::new(500, &format!("{:?}", err)))?;
+        if let Some(version) = bumped {
+            entity.version = version;
+            Self::invalidate_membership(context, entity.id);
+            return Ok(());
+        }
+
+        let current = repository.get(&entity.id).await.map_err(|err| HttpError::new(500, &format!("{:?}", err)))?;
+        match current {
+            Some(current) => Err(HttpError::new(409, &serde_json::to_string(&current).unwrap_or_default())),
+            None => Err(HttpError::new(404, "Album not found")),
+        }
+    }
 }
@@ -20,11 +20,13 @@ pub struct AlbumPhoto {
     pub photo_id: Uuid,
     #[serde(alias = "created_at")]
     pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub position: Option<i32>,
 }
 
 impl AlbumPhoto {
     pub fn new(album_id: Uuid, photo_id: Uuid) -> Self {
-        Self { id: Uuid::new_v4(), album_id, photo_id, created_at: Some(Utc::now()) }
+        Self { id: Uuid::new_v4(), album_id, photo_id, created_at: Some(Utc::now()), position: None }
     }
 }
 
@@ -51,7 +53,7 @@ impl PostgresEntity for AlbumPhoto {
     }
 
     fn insert_columns() -> &'static [&'static str] {
-        &["id", "album_id", "photo_id", "created_at"]
+        &["id", "album_id", "photo_id", "created_at", "position"]
     }
 
     fn insert_values(&self) -> Vec<Value> {
@@ -60,11 +62,12 @@ impl PostgresEntity for AlbumPhoto {
             Value::Uuid(self.album_id),
             Value::Uuid(self.photo_id),
             PostgresValueBuilder::optional_datetime(&self.created_at),
+            PostgresValueBuilder::optional_i32(self.position),
         ]
     }
 
     fn update_columns() -> &'static [&'static str] {
-        &["album_id", "photo_id", "created_at"]
+        &["album_id", "photo_id", "created_at", "position"]
     }
 
     fn update_values(&self) -> Vec<Value> {
@@ -72,6 +75,7 @@ impl PostgresEntity for AlbumPhoto {
             Value::Uuid(self.album_id),
             Value::Uuid(self.photo_id),
             PostgresValueBuilder::optional_datetime(&self.created_at),
+            PostgresValueBuilder::optional_i32(self.position),
         ]
     }
 
@@ -81,6 +85,7 @@ impl PostgresEntity for AlbumPhoto {
             ColumnDef::new("album_id", ColumnType::Uuid).not_null(),
             ColumnDef::new("photo_id", ColumnType::Uuid).not_null(),
             ColumnDef::new("created_at", ColumnType::Timestamp).not_null(),
+            ColumnDef::new("position", ColumnType::Integer),
         ]
     }
 }
@@ -0,0 +1,123 @@
+use crate::prelude::*;
+
+#[cfg(feature = "postgres")]
+use {
+    nimble_web::data::postgres::{PostgresEntity, value_builder::PostgresValueBuilder},
+    nimble_web::data::query::Value,
+    nimble_web::data::schema::{ColumnDef, ColumnType},
+    sqlx::postgres::PgRow,
+    sqlx::{FromRow, Row},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLog {
+    pub id: Uuid,
+    pub actor_user_id: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub details: Option<JsonValue>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditLog {
+    pub fn new(actor_user_id: Option<Uuid>, action: &str, target_type: &str, target_id: impl ToString) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            actor_user_id,
+            action: action.to_string(),
+            target_type: target_type.to_string(),
+            target_id: Some(target_id.to_string()),
+            details: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn with_details(mut self, details: JsonValue) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    #[cfg(feature = "postgres")]
+    fn details_text(&self) -> Option<String> {
+        self.details.as_ref().and_then(|details| serde_json::to_string(details).ok())
+    }
+}
+
+impl Entity for AuditLog {
+    type Id = Uuid;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn name() -> &'static str {
+        "audit_log"
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'r> FromRow<'r, PgRow> for AuditLog {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let raw_details: Option<String> = row.try_get("details")?;
+        let details = raw_details.as_deref().and_then(|raw| serde_json::from_str::<JsonValue>(raw).ok());
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            actor_user_id: row.try_get("actor_user_id")?,
+            action: row.try_get("action")?,
+            target_type: row.try_get("target_type")?,
+            target_id: row.try_get("target_id")?,
+            details,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresEntity for AuditLog {
+    fn id_column() -> &'static str {
+        "id"
+    }
+
+    fn id_value(id: &Self::Id) -> Value {
+        Value::Uuid(*id)
+    }
+
+    fn insert_columns() -> &'static [&'static str] {
+        &["id", "actor_user_id", "action", "target_type", "target_id", "details", "created_at"]
+    }
+
+    fn insert_values(&self) -> Vec<Value> {
+        vec![
+            Value::Uuid(self.id),
+            PostgresValueBuilder::optional_uuid(self.actor_user_id),
+            Value::String(self.action.clone()),
+            Value::String(self.target_type.clone()),
+            PostgresValueBuilder::optional_string(&self.target_id),
+            PostgresValueBuilder::optional_string(&self.details_text()),
+            Value::DateTime(self.created_at),
+        ]
+    }
+
+    fn update_columns() -> &'static [&'static str] {
+        &["details"]
+    }
+
+    fn update_values(&self) -> Vec<Value> {
+        vec![PostgresValueBuilder::optional_string(&self.details_text())]
+    }
+
+    fn table_columns() -> Vec<ColumnDef> {
+        vec![
+            ColumnDef::new("id", ColumnType::Uuid).primary_key().default("gen_random_uuid()"),
+            ColumnDef::new("actor_user_id", ColumnType::Uuid),
+            ColumnDef::new("action", ColumnType::Text).not_null(),
+            ColumnDef::new("target_type", ColumnType::Text).not_null(),
+            ColumnDef::new("target_id", ColumnType::Text),
+            ColumnDef::new("details", ColumnType::Text),
+            ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
+        ]
+    }
+}
@@ -0,0 +1,103 @@
+use crate::prelude::*;
+
+#[cfg(feature = "postgres")]
+use {
+    nimble_web::data::postgres::PostgresEntity,
+    nimble_web::data::query::Value,
+    nimble_web::data::schema::{ColumnDef, ColumnType},
+    sqlx::FromRow,
+};
+
+#[cfg_attr(feature = "postgres", derive(FromRow))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestRun {
+    pub id: Uuid,
+    pub kind: String,
+    pub since: DateTime<Utc>,
+    #[serde(alias = "sent_at")]
+    pub sent_at: DateTime<Utc>,
+    pub recipients: i64,
+    pub photos: i64,
+    pub comments: i64,
+    pub albums: i64,
+}
+
+impl DigestRun {
+    pub const KIND_DAILY: &'static str = "daily";
+    pub const KIND_TEST: &'static str = "test";
+
+    pub fn new(kind: &str, summary: &DigestSummary, recipients: usize) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            kind: kind.to_string(),
+            since: summary.since,
+            sent_at: summary.until,
+            recipients: recipients as i64,
+            photos: summary.photos,
+            comments: summary.comments,
+            albums: summary.albums,
+        }
+    }
+}
+
+impl Entity for DigestRun {
+    type Id = Uuid;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn name() -> &'static str {
+        "digest_run"
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresEntity for DigestRun {
+    fn id_column() -> &'static str {
+        "id"
+    }
+
+    fn id_value(id: &Self::Id) -> Value {
+        Value::Uuid(*id)
+    }
+
+    fn insert_columns() -> &'static [&'static str] {
+        &["id", "kind", "since", "sent_at", "recipients", "photos", "comments", "albums"]
+    }
+
+    fn insert_values(&self) -> Vec<Value> {
+        vec![
+            Value::Uuid(self.id),
+            Value::String(self.kind.clone()),
+            Value::DateTime(self.since),
+            Value::DateTime(self.sent_at),
+            Value::Int(self.recipients),
+            Value::Int(self.photos),
+            Value::Int(self.comments),
+            Value::Int(self.albums),
+        ]
+    }
+
+    fn update_columns() -> &'static [&'static str] {
+        &["recipients"]
+    }
+
+    fn update_values(&self) -> Vec<Value> {
+        vec![Value::Int(self.recipients)]
+    }
+
+    fn table_columns() -> Vec<ColumnDef> {
+        vec![
+            ColumnDef::new("id", ColumnType::Uuid).primary_key().default("gen_random_uuid()"),
+            ColumnDef::new("kind", ColumnType::Text).not_null(),
+            ColumnDef::new("since", ColumnType::Timestamp).not_null(),
+            ColumnDef::new("sent_at", ColumnType::Timestamp).not_null().default("NOW()"),
+            ColumnDef::new("recipients", ColumnType::BigInt).not_null().default("0"),
+            ColumnDef::new("photos", ColumnType::BigInt).not_null().default("0"),
+            ColumnDef::new("comments", ColumnType::BigInt).not_null().default("0"),
+            ColumnDef::new("albums", ColumnType::BigInt).not_null().default("0"),
+        ]
+    }
+}
@@ -102,6 +102,7 @@ const EXIF_INSERT_COLUMNS: &[&str] = &[
     "photographic_sensitivity",
     "interop_index",
     "interop_version",
+    "edited_fields",
 ];
 
 const EXIF_UPDATE_COLUMNS: &[&str] = &[
@@ -195,6 +196,7 @@ const EXIF_UPDATE_COLUMNS: &[&str] = &[
     "photographic_sensitivity",
     "interop_index",
     "interop_version",
+    "edited_fields",
 ];
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -305,9 +307,18 @@ pub struct ExifModel {
     pub photographic_sensitivity: Option<u32>,
     pub interop_index: Option<String>,
     pub interop_version: Option<String>,
+
+    // Fields overridden by hand; re-imports must not clobber them.
+    #[serde(default)]
+    pub edited_fields: Vec<String>,
 }
 
 impl ExifModel {
+    pub const FIELD_MAKE: &'static str = "make";
+    pub const FIELD_MODEL: &'static str = "model";
+    pub const FIELD_LENS_MODEL: &'static str = "lensModel";
+    pub const FIELD_GPS: &'static str = "gps";
+
     pub fn get_date_taken(&self) -> Option<DateTime<Utc>> {
         let candidates =
             [self.datetime_original.as_deref(), self.datetime.as_deref(), self.datetime_digitized.as_deref()];
@@ -337,6 +348,31 @@ impl ExifModel {
         self.iso.or(self.photographic_sensitivity)
     }
 
+    pub fn mark_edited(&mut self, field: &str) {
+        if !self.edited_fields.iter().any(|existing| existing == field) {
+            self.edited_fields.push(field.to_string());
+        }
+    }
+
+    // Copies hand-edited values from the stored row over freshly extracted metadata.
+    pub fn keep_edits_from(&mut self, stored: &ExifModel) {
+        for field in &stored.edited_fields {
+            match field.as_str() {
+                Self::FIELD_MAKE => self.make = stored.make.clone(),
+                Self::FIELD_MODEL => self.model = stored.model.clone(),
+                Self::FIELD_LENS_MODEL => self.lens_model = stored.lens_model.clone(),
+                Self::FIELD_GPS => {
+                    self.gps_latitude = stored.gps_latitude;
+                    self.gps_longitude = stored.gps_longitude;
+                    self.gps_latitude_ref = stored.gps_latitude_ref.clone();
+                    self.gps_longitude_ref = stored.gps_longitude_ref.clone();
+                }
+                _ => continue,
+            }
+            self.mark_edited(field);
+        }
+    }
+
     fn parse_exif_timestamp(raw: &str) -> Option<DateTime<Utc>> {
         let trimmed = raw.trim();
         if trimmed.is_empty() {
@@ -477,6 +513,10 @@ impl<'r> FromRow<'r, PgRow> for ExifModel {
                 .map(|value| value as u32),
             interop_index: row.try_get("interop_index")?,
             interop_version: row.try_get("interop_version")?,
+            edited_fields: row
+                .try_get::<Option<String>, _>("edited_fields")?
+                .and_then(|raw| serde_json::from_str(&raw).ok())
+                .unwrap_or_default(),
         })
     }
 }
@@ -588,6 +628,7 @@ impl PostgresEntity for ExifModel {
             PostgresValueBuilder::optional_u32(self.photographic_sensitivity),
             PostgresValueBuilder::optional_string(&self.interop_index),
             PostgresValueBuilder::optional_string(&self.interop_version),
+            Value::String(serde_json::to_string(&self.edited_fields).unwrap_or_else(|_| "[]".to_string())),
         ]
     }
 
@@ -694,6 +735,7 @@ impl PostgresEntity for ExifModel {
             ColumnDef::new("photographic_sensitivity", ColumnType::Integer),
             ColumnDef::new("interop_index", ColumnType::Text),
             ColumnDef::new("interop_version", ColumnType::Text),
+            ColumnDef::new("edited_fields", ColumnType::Text),
         ]
     }
 }
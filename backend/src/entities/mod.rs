@@ -2,22 +2,31 @@ pub use album::Album;
 pub use album::AlbumKind;
 pub use album_comment::AlbumComment;
 pub use album_photo::AlbumPhoto;
+pub use audit_log::AuditLog;
 pub use client::Client;
 pub use client_storage::ClientStorage;
+pub use digest_run::DigestRun;
 pub use exif::ExifModel;
 #[cfg(not(feature = "postgres"))]
 use nimble_web::MemoryRepository;
 use nimble_web::{AppBuilder, Application, EntityOperation, Policy, Repository};
+pub use notification::Notification;
 pub use permission::Permission;
 pub use photo::Photo;
 pub use photo::PhotoViewModel;
 pub use photo_browse::{
-    BrowseDimension, BrowseNodeType, BrowseOptions, BrowsePhoto, BrowseRequest, BrowseResponse, SortDirection,
-    StorageFolder,
+    BrowseDimension, BrowseNodeType, BrowseOptions, BrowsePhoto, BrowseRequest, BrowseResponse, BrowseSort,
+    SortDirection, StorageFolder,
 };
 pub use photo_comment::PhotoComment;
-pub use photo_cursor::PhotoCursor;
+pub use photo_cursor::{InvalidCursor, PhotoCursor};
+pub use photo_person::PhotoPerson;
 pub use photo_tag::PhotoTag;
+pub use photo_tag_suggestion::PhotoTagSuggestion;
+pub use pipeline_job::PipelineJob;
+#[cfg(feature = "postgres")]
+pub use schema_migration::{Migration, MigrationStep, SchemaMigrator};
+pub use refresh_session::RefreshSession;
 pub use setting::Setting;
 pub use setting::SettingValueType;
 pub use storage_location::{
@@ -25,12 +34,15 @@ pub use storage_location::{
     UpdateStoragePayload,
 };
 pub use tag::Tag;
+pub use tag_rule::TagRule;
 pub use timeline::TimelineDay;
+pub use two_factor::TwoFactor;
 pub use user::User;
 pub use user_settings::UserSettings;
 pub use uuid_id::{EnsureUuidIdHooks, HasOptionalUuidId};
 
 use crate::entities::album_hooks::AlbumHooks;
+use crate::entities::photo_hooks::PhotoHooks;
 #[cfg(feature = "postgres")]
 use crate::models::setting_consts::SettingConsts;
 use anyhow::{Result, anyhow};
@@ -44,29 +56,37 @@ pub mod album;
 pub mod album_comment;
 pub mod album_hooks;
 pub mod album_photo;
+pub mod audit_log;
 pub mod client;
 pub mod client_storage;
+pub mod digest_run;
 pub mod exif;
+pub mod notification;
 pub mod permission;
 pub mod photo;
 pub mod photo_browse;
 pub mod photo_comment;
 pub mod photo_cursor;
+pub mod photo_hooks;
+pub mod photo_person;
 pub mod photo_tag;
+pub mod photo_tag_suggestion;
+pub mod pipeline_job;
+#[cfg(feature = "postgres")]
+pub mod schema_migration;
+pub mod refresh_session;
 pub mod setting;
 pub mod storage_location;
 pub mod tag;
+pub mod tag_rule;
 pub mod timeline;
+pub mod two_factor;
 pub mod user;
 pub mod user_settings;
 pub mod uuid_id;
 
 pub fn register_entities(builder: &mut AppBuilder) -> &mut AppBuilder {
     builder.use_entity_with_operations::<StorageLocation>(&EntityOperation::all());
-    builder.use_entity_with_operations_and_policy::<User>(
-        &[EntityOperation::Get, EntityOperation::List],
-        Policy::Authenticated,
-    );
     builder.use_entity_with_operations_and_policy::<Client>(
         &[EntityOperation::Get, EntityOperation::List],
         Policy::Authenticated,
@@ -75,27 +95,25 @@ pub fn register_entities(builder: &mut AppBuilder) -> &mut AppBuilder {
         &[EntityOperation::Get, EntityOperation::List],
         Policy::Authenticated,
     );
-    builder.use_entity_with_operations::<UserSettings>(&[EntityOperation::Get, EntityOperation::Update]);
-    builder.use_entity_with_operations::<Photo>(&[
-        EntityOperation::List,
-        EntityOperation::Get,
-        EntityOperation::Update,
-        EntityOperation::Delete,
-    ]);
+    builder.use_entity_with_hooks_and_policy(
+        PhotoHooks::new(),
+        &[EntityOperation::List, EntityOperation::Get],
+        Policy::Authenticated,
+    );
     builder.use_entity_with_hooks_and_policy(
         AlbumHooks::new(),
         &[EntityOperation::List, EntityOperation::Get, EntityOperation::Create, EntityOperation::Update],
         Policy::Authenticated,
     );
+    // Raw exif carries exact GPS; everyone else reads it through /api/photos/metadata, which rounds it.
     builder.use_entity_with_hooks_and_policy(
-        AlbumHooks::new(),
-        &[EntityOperation::Delete],
+        EnsureUuidIdHooks::<ExifModel>::new(),
+        &[EntityOperation::Get],
         Policy::InRole("admin".to_string()),
     );
-    builder.use_entity_with_hooks(EnsureUuidIdHooks::<ExifModel>::new(), &[EntityOperation::Get]);
     builder.use_entity_with_hooks(
         EnsureUuidIdHooks::<PhotoComment>::new(),
-        &[EntityOperation::List, EntityOperation::Get, EntityOperation::Create],
+        &[EntityOperation::List, EntityOperation::Get],
     );
     builder.use_entity_with_hooks(
         EnsureUuidIdHooks::<AlbumComment>::new(),
@@ -141,6 +159,10 @@ pub fn register_entities(builder: &mut AppBuilder) -> &mut AppBuilder {
             let provider = MemoryRepository::<PhotoComment>::new();
             Repository::<PhotoComment>::new(Box::new(provider))
         });
+        builder.register_singleton(|_| {
+            let provider = MemoryRepository::<AlbumComment>::new();
+            Repository::<AlbumComment>::new(Box::new(provider))
+        });
         builder.register_singleton(|_| {
             let provider = MemoryRepository::<AlbumPhoto>::new();
             Repository::<AlbumPhoto>::new(Box::new(provider))
@@ -153,6 +175,34 @@ pub fn register_entities(builder: &mut AppBuilder) -> &mut AppBuilder {
             let provider = MemoryRepository::<TimelineDay>::new();
             Repository::<TimelineDay>::new(Box::new(provider))
         });
+        builder.register_singleton(|_| {
+            let provider = MemoryRepository::<Tag>::new();
+            Repository::<Tag>::new(Box::new(provider))
+        });
+        builder.register_singleton(|_| {
+            let provider = MemoryRepository::<TagRule>::new();
+            Repository::<TagRule>::new(Box::new(provider))
+        });
+        builder.register_singleton(|_| {
+            let provider = MemoryRepository::<DigestRun>::new();
+            Repository::<DigestRun>::new(Box::new(provider))
+        });
+        builder.register_singleton(|_| {
+            let provider = MemoryRepository::<Notification>::new();
+            Repository::<Notification>::new(Box::new(provider))
+        });
+        builder.register_singleton(|_| {
+            let provider = MemoryRepository::<AuditLog>::new();
+            Repository::<AuditLog>::new(Box::new(provider))
+        });
+        builder.register_singleton(|_| {
+            let provider = MemoryRepository::<RefreshSession>::new();
+            Repository::<RefreshSession>::new(Box::new(provider))
+        });
+        builder.register_singleton(|_| {
+            let provider = MemoryRepository::<TwoFactor>::new();
+            Repository::<TwoFactor>::new(Box::new(provider))
+        });
     }
 
     #[cfg(feature = "postgres")]
@@ -230,6 +280,46 @@ pub fn register_entities(builder: &mut AppBuilder) -> &mut AppBuilder {
             let provider = PostgresProvider::<TimelineDay>::new((*pool).clone());
             Repository::<TimelineDay>::new(Box::new(provider))
         });
+        builder.register_singleton(|p| {
+            let pool = p.get::<PgPool>();
+            let provider = PostgresProvider::<Tag>::new((*pool).clone());
+            Repository::<Tag>::new(Box::new(provider))
+        });
+        builder.register_singleton(|p| {
+            let pool = p.get::<PgPool>();
+            let provider = PostgresProvider::<TagRule>::new((*pool).clone());
+            Repository::<TagRule>::new(Box::new(provider))
+        });
+        builder.register_singleton(|p| {
+            let pool = p.get::<PgPool>();
+            let provider = PostgresProvider::<DigestRun>::new((*pool).clone());
+            Repository::<DigestRun>::new(Box::new(provider))
+        });
+        builder.register_singleton(|p| {
+            let pool = p.get::<PgPool>();
+            let provider = PostgresProvider::<Notification>::new((*pool).clone());
+            Repository::<Notification>::new(Box::new(provider))
+        });
+        builder.register_singleton(|p| {
+            let pool = p.get::<PgPool>();
+            let provider = PostgresProvider::<AuditLog>::new((*pool).clone());
+            Repository::<AuditLog>::new(Box::new(provider))
+        });
+        builder.register_singleton(|p| {
+            let pool = p.get::<PgPool>();
+            let provider = PostgresProvider::<PipelineJob>::new((*pool).clone());
+            Repository::<PipelineJob>::new(Box::new(provider))
+        });
+        builder.register_singleton(|p| {
+            let pool = p.get::<PgPool>();
+            let provider = PostgresProvider::<RefreshSession>::new((*pool).clone());
+            Repository::<RefreshSession>::new(Box::new(provider))
+        });
+        builder.register_singleton(|p| {
+            let pool = p.get::<PgPool>();
+            let provider = PostgresProvider::<TwoFactor>::new((*pool).clone());
+            Repository::<TwoFactor>::new(Box::new(provider))
+        });
     }
 
     builder
@@ -257,6 +347,13 @@ pub async fn migrate_entities(app: &Application) -> Result<()> {
         migrate_entity::<AlbumPhoto>(app).await?;
         migrate_entity::<Setting>(app).await?;
         migrate_entity::<TimelineDay>(app).await?;
+        migrate_entity::<AuditLog>(app).await?;
+        migrate_entity::<PipelineJob>(app).await?;
+        migrate_entity::<TagRule>(app).await?;
+        migrate_entity::<DigestRun>(app).await?;
+        migrate_entity::<Notification>(app).await?;
+        migrate_entity::<RefreshSession>(app).await?;
+        migrate_entity::<TwoFactor>(app).await?;
 
         let pool =
             app.services().resolve::<sqlx::PgPool>().ok_or_else(|| anyhow!("PgPool not found in service provider"))?;
@@ -282,111 +379,115 @@ where
 
 #[cfg(feature = "postgres")]
 pub async fn ensure_supporting_schema(pool: &sqlx::PgPool) -> Result<()> {
-    let sqls = [
-        "CREATE EXTENSION IF NOT EXISTS \"pgcrypto\"",
-        "ALTER TABLE clientstorages ADD COLUMN IF NOT EXISTS id UUID",
-        r#"DO $$ BEGIN
-                IF EXISTS (
-                    SELECT 1
-                    FROM information_schema.columns
-                    WHERE table_name = 'clientstorages'
-                    AND column_name = 'id'
-                    AND data_type <> 'uuid'
-                ) THEN
-                    EXECUTE 'ALTER TABLE clientstorages ALTER COLUMN id TYPE UUID USING gen_random_uuid()';
-                END IF;
-            END $$;"#,
-        "ALTER TABLE clientstorages ALTER COLUMN id SET DEFAULT gen_random_uuid()",
-        "UPDATE clientstorages SET id = gen_random_uuid() WHERE id IS NULL",
-        "ALTER TABLE clientstorages DROP CONSTRAINT IF EXISTS clientstorages_pkey",
-        "ALTER TABLE clientstorages ADD CONSTRAINT clientstorages_pkey PRIMARY KEY (id)",
-        "CREATE UNIQUE INDEX IF NOT EXISTS ux_clientstorages_client_storage ON clientstorages (client_id, storage_id)",
-        "ALTER TABLE storages ADD COLUMN IF NOT EXISTS readonly BOOLEAN NOT NULL DEFAULT false",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS year INTEGER",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS month_day TEXT",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS artist TEXT",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS make TEXT",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS model TEXT",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS lens_make TEXT",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS lens_model TEXT",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS exposure_time TEXT",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS iso INTEGER",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS aperture REAL",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS focal_length REAL",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS label TEXT",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS rating INTEGER",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS flagged INTEGER",
-        "ALTER TABLE photos ADD COLUMN IF NOT EXISTS orientation INTEGER",
-        "UPDATE storages SET readonly = true WHERE id = '00000000-0000-0000-0000-000000000001'::uuid",
-        r#"UPDATE photos p
-           SET
-               year = COALESCE(p.year, EXTRACT(YEAR FROM COALESCE(p.date_taken, p.created_at, p.sort_date) AT TIME ZONE 'UTC')::int),
-               month_day = COALESCE(p.month_day, to_char(COALESCE(p.date_taken, p.created_at, p.sort_date) AT TIME ZONE 'UTC', 'MM-DD')),
-               sort_date = COALESCE(p.sort_date, COALESCE(p.date_taken, p.created_at, NOW())),
-               day_date = COALESCE(p.day_date, (COALESCE(p.date_taken, p.created_at, p.sort_date, NOW()) AT TIME ZONE 'UTC')::date)"#,
-        r#"UPDATE photos p
-           SET
-               artist = COALESCE(p.artist, e.artist),
-               make = COALESCE(p.make, e.make),
-               model = COALESCE(p.model, e.model),
-               lens_make = COALESCE(p.lens_make, e.lens_make),
-               lens_model = COALESCE(p.lens_model, e.lens_model),
-               exposure_time = COALESCE(p.exposure_time, e.exposure_time),
-               iso = COALESCE(p.iso, e.iso, e.photographic_sensitivity),
-               aperture = COALESCE(p.aperture, e.f_number, e.aperture_value),
-               focal_length = COALESCE(p.focal_length, e.focal_length),
-               label = COALESCE(p.label, e.label),
-               rating = COALESCE(p.rating, e.rating),
-               flagged = COALESCE(p.flagged, e.flagged),
-               orientation = COALESCE(p.orientation, e.orientation),
-               width = COALESCE(
-                   p.width,
-                   CASE
-                       WHEN e.orientation IN (5, 6, 7, 8) THEN COALESCE(e.pixel_y_dimension, e.image_length)
-                       ELSE COALESCE(e.pixel_x_dimension, e.image_width)
-                   END
-               ),
-               height = COALESCE(
-                   p.height,
-                   CASE
-                       WHEN e.orientation IN (5, 6, 7, 8) THEN COALESCE(e.pixel_x_dimension, e.image_width)
-                       ELSE COALESCE(e.pixel_y_dimension, e.image_length)
-                   END
-               ),
-               metadata_extracted = COALESCE(p.metadata_extracted, true)
-           FROM exifs e
-           WHERE e.image_id = p.id"#,
-        "CREATE INDEX IF NOT EXISTS idx_photos_day_taken ON photos (day_date DESC, date_taken DESC)",
-        "CREATE INDEX IF NOT EXISTS idx_photos_year ON photos (year DESC)",
-        "CREATE INDEX IF NOT EXISTS idx_timeline_days_day_date_year ON timeline_days (day_date, year)",
-        "CREATE INDEX IF NOT EXISTS idx_photos_hash ON photos(hash)",
-        "CREATE INDEX IF NOT EXISTS idx_photos_storage ON photos(storage_id)",
-        "CREATE INDEX IF NOT EXISTS idx_exifs_image_id ON exifs (image_id)",
-        "CREATE INDEX IF NOT EXISTS idx_photo_comments_photo_id ON photo_comments (photo_id)",
-        "CREATE INDEX IF NOT EXISTS idx_album_comments_album_id ON album_comments (album_id)",
-        "CREATE INDEX IF NOT EXISTS idx_album_photos_album_id ON album_photos (album_id)",
-        "CREATE INDEX IF NOT EXISTS idx_album_photos_photo_id ON album_photos (photo_id)",
-        "CREATE UNIQUE INDEX IF NOT EXISTS ux_album_photos_album_photo ON album_photos (album_id, photo_id)",
-        "CREATE TABLE IF NOT EXISTS tags (id UUID PRIMARY KEY DEFAULT gen_random_uuid(), name TEXT NOT NULL, name_norm TEXT NOT NULL, visibility SMALLINT NOT NULL DEFAULT 0, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), CONSTRAINT ck_tags_visibility CHECK (visibility IN (0, 1)))",
-        "CREATE UNIQUE INDEX IF NOT EXISTS ux_tags_name_norm ON tags (name_norm)",
-        "CREATE INDEX IF NOT EXISTS idx_tags_name ON tags (name)",
-        "CREATE TABLE IF NOT EXISTS photo_tags (photo_id UUID NOT NULL REFERENCES photos (id) ON DELETE CASCADE, tag_id UUID NOT NULL REFERENCES tags (id) ON DELETE CASCADE, PRIMARY KEY (photo_id, tag_id))",
-        "CREATE TABLE IF NOT EXISTS album_tags (album_id UUID NOT NULL REFERENCES albums (id) ON DELETE CASCADE, tag_id UUID NOT NULL REFERENCES tags (id) ON DELETE CASCADE, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), created_by_user_id UUID NULL REFERENCES users (id) ON DELETE SET NULL, PRIMARY KEY (album_id, tag_id))",
-        "CREATE INDEX IF NOT EXISTS idx_photo_tags_photo ON photo_tags (photo_id)",
-        "CREATE INDEX IF NOT EXISTS idx_photo_tags_tag ON photo_tags (tag_id)",
-        "CREATE INDEX IF NOT EXISTS idx_album_tags_tag_id_album_id ON album_tags (tag_id, album_id)",
-        "CREATE OR REPLACE VIEW photos_public_visible AS SELECT p.* FROM photos p WHERE NOT EXISTS (SELECT 1 FROM photo_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.photo_id = p.id AND t.visibility = 1)",
-    ];
-
-    for sql in sqls {
-        sqlx::query(sql).execute(pool).await.map_err(|err| anyhow!("Failed to execute SQL '{}': {}", sql, err))?;
+    let applied = SchemaMigrator::builtin().run(pool).await?;
+    if !applied.is_empty() {
+        log::info!("Applied schema migrations {:?}", applied);
     }
 
+    import_legacy_storage_locations(pool).await?;
+    ensure_unique_photo_hash_per_storage(pool).await?;
     ensure_default_storage(pool).await?;
 
     Ok(())
 }
 
+// Data import rather than schema: it checks its own preconditions, so it runs on every boot.
+#[cfg(feature = "postgres")]
+async fn import_legacy_storage_locations(pool: &sqlx::PgPool) -> Result<()> {
+    let sql = r#"DO $$
+    DECLARE
+        settings_table TEXT;
+        legacy_locations TEXT;
+    BEGIN
+        SELECT c.table_name INTO settings_table
+        FROM information_schema.columns c
+        WHERE c.table_schema = current_schema()
+        AND c.column_name = 'value_type'
+        AND EXISTS (
+            SELECT 1
+            FROM information_schema.columns t
+            WHERE t.table_schema = c.table_schema
+            AND t.table_name = c.table_name
+            AND t.column_name = 'group_name'
+        )
+        LIMIT 1;
+
+        IF settings_table IS NULL OR to_regclass('storages') IS NULL THEN
+            RETURN;
+        END IF;
+
+        EXECUTE format('SELECT value FROM %I WHERE key = $1', settings_table)
+        INTO legacy_locations
+        USING 'storage.locations';
+
+        IF legacy_locations IS NULL THEN
+            RETURN;
+        END IF;
+
+        BEGIN
+            INSERT INTO storages (id, label, path, is_default, readonly, created_at, category_template)
+            SELECT
+                CASE
+                    WHEN trim(item->>'id') ~* '^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$'
+                    THEN trim(item->>'id')::uuid
+                    ELSE gen_random_uuid()
+                END,
+                COALESCE(NULLIF(trim(item->>'label'), ''), trim(item->>'path')),
+                trim(item->>'path'),
+                COALESCE((item->>'isDefault')::boolean, false)
+                    AND NOT EXISTS (SELECT 1 FROM storages s WHERE s.is_default),
+                COALESCE((item->>'isReadonly')::boolean, false),
+                COALESCE(NULLIF(item->>'createdAt', ''), to_char(now() AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"+00:00"')),
+                COALESCE(NULLIF(trim(item->>'categoryTemplate'), ''), '{year}/{date:%Y-%m-%d}/{fileName}')
+            FROM jsonb_array_elements(legacy_locations::jsonb) AS item
+            WHERE COALESCE(trim(item->>'path'), '') <> ''
+            AND NOT EXISTS (SELECT 1 FROM storages s WHERE s.path = trim(item->>'path'))
+            ON CONFLICT (id) DO NOTHING;
+
+            EXECUTE format('DELETE FROM %I WHERE key = $1', settings_table) USING 'storage.locations';
+        EXCEPTION WHEN others THEN
+            RAISE WARNING 'Skipping legacy storage.locations import: %', SQLERRM;
+        END;
+    END $$;"#;
+
+    sqlx::query(sql).execute(pool).await.map_err(|err| anyhow!("Failed to import legacy storage locations: {}", err))?;
+    Ok(())
+}
+
+// Existing libraries may already hold duplicates within a storage; the index is created once they are cleaned up.
+#[cfg(feature = "postgres")]
+async fn ensure_unique_photo_hash_per_storage(pool: &sqlx::PgPool) -> Result<()> {
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass('ux_photos_storage_hash') IS NOT NULL")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| anyhow!("Failed to check photo hash index: {}", e))?;
+    if exists {
+        return Ok(());
+    }
+
+    let duplicates: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM (SELECT 1 FROM photos WHERE hash IS NOT NULL GROUP BY storage_id, hash HAVING COUNT(*) > 1) d",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| anyhow!("Failed to count duplicate photo hashes: {}", e))?;
+    if duplicates > 0 {
+        log::warn!(
+            "Skipping unique (storage_id, hash) index: {} hash(es) appear more than once within a storage",
+            duplicates
+        );
+        return Ok(());
+    }
+
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS ux_photos_storage_hash ON photos (storage_id, hash) WHERE hash IS NOT NULL",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| anyhow!("Failed to create unique photo hash index: {}", e))?;
+    Ok(())
+}
+
 #[cfg(feature = "postgres")]
 fn default_storage_root() -> PathBuf {
     if cfg!(windows) {
@@ -0,0 +1,116 @@
+use crate::prelude::*;
+
+#[cfg(feature = "postgres")]
+use {
+    nimble_web::data::postgres::{PostgresEntity, value_builder::PostgresValueBuilder},
+    nimble_web::data::query::Value,
+    nimble_web::data::schema::{ColumnDef, ColumnType},
+    sqlx::postgres::PgRow,
+    sqlx::{FromRow, Row},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub id: Uuid,
+    pub recipient_id: Uuid,
+    pub kind: String,
+    pub payload: JsonValue,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub const KIND_PHOTO_COMMENT: &'static str = "photoComment";
+    pub const KIND_ALBUM_COMMENT: &'static str = "albumComment";
+    pub const KIND_COMMENT_HIDDEN: &'static str = "commentHidden";
+
+    pub fn new(recipient_id: Uuid, kind: &str, payload: JsonValue) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            recipient_id,
+            kind: kind.to_string(),
+            payload,
+            read_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn is_read(&self) -> bool {
+        self.read_at.is_some()
+    }
+}
+
+impl Entity for Notification {
+    type Id = Uuid;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn name() -> &'static str {
+        "notification"
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'r> FromRow<'r, PgRow> for Notification {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let raw_payload: String = row.try_get("payload")?;
+        let payload = serde_json::from_str::<JsonValue>(&raw_payload).unwrap_or(JsonValue::Null);
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            recipient_id: row.try_get("recipient_id")?,
+            kind: row.try_get("kind")?,
+            payload,
+            read_at: row.try_get("read_at")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresEntity for Notification {
+    fn id_column() -> &'static str {
+        "id"
+    }
+
+    fn id_value(id: &Self::Id) -> Value {
+        Value::Uuid(*id)
+    }
+
+    fn insert_columns() -> &'static [&'static str] {
+        &["id", "recipient_id", "kind", "payload", "read_at", "created_at"]
+    }
+
+    fn insert_values(&self) -> Vec<Value> {
+        vec![
+            Value::Uuid(self.id),
+            Value::Uuid(self.recipient_id),
+            Value::String(self.kind.clone()),
+            Value::String(self.payload.to_string()),
+            PostgresValueBuilder::optional_datetime(&self.read_at),
+            Value::DateTime(self.created_at),
+        ]
+    }
+
+    fn update_columns() -> &'static [&'static str] {
+        &["read_at"]
+    }
+
+    fn update_values(&self) -> Vec<Value> {
+        vec![PostgresValueBuilder::optional_datetime(&self.read_at)]
+    }
+
+    fn table_columns() -> Vec<ColumnDef> {
+        vec![
+            ColumnDef::new("id", ColumnType::Uuid).primary_key().default("gen_random_uuid()"),
+            ColumnDef::new("recipient_id", ColumnType::Uuid).not_null(),
+            ColumnDef::new("kind", ColumnType::Text).not_null(),
+            ColumnDef::new("payload", ColumnType::Text).not_null().default("'{}'"),
+            ColumnDef::new("read_at", ColumnType::Timestamp),
+            ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
+        ]
+    }
+}
@@ -20,6 +20,10 @@ pub struct PhotoViewModel {
     pub name: String,
     pub width: Option<u32>,
     pub height: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blurhash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_count: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +75,17 @@ pub struct Photo {
     pub day_date: NaiveDate,
     #[serde(alias = "sort_date")]
     pub sort_date: DateTime<Utc>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub caption: Option<String>,
+    #[serde(default, alias = "stack_id")]
+    pub stack_id: Option<Uuid>,
+    #[serde(default)]
+    pub blurhash: Option<String>,
+    // Filled in by listing queries for stack representatives; not a column.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_count: Option<i64>,
 }
 
 impl Default for Photo {
@@ -109,6 +124,38 @@ impl Default for Photo {
             orientation: None,
             day_date: now.date_naive(),
             sort_date: now,
+            title: None,
+            caption: None,
+            stack_id: None,
+            blurhash: None,
+            stack_count: None,
+        }
+    }
+}
+
+impl Photo {
+    pub fn timeline_basis(&self) -> DateTime<Utc> {
+        self.date_taken.or(self.created_at).unwrap_or(self.sort_date)
+    }
+
+    pub fn refresh_timeline_dates(&mut self) {
+        let basis = self.timeline_basis();
+        self.sort_date = basis;
+        self.day_date = basis.date_naive();
+        self.year = Some(basis.year());
+        self.month_day = Some(basis.format("%m-%d").to_string());
+    }
+
+    // Identical files may live in several storages, so a hash alone does not identify one photo.
+    pub fn select_by_hash(candidates: Vec<Photo>, storage_id: Option<Uuid>) -> Option<Photo> {
+        if let Some(storage_id) = storage_id {
+            return candidates.into_iter().find(|photo| photo.storage_id == storage_id);
+        }
+
+        let mut candidates = candidates;
+        match candidates.iter().position(|photo| Path::new(&photo.path).exists()) {
+            Some(index) => Some(candidates.swap_remove(index)),
+            None => candidates.into_iter().next(),
         }
     }
 }
@@ -161,6 +208,11 @@ impl<'r> FromRow<'r, PgRow> for Photo {
             orientation: PostgresExtensions::optional_i32_as_u16(row, "orientation")?,
             day_date: row.try_get("day_date")?,
             sort_date: row.try_get("sort_date")?,
+            title: row.try_get("title")?,
+            caption: row.try_get("caption")?,
+            stack_id: row.try_get("stack_id")?,
+            blurhash: row.try_get("blurhash")?,
+            stack_count: None,
         })
     }
 }
@@ -209,6 +261,10 @@ impl PostgresEntity for Photo {
             "orientation",
             "day_date",
             "sort_date",
+            "title",
+            "caption",
+            "stack_id",
+            "blurhash",
         ]
     }
 
@@ -246,6 +302,10 @@ impl PostgresEntity for Photo {
             PostgresValueBuilder::optional_u16(self.orientation),
             Value::Date(self.day_date),
             Value::DateTime(self.sort_date.clone()),
+            PostgresValueBuilder::optional_string(&self.title),
+            PostgresValueBuilder::optional_string(&self.caption),
+            PostgresValueBuilder::optional_uuid(self.stack_id),
+            PostgresValueBuilder::optional_string(&self.blurhash),
         ]
     }
 
@@ -282,6 +342,10 @@ impl PostgresEntity for Photo {
             "orientation",
             "day_date",
             "sort_date",
+            "title",
+            "caption",
+            "stack_id",
+            "blurhash",
         ]
     }
 
@@ -318,6 +382,10 @@ impl PostgresEntity for Photo {
             PostgresValueBuilder::optional_u16(self.orientation),
             Value::Date(self.day_date),
             Value::DateTime(self.sort_date.clone()),
+            PostgresValueBuilder::optional_string(&self.title),
+            PostgresValueBuilder::optional_string(&self.caption),
+            PostgresValueBuilder::optional_uuid(self.stack_id),
+            PostgresValueBuilder::optional_string(&self.blurhash),
         ]
     }
 
@@ -355,6 +423,10 @@ impl PostgresEntity for Photo {
             ColumnDef::new("orientation", ColumnType::Integer),
             ColumnDef::new("day_date", ColumnType::Custom("DATE")).not_null(),
             ColumnDef::new("sort_date", ColumnType::Timestamp).not_null(),
+            ColumnDef::new("title", ColumnType::Text),
+            ColumnDef::new("caption", ColumnType::Text),
+            ColumnDef::new("stack_id", ColumnType::Uuid),
+            ColumnDef::new("blurhash", ColumnType::Text),
         ]
     }
 }
@@ -63,6 +63,12 @@ pub struct BrowseOptions {
     pub sort_direction: SortDirection,
     #[serde(default = "BrowseOptions::default_date_format")]
     pub date_format: String,
+    #[serde(default)]
+    pub sort: BrowseSort,
+    #[serde(default)]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub search: Option<String>,
 }
 
 impl Default for BrowseOptions {
@@ -71,11 +77,16 @@ impl Default for BrowseOptions {
             dimensions: Self::default_dimensions(),
             sort_direction: Self::default_sort_direction(),
             date_format: Self::default_date_format(),
+            sort: BrowseSort::default(),
+            filter: None,
+            search: None,
         }
     }
 }
 
 impl BrowseOptions {
+    pub const INVALID_FILTER: &'static str = "invalid browse filter";
+
     fn default_dimensions() -> Vec<BrowseDimension> {
         vec![BrowseDimension::Year, BrowseDimension::Date]
     }
@@ -87,6 +98,61 @@ impl BrowseOptions {
     fn default_date_format() -> String {
         "yyyy-MM-dd".to_string()
     }
+
+    pub fn with_request(&self, request: &BrowseRequest) -> Self {
+        let mut options = self.clone();
+        if let Some(sort) = request.sort {
+            options.sort = sort;
+        }
+        if let Some(direction) = request.sort_direction {
+            options.sort_direction = direction;
+        }
+        if request.filter.is_some() {
+            options.filter = request.filter.clone();
+        }
+        if request.search.is_some() {
+            options.search = request.search.clone();
+        }
+        options
+    }
+
+    pub fn name_patterns(&self) -> anyhow::Result<Vec<String>> {
+        let Some(filter) = self.filter.as_deref().map(str::trim).filter(|value| !value.is_empty()) else {
+            return Ok(Vec::new());
+        };
+
+        let is_glob = filter.contains('*') || filter.contains('?');
+        let mut patterns = Vec::new();
+        for token in filter.split(',').map(str::trim).filter(|token| !token.is_empty()) {
+            if is_glob {
+                patterns.push(Self::glob_to_like(token));
+                continue;
+            }
+
+            let extension = token.trim_start_matches('.');
+            if extension.is_empty() || !extension.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+                anyhow::bail!(Self::INVALID_FILTER);
+            }
+            patterns.push(format!("%.{}", extension));
+        }
+        Ok(patterns)
+    }
+
+    pub fn search_pattern(&self) -> Option<String> {
+        self.search
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| format!("%{}%", Self::escape_like(value)))
+    }
+
+    fn glob_to_like(glob: &str) -> String {
+        Self::escape_like(glob).replace('*', "%").replace('?', "_")
+    }
+
+    pub fn escape_like(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -99,12 +165,44 @@ pub struct StorageFolder {
     pub has_children: bool,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum SortDirection {
     Asc,
     Desc,
 }
 
+impl SortDirection {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "asc" => Some(Self::Asc),
+            "desc" => Some(Self::Desc),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowseSort {
+    #[default]
+    Date,
+    Name,
+    Size,
+    Modified,
+}
+
+impl BrowseSort {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "date" => Some(Self::Date),
+            "name" => Some(Self::Name),
+            "size" => Some(Self::Size),
+            "modified" => Some(Self::Modified),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum BrowseDimension {
     Year,
@@ -114,39 +212,20 @@ pub enum BrowseDimension {
     Rating,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BrowseRequest {
     pub path: Option<String>,
     pub page_size: Option<i64>,
     pub cursor: Option<String>,
+    pub filter: Option<String>,
+    pub search: Option<String>,
+    pub sort: Option<BrowseSort>,
+    pub sort_direction: Option<SortDirection>,
 }
 
 impl BrowseRequest {
-    pub fn path_segments(&self) -> anyhow::Result<Vec<String>> {
-        let decoded_path = self
-            .path
-            .as_deref()
-            .map(urlencoding::decode)
-            .transpose()?
-            .map(|value| value.into_owned())
-            .unwrap_or_default();
-
-        let segments: Vec<String> = decoded_path
-            .as_str()
-            .trim()
-            .trim_matches('/')
-            .split('/')
-            .filter_map(|s| {
-                let trimmed = s.trim();
-                if trimmed.is_empty() { None } else { Some(trimmed.to_string()) }
-            })
-            .collect();
-
-        if segments.iter().any(|s| s.contains("..")) {
-            anyhow::bail!("Invalid path segment");
-        }
-
-        Ok(segments)
+    pub fn path_segments(&self) -> Result<Vec<String>, BrowsePathError> {
+        BrowsePath::segments(self.path.as_deref().unwrap_or_default())
     }
 }
@@ -15,7 +15,7 @@ use {
 pub struct PhotoComment {
     pub id: Uuid,
     pub photo_id: Uuid,
-    pub user_id: Uuid,
+    pub user_id: Option<Uuid>,
     pub user_display_name: Option<String>,
     pub body: Option<String>,
     pub created_at: Option<DateTime<Utc>>,
@@ -23,7 +23,14 @@ pub struct PhotoComment {
 
 impl PhotoComment {
     pub fn new(photo_id: Uuid, user_id: Uuid, user_display_name: Option<String>, body: Option<String>) -> Self {
-        Self { id: Uuid::new_v4(), photo_id, user_id, user_display_name, body, created_at: Some(Utc::now()) }
+        Self {
+            id: Uuid::new_v4(),
+            photo_id,
+            user_id: Some(user_id),
+            user_display_name,
+            body,
+            created_at: Some(Utc::now()),
+        }
     }
 }
 
@@ -32,7 +39,7 @@ impl Default for PhotoComment {
         Self {
             id: Uuid::nil(),
             photo_id: Uuid::nil(),
-            user_id: Uuid::nil(),
+            user_id: None,
             user_display_name: None,
             body: None,
             created_at: None,
@@ -94,7 +101,7 @@ impl PostgresEntity for PhotoComment {
         vec![
             nimble_web::data::query::Value::Uuid(self.id),
             nimble_web::data::query::Value::Uuid(self.photo_id),
-            nimble_web::data::query::Value::Uuid(self.user_id),
+            PostgresValueBuilder::optional_uuid(self.user_id),
             PostgresValueBuilder::optional_string(&self.user_display_name),
             PostgresValueBuilder::optional_string(&self.body),
             PostgresValueBuilder::optional_datetime(&self.created_at),
@@ -102,11 +109,12 @@ impl PostgresEntity for PhotoComment {
     }
 
     fn update_columns() -> &'static [&'static str] {
-        &["user_display_name", "body"]
+        &["user_id", "user_display_name", "body"]
     }
 
     fn update_values(&self) -> Vec<nimble_web::data::query::Value> {
         vec![
+            PostgresValueBuilder::optional_uuid(self.user_id),
             PostgresValueBuilder::optional_string(&self.user_display_name),
             PostgresValueBuilder::optional_string(&self.body),
         ]
@@ -116,7 +124,7 @@ impl PostgresEntity for PhotoComment {
         vec![
             ColumnDef::new("id", ColumnType::Uuid).primary_key(),
             ColumnDef::new("photo_id", ColumnType::Uuid).not_null(),
-            ColumnDef::new("user_id", ColumnType::Uuid).not_null(),
+            ColumnDef::new("user_id", ColumnType::Uuid),
             ColumnDef::new("user_display_name", ColumnType::Text),
             ColumnDef::new("body", ColumnType::Text).not_null(),
             ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
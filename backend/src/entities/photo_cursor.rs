@@ -1,22 +1,82 @@
+use crate::entities::photo_browse::{BrowseSort, SortDirection};
 use crate::prelude::*;
+use crate::services::cursor_signer::CursorSigner;
 use base64::{Engine as _, engine::general_purpose};
+use chrono::SecondsFormat;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidCursor;
+
+impl Display for InvalidCursor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid cursor")
+    }
+}
+
+impl std::error::Error for InvalidCursor {}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PhotoCursor {
     #[serde(alias = "date_taken", alias = "dateTaken")]
     pub sort_date: DateTime<Utc>,
     pub id: Uuid,
+    #[serde(default)]
+    pub sort: BrowseSort,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<SortDirection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
 }
 
 impl PhotoCursor {
-    pub fn encode(&self) -> String {
-        let json = serde_json::to_string(self).unwrap();
-        general_purpose::STANDARD.encode(json)
+    const SEPARATOR: char = '.';
+
+    pub fn new(sort_date: DateTime<Utc>, id: Uuid) -> Self {
+        Self { sort_date, id, sort: BrowseSort::Date, direction: None, key: None, scope: None }
+    }
+
+    pub fn with_sort(mut self, sort: BrowseSort, direction: SortDirection, key: String) -> Self {
+        self.sort = sort;
+        self.direction = Some(direction);
+        self.key = Some(key);
+        self
+    }
+
+    pub fn with_scope(mut self, scope: String) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    pub fn matches(&self, sort: BrowseSort, direction: SortDirection) -> bool {
+        self.sort == sort && self.direction.is_none_or(|value| value == direction)
+    }
+
+    pub fn sort_key(&self) -> String {
+        self.key.clone().unwrap_or_else(|| self.sort_date.to_rfc3339_opts(SecondsFormat::Micros, true))
+    }
+
+    pub fn encode(&self, signer: &CursorSigner) -> String {
+        let json = serde_json::to_vec(self).unwrap();
+        let signature = signer.sign(&json);
+        format!(
+            "{}{}{}",
+            general_purpose::URL_SAFE_NO_PAD.encode(json),
+            Self::SEPARATOR,
+            general_purpose::URL_SAFE_NO_PAD.encode(signature)
+        )
     }
 
-    pub fn decode(encoded: &str) -> anyhow::Result<Self> {
-        let bytes = general_purpose::STANDARD.decode(encoded)?;
-        let cursor = serde_json::from_slice(&bytes)?;
-        Ok(cursor)
+    pub fn decode(encoded: &str, signer: &CursorSigner) -> Result<Self, InvalidCursor> {
+        let (payload, signature) = encoded.trim().split_once(Self::SEPARATOR).ok_or(InvalidCursor)?;
+        let json = general_purpose::URL_SAFE_NO_PAD.decode(payload).map_err(|_| InvalidCursor)?;
+        let signature = general_purpose::URL_SAFE_NO_PAD.decode(signature).map_err(|_| InvalidCursor)?;
+        if !signer.verify(&json, &signature) {
+            return Err(InvalidCursor);
+        }
+        serde_json::from_slice(&json).map_err(|_| InvalidCursor)
     }
 }
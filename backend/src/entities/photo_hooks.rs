@@ -0,0 +1,63 @@
+use super::photo::Photo;
+use crate::prelude::*;
+
+pub struct PhotoHooks;
+
+impl PhotoHooks {
+    pub const HASH_REQUIRED: &'static str = "Photo hash cannot be cleared";
+    pub const STORAGE_REQUIRED: &'static str = "Photo storage cannot be cleared";
+    pub const LOCATION_IMMUTABLE: &'static str = "Photo path, storage and hash cannot be changed";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn normalize(photo: &mut Photo) {
+        photo.format = photo
+            .format
+            .as_deref()
+            .map(|format| format.trim().trim_start_matches('.').to_ascii_lowercase())
+            .filter(|format| !format.is_empty());
+        photo.refresh_timeline_dates();
+    }
+
+    pub fn validate_update(current: &Photo, updated: &Photo) -> Result<(), &'static str> {
+        let has_hash = |photo: &Photo| photo.hash.as_deref().is_some_and(|hash| !hash.trim().is_empty());
+        if has_hash(current) && !has_hash(updated) {
+            return Err(Self::HASH_REQUIRED);
+        }
+        if !current.storage_id.is_nil() && updated.storage_id.is_nil() {
+            return Err(Self::STORAGE_REQUIRED);
+        }
+        // These point at the file on disk and its derivatives; only imports and path repair may move them.
+        if current.path != updated.path || current.storage_id != updated.storage_id || current.hash != updated.hash {
+            return Err(Self::LOCATION_IMMUTABLE);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EntityHooks<Photo> for PhotoHooks {
+    async fn before_insert(&self, _context: &RequestContext, entity: &mut Photo) -> HttpResult<()> {
+        Self::normalize(entity);
+        Ok(())
+    }
+
+    async fn before_update(&self, context: &RequestContext, entity: &mut Photo) -> HttpResult<()> {
+        let repository = context
+            .services()
+            .resolve::<Repository<Photo>>()
+            .ok_or_else(|| HttpError::new(500, "Photo repository is not registered"))?;
+        let current = repository
+            .get(&entity.id)
+            .await
+            .map_err(|err| HttpError::new(500, &format!("{:?}", err)))?
+            .ok_or_else(|| HttpError::new(404, "Photo not found"))?;
+
+        Self::validate_update(&current, entity).map_err(|message| HttpError::new(400, message))?;
+        Self::normalize(entity);
+        entity.updated_at = Some(Utc::now());
+        Ok(())
+    }
+}
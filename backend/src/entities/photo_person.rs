@@ -0,0 +1,41 @@
+use crate::prelude::*;
+
+#[cfg(feature = "postgres")]
+use sqlx::FromRow;
+
+#[cfg_attr(feature = "postgres", derive(FromRow))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoPerson {
+    pub id: Uuid,
+    #[serde(alias = "photo_id")]
+    pub photo_id: Uuid,
+    pub name: String,
+    #[serde(alias = "name_norm")]
+    pub name_norm: String,
+    #[serde(alias = "region_x")]
+    pub region_x: Option<f32>,
+    #[serde(alias = "region_y")]
+    pub region_y: Option<f32>,
+    #[serde(alias = "region_w")]
+    pub region_w: Option<f32>,
+    #[serde(alias = "region_h")]
+    pub region_h: Option<f32>,
+    pub source: String,
+    #[serde(alias = "created_at")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl PhotoPerson {
+    pub const SOURCE_MANUAL: &'static str = "manual";
+    pub const SOURCE_XMP: &'static str = "xmp";
+    pub const SOURCE_EMBEDDED: &'static str = "embedded";
+    pub const SOURCE_TAKEOUT: &'static str = "takeout";
+
+    pub fn region(&self) -> Option<PersonRegion> {
+        match (self.region_x, self.region_y, self.region_w, self.region_h) {
+            (Some(x), Some(y), Some(width), Some(height)) => Some(PersonRegion { x, y, width, height }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::prelude::*;
+
+#[cfg(feature = "postgres")]
+use sqlx::FromRow;
+
+#[cfg_attr(feature = "postgres", derive(FromRow))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoTagSuggestion {
+    pub id: Uuid,
+    #[serde(alias = "photo_id")]
+    pub photo_id: Uuid,
+    pub name: String,
+    #[serde(alias = "name_norm")]
+    pub name_norm: String,
+    pub confidence: f32,
+    pub analyzer: String,
+    #[serde(alias = "created_at")]
+    pub created_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,205 @@
+use crate::prelude::*;
+
+#[cfg(feature = "postgres")]
+use {
+    nimble_web::data::postgres::{PostgresEntity, value_builder::PostgresValueBuilder},
+    nimble_web::data::query::Value,
+    nimble_web::data::schema::{ColumnDef, ColumnType},
+    sqlx::FromRow,
+};
+
+#[cfg_attr(feature = "postgres", derive(FromRow))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineJob {
+    pub id: Uuid,
+    pub storage_id: Uuid,
+    pub relative_path: String,
+    pub file_name: String,
+    pub byte_size: i64,
+    pub content_type: Option<String>,
+    pub state: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub batch_id: Option<Uuid>,
+    #[serde(default)]
+    pub in_place: bool,
+    #[serde(default)]
+    pub import_format: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PipelineJob {
+    pub const STATE_QUEUED: &'static str = "queued";
+    pub const STATE_RUNNING: &'static str = "running";
+    pub const STATE_COMPLETED: &'static str = "completed";
+    pub const STATE_FAILED: &'static str = "failed";
+
+    pub fn queued(
+        storage_id: Uuid,
+        relative_path: &str,
+        file_name: &str,
+        byte_size: usize,
+        content_type: Option<String>,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            storage_id,
+            relative_path: relative_path.to_string(),
+            file_name: file_name.to_string(),
+            byte_size: byte_size as i64,
+            content_type,
+            state: Self::STATE_QUEUED.to_string(),
+            attempts: 0,
+            last_error: None,
+            batch_id: None,
+            in_place: false,
+            import_format: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn with_batch(mut self, batch_id: Option<Uuid>, in_place: bool) -> Self {
+        self.batch_id = batch_id;
+        self.in_place = in_place;
+        self
+    }
+
+    // Native imports leave the column empty so existing rows and new ones read the same.
+    pub fn with_import_format(mut self, format: ImportFormat) -> Self {
+        self.import_format = format.is_takeout().then(|| format.as_str().to_string());
+        self
+    }
+
+    pub fn format(&self) -> ImportFormat {
+        self.import_format.as_deref().and_then(ImportFormat::parse).unwrap_or_default()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.state == Self::STATE_QUEUED || self.state == Self::STATE_RUNNING
+    }
+
+    pub fn is_finished_before(&self, cutoff: DateTime<Utc>) -> bool {
+        !self.is_pending() && self.updated_at < cutoff
+    }
+
+    // Every start counts, so a job that keeps crashing the process is dropped instead of resumed forever.
+    pub fn attempts_exhausted(&self, max_attempts: i32) -> bool {
+        self.attempts >= max_attempts
+    }
+
+    pub fn mark_running(&mut self) {
+        self.state = Self::STATE_RUNNING.to_string();
+        self.attempts += 1;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn mark_completed(&mut self) {
+        self.state = Self::STATE_COMPLETED.to_string();
+        self.last_error = None;
+        self.updated_at = Utc::now();
+    }
+
+    pub fn mark_failed(&mut self, error: &str) {
+        self.state = Self::STATE_FAILED.to_string();
+        self.last_error = Some(error.to_string());
+        self.updated_at = Utc::now();
+    }
+}
+
+impl Entity for PipelineJob {
+    type Id = Uuid;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn name() -> &'static str {
+        "pipeline_job"
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresEntity for PipelineJob {
+    fn id_column() -> &'static str {
+        "id"
+    }
+
+    fn id_value(id: &Self::Id) -> Value {
+        Value::Uuid(*id)
+    }
+
+    fn insert_columns() -> &'static [&'static str] {
+        &[
+            "id",
+            "storage_id",
+            "relative_path",
+            "file_name",
+            "byte_size",
+            "content_type",
+            "state",
+            "attempts",
+            "last_error",
+            "batch_id",
+            "in_place",
+            "import_format",
+            "created_at",
+            "updated_at",
+        ]
+    }
+
+    fn insert_values(&self) -> Vec<Value> {
+        vec![
+            Value::Uuid(self.id),
+            Value::Uuid(self.storage_id),
+            Value::String(self.relative_path.clone()),
+            Value::String(self.file_name.clone()),
+            Value::Int(self.byte_size),
+            PostgresValueBuilder::optional_string(&self.content_type),
+            Value::String(self.state.clone()),
+            Value::Int(self.attempts as i64),
+            PostgresValueBuilder::optional_string(&self.last_error),
+            PostgresValueBuilder::optional_uuid(self.batch_id),
+            Value::Bool(self.in_place),
+            PostgresValueBuilder::optional_string(&self.import_format),
+            Value::DateTime(self.created_at),
+            Value::DateTime(self.updated_at),
+        ]
+    }
+
+    fn update_columns() -> &'static [&'static str] {
+        &["state", "attempts", "last_error", "updated_at"]
+    }
+
+    fn update_values(&self) -> Vec<Value> {
+        vec![
+            Value::String(self.state.clone()),
+            Value::Int(self.attempts as i64),
+            PostgresValueBuilder::optional_string(&self.last_error),
+            Value::DateTime(self.updated_at),
+        ]
+    }
+
+    fn table_columns() -> Vec<ColumnDef> {
+        vec![
+            ColumnDef::new("id", ColumnType::Uuid).primary_key().default("gen_random_uuid()"),
+            ColumnDef::new("storage_id", ColumnType::Uuid).not_null(),
+            ColumnDef::new("relative_path", ColumnType::Text).not_null(),
+            ColumnDef::new("file_name", ColumnType::Text).not_null(),
+            ColumnDef::new("byte_size", ColumnType::BigInt).not_null().default("0"),
+            ColumnDef::new("content_type", ColumnType::Text),
+            ColumnDef::new("state", ColumnType::Text).not_null().default("'queued'"),
+            ColumnDef::new("attempts", ColumnType::Integer).not_null().default("0"),
+            ColumnDef::new("last_error", ColumnType::Text),
+            ColumnDef::new("batch_id", ColumnType::Uuid),
+            ColumnDef::new("in_place", ColumnType::Boolean).not_null().default("false"),
+            ColumnDef::new("import_format", ColumnType::Text),
+            ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
+            ColumnDef::new("updated_at", ColumnType::Timestamp).not_null().default("NOW()"),
+        ]
+    }
+}
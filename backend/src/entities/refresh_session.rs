@@ -0,0 +1,122 @@
+use crate::prelude::*;
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "postgres")]
+use {
+    nimble_web::data::postgres::{PostgresEntity, value_builder::PostgresValueBuilder},
+    nimble_web::data::query::Value,
+    nimble_web::data::schema::{ColumnDef, ColumnType},
+    sqlx::FromRow,
+};
+
+// One row per device holding a refresh token. Only a hash of the token is stored; refreshing rotates
+// the hash in place, so the session keeps its id for as long as the device stays signed in.
+#[cfg_attr(feature = "postgres", derive(FromRow))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub token_hint: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: DateTime<Utc>,
+}
+
+impl RefreshSession {
+    const HINT_LENGTH: usize = 4;
+
+    pub fn new(user_id: Uuid, refresh_token: &str, client: &SessionClient) -> Self {
+        let now = Utc::now();
+        let mut session = Self {
+            id: Uuid::new_v4(),
+            user_id,
+            token_hash: String::new(),
+            token_hint: String::new(),
+            user_agent: client.user_agent.clone(),
+            ip_address: client.ip_address.clone(),
+            created_at: now,
+            last_used_at: now,
+        };
+        session.rotate(refresh_token);
+        session
+    }
+
+    pub fn hash_token(refresh_token: &str) -> String {
+        Sha256::digest(refresh_token.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    pub fn rotate(&mut self, refresh_token: &str) {
+        let chars = refresh_token.chars().collect::<Vec<_>>();
+        self.token_hash = Self::hash_token(refresh_token);
+        self.token_hint = chars[chars.len().saturating_sub(Self::HINT_LENGTH)..].iter().collect();
+        self.last_used_at = Utc::now();
+    }
+}
+
+impl Entity for RefreshSession {
+    type Id = Uuid;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn name() -> &'static str {
+        "refresh_session"
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresEntity for RefreshSession {
+    fn id_column() -> &'static str {
+        "id"
+    }
+
+    fn id_value(id: &Self::Id) -> Value {
+        Value::Uuid(*id)
+    }
+
+    fn insert_columns() -> &'static [&'static str] {
+        &["id", "user_id", "token_hash", "token_hint", "user_agent", "ip_address", "created_at", "last_used_at"]
+    }
+
+    fn insert_values(&self) -> Vec<Value> {
+        vec![
+            Value::Uuid(self.id),
+            Value::Uuid(self.user_id),
+            Value::String(self.token_hash.clone()),
+            Value::String(self.token_hint.clone()),
+            PostgresValueBuilder::optional_string(&self.user_agent),
+            PostgresValueBuilder::optional_string(&self.ip_address),
+            Value::DateTime(self.created_at),
+            Value::DateTime(self.last_used_at),
+        ]
+    }
+
+    fn update_columns() -> &'static [&'static str] {
+        &["token_hash", "token_hint", "last_used_at"]
+    }
+
+    fn update_values(&self) -> Vec<Value> {
+        vec![
+            Value::String(self.token_hash.clone()),
+            Value::String(self.token_hint.clone()),
+            Value::DateTime(self.last_used_at),
+        ]
+    }
+
+    fn table_columns() -> Vec<ColumnDef> {
+        vec![
+            ColumnDef::new("id", ColumnType::Uuid).primary_key().default("gen_random_uuid()"),
+            ColumnDef::new("user_id", ColumnType::Uuid).not_null(),
+            ColumnDef::new("token_hash", ColumnType::Text).not_null(),
+            ColumnDef::new("token_hint", ColumnType::Text).not_null(),
+            ColumnDef::new("user_agent", ColumnType::Text),
+            ColumnDef::new("ip_address", ColumnType::Text),
+            ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
+            ColumnDef::new("last_used_at", ColumnType::Timestamp).not_null().default("NOW()"),
+        ]
+    }
+}
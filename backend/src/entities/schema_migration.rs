@@ -0,0 +1,451 @@
+use anyhow::{Result, anyhow};
+use futures_util::future::BoxFuture;
+use sqlx::{PgConnection, PgPool};
+
+pub type MigrationFn = for<'c> fn(&'c mut PgConnection) -> BoxFuture<'c, Result<()>>;
+
+pub enum MigrationStep {
+    Sql(&'static [&'static str]),
+    Rust(MigrationFn),
+}
+
+pub struct Migration {
+    pub id: i64,
+    pub description: &'static str,
+    pub step: MigrationStep,
+}
+
+impl Migration {
+    pub const fn sql(id: i64, description: &'static str, statements: &'static [&'static str]) -> Self {
+        Self { id, description, step: MigrationStep::Sql(statements) }
+    }
+
+    pub const fn rust(id: i64, description: &'static str, run: MigrationFn) -> Self {
+        Self { id, description, step: MigrationStep::Rust(run) }
+    }
+}
+
+pub struct SchemaMigrator {
+    migrations: Vec<Migration>,
+}
+
+impl SchemaMigrator {
+    // Arbitrary key shared by every instance so concurrent boots apply each migration once.
+    const ADVISORY_LOCK_KEY: i64 = 0x6e_696d_626c_65;
+
+    pub fn new(migrations: Vec<Migration>) -> Result<Self> {
+        for pair in migrations.windows(2) {
+            if pair[1].id <= pair[0].id {
+                return Err(anyhow!(
+                    "migration ids must be strictly increasing: {} follows {}",
+                    pair[1].id,
+                    pair[0].id
+                ));
+            }
+        }
+        Ok(Self { migrations })
+    }
+
+    pub fn builtin() -> Self {
+        Self::new(builtin_migrations()).expect("built-in migrations are ordered")
+    }
+
+    pub fn latest_version(&self) -> i64 {
+        self.migrations.last().map(|migration| migration.id).unwrap_or(0)
+    }
+
+    pub fn ids(&self) -> Vec<i64> {
+        self.migrations.iter().map(|migration| migration.id).collect()
+    }
+
+    pub fn check_compatible(&self, applied: &[i64]) -> Result<()> {
+        let known = self.ids();
+        let unknown: Vec<i64> = applied.iter().copied().filter(|id| !known.contains(id)).collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow!(
+            "database schema is ahead of this binary: migration(s) {:?} are not known (latest known is {})",
+            unknown,
+            self.latest_version()
+        ))
+    }
+
+    pub fn pending(&self, applied: &[i64]) -> Vec<&Migration> {
+        self.migrations.iter().filter(|migration| !applied.contains(&migration.id)).collect()
+    }
+
+    pub async fn applied_versions(pool: &PgPool) -> Result<Vec<i64>> {
+        Self::ensure_table(pool).await?;
+        sqlx::query_scalar("SELECT id FROM schema_migrations ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map_err(|err| anyhow!("Failed to read schema_migrations: {}", err))
+    }
+
+    pub async fn run(&self, pool: &PgPool) -> Result<Vec<i64>> {
+        let applied = Self::applied_versions(pool).await?;
+        self.check_compatible(&applied)?;
+
+        let mut newly_applied = Vec::new();
+        for migration in self.pending(&applied) {
+            if self.apply(pool, migration).await? {
+                newly_applied.push(migration.id);
+            }
+        }
+        Ok(newly_applied)
+    }
+
+    async fn ensure_table(pool: &PgPool) -> Result<()> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (id BIGINT PRIMARY KEY, description TEXT NOT NULL, applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW())",
+        )
+        .execute(pool)
+        .await
+        .map_err(|err| anyhow!("Failed to create schema_migrations: {}", err))?;
+        Ok(())
+    }
+
+    async fn apply(&self, pool: &PgPool, migration: &Migration) -> Result<bool> {
+        let failed =
+            |err: sqlx::Error| anyhow!("Migration {} ({}) failed: {}", migration.id, migration.description, err);
+        let mut tx = pool.begin().await.map_err(failed)?;
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(Self::ADVISORY_LOCK_KEY)
+            .execute(&mut *tx)
+            .await
+            .map_err(failed)?;
+
+        let already_applied: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM schema_migrations WHERE id = $1)")
+            .bind(migration.id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(failed)?;
+        if already_applied {
+            return Ok(false);
+        }
+
+        log::info!("Applying migration {}: {}", migration.id, migration.description);
+        match &migration.step {
+            MigrationStep::Sql(statements) => {
+                for sql in statements.iter() {
+                    sqlx::query(sql).execute(&mut *tx).await.map_err(|err| {
+                        anyhow!("Migration {} ({}) failed on '{}': {}", migration.id, migration.description, sql, err)
+                    })?;
+                }
+            }
+            MigrationStep::Rust(run) => {
+                run(&mut *tx)
+                    .await
+                    .map_err(|err| anyhow!("Migration {} ({}) failed: {}", migration.id, migration.description, err))?;
+            }
+        }
+
+        sqlx::query("INSERT INTO schema_migrations (id, description) VALUES ($1, $2)")
+            .bind(migration.id)
+            .bind(migration.description)
+            .execute(&mut *tx)
+            .await
+            .map_err(failed)?;
+        tx.commit().await.map_err(failed)?;
+        Ok(true)
+    }
+}
+
+fn builtin_migrations() -> Vec<Migration> {
+    vec![
+        Migration::sql(1, "Enable pgcrypto", M0001),
+        Migration::sql(2, "Give client storages a UUID primary key", M0002),
+        Migration::sql(3, "Store user settings user_id as UUID", M0003),
+        Migration::sql(4, "Add storage readonly flag", M0004),
+        Migration::sql(5, "Denormalize EXIF columns onto photos", M0005),
+        Migration::sql(6, "Index photos, timeline days and exifs", M0006),
+        Migration::sql(7, "Allow anonymous comment authors and index comments", M0007),
+        Migration::sql(8, "Order album photos and version albums", M0008),
+        Migration::sql(9, "Index audit logs and pipeline jobs", M0009),
+        Migration::sql(10, "Create tag tables", M0010),
+        Migration::sql(11, "Create public visible photos view", M0011),
+        Migration::sql(12, "Add album dates and location", M0012),
+        Migration::sql(13, "Add photo title and caption", M0013),
+        Migration::sql(14, "Track manually edited EXIF fields", M0014),
+        Migration::sql(15, "Track derivative generation profiles", M0015),
+        Migration::sql(16, "Stack RAW and JPEG pairs", M0016),
+        Migration::sql(17, "Add user email digest opt-in", M0017),
+        Migration::sql(18, "Index notifications by recipient", M0018),
+        Migration::sql(19, "Record pipeline job import format", M0019),
+        Migration::sql(20, "Constrain photo ratings to 0-5", M0020),
+        Migration::sql(21, "Create photo people table", M0021),
+        Migration::sql(22, "Create photo tag suggestions table", M0022),
+        Migration::sql(23, "Add photo blurhash", M0023),
+        Migration::sql(24, "Index refresh sessions", M0024),
+    ]
+}
+
+const M0001: &[&str] = &["CREATE EXTENSION IF NOT EXISTS \"pgcrypto\""];
+
+const M0002: &[&str] = &[
+    "ALTER TABLE clientstorages ADD COLUMN IF NOT EXISTS id UUID",
+    r#"DO $$ BEGIN
+            IF EXISTS (
+                SELECT 1
+                FROM information_schema.columns
+                WHERE table_name = 'clientstorages'
+                AND column_name = 'id'
+                AND data_type <> 'uuid'
+            ) THEN
+                EXECUTE 'ALTER TABLE clientstorages ALTER COLUMN id TYPE UUID USING gen_random_uuid()';
+            END IF;
+        END $$;"#,
+    "ALTER TABLE clientstorages ALTER COLUMN id SET DEFAULT gen_random_uuid()",
+    "UPDATE clientstorages SET id = gen_random_uuid() WHERE id IS NULL",
+    "ALTER TABLE clientstorages DROP CONSTRAINT IF EXISTS clientstorages_pkey",
+    "ALTER TABLE clientstorages ADD CONSTRAINT clientstorages_pkey PRIMARY KEY (id)",
+    "CREATE UNIQUE INDEX IF NOT EXISTS ux_clientstorages_client_storage ON clientstorages (client_id, storage_id)",
+];
+
+const M0003: &[&str] = &[r#"DO $$
+        DECLARE
+            settings_table TEXT;
+        BEGIN
+            SELECT c.table_name INTO settings_table
+            FROM information_schema.columns c
+            WHERE c.table_schema = current_schema()
+            AND c.column_name = 'user_id'
+            AND c.data_type <> 'uuid'
+            AND EXISTS (
+                SELECT 1
+                FROM information_schema.columns t
+                WHERE t.table_schema = c.table_schema
+                AND t.table_name = c.table_name
+                AND t.column_name = 'theme'
+            )
+            LIMIT 1;
+
+            IF settings_table IS NOT NULL THEN
+                EXECUTE format('DELETE FROM %I WHERE user_id IS NULL OR trim(user_id) !~* ''^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$''', settings_table);
+                EXECUTE format('DELETE FROM %I a USING %I b WHERE lower(trim(a.user_id)) = lower(trim(b.user_id)) AND a.ctid < b.ctid', settings_table, settings_table);
+                EXECUTE format('ALTER TABLE %I ALTER COLUMN user_id TYPE UUID USING lower(trim(user_id))::uuid', settings_table);
+            END IF;
+        END $$;"#];
+
+const M0004: &[&str] = &[
+    "ALTER TABLE storages ADD COLUMN IF NOT EXISTS readonly BOOLEAN NOT NULL DEFAULT false",
+    "UPDATE storages SET readonly = true WHERE id = '00000000-0000-0000-0000-000000000001'::uuid",
+];
+
+const M0005: &[&str] = &[
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS year INTEGER",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS month_day TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS artist TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS make TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS model TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS lens_make TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS lens_model TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS exposure_time TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS iso INTEGER",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS aperture REAL",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS focal_length REAL",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS label TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS rating INTEGER",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS flagged INTEGER",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS orientation INTEGER",
+    r#"UPDATE photos p
+       SET
+           year = COALESCE(p.year, EXTRACT(YEAR FROM COALESCE(p.date_taken, p.created_at, p.sort_date) AT TIME ZONE 'UTC')::int),
+           month_day = COALESCE(p.month_day, to_char(COALESCE(p.date_taken, p.created_at, p.sort_date) AT TIME ZONE 'UTC', 'MM-DD')),
+           sort_date = COALESCE(p.sort_date, COALESCE(p.date_taken, p.created_at, NOW())),
+           day_date = COALESCE(p.day_date, (COALESCE(p.date_taken, p.created_at, p.sort_date, NOW()) AT TIME ZONE 'UTC')::date)"#,
+    r#"UPDATE photos p
+       SET
+           artist = COALESCE(p.artist, e.artist),
+           make = COALESCE(p.make, e.make),
+           model = COALESCE(p.model, e.model),
+           lens_make = COALESCE(p.lens_make, e.lens_make),
+           lens_model = COALESCE(p.lens_model, e.lens_model),
+           exposure_time = COALESCE(p.exposure_time, e.exposure_time),
+           iso = COALESCE(p.iso, e.iso, e.photographic_sensitivity),
+           aperture = COALESCE(p.aperture, e.f_number, e.aperture_value),
+           focal_length = COALESCE(p.focal_length, e.focal_length),
+           label = COALESCE(p.label, e.label),
+           rating = COALESCE(p.rating, e.rating),
+           flagged = COALESCE(p.flagged, e.flagged),
+           orientation = COALESCE(p.orientation, e.orientation),
+           width = COALESCE(
+               p.width,
+               CASE
+                   WHEN e.orientation IN (5, 6, 7, 8) THEN COALESCE(e.pixel_y_dimension, e.image_length)
+                   ELSE COALESCE(e.pixel_x_dimension, e.image_width)
+               END
+           ),
+           height = COALESCE(
+               p.height,
+               CASE
+                   WHEN e.orientation IN (5, 6, 7, 8) THEN COALESCE(e.pixel_x_dimension, e.image_width)
+                   ELSE COALESCE(e.pixel_y_dimension, e.image_length)
+               END
+           ),
+           metadata_extracted = COALESCE(p.metadata_extracted, true)
+       FROM exifs e
+       WHERE e.image_id = p.id"#,
+];
+
+const M0006: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_photos_day_taken ON photos (day_date DESC, date_taken DESC)",
+    "CREATE INDEX IF NOT EXISTS idx_photos_year ON photos (year DESC)",
+    "CREATE INDEX IF NOT EXISTS idx_timeline_days_day_date_year ON timeline_days (day_date, year)",
+    "CREATE INDEX IF NOT EXISTS idx_photos_hash ON photos(hash)",
+    "CREATE INDEX IF NOT EXISTS idx_photos_storage ON photos(storage_id)",
+    "CREATE INDEX IF NOT EXISTS idx_exifs_image_id ON exifs (image_id)",
+];
+
+const M0007: &[&str] = &[
+    "ALTER TABLE photo_comments ALTER COLUMN user_id DROP NOT NULL",
+    "ALTER TABLE album_comments ALTER COLUMN user_id DROP NOT NULL",
+    "CREATE INDEX IF NOT EXISTS idx_photo_comments_photo_id ON photo_comments (photo_id)",
+    "CREATE INDEX IF NOT EXISTS idx_album_comments_album_id ON album_comments (album_id)",
+    "CREATE INDEX IF NOT EXISTS idx_photo_comments_user_id ON photo_comments (user_id)",
+    "CREATE INDEX IF NOT EXISTS idx_album_comments_user_id ON album_comments (user_id)",
+];
+
+const M0008: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_album_photos_album_id ON album_photos (album_id)",
+    "CREATE INDEX IF NOT EXISTS idx_album_photos_photo_id ON album_photos (photo_id)",
+    "CREATE UNIQUE INDEX IF NOT EXISTS ux_album_photos_album_photo ON album_photos (album_id, photo_id)",
+    "ALTER TABLE album_photos ADD COLUMN IF NOT EXISTS position INTEGER",
+    "ALTER TABLE albums ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1",
+    r#"UPDATE album_photos ap
+       SET position = ordered.position
+       FROM (
+           SELECT
+               ap.id,
+               ROW_NUMBER() OVER (PARTITION BY ap.album_id ORDER BY p.sort_date DESC NULLS LAST, p.id)::int AS position
+           FROM album_photos ap
+           JOIN photos p ON p.id = ap.photo_id
+           WHERE ap.album_id IN (SELECT album_id FROM album_photos GROUP BY album_id HAVING bool_and(position IS NULL))
+       ) ordered
+       WHERE ap.id = ordered.id"#,
+    "CREATE INDEX IF NOT EXISTS idx_album_photos_album_position ON album_photos (album_id, position)",
+];
+
+const M0009: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_audit_logs_created_at ON audit_logs (created_at DESC)",
+    "CREATE INDEX IF NOT EXISTS idx_audit_logs_action ON audit_logs (action)",
+    "CREATE INDEX IF NOT EXISTS idx_audit_logs_actor ON audit_logs (actor_user_id)",
+    "CREATE INDEX IF NOT EXISTS idx_pipeline_jobs_state ON pipeline_jobs (state)",
+    "ALTER TABLE pipeline_jobs ADD COLUMN IF NOT EXISTS batch_id UUID",
+    "ALTER TABLE pipeline_jobs ADD COLUMN IF NOT EXISTS in_place BOOLEAN NOT NULL DEFAULT false",
+    "CREATE INDEX IF NOT EXISTS idx_pipeline_jobs_batch_id ON pipeline_jobs (batch_id) WHERE batch_id IS NOT NULL",
+];
+
+const M0010: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS tags (id UUID PRIMARY KEY DEFAULT gen_random_uuid(), name TEXT NOT NULL, name_norm TEXT NOT NULL, visibility SMALLINT NOT NULL DEFAULT 0, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), CONSTRAINT ck_tags_visibility CHECK (visibility IN (0, 1)))",
+    "CREATE UNIQUE INDEX IF NOT EXISTS ux_tags_name_norm ON tags (name_norm)",
+    "CREATE INDEX IF NOT EXISTS idx_tags_name ON tags (name)",
+    "CREATE TABLE IF NOT EXISTS photo_tags (photo_id UUID NOT NULL REFERENCES photos (id) ON DELETE CASCADE, tag_id UUID NOT NULL REFERENCES tags (id) ON DELETE CASCADE, PRIMARY KEY (photo_id, tag_id))",
+    "CREATE TABLE IF NOT EXISTS album_tags (album_id UUID NOT NULL REFERENCES albums (id) ON DELETE CASCADE, tag_id UUID NOT NULL REFERENCES tags (id) ON DELETE CASCADE, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), created_by_user_id UUID NULL REFERENCES users (id) ON DELETE SET NULL, PRIMARY KEY (album_id, tag_id))",
+    "CREATE INDEX IF NOT EXISTS idx_photo_tags_photo ON photo_tags (photo_id)",
+    "CREATE INDEX IF NOT EXISTS idx_photo_tags_tag ON photo_tags (tag_id)",
+    "CREATE INDEX IF NOT EXISTS idx_album_tags_tag_id_album_id ON album_tags (tag_id, album_id)",
+];
+
+const M0011: &[&str] = &[
+    "CREATE OR REPLACE VIEW photos_public_visible AS SELECT p.* FROM photos p WHERE NOT EXISTS (SELECT 1 FROM photo_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.photo_id = p.id AND t.visibility = 1)",
+];
+
+const M0012: &[&str] = &[
+    "ALTER TABLE albums ADD COLUMN IF NOT EXISTS start_date TIMESTAMPTZ",
+    "ALTER TABLE albums ADD COLUMN IF NOT EXISTS end_date TIMESTAMPTZ",
+    "ALTER TABLE albums ADD COLUMN IF NOT EXISTS dates_overridden BOOLEAN NOT NULL DEFAULT false",
+    "ALTER TABLE albums ADD COLUMN IF NOT EXISTS location_name TEXT",
+    r#"UPDATE albums a
+       SET start_date = range.start_date, end_date = range.end_date
+       FROM (
+           SELECT ap.album_id, min(COALESCE(p.date_taken, p.sort_date)) AS start_date, max(COALESCE(p.date_taken, p.sort_date)) AS end_date
+           FROM album_photos ap
+           JOIN photos p ON p.id = ap.photo_id
+           GROUP BY ap.album_id
+       ) range
+       WHERE a.id = range.album_id
+       AND a.start_date IS NULL
+       AND a.end_date IS NULL"#,
+];
+
+const M0013: &[&str] = &[
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS title TEXT",
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS caption TEXT",
+    // The view expanded p.* when it was created, so it has to be rebuilt to pick up the new columns.
+    "CREATE OR REPLACE VIEW photos_public_visible AS SELECT p.* FROM photos p WHERE NOT EXISTS (SELECT 1 FROM photo_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.photo_id = p.id AND t.visibility = 1)",
+];
+
+const M0014: &[&str] = &["ALTER TABLE exifs ADD COLUMN IF NOT EXISTS edited_fields TEXT NOT NULL DEFAULT '[]'"];
+
+const M0015: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS derivative_profiles (storage_id UUID NOT NULL, hash TEXT NOT NULL, kind TEXT NOT NULL, profile TEXT NOT NULL, updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), PRIMARY KEY (storage_id, hash, kind))",
+];
+
+const M0016: &[&str] = &[
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS stack_id UUID",
+    "CREATE INDEX IF NOT EXISTS idx_photos_stack_id ON photos (stack_id) WHERE stack_id IS NOT NULL",
+    "CREATE OR REPLACE VIEW photos_public_visible AS SELECT p.* FROM photos p WHERE NOT EXISTS (SELECT 1 FROM photo_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.photo_id = p.id AND t.visibility = 1)",
+];
+
+// The user settings table name predates the naming convention, so locate it the same way M0003 does.
+const M0017: &[&str] = &[r#"DO $$
+        DECLARE
+            settings_table TEXT;
+        BEGIN
+            SELECT c.table_name INTO settings_table
+            FROM information_schema.columns c
+            WHERE c.table_schema = current_schema()
+            AND c.column_name = 'user_id'
+            AND EXISTS (
+                SELECT 1
+                FROM information_schema.columns t
+                WHERE t.table_schema = c.table_schema
+                AND t.table_name = c.table_name
+                AND t.column_name = 'theme'
+            )
+            LIMIT 1;
+
+            IF settings_table IS NOT NULL THEN
+                EXECUTE format('ALTER TABLE %I ADD COLUMN IF NOT EXISTS email_digest BOOLEAN NOT NULL DEFAULT false', settings_table);
+            END IF;
+        END $$;"#];
+
+const M0018: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_notifications_recipient ON notifications (recipient_id, created_at DESC)",
+    "CREATE INDEX IF NOT EXISTS idx_notifications_unread ON notifications (recipient_id) WHERE read_at IS NULL",
+];
+
+const M0019: &[&str] = &["ALTER TABLE pipeline_jobs ADD COLUMN IF NOT EXISTS import_format TEXT"];
+
+// Ratings copied from EXIF before this were not range-checked, so clear anything a CHECK would reject.
+const M0020: &[&str] = &[
+    "UPDATE photos SET rating = NULL WHERE rating < 0 OR rating > 5",
+    "ALTER TABLE photos DROP CONSTRAINT IF EXISTS ck_photos_rating",
+    "ALTER TABLE photos ADD CONSTRAINT ck_photos_rating CHECK (rating BETWEEN 0 AND 5)",
+    "CREATE INDEX IF NOT EXISTS idx_photos_rating ON photos (rating) WHERE rating > 0",
+];
+
+// Regions are stored as top-left fractions of the image so they survive derivative resizing.
+const M0021: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS photo_people (id UUID PRIMARY KEY DEFAULT gen_random_uuid(), photo_id UUID NOT NULL REFERENCES photos (id) ON DELETE CASCADE, name TEXT NOT NULL, name_norm TEXT NOT NULL, region_x REAL NULL, region_y REAL NULL, region_w REAL NULL, region_h REAL NULL, source TEXT NOT NULL DEFAULT 'manual', created_at TIMESTAMPTZ NOT NULL DEFAULT NOW())",
+    "CREATE UNIQUE INDEX IF NOT EXISTS ux_photo_people_photo_name ON photo_people (photo_id, name_norm)",
+    "CREATE INDEX IF NOT EXISTS idx_photo_people_name_norm ON photo_people (name_norm)",
+];
+
+const M0022: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS photo_tag_suggestions (id UUID PRIMARY KEY DEFAULT gen_random_uuid(), photo_id UUID NOT NULL REFERENCES photos (id) ON DELETE CASCADE, name TEXT NOT NULL, name_norm TEXT NOT NULL, confidence REAL NOT NULL, analyzer TEXT NOT NULL, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW())",
+    "CREATE UNIQUE INDEX IF NOT EXISTS ux_photo_tag_suggestions_photo_name ON photo_tag_suggestions (photo_id, name_norm)",
+];
+
+const M0023: &[&str] = &[
+    "ALTER TABLE photos ADD COLUMN IF NOT EXISTS blurhash TEXT",
+    "CREATE OR REPLACE VIEW photos_public_visible AS SELECT p.* FROM photos p WHERE NOT EXISTS (SELECT 1 FROM photo_tags pt JOIN tags t ON t.id = pt.tag_id WHERE pt.photo_id = p.id AND t.visibility = 1)",
+];
+
+// Not unique: tokens for the same user issued within the same second can be identical.
+const M0024: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_refresh_sessions_token_hash ON refresh_sessions (token_hash)",
+    "CREATE INDEX IF NOT EXISTS idx_refresh_sessions_user ON refresh_sessions (user_id, last_used_at DESC)",
+];
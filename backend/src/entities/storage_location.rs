@@ -35,7 +35,7 @@ impl StorageLocation {
         }
     }
 
-    fn default_category_template() -> String {
+    pub fn default_category_template() -> String {
         "{year}/{date:%Y-%m-%d}/{fileName}".to_string()
     }
 }
@@ -138,6 +138,20 @@ pub struct CreateStoragePayload {
     pub category_template: Option<String>,
 }
 
+impl Validate for CreateStoragePayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        validator
+            .required("label", "Storage label", &self.label)
+            .required("mountPoint", "Mount point", &self.mount_point)
+            .required("path", "Storage path", &self.path);
+        if let Some(template) = self.category_template.as_deref().filter(|value| !value.trim().is_empty()) {
+            validator.template("categoryTemplate", template);
+        }
+        validator.finish()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateStoragePayload {
@@ -147,6 +161,25 @@ pub struct UpdateStoragePayload {
     pub category_template: Option<String>,
 }
 
+impl Validate for UpdateStoragePayload {
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut validator = Validator::new();
+        if let Some(label) = &self.label {
+            validator.required("label", "Storage label", label);
+        }
+        if let Some(path) = &self.path {
+            validator.required("path", "Storage path", path);
+        }
+        if let Some(template) = &self.category_template {
+            validator.required("categoryTemplate", "Category template", template);
+            if !template.trim().is_empty() {
+                validator.template("categoryTemplate", template);
+            }
+        }
+        validator.finish()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateClientStorageSettingsPayload {
@@ -14,10 +14,30 @@ use {
 pub struct Tag {
     pub id: Uuid,
     pub name: String,
+    #[serde(alias = "name_norm")]
+    pub name_norm: String,
     pub visibility: i16,
+    #[serde(alias = "created_at")]
     pub created_at: Option<DateTime<Utc>>,
 }
 
+impl Tag {
+    pub const VISIBILITY_PUBLIC: i16 = 0;
+    pub const VISIBILITY_HIDDEN: i16 = 1;
+    pub const HIDDEN_TAG_FORBIDDEN: &'static str = "Only administrators can add or remove admin-only tags";
+
+    pub fn new(name: &str, visibility: i16) -> Self {
+        let name = name.trim();
+        Self {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            name_norm: name.to_lowercase(),
+            visibility,
+            created_at: Some(Utc::now()),
+        }
+    }
+}
+
 impl Entity for Tag {
     type Id = Uuid;
 
@@ -41,31 +61,33 @@ impl PostgresEntity for Tag {
     }
 
     fn insert_columns() -> &'static [&'static str] {
-        &["id", "name", "visibility", "created_at"]
+        &["id", "name", "name_norm", "visibility", "created_at"]
     }
 
     fn insert_values(&self) -> Vec<Value> {
         vec![
             Value::Uuid(self.id),
             Value::String(self.name.clone()),
-            Value::Int(self.visibility as i64),
+            Value::String(self.name_norm.clone()),
+            Value::I16(self.visibility),
             PostgresValueBuilder::optional_datetime(&self.created_at),
         ]
     }
 
     fn update_columns() -> &'static [&'static str] {
-        &["name", "visibility"]
+        &["name", "name_norm", "visibility"]
     }
 
     fn update_values(&self) -> Vec<Value> {
-        vec![Value::String(self.name.clone()), Value::Int(self.visibility as i64)]
+        vec![Value::String(self.name.clone()), Value::String(self.name_norm.clone()), Value::I16(self.visibility)]
     }
 
     fn table_columns() -> Vec<ColumnDef> {
         vec![
-            ColumnDef::new("id", ColumnType::Uuid).primary_key(),
+            ColumnDef::new("id", ColumnType::Uuid).primary_key().default("gen_random_uuid()"),
             ColumnDef::new("name", ColumnType::Text).not_null(),
-            ColumnDef::new("visibility", ColumnType::Integer).not_null().default("0"),
+            ColumnDef::new("name_norm", ColumnType::Text).not_null(),
+            ColumnDef::new("visibility", ColumnType::Custom("SMALLINT")).not_null().default("0"),
             ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
         ]
     }
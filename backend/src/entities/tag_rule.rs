@@ -0,0 +1,131 @@
+use crate::prelude::*;
+
+#[cfg(feature = "postgres")]
+use {
+    nimble_web::data::postgres::{PostgresEntity, value_builder::PostgresValueBuilder},
+    nimble_web::data::query::Value,
+    nimble_web::data::schema::{ColumnDef, ColumnType},
+    sqlx::FromRow,
+};
+
+#[cfg_attr(feature = "postgres", derive(FromRow))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagRule {
+    pub id: Uuid,
+    pub field: String,
+    pub operator: String,
+    pub value: String,
+    #[serde(alias = "tag_name")]
+    pub tag_name: String,
+    #[serde(default, alias = "created_by")]
+    pub created_by: Option<Uuid>,
+    // Only rules written by an administrator may apply admin-only tags.
+    #[serde(default, alias = "author_is_admin")]
+    pub author_is_admin: bool,
+    #[serde(alias = "created_at")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl TagRule {
+    pub fn new(
+        field: TagRuleField,
+        operator: TagRuleOperator,
+        value: &str,
+        tag_name: &str,
+        created_by: Option<Uuid>,
+        author_is_admin: bool,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            field: field.as_str().to_string(),
+            operator: operator.as_str().to_string(),
+            value: value.trim().to_string(),
+            tag_name: tag_name.trim().to_string(),
+            created_by,
+            author_is_admin,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn condition(&self) -> Option<(TagRuleField, TagRuleOperator)> {
+        Some((TagRuleField::parse(&self.field)?, TagRuleOperator::parse(&self.operator)?))
+    }
+
+    pub fn needs_exif(&self) -> bool {
+        self.condition().is_some_and(|(field, _)| field.needs_exif())
+    }
+
+    pub fn matches(&self, photo: &Photo, exif: Option<&ExifModel>) -> bool {
+        let Some((field, operator)) = self.condition() else {
+            return false;
+        };
+        field.read(photo, exif).is_some_and(|actual| operator.evaluate(&actual, &self.value))
+    }
+}
+
+impl Entity for TagRule {
+    type Id = Uuid;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+
+    fn name() -> &'static str {
+        "tag_rule"
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresEntity for TagRule {
+    fn id_column() -> &'static str {
+        "id"
+    }
+
+    fn id_value(id: &Self::Id) -> Value {
+        Value::Uuid(*id)
+    }
+
+    fn insert_columns() -> &'static [&'static str] {
+        &["id", "field", "operator", "value", "tag_name", "created_by", "author_is_admin", "created_at"]
+    }
+
+    fn insert_values(&self) -> Vec<Value> {
+        vec![
+            Value::Uuid(self.id),
+            Value::String(self.field.clone()),
+            Value::String(self.operator.clone()),
+            Value::String(self.value.clone()),
+            Value::String(self.tag_name.clone()),
+            PostgresValueBuilder::optional_uuid(self.created_by),
+            Value::Bool(self.author_is_admin),
+            Value::DateTime(self.created_at),
+        ]
+    }
+
+    fn update_columns() -> &'static [&'static str] {
+        &["field", "operator", "value", "tag_name"]
+    }
+
+    fn update_values(&self) -> Vec<Value> {
+        vec![
+            Value::String(self.field.clone()),
+            Value::String(self.operator.clone()),
+            Value::String(self.value.clone()),
+            Value::String(self.tag_name.clone()),
+        ]
+    }
+
+    fn table_columns() -> Vec<ColumnDef> {
+        vec![
+            ColumnDef::new("id", ColumnType::Uuid).primary_key().default("gen_random_uuid()"),
+            ColumnDef::new("field", ColumnType::Text).not_null(),
+            ColumnDef::new("operator", ColumnType::Text).not_null(),
+            ColumnDef::new("value", ColumnType::Text).not_null(),
+            ColumnDef::new("tag_name", ColumnType::Text).not_null(),
+            ColumnDef::new("created_by", ColumnType::Uuid),
+            ColumnDef::new("author_is_admin", ColumnType::Boolean).not_null().default("false"),
+            ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
+        ]
+    }
+}
@@ -0,0 +1,162 @@
+use crate::prelude::*;
+
+#[cfg(feature = "postgres")]
+use {
+    nimble_web::data::postgres::{PostgresEntity, value_builder::PostgresValueBuilder},
+    nimble_web::data::query::Value,
+    nimble_web::data::schema::{ColumnDef, ColumnType},
+    sqlx::FromRow,
+};
+
+// At most one row per user. The row exists from setup onwards but only counts once `enabled` is set,
+// so an abandoned setup never locks anyone out.
+#[cfg_attr(feature = "postgres", derive(FromRow))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoFactor {
+    pub user_id: Uuid,
+    // The base32 TOTP secret, encrypted with EncryptService.
+    pub secret: String,
+    pub enabled: bool,
+    // Comma separated SHA-256 hashes of the unused backup codes.
+    pub backup_codes: String,
+    // Last accepted TOTP step; codes from that step or earlier are refused so a code can't be replayed.
+    pub last_used_step: i64,
+    // Wrong codes since the last success; reaching the limit sets `locked_until` and starts the count over.
+    pub failed_codes: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub enabled_at: Option<DateTime<Utc>>,
+}
+
+impl TwoFactor {
+    pub fn new(user_id: Uuid, encrypted_secret: String) -> Self {
+        Self {
+            user_id,
+            secret: encrypted_secret,
+            enabled: false,
+            backup_codes: String::new(),
+            last_used_step: 0,
+            failed_codes: 0,
+            locked_until: None,
+            created_at: Utc::now(),
+            enabled_at: None,
+        }
+    }
+
+    pub fn backup_code_hashes(&self) -> Vec<&str> {
+        self.backup_codes.split(',').filter(|hash| !hash.is_empty()).collect()
+    }
+
+    pub fn set_backup_codes(&mut self, codes: &[String]) {
+        self.backup_codes = codes.iter().map(|code| BackupCode::hash(code)).collect::<Vec<_>>().join(",");
+    }
+
+    // Counts a wrong code and returns the lockout end once `max_failed` misses have piled up.
+    pub fn record_failed_code(
+        &mut self,
+        now: DateTime<Utc>,
+        max_failed: i32,
+        lockout_seconds: i64,
+    ) -> Option<DateTime<Utc>> {
+        self.failed_codes += 1;
+        if self.failed_codes < max_failed {
+            return None;
+        }
+        self.failed_codes = 0;
+        self.locked_until = Some(now + Duration::seconds(lockout_seconds));
+        self.locked_until
+    }
+
+    // Removes the code so it can't be used again; false when it doesn't match any remaining code.
+    pub fn consume_backup_code(&mut self, code: &str) -> bool {
+        let hash = BackupCode::hash(code);
+        let hashes = self.backup_code_hashes();
+        if !hashes.contains(&hash.as_str()) {
+            return false;
+        }
+        self.backup_codes = hashes.into_iter().filter(|stored| *stored != hash).collect::<Vec<_>>().join(",");
+        true
+    }
+}
+
+impl Entity for TwoFactor {
+    type Id = Uuid;
+
+    fn id(&self) -> &Self::Id {
+        &self.user_id
+    }
+
+    fn name() -> &'static str {
+        "two_factor"
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresEntity for TwoFactor {
+    fn id_column() -> &'static str {
+        "user_id"
+    }
+
+    fn id_value(id: &Self::Id) -> Value {
+        Value::Uuid(*id)
+    }
+
+    fn insert_columns() -> &'static [&'static str] {
+        &[
+            "user_id",
+            "secret",
+            "enabled",
+            "backup_codes",
+            "last_used_step",
+            "failed_codes",
+            "locked_until",
+            "created_at",
+            "enabled_at",
+        ]
+    }
+
+    fn insert_values(&self) -> Vec<Value> {
+        vec![
+            Value::Uuid(self.user_id),
+            Value::String(self.secret.clone()),
+            Value::Bool(self.enabled),
+            Value::String(self.backup_codes.clone()),
+            Value::Int(self.last_used_step),
+            Value::Int(self.failed_codes as i64),
+            PostgresValueBuilder::optional_datetime(&self.locked_until),
+            Value::DateTime(self.created_at),
+            PostgresValueBuilder::optional_datetime(&self.enabled_at),
+        ]
+    }
+
+    fn update_columns() -> &'static [&'static str] {
+        &["secret", "enabled", "backup_codes", "last_used_step", "failed_codes", "locked_until", "enabled_at"]
+    }
+
+    fn update_values(&self) -> Vec<Value> {
+        vec![
+            Value::String(self.secret.clone()),
+            Value::Bool(self.enabled),
+            Value::String(self.backup_codes.clone()),
+            Value::Int(self.last_used_step),
+            Value::Int(self.failed_codes as i64),
+            PostgresValueBuilder::optional_datetime(&self.locked_until),
+            PostgresValueBuilder::optional_datetime(&self.enabled_at),
+        ]
+    }
+
+    fn table_columns() -> Vec<ColumnDef> {
+        vec![
+            ColumnDef::new("user_id", ColumnType::Uuid).primary_key(),
+            ColumnDef::new("secret", ColumnType::Text).not_null(),
+            ColumnDef::new("enabled", ColumnType::Boolean).not_null().default("false"),
+            ColumnDef::new("backup_codes", ColumnType::Text).not_null().default("''"),
+            ColumnDef::new("last_used_step", ColumnType::BigInt).not_null().default("0"),
+            ColumnDef::new("failed_codes", ColumnType::Integer).not_null().default("0"),
+            ColumnDef::new("locked_until", ColumnType::Timestamp),
+            ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
+            ColumnDef::new("enabled_at", ColumnType::Timestamp),
+        ]
+    }
+}
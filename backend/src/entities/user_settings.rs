@@ -17,9 +17,29 @@ pub struct UserSettings {
     pub theme: String,
     pub language: String,
     pub timezone: String,
+    #[serde(default)]
+    pub email_digest: bool,
     pub created_at: DateTime<Utc>,
 }
 
+impl UserSettings {
+    pub const THEMES: &'static [&'static str] = &["light", "dark", "system"];
+    pub const LANGUAGES: &'static [&'static str] = &["en", "de", "es", "fr", "ja", "zh-CN", "zh-TW"];
+
+    pub fn new(user_id: Uuid, display_name: String) -> Self {
+        Self {
+            user_id,
+            display_name,
+            avatar_url: None,
+            theme: "light".to_string(),
+            language: "en".to_string(),
+            timezone: TimelineZone::UTC.to_string(),
+            email_digest: false,
+            created_at: Utc::now(),
+        }
+    }
+}
+
 impl Entity for UserSettings {
     type Id = Uuid;
 
@@ -43,7 +63,7 @@ impl PostgresEntity for UserSettings {
     }
 
     fn insert_columns() -> &'static [&'static str] {
-        &["user_id", "display_name", "avatar_url", "theme", "language", "timezone", "created_at"]
+        &["user_id", "display_name", "avatar_url", "theme", "language", "timezone", "email_digest", "created_at"]
     }
 
     fn insert_values(&self) -> Vec<Value> {
@@ -57,12 +77,13 @@ impl PostgresEntity for UserSettings {
             Value::String(self.theme.clone()),
             Value::String(self.language.clone()),
             Value::String(self.timezone.clone()),
+            Value::Bool(self.email_digest),
             Value::DateTime(self.created_at),
         ]
     }
 
     fn update_columns() -> &'static [&'static str] {
-        &["display_name", "avatar_url", "theme", "language", "timezone"]
+        &["display_name", "avatar_url", "theme", "language", "timezone", "email_digest"]
     }
 
     fn update_values(&self) -> Vec<Value> {
@@ -75,6 +96,7 @@ impl PostgresEntity for UserSettings {
             Value::String(self.theme.clone()),
             Value::String(self.language.clone()),
             Value::String(self.timezone.clone()),
+            Value::Bool(self.email_digest),
         ]
     }
 
@@ -86,6 +108,7 @@ impl PostgresEntity for UserSettings {
             ColumnDef::new("theme", ColumnType::Text).not_null(),
             ColumnDef::new("language", ColumnType::Text).not_null(),
             ColumnDef::new("timezone", ColumnType::Text).not_null(),
+            ColumnDef::new("email_digest", ColumnType::Boolean).not_null().default("false"),
             ColumnDef::new("created_at", ColumnType::Timestamp).not_null().default("NOW()"),
         ]
     }
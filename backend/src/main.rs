@@ -14,9 +14,16 @@ async fn main() -> std::result::Result<(), AppError> {
         .use_env()
         .use_address(&bind_address)
         .use_postgres()
-        .use_middleware(CorsMiddleware::default())
+        .use_middleware(RequestLoggingMiddleware::new())
+        .use_middleware(MetricsMiddleware::new())
+        .use_middleware(CorsPolicyMiddleware::new())
+        .use_middleware(CompressionMiddleware::new())
+        .use_middleware(ErrorResponseMiddleware::new())
         .use_authentication()
+        .use_middleware(RevokedSubjectMiddleware::new())
+        .use_middleware(ApiDocsMiddleware::new())
         .use_middleware(PublicAccessMiddleware::new())
+        .use_middleware(FeedMiddleware::new())
         .use_middleware(StaticFileMiddleware::default());
 
     register_services(&mut builder);
@@ -25,18 +32,120 @@ async fn main() -> std::result::Result<(), AppError> {
 
     log::info!("Starting application...");
     let app = builder.build();
+    let config = AppConfig::from_configuration(&app.services().get::<Configuration>())
+        .map_err(|err| AppError::Runtime(err.to_string()))?;
+    for warning in &config.warnings {
+        log::warn!("Configuration: {}", warning);
+    }
+    if let Some(level) = config.log_level {
+        log::set_max_level(level);
+    }
+    let secrets =
+        StartupSecrets::from_config(&config).map_err(|err| AppError::Runtime(format!("startup secrets: {err}")))?;
+    log::info!("Startup configuration: {}", secrets.summary());
     let _ = app.services().get::<PhotoService>();
+    let _ = app.services().get::<UploadSessionService>();
 
     app.log_routes();
 
     log::info!("Migrating database...");
-    migrate_entities(&app).await.map_err(|err| AppError::Runtime(format!("migrate entities: {err}")))?;
+    let database = app.services().get::<DatabaseHealthService>();
+    let (database_ref, app_ref) = (database.as_ref(), &app);
+    StartupRetryPolicy::from_config(&config)
+        .run("database migration", move || async move {
+            database_ref.ping().await?;
+            migrate_entities(app_ref).await
+        })
+        .await
+        .map_err(|err| {
+            database.record_error(&format!("{err:#}"));
+            AppError::Runtime(format!("migrate entities: {err:#}"))
+        })?;
+    database.mark_migrated();
+
+    let runner = app.services().get::<BackgroundTaskRunner>();
+    let pipeline = app.services().get::<ImageProcessPipeline>();
+    let grace_period = resolve_shutdown_grace(&app);
+    if let Err(error) = pipeline.resume_pending_jobs().await {
+        log::error!("Failed to resume pending pipeline jobs: {:?}", error);
+    }
+    restore_spooled_imports(&app, &pipeline).await;
+    app.services().get::<NotificationDigestService>().start_scheduler();
+    app.services().get::<PreviewWarmerService>().start_scheduler();
+    app.services().get::<PreviewCacheService>().start_scheduler();
+    app.services().get::<LiveUpdateService>().start();
+
+    tokio::select! {
+        result = app.start() => result?,
+        _ = shutdown_signal() => log::info!("Shutdown signal received, no longer accepting connections"),
+    }
 
-    app.start().await?;
+    drain_background_tasks(&runner, &pipeline, grace_period).await;
 
     Ok(())
 }
 
+async fn restore_spooled_imports(app: &Application, pipeline: &ImageProcessPipeline) {
+    let storages = match app.services().get::<Repository<StorageLocation>>().load_storages().await {
+        Ok(storages) => storages,
+        Err(error) => {
+            log::error!("Failed to load storages for spooled imports: {:?}", error);
+            return;
+        }
+    };
+
+    if let Err(error) = pipeline.restore_spooled(&storages).await {
+        log::error!("Failed to restore spooled imports: {:?}", error);
+    }
+}
+
+fn resolve_shutdown_grace(app: &Application) -> std::time::Duration {
+    std::time::Duration::from_secs(app.services().get::<AppConfig>().shutdown_grace_seconds)
+}
+
+async fn drain_background_tasks(
+    runner: &BackgroundTaskRunner,
+    pipeline: &ImageProcessPipeline,
+    grace_period: std::time::Duration,
+) {
+    log::info!("Draining background tasks for up to {:?}...", grace_period);
+    match runner.shutdown(grace_period).await {
+        Ok(pending) if pending.is_empty() => log::info!("Background tasks drained"),
+        Ok(pending) => match pipeline.spool_pending(pending) {
+            Ok(count) => log::info!("Spooled {} pending image process request(s) for next startup", count),
+            Err(error) => log::error!("Failed to spool pending image process requests: {:?}", error),
+        },
+        Err(error) => log::error!("Failed to shut down background task runner: {:?}", error),
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                log::error!("Failed to install SIGTERM handler: {}", error);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
 fn resolve_bind_address() -> String {
     if let Ok(address) = std::env::var("Nimble_Photo_Url") {
         return address;
@@ -0,0 +1,51 @@
+use crate::prelude::*;
+use nimble_web::ResponseBody;
+
+pub struct ApiDocsMiddleware;
+
+impl ApiDocsMiddleware {
+    const JSON_CONTENT_TYPE: &'static str = "application/json";
+    const HTML_CONTENT_TYPE: &'static str = "text/html; charset=utf-8";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn is_allowed(context: &HttpContext) -> bool {
+        matches!(context.app_config().environment, AppEnvironment::Development) || context.is_admin()
+    }
+
+    fn serve(context: &mut HttpContext, path: &str) -> Result<(), PipelineError> {
+        if !Self::is_allowed(context) {
+            context.response_mut().set_status(404);
+            return Ok(());
+        }
+
+        let docs = context.service::<ApiDocService>()?;
+        let (content_type, body) = if path == ApiDocService::OPENAPI_PATH {
+            (Self::JSON_CONTENT_TYPE, docs.document().to_string())
+        } else {
+            (Self::HTML_CONTENT_TYPE, docs.docs_page())
+        };
+
+        let response = context.response_mut();
+        response.set_status(200);
+        response.headers_mut().insert("content-type", content_type);
+        response.set_body(ResponseBody::Text(body));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Middleware for ApiDocsMiddleware {
+    async fn handle(&self, context: &mut HttpContext, next: Next<'_>) -> Result<(), PipelineError> {
+        let path = context.request().path().to_string();
+        if context.request().method() == "GET"
+            && (path == ApiDocService::OPENAPI_PATH || path == ApiDocService::DOCS_PATH)
+        {
+            return Self::serve(context, &path);
+        }
+
+        next.run(context).await
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::Write;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+use crate::prelude::*;
+use nimble_web::ResponseBody;
+
+pub struct CompressionMiddleware {
+    min_size: usize,
+}
+
+impl CompressionMiddleware {
+    pub const DEFAULT_MIN_SIZE: usize = 1024;
+    const GZIP: &'static str = "gzip";
+    const VARY_VALUE: &'static str = "Accept-Encoding";
+
+    pub fn new() -> Self {
+        Self { min_size: Self::DEFAULT_MIN_SIZE }
+    }
+
+    pub fn with_min_size(min_size: usize) -> Self {
+        Self { min_size }
+    }
+
+    pub fn accepts_gzip(accept_encoding: Option<&str>) -> bool {
+        accept_encoding
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';').map(str::trim);
+                let name = parts.next()?;
+                let quality = parts
+                    .find_map(|param| param.strip_prefix("q="))
+                    .and_then(|value| value.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((name, quality))
+            })
+            .any(|(name, quality)| quality > 0.0 && (name.eq_ignore_ascii_case(Self::GZIP) || name == "*"))
+    }
+
+    pub fn is_compressible(content_type: Option<&str>) -> bool {
+        let Some(mime) = content_type.and_then(|value| value.split(';').next()) else {
+            return false;
+        };
+        let mime = mime.trim().to_ascii_lowercase();
+        mime == "application/json" || mime.ends_with("+json") || mime.starts_with("text/")
+    }
+
+    pub fn gzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::with_capacity(bytes.len() / 4), Compression::default());
+        encoder.write_all(bytes)?;
+        encoder.finish()
+    }
+
+    fn compress_response(&self, context: &mut HttpContext, accepts_gzip: bool) {
+        let response = context.response_mut();
+        if response.headers().get("content-encoding").is_some()
+            || !Self::is_compressible(response.headers().get("content-type"))
+        {
+            return;
+        }
+        response.headers_mut().insert("vary", Self::VARY_VALUE);
+
+        let ResponseBody::Text(text) = response.body() else {
+            return;
+        };
+        if !accepts_gzip || text.len() < self.min_size {
+            return;
+        }
+
+        let original_size = text.len();
+        match Self::gzip(text.as_bytes()) {
+            Ok(compressed) if compressed.len() < original_size => {
+                response.headers_mut().insert("content-encoding", Self::GZIP);
+                response.set_body(ResponseBody::Bytes(compressed));
+            }
+            Ok(_) => {}
+            Err(error) => log::warn!("Failed to compress response: {}", error),
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for CompressionMiddleware {
+    async fn handle(&self, context: &mut HttpContext, next: Next<'_>) -> Result<(), PipelineError> {
+        let accepts_gzip = Self::accepts_gzip(context.request().headers().get("accept-encoding"));
+
+        next.run(context).await?;
+
+        self.compress_response(context, accepts_gzip);
+        Ok(())
+    }
+}
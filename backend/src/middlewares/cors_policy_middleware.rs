@@ -0,0 +1,85 @@
+use crate::prelude::*;
+
+pub struct CorsPolicyMiddleware;
+
+impl CorsPolicyMiddleware {
+    const PREFLIGHT_METHOD_HEADER: &'static str = "access-control-request-method";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn load_policy(context: &HttpContext) -> Result<Arc<CorsPolicy>, PipelineError> {
+        let settings = context.service::<SettingService>()?;
+        let environment = context.app_config().environment;
+        match context.service::<CorsPolicyCache>() {
+            Ok(cache) => cache.policy(&settings, environment).await,
+            Err(_) => Ok(Arc::new(settings.cors_policy(environment).await?)),
+        }
+    }
+
+    fn apply_headers(context: &mut HttpContext, policy: &CorsPolicy, origin: &str, preflight: bool) {
+        let vary = match context.response().headers().get("vary") {
+            Some(existing) if !existing.to_ascii_lowercase().contains("origin") => format!("{}, Origin", existing),
+            Some(existing) => existing.to_string(),
+            None => "Origin".to_string(),
+        };
+
+        let headers = context.response_mut().headers_mut();
+        headers.insert("access-control-allow-origin", origin);
+        headers.insert("vary", &vary);
+        if policy.allow_credentials {
+            headers.insert("access-control-allow-credentials", "true");
+        }
+        if preflight {
+            headers.insert("access-control-allow-methods", CorsPolicy::ALLOWED_METHODS);
+            headers.insert("access-control-allow-headers", &policy.allowed_headers_value());
+            headers.insert("access-control-max-age", &policy.max_age_seconds.to_string());
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for CorsPolicyMiddleware {
+    async fn handle(&self, context: &mut HttpContext, next: Next<'_>) -> Result<(), PipelineError> {
+        let origin = context.request().headers().get("origin").map(str::to_string);
+        if origin.is_none() {
+            return next.run(context).await;
+        }
+
+        let policy = match Self::load_policy(context).await {
+            Ok(policy) => policy,
+            Err(error) => {
+                log::warn!("Failed to load CORS policy, skipping CORS headers: {:?}", error);
+                return next.run(context).await;
+            }
+        };
+
+        let host = context.request().headers().get("host").map(str::to_string);
+        let preflight = context.request().method() == "OPTIONS"
+            && context.request().headers().get(Self::PREFLIGHT_METHOD_HEADER).is_some();
+
+        match policy.decide(origin.as_deref(), host.as_deref()) {
+            CorsDecision::Reject => {
+                log::debug!("Rejected request from origin {:?}", origin);
+                context.response_mut().set_status(403);
+                Ok(())
+            }
+            CorsDecision::Allow(origin) if preflight => {
+                context.response_mut().set_status(204);
+                Self::apply_headers(context, &policy, &origin, true);
+                Ok(())
+            }
+            CorsDecision::Allow(origin) => {
+                let result = next.run(context).await;
+                Self::apply_headers(context, &policy, &origin, false);
+                result
+            }
+            CorsDecision::Skip if preflight => {
+                context.response_mut().set_status(204);
+                Ok(())
+            }
+            CorsDecision::Skip => next.run(context).await,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::prelude::*;
+use nimble_web::ResponseBody;
+
+pub struct ErrorResponseMiddleware;
+
+impl ErrorResponseMiddleware {
+    const CONTENT_TYPE: &'static str = "application/json";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    // An explicit ApiError wins, then a client error status the handler set. Controllers map repository
+    // errors with `or_fail`, so anything else is an unexpected failure and its text stays in the logs.
+    pub fn resolve(context: &HttpContext) -> ApiError {
+        if let Some(api_error) = context.get::<ApiError>() {
+            return api_error.clone();
+        }
+        let status = context.response().status();
+        if (400..500).contains(&status) {
+            return ApiError::from_status(status);
+        }
+        ApiError::internal("Internal server error")
+    }
+}
+
+#[async_trait]
+impl Middleware for ErrorResponseMiddleware {
+    async fn handle(&self, context: &mut HttpContext, next: Next<'_>) -> Result<(), PipelineError> {
+        let Err(error) = next.run(context).await else {
+            return Ok(());
+        };
+
+        let api_error = Self::resolve(context);
+        if api_error.status() >= 500 {
+            log::error!("Request {} failed: {:?}", context.request().path(), error);
+        }
+
+        let body = serde_json::to_string(&api_error.body()).unwrap_or_default();
+        let response = context.response_mut();
+        response.set_status(api_error.status());
+        response.headers_mut().insert("content-type", Self::CONTENT_TYPE);
+        response.set_body(ResponseBody::Text(body));
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use crate::prelude::*;
+use nimble_web::ResponseBody;
+
+pub struct FeedMiddleware;
+
+impl FeedMiddleware {
+    const CACHE_CONTROL: &'static str = "public, max-age=300";
+    const DEFAULT_HOST: &'static str = "localhost";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Feeds are consumed off-site, so links have to be absolute; proxies report the public origin.
+    pub fn base_url(proto: Option<&str>, host: Option<&str>) -> String {
+        let first = |value: &str| value.split(',').next().unwrap_or_default().trim().to_string();
+        let proto =
+            proto.map(first).filter(|proto| proto == "http" || proto == "https").unwrap_or_else(|| "http".to_string());
+        let host = host
+            .map(first)
+            .filter(|host| !host.is_empty() && !host.contains(['/', '\\', '"', '<', '>', ' ']))
+            .unwrap_or_else(|| Self::DEFAULT_HOST.to_string());
+        format!("{}://{}", proto, host)
+    }
+
+    async fn serve(context: &mut HttpContext, path: &str) -> Result<(), PipelineError> {
+        if !context.service::<SettingService>()?.is_site_public().await? {
+            context.response_mut().set_status(404);
+            return Ok(());
+        }
+
+        let base_url = context.public_base_url();
+        let headers = context.request().headers();
+        let if_none_match = headers.get("if-none-match").map(str::to_string);
+        let if_modified_since = headers.get("if-modified-since").map(str::to_string);
+        let enclosure_type = context.derivative_profile().await.thumbnail_format.content_type();
+
+        let document = context.service::<FeedService>()?.document(path, &base_url, enclosure_type).await?;
+        let not_modified = document.is_not_modified(if_none_match.as_deref(), if_modified_since.as_deref());
+
+        let response = context.response_mut();
+        response.headers_mut().insert("etag", &document.etag);
+        response.headers_mut().insert("last-modified", &document.last_modified_header());
+        response.headers_mut().insert("cache-control", Self::CACHE_CONTROL);
+        if not_modified {
+            response.set_status(304);
+            return Ok(());
+        }
+        response.set_status(200);
+        response.headers_mut().insert("content-type", AtomFeed::CONTENT_TYPE);
+        response.set_body(ResponseBody::Text(document.body.clone()));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Middleware for FeedMiddleware {
+    async fn handle(&self, context: &mut HttpContext, next: Next<'_>) -> Result<(), PipelineError> {
+        let path = context.request().path().to_string();
+        if context.request().method() == "GET" && FeedService::is_feed_path(&path) {
+            return Self::serve(context, &path).await;
+        }
+
+        next.run(context).await
+    }
+}
@@ -0,0 +1,96 @@
+use crate::prelude::*;
+use nimble_web::ResponseBody;
+
+pub struct MetricsMiddleware;
+
+impl MetricsMiddleware {
+    const METRICS_PATH: &'static str = "/metrics";
+    const BEARER_PREFIX: &'static str = "Bearer ";
+    const OTHER_ROUTE: &'static str = "other";
+    const SERVER_ERROR_STATUS: u16 = 500;
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Labels use the registered route template so the series count stays bounded; static files and
+    // unknown paths all share one bucket.
+    fn route_label(context: &HttpContext) -> String {
+        let request = context.request();
+        context
+            .service::<ApiDocService>()
+            .ok()
+            .and_then(|docs| docs.route_template(&request.method().to_string(), request.path()).map(str::to_string))
+            .unwrap_or_else(|| Self::OTHER_ROUTE.to_string())
+    }
+
+    fn is_authorized(context: &HttpContext) -> bool {
+        let config = context.app_config();
+        let Some(expected) = config.metrics_token.as_deref() else {
+            return false;
+        };
+
+        let header_token = context
+            .request()
+            .headers()
+            .get("authorization")
+            .and_then(|header| header.strip_prefix(Self::BEARER_PREFIX))
+            .map(str::to_string);
+        let query_token = context.request().query_params().get("token").cloned();
+
+        header_token.or(query_token).map(|token| token == expected).unwrap_or(false)
+    }
+
+    fn serve_metrics(context: &mut HttpContext) -> Result<(), PipelineError> {
+        if !Self::is_authorized(context) {
+            context.response_mut().set_status(404);
+            return Ok(());
+        }
+
+        let metrics = context.service::<MetricsService>()?;
+        if let Ok(runner) = context.service::<BackgroundTaskRunner>() {
+            metrics.set_gauge(MetricNames::BACKGROUND_QUEUE_DEPTH, &[], runner.queued_count() as f64);
+            metrics.set_gauge(MetricNames::BACKGROUND_RUNNING_TASKS, &[], runner.running_count() as f64);
+        }
+        if let Ok(coordinator) = context.service::<PreviewCoordinator>() {
+            metrics.set_gauge(
+                MetricNames::PREVIEW_EXTRACTIONS_IN_FLIGHT,
+                &[],
+                coordinator.running_extractions() as f64,
+            );
+        }
+
+        let body = metrics.render();
+        let response = context.response_mut();
+        response.set_status(200);
+        response.headers_mut().insert("content-type", MetricsService::CONTENT_TYPE);
+        response.set_body(ResponseBody::Text(body));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Middleware for MetricsMiddleware {
+    async fn handle(&self, context: &mut HttpContext, next: Next<'_>) -> Result<(), PipelineError> {
+        if context.request().method() == "GET" && context.request().path() == Self::METRICS_PATH {
+            return Self::serve_metrics(context);
+        }
+
+        let started = Instant::now();
+        let method = context.request().method().to_string();
+        let route = Self::route_label(context);
+
+        let result = next.run(context).await;
+
+        let status = if result.is_ok() { context.response().status() } else { Self::SERVER_ERROR_STATUS };
+        if let Ok(metrics) = context.service::<MetricsService>() {
+            metrics.observe(
+                MetricNames::HTTP_REQUEST_DURATION_SECONDS,
+                &[("method", &method), ("route", &route), ("status", &status.to_string())],
+                started.elapsed().as_secs_f64(),
+            );
+        }
+
+        result
+    }
+}
@@ -1,5 +1,21 @@
+pub mod api_docs_middleware;
+pub mod compression_middleware;
+pub mod cors_policy_middleware;
+pub mod error_response_middleware;
+pub mod feed_middleware;
+pub mod metrics_middleware;
 pub mod public_middleware;
+pub mod request_logging_middleware;
+pub mod revoked_subject_middleware;
 pub mod static_file_middleware;
 
+pub use api_docs_middleware::ApiDocsMiddleware;
+pub use compression_middleware::CompressionMiddleware;
+pub use cors_policy_middleware::CorsPolicyMiddleware;
+pub use error_response_middleware::ErrorResponseMiddleware;
+pub use feed_middleware::FeedMiddleware;
+pub use metrics_middleware::MetricsMiddleware;
 pub use public_middleware::PublicAccessMiddleware;
+pub use request_logging_middleware::RequestLoggingMiddleware;
+pub use revoked_subject_middleware::RevokedSubjectMiddleware;
 pub use static_file_middleware::StaticFileMiddleware;
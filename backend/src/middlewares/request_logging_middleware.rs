@@ -0,0 +1,111 @@
+use crate::prelude::*;
+
+pub struct RequestLoggingMiddleware;
+
+impl RequestLoggingMiddleware {
+    const LOG_TARGET: &'static str = "nimble_photos::request";
+    const ERROR_STATUS_THRESHOLD: u16 = 400;
+    const SERVER_ERROR_STATUS: u16 = 500;
+    const UNLOGGED_PATH_PREFIXES: [&'static str; 2] = ["/api/auth/", "/api/setup/"];
+    const SECRET_KEY_MARKERS: [&'static str; 3] = ["password", "token", "secret"];
+    const SECRET_KEYS: [&'static str; 2] = ["code", "backupcode"];
+    const REDACTED: &'static str = "[redacted]";
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn should_log_body(context: &HttpContext) -> bool {
+        context.app_config().log_request_body_on_error
+    }
+
+    fn body_excerpt(context: &HttpContext) -> Option<String> {
+        let max_bytes = context.app_config().request_body_max_bytes;
+
+        let bytes = match context.request().body() {
+            RequestBody::Text(text) => text.as_bytes(),
+            RequestBody::Bytes(bytes) => bytes.as_slice(),
+            _ => return None,
+        };
+        Self::loggable_body(context.request().path(), bytes, max_bytes)
+    }
+
+    // Sign-in and setup bodies are never logged; elsewhere JSON fields that look like credentials are masked
+    // before truncating, so a cut never leaves half a secret behind.
+    pub fn loggable_body(path: &str, bytes: &[u8], max_bytes: usize) -> Option<String> {
+        if Self::UNLOGGED_PATH_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+            return None;
+        }
+
+        let body = match serde_json::from_slice::<JsonValue>(bytes) {
+            Ok(mut value) => {
+                Self::redact(&mut value);
+                value.to_string()
+            }
+            Err(_) => String::from_utf8_lossy(bytes).into_owned(),
+        };
+        let mut end = body.len().min(max_bytes);
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        Some(body[..end].to_string())
+    }
+
+    fn redact(value: &mut JsonValue) {
+        match value {
+            JsonValue::Object(fields) => {
+                for (key, field) in fields.iter_mut() {
+                    if Self::is_secret_key(key) {
+                        *field = JsonValue::String(Self::REDACTED.to_string());
+                    } else {
+                        Self::redact(field);
+                    }
+                }
+            }
+            JsonValue::Array(items) => items.iter_mut().for_each(Self::redact),
+            _ => {}
+        }
+    }
+
+    fn is_secret_key(key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+        Self::SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker)) || Self::SECRET_KEYS.contains(&key.as_str())
+    }
+}
+
+#[async_trait]
+impl Middleware for RequestLoggingMiddleware {
+    async fn handle(&self, context: &mut HttpContext, next: Next<'_>) -> Result<(), PipelineError> {
+        let request_id = RequestId::from_header(context.request().headers().get(RequestId::HEADER));
+        let method = context.request().method().to_string();
+        let path = context.request().path().to_string();
+        let started = Instant::now();
+
+        context.insert(request_id.clone());
+
+        let result = next.run(context).await;
+
+        let status = if result.is_ok() { context.response().status() } else { Self::SERVER_ERROR_STATUS };
+        context.response_mut().headers_mut().insert(RequestId::HEADER, request_id.as_str());
+
+        let user_id = context.current_user_id().map(|id| id.to_string()).unwrap_or_else(|_| "-".to_string());
+        log::info!(
+            target: Self::LOG_TARGET,
+            "request_id={} method={} path={} status={} duration_ms={} user_id={}",
+            request_id.as_str(),
+            method,
+            path,
+            status,
+            started.elapsed().as_millis(),
+            user_id
+        );
+
+        if status >= Self::ERROR_STATUS_THRESHOLD && Self::should_log_body(context) {
+            if let Some(body) = Self::body_excerpt(context) {
+                log::warn!(target: Self::LOG_TARGET, "request_id={} body={}", request_id.as_str(), body);
+            }
+        }
+
+        result
+    }
+}
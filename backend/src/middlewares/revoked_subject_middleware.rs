@@ -0,0 +1,29 @@
+use crate::prelude::*;
+
+pub struct RevokedSubjectMiddleware;
+
+impl RevokedSubjectMiddleware {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Middleware for RevokedSubjectMiddleware {
+    async fn handle(&self, context: &mut HttpContext, next: Next<'_>) -> Result<(), PipelineError> {
+        let subject = context
+            .get::<IdentityContext>()
+            .filter(|ctx| ctx.is_authenticated())
+            .map(|ctx| ctx.identity().subject().to_string());
+
+        if let (Some(subject), Ok(registry)) = (subject, context.service::<RevokedSubjectRegistry>()) {
+            if registry.is_revoked(&subject) {
+                log::debug!("Rejected token for revoked subject {}.", subject);
+                context.response_mut().set_status(401);
+                return Ok(());
+            }
+        }
+
+        next.run(context).await
+    }
+}
@@ -1,60 +1,28 @@
 use crate::prelude::*;
 
+#[derive(Default)]
 pub struct StaticFileMiddleware {
-    root: PathBuf,
+    root: Option<PathBuf>,
 }
 
 impl StaticFileMiddleware {
     pub fn new<P: Into<PathBuf>>(root: P) -> Self {
-        Self { root: root.into() }
+        Self { root: Some(root.into()) }
     }
 
-    fn resolve_default_root() -> PathBuf {
-        let mut candidates = vec![PathBuf::from("www"), PathBuf::from("backend/www")];
-
-        if let Ok(exe) = std::env::current_exe() {
-            if let Some(parent) = exe.parent() {
-                candidates.push(parent.join("www"));
-            }
-        }
-
-        for candidate in candidates {
-            if candidate.join("index.html").exists() {
-                log::info!("Using static root: {}", candidate.display());
-                return candidate;
-            }
-        }
-
-        PathBuf::from("www")
-    }
-
-    fn normalize_request_path(path: &str) -> Option<PathBuf> {
-        let trimmed = path.trim_start_matches('/');
-        if trimmed.is_empty() {
-            return Some(PathBuf::from("index.html"));
+    async fn web_root(&self, context: &HttpContext) -> Option<PathBuf> {
+        if let Some(root) = &self.root {
+            return Some(root.clone());
         }
 
-        let mut normalized = PathBuf::new();
-        for component in Path::new(trimmed).components() {
-            match component {
-                Component::Normal(segment) => normalized.push(segment),
-                Component::CurDir => {}
-                _ => return None,
+        let settings = context.service::<SettingService>().ok()?;
+        match settings.web_root().await {
+            Ok(configured) => AssetsController::resolve_web_root(&configured),
+            Err(error) => {
+                log::warn!("Failed to load web root setting: {:?}", error);
+                None
             }
         }
-
-        Some(normalized)
-    }
-
-    fn should_try_spa_fallback(path: &str) -> bool {
-        let leaf = path.rsplit('/').next().unwrap_or_default();
-        !leaf.contains('.')
-    }
-}
-
-impl Default for StaticFileMiddleware {
-    fn default() -> Self {
-        Self::new(Self::resolve_default_root())
     }
 }
 
@@ -66,34 +34,25 @@ impl Middleware for StaticFileMiddleware {
             return next.run(context).await;
         }
 
-        let request_path = context.request().path();
-        if request_path.starts_with("/api") {
+        let request_path = context.request().path().to_string();
+        if let Some(hash) = AssetsController::share_hash(&request_path) {
+            return AssetsController::serve_share_page(context, &hash).await;
+        }
+        if AssetsController::is_api_path(&request_path) {
             return next.run(context).await;
         }
 
-        let Some(normalized_path) = Self::normalize_request_path(request_path) else {
-            context.response_mut().set_status(400);
-            return Ok(());
+        let Some(root) = self.web_root(context).await else {
+            return next.run(context).await;
         };
 
-        let mut file_path = self.root.join(normalized_path);
-        if file_path.is_dir() {
-            file_path = file_path.join("index.html");
-        }
-
-        if !file_path.exists() {
-            if Self::should_try_spa_fallback(request_path) {
-                file_path = self.root.join("index.html");
-            } else {
-                return next.run(context).await;
+        match AssetsController::web_asset_path(&root, &request_path) {
+            Ok(Some(path)) => {
+                AssetsController::serve_web_asset(context, path);
+                Ok(())
             }
+            Ok(None) => next.run(context).await,
+            Err(error) => Err(context.fail(error)),
         }
-
-        if !file_path.exists() {
-            return next.run(context).await;
-        }
-
-        FileResponse::from_path(file_path).into_response(context);
-        Ok(())
     }
 }
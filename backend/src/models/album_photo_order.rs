@@ -0,0 +1,41 @@
+use crate::prelude::*;
+
+pub struct AlbumPhotoOrder;
+
+impl AlbumPhotoOrder {
+    pub const NOT_A_PERMUTATION: &'static str = "photoIds must list every photo in the album exactly once";
+    pub const UNKNOWN_PHOTO: &'static str = "photoId is not in the album";
+    pub const UNKNOWN_ANCHOR: &'static str = "beforePhotoId is not in the album";
+
+    pub fn validate(current: &[Uuid], requested: &[Uuid]) -> Result<(), &'static str> {
+        if current.len() != requested.len() {
+            return Err(Self::NOT_A_PERMUTATION);
+        }
+
+        let expected: HashSet<&Uuid> = current.iter().collect();
+        let mut seen: HashSet<&Uuid> = HashSet::with_capacity(requested.len());
+        if requested.iter().all(|id| expected.contains(id) && seen.insert(id)) {
+            Ok(())
+        } else {
+            Err(Self::NOT_A_PERMUTATION)
+        }
+    }
+
+    pub fn move_before(current: &[Uuid], photo_id: Uuid, before: Option<Uuid>) -> Result<Vec<Uuid>, &'static str> {
+        if !current.contains(&photo_id) {
+            return Err(Self::UNKNOWN_PHOTO);
+        }
+        if before.is_some_and(|anchor| !current.contains(&anchor)) {
+            return Err(Self::UNKNOWN_ANCHOR);
+        }
+
+        let mut order: Vec<Uuid> = current.iter().copied().filter(|id| *id != photo_id).collect();
+        let index = match before {
+            Some(anchor) if anchor == photo_id => current.iter().position(|id| *id == photo_id).unwrap_or(order.len()),
+            Some(anchor) => order.iter().position(|id| *id == anchor).unwrap_or(order.len()),
+            None => order.len(),
+        };
+        order.insert(index, photo_id);
+        Ok(order)
+    }
+}
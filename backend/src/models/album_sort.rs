@@ -0,0 +1,73 @@
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AlbumSortField {
+    #[default]
+    CreateDate,
+    Title,
+    PhotoCount,
+}
+
+impl AlbumSortField {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "createdate" | "create_date" | "date" => Some(Self::CreateDate),
+            "title" | "name" => Some(Self::Title),
+            "photocount" | "photo_count" | "count" => Some(Self::PhotoCount),
+            _ => None,
+        }
+    }
+
+    fn expression(&self, alias: &str) -> String {
+        match self {
+            Self::CreateDate => format!("{}.create_date", alias),
+            Self::Title => format!("lower({}.name)", alias),
+            Self::PhotoCount => format!("{}.photo_count", alias),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AlbumListQuery {
+    pub search: Option<String>,
+    pub sort: AlbumSortField,
+    pub direction: Option<SortDirection>,
+}
+
+impl AlbumListQuery {
+    pub const INVALID_SORT: &'static str = "invalid sort";
+    pub const INVALID_DIRECTION: &'static str = "invalid sort direction";
+
+    pub fn parse(search: Option<&str>, sort: Option<&str>, direction: Option<&str>) -> Result<Self, &'static str> {
+        let sort = match sort.map(str::trim).filter(|value| !value.is_empty()) {
+            Some(value) => AlbumSortField::parse(value).ok_or(Self::INVALID_SORT)?,
+            None => AlbumSortField::default(),
+        };
+        let direction = match direction.map(str::trim).filter(|value| !value.is_empty()) {
+            Some(value) => Some(SortDirection::parse(value).ok_or(Self::INVALID_DIRECTION)?),
+            None => None,
+        };
+        let search = search.map(str::trim).filter(|value| !value.is_empty()).map(str::to_string);
+        Ok(Self { search, sort, direction })
+    }
+
+    pub fn direction(&self) -> SortDirection {
+        self.direction.unwrap_or(match self.sort {
+            AlbumSortField::Title => SortDirection::Asc,
+            _ => SortDirection::Desc,
+        })
+    }
+
+    pub fn search_pattern(&self) -> Option<String> {
+        self.search.as_deref().map(|value| format!("%{}%", BrowseOptions::escape_like(value)))
+    }
+
+    pub fn order_clause(&self, alias: &str) -> String {
+        let direction = match self.direction() {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        format!("{} {direction} NULLS LAST, {alias}.id {direction}", self.sort.expression(alias))
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    BadRequest(String),
+    NotFound(String),
+    Forbidden(String),
+    Conflict(String),
+    Internal(String),
+    Unavailable(String),
+    Invalid(Vec<FieldError>),
+    EmailNotVerified(String),
+    TooManyRequests(String),
+}
+
+impl ApiError {
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::BadRequest(message.into())
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::NotFound(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::Conflict(message.into())
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal(message.into())
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::Unavailable(message.into())
+    }
+
+    pub fn invalid(errors: Vec<FieldError>) -> Self {
+        Self::Invalid(errors)
+    }
+
+    pub fn email_not_verified(message: impl Into<String>) -> Self {
+        Self::EmailNotVerified(message.into())
+    }
+
+    pub fn too_many_requests(message: impl Into<String>) -> Self {
+        Self::TooManyRequests(message.into())
+    }
+
+    // Handlers that only set a status before failing get the generic message for that status.
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            400 => Self::bad_request("Bad request"),
+            403 => Self::forbidden("Forbidden"),
+            404 => Self::not_found("Not found"),
+            409 => Self::conflict("Conflict"),
+            503 => Self::unavailable("Service unavailable"),
+            _ => Self::internal("Internal server error"),
+        }
+    }
+
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::BadRequest(_) | Self::Invalid(_) => 400,
+            Self::Forbidden(_) | Self::EmailNotVerified(_) => 403,
+            Self::NotFound(_) => 404,
+            Self::Conflict(_) => 409,
+            Self::TooManyRequests(_) => 429,
+            Self::Internal(_) => 500,
+            Self::Unavailable(_) => 503,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BadRequest(_) => "bad_request",
+            Self::Forbidden(_) => "forbidden",
+            Self::NotFound(_) => "not_found",
+            Self::Conflict(_) => "conflict",
+            Self::Internal(_) => "internal",
+            Self::Unavailable(_) => "unavailable",
+            Self::Invalid(_) => "validation_failed",
+            Self::EmailNotVerified(_) => "email_not_verified",
+            Self::TooManyRequests(_) => "too_many_requests",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::BadRequest(message)
+            | Self::NotFound(message)
+            | Self::Forbidden(message)
+            | Self::Conflict(message)
+            | Self::Internal(message)
+            | Self::Unavailable(message)
+            | Self::EmailNotVerified(message)
+            | Self::TooManyRequests(message) => message,
+            Self::Invalid(errors) => errors.first().map(|error| error.message.as_str()).unwrap_or("Invalid request"),
+        }
+    }
+
+    pub fn body(&self) -> ApiErrorBody {
+        let body = ApiErrorBody { code: self.code().to_string(), message: self.message().to_string(), details: None };
+        match self {
+            Self::Invalid(errors) => body.with_details(json!(errors)),
+            _ => body,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ApiError> for PipelineError {
+    fn from(error: ApiError) -> Self {
+        PipelineError::message(error.message())
+    }
+}
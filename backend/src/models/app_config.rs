@@ -0,0 +1,319 @@
+use crate::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AppEnvironment {
+    #[default]
+    Development,
+    Production,
+}
+
+impl AppEnvironment {
+    pub fn parse(value: Option<&str>) -> Option<Self> {
+        match value.map(|value| value.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("development") | Some("dev") => Some(Self::Development),
+            Some("production") | Some("prod") => Some(Self::Production),
+            Some(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Development => "development",
+            Self::Production => "production",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppConfigError {
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for AppConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration: {}", self.errors.join("; "))
+    }
+}
+
+impl std::error::Error for AppConfigError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppConfig {
+    pub environment: AppEnvironment,
+    pub secrets_file: PathBuf,
+    pub jwt_secret: Option<String>,
+    pub jwt_issuer: String,
+    pub cursor_secret: Option<String>,
+    pub encryption_key: Option<String>,
+    pub eventbus_capacity: usize,
+    pub background_parallelism: usize,
+    pub upload_max_file_size_bytes: u64,
+    pub upload_chunk_size_bytes: u64,
+    pub upload_session_ttl_minutes: u64,
+    pub disk_refresh_seconds: u64,
+    pub allow_symlink_escape: bool,
+    pub preview_max_concurrent_extractions: usize,
+    pub preview_extraction_timeout_seconds: u64,
+    pub pipeline_max_attempts: i32,
+    pub pipeline_job_retention_days: i64,
+    pub thumbnail_base_path: PathBuf,
+    pub album_auto_gap_hours: i64,
+    pub shutdown_grace_seconds: u64,
+    pub database_startup_retries: u32,
+    pub database_retry_delay_ms: u64,
+    pub metrics_token: Option<String>,
+    pub exiftool_path: Option<PathBuf>,
+    pub log_level: Option<log::LevelFilter>,
+    pub log_request_body_on_error: bool,
+    pub request_body_max_bytes: usize,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_from: Option<String>,
+    pub smtp_starttls: bool,
+    pub live_enabled: bool,
+    pub live_address: String,
+    pub live_max_connections_per_user: usize,
+    pub warnings: Vec<String>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        let parallelism = Self::default_parallelism();
+        Self {
+            environment: AppEnvironment::Development,
+            secrets_file: PathBuf::from(Self::DEFAULT_SECRETS_FILE),
+            jwt_secret: None,
+            jwt_issuer: Self::DEFAULT_JWT_ISSUER.to_string(),
+            cursor_secret: None,
+            encryption_key: None,
+            eventbus_capacity: Self::DEFAULT_EVENTBUS_CAPACITY,
+            background_parallelism: parallelism,
+            upload_max_file_size_bytes: Self::DEFAULT_UPLOAD_MAX_FILE_SIZE_BYTES,
+            upload_chunk_size_bytes: Self::DEFAULT_UPLOAD_CHUNK_SIZE_BYTES,
+            upload_session_ttl_minutes: Self::DEFAULT_UPLOAD_SESSION_TTL_MINUTES,
+            disk_refresh_seconds: DiskInfoService::DEFAULT_REFRESH_INTERVAL_SECONDS,
+            allow_symlink_escape: false,
+            preview_max_concurrent_extractions: parallelism,
+            preview_extraction_timeout_seconds: Self::DEFAULT_PREVIEW_TIMEOUT_SECONDS,
+            pipeline_max_attempts: Self::DEFAULT_PIPELINE_MAX_ATTEMPTS,
+            pipeline_job_retention_days: Self::DEFAULT_PIPELINE_JOB_RETENTION_DAYS,
+            thumbnail_base_path: PathBuf::from(format!("./{}", SettingConsts::THUMBNAIL_FOLDER)),
+            album_auto_gap_hours: EventAlbumService::DEFAULT_GAP_HOURS,
+            shutdown_grace_seconds: Self::DEFAULT_SHUTDOWN_GRACE_SECONDS,
+            database_startup_retries: Self::DEFAULT_DATABASE_STARTUP_RETRIES,
+            database_retry_delay_ms: Self::DEFAULT_DATABASE_RETRY_DELAY_MS,
+            metrics_token: None,
+            exiftool_path: None,
+            log_level: None,
+            log_request_body_on_error: false,
+            request_body_max_bytes: Self::DEFAULT_REQUEST_BODY_MAX_BYTES,
+            smtp_host: None,
+            smtp_port: Self::DEFAULT_SMTP_PORT,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_from: None,
+            smtp_starttls: true,
+            live_enabled: true,
+            live_address: Self::DEFAULT_LIVE_ADDRESS.to_string(),
+            live_max_connections_per_user: Self::DEFAULT_LIVE_MAX_CONNECTIONS_PER_USER,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl AppConfig {
+    pub const DEFAULT_SECRETS_FILE: &'static str = ".nimble/secrets.json";
+    pub const DEFAULT_JWT_ISSUER: &'static str = "nimble";
+    pub const DEFAULT_EVENTBUS_CAPACITY: usize = 256;
+    pub const DEFAULT_UPLOAD_MAX_FILE_SIZE_BYTES: u64 = 64 * 1024 * 1024;
+    pub const DEFAULT_UPLOAD_CHUNK_SIZE_BYTES: u64 = 8 * 1024 * 1024;
+    pub const DEFAULT_UPLOAD_SESSION_TTL_MINUTES: u64 = 24 * 60;
+    pub const DEFAULT_PREVIEW_TIMEOUT_SECONDS: u64 = 10;
+    pub const DEFAULT_PIPELINE_MAX_ATTEMPTS: i32 = 3;
+    pub const DEFAULT_PIPELINE_JOB_RETENTION_DAYS: i64 = 7;
+    pub const DEFAULT_SHUTDOWN_GRACE_SECONDS: u64 = 30;
+    pub const DEFAULT_REQUEST_BODY_MAX_BYTES: usize = 2048;
+    pub const DEFAULT_DATABASE_STARTUP_RETRIES: u32 = 10;
+    pub const DEFAULT_DATABASE_RETRY_DELAY_MS: u64 = 1000;
+    pub const DEFAULT_SMTP_PORT: u16 = 587;
+    pub const DEFAULT_LIVE_ADDRESS: &'static str = "0.0.0.0:5152";
+    pub const DEFAULT_LIVE_MAX_CONNECTIONS_PER_USER: usize = 4;
+
+    pub fn from_configuration(config: &Configuration) -> Result<Self, AppConfigError> {
+        Self::from_lookup(|key| config.get(key).map(ToString::to_string))
+    }
+
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, AppConfigError> {
+        let mut reader = ConfigReader { lookup: &lookup, errors: Vec::new(), warnings: Vec::new() };
+        let defaults = Self::default();
+
+        let environment = match reader.text("app.environment", &[]) {
+            Some(value) => AppEnvironment::parse(Some(&value)).unwrap_or_else(|| {
+                reader.errors.push(format!("app.environment: expected development or production, got '{}'", value));
+                defaults.environment
+            }),
+            None => defaults.environment,
+        };
+
+        let log_level = reader.text("logging.level", &[]).and_then(|value| match value.parse::<log::LevelFilter>() {
+            Ok(level) => Some(level),
+            Err(_) => {
+                reader
+                    .errors
+                    .push(format!("logging.level: expected off, error, warn, info, debug or trace, got '{}'", value));
+                None
+            }
+        });
+
+        let config = Self {
+            environment,
+            secrets_file: reader.text("app.secretsFile", &[]).map(PathBuf::from).unwrap_or(defaults.secrets_file),
+            jwt_secret: reader.text("jwt.secret", &[]),
+            jwt_issuer: reader.text("jwt.issuer", &[]).unwrap_or(defaults.jwt_issuer),
+            cursor_secret: reader.text("cursor.secret", &[]),
+            encryption_key: reader.text("encryption.key", &[]),
+            eventbus_capacity: reader.positive("eventbus.capacity", &[], defaults.eventbus_capacity),
+            background_parallelism: reader.positive("background.parallelism", &[], defaults.background_parallelism),
+            upload_max_file_size_bytes: reader.positive(
+                "upload.maxFileSizeBytes",
+                &["upload.max_file_size_bytes"],
+                defaults.upload_max_file_size_bytes,
+            ),
+            upload_chunk_size_bytes: reader.positive("upload.chunkSizeBytes", &[], defaults.upload_chunk_size_bytes),
+            upload_session_ttl_minutes: reader.positive(
+                "upload.sessionTtlMinutes",
+                &[],
+                defaults.upload_session_ttl_minutes,
+            ),
+            disk_refresh_seconds: reader.parse("storage.diskRefreshSeconds", &[], defaults.disk_refresh_seconds),
+            allow_symlink_escape: reader.flag("storage.allowSymlinkEscape", &[], defaults.allow_symlink_escape),
+            preview_max_concurrent_extractions: reader.positive(
+                "preview.maxConcurrentExtractions",
+                &[],
+                defaults.preview_max_concurrent_extractions,
+            ),
+            preview_extraction_timeout_seconds: reader.parse(
+                "preview.extractionTimeoutSeconds",
+                &[],
+                defaults.preview_extraction_timeout_seconds,
+            ),
+            pipeline_max_attempts: reader.positive("pipeline.maxAttempts", &[], defaults.pipeline_max_attempts),
+            pipeline_job_retention_days: reader.positive(
+                "pipeline.jobRetentionDays",
+                &[],
+                defaults.pipeline_job_retention_days,
+            ),
+            thumbnail_base_path: reader
+                .text("thumbnail.basePath", &["thumbnail.base.path"])
+                .map(PathBuf::from)
+                .unwrap_or(defaults.thumbnail_base_path),
+            album_auto_gap_hours: reader.positive("albums.autoGapHours", &[], defaults.album_auto_gap_hours),
+            shutdown_grace_seconds: reader.parse("shutdown.graceSeconds", &[], defaults.shutdown_grace_seconds),
+            database_startup_retries: reader.parse("database.startupRetries", &[], defaults.database_startup_retries),
+            database_retry_delay_ms: reader.positive("database.retryDelayMs", &[], defaults.database_retry_delay_ms),
+            metrics_token: reader.text("metrics.token", &[]),
+            exiftool_path: reader.text("metadata.exiftoolPath", &[]).map(PathBuf::from),
+            log_level,
+            log_request_body_on_error: reader.flag(
+                "logging.requestBodyOnError",
+                &[],
+                defaults.log_request_body_on_error,
+            ),
+            request_body_max_bytes: reader.parse("logging.requestBodyMaxBytes", &[], defaults.request_body_max_bytes),
+            smtp_host: reader.text("email.smtpHost", &[]),
+            smtp_port: reader.positive("email.smtpPort", &[], defaults.smtp_port),
+            smtp_username: reader.text("email.smtpUsername", &[]),
+            smtp_password: reader.text("email.smtpPassword", &[]),
+            smtp_from: reader.text("email.from", &[]),
+            smtp_starttls: reader.flag("email.startTls", &[], defaults.smtp_starttls),
+            live_enabled: reader.flag("live.enabled", &[], defaults.live_enabled),
+            live_address: reader.text("live.address", &[]).unwrap_or(defaults.live_address),
+            live_max_connections_per_user: reader.positive(
+                "live.maxConnectionsPerUser",
+                &[],
+                defaults.live_max_connections_per_user,
+            ),
+            warnings: Vec::new(),
+        };
+
+        if !reader.errors.is_empty() {
+            return Err(AppConfigError { errors: reader.errors });
+        }
+        Ok(Self { warnings: reader.warnings, ..config })
+    }
+
+    fn default_parallelism() -> usize {
+        std::thread::available_parallelism().map(|value| value.get()).unwrap_or(4)
+    }
+}
+
+struct ConfigReader<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl ConfigReader<'_> {
+    fn raw(&mut self, key: &str, legacy: &[&str]) -> Option<(String, String)> {
+        if let Some(value) = (self.lookup)(key) {
+            return Some((key.to_string(), value));
+        }
+        for legacy_key in legacy {
+            if let Some(value) = (self.lookup)(legacy_key) {
+                self.warnings.push(format!("{} is deprecated, use {} instead", legacy_key, key));
+                return Some((legacy_key.to_string(), value));
+            }
+        }
+        None
+    }
+
+    fn text(&mut self, key: &str, legacy: &[&str]) -> Option<String> {
+        self.raw(key, legacy).map(|(_, value)| value.trim().to_string()).filter(|value| !value.is_empty())
+    }
+
+    fn parse<T: FromStr>(&mut self, key: &str, legacy: &[&str], default: T) -> T {
+        self.parse_where(key, legacy, default, |_| true, "a number")
+    }
+
+    fn positive<T: FromStr + PartialOrd + Default>(&mut self, key: &str, legacy: &[&str], default: T) -> T {
+        self.parse_where(key, legacy, default, |value| *value > T::default(), "a positive number")
+    }
+
+    fn flag(&mut self, key: &str, legacy: &[&str], default: bool) -> bool {
+        match self.raw(key, legacy) {
+            Some((used, value)) => match value.trim().to_ascii_lowercase().parse::<bool>() {
+                Ok(value) => value,
+                Err(_) => {
+                    self.errors.push(format!("{}: expected true or false, got '{}'", used, value));
+                    default
+                }
+            },
+            None => default,
+        }
+    }
+
+    fn parse_where<T: FromStr>(
+        &mut self,
+        key: &str,
+        legacy: &[&str],
+        default: T,
+        valid: impl Fn(&T) -> bool,
+        expected: &str,
+    ) -> T {
+        let Some((used, value)) = self.raw(key, legacy) else {
+            return default;
+        };
+        match value.trim().parse::<T>() {
+            Ok(parsed) if valid(&parsed) => parsed,
+            _ => {
+                self.errors.push(format!("{}: expected {}, got '{}'", used, expected, value));
+                default
+            }
+        }
+    }
+}
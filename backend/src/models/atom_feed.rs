@@ -0,0 +1,123 @@
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtomLink {
+    pub href: String,
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtomEntry {
+    pub id: String,
+    pub title: String,
+    pub updated: DateTime<Utc>,
+    pub published: Option<DateTime<Utc>>,
+    pub summary: Option<String>,
+    pub link: String,
+    pub enclosure: Option<AtomLink>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AtomFeed {
+    pub id: String,
+    pub title: String,
+    pub self_link: String,
+    pub alternate_link: String,
+    pub entries: Vec<AtomEntry>,
+}
+
+impl AtomFeed {
+    pub const CONTENT_TYPE: &'static str = "application/atom+xml; charset=utf-8";
+
+    // An empty feed still needs a stable <updated>, otherwise every poll would look like a change.
+    pub fn updated(&self) -> DateTime<Utc> {
+        self.entries.iter().map(|entry| entry.updated).max().unwrap_or(DateTime::<Utc>::UNIX_EPOCH)
+    }
+
+    pub fn render(&self) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        xml.push_str(&format!("  <id>{}</id>\n", Markup::escape(&self.id)));
+        xml.push_str(&format!("  <title>{}</title>\n", Markup::escape(&self.title)));
+        xml.push_str(&format!("  <updated>{}</updated>\n", self.updated().to_rfc3339()));
+        xml.push_str(&format!(
+            "  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\"/>\n",
+            Markup::escape(&self.self_link)
+        ));
+        xml.push_str(&format!(
+            "  <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n",
+            Markup::escape(&self.alternate_link)
+        ));
+        xml.push_str("  <generator>Nimble Photos</generator>\n");
+        for entry in &self.entries {
+            xml.push_str("  <entry>\n");
+            xml.push_str(&format!("    <id>{}</id>\n", Markup::escape(&entry.id)));
+            xml.push_str(&format!("    <title>{}</title>\n", Markup::escape(&entry.title)));
+            xml.push_str(&format!("    <updated>{}</updated>\n", entry.updated.to_rfc3339()));
+            if let Some(published) = entry.published {
+                xml.push_str(&format!("    <published>{}</published>\n", published.to_rfc3339()));
+            }
+            xml.push_str(&format!("    <link rel=\"alternate\" href=\"{}\"/>\n", Markup::escape(&entry.link)));
+            if let Some(enclosure) = &entry.enclosure {
+                let content_type = enclosure
+                    .content_type
+                    .as_deref()
+                    .map(|content_type| format!(" type=\"{}\"", Markup::escape(content_type)))
+                    .unwrap_or_default();
+                xml.push_str(&format!(
+                    "    <link rel=\"enclosure\"{} href=\"{}\"/>\n",
+                    content_type,
+                    Markup::escape(&enclosure.href)
+                ));
+            }
+            if let Some(summary) = entry.summary.as_deref().filter(|summary| !summary.trim().is_empty()) {
+                xml.push_str(&format!("    <summary>{}</summary>\n", Markup::escape(summary.trim())));
+            }
+            xml.push_str("  </entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    pub fn document(&self) -> FeedDocument {
+        FeedDocument::new(self.render(), self.updated())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedDocument {
+    pub body: String,
+    pub etag: String,
+    pub last_modified: DateTime<Utc>,
+}
+
+impl FeedDocument {
+    const HTTP_DATE_FORMAT: &'static str = "%a, %d %b %Y %H:%M:%S GMT";
+
+    pub fn new(body: String, last_modified: DateTime<Utc>) -> Self {
+        let etag = format!("\"{:016x}\"", xxh3_64(body.as_bytes()));
+        Self { body, etag, last_modified }
+    }
+
+    pub fn last_modified_header(&self) -> String {
+        self.last_modified.format(Self::HTTP_DATE_FORMAT).to_string()
+    }
+
+    // If-None-Match wins over If-Modified-Since, as RFC 9110 requires.
+    pub fn is_not_modified(&self, if_none_match: Option<&str>, if_modified_since: Option<&str>) -> bool {
+        if let Some(if_none_match) = if_none_match {
+            return if_none_match
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == self.etag);
+        }
+        let Some(since) = if_modified_since
+            .and_then(|value| NaiveDateTime::parse_from_str(value.trim(), Self::HTTP_DATE_FORMAT).ok())
+        else {
+            return false;
+        };
+        self.last_modified.timestamp() <= since.and_utc().timestamp()
+    }
+}
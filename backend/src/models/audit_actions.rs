@@ -0,0 +1,44 @@
+pub struct AuditActions;
+
+impl AuditActions {
+    pub const SETTING_UPDATE: &'static str = "setting.update";
+    pub const STORAGE_CREATE: &'static str = "storage.create";
+    pub const STORAGE_UPDATE: &'static str = "storage.update";
+    pub const STORAGE_SET_DEFAULT: &'static str = "storage.setDefault";
+    pub const STORAGE_DELETE: &'static str = "storage.delete";
+    pub const STORAGE_CACHE_PRUNE: &'static str = "storage.cache.prune";
+    pub const PHOTO_DELETE: &'static str = "photo.delete";
+    pub const PHOTO_PATHS_REPAIR: &'static str = "photo.paths.repair";
+    pub const PHOTO_METADATA_UPDATE: &'static str = "photo.metadata.update";
+    pub const PHOTO_UNSTACK: &'static str = "photo.unstack";
+    pub const TAG_RULE_CREATE: &'static str = "tag.rule.create";
+    pub const TAG_RULE_DELETE: &'static str = "tag.rule.delete";
+    pub const TAG_RULES_APPLY: &'static str = "tag.rules.apply";
+    pub const PERSON_RENAME: &'static str = "person.rename";
+    pub const ALBUM_DELETE: &'static str = "album.delete";
+    pub const ALBUM_AUTO_GENERATE: &'static str = "album.autoGenerate";
+    pub const USER_ROLES_UPDATE: &'static str = "user.roles.update";
+    pub const USER_DELETE: &'static str = "user.delete";
+    pub const USER_TWO_FACTOR_DISABLE: &'static str = "user.twoFactor.disable";
+    pub const COMMENT_VISIBILITY_UPDATE: &'static str = "comment.visibility.update";
+    pub const CONFIG_RELOAD: &'static str = "config.reload";
+    pub const ASSETS_RELOCATE: &'static str = "assets.relocate";
+    pub const BLURHASH_BACKFILL: &'static str = "blurhash.backfill";
+    pub const NOTIFICATION_TEST_DIGEST: &'static str = "notification.digest.test";
+}
+
+pub struct AuditTargets;
+
+impl AuditTargets {
+    pub const SETTING: &'static str = "setting";
+    pub const STORAGE: &'static str = "storage";
+    pub const PHOTO: &'static str = "photo";
+    pub const ALBUM: &'static str = "album";
+    pub const USER: &'static str = "user";
+    pub const ALBUM_COMMENT: &'static str = "album_comment";
+    pub const CONFIG: &'static str = "config";
+    pub const TASK: &'static str = "task";
+    pub const TAG_RULE: &'static str = "tag_rule";
+    pub const PERSON: &'static str = "person";
+    pub const DIGEST_RUN: &'static str = "digest_run";
+}
@@ -0,0 +1,33 @@
+use rand::RngExt;
+use sha2::{Digest, Sha256};
+
+// One-time recovery codes handed out when two-factor sign-in is turned on. Only their hashes are kept;
+// a code is removed from the list the first time it is used.
+pub struct BackupCode;
+
+impl BackupCode {
+    pub const COUNT: usize = 10;
+    const LENGTH: usize = 10;
+    // No o, i or l, which are easy to misread as digits when the codes are typed in from paper.
+    const ALPHABET: &'static [u8; 32] = b"023456789abcdefghjkmnpqrstuvwxyz";
+
+    pub fn generate() -> Vec<String> {
+        (0..Self::COUNT).map(|_| Self::generate_one()).collect()
+    }
+
+    fn generate_one() -> String {
+        let mut bytes = [0u8; Self::LENGTH];
+        rand::rng().fill(&mut bytes);
+        let code = bytes.iter().map(|byte| Self::ALPHABET[(*byte % 32) as usize] as char).collect::<String>();
+        format!("{}-{}", &code[..Self::LENGTH / 2], &code[Self::LENGTH / 2..])
+    }
+
+    // Separators, spaces and case don't matter when the code is typed back in.
+    pub fn normalize(code: &str) -> String {
+        code.chars().filter(|ch| ch.is_ascii_alphanumeric()).map(|ch| ch.to_ascii_lowercase()).collect()
+    }
+
+    pub fn hash(code: &str) -> String {
+        Sha256::digest(Self::normalize(code).as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
@@ -0,0 +1,97 @@
+use crate::prelude::*;
+
+// Encodes the compact placeholder string described at blurha.sh, so the gallery can paint a blurred
+// preview before the thumbnail arrives.
+pub struct Blurhash;
+
+impl Blurhash {
+    pub const COMPONENTS_X: u32 = 4;
+    pub const COMPONENTS_Y: u32 = 3;
+    const ALPHABET: &'static [u8; 83] =
+        b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz#$%*+,-.:;=?@[]^_{|}~";
+
+    // `rgb` holds three bytes per pixel, row by row. Keep the image small: the cost grows with
+    // pixels times components, and a 32px sample looks the same once blurred.
+    pub fn encode(width: u32, height: u32, rgb: &[u8]) -> Option<String> {
+        let pixels = (width as usize).checked_mul(height as usize)?;
+        if pixels == 0 || rgb.len() != pixels * 3 {
+            return None;
+        }
+
+        let linear = rgb.iter().map(|channel| Self::srgb_to_linear(*channel)).collect::<Vec<_>>();
+        let mut factors = Vec::with_capacity((Self::COMPONENTS_X * Self::COMPONENTS_Y) as usize);
+        for j in 0..Self::COMPONENTS_Y {
+            for i in 0..Self::COMPONENTS_X {
+                factors.push(Self::factor(i, j, width, height, &linear));
+            }
+        }
+
+        let (dc, ac) = factors.split_first()?;
+        let mut hash = String::with_capacity(4 + 2 * factors.len());
+        Self::push_base83(&mut hash, (Self::COMPONENTS_X - 1) + (Self::COMPONENTS_Y - 1) * 9, 1);
+
+        let actual_max = ac.iter().flat_map(|factor| factor.iter()).fold(0.0f64, |max, value| max.max(value.abs()));
+        let quantised_max = (actual_max * 166.0 - 0.5).floor().clamp(0.0, 82.0) as u32;
+        let maximum = (quantised_max + 1) as f64 / 166.0;
+        Self::push_base83(&mut hash, if ac.is_empty() { 0 } else { quantised_max }, 1);
+
+        let dc_value =
+            (Self::linear_to_srgb(dc[0]) << 16) + (Self::linear_to_srgb(dc[1]) << 8) + Self::linear_to_srgb(dc[2]);
+        Self::push_base83(&mut hash, dc_value, 4);
+
+        for factor in ac {
+            let quantise =
+                |value: f64| (Self::sign_pow(value / maximum, 0.5) * 9.0 + 9.5).floor().clamp(0.0, 18.0) as u32;
+            let value = quantise(factor[0]) * 19 * 19 + quantise(factor[1]) * 19 + quantise(factor[2]);
+            Self::push_base83(&mut hash, value, 2);
+        }
+
+        Some(hash)
+    }
+
+    fn factor(i: u32, j: u32, width: u32, height: u32, linear: &[f64]) -> [f64; 3] {
+        let normalisation = if i == 0 && j == 0 { 1.0 } else { 2.0 };
+        let mut sum = [0.0f64; 3];
+        for y in 0..height {
+            let basis_y = (std::f64::consts::PI * j as f64 * y as f64 / height as f64).cos();
+            for x in 0..width {
+                let basis = basis_y * (std::f64::consts::PI * i as f64 * x as f64 / width as f64).cos();
+                let offset = ((y * width + x) * 3) as usize;
+                for (channel, total) in sum.iter_mut().enumerate() {
+                    *total += basis * linear[offset + channel];
+                }
+            }
+        }
+        let scale = normalisation / (width as f64 * height as f64);
+        sum.map(|total| total * scale)
+    }
+
+    fn srgb_to_linear(value: u8) -> f64 {
+        let value = value as f64 / 255.0;
+        if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+    }
+
+    fn linear_to_srgb(value: f64) -> u32 {
+        let value = value.clamp(0.0, 1.0);
+        let srgb = if value <= 0.0031308 { value * 12.92 } else { 1.055 * value.powf(1.0 / 2.4) - 0.055 };
+        (srgb * 255.0 + 0.5) as u32
+    }
+
+    fn sign_pow(value: f64, exponent: f64) -> f64 {
+        value.abs().powf(exponent).copysign(value)
+    }
+
+    fn push_base83(hash: &mut String, value: u32, length: u32) {
+        for position in (0..length).rev() {
+            let digit = (value / 83u32.pow(position)) % 83;
+            hash.push(Self::ALPHABET[digit as usize] as char);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BlurhashCandidate {
+    #[serde(alias = "storage_id")]
+    pub storage_id: Uuid,
+    pub hash: String,
+}
@@ -1,11 +1,12 @@
 use anyhow::Result;
 
-use crate::entities::photo_browse::{BrowseDimension, SortDirection};
+use crate::entities::photo_browse::{BrowseDimension, BrowseSort, SortDirection};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum SqlParam {
     Int(i32),
     String(String),
+    TextList(Vec<String>),
 }
 
 pub struct BrowseDimensionSqlAdapter {
@@ -68,6 +69,15 @@ impl BrowseDimensionSqlAdapter {
         }
     }
 
+    pub fn sort_expression(sort: BrowseSort) -> (&'static str, &'static str) {
+        match sort {
+            BrowseSort::Date => ("p.sort_date", "timestamptz"),
+            BrowseSort::Name => ("p.name", "text"),
+            BrowseSort::Size => ("COALESCE(p.size, 0)", "bigint"),
+            BrowseSort::Modified => ("COALESCE(p.updated_at, p.created_at, p.sort_date)", "timestamptz"),
+        }
+    }
+
     pub fn order_direction(direction: &SortDirection) -> &'static str {
         match direction {
             SortDirection::Asc => "ASC",
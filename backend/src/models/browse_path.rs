@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowsePathError {
+    InvalidEncoding,
+    Absolute,
+    Traversal,
+    InvalidSegment(String),
+    TooDeep,
+    NotFound,
+    OutsideRoot,
+}
+
+impl Display for BrowsePathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidEncoding => write!(f, "invalid browse path encoding"),
+            Self::Absolute => write!(f, "absolute browse paths are not allowed"),
+            Self::Traversal => write!(f, "browse path must not contain traversal segments"),
+            Self::InvalidSegment(segment) => write!(f, "invalid browse path segment: {}", segment),
+            Self::TooDeep => write!(f, "invalid browse path depth"),
+            Self::NotFound => write!(f, "browse path not found"),
+            Self::OutsideRoot => write!(f, "browse path resolves outside the storage root"),
+        }
+    }
+}
+
+impl std::error::Error for BrowsePathError {}
+
+pub struct BrowsePath;
+
+impl BrowsePath {
+    pub fn segments(path: &str) -> Result<Vec<String>, BrowsePathError> {
+        let decoded = urlencoding::decode(path).map_err(|_| BrowsePathError::InvalidEncoding)?;
+        let trimmed = decoded.trim();
+        if Self::is_absolute(trimmed) {
+            return Err(BrowsePathError::Absolute);
+        }
+
+        let mut segments = Vec::new();
+        for segment in trimmed.split('/').map(str::trim).filter(|segment| !segment.is_empty()) {
+            if segment == "." || segment == ".." {
+                return Err(BrowsePathError::Traversal);
+            }
+            if segment.contains('\\') || segment.chars().any(char::is_control) {
+                return Err(BrowsePathError::InvalidSegment(segment.to_string()));
+            }
+            segments.push(segment.to_string());
+        }
+        Ok(segments)
+    }
+
+    fn is_absolute(path: &str) -> bool {
+        let bytes = path.as_bytes();
+        path.starts_with('/')
+            || path.starts_with('\\')
+            || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+    }
+}
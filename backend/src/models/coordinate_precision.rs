@@ -0,0 +1,55 @@
+use crate::prelude::*;
+
+// How precisely GPS positions are shown to anyone other than an admin. Each decimal place is roughly
+// ten times finer: two places are about 1 km at the equator, enough for a map pin but not a street address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CoordinatePrecision {
+    #[default]
+    Exact,
+    Decimals(u8),
+}
+
+impl CoordinatePrecision {
+    pub const EXACT: &'static str = "exact";
+    pub const MAX_DECIMALS: u8 = 6;
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        if value == Self::EXACT {
+            return Some(Self::Exact);
+        }
+        value.parse::<u8>().ok().filter(|places| *places <= Self::MAX_DECIMALS).map(Self::Decimals)
+    }
+
+    // Admins always see exact positions; the setting only applies to everyone else.
+    pub fn for_requester(self, is_admin: bool) -> Self {
+        if is_admin { Self::Exact } else { self }
+    }
+
+    pub fn decimals(&self) -> Option<u8> {
+        match self {
+            Self::Exact => None,
+            Self::Decimals(places) => Some(*places),
+        }
+    }
+
+    pub fn round(&self, value: f64) -> f64 {
+        match self {
+            Self::Exact => value,
+            Self::Decimals(places) => {
+                let factor = 10f64.powi(*places as i32);
+                (value * factor).round() / factor
+            }
+        }
+    }
+
+    // The free-text area name may spell out the exact place, so it goes along with the precise position.
+    pub fn apply(&self, exif: &mut ExifModel) {
+        if *self == Self::Exact {
+            return;
+        }
+        exif.gps_latitude = exif.gps_latitude.map(|value| self.round(value));
+        exif.gps_longitude = exif.gps_longitude.map(|value| self.round(value));
+        exif.gps_area_information = None;
+    }
+}
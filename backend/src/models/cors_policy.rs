@@ -0,0 +1,84 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsDecision {
+    Skip,
+    Allow(String),
+    Reject,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorsPolicy {
+    pub allowed_origins: Vec<String>,
+    pub allow_any_origin: bool,
+    pub allow_credentials: bool,
+    pub allowed_headers: Vec<String>,
+    pub max_age_seconds: u64,
+    pub strict: bool,
+}
+
+impl CorsPolicy {
+    pub const SETTING_PREFIX: &'static str = "cors.";
+    pub const ALLOWED_METHODS: &'static str = "GET, POST, PUT, PATCH, DELETE, OPTIONS";
+    const ANY_ORIGIN: &'static str = "*";
+
+    pub fn new(
+        environment: AppEnvironment,
+        allowed_origins: Vec<String>,
+        allow_credentials: bool,
+        allowed_headers: Vec<String>,
+        max_age_seconds: u64,
+        strict: bool,
+    ) -> Self {
+        let allowed_origins = allowed_origins
+            .iter()
+            .map(|origin| Self::normalize(origin))
+            .filter(|origin| !origin.is_empty())
+            .collect::<Vec<_>>();
+        let allow_any_origin = allowed_origins.iter().any(|origin| origin == Self::ANY_ORIGIN)
+            || (allowed_origins.is_empty() && environment == AppEnvironment::Development);
+        // Reflecting arbitrary origins with credentials would let any site act as the signed-in user.
+        let allow_credentials = if allow_any_origin && allow_credentials {
+            log::warn!("Ignoring cors.allowCredentials because every origin is allowed; list explicit origins instead");
+            false
+        } else {
+            allow_credentials
+        };
+
+        Self { allowed_origins, allow_any_origin, allow_credentials, allowed_headers, max_age_seconds, strict }
+    }
+
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.allow_any_origin || self.allowed_origins.contains(&Self::normalize(origin))
+    }
+
+    pub fn decide(&self, origin: Option<&str>, host: Option<&str>) -> CorsDecision {
+        let Some(origin) = origin.map(str::trim).filter(|origin| !origin.is_empty()) else {
+            return CorsDecision::Skip;
+        };
+
+        if self.allows_origin(origin) {
+            return CorsDecision::Allow(origin.to_string());
+        }
+        if self.strict && !Self::is_same_origin(origin, host) {
+            return CorsDecision::Reject;
+        }
+        CorsDecision::Skip
+    }
+
+    pub fn allowed_headers_value(&self) -> String {
+        self.allowed_headers.join(", ")
+    }
+
+    pub fn is_same_origin(origin: &str, host: Option<&str>) -> bool {
+        let Some(host) = host.map(str::trim).filter(|host| !host.is_empty()) else {
+            return false;
+        };
+        let authority = origin.split_once("://").map(|(_, rest)| rest).unwrap_or(origin);
+        authority.trim_end_matches('/').eq_ignore_ascii_case(host)
+    }
+
+    fn normalize(origin: &str) -> String {
+        origin.trim().trim_end_matches('/').to_ascii_lowercase()
+    }
+}
@@ -0,0 +1,137 @@
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailFormat {
+    Webp,
+    Jpeg,
+    Avif,
+}
+
+impl ThumbnailFormat {
+    pub const ALL: [ThumbnailFormat; 3] = [ThumbnailFormat::Webp, ThumbnailFormat::Jpeg, ThumbnailFormat::Avif];
+    // Formats every thumbnail is generated in; AVIF is only ever an optional sibling.
+    pub const BASE: [ThumbnailFormat; 2] = [ThumbnailFormat::Webp, ThumbnailFormat::Jpeg];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "webp" => Some(Self::Webp),
+            "jpeg" | "jpg" => Some(Self::Jpeg),
+            "avif" => Some(Self::Avif),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Webp => "webp",
+            Self::Jpeg => "jpeg",
+            Self::Avif => "avif",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Webp => "webp",
+            Self::Jpeg => "jpg",
+            Self::Avif => "avif",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Webp => "image/webp",
+            Self::Jpeg => "image/jpeg",
+            Self::Avif => "image/avif",
+        }
+    }
+
+    pub fn image_format(&self) -> ImageFormat {
+        match self {
+            Self::Webp => ImageFormat::WebP,
+            Self::Jpeg => ImageFormat::Jpeg,
+            Self::Avif => ImageFormat::Avif,
+        }
+    }
+
+    // The configured format first, so lookups prefer it over files left from an earlier setting.
+    pub fn lookup_order(&self) -> Vec<ThumbnailFormat> {
+        let mut formats = vec![*self];
+        formats.extend(Self::BASE.into_iter().filter(|format| format != self));
+        formats
+    }
+
+    pub fn is_accepted_by(&self, accept: &str) -> bool {
+        accept.split(',').any(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let matches = parts.next().is_some_and(|mime| mime.eq_ignore_ascii_case(self.content_type()));
+            let rejected = parts
+                .filter_map(|param| param.strip_prefix("q="))
+                .any(|quality| quality.parse::<f32>().is_ok_and(|quality| quality <= 0.0));
+            matches && !rejected
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivativeProfile {
+    pub preview_max_dimension: u32,
+    pub preview_jpeg_quality: u8,
+    pub thumbnail_max_dimension: u32,
+    pub thumbnail_format: ThumbnailFormat,
+    pub thumbnail_avif: bool,
+}
+
+impl Default for DerivativeProfile {
+    fn default() -> Self {
+        Self {
+            preview_max_dimension: 1920,
+            preview_jpeg_quality: 75,
+            thumbnail_max_dimension: 400,
+            thumbnail_format: ThumbnailFormat::Webp,
+            thumbnail_avif: false,
+        }
+    }
+}
+
+impl DerivativeProfile {
+    pub const KIND_THUMBNAIL: &'static str = "thumbnail";
+    pub const KIND_PREVIEW: &'static str = "preview";
+
+    pub const PREVIEW_DIMENSION_RANGE: (f64, f64) = (256.0, 8192.0);
+    pub const JPEG_QUALITY_RANGE: (f64, f64) = (1.0, 100.0);
+    pub const THUMBNAIL_DIMENSION_RANGE: (f64, f64) = (64.0, 1024.0);
+
+    pub fn is_setting_key(key: &str) -> bool {
+        key.starts_with("preview.") || key.starts_with("thumbnail.")
+    }
+
+    // AVIF is offered first when enabled and the client asks for it explicitly.
+    pub fn thumbnail_candidates(&self, accept: Option<&str>) -> Vec<ThumbnailFormat> {
+        let mut formats = Vec::new();
+        if self.thumbnail_avif && accept.is_some_and(|accept| ThumbnailFormat::Avif.is_accepted_by(accept)) {
+            formats.push(ThumbnailFormat::Avif);
+        }
+        formats.extend(self.thumbnail_format.lookup_order());
+        formats
+    }
+
+    pub fn preview_signature(&self) -> String {
+        format!("{}px-q{}", self.preview_max_dimension, self.preview_jpeg_quality)
+    }
+
+    pub fn thumbnail_signature(&self) -> String {
+        format!("{}px-{}", self.thumbnail_max_dimension, self.thumbnail_format.as_str())
+    }
+
+    // Files recorded before profiles were tracked were built with the defaults.
+    pub fn is_stale(&self, kind: &str, recorded: Option<&str>) -> bool {
+        let defaults = Self::default();
+        let (current, legacy) = match kind {
+            Self::KIND_THUMBNAIL => (self.thumbnail_signature(), defaults.thumbnail_signature()),
+            _ => (self.preview_signature(), defaults.preview_signature()),
+        };
+        recorded.unwrap_or(&legacy) != current
+    }
+}
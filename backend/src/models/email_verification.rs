@@ -0,0 +1,62 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationEmail {
+    pub site_title: String,
+    pub link: String,
+}
+
+impl VerificationEmail {
+    pub fn new(site_title: &str, base_url: &str, token: &str) -> Self {
+        Self {
+            site_title: site_title.to_string(),
+            link: format!("{}/verify-email?token={}", base_url.trim_end_matches('/'), token),
+        }
+    }
+
+    pub fn subject(&self) -> String {
+        format!("Confirm your email for {}", self.site_title)
+    }
+
+    pub fn render_text(&self) -> String {
+        format!(
+            "Confirm your email address to finish setting up your {} account:\n\n{}\n\nIf you didn't sign up, you can ignore this message.\n",
+            self.site_title, self.link
+        )
+    }
+
+    pub fn render_html(&self) -> String {
+        format!(
+            "<p>Confirm your email address to finish setting up your {} account:</p>\n<p><a href=\"{}\">Verify email</a></p>\n<p>If you didn't sign up, you can ignore this message.</p>\n",
+            Markup::escape(&self.site_title),
+            Markup::escape(&self.link)
+        )
+    }
+}
+
+// Remembers when each address was last mailed so the resend endpoint can't be used to flood an inbox.
+// Kept in memory: a restart forgets the window, which only ever allows one extra email.
+pub struct ResendThrottle {
+    cooldown: Duration,
+    last_sent: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl ResendThrottle {
+    pub fn new(cooldown: Duration) -> Self {
+        Self { cooldown, last_sent: Mutex::new(HashMap::new()) }
+    }
+
+    // Records the send and returns true, or false while the address is still cooling down.
+    pub fn try_acquire(&self, email: &str, now: DateTime<Utc>) -> bool {
+        let key = email.trim().to_lowercase();
+        let Ok(mut last_sent) = self.last_sent.lock() else {
+            return true;
+        };
+        last_sent.retain(|_, sent_at| now - *sent_at < self.cooldown);
+        if last_sent.contains_key(&key) {
+            return false;
+        }
+        last_sent.insert(key, now);
+        true
+    }
+}
@@ -3,4 +3,8 @@ pub struct EventNames;
 
 impl EventNames {
     pub const IMAGES_PROCESSED: &'static str = "images.processed";
+    pub const PHOTO_ADDED: &'static str = "photo.added";
+    pub const PHOTO_DELETED: &'static str = "photo.deleted";
+    pub const ALBUM_UPDATED: &'static str = "album.updated";
+    pub const COMMENT_ADDED: &'static str = "comment.added";
 }
\ No newline at end of file
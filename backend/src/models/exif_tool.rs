@@ -17,6 +17,32 @@ impl ExifTool {
         Self { exe_path: Self::default_exe_path() }
     }
 
+    pub fn with_exe_path(exe_path: impl Into<PathBuf>) -> Self {
+        Self { exe_path: exe_path.into() }
+    }
+
+    // Tags are written as numeric values (-n) straight into the original, without a backup copy.
+    pub fn write_tags(&self, path: &str, tags: &[(String, String)]) -> Result<()> {
+        if !self.exe_path.exists() {
+            anyhow::bail!("ExifTool binary not found at {:?}", self.exe_path);
+        }
+        if tags.is_empty() {
+            return Ok(());
+        }
+
+        let output = Command::new(&self.exe_path)
+            .arg("-n")
+            .arg("-overwrite_original")
+            .args(tags.iter().map(|(tag, value)| format!("-{}={}", tag, value)))
+            .arg(path)
+            .output()?;
+
+        if !output.status.success() {
+            anyhow::bail!("ExifTool failed: {}", String::from_utf8_lossy(&output.stderr));
+        }
+        Ok(())
+    }
+
     pub fn read_exif(&self, path: &str) -> Result<ExifMap> {
         if !self.exe_path.exists() {
             anyhow::bail!("ExifTool binary not found at {:?}", self.exe_path);
@@ -0,0 +1,59 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSignature {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Bmp,
+    Tiff,
+    Heif,
+    Raw,
+}
+
+impl ImageSignature {
+    const ISO_BMFF_IMAGE_BRANDS: [&'static [u8]; 6] = [b"heic", b"heix", b"mif1", b"msf1", b"avif", b"hevc"];
+    const ISO_BMFF_RAW_BRANDS: [&'static [u8]; 1] = [b"crx "];
+
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        if head.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return Some(Self::Jpeg);
+        }
+        if head.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            return Some(Self::Png);
+        }
+        if head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a") {
+            return Some(Self::Gif);
+        }
+        if head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+            return Some(Self::Webp);
+        }
+        if head.starts_with(b"BM") && head.len() >= 26 {
+            return Some(Self::Bmp);
+        }
+        // Most camera RAW formats (CR2, NEF, ARW, DNG, PEF, SRW) are TIFF containers.
+        if head.starts_with(b"II*\0") || head.starts_with(b"MM\0*") {
+            return Some(Self::Tiff);
+        }
+        if head.starts_with(b"IIRO") || head.starts_with(b"IIRS") || head.starts_with(b"MMOR") {
+            return Some(Self::Raw);
+        }
+        if head.starts_with(b"IIU\0") || head.starts_with(b"FUJIFILMCCD-RAW") {
+            return Some(Self::Raw);
+        }
+        if head.len() >= 12 && &head[4..8] == b"ftyp" {
+            let brand = &head[8..12];
+            if Self::ISO_BMFF_IMAGE_BRANDS.contains(&brand) {
+                return Some(Self::Heif);
+            }
+            if Self::ISO_BMFF_RAW_BRANDS.contains(&brand) {
+                return Some(Self::Raw);
+            }
+        }
+        None
+    }
+
+    // RAW containers only expose embedded previews in their headers, so their dimensions are not checked here.
+    pub fn has_decodable_header(&self) -> bool {
+        !matches!(self, Self::Tiff | Self::Raw)
+    }
+}
@@ -0,0 +1,30 @@
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    #[default]
+    Native,
+    Takeout,
+}
+
+impl ImportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "native" | "" => Some(Self::Native),
+            "takeout" => Some(Self::Takeout),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Native => "native",
+            Self::Takeout => "takeout",
+        }
+    }
+
+    pub fn is_takeout(&self) -> bool {
+        *self == Self::Takeout
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum LiveEvent {
+    PhotoAdded { photo_id: Uuid, day: NaiveDate, hash: Option<String> },
+    PhotoDeleted { photo_id: Uuid },
+    AlbumUpdated { album_id: Uuid },
+    CommentAdded { comment_id: Uuid, photo_id: Option<Uuid>, album_id: Option<Uuid> },
+    // Sent when a slow connection missed events and should refetch.
+    Resync { skipped: u64 },
+}
+
+impl LiveEvent {
+    pub const PHOTO_ADDED: &'static str = "photoAdded";
+    pub const PHOTO_DELETED: &'static str = "photoDeleted";
+    pub const ALBUM_UPDATED: &'static str = "albumUpdated";
+    pub const COMMENT_ADDED: &'static str = "commentAdded";
+    pub const RESYNC: &'static str = "resync";
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::PhotoAdded { .. } => Self::PHOTO_ADDED,
+            Self::PhotoDeleted { .. } => Self::PHOTO_DELETED,
+            Self::AlbumUpdated { .. } => Self::ALBUM_UPDATED,
+            Self::CommentAdded { .. } => Self::COMMENT_ADDED,
+            Self::Resync { .. } => Self::RESYNC,
+        }
+    }
+
+    pub fn album_id(&self) -> Option<Uuid> {
+        match self {
+            Self::AlbumUpdated { album_id } => Some(*album_id),
+            Self::CommentAdded { album_id, .. } => *album_id,
+            _ => None,
+        }
+    }
+
+    pub fn photo_id(&self) -> Option<Uuid> {
+        match self {
+            Self::PhotoAdded { photo_id, .. } => Some(*photo_id),
+            Self::CommentAdded { photo_id, .. } => *photo_id,
+            _ => None,
+        }
+    }
+
+    pub fn topic(&self) -> Option<&'static str> {
+        match self {
+            Self::PhotoAdded { .. } => Some(EventNames::PHOTO_ADDED),
+            Self::PhotoDeleted { .. } => Some(EventNames::PHOTO_DELETED),
+            Self::AlbumUpdated { .. } => Some(EventNames::ALBUM_UPDATED),
+            Self::CommentAdded { .. } => Some(EventNames::COMMENT_ADDED),
+            Self::Resync { .. } => None,
+        }
+    }
+
+    // Only the live topics are forwarded; everything else on the bus stays internal.
+    pub fn from_app_event(event: &AppEvent) -> Option<Self> {
+        let live_topic =
+            [EventNames::PHOTO_ADDED, EventNames::PHOTO_DELETED, EventNames::ALBUM_UPDATED, EventNames::COMMENT_ADDED]
+                .contains(&event.topic.as_str());
+        if !live_topic {
+            return None;
+        }
+        serde_json::from_value::<Self>(event.payload.clone())
+            .ok()
+            .filter(|live| live.topic() == Some(event.topic.as_str()))
+    }
+}
+
+// Fixed when the ticket is issued; a role change takes effect on the next connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveAudience {
+    Admin,
+    Member,
+    Viewer,
+}
+
+impl LiveAudience {
+    pub fn new(is_admin: bool, is_viewer: bool) -> Self {
+        match (is_admin, is_viewer) {
+            (true, _) => Self::Admin,
+            (false, true) => Self::Viewer,
+            (false, false) => Self::Member,
+        }
+    }
+
+    // Mirrors the listing rules: admin-only tags hide a photo from everyone else, and viewers also lose
+    // photos carrying one of the configured viewer-hidden tags.
+    pub fn can_see(&self, tags: &[Tag], viewer_hidden_tags: &HashSet<String>) -> bool {
+        match self {
+            Self::Admin => true,
+            Self::Member => tags.iter().all(|tag| tag.visibility != Tag::VISIBILITY_HIDDEN),
+            Self::Viewer => tags
+                .iter()
+                .all(|tag| tag.visibility != Tag::VISIBILITY_HIDDEN && !viewer_hidden_tags.contains(&tag.name_norm)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSubscription {
+    #[serde(default)]
+    pub events: HashSet<String>,
+    #[serde(default)]
+    pub albums: HashSet<Uuid>,
+}
+
+impl LiveSubscription {
+    // Empty sets mean "everything"; an album filter drops events that are not about one of those albums.
+    pub fn matches(&self, event: &LiveEvent) -> bool {
+        if matches!(event, LiveEvent::Resync { .. }) {
+            return true;
+        }
+        if !self.events.is_empty() && !self.events.contains(event.kind()) {
+            return false;
+        }
+        if self.albums.is_empty() {
+            return true;
+        }
+        event.album_id().is_some_and(|album_id| self.albums.contains(&album_id))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LiveClientMessage {
+    Subscribe(LiveSubscription),
+    Unsubscribe,
+}
@@ -0,0 +1,21 @@
+pub struct Markup;
+
+impl Markup {
+    // Safe for HTML and XML text and for quoted attributes of either quote style.
+    // Control characters other than whitespace are dropped because XML 1.0 cannot carry them at all.
+    pub fn escape(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for ch in value.chars() {
+            match ch {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                ch if ch.is_control() && !matches!(ch, '\n' | '\r' | '\t') => {}
+                ch => escaped.push(ch),
+            }
+        }
+        escaped
+    }
+}
@@ -0,0 +1,12 @@
+pub struct MetricNames;
+
+impl MetricNames {
+    pub const HTTP_REQUEST_DURATION_SECONDS: &'static str = "nimble_http_request_duration_seconds";
+    pub const IMAGES_PROCESSED_TOTAL: &'static str = "nimble_images_processed_total";
+    pub const IMAGES_FAILED_TOTAL: &'static str = "nimble_images_failed_total";
+    pub const BACKGROUND_QUEUE_DEPTH: &'static str = "nimble_background_queue_depth";
+    pub const BACKGROUND_RUNNING_TASKS: &'static str = "nimble_background_running_tasks";
+    pub const PREVIEW_GENERATION_SECONDS: &'static str = "nimble_preview_generation_seconds";
+    pub const PREVIEW_EXTRACTIONS_IN_FLIGHT: &'static str = "nimble_preview_extractions_in_flight";
+    pub const DB_QUERY_ERRORS_TOTAL: &'static str = "nimble_db_query_errors_total";
+}
@@ -1,17 +1,93 @@
+pub mod album_photo_order;
+pub mod api_error;
+pub mod app_config;
+pub mod album_sort;
+pub mod atom_feed;
+pub mod audit_actions;
+pub mod backup_code;
+pub mod blurhash;
 pub mod browse_dimension_sql_adapter;
+pub mod browse_path;
 pub mod category_template;
+pub mod coordinate_precision;
+pub mod cors_policy;
+pub mod derivative_profile;
+pub mod email_verification;
 pub mod event_names;
 pub mod exif_tool;
+pub mod image_signature;
+pub mod import_format;
+pub mod live_event;
+pub mod markup;
+pub mod metric_names;
+pub mod notification_digest;
+pub mod person;
+pub mod photo_embed;
+pub mod photo_filter;
+pub mod photo_rating;
+pub mod photo_sort;
+pub mod photo_stack;
+pub mod preview_cache;
+pub mod preview_warmer;
+pub mod preview_watermark;
 pub mod property_map;
+pub mod random_sampling;
+pub mod request_id;
+pub mod session_client;
 pub mod setting_consts;
 pub mod string_id;
+pub mod tag_match;
+pub mod takeout_sidecar;
+pub mod tag_rule_condition;
+pub mod tag_suggestion;
 pub mod template;
+pub mod timeline_zone;
+pub mod totp;
+pub mod xmp_sidecar;
 
+pub use album_photo_order::AlbumPhotoOrder;
+pub use api_error::ApiError;
+pub use app_config::{AppConfig, AppConfigError, AppEnvironment};
+pub use album_sort::{AlbumListQuery, AlbumSortField};
+pub use atom_feed::{AtomEntry, AtomFeed, AtomLink, FeedDocument};
+pub use audit_actions::{AuditActions, AuditTargets};
+pub use backup_code::BackupCode;
+pub use blurhash::{Blurhash, BlurhashCandidate};
 pub use browse_dimension_sql_adapter::{BrowseDimensionSqlAdapter, SqlParam};
+pub use browse_path::{BrowsePath, BrowsePathError};
 pub use category_template::CategoryTemplateParser;
+pub use coordinate_precision::CoordinatePrecision;
+pub use cors_policy::{CorsDecision, CorsPolicy};
+pub use derivative_profile::{DerivativeProfile, ThumbnailFormat};
+pub use email_verification::{ResendThrottle, VerificationEmail};
 pub use event_names::EventNames;
 pub use exif_tool::{ExifMap, ExifTool};
+pub use image_signature::ImageSignature;
+pub use import_format::ImportFormat;
+pub use live_event::{LiveAudience, LiveClientMessage, LiveEvent, LiveSubscription};
+pub use markup::Markup;
+pub use metric_names::MetricNames;
+pub use notification_digest::{DigestSchedule, DigestSummary};
+pub use person::{PersonMention, PersonRegion};
+pub use photo_embed::{AlbumEmbed, EmbedTarget, PhotoEmbed};
+pub use photo_filter::PhotoFilter;
+pub use photo_rating::PhotoRating;
+pub use photo_sort::{PhotoSort, PhotoSortField};
+pub use photo_stack::{PhotoStack, StackRepresentative};
+pub use preview_cache::{PreviewCache, PreviewCacheBudgets, PreviewCacheFile};
+pub use preview_warmer::{PreviewWarmCandidate, PreviewWarmScope, PreviewWarmerStatus};
+pub use preview_watermark::PreviewWatermark;
 pub use property_map::{InsertEntry, PropertyMap};
+pub use random_sampling::RandomSampling;
+pub use request_id::RequestId;
+pub use session_client::SessionClient;
 pub use setting_consts::SettingConsts;
 pub use string_id::ToUuid;
+pub use tag_match::TagMatch;
+pub use takeout_sidecar::{TakeoutAlbum, TakeoutGeoData, TakeoutLocation, TakeoutPerson, TakeoutSidecar, TakeoutTimestamp};
+pub use tag_rule_condition::{TagRuleField, TagRuleOperator, TagRuleValue};
+pub use tag_suggestion::{ImageAnalyzerConfig, TagSuggestion};
 pub use template::{CompiledTemplate, PropertyMapTemplateContext, TemplateContext, TemplateEngine, TemplateTokenNames};
+pub use timeline_zone::{TimelineGranularity, TimelineZone};
+pub use totp::Totp;
+pub use xmp_sidecar::XmpSidecar;
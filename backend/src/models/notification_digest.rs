@@ -0,0 +1,81 @@
+use chrono::{DateTime, Timelike, Utc};
+
+use crate::models::Markup;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestSummary {
+    pub site_title: String,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub photos: i64,
+    pub comments: i64,
+    pub albums: i64,
+}
+
+impl DigestSummary {
+    pub fn is_empty(&self) -> bool {
+        self.photos == 0 && self.comments == 0 && self.albums == 0
+    }
+
+    pub fn subject(&self) -> String {
+        format!("{} daily digest for {}", self.site_title, self.until.format("%Y-%m-%d"))
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut text = format!("Activity on {} since {}:\n\n", self.site_title, Self::timestamp(self.since));
+        for (label, count) in self.lines() {
+            text.push_str(&format!("- {}: {}\n", label, count));
+        }
+        if self.is_empty() {
+            text.push_str("\nNothing new this time.\n");
+        }
+        text
+    }
+
+    pub fn render_html(&self) -> String {
+        let mut html = format!(
+            "<h2>{}</h2>\n<p>Activity since {}:</p>\n<ul>\n",
+            Markup::escape(&self.site_title),
+            Self::timestamp(self.since)
+        );
+        for (label, count) in self.lines() {
+            html.push_str(&format!("  <li>{}: <strong>{}</strong></li>\n", label, count));
+        }
+        html.push_str("</ul>\n");
+        if self.is_empty() {
+            html.push_str("<p>Nothing new this time.</p>\n");
+        }
+        html
+    }
+
+    fn lines(&self) -> [(&'static str, i64); 3] {
+        [("New photos", self.photos), ("New comments", self.comments), ("New albums", self.albums)]
+    }
+
+    fn timestamp(value: DateTime<Utc>) -> String {
+        value.format("%Y-%m-%d %H:%M UTC").to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigestSchedule {
+    pub hour: u32,
+}
+
+impl DigestSchedule {
+    pub const HOUR_RANGE: (f64, f64) = (0.0, 23.0);
+
+    pub fn new(hour: f64) -> Self {
+        let (min, max) = Self::HOUR_RANGE;
+        let hour = if hour.is_finite() { hour.clamp(min, max).floor() } else { min };
+        Self { hour: hour as u32 }
+    }
+
+    // Due once per UTC day, from the configured hour onwards, so a late start still sends that day's digest.
+    pub fn is_due(&self, now: DateTime<Utc>, last_sent: Option<DateTime<Utc>>) -> bool {
+        if now.hour() < self.hour {
+            return false;
+        }
+        last_sent.is_none_or(|last| last.date_naive() < now.date_naive())
+    }
+}
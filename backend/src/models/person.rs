@@ -0,0 +1,90 @@
+use crate::prelude::*;
+
+// A face rectangle in fractions of the image size, measured from the top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonRegion {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PersonRegion {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Option<Self> {
+        if ![x, y, width, height].iter().all(|value| value.is_finite()) || width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let left = x.clamp(0.0, 1.0);
+        let top = y.clamp(0.0, 1.0);
+        let right = (x + width).clamp(0.0, 1.0);
+        let bottom = (y + height).clamp(0.0, 1.0);
+        (right > left && bottom > top).then(|| Self { x: left, y: top, width: right - left, height: bottom - top })
+    }
+
+    // MWG regions (Lightroom, digiKam, Picasa) store the centre of the rectangle.
+    pub fn from_center(center_x: f32, center_y: f32, width: f32, height: f32) -> Option<Self> {
+        Self::new(center_x - width / 2.0, center_y - height / 2.0, width, height)
+    }
+
+    // Windows Photo Gallery writes "x, y, w, h" from the top-left corner.
+    pub fn parse_rectangle(value: &str) -> Option<Self> {
+        let values = value.split(',').map(|part| part.trim().parse::<f32>().ok()).collect::<Option<Vec<_>>>()?;
+        match values.as_slice() {
+            [x, y, width, height] => Self::new(*x, *y, *width, *height),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonMention {
+    pub name: String,
+    #[serde(default)]
+    pub region: Option<PersonRegion>,
+}
+
+impl PersonMention {
+    pub const MAX_NAME_LENGTH: usize = 200;
+
+    pub fn new(name: &str, region: Option<PersonRegion>) -> Option<Self> {
+        let (name, _) = Self::normalize_name(name)?;
+        Some(Self { name, region })
+    }
+
+    // Like tag names, matching ignores case; runs of whitespace are collapsed so sidecar spellings line up.
+    pub fn normalize_name(raw: &str) -> Option<(String, String)> {
+        let name = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        if name.is_empty() {
+            return None;
+        }
+        let name_norm = name.to_lowercase();
+        Some((name, name_norm))
+    }
+
+    pub fn name_norm(&self) -> String {
+        self.name.to_lowercase()
+    }
+
+    // Keeps the first spelling of each name and the first valid region seen for it.
+    pub fn dedup(mentions: Vec<PersonMention>) -> Vec<PersonMention> {
+        let mut unique: Vec<PersonMention> = Vec::new();
+        for mention in mentions {
+            let region =
+                mention.region.and_then(|region| PersonRegion::new(region.x, region.y, region.width, region.height));
+            let Some(mention) = Self::new(&mention.name, region) else {
+                continue;
+            };
+            match unique.iter_mut().find(|existing| existing.name_norm() == mention.name_norm()) {
+                Some(existing) => {
+                    if existing.region.is_none() {
+                        existing.region = mention.region;
+                    }
+                }
+                None => unique.push(mention),
+            }
+        }
+        unique
+    }
+}
@@ -0,0 +1,207 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmbedTarget {
+    Photo(Uuid),
+    SharedPhoto(String),
+    Album(Uuid),
+}
+
+impl EmbedTarget {
+    // Only links back to this instance are embeddable; anything else is treated as unknown.
+    pub fn parse(url: &str, host: &str) -> Option<Self> {
+        let url = url.trim();
+        let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))?;
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if !authority.eq_ignore_ascii_case(host) {
+            return None;
+        }
+
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let segments = path.split('/').filter(|segment| !segment.is_empty()).collect::<Vec<_>>();
+        match segments.as_slice() {
+            ["p", hash] => HashService::normalize(hash).map(Self::SharedPhoto),
+            ["photo", id] | ["albums", _, "photo", id] => Uuid::parse_str(id).ok().map(Self::Photo),
+            ["albums", id] => Uuid::parse_str(id).ok().map(Self::Album),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhotoEmbed {
+    pub site_title: String,
+    pub base_url: String,
+    pub photo_id: Uuid,
+    pub hash: String,
+    pub title: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl PhotoEmbed {
+    pub const OEMBED_VERSION: &'static str = "1.0";
+    pub const CACHE_AGE_SECONDS: u64 = 3600;
+
+    // EXIF orientations 5-8 are rotated a quarter turn, so the stored dimensions are swapped on screen.
+    pub fn new(site_title: &str, base_url: &str, photo: &Photo, hash: &str) -> Self {
+        let rotated = photo.orientation.is_some_and(|orientation| (5..=8).contains(&orientation));
+        let (width, height) = if rotated { (photo.height, photo.width) } else { (photo.width, photo.height) };
+        let title = photo.title.as_deref().map(str::trim).filter(|title| !title.is_empty()).unwrap_or(&photo.name);
+        Self {
+            site_title: site_title.to_string(),
+            base_url: base_url.to_string(),
+            photo_id: photo.id,
+            hash: hash.to_string(),
+            title: title.to_string(),
+            width,
+            height,
+        }
+    }
+
+    pub fn share_url(&self) -> String {
+        format!("{}/p/{}", self.base_url, self.hash)
+    }
+
+    pub fn preview_url(&self) -> String {
+        format!("{}/api/photos/preview/{}", self.base_url, self.hash)
+    }
+
+    pub fn thumbnail_url(&self) -> String {
+        format!("{}/api/photos/thumbnail/{}", self.base_url, self.hash)
+    }
+
+    pub fn size_within(&self, max_dimension: u32) -> Option<(u32, u32)> {
+        let (width, height) = (self.width?, self.height?);
+        Some(Self::fit_within(width, height, max_dimension, max_dimension))
+    }
+
+    // The preview is returned when it fits the consumer's limits, otherwise the smaller thumbnail.
+    pub fn oembed(
+        &self,
+        profile: &DerivativeProfile,
+        max_width: Option<u32>,
+        max_height: Option<u32>,
+    ) -> OEmbedResponse {
+        let thumbnail = self.size_within(profile.thumbnail_max_dimension);
+        let mut response = OEmbedResponse {
+            version: Self::OEMBED_VERSION.to_string(),
+            kind: OEmbedResponse::TYPE_LINK.to_string(),
+            title: self.title.clone(),
+            provider_name: self.site_title.clone(),
+            provider_url: format!("{}/", self.base_url),
+            cache_age: Self::CACHE_AGE_SECONDS,
+            url: None,
+            width: None,
+            height: None,
+            thumbnail_url: thumbnail.map(|_| self.thumbnail_url()),
+            thumbnail_width: thumbnail.map(|(width, _)| width),
+            thumbnail_height: thumbnail.map(|(_, height)| height),
+        };
+
+        let Some(preview) = self.size_within(profile.preview_max_dimension) else {
+            return response;
+        };
+        let limit = (max_width.unwrap_or(u32::MAX), max_height.unwrap_or(u32::MAX));
+        let fits = |(width, height): (u32, u32)| width <= limit.0 && height <= limit.1;
+        let (url, size) = match thumbnail {
+            Some(thumbnail) if !fits(preview) => (self.thumbnail_url(), thumbnail),
+            _ => (self.preview_url(), preview),
+        };
+        let (width, height) = Self::fit_within(size.0, size.1, limit.0, limit.1);
+
+        response.kind = OEmbedResponse::TYPE_PHOTO.to_string();
+        response.url = Some(url);
+        response.width = Some(width);
+        response.height = Some(height);
+        response
+    }
+
+    // Chat apps read Open Graph tags from the HTML and never run the client app, so the page is
+    // rendered here and sends people on to the regular photo view.
+    pub fn render_page(&self, profile: &DerivativeProfile) -> String {
+        let title = Markup::escape(&self.title);
+        let site_title = Markup::escape(&self.site_title);
+        let share_url = Markup::escape(&self.share_url());
+        let preview_url = Markup::escape(&self.preview_url());
+        let photo_url = Markup::escape(&format!("{}/photo/{}", self.base_url, self.photo_id));
+        let oembed_url = Markup::escape(&format!(
+            "{}/api/oembed?format=json&url={}",
+            self.base_url,
+            urlencoding::encode(&self.share_url())
+        ));
+        let dimensions = self
+            .size_within(profile.preview_max_dimension)
+            .map(|(width, height)| {
+                let width = format!("    <meta property=\"og:image:width\" content=\"{}\">\n", width);
+                let height = format!("    <meta property=\"og:image:height\" content=\"{}\">\n", height);
+                width + &height
+            })
+            .unwrap_or_default();
+
+        format!(
+            r#"<!doctype html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <title>{title} - {site_title}</title>
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <meta property="og:type" content="website">
+    <meta property="og:site_name" content="{site_title}">
+    <meta property="og:title" content="{title}">
+    <meta property="og:url" content="{share_url}">
+    <meta property="og:image" content="{preview_url}">
+{dimensions}    <meta name="twitter:card" content="summary_large_image">
+    <link rel="alternate" type="application/json+oembed" href="{oembed_url}" title="{title}">
+    <link rel="canonical" href="{share_url}">
+    <meta http-equiv="refresh" content="0; url={photo_url}">
+</head>
+<body>
+    <a href="{photo_url}"><img src="{preview_url}" alt="{title}"></a>
+</body>
+</html>
+"#
+        )
+    }
+
+    pub fn fit_within(width: u32, height: u32, max_width: u32, max_height: u32) -> (u32, u32) {
+        if width == 0 || height == 0 || (width <= max_width && height <= max_height) {
+            return (width, height);
+        }
+        let scale = (max_width as f64 / width as f64).min(max_height as f64 / height as f64);
+        let scaled = |value: u32| ((value as f64 * scale).round() as u32).max(1);
+        (scaled(width), scaled(height))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlbumEmbed {
+    pub site_title: String,
+    pub base_url: String,
+    pub album_id: Uuid,
+    pub name: String,
+    pub cover: Option<PhotoEmbed>,
+}
+
+impl AlbumEmbed {
+    // Albums have no single image, so they embed as a link card with the cover as thumbnail.
+    pub fn oembed(&self, profile: &DerivativeProfile) -> OEmbedResponse {
+        let thumbnail = self.cover.as_ref().and_then(|cover| {
+            cover.size_within(profile.thumbnail_max_dimension).map(|size| (cover.thumbnail_url(), size))
+        });
+        OEmbedResponse {
+            version: PhotoEmbed::OEMBED_VERSION.to_string(),
+            kind: OEmbedResponse::TYPE_LINK.to_string(),
+            title: self.name.clone(),
+            provider_name: self.site_title.clone(),
+            provider_url: format!("{}/", self.base_url),
+            cache_age: PhotoEmbed::CACHE_AGE_SECONDS,
+            url: None,
+            width: None,
+            height: None,
+            thumbnail_width: thumbnail.as_ref().map(|(_, (width, _))| *width),
+            thumbnail_height: thumbnail.as_ref().map(|(_, (_, height))| *height),
+            thumbnail_url: thumbnail.map(|(url, _)| url),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PhotoFilter {
+    pub tags: Vec<String>,
+    pub matching: TagMatch,
+    pub min_rating: Option<u8>,
+    pub person: Option<String>,
+    pub date_from: Option<DateTime<Utc>>,
+    pub date_to: Option<DateTime<Utc>>,
+}
+
+impl PhotoFilter {
+    pub fn new(tags: &[String], matching: TagMatch) -> Self {
+        Self { tags: tags.to_vec(), matching, ..Self::default() }
+    }
+
+    pub fn with_min_rating(mut self, min_rating: Option<u8>) -> Self {
+        self.min_rating = min_rating;
+        self
+    }
+
+    pub fn with_person(mut self, person: Option<&str>) -> Self {
+        self.person = person.and_then(PersonMention::normalize_name).map(|(_, name_norm)| name_norm);
+        self
+    }
+
+    // Half-open on sort_date: `from` is included, `to` is not.
+    pub fn with_date_range(mut self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Self {
+        self.date_from = from;
+        self.date_to = to;
+        self
+    }
+
+    pub fn normalized_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> =
+            self.tags.iter().map(|tag| tag.trim().to_lowercase()).filter(|tag| !tag.is_empty()).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+}
@@ -0,0 +1,29 @@
+pub struct PhotoRating;
+
+impl PhotoRating {
+    pub const MAX: u8 = 5;
+    pub const INVALID_RATING: &'static str = "rating must be between 0 and 5";
+
+    pub fn is_valid(rating: u8) -> bool {
+        rating <= Self::MAX
+    }
+
+    // Cameras and other tools occasionally write values outside 0-5; those are treated as unrated.
+    pub fn normalize(rating: Option<u8>) -> Option<u8> {
+        rating.filter(|rating| Self::is_valid(*rating))
+    }
+
+    pub fn parse_min(value: Option<&str>) -> Result<Option<u8>, &'static str> {
+        match value.map(str::trim).filter(|value| !value.is_empty()) {
+            None => Ok(None),
+            Some(value) => {
+                value.parse::<u8>().ok().filter(|rating| Self::is_valid(*rating)).map(Some).ok_or(Self::INVALID_RATING)
+            }
+        }
+    }
+
+    // Unrated photos count as zero stars, so a minimum of 0 keeps everything.
+    pub fn min_clause(alias: &str, param_index: usize) -> String {
+        format!("COALESCE({alias}.rating, 0) >= ${param_index}")
+    }
+}
@@ -0,0 +1,89 @@
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PhotoSortField {
+    #[default]
+    DateTaken,
+    DateImported,
+    Size,
+    Name,
+}
+
+impl PhotoSortField {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "datetaken" | "date_taken" | "date" => Some(Self::DateTaken),
+            "dateimported" | "date_imported" | "imported" => Some(Self::DateImported),
+            "size" => Some(Self::Size),
+            "name" => Some(Self::Name),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DateTaken => "dateTaken",
+            Self::DateImported => "dateImported",
+            Self::Size => "size",
+            Self::Name => "name",
+        }
+    }
+
+    fn expression(&self, alias: &str) -> String {
+        match self {
+            Self::DateTaken => format!("{}.sort_date", alias),
+            Self::DateImported => format!("COALESCE({alias}.date_imported, {alias}.created_at)"),
+            Self::Size => format!("{}.size", alias),
+            Self::Name => format!("lower({}.name)", alias),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PhotoSort {
+    pub field: PhotoSortField,
+    pub direction: SortDirection,
+}
+
+impl Default for PhotoSort {
+    fn default() -> Self {
+        Self { field: PhotoSortField::DateTaken, direction: SortDirection::Desc }
+    }
+}
+
+impl PhotoSort {
+    pub const INVALID_SORT: &'static str = "invalid sort";
+    pub const INVALID_DIRECTION: &'static str = "invalid sort direction";
+
+    pub fn new(field: PhotoSortField, direction: SortDirection) -> Self {
+        Self { field, direction }
+    }
+
+    pub fn parse(sort: Option<&str>, direction: Option<&str>) -> Result<Self, &'static str> {
+        let field = match sort.map(str::trim).filter(|value| !value.is_empty()) {
+            Some(value) => PhotoSortField::parse(value).ok_or(Self::INVALID_SORT)?,
+            None => PhotoSortField::default(),
+        };
+        let direction = match direction.map(str::trim).filter(|value| !value.is_empty()) {
+            Some(value) => SortDirection::parse(value).ok_or(Self::INVALID_DIRECTION)?,
+            None => Self::default_direction(field),
+        };
+        Ok(Self { field, direction })
+    }
+
+    pub fn order_clause(&self, alias: &str) -> String {
+        let direction = match self.direction {
+            SortDirection::Asc => "ASC",
+            SortDirection::Desc => "DESC",
+        };
+        format!("{} {direction} NULLS LAST, {alias}.id {direction}", self.field.expression(alias))
+    }
+
+    fn default_direction(field: PhotoSortField) -> SortDirection {
+        match field {
+            PhotoSortField::Name => SortDirection::Asc,
+            _ => SortDirection::Desc,
+        }
+    }
+}
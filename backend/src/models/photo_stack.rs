@@ -0,0 +1,68 @@
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StackRepresentative {
+    #[default]
+    Jpeg,
+    Raw,
+}
+
+impl StackRepresentative {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "jpeg" | "jpg" => Some(Self::Jpeg),
+            "raw" => Some(Self::Raw),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpeg",
+            Self::Raw => "raw",
+        }
+    }
+
+    pub fn prefers_raw(&self) -> bool {
+        *self == Self::Raw
+    }
+}
+
+// A stack groups the RAW and JPEG files a camera writes for the same shot. Members share a
+// stack_id, which is always the id of the member shown in listings.
+pub struct PhotoStack;
+
+impl PhotoStack {
+    pub fn base_name(name: &str) -> String {
+        Path::new(name).file_stem().and_then(|stem| stem.to_str()).unwrap_or(name).to_lowercase()
+    }
+
+    pub fn directory(photo: &Photo) -> &str {
+        photo.path.strip_suffix(photo.name.as_str()).unwrap_or("")
+    }
+
+    pub fn is_pair(first: &Photo, second: &Photo) -> bool {
+        first.id != second.id
+            && first.storage_id == second.storage_id
+            && first.date_taken.is_some()
+            && first.date_taken == second.date_taken
+            && first.is_raw.unwrap_or(false) != second.is_raw.unwrap_or(false)
+            && Self::directory(first) == Self::directory(second)
+            && Self::base_name(&first.name) == Self::base_name(&second.name)
+    }
+
+    pub fn representative(members: &[Photo], preference: StackRepresentative) -> Option<&Photo> {
+        members.iter().min_by_key(|photo| (photo.is_raw.unwrap_or(false) != preference.prefers_raw(), photo.id))
+    }
+
+    // Keeps stand-alone photos and the representative of each stack.
+    pub fn representative_clause(alias: &str) -> String {
+        format!("({alias}.stack_id IS NULL OR {alias}.stack_id = {alias}.id)")
+    }
+
+    pub fn count_expression(alias: &str) -> String {
+        format!(
+            "CASE WHEN {alias}.stack_id IS NULL THEN NULL ELSE (SELECT count(*) FROM photos sm WHERE sm.stack_id = {alias}.stack_id) END"
+        )
+    }
+}
@@ -0,0 +1,71 @@
+use crate::prelude::*;
+use std::time::SystemTime;
+
+// Byte budgets for the `.previews` folder, keyed by storage id. `*` applies to storages without an
+// entry of their own; 0 or no entry at all means the cache may grow without limit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PreviewCacheBudgets {
+    pub default_bytes: u64,
+    pub storages: HashMap<Uuid, u64>,
+}
+
+impl PreviewCacheBudgets {
+    pub const ANY_STORAGE: &'static str = "*";
+
+    pub fn parse(value: &serde_json::Value) -> Option<Self> {
+        let mut budgets = Self::default();
+        for (key, limit) in value.as_object()? {
+            let bytes = limit.as_f64().filter(|bytes| *bytes >= 0.0)? as u64;
+            if key == Self::ANY_STORAGE {
+                budgets.default_bytes = bytes;
+            } else {
+                budgets.storages.insert(Uuid::parse_str(key).ok()?, bytes);
+            }
+        }
+        Some(budgets)
+    }
+
+    pub fn max_bytes(&self, storage_id: Uuid) -> u64 {
+        self.storages.get(&storage_id).copied().unwrap_or(self.default_bytes)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreviewCacheFile {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub last_access: SystemTime,
+}
+
+pub struct PreviewCache;
+
+impl PreviewCache {
+    // Files served this recently may still be streaming to a client, so they are never evicted.
+    pub const SERVE_GRACE: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+    // Least recently accessed first until the total fits; protected files count but are kept.
+    pub fn eviction_plan(
+        mut files: Vec<PreviewCacheFile>,
+        max_bytes: u64,
+        is_protected: impl Fn(&PreviewCacheFile) -> bool,
+    ) -> Vec<PreviewCacheFile> {
+        let mut total = files.iter().map(|file| file.bytes).sum::<u64>();
+        if max_bytes == 0 || total <= max_bytes {
+            return Vec::new();
+        }
+
+        files.sort_by(|left, right| left.last_access.cmp(&right.last_access).then_with(|| left.path.cmp(&right.path)));
+        let mut evicted = Vec::new();
+        for file in files {
+            if total <= max_bytes {
+                break;
+            }
+            if is_protected(&file) {
+                continue;
+            }
+            total = total.saturating_sub(file.bytes);
+            evicted.push(file);
+        }
+        evicted
+    }
+}
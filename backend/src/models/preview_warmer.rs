@@ -0,0 +1,79 @@
+use chrono::Timelike;
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PreviewWarmScope {
+    None,
+    #[default]
+    Recent,
+    All,
+}
+
+impl PreviewWarmScope {
+    pub const RECENT_DAYS: i64 = 30;
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "recent" => Some(Self::Recent),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Recent => "recent",
+            Self::All => "all",
+        }
+    }
+
+    // Photos added before this instant are left to be generated on first view.
+    pub fn since(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Recent => Some(now - Duration::days(Self::RECENT_DAYS)),
+            Self::None | Self::All => None,
+        }
+    }
+}
+
+// Progress of the current or last warm-up run, as reported on the dashboard.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewWarmerStatus {
+    pub scope: PreviewWarmScope,
+    pub running: bool,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub scanned: u64,
+    pub generated: u64,
+    pub failed: u64,
+    // Photos in scope still missing a preview once the per-run limit was reached.
+    pub remaining: u64,
+}
+
+impl PreviewWarmerStatus {
+    pub const MAX_PER_RUN_RANGE: (f64, f64) = (1.0, 100_000.0);
+    pub const DEFAULT_MAX_PER_RUN: u64 = 500;
+    pub const NIGHTLY_HOUR: u32 = 3;
+
+    // Runs once per UTC day from the nightly hour on, like the notification digest.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        if self.running || now.hour() < Self::NIGHTLY_HOUR {
+            return false;
+        }
+        self.started_at.is_none_or(|started| started.date_naive() < now.date_naive())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PreviewWarmCandidate {
+    pub id: Uuid,
+    #[serde(alias = "storage_id")]
+    pub storage_id: Uuid,
+    pub path: String,
+    pub hash: String,
+}
@@ -0,0 +1,42 @@
+use std::path::{Path, PathBuf};
+use xxhash_rust::xxh3::Xxh3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreviewWatermark {
+    pub image_path: PathBuf,
+    pub opacity: f32,
+}
+
+impl PreviewWatermark {
+    pub const DEFAULT_OPACITY: f32 = 0.35;
+    // The watermark spans at most this share of the preview width.
+    pub const MAX_WIDTH_RATIO: f32 = 0.25;
+    pub const MARGIN_RATIO: f32 = 0.02;
+
+    pub fn new(image_path: impl Into<PathBuf>, opacity: f64) -> Self {
+        let opacity = if opacity.is_finite() { opacity.clamp(0.0, 1.0) as f32 } else { Self::DEFAULT_OPACITY };
+        Self { image_path: image_path.into(), opacity }
+    }
+
+    // Changes to the settings or the watermark file produce a new key, so stale variants are never served.
+    pub fn cache_key(&self) -> String {
+        let mut hasher = Xxh3::new();
+        hasher.update(self.image_path.to_string_lossy().as_bytes());
+        hasher.update(&self.opacity.to_bits().to_le_bytes());
+        if let Ok(metadata) = std::fs::metadata(&self.image_path) {
+            hasher.update(&metadata.len().to_le_bytes());
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|elapsed| elapsed.as_nanos())
+                .unwrap_or_default();
+            hasher.update(&modified.to_le_bytes());
+        }
+        format!("{:08x}", hasher.digest() as u32)
+    }
+
+    pub fn variant_path(&self, preview_path: &Path, hash: &str) -> PathBuf {
+        preview_path.with_file_name(format!("{}.wm-{}.jpg", hash, self.cache_key()))
+    }
+}
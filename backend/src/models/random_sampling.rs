@@ -0,0 +1,27 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RandomSampling {
+    Full,
+    Sample(f64),
+}
+
+impl RandomSampling {
+    pub const ROW_THRESHOLD: i64 = 20_000;
+    const OVERSAMPLE: f64 = 50.0;
+    const MIN_PERCENT: f64 = 0.01;
+
+    pub fn choose(estimated_rows: i64, count: u32) -> Self {
+        if estimated_rows <= Self::ROW_THRESHOLD {
+            return Self::Full;
+        }
+
+        let percent = (count.max(1) as f64 * Self::OVERSAMPLE * 100.0) / estimated_rows as f64;
+        if percent >= 100.0 { Self::Full } else { Self::Sample(percent.max(Self::MIN_PERCENT)) }
+    }
+
+    pub fn table_clause(&self, table: &str, alias: &str) -> String {
+        match self {
+            Self::Full => format!("{} {}", table, alias),
+            Self::Sample(percent) => format!("{} {} TABLESAMPLE SYSTEM ({:.4})", table, alias, percent),
+        }
+    }
+}
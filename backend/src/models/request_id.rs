@@ -0,0 +1,31 @@
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    pub const HEADER: &'static str = "x-request-id";
+    const MAX_LENGTH: usize = 128;
+
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    pub fn from_header(value: Option<&str>) -> Self {
+        value
+            .map(str::trim)
+            .filter(|candidate| Self::is_valid(candidate))
+            .map(|candidate| Self(candidate.to_string()))
+            .unwrap_or_else(Self::generate)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn is_valid(candidate: &str) -> bool {
+        !candidate.is_empty()
+            && candidate.len() <= Self::MAX_LENGTH
+            && candidate.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '.')
+    }
+}
@@ -0,0 +1,62 @@
+// What is recorded about the device a refresh token was issued to. The user agent is reduced to a
+// short summary, which is all the session list needs and keeps fingerprinting detail out of the table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionClient {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl SessionClient {
+    pub const SESSION_HEADER: &'static str = "x-session-id";
+    const MAX_IP_LENGTH: usize = 64;
+
+    pub fn from_headers(user_agent: Option<&str>, forwarded_for: Option<&str>, real_ip: Option<&str>) -> Self {
+        // The first x-forwarded-for entry is the original client; later ones are proxies.
+        let ip_address = forwarded_for
+            .and_then(|value| value.split(',').next())
+            .or(real_ip)
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty() && ip.len() <= Self::MAX_IP_LENGTH)
+            .map(str::to_string);
+        Self { user_agent: user_agent.and_then(Self::summarize_user_agent), ip_address }
+    }
+
+    pub fn summarize_user_agent(user_agent: &str) -> Option<String> {
+        let user_agent = user_agent.trim();
+        if user_agent.is_empty() {
+            return None;
+        }
+        // Order matters: Edge and Opera also claim Chrome, and Chrome also claims Safari.
+        let browser = [
+            ("Edg/", "Edge"),
+            ("OPR/", "Opera"),
+            ("Firefox/", "Firefox"),
+            ("Chrome/", "Chrome"),
+            ("CriOS/", "Chrome"),
+            ("Safari/", "Safari"),
+        ]
+        .iter()
+        .find(|(marker, _)| user_agent.contains(marker))
+        .map(|(_, name)| *name);
+        let os = [
+            ("Android", "Android"),
+            ("iPhone", "iOS"),
+            ("iPad", "iPadOS"),
+            ("Windows", "Windows"),
+            ("Mac OS X", "macOS"),
+            ("CrOS", "ChromeOS"),
+            ("Linux", "Linux"),
+        ]
+        .iter()
+        .find(|(marker, _)| user_agent.contains(marker))
+        .map(|(_, name)| *name);
+
+        let summary = match (browser, os) {
+            (Some(browser), Some(os)) => format!("{} on {}", browser, os),
+            (Some(name), None) | (None, Some(name)) => name.to_string(),
+            // Scripts and apps: keep the product token, e.g. "curl/8.4.0".
+            (None, None) => user_agent.split_whitespace().next().unwrap_or_default().chars().take(40).collect(),
+        };
+        Some(summary)
+    }
+}
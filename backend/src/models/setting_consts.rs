@@ -11,7 +11,16 @@ impl SettingConsts {
     pub const PREVIEW_FORMAT: &'static str = "jpg";
     pub const PREVIEW_CONTENT_TYPE: &'static str = "image/jpeg";
 
+    pub const SCRUBBED_FOLDER: &'static str = ".scrubbed";
+
+    pub const PENDING_IMPORTS_FILE: &'static str = ".pending-imports.json";
+
+    pub const DEFAULT_TIMELINE_PER_DAY_LIMIT: u32 = 100;
+    pub const MAX_TIMELINE_PER_DAY_LIMIT: u32 = 1000;
+
     pub const DEFAULT_HTTP_IMAGE_CACHE_HEADER: &'static str = "public, max-age=31536000, immutable";
+    pub const WATERMARKED_HTTP_IMAGE_CACHE_HEADER: &'static str = "private, max-age=3600";
+    pub const PRIVATE_HTTP_IMAGE_CACHE_HEADER: &'static str = "private, max-age=31536000";
 
     pub const DEFAULT_STORAGE_ID: Uuid = Uuid::from_u128(0x00000000000000000000000000000001);
 }
@@ -0,0 +1,28 @@
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TagMatch {
+    #[default]
+    Any,
+    All,
+}
+
+impl TagMatch {
+    pub const INVALID_MATCH: &'static str = "invalid tag match";
+
+    pub fn parse(value: Option<&str>) -> Result<Self, &'static str> {
+        match value.map(str::trim).filter(|value| !value.is_empty()) {
+            None => Ok(Self::default()),
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "any" => Ok(Self::Any),
+                "all" => Ok(Self::All),
+                _ => Err(Self::INVALID_MATCH),
+            },
+        }
+    }
+
+    pub fn requires_all(&self) -> bool {
+        matches!(self, Self::All)
+    }
+}
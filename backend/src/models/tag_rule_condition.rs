@@ -0,0 +1,211 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagRuleField {
+    Make,
+    Model,
+    LensMake,
+    LensModel,
+    Artist,
+    Software,
+    Format,
+    FocalLength,
+    FocalLength35mm,
+    Iso,
+    Aperture,
+    Rating,
+    Width,
+    Height,
+    IsRaw,
+}
+
+impl TagRuleField {
+    pub const ALL: [TagRuleField; 15] = [
+        Self::Make,
+        Self::Model,
+        Self::LensMake,
+        Self::LensModel,
+        Self::Artist,
+        Self::Software,
+        Self::Format,
+        Self::FocalLength,
+        Self::FocalLength35mm,
+        Self::Iso,
+        Self::Aperture,
+        Self::Rating,
+        Self::Width,
+        Self::Height,
+        Self::IsRaw,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|field| field.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Make => "make",
+            Self::Model => "model",
+            Self::LensMake => "lensMake",
+            Self::LensModel => "lensModel",
+            Self::Artist => "artist",
+            Self::Software => "software",
+            Self::Format => "format",
+            Self::FocalLength => "focalLength",
+            Self::FocalLength35mm => "focalLength35mm",
+            Self::Iso => "iso",
+            Self::Aperture => "aperture",
+            Self::Rating => "rating",
+            Self::Width => "width",
+            Self::Height => "height",
+            Self::IsRaw => "isRaw",
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::FocalLength
+                | Self::FocalLength35mm
+                | Self::Iso
+                | Self::Aperture
+                | Self::Rating
+                | Self::Width
+                | Self::Height
+        )
+    }
+
+    // Fields only stored on the EXIF record; the rest are denormalized onto the photo.
+    pub fn needs_exif(&self) -> bool {
+        matches!(self, Self::Software | Self::FocalLength35mm)
+    }
+
+    pub fn read(&self, photo: &Photo, exif: Option<&ExifModel>) -> Option<TagRuleValue> {
+        let text = |value: &Option<String>| value.as_deref().map(|value| TagRuleValue::Text(value.to_string()));
+        let number = |value: Option<f64>| value.map(TagRuleValue::Number);
+        match self {
+            Self::Make => text(&photo.make),
+            Self::Model => text(&photo.model),
+            Self::LensMake => text(&photo.lens_make),
+            Self::LensModel => text(&photo.lens_model),
+            Self::Artist => text(&photo.artist),
+            Self::Software => exif.and_then(|exif| text(&exif.software)),
+            Self::Format => text(&photo.format),
+            Self::FocalLength => number(photo.focal_length.map(f64::from)),
+            Self::FocalLength35mm => number(exif.and_then(|exif| exif.focal_length_in_35mm_film).map(f64::from)),
+            Self::Iso => number(photo.iso.map(f64::from)),
+            Self::Aperture => number(photo.aperture.map(f64::from)),
+            Self::Rating => number(photo.rating.map(f64::from)),
+            Self::Width => number(photo.width.map(f64::from)),
+            Self::Height => number(photo.height.map(f64::from)),
+            Self::IsRaw => photo.is_raw.map(TagRuleValue::Bool),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagRuleOperator {
+    Equals,
+    NotEquals,
+    Contains,
+    StartsWith,
+    GreaterThan,
+    GreaterOrEqual,
+    LessThan,
+    LessOrEqual,
+}
+
+impl TagRuleOperator {
+    pub const ALL: [TagRuleOperator; 8] = [
+        Self::Equals,
+        Self::NotEquals,
+        Self::Contains,
+        Self::StartsWith,
+        Self::GreaterThan,
+        Self::GreaterOrEqual,
+        Self::LessThan,
+        Self::LessOrEqual,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "==" | "=" => Some(Self::Equals),
+            "!=" => Some(Self::NotEquals),
+            ">" => Some(Self::GreaterThan),
+            ">=" => Some(Self::GreaterOrEqual),
+            "<" => Some(Self::LessThan),
+            "<=" => Some(Self::LessOrEqual),
+            other => Self::ALL.into_iter().find(|operator| operator.as_str().eq_ignore_ascii_case(other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equals => "eq",
+            Self::NotEquals => "ne",
+            Self::Contains => "contains",
+            Self::StartsWith => "startsWith",
+            Self::GreaterThan => "gt",
+            Self::GreaterOrEqual => "gte",
+            Self::LessThan => "lt",
+            Self::LessOrEqual => "lte",
+        }
+    }
+
+    pub fn is_ordering(&self) -> bool {
+        matches!(self, Self::GreaterThan | Self::GreaterOrEqual | Self::LessThan | Self::LessOrEqual)
+    }
+
+    pub fn is_text_only(&self) -> bool {
+        matches!(self, Self::Contains | Self::StartsWith)
+    }
+
+    // A missing value never matches, not even a "not equals" rule.
+    pub fn evaluate(&self, actual: &TagRuleValue, expected: &str) -> bool {
+        let expected = expected.trim();
+        match actual {
+            TagRuleValue::Text(actual) => {
+                let actual = actual.trim().to_lowercase();
+                let expected = expected.to_lowercase();
+                match self {
+                    Self::Equals => actual == expected,
+                    Self::NotEquals => actual != expected,
+                    Self::Contains => actual.contains(&expected),
+                    Self::StartsWith => actual.starts_with(&expected),
+                    _ => false,
+                }
+            }
+            TagRuleValue::Number(actual) => {
+                let Ok(expected) = expected.parse::<f64>() else {
+                    return false;
+                };
+                match self {
+                    Self::Equals => (actual - expected).abs() < f64::EPSILON,
+                    Self::NotEquals => (actual - expected).abs() >= f64::EPSILON,
+                    Self::GreaterThan => *actual > expected,
+                    Self::GreaterOrEqual => *actual >= expected,
+                    Self::LessThan => *actual < expected,
+                    Self::LessOrEqual => *actual <= expected,
+                    _ => false,
+                }
+            }
+            TagRuleValue::Bool(actual) => {
+                let Ok(expected) = expected.to_lowercase().parse::<bool>() else {
+                    return false;
+                };
+                match self {
+                    Self::Equals => *actual == expected,
+                    Self::NotEquals => *actual != expected,
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagRuleValue {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+}
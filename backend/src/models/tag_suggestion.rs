@@ -0,0 +1,49 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSuggestion {
+    #[serde(alias = "tag", alias = "label")]
+    pub name: String,
+    #[serde(default, alias = "score")]
+    pub confidence: f32,
+}
+
+impl TagSuggestion {
+    pub fn new(name: &str, confidence: f32) -> Self {
+        Self { name: name.to_string(), confidence }
+    }
+
+    // Trims names, clamps confidences, keeps the best score per name and drops anything below the threshold.
+    pub fn normalize(suggestions: Vec<TagSuggestion>, min_confidence: f32) -> Vec<TagSuggestion> {
+        let mut best: Vec<TagSuggestion> = Vec::new();
+        for suggestion in suggestions {
+            let name = suggestion.name.trim();
+            let confidence =
+                if suggestion.confidence.is_finite() { suggestion.confidence.clamp(0.0, 1.0) } else { 0.0 };
+            if name.is_empty() || confidence < min_confidence {
+                continue;
+            }
+            match best.iter_mut().find(|existing| existing.name.to_lowercase() == name.to_lowercase()) {
+                Some(existing) => existing.confidence = existing.confidence.max(confidence),
+                None => best.push(Self::new(name, confidence)),
+            }
+        }
+        best.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| a.name.cmp(&b.name)));
+        best
+    }
+}
+
+// Connection details for an external inference service; built from the ml.* settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageAnalyzerConfig {
+    pub url: String,
+    pub timeout: std::time::Duration,
+    pub min_confidence: f32,
+}
+
+impl ImageAnalyzerConfig {
+    pub const TIMEOUT_RANGE: (f64, f64) = (1.0, 120.0);
+    pub const DEFAULT_TIMEOUT_SECONDS: u64 = 10;
+    pub const DEFAULT_MIN_CONFIDENCE: f32 = 0.5;
+}
@@ -0,0 +1,178 @@
+use crate::prelude::*;
+
+// Google Takeout writes one JSON sidecar per media file. Only the fields below are read; anything
+// else in the file is ignored so newer export formats keep importing.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TakeoutSidecar {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub photo_taken_time: Option<TakeoutTimestamp>,
+    #[serde(default)]
+    pub geo_data: Option<TakeoutGeoData>,
+    #[serde(default)]
+    pub geo_data_exif: Option<TakeoutGeoData>,
+    #[serde(default)]
+    pub people: Vec<TakeoutPerson>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct TakeoutTimestamp {
+    // Exported as a string of epoch seconds, but some tools rewrite it as a number.
+    #[serde(default)]
+    pub timestamp: JsonValue,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct TakeoutGeoData {
+    #[serde(default)]
+    pub latitude: f64,
+    #[serde(default)]
+    pub longitude: f64,
+    #[serde(default)]
+    pub altitude: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct TakeoutPerson {
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TakeoutLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,
+}
+
+impl TakeoutSidecar {
+    pub const EXTENSION: &'static str = "json";
+    pub const SUPPLEMENTAL_SUFFIX: &'static str = ".supplemental-metadata";
+    pub const ALBUM_METADATA_FILE: &'static str = "metadata.json";
+    // Takeout truncates sidecar names to this many characters before appending ".json".
+    const MAX_NAME_LENGTH: usize = 46;
+
+    pub fn parse(content: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(content)
+    }
+
+    pub fn date_taken(&self) -> Option<DateTime<Utc>> {
+        let timestamp = &self.photo_taken_time.as_ref()?.timestamp;
+        let seconds = match timestamp {
+            JsonValue::String(value) => value.trim().parse::<i64>().ok()?,
+            JsonValue::Number(value) => value.as_i64()?,
+            _ => return None,
+        };
+        // Zero is what Takeout writes when Google never knew the capture time.
+        if seconds <= 0 {
+            return None;
+        }
+        DateTime::<Utc>::from_timestamp(seconds, 0)
+    }
+
+    pub fn caption(&self) -> Option<String> {
+        self.description.as_deref().map(str::trim).filter(|text| !text.is_empty()).map(str::to_string)
+    }
+
+    // Edits made in Google Photos land in geoData; geoDataExif keeps what the camera recorded.
+    pub fn location(&self) -> Option<TakeoutLocation> {
+        [self.geo_data.as_ref(), self.geo_data_exif.as_ref()].into_iter().flatten().find_map(|geo| {
+            let valid = geo.latitude.is_finite()
+                && geo.longitude.is_finite()
+                && geo.latitude.abs() <= 90.0
+                && geo.longitude.abs() <= 180.0
+                && (geo.latitude != 0.0 || geo.longitude != 0.0);
+            valid.then(|| TakeoutLocation {
+                latitude: geo.latitude,
+                longitude: geo.longitude,
+                altitude: geo.altitude.filter(|altitude| altitude.is_finite() && *altitude != 0.0),
+            })
+        })
+    }
+
+    pub fn people(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.people
+            .iter()
+            .map(|person| person.name.trim())
+            .filter(|name| !name.is_empty() && seen.insert(name.to_lowercase()))
+            .map(str::to_string)
+            .collect()
+    }
+
+    // Edited copies and duplicates are named "IMG_1(1).jpg" but their sidecar is "IMG_1.jpg(1).json".
+    pub fn candidate_names(file_name: &str) -> Vec<String> {
+        let mut bases = vec![file_name.to_string()];
+        if let Some((stem, extension)) = file_name.rsplit_once('.') {
+            if let Some((original, counter)) = stem.strip_suffix(')').and_then(|stem| stem.rsplit_once('(')) {
+                if !counter.is_empty() && counter.chars().all(|ch| ch.is_ascii_digit()) {
+                    bases.push(format!("{}.{}({})", original, extension, counter));
+                }
+            }
+            if let Some(original) = stem.strip_suffix("-edited") {
+                bases.push(format!("{}.{}", original, extension));
+            }
+        }
+
+        let mut names = Vec::new();
+        for base in bases {
+            for name in [
+                format!("{}.{}", base, Self::EXTENSION),
+                format!("{}{}.{}", base, Self::SUPPLEMENTAL_SUFFIX, Self::EXTENSION),
+                format!("{}.{}", base.chars().take(Self::MAX_NAME_LENGTH).collect::<String>(), Self::EXTENSION),
+            ] {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    pub fn find(image_path: &Path) -> Option<PathBuf> {
+        let folder = image_path.parent()?;
+        let file_name = image_path.file_name()?.to_str()?;
+        Self::candidate_names(file_name).into_iter().map(|name| folder.join(name)).find(|path| path.is_file())
+    }
+
+    // A missing or unreadable sidecar is not an error; the photo imports with its EXIF data alone.
+    pub fn load(image_path: &Path) -> Option<Self> {
+        let path = Self::find(image_path)?;
+        let content = std::fs::read(&path)
+            .map_err(|error| log::warn!("Failed to read Takeout sidecar {}: {}", path.display(), error))
+            .ok()?;
+        Self::parse(&content)
+            .map_err(|error| log::warn!("Ignoring malformed Takeout sidecar {}: {}", path.display(), error))
+            .ok()
+    }
+}
+
+// Album folders in a Takeout carry a metadata.json describing the album; year folders such as
+// "Photos from 2020" do not, so they never become albums.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TakeoutAlbum {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl TakeoutAlbum {
+    pub fn parse(content: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<Self>(content)
+            .ok()
+            .map(|album| Self { title: album.title.trim().to_string(), ..album })
+            .filter(|album| !album.title.is_empty())
+    }
+
+    pub fn load(image_path: &Path) -> Option<Self> {
+        let path = image_path.parent()?.join(TakeoutSidecar::ALBUM_METADATA_FILE);
+        let content = std::fs::read(path).ok()?;
+        Self::parse(&content)
+    }
+}
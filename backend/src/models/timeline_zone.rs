@@ -0,0 +1,126 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineZone(String);
+
+impl TimelineZone {
+    pub const UTC: &'static str = "UTC";
+    pub const DAY_ORDER: &'static str = "day_date DESC NULLS LAST";
+    const MAX_LENGTH: usize = 64;
+
+    pub fn utc() -> Self {
+        Self(Self::UTC.to_string())
+    }
+
+    pub fn from_name(value: Option<&str>) -> Self {
+        value
+            .map(str::trim)
+            .filter(|candidate| Self::is_valid(candidate))
+            .map(|candidate| Self(candidate.to_string()))
+            .unwrap_or_else(Self::utc)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_utc(&self) -> bool {
+        matches!(self.0.as_str(), "UTC" | "Etc/UTC" | "Etc/UCT" | "UCT" | "Zulu" | "Etc/Zulu")
+    }
+
+    pub fn sql_cte(param_index: usize) -> String {
+        format!(
+            "tz AS (SELECT COALESCE((SELECT name FROM pg_timezone_names WHERE name = ${} LIMIT 1), 'UTC') AS name)",
+            param_index
+        )
+    }
+
+    pub fn day_expression(&self, alias: &str) -> String {
+        if self.is_utc() {
+            format!("{}.day_date", alias)
+        } else {
+            format!("({}.sort_date AT TIME ZONE tz.name)::date", alias)
+        }
+    }
+
+    pub fn day_groups_cte(&self, tz_param_index: usize) -> String {
+        self.filtered_day_groups_cte(tz_param_index, None)
+    }
+
+    // The condition is ANDed onto the photos scan, so days without a matching photo are left out.
+    pub fn filtered_day_groups_cte(&self, tz_param_index: usize, condition: Option<&str>) -> String {
+        format!(
+            "{}, day_groups AS (SELECT DISTINCT {} AS day_date FROM photos p CROSS JOIN tz WHERE p.day_date IS NOT NULL{})",
+            Self::sql_cte(tz_param_index),
+            self.day_expression("p"),
+            condition.map(|condition| format!(" AND {}", condition)).unwrap_or_default()
+        )
+    }
+
+    pub fn days_page_sql(&self) -> String {
+        self.filtered_days_page_sql(None)
+    }
+
+    pub fn filtered_days_page_sql(&self, condition: Option<&str>) -> String {
+        format!(
+            "WITH {} SELECT to_char(day_date, 'YYYY-MM-DD') AS day FROM day_groups ORDER BY {} LIMIT $1 OFFSET $2",
+            self.filtered_day_groups_cte(3, condition),
+            Self::DAY_ORDER
+        )
+    }
+
+    pub fn year_offset_sql(&self) -> String {
+        self.granular_year_offset_sql(TimelineGranularity::Day)
+    }
+
+    // Month granularity ranks distinct months instead of days, for scrubbers that page by month.
+    pub fn granular_year_offset_sql(&self, granularity: TimelineGranularity) -> String {
+        format!(
+            r#"WITH {}, periods AS (
+                SELECT DISTINCT {} AS day_date
+                FROM day_groups
+            ), ranked_days AS (
+                SELECT day_date, ROW_NUMBER() OVER (ORDER BY {}) - 1 AS position
+                FROM periods
+            )
+            SELECT COALESCE(
+                MIN(position) FILTER (WHERE EXTRACT(YEAR FROM day_date)::int <= $1),
+                count(*) FILTER (WHERE day_date IS NOT NULL)
+            ) AS offset
+            FROM ranked_days"#,
+            self.day_groups_cte(2),
+            granularity.period_expression("day_date"),
+            Self::DAY_ORDER
+        )
+    }
+
+    pub fn is_valid(candidate: &str) -> bool {
+        !candidate.is_empty()
+            && candidate.len() <= Self::MAX_LENGTH
+            && candidate.chars().all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '/' | '_' | '-' | '+'))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimelineGranularity {
+    #[default]
+    Day,
+    Month,
+}
+
+impl TimelineGranularity {
+    pub const INVALID_GRANULARITY: &'static str = "granularity must be 'day' or 'month'";
+
+    pub fn parse(value: Option<&str>) -> Result<Self, &'static str> {
+        match value.map(|value| value.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("day") => Ok(Self::Day),
+            Some("month") => Ok(Self::Month),
+            Some(_) => Err(Self::INVALID_GRANULARITY),
+        }
+    }
+
+    pub fn period_expression(&self, column: &str) -> String {
+        match self {
+            Self::Day => column.to_string(),
+            Self::Month => format!("date_trunc('month', {column})::date"),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::prelude::*;
+use hmac::{Hmac, Mac};
+use rand::RngExt;
+use sha1::Sha1;
+
+type HmacSha1 = Hmac<Sha1>;
+
+// RFC 6238 time-based codes with the parameters every authenticator app understands: HMAC-SHA1,
+// six digits, 30 second steps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Totp {
+    secret: Vec<u8>,
+}
+
+impl Totp {
+    pub const DIGITS: usize = 6;
+    pub const PERIOD_SECONDS: i64 = 30;
+    // One step either side absorbs clock drift between the server and the phone.
+    pub const ALLOWED_DRIFT_STEPS: i64 = 1;
+    const SECRET_BYTES: usize = 20;
+    const BASE32_ALPHABET: &'static [u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    pub fn generate() -> Self {
+        let mut secret = vec![0u8; Self::SECRET_BYTES];
+        rand::rng().fill(&mut secret[..]);
+        Self { secret }
+    }
+
+    pub fn from_bytes(secret: &[u8]) -> Self {
+        Self { secret: secret.to_vec() }
+    }
+
+    pub fn from_base32(encoded: &str) -> Option<Self> {
+        let mut secret = Vec::new();
+        let mut buffer = 0u32;
+        let mut bits = 0;
+        for ch in encoded.chars().filter(|ch| !ch.is_whitespace() && *ch != '=') {
+            let value = Self::BASE32_ALPHABET.iter().position(|symbol| *symbol as char == ch.to_ascii_uppercase())?;
+            buffer = (buffer << 5) | value as u32;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                secret.push((buffer >> bits) as u8);
+                buffer &= (1 << bits) - 1;
+            }
+        }
+        if secret.is_empty() { None } else { Some(Self { secret }) }
+    }
+
+    pub fn to_base32(&self) -> String {
+        let mut encoded = String::new();
+        let mut buffer = 0u32;
+        let mut bits = 0;
+        for byte in &self.secret {
+            buffer = (buffer << 8) | *byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                encoded.push(Self::BASE32_ALPHABET[((buffer >> bits) & 31) as usize] as char);
+            }
+            buffer &= (1 << bits) - 1;
+        }
+        if bits > 0 {
+            encoded.push(Self::BASE32_ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+        }
+        encoded
+    }
+
+    pub fn step(at: DateTime<Utc>) -> i64 {
+        at.timestamp().div_euclid(Self::PERIOD_SECONDS)
+    }
+
+    pub fn code_for_step(&self, step: i64) -> String {
+        let mut mac = HmacSha1::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(&(step as u64).to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let value =
+            u32::from_be_bytes([digest[offset] & 0x7f, digest[offset + 1], digest[offset + 2], digest[offset + 3]]);
+        format!("{:0width$}", value % 10u32.pow(Self::DIGITS as u32), width = Self::DIGITS)
+    }
+
+    pub fn code_at(&self, at: DateTime<Utc>) -> String {
+        self.code_for_step(Self::step(at))
+    }
+
+    // Returns the step the code belongs to so callers can refuse to accept the same code twice.
+    pub fn verify(&self, code: &str, at: DateTime<Utc>) -> Option<i64> {
+        let code = code.chars().filter(|ch| !ch.is_whitespace()).collect::<String>();
+        if code.len() != Self::DIGITS || !code.chars().all(|ch| ch.is_ascii_digit()) {
+            return None;
+        }
+        let current = Self::step(at);
+        (current - Self::ALLOWED_DRIFT_STEPS..=current + Self::ALLOWED_DRIFT_STEPS)
+            .find(|step| self.code_for_step(*step) == code)
+    }
+
+    pub fn provisioning_uri(&self, issuer: &str, account: &str) -> String {
+        let issuer = urlencoding::encode(issuer);
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            issuer,
+            urlencoding::encode(account),
+            self.to_base32(),
+            issuer,
+            Self::DIGITS,
+            Self::PERIOD_SECONDS
+        )
+    }
+}
@@ -0,0 +1,504 @@
+use anyhow::{Result, bail};
+use quick_xml::Writer;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::reader::NsReader;
+use std::io::Read;
+
+use crate::prelude::*;
+
+const RDF_NS: &[u8] = b"http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const DC_NS: &[u8] = b"http://purl.org/dc/elements/1.1/";
+const XMP_NS: &[u8] = b"http://ns.adobe.com/xap/1.0/";
+const MWG_RS_NS: &[u8] = b"http://www.metadataworkinggroup.com/schemas/regions/";
+const ST_AREA_NS: &[u8] = b"http://ns.adobe.com/xmp/sType/Area#";
+const MP_RI_NS: &[u8] = b"http://ns.microsoft.com/photo/1.2/t/RegionInfo#";
+const MP_REG_NS: &[u8] = b"http://ns.microsoft.com/photo/1.2/t/Region#";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum XmpProperty {
+    Subject,
+    Title,
+    Description,
+    Rating,
+    Label,
+}
+
+impl XmpProperty {
+    fn resolve(namespace: &ResolveResult, local_name: &[u8]) -> Option<Self> {
+        let ResolveResult::Bound(Namespace(namespace)) = namespace else {
+            return None;
+        };
+        match (*namespace, local_name) {
+            (DC_NS, b"subject") => Some(Self::Subject),
+            (DC_NS, b"title") => Some(Self::Title),
+            (DC_NS, b"description") => Some(Self::Description),
+            (XMP_NS, b"Rating") => Some(Self::Rating),
+            (XMP_NS, b"Label") => Some(Self::Label),
+            _ => None,
+        }
+    }
+
+    // Only keywords, title and caption are written back; rating and label stay as the other tool left them.
+    fn is_written(&self) -> bool {
+        matches!(self, Self::Subject | Self::Title | Self::Description)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum XmpElement {
+    Root,
+    Description,
+    Property(XmpProperty),
+    Regions,
+    Item,
+    Other,
+}
+
+impl XmpElement {
+    fn resolve(namespace: &ResolveResult, local_name: &[u8]) -> Self {
+        let is =
+            |expected: &[u8]| matches!(namespace, ResolveResult::Bound(Namespace(namespace)) if *namespace == expected);
+        match local_name {
+            b"RDF" if is(RDF_NS) => Self::Root,
+            b"Description" if is(RDF_NS) => Self::Description,
+            b"li" if is(RDF_NS) => Self::Item,
+            b"RegionList" if is(MWG_RS_NS) => Self::Regions,
+            b"Regions" if is(MP_RI_NS) => Self::Regions,
+            _ => XmpProperty::resolve(namespace, local_name).map(Self::Property).unwrap_or(Self::Other),
+        }
+    }
+}
+
+// Fields of one face region, in the MWG form (Lightroom, digiKam, Picasa) or the older Windows
+// Photo Gallery form. Both may appear as attributes or as child elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RegionField {
+    Name,
+    Type,
+    AreaX,
+    AreaY,
+    AreaWidth,
+    AreaHeight,
+    AreaUnit,
+    Rectangle,
+}
+
+impl RegionField {
+    fn resolve(namespace: &ResolveResult, local_name: &[u8]) -> Option<Self> {
+        let ResolveResult::Bound(Namespace(namespace)) = namespace else {
+            return None;
+        };
+        match (*namespace, local_name) {
+            (MWG_RS_NS, b"Name") | (MP_REG_NS, b"PersonDisplayName") => Some(Self::Name),
+            (MWG_RS_NS, b"Type") => Some(Self::Type),
+            (ST_AREA_NS, b"x") => Some(Self::AreaX),
+            (ST_AREA_NS, b"y") => Some(Self::AreaY),
+            (ST_AREA_NS, b"w") => Some(Self::AreaWidth),
+            (ST_AREA_NS, b"h") => Some(Self::AreaHeight),
+            (ST_AREA_NS, b"unit") => Some(Self::AreaUnit),
+            (MP_REG_NS, b"Rectangle") => Some(Self::Rectangle),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RegionBuilder {
+    name: Option<String>,
+    kind: Option<String>,
+    area: [Option<f32>; 4],
+    unit: Option<String>,
+    rectangle: Option<String>,
+}
+
+impl RegionBuilder {
+    fn set(&mut self, field: RegionField, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        match field {
+            RegionField::Name => self.name = Some(value.to_string()),
+            RegionField::Type => self.kind = Some(value.to_string()),
+            RegionField::AreaX => self.area[0] = value.parse().ok(),
+            RegionField::AreaY => self.area[1] = value.parse().ok(),
+            RegionField::AreaWidth => self.area[2] = value.parse().ok(),
+            RegionField::AreaHeight => self.area[3] = value.parse().ok(),
+            RegionField::AreaUnit => self.unit = Some(value.to_string()),
+            RegionField::Rectangle => self.rectangle = Some(value.to_string()),
+        }
+    }
+
+    fn read_attributes(&mut self, reader: &NsReader<&[u8]>, start: &BytesStart) -> Result<()> {
+        for attribute in start.attributes() {
+            let attribute = attribute?;
+            let (namespace, local_name) = reader.resolve_attribute(attribute.key);
+            if let Some(field) = RegionField::resolve(&namespace, local_name.as_ref()) {
+                self.set(field, &attribute.unescape_value()?);
+            }
+        }
+        Ok(())
+    }
+
+    // Pets, barcodes and focus areas share the region list; only named faces become people.
+    fn finish(self) -> Option<PersonMention> {
+        if self.kind.as_deref().is_some_and(|kind| !kind.eq_ignore_ascii_case("Face")) {
+            return None;
+        }
+        let region = match (self.rectangle, self.area) {
+            (Some(rectangle), _) => PersonRegion::parse_rectangle(&rectangle),
+            (None, [Some(x), Some(y), Some(width), Some(height)])
+                if self.unit.as_deref().is_none_or(|unit| unit.eq_ignore_ascii_case("normalized")) =>
+            {
+                PersonRegion::from_center(x, y, width, height)
+            }
+            _ => None,
+        };
+        PersonMention::new(self.name.as_deref()?, region)
+    }
+}
+
+// The subset of an XMP sidecar that Lightroom and digiKam both write and that maps onto photo fields.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmpSidecar {
+    pub keywords: Vec<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub rating: Option<u8>,
+    pub label: Option<String>,
+    pub people: Vec<PersonMention>,
+}
+
+impl XmpSidecar {
+    pub const EXTENSION: &'static str = "xmp";
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut reader = NsReader::from_str(content);
+        reader.config_mut().trim_text(true);
+
+        let mut sidecar = Self::default();
+        let mut current = None;
+        let mut has_root = false;
+        let mut depth = 0usize;
+        let mut regions_depth = None;
+        let mut region: Option<(usize, RegionBuilder)> = None;
+        let mut region_field = None;
+        loop {
+            let (namespace, event) = reader.read_resolved_event()?;
+            let (element, field) = match &event {
+                Event::Start(start) | Event::Empty(start) => {
+                    let local_name = start.local_name();
+                    (
+                        XmpElement::resolve(&namespace, local_name.as_ref()),
+                        RegionField::resolve(&namespace, local_name.as_ref()),
+                    )
+                }
+                Event::End(end) => (XmpElement::resolve(&namespace, end.local_name().as_ref()), None),
+                _ => (XmpElement::Other, None),
+            };
+
+            // Each rdf:li of a region list is one region, whatever nesting the writer chose inside it.
+            match &event {
+                Event::Start(start) => {
+                    depth += 1;
+                    if element == XmpElement::Regions && regions_depth.is_none() {
+                        regions_depth = Some(depth);
+                    } else if element == XmpElement::Item && regions_depth.is_some() && region.is_none() {
+                        region = Some((depth, RegionBuilder::default()));
+                    }
+                    if let Some((_, builder)) = region.as_mut() {
+                        builder.read_attributes(&reader, start)?;
+                        region_field = field;
+                    }
+                }
+                Event::Empty(start) => {
+                    if element == XmpElement::Item && regions_depth.is_some() && region.is_none() {
+                        let mut builder = RegionBuilder::default();
+                        builder.read_attributes(&reader, start)?;
+                        sidecar.people.extend(builder.finish());
+                    } else if let Some((_, builder)) = region.as_mut() {
+                        builder.read_attributes(&reader, start)?;
+                    }
+                }
+                Event::Text(text) => {
+                    if let (Some(field), Some((_, builder))) = (region_field, region.as_mut()) {
+                        builder.set(field, &text.unescape()?);
+                    }
+                }
+                Event::End(_) => {
+                    region_field = None;
+                    if region.as_ref().is_some_and(|(item_depth, _)| *item_depth == depth) {
+                        sidecar.people.extend(region.take().and_then(|(_, builder)| builder.finish()));
+                    }
+                    if regions_depth == Some(depth) {
+                        regions_depth = None;
+                    }
+                    depth = depth.saturating_sub(1);
+                }
+                _ => {}
+            }
+
+            match event {
+                Event::Start(start) | Event::Empty(start) if element == XmpElement::Description => {
+                    // Lightroom writes simple values such as xmp:Rating as attributes on the description.
+                    for attribute in start.attributes() {
+                        let attribute = attribute?;
+                        let (namespace, local_name) = reader.resolve_attribute(attribute.key);
+                        if let Some(property) = XmpProperty::resolve(&namespace, local_name.as_ref()) {
+                            let value = attribute.unescape_value()?;
+                            sidecar.assign(property, &value);
+                        }
+                    }
+                }
+                Event::Start(_) => match element {
+                    XmpElement::Root => has_root = true,
+                    XmpElement::Property(property) => current = Some(property),
+                    _ => {}
+                },
+                Event::Empty(_) if element == XmpElement::Root => has_root = true,
+                Event::Text(text) => {
+                    if let Some(property) = current {
+                        sidecar.assign(property, &text.unescape()?);
+                    }
+                }
+                Event::End(_) => {
+                    if matches!(element, XmpElement::Property(property) if Some(property) == current) {
+                        current = None;
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if !has_root {
+            bail!("missing rdf:RDF element");
+        }
+        sidecar.people = PersonMention::dedup(sidecar.people);
+        Ok(sidecar)
+    }
+
+    fn assign(&mut self, property: XmpProperty, value: &str) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        match property {
+            XmpProperty::Subject => {
+                if !self.keywords.iter().any(|keyword| keyword.eq_ignore_ascii_case(value)) {
+                    self.keywords.push(value.to_string());
+                }
+            }
+            // Alt containers list one entry per language; the first is the default.
+            XmpProperty::Title => {
+                self.title.get_or_insert_with(|| value.to_string());
+            }
+            XmpProperty::Description => {
+                self.description.get_or_insert_with(|| value.to_string());
+            }
+            // Lightroom marks rejected photos with -1, which has no equivalent here.
+            XmpProperty::Rating => {
+                self.rating = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|rating| (0.0..=5.0).contains(rating))
+                    .map(|rating| rating.round() as u8);
+            }
+            XmpProperty::Label => self.label = Some(value.to_string()),
+        }
+    }
+
+    // digiKam and darktable append ".xmp" to the full file name; Lightroom replaces the extension.
+    pub fn candidate_paths(image_path: &Path) -> Vec<PathBuf> {
+        let Some(file_name) = image_path.file_name().and_then(|name| name.to_str()) else {
+            return Vec::new();
+        };
+        let folder = image_path.parent().unwrap_or_else(|| Path::new(""));
+        let stem = image_path.file_stem().and_then(|stem| stem.to_str()).unwrap_or(file_name);
+        [
+            format!("{}.{}", file_name, Self::EXTENSION),
+            format!("{}.{}", file_name, Self::EXTENSION.to_ascii_uppercase()),
+            format!("{}.{}", stem, Self::EXTENSION),
+            format!("{}.{}", stem, Self::EXTENSION.to_ascii_uppercase()),
+        ]
+        .into_iter()
+        .map(|name| folder.join(name))
+        .collect()
+    }
+
+    pub fn find(image_path: &Path) -> Option<PathBuf> {
+        Self::candidate_paths(image_path).into_iter().find(|path| path.is_file())
+    }
+
+    // A missing sidecar is normal and a malformed one must not fail the import, so both yield None.
+    pub fn load(image_path: &Path) -> Option<Self> {
+        let path = Self::find(image_path)?;
+        let content = std::fs::read_to_string(&path)
+            .map_err(|error| log::warn!("Failed to read XMP sidecar {}: {}", path.display(), error))
+            .ok()?;
+        Self::parse(&content)
+            .map_err(|error| log::warn!("Skipping malformed XMP sidecar {}: {}", path.display(), error))
+            .ok()
+    }
+
+    // Cameras and editors also embed a packet in the image itself. Writers put it near the start of
+    // JPEG, TIFF and DNG files, so only the head is scanned; extended XMP split across segments is not read.
+    pub fn load_embedded(image_path: &Path) -> Option<Self> {
+        let mut head = Vec::new();
+        std::fs::File::open(image_path).ok()?.take(Self::EMBEDDED_SCAN_BYTES).read_to_end(&mut head).ok()?;
+        let packet = Self::embedded_packet(&head)?;
+        Self::parse(&String::from_utf8_lossy(packet))
+            .map_err(|error| log::debug!("Skipping malformed embedded XMP in {}: {}", image_path.display(), error))
+            .ok()
+    }
+
+    const EMBEDDED_SCAN_BYTES: u64 = 2 * 1024 * 1024;
+
+    fn embedded_packet(bytes: &[u8]) -> Option<&[u8]> {
+        const OPEN: &[u8] = b"<x:xmpmeta";
+        const CLOSE: &[u8] = b"</x:xmpmeta>";
+        let start = bytes.windows(OPEN.len()).position(|window| window == OPEN)?;
+        let length = bytes[start..].windows(CLOSE.len()).position(|window| window == CLOSE)? + CLOSE.len();
+        Some(&bytes[start..start + length])
+    }
+
+    // Rewrites keywords, title and description in the first rdf:Description and copies everything
+    // else through untouched, so settings written by other tools survive.
+    pub fn update(existing: &str, keywords: &[String], title: Option<&str>, caption: Option<&str>) -> Result<String> {
+        let mut reader = NsReader::from_str(existing);
+        let mut writer = Writer::new(Vec::new());
+        let mut depth = 0usize;
+        let mut description_depth = None;
+        let mut skip_depth = 0usize;
+        let mut written = false;
+        let mut dc_declared = false;
+
+        loop {
+            let (namespace, event) = reader.read_resolved_event()?;
+            let element = match &event {
+                Event::Start(start) | Event::Empty(start) => {
+                    XmpElement::resolve(&namespace, start.local_name().as_ref())
+                }
+                Event::End(end) => XmpElement::resolve(&namespace, end.local_name().as_ref()),
+                _ => XmpElement::Other,
+            };
+            let in_description = description_depth == Some(depth);
+            let is_empty = matches!(event, Event::Empty(_));
+            let replaced = matches!(element, XmpElement::Property(property) if property.is_written());
+
+            match event {
+                Event::Eof => break,
+                _ if skip_depth > 0 => match event {
+                    Event::Start(_) => skip_depth += 1,
+                    Event::End(_) => skip_depth -= 1,
+                    _ => {}
+                },
+                Event::Start(_) if in_description && replaced => skip_depth = 1,
+                Event::Empty(_) if in_description && replaced => {}
+                // Face regions nest their own rdf:Description; only the outermost one takes our properties.
+                Event::Start(start) | Event::Empty(start)
+                    if element == XmpElement::Description && !written && description_depth.is_none() =>
+                {
+                    let start = Self::strip_written_attributes(&reader, start, dc_declared)?;
+                    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+                    writer.write_event(Event::Start(start))?;
+                    if is_empty {
+                        Self::write_properties(&mut writer, keywords, title, caption)?;
+                        writer.write_event(Event::End(BytesEnd::new(name)))?;
+                        written = true;
+                    } else {
+                        depth += 1;
+                        description_depth = Some(depth);
+                    }
+                }
+                Event::Start(start) => {
+                    dc_declared |= Self::declares_dc(&start);
+                    depth += 1;
+                    writer.write_event(Event::Start(start))?;
+                }
+                Event::End(end) => {
+                    if description_depth == Some(depth) && !written {
+                        Self::write_properties(&mut writer, keywords, title, caption)?;
+                        written = true;
+                        description_depth = None;
+                    }
+                    depth = depth.saturating_sub(1);
+                    writer.write_event(Event::End(end))?;
+                }
+                event => writer.write_event(event)?,
+            }
+        }
+
+        if !written {
+            bail!("missing rdf:Description element");
+        }
+        Ok(String::from_utf8(writer.into_inner())?)
+    }
+
+    pub fn render(keywords: &[String], title: Option<&str>, caption: Option<&str>) -> Result<String> {
+        Self::update(Self::EMPTY_PACKET, keywords, title, caption)
+    }
+
+    const EMPTY_PACKET: &'static str = concat!(
+        "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n",
+        "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\" x:xmptk=\"Nimble Photos\">\n",
+        " <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n",
+        "  <rdf:Description rdf:about=\"\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n",
+        "  </rdf:Description>\n",
+        " </rdf:RDF>\n",
+        "</x:xmpmeta>\n",
+        "<?xpacket end=\"w\"?>\n"
+    );
+
+    fn declares_dc(start: &BytesStart) -> bool {
+        start.attributes().flatten().any(|attribute| attribute.key.as_ref() == b"xmlns:dc")
+    }
+
+    fn strip_written_attributes(
+        reader: &NsReader<&[u8]>,
+        start: BytesStart<'_>,
+        dc_declared: bool,
+    ) -> Result<BytesStart<'static>> {
+        let mut stripped = BytesStart::new(String::from_utf8_lossy(start.name().as_ref()).into_owned());
+        for attribute in start.attributes() {
+            let attribute = attribute?;
+            let (namespace, local_name) = reader.resolve_attribute(attribute.key);
+            if XmpProperty::resolve(&namespace, local_name.as_ref()).is_some_and(|property| property.is_written()) {
+                continue;
+            }
+            stripped.push_attribute(attribute);
+        }
+        if !dc_declared && !Self::declares_dc(&stripped) {
+            stripped.push_attribute(("xmlns:dc", std::str::from_utf8(DC_NS)?));
+        }
+        Ok(stripped.into_owned())
+    }
+
+    fn write_properties(
+        writer: &mut Writer<Vec<u8>>,
+        keywords: &[String],
+        title: Option<&str>,
+        caption: Option<&str>,
+    ) -> Result<()> {
+        let mut xml = String::new();
+        let keywords = keywords.iter().map(|keyword| keyword.trim()).filter(|keyword| !keyword.is_empty());
+        let items = keywords.map(|keyword| format!("     <rdf:li>{}</rdf:li>\n", escape(keyword))).collect::<String>();
+        if !items.is_empty() {
+            xml.push_str(&format!("   <dc:subject>\n    <rdf:Bag>\n{}    </rdf:Bag>\n   </dc:subject>\n", items));
+        }
+        for (name, value) in [("dc:title", title), ("dc:description", caption)] {
+            if let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) {
+                xml.push_str(&format!(
+                    "   <{name}>\n    <rdf:Alt>\n     <rdf:li xml:lang=\"x-default\">{}</rdf:li>\n    </rdf:Alt>\n   </{name}>\n",
+                    escape(value)
+                ));
+            }
+        }
+        if !xml.is_empty() {
+            writer.write_event(Event::Text(BytesText::from_escaped(format!("\n{}  ", xml))))?;
+        }
+        Ok(())
+    }
+}
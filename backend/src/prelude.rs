@@ -1,12 +1,16 @@
 #![allow(unused_imports)]
 
 pub use crate::controllers::{
-    self, AdminUserController, AlbumController, AssetsController, AuthController, ClientHandlers, DashboardController,
-    HttpContextExtensions, PhotoController, StorageController, TagController, register_controllers,
+    self, AdminUserController, AlbumController, ApiResultExtensions, AssetsController, AuditController, AuthController,
+    ClientHandlers, DashboardController, HttpContextExtensions, PhotoController, StorageController, TagController,
+    TaskController, register_controllers,
 };
 pub use crate::dtos::{self, *};
 pub use crate::entities::{self, migrate_entities, register_entities, *};
-pub use crate::middlewares::{self, PublicAccessMiddleware, StaticFileMiddleware};
+pub use crate::middlewares::{
+    self, ApiDocsMiddleware, CompressionMiddleware, CorsPolicyMiddleware, ErrorResponseMiddleware, FeedMiddleware,
+    MetricsMiddleware, PublicAccessMiddleware, RequestLoggingMiddleware, RevokedSubjectMiddleware, StaticFileMiddleware,
+};
 pub use crate::models::{self, *};
 pub use crate::repositories::{self, *};
 pub use crate::services::{self, register_services, *};
@@ -22,7 +26,7 @@ pub use nimble_web::{
     QueryBuilder, Repository, RequestBody, RequestContext, ResponseValue, Result as HttpResult, ServiceProvider,
     TokenService, UserIdentity,
 };
-pub use nimble_web::{delete, get, post, put};
+pub use nimble_web::{delete, get, patch, post, put};
 
 pub use async_trait::async_trait;
 pub use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
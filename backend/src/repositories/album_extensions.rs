@@ -1,15 +1,320 @@
 use crate::prelude::*;
 
 #[async_trait]
-pub trait AlbumExtensions {}
+pub trait AlbumExtensions {
+    async fn search_albums(
+        &self,
+        query: &AlbumListQuery,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<Album>, PipelineError>;
+    async fn bump_album_version(&self, album_id: Uuid, expected: i32) -> Result<Option<i32>, PipelineError>;
+    async fn album_membership_stamp(&self, album_id: Uuid) -> Result<Option<AlbumMembershipStamp>, PipelineError>;
+    async fn album_date_range(
+        &self,
+        album_id: Uuid,
+    ) -> Result<(Option<DateTime<Utc>>, Option<DateTime<Utc>>), PipelineError>;
+    async fn refresh_album_dates(&self, album_id: Uuid) -> Result<(), PipelineError>;
+    async fn latest_albums(&self, limit: u32) -> Result<Vec<FeedAlbum>, PipelineError>;
+}
 
 #[async_trait]
-impl AlbumExtensions for Repository<Album> {}
+impl AlbumExtensions for Repository<Album> {
+    async fn search_albums(
+        &self,
+        query: &AlbumListQuery,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<Album>, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct AlbumPageRow {
+                total: i64,
+                ids: Vec<Uuid>,
+            }
+
+            let sql = format!(
+                r#"
+                WITH matched AS (
+                    SELECT
+                        a.id,
+                        a.name,
+                        a.create_date,
+                        (SELECT count(*) FROM album_photos ap WHERE ap.album_id = a.id) AS photo_count
+                    FROM albums a
+                    WHERE $1 = '' OR a.name ILIKE $1
+                ),
+                page AS (
+                    SELECT m.id, ROW_NUMBER() OVER (ORDER BY {order}) AS position
+                    FROM matched m
+                    ORDER BY {order}
+                    LIMIT $2 OFFSET $3
+                )
+                SELECT
+                    (SELECT count(*) FROM matched) AS total,
+                    COALESCE(json_agg(page.id ORDER BY page.position), '[]'::json) AS ids
+                FROM page
+            "#,
+                order = query.order_clause("m"),
+            );
+
+            let offset = page.saturating_sub(1).saturating_mul(page_size);
+            let matched = self
+                .raw_query::<AlbumPageRow>(
+                    &sql,
+                    &[
+                        Value::String(query.search_pattern().unwrap_or_default()),
+                        Value::Int(page_size as i64),
+                        Value::Int(offset as i64),
+                    ],
+                )
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .next()
+                .unwrap_or(AlbumPageRow { total: 0, ids: Vec::new() });
+
+            let mut albums: HashMap<Uuid, Album> = if matched.ids.is_empty() {
+                HashMap::new()
+            } else {
+                let by_id = QueryBuilder::<Album>::new()
+                    .filter(
+                        "id",
+                        FilterOperator::In,
+                        Value::List(matched.ids.iter().copied().map(Value::Uuid).collect()),
+                    )
+                    .build();
+                self.all(by_id)
+                    .await
+                    .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                    .into_iter()
+                    .map(|album| (album.id, album))
+                    .collect()
+            };
+            let items = matched.ids.iter().filter_map(|id| albums.remove(id)).collect();
+
+            return Ok(Page { items, total: matched.total.max(0) as u64, page, page_size });
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let search = query.search.as_deref().map(str::to_lowercase);
+            let mut items: Vec<Album> = self
+                .all(QueryBuilder::<Album>::new().build())
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .filter(|album| search.as_ref().is_none_or(|search| album.name.to_lowercase().contains(search)))
+                .collect();
+
+            items.sort_by(|left, right| match query.sort {
+                AlbumSortField::CreateDate => left.create_date.cmp(&right.create_date),
+                AlbumSortField::Title => left.name.to_lowercase().cmp(&right.name.to_lowercase()),
+                AlbumSortField::PhotoCount => left.image_count.cmp(&right.image_count),
+            });
+            if query.direction() == SortDirection::Desc {
+                items.reverse();
+            }
+
+            let total = items.len() as u64;
+            let offset = page.saturating_sub(1).saturating_mul(page_size) as usize;
+            let items = items.into_iter().skip(offset).take(page_size as usize).collect();
+            Ok(Page { items, total, page, page_size })
+        }
+    }
+
+    async fn bump_album_version(&self, album_id: Uuid, expected: i32) -> Result<Option<i32>, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct VersionRow {
+                version: i32,
+            }
+
+            let sql = "UPDATE albums SET version = version + 1 WHERE id = $1 AND version = $2 RETURNING version";
+            let rows = self
+                .raw_query::<VersionRow>(sql, &[Value::Uuid(album_id), Value::Int(expected as i64)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            return Ok(rows.into_iter().next().map(|row| row.version));
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let Some(mut album) = self.get(&album_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+            else {
+                return Ok(None);
+            };
+            if album.version != expected {
+                return Ok(None);
+            }
+            album.version += 1;
+            let saved = self.update(album).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            Ok(Some(saved.version))
+        }
+    }
+
+    async fn album_membership_stamp(&self, album_id: Uuid) -> Result<Option<AlbumMembershipStamp>, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            let sql = r#"
+                SELECT a.version, (SELECT count(*) FROM album_photos ap WHERE ap.album_id = a.id) AS members
+                FROM albums a
+                WHERE a.id = $1
+            "#;
+            let rows = self
+                .raw_query::<AlbumMembershipStamp>(sql, &[Value::Uuid(album_id)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            return Ok(rows.into_iter().next());
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let album = self.get(&album_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            Ok(album.map(|album| AlbumMembershipStamp { version: album.version, members: -1 }))
+        }
+    }
+
+    async fn album_date_range(
+        &self,
+        album_id: Uuid,
+    ) -> Result<(Option<DateTime<Utc>>, Option<DateTime<Utc>>), PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct DateRangeRow {
+                start_date: Option<DateTime<Utc>>,
+                end_date: Option<DateTime<Utc>>,
+            }
+
+            let sql = r#"
+                SELECT
+                    min(COALESCE(p.date_taken, p.sort_date)) AS start_date,
+                    max(COALESCE(p.date_taken, p.sort_date)) AS end_date
+                FROM album_photos ap
+                JOIN photos p ON p.id = ap.photo_id
+                WHERE ap.album_id = $1
+            "#;
+            let row = self
+                .raw_query::<DateRangeRow>(sql, &[Value::Uuid(album_id)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .next();
+            return Ok(row.map(|row| (row.start_date, row.end_date)).unwrap_or((None, None)));
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            // Member photos live in another repository here, so the stored range is kept.
+            let album = self.get(&album_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            Ok(album.map(|album| (album.start_date, album.end_date)).unwrap_or((None, None)))
+        }
+    }
+
+    // Derived dates follow membership; albums whose dates were set by hand are left alone.
+    async fn refresh_album_dates(&self, album_id: Uuid) -> Result<(), PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            let sql = r#"
+                UPDATE albums a
+                SET start_date = range.start_date, end_date = range.end_date
+                FROM (
+                    SELECT
+                        min(COALESCE(p.date_taken, p.sort_date)) AS start_date,
+                        max(COALESCE(p.date_taken, p.sort_date)) AS end_date
+                    FROM album_photos ap
+                    JOIN photos p ON p.id = ap.photo_id
+                    WHERE ap.album_id = $1
+                ) range
+                WHERE a.id = $1
+                AND NOT a.dates_overridden
+                RETURNING a.id
+            "#;
+            self.raw_query::<serde_json::Value>(sql, &[Value::Uuid(album_id)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            return Ok(());
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let _ = album_id;
+            Ok(())
+        }
+    }
+
+    // Covers that belong to admin-only photos are dropped rather than leaked into the feed.
+    async fn latest_albums(&self, limit: u32) -> Result<Vec<FeedAlbum>, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            let sql = r#"
+                SELECT
+                    a.id,
+                    a.name,
+                    a.description,
+                    a.create_date,
+                    CASE
+                        WHEN EXISTS (SELECT 1 FROM photos_public_visible p WHERE p.hash = a.thumbnail_hash)
+                        THEN a.thumbnail_hash
+                    END AS thumbnail_hash,
+                    (SELECT count(*) FROM album_photos ap WHERE ap.album_id = a.id) AS photo_count
+                FROM albums a
+                WHERE a.create_date IS NOT NULL
+                ORDER BY a.create_date DESC, a.id DESC
+                LIMIT $1
+            "#;
+            return self
+                .raw_query::<FeedAlbum>(sql, &[Value::Int(limit as i64)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)));
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let mut albums = self
+                .all(QueryBuilder::<Album>::new().build())
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .filter_map(|album| {
+                    Some(FeedAlbum {
+                        id: album.id,
+                        create_date: album.create_date?,
+                        name: album.name,
+                        description: album.description,
+                        thumbnail_hash: None,
+                        photo_count: album.image_count.unwrap_or_default(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            albums.sort_by(|left, right| right.create_date.cmp(&left.create_date));
+            albums.truncate(limit as usize);
+            Ok(albums)
+        }
+    }
+}
 
 #[async_trait]
 pub trait AlbumPhotoExtensions {
     async fn add_photos_to_album(&self, album_id: Uuid, photo_ids: &[Uuid]) -> Result<u32, PipelineError>;
     async fn remove_photos_from_album(&self, album_id: Uuid, photo_ids: &[Uuid]) -> Result<u32, PipelineError>;
+    async fn album_photo_order(&self, album_id: Uuid) -> Result<Vec<Uuid>, PipelineError>;
+    async fn set_album_photo_order(&self, album_id: Uuid, photo_ids: &[Uuid]) -> Result<u32, PipelineError>;
+    async fn photo_counts(
+        &self,
+        album_ids: &[Uuid],
+        hidden_tags: &HashSet<String>,
+    ) -> Result<HashMap<Uuid, AlbumPhotoCounts>, PipelineError>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlbumPhotoCounts {
+    pub total: i64,
+    pub visible: i64,
 }
 
 #[async_trait]
@@ -18,18 +323,17 @@ impl AlbumPhotoExtensions for Repository<AlbumPhoto> {
         let query =
             QueryBuilder::<AlbumPhoto>::new().filter("album_id", FilterOperator::Eq, Value::Uuid(album_id)).build();
 
-        let photo_ids_set: HashSet<Uuid> = self
-            .all(query)
-            .await
-            .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
-            .into_iter()
-            .map(|item| item.id)
-            .collect();
+        let existing = self.all(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        let mut photo_ids_set: HashSet<Uuid> = existing.iter().map(|item| item.photo_id).collect();
+        let mut next_position = existing.iter().filter_map(|item| item.position).max().unwrap_or(0);
 
         let entities = photo_ids
             .iter()
-            .filter(|photo_id| !photo_ids_set.contains(photo_id))
-            .map(|photo_id| AlbumPhoto::new(album_id, *photo_id))
+            .filter(|photo_id| photo_ids_set.insert(**photo_id))
+            .map(|photo_id| {
+                next_position += 1;
+                AlbumPhoto { position: Some(next_position), ..AlbumPhoto::new(album_id, *photo_id) }
+            })
             .collect::<Vec<_>>();
 
         let mut added = 0;
@@ -58,9 +362,248 @@ impl AlbumPhotoExtensions for Repository<AlbumPhoto> {
 
         Ok(removed)
     }
+
+    async fn album_photo_order(&self, album_id: Uuid) -> Result<Vec<Uuid>, PipelineError> {
+        let query =
+            QueryBuilder::<AlbumPhoto>::new().filter("album_id", FilterOperator::Eq, Value::Uuid(album_id)).build();
+        let mut items = self.all(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+
+        items.sort_by(|left, right| {
+            (left.position.is_none(), left.position, left.created_at, left.photo_id).cmp(&(
+                right.position.is_none(),
+                right.position,
+                right.created_at,
+                right.photo_id,
+            ))
+        });
+        Ok(items.into_iter().map(|item| item.photo_id).collect())
+    }
+
+    async fn set_album_photo_order(&self, album_id: Uuid, photo_ids: &[Uuid]) -> Result<u32, PipelineError> {
+        if photo_ids.is_empty() {
+            return Ok(0);
+        }
+
+        #[cfg(feature = "postgres")]
+        {
+            let sql = r#"
+                UPDATE album_photos ap
+                SET position = ordered.position::int
+                FROM jsonb_array_elements_text($2::jsonb) WITH ORDINALITY AS ordered(photo_id, position)
+                WHERE ap.album_id = $1
+                AND ap.photo_id = ordered.photo_id::uuid
+                RETURNING ap.id
+            "#;
+
+            let ids: Vec<String> = photo_ids.iter().map(Uuid::to_string).collect();
+            let ids_json = serde_json::to_string(&ids)
+                .map_err(|e| PipelineError::message(&format!("failed to encode photo ids: {:?}", e)))?;
+
+            let updated = self
+                .raw_query::<serde_json::Value>(sql, &[Value::Uuid(album_id), Value::String(ids_json)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            return Ok(updated.len() as u32);
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let positions: HashMap<Uuid, i32> =
+                photo_ids.iter().enumerate().map(|(index, photo_id)| (*photo_id, index as i32 + 1)).collect();
+            let query =
+                QueryBuilder::<AlbumPhoto>::new().filter("album_id", FilterOperator::Eq, Value::Uuid(album_id)).build();
+            let items = self.all(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+
+            let mut updated = 0;
+            for mut item in items {
+                if let Some(position) = positions.get(&item.photo_id) {
+                    item.position = Some(*position);
+                    self.update(item).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+                    updated += 1;
+                }
+            }
+            Ok(updated)
+        }
+    }
+
+    async fn photo_counts(
+        &self,
+        album_ids: &[Uuid],
+        hidden_tags: &HashSet<String>,
+    ) -> Result<HashMap<Uuid, AlbumPhotoCounts>, PipelineError> {
+        if album_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct AlbumCountRow {
+                #[serde(rename = "albumId")]
+                album_id: Uuid,
+                total: i64,
+                visible: i64,
+            }
+
+            let sql = r#"
+                SELECT
+                    ap.album_id AS "albumId",
+                    count(*) AS total,
+                    count(*) FILTER (
+                        WHERE NOT EXISTS (
+                            SELECT 1
+                            FROM photo_tags hpt
+                            JOIN tags ht ON ht.id = hpt.tag_id
+                            WHERE hpt.photo_id = ap.photo_id
+                            AND lower(ht.name) IN (SELECT jsonb_array_elements_text($2::jsonb))
+                        )
+                    ) AS visible
+                FROM album_photos ap
+                WHERE ap.album_id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+                GROUP BY ap.album_id
+            "#;
+
+            let ids: Vec<String> = album_ids.iter().map(Uuid::to_string).collect();
+            let hidden: Vec<&String> = hidden_tags.iter().collect();
+            let ids_json = serde_json::to_string(&ids)
+                .map_err(|e| PipelineError::message(&format!("failed to encode album ids: {:?}", e)))?;
+            let hidden_json = serde_json::to_string(&hidden)
+                .map_err(|e| PipelineError::message(&format!("failed to encode hidden tags: {:?}", e)))?;
+
+            let rows = self
+                .raw_query::<AlbumCountRow>(sql, &[Value::String(ids_json), Value::String(hidden_json)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+
+            return Ok(rows
+                .into_iter()
+                .map(|row| (row.album_id, AlbumPhotoCounts { total: row.total, visible: row.visible }))
+                .collect());
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let _ = hidden_tags;
+            let query = QueryBuilder::<AlbumPhoto>::new()
+                .filter(
+                    "album_id",
+                    FilterOperator::In,
+                    Value::List(album_ids.iter().copied().map(Value::Uuid).collect()),
+                )
+                .build();
+            let items = self.all(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+
+            let mut counts: HashMap<Uuid, AlbumPhotoCounts> = HashMap::new();
+            for item in items {
+                let entry = counts.entry(item.album_id).or_default();
+                entry.total += 1;
+                entry.visible += 1;
+            }
+            Ok(counts)
+        }
+    }
 }
 
 #[async_trait]
-pub trait AlbumCommentExtensions {}
+pub trait AlbumCommentExtensions {
+    async fn visible_album_comments(
+        &self,
+        album_id: Uuid,
+        viewer: Option<Uuid>,
+        include_hidden: bool,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<AlbumComment>, PipelineError>;
+}
 
-impl AlbumCommentExtensions for Repository<AlbumComment> {}
+#[async_trait]
+impl AlbumCommentExtensions for Repository<AlbumComment> {
+    // Hidden comments stay visible to their author and to admins. The filter runs before paging so
+    // the total counts only what the caller can see.
+    async fn visible_album_comments(
+        &self,
+        album_id: Uuid,
+        viewer: Option<Uuid>,
+        include_hidden: bool,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<AlbumComment>, PipelineError> {
+        let page = page.max(1);
+        let page_size = page_size.max(1);
+
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct CountRow {
+                total: i64,
+            }
+
+            let visibility = "c.album_id = $1::uuid AND ($2::boolean OR c.hidden = false OR c.user_id = $3::uuid)";
+            let count_sql = format!("SELECT count(*) AS total FROM album_comments c WHERE {visibility}");
+            let page_sql = format!(
+                r#"
+                SELECT
+                    c.id,
+                    c.album_id AS "albumId",
+                    c.user_id AS "userId",
+                    c.user_display_name AS "userDisplayName",
+                    c.body,
+                    c.created_at AS "createdAt",
+                    c.hidden
+                FROM album_comments c
+                WHERE {visibility}
+                ORDER BY c.created_at DESC, c.id DESC
+                LIMIT $4 OFFSET $5
+            "#
+            );
+
+            let params = |paged: bool| {
+                let mut params = vec![
+                    Value::Uuid(album_id),
+                    Value::Bool(include_hidden),
+                    Value::Uuid(viewer.unwrap_or_else(Uuid::nil)),
+                ];
+                if paged {
+                    params.push(Value::Int(page_size as i64));
+                    params.push(Value::Int((page - 1).saturating_mul(page_size) as i64));
+                }
+                params
+            };
+            let total = self
+                .raw_query::<CountRow>(&count_sql, &params(false))
+                .await
+                .map_err(|e| PipelineError::message(&format!("failed to count album comments: {:?}", e)))?
+                .into_iter()
+                .next()
+                .map(|row| row.total.max(0) as u64)
+                .unwrap_or(0);
+            let items = self
+                .raw_query::<AlbumComment>(&page_sql, &params(true))
+                .await
+                .map_err(|e| PipelineError::message(&format!("failed to load album comments: {:?}", e)))?;
+            return Ok(Page { items, total, page, page_size });
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let query = QueryBuilder::<AlbumComment>::new()
+                .filter("album_id", FilterOperator::Eq, Value::Uuid(album_id))
+                .sort_desc("created_at")
+                .build();
+            let visible: Vec<AlbumComment> = self
+                .all(query)
+                .await
+                .map_err(|e| PipelineError::message(&format!("failed to load album comments: {:?}", e)))?
+                .into_iter()
+                .filter(|comment| include_hidden || !comment.hidden || (viewer.is_some() && comment.user_id == viewer))
+                .collect();
+            let total = visible.len() as u64;
+            let items = visible
+                .into_iter()
+                .skip((page - 1).saturating_mul(page_size) as usize)
+                .take(page_size as usize)
+                .collect();
+            Ok(Page { items, total, page, page_size })
+        }
+    }
+}
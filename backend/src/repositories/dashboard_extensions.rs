@@ -0,0 +1,54 @@
+use crate::prelude::*;
+
+#[async_trait]
+pub trait DashboardRepositoryExtensions {
+    async fn dashboard_totals(&self, now: DateTime<Utc>) -> Result<DashboardTotals, PipelineError>;
+
+    async fn storage_usage(&self) -> Result<Vec<StorageUsage>, PipelineError>;
+}
+
+#[async_trait]
+impl DashboardRepositoryExtensions for Repository<Photo> {
+    // One round trip; every count is a plain aggregate over its own table.
+    async fn dashboard_totals(&self, now: DateTime<Utc>) -> Result<DashboardTotals, PipelineError> {
+        let sql = r#"
+            SELECT
+                (SELECT count(*) FROM photos) AS total_photos,
+                (SELECT count(*) FROM albums) AS total_albums,
+                (SELECT count(*) FROM users) AS total_users,
+                (SELECT count(*) FROM tags) AS total_tags,
+                (SELECT count(*) FROM photos WHERE date_imported >= $1) AS added_last_7_days,
+                (SELECT count(*) FROM photos WHERE date_imported >= $2) AS added_last_30_days,
+                (SELECT count(*) FROM pipeline_jobs WHERE state IN ($3, $4)) AS pending_jobs
+        "#;
+
+        let rows = self
+            .raw_query::<DashboardTotals>(
+                sql,
+                &[
+                    Value::DateTime(now - Duration::days(7)),
+                    Value::DateTime(now - Duration::days(30)),
+                    Value::String(PipelineJob::STATE_QUEUED.to_string()),
+                    Value::String(PipelineJob::STATE_RUNNING.to_string()),
+                ],
+            )
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to load dashboard totals: {:?}", e)))?;
+        Ok(rows.into_iter().next().unwrap_or_default())
+    }
+
+    // Sizes recorded at import, which is far cheaper than walking the storage folders.
+    async fn storage_usage(&self) -> Result<Vec<StorageUsage>, PipelineError> {
+        let sql = r#"
+            SELECT s.id AS storage_id, s.label, count(p.id) AS photo_count, COALESCE(sum(p.size), 0)::BIGINT AS bytes
+            FROM storages s
+            LEFT JOIN photos p ON p.storage_id = s.id
+            GROUP BY s.id, s.label
+            ORDER BY s.label, s.id
+        "#;
+
+        self.raw_query::<StorageUsage>(sql, &[])
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to load storage usage: {:?}", e)))
+    }
+}
@@ -1,15 +1,35 @@
 pub mod album_extensions;
+pub mod dashboard_extensions;
+pub mod notification_extensions;
+pub mod people_extensions;
+pub mod photo_query_builder;
 pub mod photo_repo;
 pub mod postgres_extensions;
+pub mod read_retry;
+pub mod repository_error;
 pub mod storage_repo;
 pub mod tag_extensions;
+pub mod tag_suggestion_extensions;
 pub mod timeline_repo;
+pub mod two_factor_extensions;
+pub mod user_extensions;
+pub mod user_settings_extensions;
 pub mod validation;
 
-pub use album_extensions::{AlbumCommentExtensions, AlbumExtensions, AlbumPhotoExtensions};
+pub use album_extensions::{AlbumCommentExtensions, AlbumExtensions, AlbumPhotoCounts, AlbumPhotoExtensions};
+pub use dashboard_extensions::DashboardRepositoryExtensions;
+pub use notification_extensions::NotificationRepositoryExtensions;
+pub use people_extensions::PeopleRepositoryExtensions;
+pub use photo_query_builder::PhotoQueryBuilder;
 pub use photo_repo::PhotoRepositoryExtensions;
 pub use postgres_extensions::PostgresExtensions;
+pub use read_retry::ReadRetry;
+pub use repository_error::{RepositoryError, RepositoryResultExtensions};
 pub use storage_repo::{ClientStorageRepositoryExtensions, StorageRepositoryExtensions};
 pub use tag_extensions::TagRepositoryExtensions;
+pub use tag_suggestion_extensions::TagSuggestionRepositoryExtensions;
 pub use timeline_repo::TimelineRepositoryExtensions;
-pub use validation::StringValidations;
+pub use two_factor_extensions::TwoFactorExtensions;
+pub use user_extensions::UserRepositoryExtensions;
+pub use user_settings_extensions::UserSettingsExtensions;
+pub use validation::{StringValidations, Validate, Validator};
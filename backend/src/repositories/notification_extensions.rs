@@ -0,0 +1,162 @@
+use crate::prelude::*;
+
+#[async_trait]
+pub trait NotificationRepositoryExtensions {
+    async fn notifications_for(
+        &self,
+        recipient_id: Uuid,
+        unread_only: bool,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<Notification>, PipelineError>;
+    async fn unread_count(&self, recipient_id: Uuid) -> Result<u64, PipelineError>;
+    async fn mark_all_read(&self, recipient_id: Uuid) -> Result<u64, PipelineError>;
+}
+
+#[async_trait]
+impl NotificationRepositoryExtensions for Repository<Notification> {
+    async fn notifications_for(
+        &self,
+        recipient_id: Uuid,
+        unread_only: bool,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<Notification>, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            // read_at IS NULL cannot be expressed through the query builder, so unread pages go through SQL.
+            if !unread_only {
+                let query = QueryBuilder::<Notification>::new()
+                    .filter("recipient_id", FilterOperator::Eq, Value::Uuid(recipient_id))
+                    .sort_desc("created_at")
+                    .page(page, page_size)
+                    .build();
+                return self.query(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)));
+            }
+
+            #[derive(Deserialize)]
+            struct IdRow {
+                id: Uuid,
+            }
+
+            let sql = r#"
+                SELECT id
+                FROM notifications
+                WHERE recipient_id = $1 AND read_at IS NULL
+                ORDER BY created_at DESC
+                LIMIT $2 OFFSET $3
+            "#;
+            let offset = page.saturating_sub(1).saturating_mul(page_size);
+            let ids = self
+                .raw_query::<IdRow>(
+                    sql,
+                    &[Value::Uuid(recipient_id), Value::Int(page_size as i64), Value::Int(offset as i64)],
+                )
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .map(|row| row.id)
+                .collect::<Vec<_>>();
+
+            let mut items = if ids.is_empty() {
+                Vec::new()
+            } else {
+                let by_id = QueryBuilder::<Notification>::new()
+                    .filter("id", FilterOperator::In, Value::List(ids.into_iter().map(Value::Uuid).collect()))
+                    .build();
+                self.all(by_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+            };
+            items.sort_by(|left, right| right.created_at.cmp(&left.created_at));
+            let total = self.unread_count(recipient_id).await?;
+            return Ok(Page { items, total, page, page_size });
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let mut items: Vec<Notification> = self
+                .all(
+                    QueryBuilder::<Notification>::new()
+                        .filter("recipient_id", FilterOperator::Eq, Value::Uuid(recipient_id))
+                        .build(),
+                )
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .filter(|notification| !unread_only || !notification.is_read())
+                .collect();
+            items.sort_by(|left, right| right.created_at.cmp(&left.created_at));
+
+            let total = items.len() as u64;
+            let offset = page.saturating_sub(1).saturating_mul(page_size) as usize;
+            let items = items.into_iter().skip(offset).take(page_size as usize).collect();
+            Ok(Page { items, total, page, page_size })
+        }
+    }
+
+    async fn unread_count(&self, recipient_id: Uuid) -> Result<u64, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct CountRow {
+                total: i64,
+            }
+
+            let sql = "SELECT count(*) AS total FROM notifications WHERE recipient_id = $1 AND read_at IS NULL";
+            let total = self
+                .raw_query::<CountRow>(sql, &[Value::Uuid(recipient_id)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .next()
+                .map(|row| row.total)
+                .unwrap_or_default();
+            return Ok(total.max(0) as u64);
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let page = self.notifications_for(recipient_id, true, 1, u32::MAX).await?;
+            Ok(page.total)
+        }
+    }
+
+    async fn mark_all_read(&self, recipient_id: Uuid) -> Result<u64, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct CountRow {
+                total: i64,
+            }
+
+            let sql = r#"
+                WITH updated AS (
+                    UPDATE notifications
+                    SET read_at = NOW()
+                    WHERE recipient_id = $1 AND read_at IS NULL
+                    RETURNING id
+                )
+                SELECT count(*) AS total FROM updated
+            "#;
+            let total = self
+                .raw_query::<CountRow>(sql, &[Value::Uuid(recipient_id)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .next()
+                .map(|row| row.total)
+                .unwrap_or_default();
+            return Ok(total.max(0) as u64);
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let unread = self.notifications_for(recipient_id, true, 1, u32::MAX).await?;
+            let now = Utc::now();
+            for mut notification in unread.items {
+                notification.read_at = Some(now);
+                self.update(notification).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            }
+            Ok(unread.total)
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::prelude::*;
+
+#[async_trait]
+pub trait PeopleRepositoryExtensions {
+    async fn list_people(&self, hidden_tags: &HashSet<String>) -> Result<Vec<PersonSummary>, PipelineError>;
+
+    async fn photo_people(&self, photo_id: Uuid) -> Result<Vec<PhotoPerson>, PipelineError>;
+
+    async fn add_photo_people(
+        &self,
+        photo_id: Uuid,
+        mentions: &[PersonMention],
+        source: &str,
+    ) -> Result<(), PipelineError>;
+
+    async fn replace_photo_people(&self, photo_id: Uuid, mentions: &[PersonMention]) -> Result<(), PipelineError>;
+
+    async fn rename_person(&self, from: &str, to: &str) -> Result<u64, PipelineError>;
+}
+
+#[async_trait]
+impl PeopleRepositoryExtensions for Repository<Photo> {
+    // Counts follow the photo list: stacked members and photos carrying a hidden tag are left out.
+    async fn list_people(&self, hidden_tags: &HashSet<String>) -> Result<Vec<PersonSummary>, PipelineError> {
+        let sql = format!(
+            r#"
+            SELECT
+                (array_agg(pp.name ORDER BY pp.created_at, pp.name))[1] AS name,
+                count(DISTINCT pp.photo_id) AS photo_count
+            FROM photo_people pp
+            JOIN photos p ON p.id = pp.photo_id
+            WHERE {representative}
+            AND NOT EXISTS (
+                SELECT 1
+                FROM photo_tags hpt
+                JOIN tags ht ON ht.id = hpt.tag_id
+                WHERE hpt.photo_id = p.id
+                AND lower(ht.name) IN (SELECT jsonb_array_elements_text($1::jsonb))
+            )
+            GROUP BY pp.name_norm
+            ORDER BY photo_count DESC, name
+        "#,
+            representative = PhotoStack::representative_clause("p"),
+        );
+
+        let hidden: Vec<&String> = hidden_tags.iter().collect();
+        let hidden_json = serde_json::to_string(&hidden)
+            .map_err(|e| PipelineError::message(&format!("failed to encode hidden tags: {:?}", e)))?;
+
+        self.raw_query::<PersonSummary>(&sql, &[Value::String(hidden_json)])
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to list people: {:?}", e)))
+    }
+
+    async fn photo_people(&self, photo_id: Uuid) -> Result<Vec<PhotoPerson>, PipelineError> {
+        let sql = r#"
+            SELECT id, photo_id, name, name_norm, region_x, region_y, region_w, region_h, source, created_at
+            FROM photo_people
+            WHERE photo_id = $1
+            ORDER BY name
+        "#;
+
+        self.raw_query::<PhotoPerson>(sql, &[Value::Uuid(photo_id)])
+            .await
+            .map_err(|e| PipelineError::message(&format!("{:?}", e)))
+    }
+
+    // Imports never overwrite a name that is already there, but they fill in a missing region.
+    async fn add_photo_people(
+        &self,
+        photo_id: Uuid,
+        mentions: &[PersonMention],
+        source: &str,
+    ) -> Result<(), PipelineError> {
+        let mentions = PersonMention::dedup(mentions.to_vec());
+        if mentions.is_empty() {
+            return Ok(());
+        }
+
+        let sql = r#"
+            INSERT INTO photo_people (photo_id, name, name_norm, region_x, region_y, region_w, region_h, source)
+            SELECT $1, m.name, m.name_norm, m.x, m.y, m.width, m.height, $3
+            FROM jsonb_to_recordset($2::jsonb) AS m(name TEXT, name_norm TEXT, x REAL, y REAL, width REAL, height REAL)
+            ON CONFLICT (photo_id, name_norm) DO UPDATE
+            SET region_x = EXCLUDED.region_x,
+                region_y = EXCLUDED.region_y,
+                region_w = EXCLUDED.region_w,
+                region_h = EXCLUDED.region_h
+            WHERE photo_people.region_x IS NULL AND EXCLUDED.region_x IS NOT NULL
+        "#;
+
+        self.raw_query::<serde_json::Value>(
+            sql,
+            &[Value::Uuid(photo_id), encode_mentions(&mentions)?, Value::String(source.to_string())],
+        )
+        .await
+        .map_err(|e| PipelineError::message(&format!("failed to add photo people: {:?}", e)))?;
+        Ok(())
+    }
+
+    // A name sent without a region keeps the stored one, so clients can rename or add people without
+    // round-tripping the face rectangles.
+    async fn replace_photo_people(&self, photo_id: Uuid, mentions: &[PersonMention]) -> Result<(), PipelineError> {
+        let mentions = PersonMention::dedup(mentions.to_vec());
+
+        let sql = r#"
+            WITH requested AS (
+                SELECT *
+                FROM jsonb_to_recordset($2::jsonb) AS m(name TEXT, name_norm TEXT, x REAL, y REAL, width REAL, height REAL)
+            ),
+            removed AS (
+                DELETE FROM photo_people
+                WHERE photo_id = $1
+                  AND name_norm NOT IN (SELECT name_norm FROM requested)
+            )
+            INSERT INTO photo_people (photo_id, name, name_norm, region_x, region_y, region_w, region_h, source)
+            SELECT $1, r.name, r.name_norm, r.x, r.y, r.width, r.height, $3
+            FROM requested r
+            ON CONFLICT (photo_id, name_norm) DO UPDATE
+            SET name = EXCLUDED.name,
+                region_x = COALESCE(EXCLUDED.region_x, photo_people.region_x),
+                region_y = COALESCE(EXCLUDED.region_y, photo_people.region_y),
+                region_w = COALESCE(EXCLUDED.region_w, photo_people.region_w),
+                region_h = COALESCE(EXCLUDED.region_h, photo_people.region_h)
+        "#;
+
+        self.raw_query::<serde_json::Value>(
+            sql,
+            &[
+                Value::Uuid(photo_id),
+                encode_mentions(&mentions)?,
+                Value::String(PhotoPerson::SOURCE_MANUAL.to_string()),
+            ],
+        )
+        .await
+        .map_err(|e| PipelineError::message(&format!("failed to replace photo people: {:?}", e)))?;
+        Ok(())
+    }
+
+    // Re-points every row of one name to another. When a photo already names the target the two rows
+    // are merged, keeping whichever region is known, and every target row takes the new spelling.
+    async fn rename_person(&self, from: &str, to: &str) -> Result<u64, PipelineError> {
+        #[derive(Deserialize)]
+        struct RenamedRow {
+            photos: i64,
+        }
+
+        let (Some((_, from_norm)), Some((to_name, to_norm))) =
+            (PersonMention::normalize_name(from), PersonMention::normalize_name(to))
+        else {
+            return Ok(0);
+        };
+
+        let sql = r#"
+            WITH source AS (
+                SELECT id, photo_id, region_x, region_y, region_w, region_h
+                FROM photo_people
+                WHERE name_norm = $1
+            ),
+            merged AS (
+                UPDATE photo_people t
+                SET name = $2,
+                    region_x = COALESCE(t.region_x, s.region_x),
+                    region_y = COALESCE(t.region_y, s.region_y),
+                    region_w = COALESCE(t.region_w, s.region_w),
+                    region_h = COALESCE(t.region_h, s.region_h)
+                FROM source s
+                WHERE t.photo_id = s.photo_id AND t.name_norm = $3 AND t.id <> s.id
+                RETURNING t.photo_id
+            ),
+            removed AS (
+                DELETE FROM photo_people d
+                USING merged m
+                WHERE d.photo_id = m.photo_id AND d.name_norm = $1
+            ),
+            moved AS (
+                UPDATE photo_people u
+                SET name = $2, name_norm = $3
+                WHERE u.name_norm = $1
+                  AND NOT EXISTS (
+                      SELECT 1 FROM photo_people o WHERE o.photo_id = u.photo_id AND o.name_norm = $3 AND o.id <> u.id
+                  )
+                RETURNING u.photo_id
+            ),
+            respelled AS (
+                UPDATE photo_people r
+                SET name = $2
+                WHERE r.name_norm = $3
+                  AND r.name_norm <> $1
+                  AND r.photo_id NOT IN (SELECT photo_id FROM merged)
+            )
+            SELECT count(DISTINCT photo_id) AS photos
+            FROM (SELECT photo_id FROM merged UNION ALL SELECT photo_id FROM moved) affected
+        "#;
+
+        let rows = self
+            .raw_query::<RenamedRow>(sql, &[Value::String(from_norm), Value::String(to_name), Value::String(to_norm)])
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to rename person: {:?}", e)))?;
+        Ok(rows.into_iter().next().map(|row| row.photos.max(0) as u64).unwrap_or(0))
+    }
+}
+
+fn encode_mentions(mentions: &[PersonMention]) -> Result<Value, PipelineError> {
+    let rows = mentions
+        .iter()
+        .map(|mention| {
+            let region = mention.region;
+            json!({
+                "name": mention.name,
+                "name_norm": mention.name_norm(),
+                "x": region.map(|region| region.x),
+                "y": region.map(|region| region.y),
+                "width": region.map(|region| region.width),
+                "height": region.map(|region| region.height),
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&rows)
+        .map(Value::String)
+        .map_err(|e| PipelineError::message(&format!("failed to encode people: {:?}", e)))
+}
@@ -0,0 +1,122 @@
+use crate::prelude::*;
+
+// Renders the filtered photo set once and derives both the count and the page query from it, so a
+// new filter cannot end up counted differently from how it is paged. Only filters that are set add a
+// clause, which keeps the planner away from always-true OR branches.
+pub struct PhotoQueryBuilder {
+    clauses: Vec<String>,
+    params: Vec<Value>,
+}
+
+impl PhotoQueryBuilder {
+    const CLAUSE_SEPARATOR: &'static str = "\n                AND ";
+
+    pub fn new(filter: &PhotoFilter, hidden_tags: &HashSet<String>) -> Result<Self, PipelineError> {
+        let mut builder = Self { clauses: vec![PhotoStack::representative_clause("p")], params: Vec::new() };
+
+        if let Some(min_rating) = filter.min_rating.filter(|rating| *rating > 0) {
+            let index = builder.bind(Value::Int(min_rating as i64));
+            builder.clauses.push(PhotoRating::min_clause("p", index));
+        }
+        if let Some(person) = filter.person.clone() {
+            let index = builder.bind(Value::String(person));
+            builder.clauses.push(format!(
+                "EXISTS (SELECT 1 FROM photo_people fpp WHERE fpp.photo_id = p.id AND fpp.name_norm = ${index}::text)"
+            ));
+        }
+        if let Some(from) = filter.date_from {
+            let index = builder.bind(Value::DateTime(from));
+            builder.clauses.push(format!("p.sort_date >= ${index}::timestamptz"));
+        }
+        if let Some(to) = filter.date_to {
+            let index = builder.bind(Value::DateTime(to));
+            builder.clauses.push(format!("p.sort_date < ${index}::timestamptz"));
+        }
+
+        let tags = filter.normalized_tags();
+        if !tags.is_empty() {
+            let required = if filter.matching.requires_all() { tags.len() } else { 1 };
+            let index = builder.bind(Self::encode(&tags, "tag filter")?);
+            builder.clauses.push(format!(
+                r#"(
+                    SELECT count(DISTINCT lower(ft.name))
+                    FROM photo_tags fpt
+                    JOIN tags ft ON ft.id = fpt.tag_id
+                    WHERE fpt.photo_id = p.id
+                    AND lower(ft.name) IN (SELECT jsonb_array_elements_text(${index}::jsonb))
+                ) >= {required}"#
+            ));
+        }
+        if !hidden_tags.is_empty() {
+            let mut hidden: Vec<&String> = hidden_tags.iter().collect();
+            hidden.sort();
+            let index = builder.bind(Self::encode(&hidden, "hidden tags")?);
+            builder.clauses.push(format!(
+                r#"NOT EXISTS (
+                    SELECT 1
+                    FROM photo_tags hpt
+                    JOIN tags ht ON ht.id = hpt.tag_id
+                    WHERE hpt.photo_id = p.id
+                    AND lower(ht.name) IN (SELECT jsonb_array_elements_text(${index}::jsonb))
+                )"#
+            ));
+        }
+
+        Ok(builder)
+    }
+
+    pub fn params(&self) -> &[Value] {
+        &self.params
+    }
+
+    pub fn where_clause(&self) -> String {
+        self.clauses.join(Self::CLAUSE_SEPARATOR)
+    }
+
+    pub fn count_sql(&self) -> String {
+        format!("SELECT count(*) AS total FROM photos p WHERE {}", self.where_clause())
+    }
+
+    // One statement returning the total and the page ids, both read from the same filtered set.
+    pub fn page_sql(self, sort: PhotoSort, page: u32, page_size: u32) -> (String, Vec<Value>) {
+        let mut params = self.params;
+        params.push(Value::Int(page_size as i64));
+        let limit = params.len();
+        params.push(Value::Int(page.saturating_sub(1).saturating_mul(page_size) as i64));
+        let offset = params.len();
+
+        let sql = format!(
+            r#"
+            WITH visible AS (
+                SELECT p.*
+                FROM photos p
+                WHERE {where_clause}
+            ),
+            page AS (
+                SELECT v.id, ROW_NUMBER() OVER (ORDER BY {order}) AS position
+                FROM visible v
+                ORDER BY {order}
+                LIMIT ${limit} OFFSET ${offset}
+            )
+            SELECT
+                (SELECT count(*) FROM visible) AS total,
+                COALESCE(json_agg(page.id ORDER BY page.position), '[]'::json) AS ids
+            FROM page
+        "#,
+            where_clause = self.clauses.join(Self::CLAUSE_SEPARATOR),
+            order = sort.order_clause("v"),
+        );
+        (sql, params)
+    }
+
+    fn bind(&mut self, value: Value) -> usize {
+        self.params.push(value);
+        self.params.len()
+    }
+
+    fn encode<T: Serialize>(value: &T, what: &str) -> Result<Value, PipelineError> {
+        serde_json::to_string(value)
+            .map(Value::String)
+            .map_err(|e| PipelineError::message(&format!("failed to encode {}: {:?}", what, e)))
+    }
+}
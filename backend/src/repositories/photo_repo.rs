@@ -7,9 +7,102 @@ use crate::prelude::*;
 
 #[async_trait]
 pub trait PhotoRepositoryExtensions {
-    async fn find_by_hash(&self, hash: &str) -> Result<Option<Photo>, PipelineError>;
+    async fn find_by_hash_all(&self, hash: &str) -> Result<Vec<Photo>, PipelineError>;
 
-    async fn photos_in_album(&self, album_id: Uuid, page: u32, page_size: u32) -> Result<Page<Photo>, PipelineError>;
+    async fn find_visible_by_hash(&self, hash: &str, include_hidden: bool) -> Result<Vec<Photo>, PipelineError>;
+
+    async fn find_hash_locations(
+        &self,
+        hashes: &[String],
+        include_hidden: bool,
+    ) -> Result<Vec<PhotoHashLocation>, PipelineError>;
+
+    async fn get_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Photo>, PipelineError>;
+
+    async fn insert_photo_with_exif(
+        &self,
+        exif_repo: &Repository<ExifModel>,
+        photo: Photo,
+        exif: ExifModel,
+    ) -> Result<Photo, PipelineError>;
+
+    async fn find_incomplete_import(
+        &self,
+        exif_repo: &Repository<ExifModel>,
+        storage_id: Uuid,
+        hash: &str,
+    ) -> Result<Option<Photo>, PipelineError>;
+
+    async fn preview_warm_candidates(
+        &self,
+        since: Option<DateTime<Utc>>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<PreviewWarmCandidate>, PipelineError>;
+
+    async fn blurhash_candidates(
+        &self,
+        after: Option<&BlurhashCandidate>,
+        limit: u32,
+    ) -> Result<Vec<BlurhashCandidate>, PipelineError>;
+
+    async fn set_blurhash(&self, storage_id: Uuid, hash: &str, blurhash: &str) -> Result<(), PipelineError>;
+
+    async fn add_photo_tags_bulk(&self, photo_ids: &[Uuid], tag_ids: &[Uuid]) -> Result<(), PipelineError>;
+
+    async fn remove_photo_tags_bulk(&self, photo_ids: &[Uuid], tag_ids: &[Uuid]) -> Result<(), PipelineError>;
+
+    async fn replace_photo_tags_bulk(
+        &self,
+        photo_ids: &[Uuid],
+        tag_ids: &[Uuid],
+        keep_hidden: bool,
+    ) -> Result<(), PipelineError>;
+
+    async fn update_photo_fields_bulk(
+        &self,
+        photo_ids: &[Uuid],
+        changes: &BatchPhotoChanges,
+    ) -> Result<Vec<Uuid>, PipelineError>;
+
+    async fn photos_in_album(
+        &self,
+        album_id: Uuid,
+        page: u32,
+        page_size: u32,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Page<Photo>, PipelineError>;
+
+    async fn album_member_ids(&self, album_id: Uuid) -> Result<Vec<Uuid>, PipelineError>;
+
+    async fn get_photos_page(
+        &self,
+        page: u32,
+        page_size: u32,
+        sort: PhotoSort,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Page<Photo>, PipelineError>;
+
+    async fn filter_photos_by_tags(
+        &self,
+        tags: &[String],
+        matching: TagMatch,
+        page: u32,
+        page_size: u32,
+        sort: PhotoSort,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Page<Photo>, PipelineError>;
+
+    async fn query_photos(
+        &self,
+        filter: &PhotoFilter,
+        page: u32,
+        page_size: u32,
+        sort: PhotoSort,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Page<Photo>, PipelineError>;
+
+    async fn count_photos(&self, filter: &PhotoFilter, hidden_tags: &HashSet<String>) -> Result<u64, PipelineError>;
 
     async fn delete_photo(&self, context: &HttpContext, photo: &Photo) -> Result<u32, PipelineError>;
 
@@ -17,33 +110,617 @@ pub trait PhotoRepositoryExtensions {
 
     async fn delete_records(&self, photo: &Photo, context: &HttpContext) -> Result<(), PipelineError>;
 
-    async fn get_years(&self) -> Result<Vec<String>, PipelineError>;
+    async fn get_years(&self, zone: &TimelineZone) -> Result<Vec<i32>, PipelineError>;
+
+    async fn get_year_counts(
+        &self,
+        zone: &TimelineZone,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Vec<TimelineYearCount>, PipelineError>;
+
+    async fn get_year_offset(
+        &self,
+        year: &str,
+        zone: &TimelineZone,
+        granularity: TimelineGranularity,
+    ) -> Result<u32, PipelineError>;
+
+    async fn get_yeardays(&self, zone: &TimelineZone) -> Result<Vec<TimelineYearDays>, PipelineError>;
+
+    async fn get_days(
+        &self,
+        page: u32,
+        page_size: u32,
+        zone: &TimelineZone,
+        min_rating: Option<u8>,
+    ) -> Result<Vec<String>, PipelineError>;
+
+    async fn photos_with_gps_with_tags(
+        &self,
+        limit: u32,
+        offset: u32,
+        hidden_tags: &HashSet<String>,
+        include_hidden_tags: bool,
+        precision: CoordinatePrecision,
+    ) -> Result<Vec<PhotoLocWithTags>, PipelineError>;
+
+    async fn recently_added(
+        &self,
+        page: u32,
+        page_size: u32,
+        days: Option<u32>,
+        hidden_tags: &HashSet<String>,
+        include_hidden_tags: bool,
+        zone: &TimelineZone,
+    ) -> Result<Page<PhotoWithTags>, PipelineError>;
+
+    async fn photos_without_album(&self) -> Result<Vec<EventCandidate>, PipelineError>;
 
-    async fn get_year_offset(&self, year: &str) -> Result<u32, PipelineError>;
+    async fn random_photos(
+        &self,
+        count: u32,
+        tags: &[String],
+        hidden_tags: &HashSet<String>,
+        include_hidden: bool,
+    ) -> Result<Vec<RandomPhoto>, PipelineError>;
 
-    async fn photos_with_gps(&self, limit: u32, offset: u32) -> Result<Vec<PhotoLoc>, PipelineError>;
+    async fn photos_for_days(
+        &self,
+        days: Vec<String>,
+        per_day_limit: u32,
+        zone: &TimelineZone,
+        min_rating: Option<u8>,
+    ) -> Result<Vec<TimelineGroup>, PipelineError>;
 
-    async fn photos_for_days(&self, days: Vec<String>) -> Result<Vec<TimelineGroup>, PipelineError>;
+    async fn get_photos_for_day(
+        &self,
+        day: NaiveDate,
+        page: u32,
+        page_size: u32,
+        zone: &TimelineZone,
+        min_rating: Option<u8>,
+    ) -> Result<Page<PhotoViewModel>, PipelineError>;
 
-    async fn build_timeline(&self, limit: u32, offset: u32) -> Result<Vec<TimelineGroup>, PipelineError>;
+    async fn build_timeline(
+        &self,
+        limit: u32,
+        offset: u32,
+        per_day_limit: u32,
+        zone: &TimelineZone,
+    ) -> Result<Vec<TimelineGroup>, PipelineError>;
+
+    async fn count_path_prefix(&self, storage_id: Uuid, prefix: &str) -> Result<u64, PipelineError>;
+
+    async fn sample_path_prefix(
+        &self,
+        storage_id: Uuid,
+        prefix: &str,
+        limit: u32,
+    ) -> Result<Vec<String>, PipelineError>;
+
+    async fn rewrite_path_prefix(
+        &self,
+        storage_id: Uuid,
+        old_prefix: &str,
+        new_prefix: &str,
+    ) -> Result<u64, PipelineError>;
+
+    async fn record_derivative_profile(
+        &self,
+        storage_id: Uuid,
+        hash: &str,
+        kind: &str,
+        profile: &str,
+    ) -> Result<(), PipelineError>;
+
+    async fn derivative_profiles(&self, storage_id: Uuid) -> Result<HashMap<(String, String), String>, PipelineError>;
+
+    async fn find_stack_partner(&self, photo: &Photo) -> Result<Option<Photo>, PipelineError>;
+
+    async fn link_stack(&self, member_ids: &[Uuid], representative: Uuid) -> Result<(), PipelineError>;
+
+    async fn stack_members(&self, photo_id: Uuid) -> Result<Vec<Photo>, PipelineError>;
+
+    async fn stack_counts(&self, stack_ids: &[Uuid]) -> Result<HashMap<Uuid, i64>, PipelineError>;
+
+    async fn unstack(&self, photo_id: Uuid) -> Result<u64, PipelineError>;
+
+    async fn restack_representatives(&self, preference: StackRepresentative) -> Result<u64, PipelineError>;
+
+    async fn latest_public_photos(&self, limit: u32) -> Result<Vec<FeedPhoto>, PipelineError>;
 }
 
 #[async_trait]
 impl PhotoRepositoryExtensions for Repository<Photo> {
-    async fn find_by_hash(&self, hash: &str) -> Result<Option<Photo>, PipelineError> {
-        self.get_by("hash", Value::String(hash.to_string()))
+    async fn find_by_hash_all(&self, hash: &str) -> Result<Vec<Photo>, PipelineError> {
+        let query = || {
+            self.all(
+                QueryBuilder::<Photo>::new()
+                    .filter("hash", FilterOperator::Eq, Value::String(hash.to_string()))
+                    .sort_asc("created_at")
+                    .sort_asc("id")
+                    .build(),
+            )
+        };
+        ReadRetry::once("find_by_hash_all", query)
+            .await
+            .map_err(|e| Self::query_failed("find_by_hash_all", format!("failed to load photos by hash: {:?}", e)))
+    }
+
+    // Files are shared per hash, so one hidden copy hides every copy of the same content.
+    async fn find_visible_by_hash(&self, hash: &str, include_hidden: bool) -> Result<Vec<Photo>, PipelineError> {
+        if include_hidden {
+            return self.find_by_hash_all(hash).await;
+        }
+
+        let sql = r#"
+            SELECT p.*
+            FROM photos p
+            WHERE p.hash = $1
+            AND NOT EXISTS (
+                SELECT 1
+                FROM photos hp
+                JOIN photo_tags pt ON pt.photo_id = hp.id
+                JOIN tags t ON t.id = pt.tag_id
+                WHERE hp.hash = $1
+                AND t.visibility = $2
+            )
+            ORDER BY p.created_at, p.id
+        "#;
+        self.raw_query::<Photo>(sql, &[Value::String(hash.to_string()), Value::I16(Tag::VISIBILITY_HIDDEN)])
             .await
-            .map_err(|_| PipelineError::message("failed to load photo by hash"))
+            .map_err(|e| Self::query_failed("find_visible_by_hash", format!("failed to load photos by hash: {:?}", e)))
     }
 
-    async fn photos_in_album(&self, album_id: Uuid, page: u32, page_size: u32) -> Result<Page<Photo>, PipelineError> {
+    // Providers expose no transaction, so a failed exif insert removes the photo again. A photo whose
+    // exif row is missing would otherwise break the orientation-corrected dimensions and block re-imports.
+    async fn insert_photo_with_exif(
+        &self,
+        exif_repo: &Repository<ExifModel>,
+        photo: Photo,
+        mut exif: ExifModel,
+    ) -> Result<Photo, PipelineError> {
+        let saved = self
+            .insert(photo)
+            .await
+            .map_err(|e| Self::query_failed("insert_photo_with_exif", format!("failed to insert photo: {:?}", e)))?;
+        exif.image_id = saved.id;
+
+        if let Err(error) = exif_repo.insert(exif).await {
+            if let Err(cleanup) = self.delete(&saved.id).await {
+                log::error!("Failed to remove photo {} after its exif insert failed: {:?}", saved.id, cleanup);
+            }
+            return Err(Self::query_failed(
+                "insert_photo_with_exif",
+                format!("failed to insert exif metadata: {:?}", error),
+            ));
+        }
+        Ok(saved)
+    }
+
+    // A photo left without exif by an interrupted import, which a retry of the same file should finish.
+    async fn find_incomplete_import(
+        &self,
+        exif_repo: &Repository<ExifModel>,
+        storage_id: Uuid,
+        hash: &str,
+    ) -> Result<Option<Photo>, PipelineError> {
+        for photo in self.find_by_hash_all(hash).await?.into_iter().filter(|photo| photo.storage_id == storage_id) {
+            let exif = exif_repo
+                .all(
+                    QueryBuilder::<ExifModel>::new()
+                        .filter("image_id", FilterOperator::Eq, Value::Uuid(photo.id))
+                        .build(),
+                )
+                .await
+                .map_err(|e| {
+                    Self::query_failed("find_incomplete_import", format!("failed to load exif metadata: {:?}", e))
+                })?;
+            if exif.is_empty() {
+                return Ok(Some(photo));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn find_hash_locations(
+        &self,
+        hashes: &[String],
+        include_hidden: bool,
+    ) -> Result<Vec<PhotoHashLocation>, PipelineError> {
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = r#"
+            SELECT DISTINCT p.hash, p.storage_id
+            FROM photos p
+            WHERE p.hash = ANY(ARRAY(SELECT jsonb_array_elements_text($1::jsonb)))
+            AND (
+                $2
+                OR NOT EXISTS (
+                    SELECT 1
+                    FROM photos hp
+                    JOIN photo_tags pt ON pt.photo_id = hp.id
+                    JOIN tags t ON t.id = pt.tag_id
+                    WHERE hp.hash = p.hash
+                    AND t.visibility = $3
+                )
+            )
+            ORDER BY p.hash, p.storage_id
+        "#;
+        let hashes_json = serde_json::to_string(hashes)
+            .map_err(|e| PipelineError::message(&format!("failed to encode hashes: {:?}", e)))?;
+        let params = [Value::String(hashes_json), Value::Bool(include_hidden), Value::I16(Tag::VISIBILITY_HIDDEN)];
+
+        self.raw_query::<PhotoHashLocation>(sql, &params).await.map_err(|e| {
+            Self::query_failed("find_hash_locations", format!("failed to load photo hash locations: {:?}", e))
+        })
+    }
+
+    // Newest first, so a per-run limit always spends its budget on the photos most likely to be opened.
+    async fn preview_warm_candidates(
+        &self,
+        since: Option<DateTime<Utc>>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<PreviewWarmCandidate>, PipelineError> {
+        let sql = r#"
+            SELECT p.id, p.storage_id, p.path, p.hash
+            FROM photos p
+            WHERE p.hash IS NOT NULL
+            AND ($3::timestamptz IS NULL OR p.created_at >= $3::timestamptz)
+            ORDER BY p.created_at DESC NULLS LAST, p.id
+            LIMIT $1 OFFSET $2
+        "#;
+        let since = since.map(Value::DateTime).unwrap_or(Value::Null);
+
+        self.raw_query::<PreviewWarmCandidate>(sql, &[Value::Int(limit as i64), Value::Int(offset as i64), since])
+            .await
+            .map_err(|e| {
+                Self::query_failed("preview_warm_candidates", format!("failed to load preview candidates: {:?}", e))
+            })
+    }
+
+    // Keyset paging, because a thumbnail that cannot be decoded keeps its photos in the result set.
+    async fn blurhash_candidates(
+        &self,
+        after: Option<&BlurhashCandidate>,
+        limit: u32,
+    ) -> Result<Vec<BlurhashCandidate>, PipelineError> {
+        let sql = r#"
+            SELECT DISTINCT p.storage_id, p.hash
+            FROM photos p
+            WHERE p.hash IS NOT NULL
+            AND p.blurhash IS NULL
+            AND ($2::uuid IS NULL OR (p.storage_id, p.hash) > ($2::uuid, $3::text))
+            ORDER BY p.storage_id, p.hash
+            LIMIT $1
+        "#;
+        let (storage_id, hash) = match after {
+            Some(candidate) => (Value::Uuid(candidate.storage_id), Value::String(candidate.hash.clone())),
+            None => (Value::Null, Value::Null),
+        };
+
+        self.raw_query::<BlurhashCandidate>(sql, &[Value::Int(limit as i64), storage_id, hash]).await.map_err(|e| {
+            Self::query_failed("blurhash_candidates", format!("failed to load blurhash candidates: {:?}", e))
+        })
+    }
+
+    // Duplicates in the same storage share one thumbnail, so they share the placeholder too.
+    async fn set_blurhash(&self, storage_id: Uuid, hash: &str, blurhash: &str) -> Result<(), PipelineError> {
+        let sql = "UPDATE photos SET blurhash = $3 WHERE storage_id = $1 AND hash = $2 AND blurhash IS NULL";
+        self.raw_query::<serde_json::Value>(
+            sql,
+            &[Value::Uuid(storage_id), Value::String(hash.to_string()), Value::String(blurhash.to_string())],
+        )
+        .await
+        .map_err(|e| PipelineError::message(&format!("failed to store blurhash: {:?}", e)))?;
+        Ok(())
+    }
+
+    async fn get_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Photo>, PipelineError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
         let query = QueryBuilder::<Photo>::new()
-            .join::<AlbumPhoto>("photo_id", "id")
-            .filter("album_id", FilterOperator::Eq, Value::Uuid(album_id))
-            .page(page, page_size)
+            .filter("id", FilterOperator::In, Value::List(ids.iter().copied().map(Value::Uuid).collect()))
             .build();
 
-        self.query(query).await.map_err(|_| PipelineError::message("failed to load photos in album"))
+        let mut photos: HashMap<Uuid, Photo> = self
+            .all(query)
+            .await
+            .map_err(|e| Self::query_failed("get_by_ids", format!("failed to load photos by id: {:?}", e)))?
+            .into_iter()
+            .map(|photo| (photo.id, photo))
+            .collect();
+        // Callers page over id lists, so results come back in the order they were asked for.
+        Ok(ids.iter().filter_map(|id| photos.remove(id)).collect())
+    }
+
+    async fn add_photo_tags_bulk(&self, photo_ids: &[Uuid], tag_ids: &[Uuid]) -> Result<(), PipelineError> {
+        if photo_ids.is_empty() || tag_ids.is_empty() {
+            return Ok(());
+        }
+
+        let sql = r#"
+            INSERT INTO photo_tags (photo_id, tag_id)
+            SELECT photo_ids.value::uuid, tag_ids.value::uuid
+            FROM jsonb_array_elements_text($1::jsonb) AS photo_ids
+            CROSS JOIN jsonb_array_elements_text($2::jsonb) AS tag_ids
+            ON CONFLICT (photo_id, tag_id) DO NOTHING
+        "#;
+
+        self.raw_query::<serde_json::Value>(sql, &[encode_id_list(photo_ids)?, encode_id_list(tag_ids)?])
+            .await
+            .map_err(|e| Self::query_failed("add_photo_tags_bulk", format!("failed to add photo tags: {:?}", e)))?;
+        Ok(())
+    }
+
+    async fn remove_photo_tags_bulk(&self, photo_ids: &[Uuid], tag_ids: &[Uuid]) -> Result<(), PipelineError> {
+        if photo_ids.is_empty() || tag_ids.is_empty() {
+            return Ok(());
+        }
+
+        let sql = r#"
+            DELETE FROM photo_tags
+            WHERE photo_id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+              AND tag_id IN (SELECT value::uuid FROM jsonb_array_elements_text($2::jsonb))
+        "#;
+
+        self.raw_query::<serde_json::Value>(sql, &[encode_id_list(photo_ids)?, encode_id_list(tag_ids)?])
+            .await
+            .map_err(|e| {
+                Self::query_failed("remove_photo_tags_bulk", format!("failed to remove photo tags: {:?}", e))
+            })?;
+        Ok(())
+    }
+
+    async fn replace_photo_tags_bulk(
+        &self,
+        photo_ids: &[Uuid],
+        tag_ids: &[Uuid],
+        keep_hidden: bool,
+    ) -> Result<(), PipelineError> {
+        if photo_ids.is_empty() {
+            return Ok(());
+        }
+
+        let sql = r#"
+            WITH removed AS (
+                DELETE FROM photo_tags
+                WHERE photo_id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+                  AND tag_id NOT IN (SELECT value::uuid FROM jsonb_array_elements_text($2::jsonb))
+                  AND NOT ($3 AND tag_id IN (SELECT id FROM tags WHERE visibility = $4))
+            )
+            INSERT INTO photo_tags (photo_id, tag_id)
+            SELECT photo_ids.value::uuid, tag_ids.value::uuid
+            FROM jsonb_array_elements_text($1::jsonb) AS photo_ids
+            CROSS JOIN jsonb_array_elements_text($2::jsonb) AS tag_ids
+            ON CONFLICT (photo_id, tag_id) DO NOTHING
+        "#;
+
+        self.raw_query::<serde_json::Value>(
+            sql,
+            &[
+                encode_id_list(photo_ids)?,
+                encode_id_list(tag_ids)?,
+                Value::Bool(keep_hidden),
+                Value::I16(Tag::VISIBILITY_HIDDEN),
+            ],
+        )
+        .await
+        .map_err(|e| Self::query_failed("replace_photo_tags_bulk", format!("failed to replace photo tags: {:?}", e)))?;
+        Ok(())
+    }
+
+    // One statement for every selected photo; timeline columns follow dateTaken like refresh_timeline_dates.
+    async fn update_photo_fields_bulk(
+        &self,
+        photo_ids: &[Uuid],
+        changes: &BatchPhotoChanges,
+    ) -> Result<Vec<Uuid>, PipelineError> {
+        #[derive(Deserialize)]
+        struct IdRow {
+            id: Uuid,
+        }
+
+        if photo_ids.is_empty() || !changes.has_field_changes() {
+            return Ok(Vec::new());
+        }
+
+        let mut assignments = vec!["updated_at = NOW()".to_string()];
+        let mut params = vec![encode_id_list(photo_ids)?];
+        if let Some(caption) = &changes.caption {
+            params.push(Value::String(caption.trim().to_string()));
+            assignments.push(format!("caption = NULLIF(${}, '')", params.len()));
+        }
+        if let Some(rating) = changes.rating {
+            params.push(Value::Int(rating as i64));
+            assignments.push(format!("rating = ${}", params.len()));
+        }
+        if let Some(date_taken) = changes.date_taken {
+            params.push(Value::DateTime(date_taken));
+            let index = params.len();
+            assignments.push(format!(
+                "date_taken = ${index}, sort_date = ${index}, day_date = (${index} AT TIME ZONE 'UTC')::date, \
+                 year = EXTRACT(YEAR FROM ${index} AT TIME ZONE 'UTC')::int, \
+                 month_day = to_char(${index} AT TIME ZONE 'UTC', 'MM-DD')"
+            ));
+        }
+
+        let sql = format!(
+            "UPDATE photos SET {} WHERE id = ANY(ARRAY(SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))) \
+             RETURNING id",
+            assignments.join(", ")
+        );
+        let rows = self.raw_query::<IdRow>(&sql, &params).await.map_err(|e| {
+            Self::query_failed("update_photo_fields_bulk", format!("failed to update photo fields: {:?}", e))
+        })?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    async fn photos_in_album(
+        &self,
+        album_id: Uuid,
+        page: u32,
+        page_size: u32,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Page<Photo>, PipelineError> {
+        #[derive(Deserialize)]
+        struct VisiblePageRow {
+            total: i64,
+            ids: Vec<Uuid>,
+        }
+
+        let sql = r#"
+            WITH visible AS (
+                SELECT p.id, ap.position, p.sort_date
+                FROM album_photos ap
+                JOIN photos p ON p.id = ap.photo_id
+                WHERE ap.album_id = $1
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM photo_tags hpt
+                    JOIN tags ht ON ht.id = hpt.tag_id
+                    WHERE hpt.photo_id = p.id
+                    AND lower(ht.name) IN (SELECT jsonb_array_elements_text($2::jsonb))
+                )
+            ),
+            page AS (
+                SELECT id, position, sort_date
+                FROM visible
+                ORDER BY position NULLS LAST, sort_date DESC NULLS LAST, id
+                LIMIT $3 OFFSET $4
+            )
+            SELECT
+                (SELECT count(*) FROM visible) AS total,
+                COALESCE(
+                    json_agg(page.id ORDER BY page.position NULLS LAST, page.sort_date DESC NULLS LAST, page.id),
+                    '[]'::json
+                ) AS ids
+            FROM page
+        "#;
+
+        let hidden: Vec<&String> = hidden_tags.iter().collect();
+        let hidden_json = serde_json::to_string(&hidden)
+            .map_err(|e| PipelineError::message(&format!("failed to encode hidden tags: {:?}", e)))?;
+        let offset = page.saturating_sub(1).saturating_mul(page_size);
+
+        let visible = self
+            .raw_query::<VisiblePageRow>(
+                sql,
+                &[
+                    Value::Uuid(album_id),
+                    Value::String(hidden_json),
+                    Value::Int(page_size as i64),
+                    Value::Int(offset as i64),
+                ],
+            )
+            .await
+            .map_err(|e| {
+                Self::query_failed("photos_in_album", format!("failed to load visible album photos: {:?}", e))
+            })?
+            .into_iter()
+            .next()
+            .unwrap_or(VisiblePageRow { total: 0, ids: Vec::new() });
+
+        let items = self.get_by_ids(&visible.ids).await?;
+        Ok(Page { items, total: visible.total.max(0) as u64, page, page_size })
+    }
+
+    // Same order as photos_in_album, so cached pages line up with the SQL-paged ones.
+    async fn album_member_ids(&self, album_id: Uuid) -> Result<Vec<Uuid>, PipelineError> {
+        #[derive(Deserialize)]
+        struct MemberRow {
+            id: Uuid,
+        }
+
+        let sql = r#"
+            SELECT p.id
+            FROM album_photos ap
+            JOIN photos p ON p.id = ap.photo_id
+            WHERE ap.album_id = $1
+            ORDER BY ap.position NULLS LAST, p.sort_date DESC NULLS LAST, p.id
+        "#;
+
+        let rows = self
+            .raw_query::<MemberRow>(sql, &[Value::Uuid(album_id)])
+            .await
+            .map_err(|e| Self::query_failed("album_member_ids", format!("failed to load album members: {:?}", e)))?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    async fn get_photos_page(
+        &self,
+        page: u32,
+        page_size: u32,
+        sort: PhotoSort,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Page<Photo>, PipelineError> {
+        self.filter_photos_by_tags(&[], TagMatch::Any, page, page_size, sort, hidden_tags).await
+    }
+
+    async fn filter_photos_by_tags(
+        &self,
+        tags: &[String],
+        matching: TagMatch,
+        page: u32,
+        page_size: u32,
+        sort: PhotoSort,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Page<Photo>, PipelineError> {
+        self.query_photos(&PhotoFilter::new(tags, matching), page, page_size, sort, hidden_tags).await
+    }
+
+    async fn query_photos(
+        &self,
+        filter: &PhotoFilter,
+        page: u32,
+        page_size: u32,
+        sort: PhotoSort,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Page<Photo>, PipelineError> {
+        #[derive(Deserialize)]
+        struct SortedPageRow {
+            total: i64,
+            ids: Vec<Uuid>,
+        }
+
+        let (sql, params) = PhotoQueryBuilder::new(filter, hidden_tags)?.page_sql(sort, page, page_size);
+        let sorted = self
+            .raw_query::<SortedPageRow>(&sql, &params)
+            .await
+            .map_err(|e| Self::query_failed("query_photos", format!("failed to load photos page: {:?}", e)))?
+            .into_iter()
+            .next()
+            .unwrap_or(SortedPageRow { total: 0, ids: Vec::new() });
+
+        let mut items = self.get_by_ids(&sorted.ids).await?;
+        let stack_ids: Vec<Uuid> = items.iter().filter_map(|photo| photo.stack_id).collect();
+        if !stack_ids.is_empty() {
+            let counts = self.stack_counts(&stack_ids).await?;
+            for photo in items.iter_mut() {
+                photo.stack_count = photo.stack_id.and_then(|stack_id| counts.get(&stack_id).copied());
+            }
+        }
+
+        Ok(Page { items, total: sorted.total.max(0) as u64, page, page_size })
+    }
+
+    async fn count_photos(&self, filter: &PhotoFilter, hidden_tags: &HashSet<String>) -> Result<u64, PipelineError> {
+        #[derive(Deserialize)]
+        struct CountRow {
+            total: i64,
+        }
+
+        let builder = PhotoQueryBuilder::new(filter, hidden_tags)?;
+        let rows = self
+            .raw_query::<CountRow>(&builder.count_sql(), builder.params())
+            .await
+            .map_err(|e| Self::query_failed("count_photos", format!("failed to count photos: {:?}", e)))?;
+        Ok(rows.into_iter().next().map(|row| row.total.max(0) as u64).unwrap_or(0))
     }
 
     async fn delete_photo(&self, context: &HttpContext, photo: &Photo) -> Result<u32, PipelineError> {
@@ -75,6 +752,10 @@ impl PhotoRepositoryExtensions for Repository<Photo> {
             .delete_by("photo_id", Value::Uuid(photo.id))
             .await
             .map_err(|e| PipelineError::message(&format!("failed to delete album_photo records: {:?}", e)))?;
+        // A stack id is its representative's id, so this also releases partners of a deleted representative.
+        if let Some(stack_id) = photo.stack_id {
+            photo_repo.unstack(stack_id).await?;
+        }
 
         Ok(())
     }
@@ -98,12 +779,11 @@ impl PhotoRepositoryExtensions for Repository<Photo> {
         let _ = file_service.remove_file(&source_path);
 
         if let Some(hash) = photo.hash.as_ref() {
-            let thumbnail_path = file_service.path_for_hash(
-                root.join(SettingConsts::THUMBNAIL_FOLDER),
-                hash,
-                SettingConsts::THUMBNAIL_FORMAT,
-            );
-            let _ = file_service.remove_file(&thumbnail_path);
+            for format in ThumbnailFormat::ALL {
+                let thumbnail_path =
+                    file_service.path_for_hash(root.join(SettingConsts::THUMBNAIL_FOLDER), hash, format.extension());
+                let _ = file_service.remove_file(&thumbnail_path);
+            }
 
             let preview_path = file_service.path_for_hash(
                 root.join(SettingConsts::PREVIEW_FOLDER),
@@ -116,190 +796,951 @@ impl PhotoRepositoryExtensions for Repository<Photo> {
         Ok(())
     }
 
-    async fn get_years(&self) -> Result<Vec<String>, PipelineError> {
+    async fn get_years(&self, zone: &TimelineZone) -> Result<Vec<i32>, PipelineError> {
         #[derive(Deserialize)]
         struct YearRow {
-            year: String,
+            year: i32,
         }
 
         let sql = format!(
             r#"
-            SELECT DISTINCT p.year::text as year
-            FROM photos p
-            WHERE p.year IS NOT NULL
+            WITH {days}
+            SELECT DISTINCT EXTRACT(YEAR FROM day_date)::int AS year
+            FROM day_groups
             ORDER BY year DESC
-        "#
+        "#,
+            days = zone.day_groups_cte(1),
         );
 
-        let rows = self
-            .raw_query::<YearRow>(&sql, &[])
+        let params = [Value::String(zone.as_str().to_string())];
+        let rows = ReadRetry::once("get_years", || self.raw_query::<YearRow>(&sql, &params))
             .await
-            .map_err(|e| PipelineError::message(&format!("failed to load years: {:?}", e)))?;
+            .map_err(|e| Self::query_failed("get_years", format!("failed to load years: {:?}", e)))?;
 
         Ok(rows.into_iter().map(|row| row.year).collect())
     }
 
-    async fn get_year_offset(&self, year: &str) -> Result<u32, PipelineError> {
-        #[derive(Deserialize)]
-        struct OffsetRow {
-            offset: i64,
-        }
-
+    // Day counts follow the day list the timeline pages through; photo counts leave out stacked members
+    // and photos the caller may not see.
+    async fn get_year_counts(
+        &self,
+        zone: &TimelineZone,
+        hidden_tags: &HashSet<String>,
+    ) -> Result<Vec<TimelineYearCount>, PipelineError> {
         let sql = format!(
             r#"
-            WITH day_groups AS (
-                SELECT DISTINCT p.day_date as day
+            WITH {tz}, dated AS (
+                SELECT
+                    {day} AS day_date,
+                    {representative}
+                    AND NOT EXISTS (
+                        SELECT 1
+                        FROM photo_tags hpt
+                        JOIN tags ht ON ht.id = hpt.tag_id
+                        WHERE hpt.photo_id = p.id
+                        AND lower(ht.name) IN (SELECT jsonb_array_elements_text($2::jsonb))
+                    ) AS visible
                 FROM photos p
+                CROSS JOIN tz
+                WHERE p.day_date IS NOT NULL
             )
-            SELECT count(*) as offset
-            FROM day_groups
-            WHERE EXTRACT(YEAR FROM day)::int > $1
-        "#
+            SELECT
+                EXTRACT(YEAR FROM day_date)::int AS year,
+                count(DISTINCT day_date) AS day_count,
+                count(*) FILTER (WHERE visible) AS photo_count
+            FROM dated
+            GROUP BY 1
+            ORDER BY year DESC
+        "#,
+            tz = TimelineZone::sql_cte(1),
+            day = zone.day_expression("p"),
+            representative = PhotoStack::representative_clause("p"),
         );
 
+        let hidden: Vec<&String> = hidden_tags.iter().collect();
+        let hidden_json = serde_json::to_string(&hidden)
+            .map_err(|e| PipelineError::message(&format!("failed to encode hidden tags: {:?}", e)))?;
+        let params = [Value::String(zone.as_str().to_string()), Value::String(hidden_json)];
+        ReadRetry::once("get_year_counts", || self.raw_query::<TimelineYearCount>(&sql, &params))
+            .await
+            .map_err(|e| Self::query_failed("get_year_counts", format!("failed to load year counts: {:?}", e)))
+    }
+
+    async fn get_year_offset(
+        &self,
+        year: &str,
+        zone: &TimelineZone,
+        granularity: TimelineGranularity,
+    ) -> Result<u32, PipelineError> {
+        #[derive(Deserialize)]
+        struct OffsetRow {
+            offset: i64,
+        }
+
+        let sql = zone.granular_year_offset_sql(granularity);
         let search_year =
             year.parse::<i32>().map_err(|e| PipelineError::message(&format!("invalid year '{}': {}", year, e)))?;
-        let rows = self
-            .raw_query::<OffsetRow>(&sql, &[Value::Int(search_year as i64)])
+        let params = [Value::Int(search_year as i64), Value::String(zone.as_str().to_string())];
+        let rows = ReadRetry::once("get_year_offset", || self.raw_query::<OffsetRow>(&sql, &params))
             .await
-            .map_err(|e| PipelineError::message(&format!("failed to load year offset: {:?}", e)))?;
+            .map_err(|e| Self::query_failed("get_year_offset", format!("failed to load year offset: {:?}", e)))?;
         let offset = rows.first().map(|row| row.offset).unwrap_or(0);
         Ok(offset.max(0) as u32)
     }
 
-    async fn photos_with_gps(&self, limit: u32, offset: u32) -> Result<Vec<PhotoLoc>, PipelineError> {
+    async fn get_yeardays(&self, zone: &TimelineZone) -> Result<Vec<TimelineYearDays>, PipelineError> {
+        #[derive(Deserialize)]
+        struct YearDayRow {
+            year: i32,
+            day: String,
+        }
+
+        let sql = format!(
+            r#"
+            WITH {days}
+            SELECT EXTRACT(YEAR FROM day_date)::int AS year, to_char(day_date, 'YYYY-MM-DD') AS day
+            FROM day_groups
+            ORDER BY {order}
+        "#,
+            days = zone.day_groups_cte(1),
+            order = TimelineZone::DAY_ORDER,
+        );
+
+        let params = [Value::String(zone.as_str().to_string())];
+        let rows = ReadRetry::once("get_yeardays", || self.raw_query::<YearDayRow>(&sql, &params))
+            .await
+            .map_err(|e| Self::query_failed("get_yeardays", format!("failed to load year days: {:?}", e)))?;
+
+        let mut result: Vec<TimelineYearDays> = Vec::new();
+        for row in rows {
+            let day = NaiveDate::parse_from_str(&row.day, "%Y-%m-%d")
+                .map_err(|e| PipelineError::message(&format!("invalid day '{}': {}", row.day, e)))?;
+            match result.last_mut() {
+                Some(current) if current.year == row.year => current.days.push(day),
+                _ => result.push(TimelineYearDays { year: row.year, days: vec![day] }),
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn get_days(
+        &self,
+        page: u32,
+        page_size: u32,
+        zone: &TimelineZone,
+        min_rating: Option<u8>,
+    ) -> Result<Vec<String>, PipelineError> {
+        #[derive(Deserialize)]
+        struct DayRow {
+            day: String,
+        }
+
+        let offset = page.saturating_sub(1).saturating_mul(page_size);
+        let mut params =
+            vec![Value::Int(page_size as i64), Value::Int(offset as i64), Value::String(zone.as_str().to_string())];
+        let sql = match min_rating {
+            Some(min_rating) => {
+                params.push(Value::Int(min_rating as i64));
+                zone.filtered_days_page_sql(Some(&PhotoRating::min_clause("p", params.len())))
+            }
+            None => zone.days_page_sql(),
+        };
+        let rows = ReadRetry::once("get_days", || self.raw_query::<DayRow>(&sql, &params))
+            .await
+            .map_err(|e| Self::query_failed("get_days", format!("failed to load timeline days: {:?}", e)))?;
+
+        Ok(rows.into_iter().map(|row| row.day).collect())
+    }
+
+    async fn photos_with_gps_with_tags(
+        &self,
+        limit: u32,
+        offset: u32,
+        hidden_tags: &HashSet<String>,
+        include_hidden_tags: bool,
+        precision: CoordinatePrecision,
+    ) -> Result<Vec<PhotoLocWithTags>, PipelineError> {
+        // Rounded in SQL so neither the rows nor anything clustered from them carry the exact position.
+        // A negative $5 means exact.
         let sql = format!(
             r#"
             SELECT
                 p.*,
-                e.gps_latitude as lat,
-                e.gps_longitude as lon
+                CASE WHEN $5::int < 0 THEN e.gps_latitude ELSE round(e.gps_latitude::numeric, $5::int)::float8 END as lat,
+                CASE WHEN $5::int < 0 THEN e.gps_longitude ELSE round(e.gps_longitude::numeric, $5::int)::float8 END as lon,
+                COALESCE(
+                    json_agg(t.name ORDER BY t.name) FILTER (WHERE t.id IS NOT NULL AND ($4 OR t.visibility = 0)),
+                    '[]'::json
+                ) AS tags
             FROM photos p
             JOIN exifs e ON p.id = e.image_id
+            LEFT JOIN photo_tags pt ON pt.photo_id = p.id
+            LEFT JOIN tags t ON t.id = pt.tag_id
             WHERE
                 e.gps_latitude IS NOT NULL
                 AND e.gps_longitude IS NOT NULL
                 AND e.gps_latitude <> 0
                 AND e.gps_longitude <> 0
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM photo_tags hpt
+                    JOIN tags ht ON ht.id = hpt.tag_id
+                    WHERE hpt.photo_id = p.id
+                    AND lower(ht.name) IN (SELECT jsonb_array_elements_text($3::jsonb))
+                )
+            GROUP BY p.id, e.gps_latitude, e.gps_longitude
             ORDER BY p.sort_date DESC
             LIMIT $1 OFFSET $2
         "#
         );
 
+        let hidden: Vec<&String> = hidden_tags.iter().collect();
+        let hidden_json = serde_json::to_string(&hidden)
+            .map_err(|e| PipelineError::message(&format!("failed to encode hidden tags: {:?}", e)))?;
+
         let rows = self
-            .raw_query::<PhotoLoc>(&sql, &[Value::Int(limit as i64), Value::Int(offset as i64)])
+            .raw_query::<PhotoLocWithTags>(
+                &sql,
+                &[
+                    Value::Int(limit as i64),
+                    Value::Int(offset as i64),
+                    Value::String(hidden_json),
+                    Value::Bool(include_hidden_tags),
+                    Value::Int(precision.decimals().map_or(-1, i64::from)),
+                ],
+            )
             .await
-            .map_err(|e| PipelineError::message(&format!("failed to load photos with GPS: {:?}", e)))?;
+            .map_err(|e| {
+                Self::query_failed("photos_with_gps_with_tags", format!("failed to load photos with GPS: {:?}", e))
+            })?;
 
         Ok(rows)
     }
 
-    async fn build_timeline(&self, limit: u32, offset: u32) -> Result<Vec<TimelineGroup>, PipelineError> {
+    async fn recently_added(
+        &self,
+        page: u32,
+        page_size: u32,
+        days: Option<u32>,
+        hidden_tags: &HashSet<String>,
+        include_hidden_tags: bool,
+        zone: &TimelineZone,
+    ) -> Result<Page<PhotoWithTags>, PipelineError> {
+        #[derive(Deserialize)]
+        struct CountRow {
+            total: i64,
+        }
+
+        let visible = r#"
+            FROM photos p
+            WHERE ($1 <= 0 OR COALESCE(p.date_imported, p.created_at) >= now() - make_interval(days => $1::int))
+            AND NOT EXISTS (
+                SELECT 1
+                FROM photo_tags hpt
+                JOIN tags ht ON ht.id = hpt.tag_id
+                WHERE hpt.photo_id = p.id
+                AND lower(ht.name) IN (SELECT jsonb_array_elements_text($2::jsonb))
+            )
+        "#;
+        let count_sql = format!("SELECT count(*) AS total {visible}");
+        let page_sql = format!(
+            r#"
+            WITH {tz}, recent AS (
+                SELECT p.id
+                {visible}
+                ORDER BY COALESCE(p.date_imported, p.created_at) DESC NULLS LAST, p.id DESC
+                LIMIT $3 OFFSET $4
+            )
+            SELECT
+                p.*,
+                to_char((COALESCE(p.date_imported, p.created_at) AT TIME ZONE tz.name)::date, 'YYYY-MM-DD') AS imported_on,
+                COALESCE(
+                    json_agg(t.name ORDER BY t.name) FILTER (WHERE t.id IS NOT NULL AND ($6 OR t.visibility = 0)),
+                    '[]'::json
+                ) AS tags
+            FROM recent r
+            JOIN photos p ON p.id = r.id
+            CROSS JOIN tz
+            LEFT JOIN photo_tags pt ON pt.photo_id = p.id
+            LEFT JOIN tags t ON t.id = pt.tag_id
+            GROUP BY p.id, tz.name
+            ORDER BY COALESCE(p.date_imported, p.created_at) DESC NULLS LAST, p.id DESC
+        "#,
+            tz = TimelineZone::sql_cte(5),
+        );
+
+        let days = days.unwrap_or(0) as i64;
+        let hidden: Vec<&String> = hidden_tags.iter().collect();
+        let hidden_json = serde_json::to_string(&hidden)
+            .map_err(|e| PipelineError::message(&format!("failed to encode hidden tags: {:?}", e)))?;
+        let offset = page.saturating_sub(1).saturating_mul(page_size);
+
+        let total = self
+            .raw_query::<CountRow>(&count_sql, &[Value::Int(days), Value::String(hidden_json.clone())])
+            .await
+            .map_err(|e| Self::query_failed("recently_added", format!("failed to count recent photos: {:?}", e)))?
+            .first()
+            .map(|row| row.total)
+            .unwrap_or(0);
+
+        let items = self
+            .raw_query::<PhotoWithTags>(
+                &page_sql,
+                &[
+                    Value::Int(days),
+                    Value::String(hidden_json),
+                    Value::Int(page_size as i64),
+                    Value::Int(offset as i64),
+                    Value::String(zone.as_str().to_string()),
+                    Value::Bool(include_hidden_tags),
+                ],
+            )
+            .await
+            .map_err(|e| Self::query_failed("recently_added", format!("failed to load recent photos: {:?}", e)))?;
+
+        Ok(Page { items, total: total.max(0) as u64, page, page_size })
+    }
+
+    async fn photos_without_album(&self) -> Result<Vec<EventCandidate>, PipelineError> {
+        let sql = r#"
+            SELECT
+                p.id,
+                p.hash,
+                p.date_taken AS taken_at,
+                e.gps_latitude AS latitude,
+                e.gps_longitude AS longitude
+            FROM photos p
+            LEFT JOIN exifs e ON e.image_id = p.id
+            WHERE p.date_taken IS NOT NULL
+            AND NOT EXISTS (SELECT 1 FROM album_photos ap WHERE ap.photo_id = p.id)
+            ORDER BY p.date_taken, p.id
+        "#;
+
+        self.raw_query::<EventCandidate>(sql, &[]).await.map_err(|e| {
+            Self::query_failed("photos_without_album", format!("failed to load photos without album: {:?}", e))
+        })
+    }
+
+    async fn random_photos(
+        &self,
+        count: u32,
+        tags: &[String],
+        hidden_tags: &HashSet<String>,
+        include_hidden: bool,
+    ) -> Result<Vec<RandomPhoto>, PipelineError> {
+        #[derive(Deserialize)]
+        struct EstimateRow {
+            estimate: i64,
+        }
+
+        let estimate = self
+            .raw_query::<EstimateRow>(
+                "SELECT GREATEST(reltuples, 0)::bigint AS estimate FROM pg_class WHERE oid = 'photos'::regclass",
+                &[],
+            )
+            .await
+            .map_err(|e| Self::query_failed("random_photos", format!("failed to estimate photo count: {:?}", e)))?
+            .first()
+            .map(|row| row.estimate)
+            .unwrap_or(0);
+
+        let filter: Vec<String> =
+            tags.iter().map(|tag| tag.trim().to_lowercase()).filter(|tag| !tag.is_empty()).collect();
+        let filter_json = serde_json::to_string(&filter)
+            .map_err(|e| PipelineError::message(&format!("failed to encode tag filter: {:?}", e)))?;
+        let hidden: Vec<&String> = hidden_tags.iter().collect();
+        let hidden_json = serde_json::to_string(&hidden)
+            .map_err(|e| PipelineError::message(&format!("failed to encode hidden tags: {:?}", e)))?;
+
+        let mut sampling = RandomSampling::choose(estimate, count);
+        loop {
+            let sql = format!(
+                r#"
+                SELECT p.id, p.storage_id, p.hash, p.name, p.width, p.height, p.date_taken, p.sort_date
+                FROM {photos}
+                WHERE p.hash IS NOT NULL
+                AND (
+                    jsonb_array_length($1::jsonb) = 0
+                    OR EXISTS (
+                        SELECT 1
+                        FROM photo_tags fpt
+                        JOIN tags ft ON ft.id = fpt.tag_id
+                        WHERE fpt.photo_id = p.id
+                        AND lower(ft.name) IN (SELECT jsonb_array_elements_text($1::jsonb))
+                    )
+                )
+                AND NOT EXISTS (
+                    SELECT 1
+                    FROM photo_tags hpt
+                    JOIN tags ht ON ht.id = hpt.tag_id
+                    WHERE hpt.photo_id = p.id
+                    AND lower(ht.name) IN (SELECT jsonb_array_elements_text($2::jsonb))
+                )
+                AND (
+                    $4
+                    OR NOT EXISTS (
+                        SELECT 1
+                        FROM photo_tags vpt
+                        JOIN tags vt ON vt.id = vpt.tag_id
+                        WHERE vpt.photo_id = p.id
+                        AND vt.visibility = $5
+                    )
+                )
+                ORDER BY random()
+                LIMIT $3
+            "#,
+                photos = sampling.table_clause("photos", "p"),
+            );
+
+            let photos = self
+                .raw_query::<RandomPhoto>(
+                    &sql,
+                    &[
+                        Value::String(filter_json.clone()),
+                        Value::String(hidden_json.clone()),
+                        Value::Int(count as i64),
+                        Value::Bool(include_hidden),
+                        Value::I16(Tag::VISIBILITY_HIDDEN),
+                    ],
+                )
+                .await
+                .map_err(|e| Self::query_failed("random_photos", format!("failed to load random photos: {:?}", e)))?;
+
+            if photos.len() >= count as usize || sampling == RandomSampling::Full {
+                return Ok(photos);
+            }
+            sampling = RandomSampling::Full;
+        }
+    }
+
+    async fn build_timeline(
+        &self,
+        limit: u32,
+        offset: u32,
+        per_day_limit: u32,
+        zone: &TimelineZone,
+    ) -> Result<Vec<TimelineGroup>, PipelineError> {
         let sql = format!(
             r#"
-            WITH target_days AS (
-                SELECT DISTINCT
-                    p.day_date
-                FROM photos p
-                ORDER BY p.day_date DESC
+            WITH {days},
+            target_days AS (
+                SELECT day_date
+                FROM day_groups
+                ORDER BY {order}
                 LIMIT $1 OFFSET $2
             )
             SELECT
                 to_char(td.day_date, 'YYYY-MM-DD') AS day,
-                p_agg.totalCount,
-                p_agg.photosPayload
+                (SELECT count(*) FROM photos c CROSS JOIN tz WHERE {day_c} = td.day_date AND {representative_c}) AS "totalCount",
+                COALESCE(p_agg.photosPayload, '[]'::json) AS "photosPayload"
             FROM target_days td
             LEFT JOIN LATERAL (
                 SELECT
-                    count(*) AS totalCount,
                     json_agg(
                         json_build_object(
                             'id', dp.id,
                             'hash', COALESCE(dp.hash, ''),
                             'width', dp.width,
                             'height', dp.height,
-                            'name', dp.name
+                            'name', dp.name,
+                            'blurhash', dp.blurhash,
+                            'stackCount', dp.stack_count
                         )
+                        ORDER BY dp.sort_date DESC
                     ) AS photosPayload
                 FROM (
-                    SELECT p.id, p.hash, p.width, p.height, p.name
+                    SELECT p.id, p.hash, p.width, p.height, p.name, p.blurhash, p.sort_date, {stack_count} AS stack_count
                     FROM photos p
-                    WHERE p.day_date = td.day_date
+                    CROSS JOIN tz
+                    WHERE {day} = td.day_date AND {representative}
                     ORDER BY p.sort_date DESC
+                    LIMIT $3
                 ) dp
             ) p_agg ON true
-            ORDER BY td.day_date DESC;
-        "#
+            ORDER BY {order};
+        "#,
+            days = zone.day_groups_cte(4),
+            order = TimelineZone::DAY_ORDER,
+            day = zone.day_expression("p"),
+            day_c = zone.day_expression("c"),
+            representative = PhotoStack::representative_clause("p"),
+            representative_c = PhotoStack::representative_clause("c"),
+            stack_count = PhotoStack::count_expression("p"),
         );
 
-        let groups = self
-            .raw_query::<PhotoGroup>(&sql, &[Value::Int(limit as i64), Value::Int(offset as i64)])
+        let params = [
+            Value::Int(limit as i64),
+            Value::Int(offset as i64),
+            Value::Int(per_day_limit as i64),
+            Value::String(zone.as_str().to_string()),
+        ];
+        let groups = ReadRetry::once("build_timeline", || self.raw_query::<PhotoGroup>(&sql, &params))
             .await
-            .map_err(|e| PipelineError::message(&format!("failed to load timeline: {:?}", e)))?;
-
-        let mut timeline = Vec::new();
-        for group in groups {
-            timeline.push(TimelineGroup {
-                title: group.day,
-                photos: Page::new(group.photos_payload, group.total_count as u64, 1, group.total_count as u32),
-            });
-        }
+            .map_err(|e| Self::query_failed("build_timeline", format!("failed to load timeline: {:?}", e)))?;
 
-        Ok(timeline)
+        Ok(groups.into_iter().map(|group| Self::to_timeline_group(group, per_day_limit)).collect())
     }
 
-    async fn photos_for_days(&self, days: Vec<String>) -> Result<Vec<TimelineGroup>, PipelineError> {
+    async fn photos_for_days(
+        &self,
+        days: Vec<String>,
+        per_day_limit: u32,
+        zone: &TimelineZone,
+        min_rating: Option<u8>,
+    ) -> Result<Vec<TimelineGroup>, PipelineError> {
         if days.is_empty() {
             return Ok(Vec::new());
         }
 
-        let day_dates: Vec<NaiveDate> = days
-            .iter()
-            .map(|d| {
-                NaiveDate::parse_from_str(d, "%Y-%m-%d")
-                    .map_err(|e| PipelineError::message(&format!("invalid day '{}': {}", d, e)))
+        for day in &days {
+            NaiveDate::parse_from_str(day, "%Y-%m-%d")
+                .map_err(|e| PipelineError::message(&format!("invalid day '{}': {}", day, e)))?;
+        }
+
+        let sql = format!(
+            r#"
+            WITH {tz},
+            target_days AS (
+                SELECT DISTINCT value::date AS day_date
+                FROM jsonb_array_elements_text($1::jsonb)
+            )
+            SELECT
+                to_char(td.day_date, 'YYYY-MM-DD') AS day,
+                (
+                    SELECT count(*) FROM photos c CROSS JOIN tz
+                    WHERE {day_c} = td.day_date AND {representative_c} AND {rating_c}
+                ) AS "totalCount",
+                COALESCE(p_agg.photosPayload, '[]'::json) AS "photosPayload"
+            FROM target_days td
+            LEFT JOIN LATERAL (
+                SELECT
+                    json_agg(
+                        json_build_object(
+                            'id', dp.id,
+                            'hash', COALESCE(dp.hash, ''),
+                            'width', dp.width,
+                            'height', dp.height,
+                            'name', dp.name,
+                            'blurhash', dp.blurhash,
+                            'stackCount', dp.stack_count
+                        )
+                        ORDER BY dp.sort_date DESC
+                    ) AS photosPayload
+                FROM (
+                    SELECT p.id, p.hash, p.width, p.height, p.name, p.blurhash, p.sort_date, {stack_count} AS stack_count
+                    FROM photos p
+                    CROSS JOIN tz
+                    WHERE {day} = td.day_date AND {representative} AND {rating}
+                    ORDER BY p.sort_date DESC
+                    LIMIT $2
+                ) dp
+            ) p_agg ON true
+        "#,
+            tz = TimelineZone::sql_cte(3),
+            day = zone.day_expression("p"),
+            day_c = zone.day_expression("c"),
+            representative = PhotoStack::representative_clause("p"),
+            representative_c = PhotoStack::representative_clause("c"),
+            rating = PhotoRating::min_clause("p", 4),
+            rating_c = PhotoRating::min_clause("c", 4),
+            stack_count = PhotoStack::count_expression("p"),
+        );
+
+        let days_json = serde_json::to_string(&days)
+            .map_err(|e| PipelineError::message(&format!("failed to encode days: {:?}", e)))?;
+        let params = [
+            Value::String(days_json),
+            Value::Int(per_day_limit as i64),
+            Value::String(zone.as_str().to_string()),
+            Value::Int(min_rating.unwrap_or(0) as i64),
+        ];
+        let rows = ReadRetry::once("photos_for_days", || self.raw_query::<PhotoGroup>(&sql, &params))
+            .await
+            .map_err(|e| Self::query_failed("photos_for_days", format!("failed to load photos for days: {:?}", e)))?;
+
+        let mut by_day: HashMap<String, PhotoGroup> = rows.into_iter().map(|row| (row.day.clone(), row)).collect();
+        let groups = days
+            .into_iter()
+            .map(|day| {
+                let group =
+                    by_day.remove(&day).unwrap_or(PhotoGroup { day, total_count: 0, photos_payload: Vec::new() });
+                Self::to_timeline_group(group, per_day_limit)
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect();
+
+        Ok(groups)
+    }
+
+    async fn get_photos_for_day(
+        &self,
+        day: NaiveDate,
+        page: u32,
+        page_size: u32,
+        zone: &TimelineZone,
+        min_rating: Option<u8>,
+    ) -> Result<Page<PhotoViewModel>, PipelineError> {
+        #[derive(Deserialize)]
+        struct CountRow {
+            total: i64,
+        }
+
+        let count_sql = format!(
+            r#"
+            WITH {tz}
+            SELECT count(*) AS total
+            FROM photos p
+            CROSS JOIN tz
+            WHERE {day} = $1 AND {representative} AND {rating}
+        "#,
+            tz = TimelineZone::sql_cte(2),
+            day = zone.day_expression("p"),
+            representative = PhotoStack::representative_clause("p"),
+            rating = PhotoRating::min_clause("p", 3),
+        );
+        let sql = format!(
+            r#"
+            WITH {tz}
+            SELECT p.id, COALESCE(p.hash, '') AS hash, p.width, p.height, p.name, p.blurhash, {stack_count} AS "stackCount"
+            FROM photos p
+            CROSS JOIN tz
+            WHERE {day} = $1 AND {representative} AND {rating}
+            ORDER BY p.sort_date DESC
+            LIMIT $4 OFFSET $5
+        "#,
+            tz = TimelineZone::sql_cte(2),
+            day = zone.day_expression("p"),
+            representative = PhotoStack::representative_clause("p"),
+            rating = PhotoRating::min_clause("p", 3),
+            stack_count = PhotoStack::count_expression("p"),
+        );
+
+        let zone_name = Value::String(zone.as_str().to_string());
+        let min_rating = Value::Int(min_rating.unwrap_or(0) as i64);
+        let total = self
+            .raw_query::<CountRow>(&count_sql, &[Value::Date(day), zone_name.clone(), min_rating.clone()])
+            .await
+            .map_err(|e| Self::query_failed("get_photos_for_day", format!("failed to count photos for day: {:?}", e)))?
+            .first()
+            .map(|row| row.total.max(0) as u64)
+            .unwrap_or(0);
+        let offset = page.saturating_sub(1).saturating_mul(page_size);
+        let items = self
+            .raw_query::<PhotoViewModel>(
+                &sql,
+                &[Value::Date(day), zone_name, min_rating, Value::Int(page_size as i64), Value::Int(offset as i64)],
+            )
+            .await
+            .map_err(|e| Self::query_failed("get_photos_for_day", format!("failed to load photos for day: {:?}", e)))?;
+
+        Ok(Page { items, total, page, page_size })
+    }
+
+    async fn count_path_prefix(&self, storage_id: Uuid, prefix: &str) -> Result<u64, PipelineError> {
+        #[derive(Deserialize)]
+        struct CountRow {
+            total: i64,
+        }
+
+        let sql = "SELECT count(*) AS total FROM photos WHERE storage_id = $1 AND left(path, length($2)) = $2";
+        let rows = self
+            .raw_query::<CountRow>(sql, &[Value::Uuid(storage_id), Value::String(prefix.to_string())])
+            .await
+            .map_err(|e| Self::query_failed("count_path_prefix", format!("failed to count photo paths: {:?}", e)))?;
+        Ok(rows.first().map(|row| row.total.max(0) as u64).unwrap_or(0))
+    }
+
+    async fn sample_path_prefix(
+        &self,
+        storage_id: Uuid,
+        prefix: &str,
+        limit: u32,
+    ) -> Result<Vec<String>, PipelineError> {
+        #[derive(Deserialize)]
+        struct PathRow {
+            path: String,
+        }
+
+        let sql = r#"
+            SELECT path
+            FROM photos
+            WHERE storage_id = $1 AND left(path, length($2)) = $2
+            ORDER BY random()
+            LIMIT $3
+        "#;
+        let params = [Value::Uuid(storage_id), Value::String(prefix.to_string()), Value::Int(limit as i64)];
+        let rows = self
+            .raw_query::<PathRow>(sql, &params)
+            .await
+            .map_err(|e| Self::query_failed("sample_path_prefix", format!("failed to sample photo paths: {:?}", e)))?;
+        Ok(rows.into_iter().map(|row| row.path).collect())
+    }
+
+    async fn rewrite_path_prefix(
+        &self,
+        storage_id: Uuid,
+        old_prefix: &str,
+        new_prefix: &str,
+    ) -> Result<u64, PipelineError> {
+        #[derive(Deserialize)]
+        struct CountRow {
+            total: i64,
+        }
+
+        let sql = r#"
+            WITH updated AS (
+                UPDATE photos
+                SET path = $3 || substr(path, length($2) + 1)
+                WHERE storage_id = $1 AND left(path, length($2)) = $2
+                RETURNING id
+            )
+            SELECT count(*) AS total FROM updated
+        "#;
+        let params =
+            [Value::Uuid(storage_id), Value::String(old_prefix.to_string()), Value::String(new_prefix.to_string())];
+        let rows = self.raw_query::<CountRow>(sql, &params).await.map_err(|e| {
+            Self::query_failed("rewrite_path_prefix", format!("failed to rewrite photo paths: {:?}", e))
+        })?;
+        Ok(rows.first().map(|row| row.total.max(0) as u64).unwrap_or(0))
+    }
+
+    async fn record_derivative_profile(
+        &self,
+        storage_id: Uuid,
+        hash: &str,
+        kind: &str,
+        profile: &str,
+    ) -> Result<(), PipelineError> {
+        let sql = r#"
+            INSERT INTO derivative_profiles (storage_id, hash, kind, profile, updated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (storage_id, hash, kind)
+            DO UPDATE SET profile = EXCLUDED.profile, updated_at = EXCLUDED.updated_at
+            RETURNING hash
+        "#;
+        let params = [
+            Value::Uuid(storage_id),
+            Value::String(hash.to_string()),
+            Value::String(kind.to_string()),
+            Value::String(profile.to_string()),
+        ];
+        self.raw_query::<serde_json::Value>(sql, &params).await.map_err(|e| {
+            Self::query_failed("record_derivative_profile", format!("failed to record derivative profile: {:?}", e))
+        })?;
+        Ok(())
+    }
+
+    async fn derivative_profiles(&self, storage_id: Uuid) -> Result<HashMap<(String, String), String>, PipelineError> {
+        #[derive(Deserialize)]
+        struct ProfileRow {
+            hash: String,
+            kind: String,
+            profile: String,
+        }
+
+        let sql = "SELECT hash, kind, profile FROM derivative_profiles WHERE storage_id = $1";
+        let rows = self.raw_query::<ProfileRow>(sql, &[Value::Uuid(storage_id)]).await.map_err(|e| {
+            Self::query_failed("derivative_profiles", format!("failed to load derivative profiles: {:?}", e))
+        })?;
+        Ok(rows.into_iter().map(|row| ((row.hash, row.kind), row.profile)).collect())
+    }
+
+    async fn find_stack_partner(&self, photo: &Photo) -> Result<Option<Photo>, PipelineError> {
+        #[derive(Deserialize)]
+        struct IdRow {
+            id: Uuid,
+        }
+
+        let Some(date_taken) = photo.date_taken else {
+            return Ok(None);
+        };
+
+        let sql = r#"
+            SELECT id
+            FROM photos
+            WHERE storage_id = $1
+              AND id <> $2
+              AND date_taken = $3
+              AND stack_id IS NULL
+              AND COALESCE(is_raw, false) <> $4
+              AND left(path, length(path) - length(name)) = $5
+              AND lower(regexp_replace(name, '\.[^.]*$', '')) = $6
+            ORDER BY created_at
+            LIMIT 5
+        "#;
+        let params = [
+            Value::Uuid(photo.storage_id),
+            Value::Uuid(photo.id),
+            Value::DateTime(date_taken),
+            Value::Bool(photo.is_raw.unwrap_or(false)),
+            Value::String(PhotoStack::directory(photo).to_string()),
+            Value::String(PhotoStack::base_name(&photo.name)),
+        ];
+        let rows = self.raw_query::<IdRow>(sql, &params).await.map_err(|e| {
+            Self::query_failed("find_stack_partner", format!("failed to look up stack partner: {:?}", e))
+        })?;
+
+        let ids = rows.into_iter().map(|row| row.id).collect::<Vec<_>>();
+        Ok(self.get_by_ids(&ids).await?.into_iter().find(|candidate| PhotoStack::is_pair(photo, candidate)))
+    }
+
+    async fn link_stack(&self, member_ids: &[Uuid], representative: Uuid) -> Result<(), PipelineError> {
+        let sql = r#"
+            UPDATE photos
+            SET stack_id = $2
+            WHERE id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+            RETURNING id
+        "#;
+        self.raw_query::<serde_json::Value>(sql, &[encode_id_list(member_ids)?, Value::Uuid(representative)])
+            .await
+            .map_err(|e| Self::query_failed("link_stack", format!("failed to link photo stack: {:?}", e)))?;
+        Ok(())
+    }
+
+    async fn stack_members(&self, photo_id: Uuid) -> Result<Vec<Photo>, PipelineError> {
+        let Some(photo) = self
+            .get(&photo_id)
+            .await
+            .map_err(|e| Self::query_failed("stack_members", format!("failed to load photo: {:?}", e)))?
+        else {
+            return Ok(Vec::new());
+        };
+        let Some(stack_id) = photo.stack_id else {
+            return Ok(vec![photo]);
+        };
 
         let query = QueryBuilder::<Photo>::new()
-            .filter("day_date", FilterOperator::In, Value::List(day_dates.into_iter().map(Value::Date).collect()))
-            .sort_desc("sort_date")
+            .filter("stack_id", FilterOperator::Eq, Value::Uuid(stack_id))
+            .sort_asc("name")
             .build();
-        log::info!("Query: {:?}", query);
-
-        let photos = self
+        let mut members = self
             .all(query)
             .await
-            .map_err(|e| PipelineError::message(&format!("failed to load photos for days: {:?}", e)))?;
-
-        let mut groups: Vec<TimelineGroup> = Vec::new();
-
-        for day in days {
-            let day_photos: Vec<Photo> =
-                photos.iter().filter(|p| p.day_date.format("%Y-%m-%d").to_string() == day).cloned().collect();
-            let length = day_photos.len();
-
-            let group = TimelineGroup {
-                title: day.clone(),
-                photos: Page::new(
-                    day_photos
-                        .into_iter()
-                        .map(|p| PhotoViewModel {
-                            id: p.id,
-                            hash: p.hash.unwrap_or_default(),
-                            width: p.width,
-                            height: p.height,
-                            name: p.name,
-                        })
-                        .collect(),
-                    length as u64,
-                    1,
-                    length as u32,
-                ),
-            };
-            groups.push(group);
+            .map_err(|e| Self::query_failed("stack_members", format!("failed to load stack members: {:?}", e)))?;
+        // The representative leads the list.
+        members.sort_by_key(|member| member.id != stack_id);
+        Ok(members)
+    }
+
+    async fn stack_counts(&self, stack_ids: &[Uuid]) -> Result<HashMap<Uuid, i64>, PipelineError> {
+        #[derive(Deserialize)]
+        struct StackCountRow {
+            stack_id: Uuid,
+            total: i64,
+        }
+
+        let sql = r#"
+            SELECT stack_id, count(*) AS total
+            FROM photos
+            WHERE stack_id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+            GROUP BY stack_id
+        "#;
+        let rows = self
+            .raw_query::<StackCountRow>(sql, &[encode_id_list(stack_ids)?])
+            .await
+            .map_err(|e| Self::query_failed("stack_counts", format!("failed to count stack members: {:?}", e)))?;
+        Ok(rows.into_iter().map(|row| (row.stack_id, row.total)).collect())
+    }
+
+    async fn unstack(&self, photo_id: Uuid) -> Result<u64, PipelineError> {
+        #[derive(Deserialize)]
+        struct CountRow {
+            total: i64,
         }
 
-        Ok(groups)
+        let sql = r#"
+            WITH updated AS (
+                UPDATE photos
+                SET stack_id = NULL
+                WHERE stack_id = $1 OR stack_id = (SELECT stack_id FROM photos WHERE id = $1)
+                RETURNING id
+            )
+            SELECT count(*) AS total FROM updated
+        "#;
+        let rows = self
+            .raw_query::<CountRow>(sql, &[Value::Uuid(photo_id)])
+            .await
+            .map_err(|e| Self::query_failed("unstack", format!("failed to clear photo stack: {:?}", e)))?;
+        Ok(rows.first().map(|row| row.total.max(0) as u64).unwrap_or(0))
     }
+
+    async fn restack_representatives(&self, preference: StackRepresentative) -> Result<u64, PipelineError> {
+        #[derive(Deserialize)]
+        struct CountRow {
+            total: i64,
+        }
+
+        let sql = r#"
+            WITH representatives AS (
+                SELECT stack_id, (array_agg(id ORDER BY (COALESCE(is_raw, false) <> $1), id))[1] AS representative
+                FROM photos
+                WHERE stack_id IS NOT NULL
+                GROUP BY stack_id
+            ),
+            updated AS (
+                UPDATE photos p
+                SET stack_id = r.representative
+                FROM representatives r
+                WHERE p.stack_id = r.stack_id AND r.representative <> r.stack_id
+                RETURNING p.id
+            )
+            SELECT count(*) AS total FROM updated
+        "#;
+        let rows = self.raw_query::<CountRow>(sql, &[Value::Bool(preference.prefers_raw())]).await.map_err(|e| {
+            Self::query_failed("restack_representatives", format!("failed to update stack representatives: {:?}", e))
+        })?;
+        Ok(rows.first().map(|row| row.total.max(0) as u64).unwrap_or(0))
+    }
+
+    // Stack members other than the representative would repeat the same shot in the feed.
+    async fn latest_public_photos(&self, limit: u32) -> Result<Vec<FeedPhoto>, PipelineError> {
+        let sql = r#"
+            SELECT
+                p.id,
+                p.hash,
+                p.name,
+                p.title,
+                p.caption,
+                p.date_taken,
+                COALESCE(p.date_imported, p.created_at, p.sort_date) AS published
+            FROM photos_public_visible p
+            WHERE p.hash IS NOT NULL
+            AND (p.stack_id IS NULL OR p.stack_id = p.id)
+            ORDER BY published DESC, p.id DESC
+            LIMIT $1
+        "#;
+
+        self.raw_query::<FeedPhoto>(sql, &[Value::Int(limit as i64)]).await.map_err(|e| {
+            Self::query_failed("latest_public_photos", format!("failed to load latest public photos: {:?}", e))
+        })
+    }
+}
+
+trait PhotoQueryErrors {
+    fn query_failed(operation: &str, message: String) -> PipelineError;
+}
+
+impl PhotoQueryErrors for Repository<Photo> {
+    fn query_failed(operation: &str, message: String) -> PipelineError {
+        RepositoryError::from_provider(operation, &message).into()
+    }
+}
+
+trait PhotoTimelineGroups {
+    fn to_timeline_group(group: PhotoGroup, per_day_limit: u32) -> TimelineGroup;
+}
+
+impl PhotoTimelineGroups for Repository<Photo> {
+    fn to_timeline_group(group: PhotoGroup, per_day_limit: u32) -> TimelineGroup {
+        TimelineGroup {
+            title: group.day,
+            photos: Page::new(group.photos_payload, group.total_count.max(0) as u64, 1, per_day_limit),
+        }
+    }
+}
+
+fn encode_id_list(ids: &[Uuid]) -> Result<Value, PipelineError> {
+    serde_json::to_string(&ids.iter().map(|id| id.to_string()).collect::<Vec<_>>())
+        .map(Value::String)
+        .map_err(|e| PipelineError::message(&format!("failed to encode ids: {:?}", e)))
 }
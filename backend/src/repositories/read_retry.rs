@@ -0,0 +1,38 @@
+use std::fmt::Debug;
+use std::future::Future;
+
+pub struct ReadRetry;
+
+impl ReadRetry {
+    const CONNECTION_ERROR_MARKERS: [&'static str; 8] = [
+        "pooltimedout",
+        "io(",
+        "connection reset",
+        "connection refused",
+        "broken pipe",
+        "unexpected eof",
+        "terminating connection",
+        "connection closed",
+    ];
+
+    pub fn is_connection_error(message: &str) -> bool {
+        let message = message.to_ascii_lowercase();
+        Self::CONNECTION_ERROR_MARKERS.iter().any(|marker| message.contains(marker))
+    }
+
+    // Only for idempotent reads: a dropped connection is retried once on a fresh pool connection.
+    pub async fn once<T, E, F, Fut>(operation: &str, run: F) -> Result<T, E>
+    where
+        E: Debug,
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        match run().await {
+            Err(error) if Self::is_connection_error(&format!("{:?}", error)) => {
+                log::warn!("Retrying {} after connection error: {:?}", operation, error);
+                run().await
+            }
+            result => result,
+        }
+    }
+}
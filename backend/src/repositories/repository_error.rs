@@ -0,0 +1,102 @@
+use crate::prelude::*;
+use std::fmt;
+
+// What went wrong in the data layer, without the SQL. Repository helpers build it where the query fails; the
+// generic providers hand sqlx errors up as text, so `from_provider` recognises the cases from the Postgres message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryError {
+    NotFound,
+    UniqueViolation { constraint: Option<String> },
+    ForeignKeyViolation { constraint: Option<String> },
+    Forbidden(&'static str),
+    Connection,
+    Other,
+}
+
+impl RepositoryError {
+    const UNIQUE_VIOLATION: &'static str = "violates unique constraint";
+    const FOREIGN_KEY_VIOLATION: &'static str = "violates foreign key constraint";
+    const ROW_NOT_FOUND: [&'static str; 2] = ["no rows returned by a query", "RowNotFound"];
+
+    // Logs the raw error once here, since only the classified variant travels further up.
+    pub fn from_provider(operation: &str, error: &impl fmt::Debug) -> Self {
+        let classified = Self::classify(&format!("{:?}", error));
+        match classified {
+            Self::Connection | Self::Other => log::error!("Repository {} failed: {:?}", operation, error),
+            _ => log::debug!("Repository {} failed: {:?}", operation, error),
+        }
+        classified
+    }
+
+    pub fn classify(message: &str) -> Self {
+        if let Some(constraint) = Self::constraint_after(message, Self::UNIQUE_VIOLATION) {
+            return Self::UniqueViolation { constraint };
+        }
+        if let Some(constraint) = Self::constraint_after(message, Self::FOREIGN_KEY_VIOLATION) {
+            return Self::ForeignKeyViolation { constraint };
+        }
+        if Self::ROW_NOT_FOUND.iter().any(|marker| message.contains(marker)) {
+            return Self::NotFound;
+        }
+        if ReadRetry::is_connection_error(message) || message.contains("PoolClosed") {
+            return Self::Connection;
+        }
+        Self::Other
+    }
+
+    // Label for the query error counter; permission refusals are not database failures.
+    pub fn metric_label(&self) -> Option<&'static str> {
+        match self {
+            Self::NotFound => Some("not_found"),
+            Self::UniqueViolation { .. } => Some("unique_violation"),
+            Self::ForeignKeyViolation { .. } => Some("foreign_key_violation"),
+            Self::Forbidden(_) => None,
+            Self::Connection => Some("connection"),
+            Self::Other => Some("other"),
+        }
+    }
+
+    // The public message names the conflicting thing in plain words; the constraint stays in the logs.
+    pub fn api_error(&self) -> ApiError {
+        match self {
+            Self::NotFound => ApiError::not_found("Not found"),
+            Self::UniqueViolation { constraint } => ApiError::conflict(match constraint.as_deref() {
+                Some("ux_tags_name_norm") => "A tag with this name already exists",
+                _ => "The item already exists",
+            }),
+            Self::ForeignKeyViolation { .. } => {
+                ApiError::conflict("The item is still referenced or refers to a missing item")
+            }
+            Self::Forbidden(message) => ApiError::forbidden(*message),
+            Self::Connection => ApiError::unavailable("Database is unavailable"),
+            Self::Other => ApiError::internal("Internal server error"),
+        }
+    }
+
+    // Messages arrive both raw and Debug-escaped, so quotes and backslashes around the name are skipped.
+    fn constraint_after(message: &str, marker: &str) -> Option<Option<String>> {
+        let start = message.find(marker)? + marker.len();
+        let name: String = message[start..]
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '"' || c == '\\')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        Some((!name.is_empty()).then_some(name))
+    }
+}
+
+impl From<RepositoryError> for PipelineError {
+    fn from(error: RepositoryError) -> Self {
+        PipelineError::message(error.api_error().message())
+    }
+}
+
+pub trait RepositoryResultExtensions<T> {
+    fn or_repository_error(self, operation: &str) -> Result<T, RepositoryError>;
+}
+
+impl<T, E: fmt::Debug> RepositoryResultExtensions<T> for Result<T, E> {
+    fn or_repository_error(self, operation: &str) -> Result<T, RepositoryError> {
+        self.map_err(|error| RepositoryError::from_provider(operation, &error))
+    }
+}
@@ -1,17 +1,14 @@
 use async_trait::async_trait;
-use sysinfo::Disks;
 use uuid::Uuid;
 
 use crate::prelude::*;
 
 #[async_trait]
 pub trait StorageRepositoryExtensions {
-    fn list_disks(&self) -> Vec<DiskInfo>;
-    fn disk_sort_key(&self, mount_point: &str) -> (u8, String);
-    fn find_disk(&self, path: &str, disks: &[DiskInfo]) -> Option<DiskInfo>;
     fn to_storage_responses(
         &self,
         locations: Vec<StorageLocation>,
+        disks: &[DiskInfo],
     ) -> Result<Vec<StorageLocationResponse>, PipelineError>;
     async fn load_storages(&self) -> Result<Vec<StorageLocation>, PipelineError>;
     async fn find_storage_by_path(&self, path: &str) -> Result<Option<StorageLocation>, PipelineError>;
@@ -24,53 +21,15 @@ pub trait StorageRepositoryExtensions {
 
 #[async_trait]
 impl StorageRepositoryExtensions for Repository<StorageLocation> {
-    fn list_disks(&self) -> Vec<DiskInfo> {
-        let disks = Disks::new_with_refreshed_list();
-
-        let mut items = disks
-            .list()
-            .iter()
-            .filter(|disk| !disk.is_removable())
-            .map(|disk| DiskInfo {
-                name: disk.name().to_string_lossy().to_string(),
-                mount_point: disk.mount_point().to_string_lossy().to_string(),
-                total_bytes: disk.total_space(),
-                available_bytes: disk.available_space(),
-            })
-            .collect::<Vec<_>>();
-
-        items.sort_by_key(|disk| self.disk_sort_key(&disk.mount_point));
-        items
-    }
-
-    fn disk_sort_key(&self, mount_point: &str) -> (u8, String) {
-        let normalized = mount_point.trim().to_ascii_lowercase();
-        let bytes = normalized.as_bytes();
-        if bytes.len() >= 2 && bytes[1] == b':' {
-            return (0, normalized);
-        }
-        (1, normalized)
-    }
-
-    fn find_disk(&self, path: &str, disks: &[DiskInfo]) -> Option<DiskInfo> {
-        let path_lower = path.to_ascii_lowercase();
-        disks
-            .iter()
-            .filter(|disk| !disk.mount_point.is_empty())
-            .filter(|disk| path_lower.starts_with(&disk.mount_point.to_ascii_lowercase()))
-            .max_by_key(|disk| disk.mount_point.len())
-            .cloned()
-    }
-
     fn to_storage_responses(
         &self,
         locations: Vec<StorageLocation>,
+        disks: &[DiskInfo],
     ) -> Result<Vec<StorageLocationResponse>, PipelineError> {
-        let disks = self.list_disks();
         let responses = locations
             .into_iter()
             .map(|location| {
-                let disk = self.find_disk(&location.path, &disks);
+                let disk = DiskInfoService::find_disk(&location.path, disks);
                 StorageLocationResponse {
                     id: location.id.to_string(),
                     label: location.label,
@@ -3,9 +3,23 @@ use std::collections::BTreeMap;
 
 #[async_trait]
 pub trait TagRepositoryExtensions {
-    async fn set_photo_tags(&self, photo_id: Uuid, tag_refs: &[TagRef]) -> Result<(), PipelineError>;
+    async fn set_photo_tags(&self, photo_id: Uuid, tag_refs: &[TagRef], is_admin: bool) -> Result<(), RepositoryError>;
 
-    async fn resolve_tag_ids(&self, refs: &[TagRef], default_visibility: i16) -> Result<Vec<Uuid>, PipelineError>;
+    async fn includes_hidden_tags(&self, tag_ids: &[Uuid]) -> Result<bool, RepositoryError>;
+
+    async fn resolve_tag_ids(&self, refs: &[TagRef], default_visibility: i16) -> Result<Vec<Uuid>, RepositoryError>;
+
+    async fn find_tag_ids(&self, refs: &[TagRef]) -> Result<Vec<Uuid>, RepositoryError>;
+
+    async fn get_tags_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Tag>, RepositoryError>;
+
+    async fn get_photo_tags(&self, photo_id: Uuid) -> Result<Vec<Tag>, RepositoryError>;
+
+    async fn admin_only_tag_names(&self) -> Result<HashSet<String>, RepositoryError>;
+
+    async fn is_hash_admin_only(&self, hash: &str) -> Result<bool, RepositoryError>;
+
+    async fn set_tag_visibility(&self, refs: &[TagRef], visibility: i16) -> Result<Vec<Uuid>, RepositoryError>;
 
     fn normalize_tag_name(&self, raw: &str) -> Option<(String, String)>;
 
@@ -14,59 +28,84 @@ pub trait TagRepositoryExtensions {
 
 #[async_trait]
 impl TagRepositoryExtensions for Repository<Tag> {
-    async fn set_photo_tags(&self, photo_id: Uuid, tag_refs: &[TagRef]) -> Result<(), PipelineError> {
-        let ids = self.resolve_tag_ids(tag_refs, 0).await?;
-
-        if ids.is_empty() {
-            self.raw_query::<serde_json::Value>("DELETE FROM photo_tags WHERE photo_id = $1", &[Value::Uuid(photo_id)])
-                .await
-                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
-            return Ok(());
-        }
-
-        let mut params = Vec::with_capacity(ids.len() + 1);
-        params.push(Value::Uuid(photo_id));
-        for id in &ids {
-            params.push(Value::Uuid(*id));
+    async fn set_photo_tags(&self, photo_id: Uuid, tag_refs: &[TagRef], is_admin: bool) -> Result<(), RepositoryError> {
+        let ids = self.resolve_tag_ids(tag_refs, Tag::VISIBILITY_PUBLIC).await?;
+        if !is_admin && self.includes_hidden_tags(&ids).await? {
+            return Err(RepositoryError::Forbidden(Tag::HIDDEN_TAG_FORBIDDEN));
         }
 
-        let values = (0..ids.len()).map(|idx| format!("(${})", idx + 2)).collect::<Vec<_>>().join(", ");
-
-        let sql = format!(
-            r#"
-            WITH deleted AS (
-                DELETE FROM photo_tags WHERE photo_id = $1
+        // Non-admins cannot see admin-only tags, so a replace leaves those attached instead of refusing.
+        let sql = r#"
+            WITH removed AS (
+                DELETE FROM photo_tags
+                WHERE photo_id = $1
+                  AND tag_id NOT IN (SELECT value::uuid FROM jsonb_array_elements_text($2::jsonb))
+                  AND ($3 OR tag_id NOT IN (SELECT id FROM tags WHERE visibility = $4))
             )
             INSERT INTO photo_tags (photo_id, tag_id)
-            SELECT $1, v.tag_id
-            FROM (VALUES {values}) AS v(tag_id)
+            SELECT $1, value::uuid
+            FROM jsonb_array_elements_text($2::jsonb)
             ON CONFLICT (photo_id, tag_id) DO NOTHING
-            "#
-        );
+        "#;
 
-        self.raw_query::<serde_json::Value>(&sql, &params)
-            .await
-            .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        self.raw_query::<serde_json::Value>(
+            sql,
+            &[Value::Uuid(photo_id), encode_ids(&ids)?, Value::Bool(is_admin), Value::I16(Tag::VISIBILITY_HIDDEN)],
+        )
+        .await
+        .or_repository_error("set_photo_tags")?;
 
         Ok(())
     }
 
-    async fn resolve_tag_ids(&self, refs: &[TagRef], default_visibility: i16) -> Result<Vec<Uuid>, PipelineError> {
+    async fn includes_hidden_tags(&self, tag_ids: &[Uuid]) -> Result<bool, RepositoryError> {
+        #[derive(Deserialize)]
+        struct ChangeRow {
+            blocked: bool,
+        }
+
+        if tag_ids.is_empty() {
+            return Ok(false);
+        }
+
+        let sql = r#"
+            SELECT EXISTS (
+                SELECT 1 FROM tags t
+                WHERE t.visibility = $2
+                  AND t.id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+            ) AS blocked
+        "#;
+
+        let rows = self
+            .raw_query::<ChangeRow>(sql, &[encode_ids(tag_ids)?, Value::I16(Tag::VISIBILITY_HIDDEN)])
+            .await
+            .or_repository_error("includes_hidden_tags")?;
+        Ok(rows.first().map(|row| row.blocked).unwrap_or(false))
+    }
+
+    async fn resolve_tag_ids(&self, refs: &[TagRef], default_visibility: i16) -> Result<Vec<Uuid>, RepositoryError> {
         #[derive(Deserialize)]
         struct TagIdRow {
             id: Uuid,
         }
 
-        let mut ids = Vec::<Uuid>::new();
+        let mut requested_ids = Vec::<Uuid>::new();
         let mut names = Vec::<String>::new();
 
         for item in refs {
             match item {
-                TagRef::Id(id) => ids.push(*id),
+                TagRef::Id(id) => requested_ids.push(*id),
                 TagRef::Name(name) => names.push(name.clone()),
             }
         }
 
+        requested_ids.sort_unstable();
+        requested_ids.dedup();
+        let mut ids = self.get_tags_by_ids(&requested_ids).await?.into_iter().map(|tag| tag.id).collect::<Vec<_>>();
+        if ids.len() < requested_ids.len() {
+            log::warn!("Ignoring {} unknown tag id(s)", requested_ids.len() - ids.len());
+        }
+
         let normalized = self.normalize_tag_names(&names);
         let sql = r#"
             INSERT INTO tags (name, name_norm, visibility, created_at)
@@ -83,7 +122,7 @@ impl TagRepositoryExtensions for Repository<Tag> {
                     &[Value::String(name), Value::String(name_norm), Value::I16(default_visibility)],
                 )
                 .await
-                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+                .or_repository_error("resolve_tag_ids")?;
 
             if let Some(row) = rows.first() {
                 ids.push(row.id);
@@ -95,6 +134,131 @@ impl TagRepositoryExtensions for Repository<Tag> {
         Ok(ids)
     }
 
+    async fn find_tag_ids(&self, refs: &[TagRef]) -> Result<Vec<Uuid>, RepositoryError> {
+        #[derive(Deserialize)]
+        struct TagIdRow {
+            id: Uuid,
+        }
+
+        let mut requested_ids = Vec::<String>::new();
+        let mut names = Vec::<String>::new();
+        for item in refs {
+            match item {
+                TagRef::Id(id) => requested_ids.push(id.to_string()),
+                TagRef::Name(name) => names.push(name.clone()),
+            }
+        }
+        let name_norms = self.normalize_tag_names(&names).into_iter().map(|(_, norm)| norm).collect::<Vec<_>>();
+        if requested_ids.is_empty() && name_norms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let encode =
+            |values: &[String]| serde_json::to_string(values).map(Value::String).or_repository_error("encode tag refs");
+        let sql = r#"
+            SELECT t.id
+            FROM tags t
+            WHERE t.id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+               OR t.name_norm IN (SELECT value FROM jsonb_array_elements_text($2::jsonb))
+        "#;
+
+        let rows = self
+            .raw_query::<TagIdRow>(sql, &[encode(&requested_ids)?, encode(&name_norms)?])
+            .await
+            .or_repository_error("find_tag_ids")?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
+    async fn get_tags_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Tag>, RepositoryError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = r#"
+            SELECT t.id, t.name, t.name_norm, t.visibility, t.created_at
+            FROM tags t
+            WHERE t.id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+            ORDER BY t.name
+        "#;
+
+        self.raw_query::<Tag>(sql, &[encode_ids(ids)?]).await.or_repository_error("get_tags_by_ids")
+    }
+
+    async fn get_photo_tags(&self, photo_id: Uuid) -> Result<Vec<Tag>, RepositoryError> {
+        let sql = r#"
+            SELECT t.id, t.name, t.name_norm, t.visibility, t.created_at
+            FROM photo_tags pt
+            JOIN tags t ON t.id = pt.tag_id
+            WHERE pt.photo_id = $1
+            ORDER BY t.name
+        "#;
+
+        self.raw_query::<Tag>(sql, &[Value::Uuid(photo_id)]).await.or_repository_error("get_photo_tags")
+    }
+
+    async fn admin_only_tag_names(&self) -> Result<HashSet<String>, RepositoryError> {
+        let sql = r#"
+            SELECT t.id, t.name, t.name_norm, t.visibility, t.created_at
+            FROM tags t
+            WHERE t.visibility = $1
+        "#;
+
+        let tags = self
+            .raw_query::<Tag>(sql, &[Value::I16(Tag::VISIBILITY_HIDDEN)])
+            .await
+            .or_repository_error("admin_only_tag_names")?;
+        Ok(tags.into_iter().map(|tag| tag.name_norm).collect())
+    }
+
+    async fn is_hash_admin_only(&self, hash: &str) -> Result<bool, RepositoryError> {
+        #[derive(Deserialize)]
+        struct HiddenRow {
+            hidden: bool,
+        }
+
+        let sql = r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM photos p
+                JOIN photo_tags pt ON pt.photo_id = p.id
+                JOIN tags t ON t.id = pt.tag_id
+                WHERE p.hash = $1
+                  AND t.visibility = $2
+            ) AS hidden
+        "#;
+
+        let rows = self
+            .raw_query::<HiddenRow>(sql, &[Value::String(hash.to_string()), Value::I16(Tag::VISIBILITY_HIDDEN)])
+            .await
+            .or_repository_error("is_hash_admin_only")?;
+        Ok(rows.first().map(|row| row.hidden).unwrap_or(false))
+    }
+
+    async fn set_tag_visibility(&self, refs: &[TagRef], visibility: i16) -> Result<Vec<Uuid>, RepositoryError> {
+        #[derive(Deserialize)]
+        struct TagIdRow {
+            id: Uuid,
+        }
+
+        let ids = self.find_tag_ids(refs).await?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sql = r#"
+            UPDATE tags
+            SET visibility = $2
+            WHERE id IN (SELECT value::uuid FROM jsonb_array_elements_text($1::jsonb))
+            RETURNING id
+        "#;
+
+        let rows = self
+            .raw_query::<TagIdRow>(sql, &[encode_ids(&ids)?, Value::I16(visibility)])
+            .await
+            .or_repository_error("set_tag_visibility")?;
+        Ok(rows.into_iter().map(|row| row.id).collect())
+    }
+
     fn normalize_tag_name(&self, raw: &str) -> Option<(String, String)> {
         let name = raw.trim();
         if name.is_empty() {
@@ -113,3 +277,9 @@ impl TagRepositoryExtensions for Repository<Tag> {
         dedup.into_iter().map(|(norm, name)| (name, norm)).collect()
     }
 }
+
+fn encode_ids(ids: &[Uuid]) -> Result<Value, RepositoryError> {
+    serde_json::to_string(&ids.iter().map(|id| id.to_string()).collect::<Vec<_>>())
+        .map(Value::String)
+        .or_repository_error("encode tag ids")
+}
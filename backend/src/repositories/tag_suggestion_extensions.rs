@@ -0,0 +1,106 @@
+use crate::prelude::*;
+
+#[async_trait]
+pub trait TagSuggestionRepositoryExtensions {
+    async fn tag_suggestions(&self, photo_id: Uuid) -> Result<Vec<PhotoTagSuggestion>, PipelineError>;
+
+    async fn save_tag_suggestions(
+        &self,
+        photo_id: Uuid,
+        analyzer: &str,
+        suggestions: &[TagSuggestion],
+    ) -> Result<(), PipelineError>;
+
+    async fn remove_tag_suggestions(&self, photo_id: Uuid, names: &[String]) -> Result<(), PipelineError>;
+}
+
+#[async_trait]
+impl TagSuggestionRepositoryExtensions for Repository<Photo> {
+    // Suggestions for tags the photo picked up after the analysis ran are no longer useful.
+    async fn tag_suggestions(&self, photo_id: Uuid) -> Result<Vec<PhotoTagSuggestion>, PipelineError> {
+        let sql = r#"
+            SELECT s.id, s.photo_id, s.name, s.name_norm, s.confidence, s.analyzer, s.created_at
+            FROM photo_tag_suggestions s
+            WHERE s.photo_id = $1
+            AND NOT EXISTS (
+                SELECT 1
+                FROM photo_tags pt
+                JOIN tags t ON t.id = pt.tag_id
+                WHERE pt.photo_id = s.photo_id AND t.name_norm = s.name_norm
+            )
+            ORDER BY s.confidence DESC, s.name
+        "#;
+
+        self.raw_query::<PhotoTagSuggestion>(sql, &[Value::Uuid(photo_id)])
+            .await
+            .map_err(|e| PipelineError::message(&format!("{:?}", e)))
+    }
+
+    // A re-analysis keeps the higher confidence; tags the photo already carries are not suggested.
+    async fn save_tag_suggestions(
+        &self,
+        photo_id: Uuid,
+        analyzer: &str,
+        suggestions: &[TagSuggestion],
+    ) -> Result<(), PipelineError> {
+        if suggestions.is_empty() {
+            return Ok(());
+        }
+
+        let rows = suggestions
+            .iter()
+            .map(|suggestion| {
+                json!({
+                    "name": suggestion.name,
+                    "name_norm": suggestion.name.to_lowercase(),
+                    "confidence": suggestion.confidence,
+                })
+            })
+            .collect::<Vec<_>>();
+        let rows_json = serde_json::to_string(&rows)
+            .map_err(|e| PipelineError::message(&format!("failed to encode tag suggestions: {:?}", e)))?;
+
+        let sql = r#"
+            INSERT INTO photo_tag_suggestions (photo_id, name, name_norm, confidence, analyzer)
+            SELECT $1, s.name, s.name_norm, s.confidence, $3
+            FROM jsonb_to_recordset($2::jsonb) AS s(name TEXT, name_norm TEXT, confidence REAL)
+            WHERE NOT EXISTS (
+                SELECT 1
+                FROM photo_tags pt
+                JOIN tags t ON t.id = pt.tag_id
+                WHERE pt.photo_id = $1 AND t.name_norm = s.name_norm
+            )
+            ON CONFLICT (photo_id, name_norm) DO UPDATE
+            SET confidence = GREATEST(photo_tag_suggestions.confidence, EXCLUDED.confidence),
+                analyzer = EXCLUDED.analyzer
+        "#;
+
+        self.raw_query::<serde_json::Value>(
+            sql,
+            &[Value::Uuid(photo_id), Value::String(rows_json), Value::String(analyzer.to_string())],
+        )
+        .await
+        .map_err(|e| PipelineError::message(&format!("failed to save tag suggestions: {:?}", e)))?;
+        Ok(())
+    }
+
+    async fn remove_tag_suggestions(&self, photo_id: Uuid, names: &[String]) -> Result<(), PipelineError> {
+        let name_norms = names.iter().map(|name| name.trim().to_lowercase()).collect::<Vec<_>>();
+        if name_norms.is_empty() {
+            return Ok(());
+        }
+        let names_json = serde_json::to_string(&name_norms)
+            .map_err(|e| PipelineError::message(&format!("failed to encode tag suggestions: {:?}", e)))?;
+
+        let sql = r#"
+            DELETE FROM photo_tag_suggestions
+            WHERE photo_id = $1
+            AND name_norm IN (SELECT jsonb_array_elements_text($2::jsonb))
+        "#;
+
+        self.raw_query::<serde_json::Value>(sql, &[Value::Uuid(photo_id), Value::String(names_json)])
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to remove tag suggestions: {:?}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::prelude::*;
+
+// Each call is a single conditional UPDATE, so parallel sign-in attempts on one account can neither share
+// a failure count nor spend the same code twice.
+#[async_trait]
+pub trait TwoFactorExtensions {
+    async fn record_failed_two_factor_code(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+        max_failed: i32,
+        lockout_seconds: i64,
+    ) -> Result<Option<DateTime<Utc>>, PipelineError>;
+    async fn claim_two_factor_step(&self, user_id: Uuid, step: i64, now: DateTime<Utc>) -> Result<bool, PipelineError>;
+    async fn consume_two_factor_backup_code(
+        &self,
+        user_id: Uuid,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool, PipelineError>;
+}
+
+#[async_trait]
+impl TwoFactorExtensions for Repository<TwoFactor> {
+    // Returns the lockout end when this miss reached the limit.
+    async fn record_failed_two_factor_code(
+        &self,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+        max_failed: i32,
+        lockout_seconds: i64,
+    ) -> Result<Option<DateTime<Utc>>, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct LockRow {
+                locked_until: Option<DateTime<Utc>>,
+            }
+
+            let sql = r#"
+                UPDATE two_factor
+                SET failed_codes = CASE WHEN failed_codes + 1 >= $2 THEN 0 ELSE failed_codes + 1 END,
+                    locked_until = CASE WHEN failed_codes + 1 >= $2 THEN $3 ELSE locked_until END
+                WHERE user_id = $1
+                RETURNING locked_until
+            "#;
+            let locked_until = now + Duration::seconds(lockout_seconds);
+            let rows = self
+                .raw_query::<LockRow>(
+                    sql,
+                    &[Value::Uuid(user_id), Value::Int(max_failed as i64), Value::DateTime(locked_until)],
+                )
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            return Ok(rows.into_iter().next().and_then(|row| row.locked_until).filter(|until| *until > now));
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let Some(mut record) = self.get(&user_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+            else {
+                return Ok(None);
+            };
+            let locked_until = record.record_failed_code(now, max_failed, lockout_seconds);
+            self.update(record).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            Ok(locked_until)
+        }
+    }
+
+    // Only one request can move `last_used_step` past a given step, which is what makes a TOTP code single use.
+    async fn claim_two_factor_step(&self, user_id: Uuid, step: i64, now: DateTime<Utc>) -> Result<bool, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            let sql = r#"
+                UPDATE two_factor
+                SET last_used_step = $2, failed_codes = 0, locked_until = NULL
+                WHERE user_id = $1
+                  AND enabled
+                  AND last_used_step < $2
+                  AND (locked_until IS NULL OR locked_until <= $3)
+                RETURNING user_id
+            "#;
+            let rows = self
+                .raw_query::<serde_json::Value>(sql, &[Value::Uuid(user_id), Value::Int(step), Value::DateTime(now)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            return Ok(!rows.is_empty());
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let Some(mut record) = self.get(&user_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+            else {
+                return Ok(false);
+            };
+            if !record.enabled || step <= record.last_used_step || record.locked_until.is_some_and(|until| until > now)
+            {
+                return Ok(false);
+            }
+            record.last_used_step = step;
+            record.failed_codes = 0;
+            record.locked_until = None;
+            self.update(record).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            Ok(true)
+        }
+    }
+
+    // The hash is removed in the same statement that matches it, so a replayed code updates no row.
+    async fn consume_two_factor_backup_code(
+        &self,
+        user_id: Uuid,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            let sql = r#"
+                UPDATE two_factor
+                SET backup_codes = array_to_string(array_remove(string_to_array(backup_codes, ','), $2), ','),
+                    failed_codes = 0,
+                    locked_until = NULL
+                WHERE user_id = $1
+                  AND enabled
+                  AND $2 = ANY(string_to_array(backup_codes, ','))
+                  AND (locked_until IS NULL OR locked_until <= $3)
+                RETURNING user_id
+            "#;
+            let rows = self
+                .raw_query::<serde_json::Value>(
+                    sql,
+                    &[Value::Uuid(user_id), Value::String(BackupCode::hash(code)), Value::DateTime(now)],
+                )
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            return Ok(!rows.is_empty());
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let Some(mut record) = self.get(&user_id).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+            else {
+                return Ok(false);
+            };
+            if !record.enabled || record.locked_until.is_some_and(|until| until > now) {
+                return Ok(false);
+            }
+            if !record.consume_backup_code(code) {
+                return Ok(false);
+            }
+            record.failed_codes = 0;
+            record.locked_until = None;
+            self.update(record).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+            Ok(true)
+        }
+    }
+}
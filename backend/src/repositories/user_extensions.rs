@@ -0,0 +1,109 @@
+use crate::prelude::*;
+
+#[async_trait]
+pub trait UserRepositoryExtensions {
+    async fn search_users(
+        &self,
+        search: Option<&str>,
+        role: Option<&str>,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<User>, PipelineError>;
+}
+
+#[async_trait]
+impl UserRepositoryExtensions for Repository<User> {
+    // Newest accounts first; only the requested page is loaded.
+    async fn search_users(
+        &self,
+        search: Option<&str>,
+        role: Option<&str>,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<User>, PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct CountRow {
+                total: i64,
+            }
+
+            let matched = r#"
+                FROM users u
+                WHERE ($1 = '' OR u.email ILIKE $1 OR u.display_name ILIKE $1)
+                AND (
+                    $2 = ''
+                    OR EXISTS (
+                        SELECT 1
+                        FROM unnest(string_to_array(COALESCE(u.roles, ''), ',')) AS r(role)
+                        WHERE lower(trim(r.role)) = lower($2)
+                    )
+                )
+            "#;
+            let count_sql = format!("SELECT count(*) AS total {matched}");
+            let page_sql = format!("SELECT u.* {matched} ORDER BY u.created_at DESC, u.id DESC LIMIT $3 OFFSET $4");
+
+            let pattern = search
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| format!("%{}%", BrowseOptions::escape_like(value)))
+                .unwrap_or_default();
+            let role = role.map(str::trim).unwrap_or_default().to_string();
+            let offset = page.saturating_sub(1).saturating_mul(page_size);
+
+            let total = self
+                .raw_query::<CountRow>(&count_sql, &[Value::String(pattern.clone()), Value::String(role.clone())])
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .first()
+                .map(|row| row.total)
+                .unwrap_or(0);
+            let items = self
+                .raw_query::<User>(
+                    &page_sql,
+                    &[
+                        Value::String(pattern),
+                        Value::String(role),
+                        Value::Int(page_size as i64),
+                        Value::Int(offset as i64),
+                    ],
+                )
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+
+            return Ok(Page { items, total: total.max(0) as u64, page, page_size });
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            let search = search.map(str::trim).filter(|value| !value.is_empty()).map(str::to_lowercase);
+            let role = role.map(str::trim).filter(|value| !value.is_empty());
+            let mut items: Vec<User> = self
+                .all(QueryBuilder::<User>::new().build())
+                .await
+                .map_err(|e| PipelineError::message(&format!("{:?}", e)))?
+                .into_iter()
+                .filter(|user| {
+                    search.as_ref().is_none_or(|search| {
+                        user.email.to_lowercase().contains(search) || user.display_name.to_lowercase().contains(search)
+                    })
+                })
+                .filter(|user| {
+                    role.is_none_or(|role| {
+                        user.roles
+                            .as_deref()
+                            .unwrap_or_default()
+                            .split(',')
+                            .any(|value| value.trim().eq_ignore_ascii_case(role))
+                    })
+                })
+                .collect();
+            items.sort_by(|left, right| right.created_at.cmp(&left.created_at).then(right.id.cmp(&left.id)));
+
+            let total = items.len() as u64;
+            let offset = page.saturating_sub(1).saturating_mul(page_size) as usize;
+            let items = items.into_iter().skip(offset).take(page_size as usize).collect();
+            Ok(Page { items, total, page, page_size })
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::prelude::*;
+
+#[async_trait]
+pub trait UserSettingsExtensions {
+    async fn get_by_user_ids(&self, user_ids: &[Uuid]) -> Result<HashMap<Uuid, UserSettings>, PipelineError>;
+}
+
+#[async_trait]
+impl UserSettingsExtensions for Repository<UserSettings> {
+    async fn get_by_user_ids(&self, user_ids: &[Uuid]) -> Result<HashMap<Uuid, UserSettings>, PipelineError> {
+        let mut unique_ids: Vec<Uuid> = Vec::new();
+        for user_id in user_ids {
+            if !unique_ids.contains(user_id) {
+                unique_ids.push(*user_id);
+            }
+        }
+        if unique_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let query = QueryBuilder::<UserSettings>::new()
+            .filter("user_id", FilterOperator::In, Value::List(unique_ids.into_iter().map(Value::Uuid).collect()))
+            .build();
+
+        let settings = self.all(query).await.map_err(|e| PipelineError::message(&format!("{:?}", e)))?;
+        Ok(settings.into_iter().map(|item| (item.user_id, item)).collect())
+    }
+}
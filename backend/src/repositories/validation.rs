@@ -1,15 +1,15 @@
 use crate::prelude::*;
 
 pub trait StringValidations {
-    fn should_not_empty(self, field_name: &str) -> Result<Self, PipelineError>
+    fn should_not_empty(self, field_name: &str) -> Result<Self, ApiError>
     where
         Self: Sized;
 }
 
 impl<'a> StringValidations for &'a str {
-    fn should_not_empty(self, field_name: &str) -> Result<Self, PipelineError> {
+    fn should_not_empty(self, field_name: &str) -> Result<Self, ApiError> {
         if self.trim().is_empty() {
-            Err(PipelineError::message(&format!("{} should not be empty", field_name)))
+            Err(ApiError::bad_request(format!("{} should not be empty", field_name)))
         } else {
             Ok(self)
         }
@@ -17,11 +17,53 @@ impl<'a> StringValidations for &'a str {
 }
 
 impl StringValidations for String {
-    fn should_not_empty(self, field_name: &str) -> Result<Self, PipelineError> {
+    fn should_not_empty(self, field_name: &str) -> Result<Self, ApiError> {
         if self.trim().is_empty() {
-            Err(PipelineError::message(&format!("{} should not be empty", field_name)))
+            Err(ApiError::bad_request(format!("{} should not be empty", field_name)))
         } else {
             Ok(self)
         }
     }
 }
+
+pub trait Validate {
+    fn validate(&self) -> Result<(), Vec<FieldError>>;
+}
+
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn check(&mut self, valid: bool, field: &str, message: impl Into<String>) -> &mut Self {
+        if !valid {
+            self.errors.push(FieldError::new(field, message));
+        }
+        self
+    }
+
+    pub fn required(&mut self, field: &str, label: &str, value: &str) -> &mut Self {
+        self.check(!value.trim().is_empty(), field, format!("{} should not be empty", label))
+    }
+
+    pub fn max_chars(&mut self, field: &str, label: &str, value: &str, max: usize) -> &mut Self {
+        self.check(value.trim().chars().count() <= max, field, format!("{} must be {} characters or fewer", label, max))
+    }
+
+    pub fn template(&mut self, field: &str, value: &str) -> &mut Self {
+        let message = CategoryTemplateParser::new(value).err().map(|err| err.to_string());
+        match message {
+            Some(message) => self.check(false, field, message),
+            None => self,
+        }
+    }
+
+    pub fn finish(&mut self) -> Result<(), Vec<FieldError>> {
+        if self.errors.is_empty() { Ok(()) } else { Err(std::mem::take(&mut self.errors)) }
+    }
+}
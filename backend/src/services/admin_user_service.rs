@@ -1,20 +1,46 @@
 use crate::prelude::*;
 
+#[derive(Debug, Clone, Default)]
+pub struct AdminUserFilter {
+    pub search: Option<String>,
+    pub role: Option<String>,
+}
+
 pub struct AdminUserService {
     repo: Arc<Repository<User>>,
+    settings_repo: Arc<Repository<UserSettings>>,
+    photo_comments: Arc<Repository<PhotoComment>>,
+    album_comments: Arc<Repository<AlbumComment>>,
 }
 
 impl AdminUserService {
-    pub fn new(repo: Arc<Repository<User>>) -> Self {
-        Self { repo }
+    pub fn new(
+        repo: Arc<Repository<User>>,
+        settings_repo: Arc<Repository<UserSettings>>,
+        photo_comments: Arc<Repository<PhotoComment>>,
+        album_comments: Arc<Repository<AlbumComment>>,
+    ) -> Self {
+        Self { repo, settings_repo, photo_comments, album_comments }
     }
 
-    pub async fn list_users(&self) -> Result<Vec<AdminUserDto>, PipelineError> {
-        let page = self.repo.query(Query::<User>::new()).await.map_err(|_| PipelineError::message("data error"))?;
+    pub async fn list_users(
+        &self,
+        filter: AdminUserFilter,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<AdminUserDto>, PipelineError> {
+        let users = self.repo.search_users(filter.search.as_deref(), filter.role.as_deref(), page, page_size).await?;
+        let items = self.with_activity(users.items).await?;
+
+        Ok(Page { items, total: users.total, page, page_size })
+    }
 
-        let mut users = page.items;
-        users.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        Ok(users.into_iter().map(AdminUserDto::from).collect())
+    pub async fn get_user(&self, user_id: Uuid) -> Result<Option<AdminUserDto>, PipelineError> {
+        let user = self.repo.get(&user_id).await.map_err(|_| PipelineError::message("data error"))?;
+        match user {
+            Some(user) => Ok(self.with_activity(vec![user]).await?.pop()),
+            None => Ok(None),
+        }
     }
 
     pub async fn update_roles(
@@ -49,6 +75,128 @@ impl AdminUserService {
         Ok(AdminUserDto::from(updated))
     }
 
+    pub async fn delete_user(
+        &self,
+        user_id: Uuid,
+        delete_comments: bool,
+    ) -> Result<AdminUserDeletionDto, PipelineError> {
+        let user = self
+            .repo
+            .get(&user_id)
+            .await
+            .map_err(|_| PipelineError::message("data error"))?
+            .ok_or_else(|| PipelineError::message("user not found"))?;
+
+        let is_admin = Self::parse_roles(user.roles.as_deref()).iter().any(|role| role == "admin");
+        if is_admin && !self.has_other_admin(user_id).await? {
+            return Err(PipelineError::message("Cannot delete the last admin user"));
+        }
+
+        let photo_comments = self.detach_photo_comments(user_id, delete_comments).await?;
+        let album_comments = self.detach_album_comments(user_id, delete_comments).await?;
+        let detached = photo_comments + album_comments;
+
+        self.settings_repo
+            .delete(&user_id)
+            .await
+            .map_err(|_| PipelineError::message("failed to delete user settings"))?;
+        self.repo.delete(&user_id).await.map_err(|_| PipelineError::message("failed to delete user"))?;
+
+        Ok(AdminUserDeletionDto {
+            user_id,
+            comments_deleted: if delete_comments { detached } else { 0 },
+            comments_anonymized: if delete_comments { 0 } else { detached },
+        })
+    }
+
+    async fn detach_photo_comments(&self, user_id: Uuid, delete_comments: bool) -> Result<u32, PipelineError> {
+        let query =
+            QueryBuilder::<PhotoComment>::new().filter("user_id", FilterOperator::Eq, Value::Uuid(user_id)).build();
+        let comments = self.photo_comments.all(query).await.map_err(|_| PipelineError::message("data error"))?;
+
+        let mut detached = 0;
+        for mut comment in comments {
+            if delete_comments {
+                self.photo_comments
+                    .delete(&comment.id)
+                    .await
+                    .map_err(|_| PipelineError::message("failed to delete comment"))?;
+            } else {
+                comment.user_id = None;
+                self.photo_comments
+                    .update(comment)
+                    .await
+                    .map_err(|_| PipelineError::message("failed to anonymize comment"))?;
+            }
+            detached += 1;
+        }
+        Ok(detached)
+    }
+
+    async fn detach_album_comments(&self, user_id: Uuid, delete_comments: bool) -> Result<u32, PipelineError> {
+        let query =
+            QueryBuilder::<AlbumComment>::new().filter("user_id", FilterOperator::Eq, Value::Uuid(user_id)).build();
+        let comments = self.album_comments.all(query).await.map_err(|_| PipelineError::message("data error"))?;
+
+        let mut detached = 0;
+        for mut comment in comments {
+            if delete_comments {
+                self.album_comments
+                    .delete(&comment.id)
+                    .await
+                    .map_err(|_| PipelineError::message("failed to delete comment"))?;
+            } else {
+                comment.user_id = None;
+                self.album_comments
+                    .update(comment)
+                    .await
+                    .map_err(|_| PipelineError::message("failed to anonymize comment"))?;
+            }
+            detached += 1;
+        }
+        Ok(detached)
+    }
+
+    async fn with_activity(&self, users: Vec<User>) -> Result<Vec<AdminUserDto>, PipelineError> {
+        if users.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<Value> = users.iter().map(|user| Value::Uuid(user.id)).collect();
+        let photo_comments = self
+            .photo_comments
+            .all(
+                QueryBuilder::<PhotoComment>::new()
+                    .filter("user_id", FilterOperator::In, Value::List(ids.clone()))
+                    .build(),
+            )
+            .await
+            .map_err(|_| PipelineError::message("data error"))?;
+        let album_comments = self
+            .album_comments
+            .all(QueryBuilder::<AlbumComment>::new().filter("user_id", FilterOperator::In, Value::List(ids)).build())
+            .await
+            .map_err(|_| PipelineError::message("data error"))?;
+
+        Ok(users
+            .into_iter()
+            .map(|user| {
+                let user_id = Some(user.id);
+                let photo = photo_comments.iter().filter(|comment| comment.user_id == user_id);
+                let album = album_comments.iter().filter(|comment| comment.user_id == user_id);
+
+                let mut dto = AdminUserDto::from(user);
+                dto.photo_comment_count = photo.clone().count() as u64;
+                dto.album_comment_count = album.clone().count() as u64;
+                dto.last_comment_at = photo
+                    .filter_map(|comment| comment.created_at)
+                    .chain(album.filter_map(|comment| comment.created_at))
+                    .max();
+                dto
+            })
+            .collect())
+    }
+
     async fn has_other_admin(&self, user_id: Uuid) -> Result<bool, PipelineError> {
         let page = self.repo.query(Query::<User>::new()).await.map_err(|_| PipelineError::message("data error"))?;
 
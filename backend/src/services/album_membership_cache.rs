@@ -0,0 +1,74 @@
+use crate::prelude::*;
+use std::sync::RwLock;
+
+// Identifies one state of an album's membership. Edits through the album endpoints bump the version;
+// imports and photo deletions change the member count without touching it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct AlbumMembershipStamp {
+    pub version: i32,
+    pub members: i64,
+}
+
+// Ordered photo ids per album, so paging a large album reads one slice instead of re-sorting the
+// whole membership on every request. Viewer pages still go through SQL because hidden tags differ
+// per user.
+#[derive(Clone, Default)]
+pub struct AlbumMembershipCache {
+    entries: Arc<RwLock<HashMap<Uuid, (AlbumMembershipStamp, Arc<Vec<Uuid>>)>>>,
+}
+
+impl AlbumMembershipCache {
+    const MAX_ALBUMS: usize = 64;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, album_id: Uuid, stamp: AlbumMembershipStamp) -> Option<Arc<Vec<Uuid>>> {
+        let entries = self.entries.read().ok()?;
+        entries.get(&album_id).filter(|(cached, _)| *cached == stamp).map(|(_, ids)| Arc::clone(ids))
+    }
+
+    pub fn put(&self, album_id: Uuid, stamp: AlbumMembershipStamp, ids: Vec<Uuid>) -> Arc<Vec<Uuid>> {
+        let ids = Arc::new(ids);
+        if let Ok(mut entries) = self.entries.write() {
+            if entries.len() >= Self::MAX_ALBUMS && !entries.contains_key(&album_id) {
+                entries.clear();
+            }
+            entries.insert(album_id, (stamp, Arc::clone(&ids)));
+        }
+        ids
+    }
+
+    pub fn invalidate(&self, album_id: Uuid) {
+        if let Ok(mut entries) = self.entries.write() {
+            entries.remove(&album_id);
+        }
+    }
+
+    pub fn page_ids(ids: &[Uuid], page: u32, page_size: u32) -> &[Uuid] {
+        let offset = (page.saturating_sub(1) as usize).saturating_mul(page_size as usize).min(ids.len());
+        let end = offset.saturating_add(page_size as usize).min(ids.len());
+        &ids[offset..end]
+    }
+
+    pub async fn photos_page(
+        &self,
+        albums: &Repository<Album>,
+        photos: &Repository<Photo>,
+        album_id: Uuid,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<Photo>, PipelineError> {
+        let Some(stamp) = albums.album_membership_stamp(album_id).await? else {
+            return Ok(Page { items: Vec::new(), total: 0, page, page_size });
+        };
+        let ids = match self.get(album_id, stamp) {
+            Some(ids) => ids,
+            None => self.put(album_id, stamp, photos.album_member_ids(album_id).await?),
+        };
+
+        let items = photos.get_by_ids(Self::page_ids(&ids, page, page_size)).await?;
+        Ok(Page { items, total: ids.len() as u64, page, page_size })
+    }
+}
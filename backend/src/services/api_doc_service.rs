@@ -0,0 +1,571 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiAnnotation {
+    pub summary: Option<String>,
+    pub request: Option<(String, JsonValue)>,
+    pub response: Option<(String, JsonValue)>,
+}
+
+impl ApiAnnotation {
+    pub fn new(summary: impl Into<String>) -> Self {
+        Self { summary: Some(summary.into()), ..Self::default() }
+    }
+
+    pub fn with_request(mut self, name: impl Into<String>, schema: JsonValue) -> Self {
+        self.request = Some((name.into(), schema));
+        self
+    }
+
+    pub fn with_response(mut self, name: impl Into<String>, schema: JsonValue) -> Self {
+        self.response = Some((name.into(), schema));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ApiOperation {
+    pub path: String,
+    pub method: String,
+    pub policy: Option<String>,
+}
+
+pub struct ApiDocService {
+    operations: Vec<ApiOperation>,
+    annotations: HashMap<String, ApiAnnotation>,
+}
+
+impl ApiDocService {
+    pub const OPENAPI_PATH: &'static str = "/api/openapi.json";
+    pub const DOCS_PATH: &'static str = "/api/docs";
+    const OPENAPI_VERSION: &'static str = "3.0.3";
+    const TITLE: &'static str = "Nimble Photos API";
+    const ERROR_SCHEMA: &'static str = "ApiErrorBody";
+
+    pub fn new() -> Self {
+        Self { operations: Vec::new(), annotations: HashMap::new() }
+    }
+
+    pub fn from_routes<'a>(routes: impl IntoIterator<Item = &'a EndpointRoute>) -> Self {
+        let mut service = Self::new();
+        for route in routes {
+            let policy = route.endpoint.metadata().policy().map(|policy| format!("{:?}", policy));
+            service.add_operation(&route.route.method().to_string(), route.route.path(), policy);
+        }
+        service
+    }
+
+    pub fn add_operation(&mut self, method: &str, path: &str, policy: Option<String>) -> &mut Self {
+        let operation = ApiOperation { path: path.to_string(), method: method.to_ascii_uppercase(), policy };
+        if let Err(index) = self.operations.binary_search(&operation) {
+            self.operations.insert(index, operation);
+        }
+        self
+    }
+
+    pub fn annotate(&mut self, method: &str, path: &str, annotation: ApiAnnotation) -> &mut Self {
+        self.annotations.insert(Self::operation_key(method, path), annotation);
+        self
+    }
+
+    pub fn operations(&self) -> &[ApiOperation] {
+        &self.operations
+    }
+
+    // The registered template a request path belongs to; literal segments win over parameters.
+    pub fn route_template(&self, method: &str, path: &str) -> Option<&str> {
+        let segments = path.trim_end_matches('/').split('/').collect::<Vec<_>>();
+        self.operations
+            .iter()
+            .filter(|operation| operation.method.eq_ignore_ascii_case(method))
+            .filter_map(|operation| {
+                Self::literal_matches(&operation.path, &segments).map(|literals| (literals, operation.path.as_str()))
+            })
+            .max_by_key(|(literals, _)| *literals)
+            .map(|(_, template)| template)
+    }
+
+    pub fn with_default_annotations(mut self) -> Self {
+        let credentials = Self::object_schema(&[("email", "string"), ("password", "string")]);
+        let tokens = Self::object_schema(&[
+            ("accessToken", "string"),
+            ("refreshToken", "string"),
+            ("sessionId", "string"),
+            ("user", "object"),
+        ]);
+        let registration = Self::object_schema(&[
+            ("email", "string"),
+            ("password", "string"),
+            ("confirmPassword", "string"),
+            ("displayName", "string"),
+        ]);
+
+        self.annotate(
+            "POST",
+            "/api/auth/login",
+            ApiAnnotation::new(
+                "Sign in with email and password; accounts with two-factor sign-in get a challenge instead",
+            )
+            .with_request("LoginRequest", credentials)
+            .with_response("LoginResponse", tokens.clone()),
+        )
+        .annotate(
+            "POST",
+            "/api/auth/register",
+            ApiAnnotation::new("Create an account")
+                .with_request("RegisterRequest", registration)
+                .with_response("LoginResponse", tokens.clone()),
+        )
+        .annotate(
+            "POST",
+            "/api/auth/refresh",
+            ApiAnnotation::new("Exchange a refresh token for new tokens")
+                .with_request("RefreshTokenRequest", Self::object_schema(&[("refreshToken", "string")]))
+                .with_response("LoginResponse", tokens.clone()),
+        )
+        .annotate(
+            "POST",
+            "/api/auth/verify-email",
+            ApiAnnotation::new("Confirm an email address with the token from the verification email")
+                .with_request("VerifyEmailRequest", Self::object_schema(&[("token", "string")])),
+        )
+        .annotate(
+            "POST",
+            "/api/auth/resend-verification",
+            ApiAnnotation::new("Send the verification email again; limited to one per address per minute")
+                .with_request("ResendVerificationRequest", Self::object_schema(&[("email", "string")])),
+        )
+        .annotate(
+            "POST",
+            "/api/auth/2fa/setup",
+            ApiAnnotation::new("Start two-factor setup with a new TOTP secret").with_response(
+                "TwoFactorSetupResponse",
+                Self::object_schema(&[("secret", "string"), ("otpauthUri", "string")]),
+            ),
+        )
+        .annotate(
+            "POST",
+            "/api/auth/2fa/enable",
+            ApiAnnotation::new("Confirm a code from the authenticator and turn two-factor sign-in on")
+                .with_request("TwoFactorCodeRequest", Self::object_schema(&[("code", "string")]))
+                .with_response(
+                    "TwoFactorEnabledResponse",
+                    json!({
+                        "type": "object",
+                        "required": ["backupCodes"],
+                        "properties": { "backupCodes": { "type": "array", "items": { "type": "string" } } }
+                    }),
+                ),
+        )
+        .annotate(
+            "POST",
+            "/api/auth/2fa/verify",
+            ApiAnnotation::new("Finish signing in with the login challenge and a TOTP or backup code")
+                .with_request(
+                    "TwoFactorVerifyRequest",
+                    Self::object_schema(&[("challengeToken", "string"), ("code", "string")]),
+                )
+                .with_response("LoginResponse", tokens),
+        )
+        .annotate(
+            "PUT",
+            "/api/photos/{id}/caption",
+            ApiAnnotation::new("Set or clear a photo title and caption").with_request(
+                "UpdatePhotoCaptionPayload",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "title": { "type": "string", "maxLength": 200 },
+                        "caption": { "type": "string", "maxLength": 5000 }
+                    }
+                }),
+            ),
+        )
+        .annotate(
+            "PUT",
+            "/api/photos/{id}/rating",
+            ApiAnnotation::new("Set a photo's star rating; 0 means unrated").with_request(
+                "UpdatePhotoRatingPayload",
+                json!({
+                    "type": "object",
+                    "required": ["rating"],
+                    "properties": {
+                        "rating": { "type": "integer", "minimum": 0, "maximum": PhotoRating::MAX }
+                    }
+                }),
+            ),
+        )
+        .annotate(
+            "PUT",
+            "/api/photos/{id}/people",
+            ApiAnnotation::new("Replace the people named on a photo; a name without a region keeps its stored region")
+                .with_request(
+                    "UpdatePhotoPeoplePayload",
+                    json!({
+                        "type": "object",
+                        "properties": {
+                            "people": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "required": ["name"],
+                                    "properties": {
+                                        "name": { "type": "string", "maxLength": PersonMention::MAX_NAME_LENGTH },
+                                        "region": Self::object_schema(&[
+                                            ("x", "number"),
+                                            ("y", "number"),
+                                            ("width", "number"),
+                                            ("height", "number")
+                                        ])
+                                    }
+                                }
+                            }
+                        }
+                    }),
+                ),
+        )
+        .annotate(
+            "POST",
+            "/api/people/rename",
+            ApiAnnotation::new("Rename or merge a person across every photo")
+                .with_request("RenamePersonPayload", Self::object_schema(&[("from", "string"), ("to", "string")])),
+        )
+        .annotate(
+            "POST",
+            "/api/photos/{id}/tag-suggestions/accept",
+            ApiAnnotation::new("Turn pending analyzer suggestions into tags on the photo").with_request(
+                "AcceptTagSuggestionsPayload",
+                json!({
+                    "type": "object",
+                    "required": ["tags"],
+                    "properties": { "tags": { "type": "array", "items": { "type": "string" } } }
+                }),
+            ),
+        )
+        .annotate(
+            "PUT",
+            "/api/photos/{id}/metadata",
+            ApiAnnotation::new("Override camera and GPS metadata; writeToFile=true also updates the original file")
+                .with_request(
+                    "UpdatePhotoMetadataRequest",
+                    json!({
+                        "type": "object",
+                        "properties": {
+                            "make": { "type": "string" },
+                            "model": { "type": "string" },
+                            "lensModel": { "type": "string" },
+                            "gpsLatitude": { "type": "number", "minimum": -90, "maximum": 90 },
+                            "gpsLongitude": { "type": "number", "minimum": -180, "maximum": 180 }
+                        }
+                    }),
+                ),
+        )
+        .annotate(
+            "GET",
+            "/api/photos/{id}/original",
+            ApiAnnotation::new(
+                "Download the original file; stripGps=true or privacy.stripGpsOnDownload removes GPS data",
+            ),
+        )
+        .annotate(
+            "POST",
+            "/api/photos/batch-edit",
+            ApiAnnotation::new("Apply caption, date, rating and tag changes to several photos").with_request(
+                "BatchEditPhotosRequest",
+                json!({
+                    "type": "object",
+                    "required": ["photoIds", "set"],
+                    "properties": {
+                        "photoIds": { "type": "array", "items": { "type": "string", "format": "uuid" } },
+                        "set": {
+                            "type": "object",
+                            "properties": {
+                                "caption": { "type": "string" },
+                                "dateTaken": { "type": "string", "format": "date-time" },
+                                "rating": { "type": "integer", "minimum": 0, "maximum": PhotoRating::MAX },
+                                "addTags": { "type": "array", "items": { "type": "string" } },
+                                "removeTags": { "type": "array", "items": { "type": "string" } }
+                            }
+                        }
+                    }
+                }),
+            ),
+        )
+        .annotate(
+            "PUT",
+            "/api/photos/tags",
+            ApiAnnotation::new("Add, remove or replace tags on photos").with_request(
+                "UpdatePhotoTagsPayload",
+                json!({
+                    "type": "object",
+                    "required": ["photoIds", "tags"],
+                    "properties": {
+                        "photoIds": { "type": "array", "items": { "type": "string", "format": "uuid" } },
+                        "tags": { "type": "array", "items": { "type": "string" } },
+                        "mode": { "type": "string", "enum": ["add", "remove", "replace"] }
+                    }
+                }),
+            ),
+        )
+        .annotate(
+            "POST",
+            "/api/photos/exists",
+            ApiAnnotation::new("Check which content hashes are already stored")
+                .with_request(
+                    "PhotoExistsRequest",
+                    json!({
+                        "type": "object",
+                        "required": ["hashes"],
+                        "properties": {
+                            "hashes": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "maxItems": PhotoExistsRequest::MAX_HASHES
+                            }
+                        }
+                    }),
+                )
+                .with_response(
+                    "PhotoExistsResponse",
+                    json!({
+                        "type": "object",
+                        "properties": {
+                            "results": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "hash": { "type": "string" },
+                                        "exists": { "type": "boolean" },
+                                        "storageIds": {
+                                            "type": "array",
+                                            "items": { "type": "string", "format": "uuid" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }),
+                ),
+        )
+        .annotate(
+            "POST",
+            "/api/photos/haspreview",
+            ApiAnnotation::new("Check which content hashes already have a generated preview")
+                .with_request(
+                    "HasPreviewRequest",
+                    json!({
+                        "type": "object",
+                        "required": ["hashes"],
+                        "properties": {
+                            "hashes": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "maxItems": HasPreviewRequest::MAX_HASHES
+                            }
+                        }
+                    }),
+                )
+                .with_response(
+                    "HasPreviewResponse",
+                    json!({ "type": "object", "additionalProperties": { "type": "boolean" } }),
+                ),
+        )
+        .annotate(
+            "POST",
+            "/api/storage/locations",
+            ApiAnnotation::new("Register a storage location").with_request(
+                "CreateStoragePayload",
+                json!({
+                    "type": "object",
+                    "required": ["label", "mountPoint", "path"],
+                    "properties": {
+                        "label": { "type": "string" },
+                        "mountPoint": { "type": "string" },
+                        "path": { "type": "string" },
+                        "isDefault": { "type": "boolean" },
+                        "categoryTemplate": { "type": "string" }
+                    }
+                }),
+            ),
+        );
+        self
+    }
+
+    pub fn document(&self) -> JsonValue {
+        let mut paths = serde_json::Map::new();
+        let mut schemas = serde_json::Map::new();
+        schemas.insert(Self::ERROR_SCHEMA.to_string(), Self::error_schema());
+
+        for operation in &self.operations {
+            let annotation = self.annotations.get(&Self::operation_key(&operation.method, &operation.path));
+            let entry = paths.entry(operation.path.clone()).or_insert_with(|| json!({}));
+            entry[operation.method.to_ascii_lowercase()] = self.operation_document(operation, annotation, &mut schemas);
+        }
+
+        json!({
+            "openapi": Self::OPENAPI_VERSION,
+            "info": { "title": Self::TITLE, "version": env!("CARGO_PKG_VERSION") },
+            "paths": paths,
+            "components": {
+                "schemas": schemas,
+                "securitySchemes": {
+                    "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" }
+                }
+            }
+        })
+    }
+
+    pub fn docs_page(&self) -> String {
+        format!(
+            r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8" />
+  <title>{title}</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css" />
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({{ url: "{spec}", dom_id: "#swagger-ui" }});
+  </script>
+</body>
+</html>
+"##,
+            title = Self::TITLE,
+            spec = Self::OPENAPI_PATH
+        )
+    }
+
+    fn operation_document(
+        &self,
+        operation: &ApiOperation,
+        annotation: Option<&ApiAnnotation>,
+        schemas: &mut serde_json::Map<String, JsonValue>,
+    ) -> JsonValue {
+        let mut document = json!({
+            "operationId": Self::operation_id(&operation.method, &operation.path),
+            "tags": [Self::tag(&operation.path)],
+            "responses": {
+                "200": { "description": "Success" },
+                "default": {
+                    "description": "Error",
+                    "content": { "application/json": { "schema": Self::schema_ref(Self::ERROR_SCHEMA) } }
+                }
+            }
+        });
+
+        let parameters = Self::path_parameters(&operation.path)
+            .into_iter()
+            .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+            .collect::<Vec<_>>();
+        if !parameters.is_empty() {
+            document["parameters"] = json!(parameters);
+        }
+
+        if let Some(policy) = &operation.policy {
+            document["security"] = json!([{ "bearerAuth": [] }]);
+            document["x-policy"] = json!(policy);
+        }
+
+        let Some(annotation) = annotation else {
+            return document;
+        };
+        if let Some(summary) = &annotation.summary {
+            document["summary"] = json!(summary);
+        }
+        if let Some((name, schema)) = &annotation.request {
+            schemas.insert(name.clone(), schema.clone());
+            document["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": Self::schema_ref(name) } }
+            });
+        }
+        if let Some((name, schema)) = &annotation.response {
+            schemas.insert(name.clone(), schema.clone());
+            document["responses"]["200"]["content"] =
+                json!({ "application/json": { "schema": Self::schema_ref(name) } });
+        }
+        document
+    }
+
+    fn object_schema(properties: &[(&str, &str)]) -> JsonValue {
+        let required = properties.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        let properties = properties
+            .iter()
+            .map(|(name, kind)| (name.to_string(), json!({ "type": kind })))
+            .collect::<serde_json::Map<_, _>>();
+        json!({ "type": "object", "required": required, "properties": properties })
+    }
+
+    fn error_schema() -> JsonValue {
+        json!({
+            "type": "object",
+            "required": ["code", "message"],
+            "properties": {
+                "code": { "type": "string" },
+                "message": { "type": "string" },
+                "details": {}
+            }
+        })
+    }
+
+    fn schema_ref(name: &str) -> JsonValue {
+        json!({ "$ref": format!("#/components/schemas/{}", name) })
+    }
+
+    fn path_parameters(path: &str) -> Vec<String> {
+        path.split('/')
+            .filter_map(|segment| segment.strip_prefix('{').and_then(|segment| segment.strip_suffix('}')))
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn tag(path: &str) -> String {
+        path.trim_start_matches('/')
+            .split('/')
+            .find(|segment| !segment.is_empty() && *segment != "api")
+            .unwrap_or("api")
+            .to_string()
+    }
+
+    fn operation_id(method: &str, path: &str) -> String {
+        let mut id = method.to_ascii_lowercase();
+        for segment in path.split('/').filter(|segment| !segment.is_empty() && *segment != "api") {
+            let segment = segment.trim_start_matches('{').trim_end_matches('}');
+            let mut chars = segment.chars().filter(|ch| ch.is_ascii_alphanumeric());
+            if let Some(first) = chars.next() {
+                id.push(first.to_ascii_uppercase());
+                id.extend(chars);
+            }
+        }
+        id
+    }
+
+    fn literal_matches(template: &str, segments: &[&str]) -> Option<usize> {
+        let parts = template.trim_end_matches('/').split('/').collect::<Vec<_>>();
+        if parts.len() != segments.len() {
+            return None;
+        }
+
+        let mut literals = 0;
+        for (part, segment) in parts.iter().zip(segments) {
+            if part.starts_with('{') && part.ends_with('}') {
+                if segment.is_empty() {
+                    return None;
+                }
+            } else if part == segment {
+                literals += 1;
+            } else {
+                return None;
+            }
+        }
+        Some(literals)
+    }
+
+    fn operation_key(method: &str, path: &str) -> String {
+        format!("{} {}", method.to_ascii_uppercase(), path)
+    }
+}
@@ -0,0 +1,190 @@
+use crate::models::setting_consts::SettingConsts;
+use crate::prelude::*;
+use anyhow::{Result, anyhow};
+use std::fs;
+
+enum RelocationOutcome {
+    Moved,
+    AlreadyPresent,
+    Orphaned,
+    Failed,
+}
+
+pub struct AssetRelocationService {
+    photos: Arc<Repository<Photo>>,
+    storages: Arc<Repository<StorageLocation>>,
+    path_cache: Arc<StoragePathCache>,
+    legacy_thumbnail_root: PathBuf,
+}
+
+impl AssetRelocationService {
+    pub const TASK_NAME: &'static str = "relocate-legacy-assets";
+    const LOOKUP_BATCH_SIZE: usize = 200;
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photos: services.get::<Repository<Photo>>(),
+            storages: services.get::<Repository<StorageLocation>>(),
+            path_cache: services.get::<StoragePathCache>(),
+            legacy_thumbnail_root: services.get::<AppConfig>().thumbnail_base_path.clone(),
+        }
+    }
+
+    pub async fn relocate(&self) -> Result<AssetRelocationSummary> {
+        let thumbnails = self
+            .relocate_root(
+                &self.legacy_thumbnail_root,
+                SettingConsts::THUMBNAIL_FOLDER,
+                SettingConsts::THUMBNAIL_FORMAT,
+            )
+            .await?;
+
+        let previews = match self.legacy_preview_root().await? {
+            Some(root) => {
+                self.relocate_root(&root, SettingConsts::PREVIEW_FOLDER, SettingConsts::PREVIEW_FORMAT).await?
+            }
+            None => AssetRelocationReport::default(),
+        };
+
+        Ok(AssetRelocationSummary { thumbnails, previews })
+    }
+
+    pub fn scan_assets(root: &Path, extension: &str) -> Result<Vec<(PathBuf, String)>> {
+        let mut assets = Vec::new();
+        if !root.is_dir() {
+            return Ok(assets);
+        }
+
+        let mut pending = vec![root.to_path_buf()];
+        while let Some(directory) = pending.pop() {
+            for entry in fs::read_dir(&directory)?.filter_map(|entry| entry.ok()) {
+                let path = entry.path();
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if file_type.is_dir() {
+                    pending.push(path);
+                } else if let Some(hash) = Self::asset_hash(&path, extension) {
+                    assets.push((path, hash));
+                }
+            }
+        }
+
+        assets.sort();
+        Ok(assets)
+    }
+
+    pub fn asset_hash(path: &Path, extension: &str) -> Option<String> {
+        let matches_extension = path
+            .extension()
+            .and_then(|value| value.to_str())
+            .is_some_and(|value| value.eq_ignore_ascii_case(extension));
+        if !matches_extension {
+            return None;
+        }
+        path.file_stem().and_then(|stem| stem.to_str()).and_then(HashService::normalize)
+    }
+
+    // Previews used to fall back to the built-in preview cache storage, written without a folder suffix.
+    async fn legacy_preview_root(&self) -> Result<Option<PathBuf>> {
+        let storage = self
+            .storages
+            .get(&SettingConsts::DEFAULT_STORAGE_ID)
+            .await
+            .map_err(|err| anyhow!("failed to load preview cache storage: {:?}", err))?;
+        Ok(storage.map(|storage| storage.normalized_path()))
+    }
+
+    async fn relocate_root(&self, legacy_root: &Path, folder: &str, extension: &str) -> Result<AssetRelocationReport> {
+        let assets = Self::scan_assets(legacy_root, extension)?;
+        let mut report = AssetRelocationReport { scanned: assets.len(), ..Default::default() };
+        log::info!("Relocating {} legacy asset(s) from {}", assets.len(), legacy_root.display());
+
+        for batch in assets.chunks(Self::LOOKUP_BATCH_SIZE) {
+            let storages_by_hash = self.storages_by_hash(batch).await?;
+            for (source, hash) in batch {
+                let storage_ids = storages_by_hash.get(hash).map(Vec::as_slice).unwrap_or_default();
+                match self.relocate_asset(source, hash, storage_ids, folder, extension).await? {
+                    RelocationOutcome::Moved => report.moved += 1,
+                    RelocationOutcome::AlreadyPresent => report.already_present += 1,
+                    RelocationOutcome::Orphaned => report.orphaned += 1,
+                    RelocationOutcome::Failed => report.failed += 1,
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn storages_by_hash(&self, batch: &[(PathBuf, String)]) -> Result<HashMap<String, Vec<Uuid>>> {
+        let hashes = batch.iter().map(|(_, hash)| Value::String(hash.clone())).collect();
+        let photos = self
+            .photos
+            .all(QueryBuilder::<Photo>::new().filter("hash", FilterOperator::In, Value::List(hashes)).build())
+            .await
+            .map_err(|err| anyhow!("failed to load photos by hash: {:?}", err))?;
+
+        let mut storages_by_hash: HashMap<String, Vec<Uuid>> = HashMap::new();
+        for photo in photos {
+            let Some(hash) = photo.hash else {
+                continue;
+            };
+            let storage_ids = storages_by_hash.entry(hash).or_default();
+            if !storage_ids.contains(&photo.storage_id) {
+                storage_ids.push(photo.storage_id);
+            }
+        }
+        Ok(storages_by_hash)
+    }
+
+    async fn relocate_asset(
+        &self,
+        source: &Path,
+        hash: &str,
+        storage_ids: &[Uuid],
+        folder: &str,
+        extension: &str,
+    ) -> Result<RelocationOutcome> {
+        if storage_ids.is_empty() {
+            return Ok(RelocationOutcome::Orphaned);
+        }
+
+        let mut copied = false;
+        let mut removable = true;
+        for storage_id in storage_ids {
+            let root = self
+                .path_cache
+                .root(&self.storages, *storage_id)
+                .await
+                .map_err(|err| anyhow!("failed to resolve storage {}: {:?}", storage_id, err))?;
+            let Some(root) = root else {
+                log::warn!("Storage {} for legacy asset {} no longer exists", storage_id, source.display());
+                return Ok(RelocationOutcome::Failed);
+            };
+            let target = FileService::hash_path(root.join(folder), hash, extension);
+            if target == source {
+                removable = false;
+                continue;
+            }
+            if target.exists() {
+                continue;
+            }
+
+            let copy = target.parent().map(fs::create_dir_all).transpose().and_then(|_| fs::copy(source, &target));
+            match copy {
+                Ok(_) => copied = true,
+                Err(err) => {
+                    log::warn!("Failed to relocate {} to {}: {}", source.display(), target.display(), err);
+                    return Ok(RelocationOutcome::Failed);
+                }
+            }
+        }
+
+        if removable {
+            if let Err(err) = fs::remove_file(source) {
+                log::warn!("Relocated {} but could not remove it: {}", source.display(), err);
+            }
+        }
+        Ok(if copied { RelocationOutcome::Moved } else { RelocationOutcome::AlreadyPresent })
+    }
+}
@@ -0,0 +1,74 @@
+use crate::prelude::*;
+use anyhow::anyhow;
+
+use crate::services::{BackgroundTaskRunner, TaskDescriptor};
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub action: Option<String>,
+    pub actor_user_id: Option<Uuid>,
+}
+
+pub struct AuditService {
+    repository: Arc<Repository<AuditLog>>,
+    runner: Arc<BackgroundTaskRunner>,
+}
+
+impl AuditService {
+    const TASK_NAME_PREFIX: &'static str = "audit";
+
+    pub fn new(repository: Arc<Repository<AuditLog>>, runner: Arc<BackgroundTaskRunner>) -> Self {
+        Self { repository, runner }
+    }
+
+    pub async fn record(&self, entry: AuditLog) {
+        let repository = Arc::clone(&self.repository);
+        let queued_entry = entry.clone();
+        let task = TaskDescriptor::new(format!("{}:{}", Self::TASK_NAME_PREFIX, entry.action), async move {
+            repository
+                .insert(queued_entry)
+                .await
+                .map(|_| ())
+                .map_err(|err| anyhow!("Failed to write audit entry: {:?}", err))
+        });
+
+        if let Err(err) = self.runner.enqueue(task) {
+            log::warn!("Audit queue unavailable ({}), writing entry '{}' inline", err, entry.action);
+            self.write(entry).await;
+        }
+    }
+
+    pub async fn list(
+        &self,
+        filter: AuditLogFilter,
+        page: u32,
+        page_size: u32,
+    ) -> Result<Page<AuditLog>, PipelineError> {
+        let mut query = QueryBuilder::<AuditLog>::new().sort_desc("created_at").page(page, page_size).build();
+
+        if let Some(action) = filter.action {
+            query.filters.push(Filter {
+                field: "action".to_string(),
+                operator: FilterOperator::Eq,
+                value: Value::String(action),
+            });
+        }
+
+        if let Some(actor_user_id) = filter.actor_user_id {
+            query.filters.push(Filter {
+                field: "actor_user_id".to_string(),
+                operator: FilterOperator::Eq,
+                value: Value::Uuid(actor_user_id),
+            });
+        }
+
+        self.repository.query(query).await.map_err(|err| PipelineError::message(&format!("{:?}", err)))
+    }
+
+    async fn write(&self, entry: AuditLog) {
+        let action = entry.action.clone();
+        if let Err(err) = self.repository.insert(entry).await {
+            log::error!("Failed to write audit entry '{}': {:?}", action, err);
+        }
+    }
+}
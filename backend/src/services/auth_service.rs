@@ -1,22 +1,27 @@
-#[cfg(feature = "postgres")]
-use crate::prelude::QueryBuilder;
 use crate::prelude::*;
 
 pub struct AuthService {
     repo: Arc<Repository<User>>,
     settings_repo: Arc<Repository<UserSettings>>,
+    sessions: Arc<Repository<RefreshSession>>,
+    two_factor: Arc<TwoFactorService>,
     encrypt_service: EncryptService,
     tokens: Arc<dyn TokenService>,
 }
 
 impl AuthService {
+    pub const EMAIL_NOT_VERIFIED: &'static str = "email not verified";
+    pub const VERIFICATION_GRACE_DAYS: i64 = 7;
+
     pub fn new(
         repo: Arc<Repository<User>>,
         settings_repo: Arc<Repository<UserSettings>>,
+        sessions: Arc<Repository<RefreshSession>>,
+        two_factor: Arc<TwoFactorService>,
         encrypt_service: EncryptService,
         tokens: Arc<dyn TokenService>,
     ) -> Self {
-        Self { settings_repo, repo, encrypt_service, tokens }
+        Self { settings_repo, repo, sessions, two_factor, encrypt_service, tokens }
     }
 
     pub async fn register(
@@ -24,7 +29,14 @@ impl AuthService {
         email: &str,
         password: &str,
         display_name: &str,
+        client: &SessionClient,
     ) -> Result<LoginResponse, PipelineError> {
+        let user_id = self.create_account(email, password, display_name).await?;
+        self.issue_tokens(user_id, client).await
+    }
+
+    // Self-service sign-up: the very first account becomes the admin, everyone after that a viewer.
+    pub async fn create_account(&self, email: &str, password: &str, display_name: &str) -> Result<Uuid, PipelineError> {
         let is_first_user = self
             .repo
             .query({
@@ -36,6 +48,17 @@ impl AuthService {
             .map(|page| page.items.is_empty())
             .map_err(|_| PipelineError::message("data error"))?;
 
+        let role = if is_first_user { "admin" } else { "viewer" };
+        self.create_user(email, password, display_name, role).await
+    }
+
+    pub async fn create_user(
+        &self,
+        email: &str,
+        password: &str,
+        display_name: &str,
+        roles: &str,
+    ) -> Result<Uuid, PipelineError> {
         let password_hash =
             self.encrypt_service.encrypt(password).map_err(|e| PipelineError::message(&e.to_string()))?;
 
@@ -48,6 +71,9 @@ impl AuthService {
         }
 
         let display_name_value = display_name.to_string();
+        // Admins only come from setup or the first sign-up; verifying them up front keeps a fresh
+        // instance from locking itself out when verification is required.
+        let email_verified = roles.split(',').any(|role| role.trim() == "admin");
 
         let user = User {
             id: Uuid::new_v4(),
@@ -57,9 +83,9 @@ impl AuthService {
             created_at: Utc::now(),
             reset_token: None,
             reset_token_expires_at: None,
-            verification_token: Some(Uuid::new_v4().to_string()),
-            email_verified: false,
-            roles: if is_first_user { Some("admin".to_string()) } else { Some("viewer".to_string()) },
+            verification_token: (!email_verified).then(|| Uuid::new_v4().to_string()),
+            email_verified,
+            roles: Some(roles.to_string()),
         };
 
         let user_id = user.id;
@@ -69,22 +95,14 @@ impl AuthService {
             PipelineError::message("Failed to create user")
         })?;
 
-        let settings = UserSettings {
-            user_id,
-            display_name: display_name_value,
-            avatar_url: None,
-            theme: "light".to_string(),
-            language: "en".to_string(),
-            timezone: "UTC".to_string(),
-            created_at: Utc::now(),
-        };
+        let settings = UserSettings::new(user_id, display_name_value);
 
         self.settings_repo.insert(settings).await.map_err(|err| {
             log::error!("User settings insert failed: {:?}", err);
             PipelineError::message("Failed to create user settings")
         })?;
 
-        self.issue_tokens(user_id).await
+        Ok(user_id)
     }
 
     pub async fn has_admin_user(&self) -> Result<bool, PipelineError> {
@@ -112,7 +130,13 @@ impl AuthService {
         }
     }
 
-    pub async fn login(&self, email: &str, password: &str) -> Result<LoginResponse, PipelineError> {
+    pub async fn login(
+        &self,
+        email: &str,
+        password: &str,
+        client: &SessionClient,
+        require_verified_email: bool,
+    ) -> Result<LoginOutcome, PipelineError> {
         let email_val = email.to_string();
         let value = Value::String(email_val);
         let user = self
@@ -129,21 +153,120 @@ impl AuthService {
         {
             return Err(PipelineError::message("invalid credentials"));
         }
+        // Checked after the password so the error can't be used to probe which addresses exist.
+        if require_verified_email && !user.email_verified {
+            return Err(PipelineError::message(Self::EMAIL_NOT_VERIFIED));
+        }
+        // The password alone isn't enough; tokens come from /api/auth/2fa/verify once the code checks out.
+        if self.two_factor.is_enabled(user.id).await? {
+            return Ok(LoginOutcome::TwoFactorRequired(self.two_factor.challenge(user.id)));
+        }
 
-        self.issue_tokens(user.id).await
+        self.issue_tokens(user.id, client).await.map(LoginOutcome::Tokens)
     }
 
-    pub async fn refresh(&self, refresh_token: &str) -> Result<LoginResponse, PipelineError> {
+    // A token that still verifies but has no session row was revoked from another device.
+    pub async fn refresh(
+        &self,
+        refresh_token: &str,
+        require_verified_email: bool,
+        allow_grace: bool,
+    ) -> Result<LoginResponse, PipelineError> {
         let user_id =
             self.tokens.validate_refresh_token(refresh_token).map_err(|e| PipelineError::message(&e.to_string()))?;
         let user_id = Uuid::parse_str(&user_id).map_err(|_| PipelineError::message("invalid refresh token subject"))?;
-        self.issue_tokens(user_id).await
+        let session = self
+            .find_session(refresh_token)
+            .await?
+            .filter(|session| session.user_id == user_id)
+            .ok_or_else(|| PipelineError::message("refresh token revoked"))?;
+        let user = self
+            .repo
+            .get(&user_id)
+            .await
+            .map_err(|_| PipelineError::message("data error"))?
+            .ok_or_else(|| PipelineError::message("refresh token revoked"))?;
+        // Tokens issued before verification was enforced stop refreshing once the grace period is over.
+        if require_verified_email && !user.email_verified {
+            let deadline = user.created_at + Duration::days(Self::VERIFICATION_GRACE_DAYS);
+            if !allow_grace || Utc::now() >= deadline {
+                return Err(PipelineError::message(Self::EMAIL_NOT_VERIFIED));
+            }
+        }
+        self.issue(user_id, Some(session), &SessionClient::default()).await
     }
 
-    pub fn logout(&self, refresh_token: &str) -> Result<(), PipelineError> {
+    pub async fn logout(&self, refresh_token: &str) -> Result<(), PipelineError> {
+        if let Some(session) = self.find_session(refresh_token).await? {
+            self.delete_session(session.id).await?;
+        }
         self.tokens.revoke_refresh_token(refresh_token).map_err(|e| PipelineError::message(&e.to_string()))
     }
 
+    pub async fn sessions(&self, user_id: Uuid) -> Result<Vec<RefreshSession>, PipelineError> {
+        let mut sessions = self
+            .sessions
+            .all(
+                QueryBuilder::<RefreshSession>::new()
+                    .filter("user_id", FilterOperator::Eq, Value::Uuid(user_id))
+                    .build(),
+            )
+            .await
+            .map_err(|_| PipelineError::message("data error"))?;
+        sessions.sort_by(|left, right| right.last_used_at.cmp(&left.last_used_at));
+        Ok(sessions)
+    }
+
+    // False when the session does not exist or belongs to someone else, so ids cannot be probed.
+    pub async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<bool, PipelineError> {
+        let session = self.sessions.get(&session_id).await.map_err(|_| PipelineError::message("data error"))?;
+        match session {
+            Some(session) if session.user_id == user_id => self.delete_session(session.id).await.map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    pub async fn revoke_other_sessions(&self, user_id: Uuid, keep: Option<Uuid>) -> Result<u64, PipelineError> {
+        let mut revoked = 0;
+        for session in self.sessions(user_id).await? {
+            if Some(session.id) != keep {
+                self.delete_session(session.id).await?;
+                revoked += 1;
+            }
+        }
+        Ok(revoked)
+    }
+
+    async fn find_session(&self, refresh_token: &str) -> Result<Option<RefreshSession>, PipelineError> {
+        let hash = RefreshSession::hash_token(refresh_token);
+        let sessions = self
+            .sessions
+            .all(
+                QueryBuilder::<RefreshSession>::new()
+                    .filter("token_hash", FilterOperator::Eq, Value::String(hash))
+                    .build(),
+            )
+            .await
+            .map_err(|_| PipelineError::message("data error"))?;
+        Ok(sessions.into_iter().next())
+    }
+
+    async fn delete_session(&self, session_id: Uuid) -> Result<(), PipelineError> {
+        self.sessions.delete(&session_id).await.map_err(|_| PipelineError::message("failed to revoke session"))?;
+        Ok(())
+    }
+
+    pub async fn verify_password(&self, user_id: Uuid, password: &str) -> Result<bool, PipelineError> {
+        let user = self
+            .repo
+            .get(&user_id)
+            .await
+            .map_err(|_| PipelineError::message("data error"))?
+            .ok_or_else(|| PipelineError::message("user not found"))?;
+
+        self.encrypt_service.verify(password, &user.password_hash).map_err(|e| PipelineError::message(&e.to_string()))
+    }
+
     pub async fn me(&self, user_id: &str) -> Result<User, PipelineError> {
         let id = Uuid::parse_str(user_id).map_err(|_| PipelineError::message("invalid user id"))?;
         self.repo
@@ -252,7 +375,65 @@ impl AuthService {
         user.verification_token.clone().ok_or_else(|| PipelineError::message("verification token missing"))
     }
 
-    async fn issue_tokens(&self, user_id: Uuid) -> Result<LoginResponse, PipelineError> {
+    // A fresh token for an account that still needs to verify; None when there's nothing to send,
+    // so callers can answer the same way whether or not the address is registered.
+    pub async fn reissue_verification_token(&self, email: &str) -> Result<Option<String>, PipelineError> {
+        let value = Value::String(email.to_string());
+        let Some(mut user) =
+            self.repo.get_by("email", value).await.map_err(|_| PipelineError::message("data error"))?
+        else {
+            return Ok(None);
+        };
+        if user.email_verified {
+            return Ok(None);
+        }
+
+        let token = Uuid::new_v4().to_string();
+        user.verification_token = Some(token.clone());
+        self.repo.update(user).await.map_err(|_| PipelineError::message("failed to update user"))?;
+        Ok(Some(token))
+    }
+
+    pub async fn grant_role(&self, email: &str, role: &str) -> Result<Uuid, PipelineError> {
+        let value = Value::String(email.to_string());
+        let mut user = self
+            .repo
+            .get_by("email", value)
+            .await
+            .map_err(|_| PipelineError::message("data error"))?
+            .ok_or_else(|| PipelineError::message("user not found"))?;
+
+        let mut roles = user
+            .roles
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if !roles.iter().any(|value| value.eq_ignore_ascii_case(role)) {
+            roles.push(role.to_string());
+        }
+
+        let user_id = user.id;
+        user.roles = Some(roles.join(","));
+        self.repo.update(user).await.map_err(|_| PipelineError::message("failed to update user"))?;
+
+        Ok(user_id)
+    }
+
+    pub async fn issue_tokens(&self, user_id: Uuid, client: &SessionClient) -> Result<LoginResponse, PipelineError> {
+        self.issue(user_id, None, client).await
+    }
+
+    // Refreshing rotates the token of the existing session; anything else starts a new one.
+    async fn issue(
+        &self,
+        user_id: Uuid,
+        session: Option<RefreshSession>,
+        client: &SessionClient,
+    ) -> Result<LoginResponse, PipelineError> {
         let user = self
             .repo
             .get(&user_id)
@@ -263,7 +444,7 @@ impl AuthService {
         let user_id_str = user_id.to_string();
         let mut claims = Claims::new();
 
-        if let Some(roles_str) = user.roles {
+        if let Some(roles_str) = user.roles.as_deref() {
             for role in roles_str.split(',') {
                 let role = role.trim();
                 if !role.is_empty() {
@@ -273,16 +454,26 @@ impl AuthService {
         }
 
         let identity = UserIdentity::new(user_id_str.clone(), claims);
+        let settings = self
+            .settings_repo
+            .get(&user_id)
+            .await
+            .map_err(|_| PipelineError::message("data error"))?
+            .unwrap_or_else(|| UserSettings::new(user_id, user.email.clone()));
+
+        let access_token =
+            self.tokens.create_access_token(&identity).map_err(|e| PipelineError::message(&e.to_string()))?;
+        let refresh_token =
+            self.tokens.create_refresh_token(&user_id_str).map_err(|e| PipelineError::message(&e.to_string()))?;
+        let session = match session {
+            Some(mut session) => {
+                session.rotate(&refresh_token);
+                self.sessions.update(session).await
+            }
+            None => self.sessions.insert(RefreshSession::new(user_id, &refresh_token, client)).await,
+        }
+        .map_err(|_| PipelineError::message("failed to save session"))?;
 
-        Ok(LoginResponse {
-            access_token: self
-                .tokens
-                .create_access_token(&identity)
-                .map_err(|e| PipelineError::message(&e.to_string()))?,
-            refresh_token: self
-                .tokens
-                .create_refresh_token(&user_id_str)
-                .map_err(|e| PipelineError::message(&e.to_string()))?,
-        })
+        Ok(LoginResponse { access_token, refresh_token, session_id: session.id, user: (user, settings).into() })
     }
 }
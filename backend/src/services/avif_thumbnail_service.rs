@@ -0,0 +1,80 @@
+use crate::prelude::*;
+use anyhow::anyhow;
+use image::ImageReader;
+use image::codecs::avif::AvifEncoder;
+use tokio::task;
+
+use crate::services::{BackgroundTaskRunner, TaskDescriptor};
+
+// AVIF encoding is slow, so siblings are only ever produced on the background runner.
+pub struct AvifThumbnailService {
+    runner: Arc<BackgroundTaskRunner>,
+    pending: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl AvifThumbnailService {
+    const TASK_NAME_PREFIX: &'static str = "thumbnail-avif";
+    pub const ENCODER_SPEED: u8 = 8;
+    pub const ENCODER_QUALITY: u8 = 60;
+
+    pub fn new(runner: Arc<BackgroundTaskRunner>) -> Self {
+        Self { runner, pending: Arc::new(Mutex::new(HashSet::new())) }
+    }
+
+    // Encodes an AVIF copy of an existing thumbnail; the file only appears once it is complete.
+    pub fn encode(source: &Path, output: &Path) -> anyhow::Result<()> {
+        let image = ImageReader::open(source)?.with_guessed_format()?.decode()?.to_rgba8();
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let partial = output.with_extension("avif.partial");
+        let writer = std::io::BufWriter::new(fs::File::create(&partial)?);
+        let encoded = image
+            .write_with_encoder(AvifEncoder::new_with_speed_quality(writer, Self::ENCODER_SPEED, Self::ENCODER_QUALITY))
+            .map_err(|error| anyhow!("failed to encode AVIF thumbnail: {}", error))
+            .and_then(|_| fs::rename(&partial, output).map_err(Into::into));
+        if encoded.is_err() {
+            let _ = fs::remove_file(&partial);
+        }
+        encoded
+    }
+
+    // Returns false when the sibling is already queued or the runner refused the task.
+    pub fn schedule(&self, source: PathBuf, output: PathBuf) -> bool {
+        {
+            let Ok(mut pending) = self.pending.lock() else {
+                return false;
+            };
+            if !pending.insert(output.clone()) {
+                return false;
+            }
+        }
+
+        let pending = Arc::clone(&self.pending);
+        let queued_output = output.clone();
+        let name =
+            format!("{}:{}", Self::TASK_NAME_PREFIX, output.file_stem().and_then(|stem| stem.to_str()).unwrap_or(""));
+        let task = TaskDescriptor::new(name, async move {
+            let target = queued_output.clone();
+            let result = task::spawn_blocking(move || Self::encode(&source, &target)).await;
+            if let Ok(mut pending) = pending.lock() {
+                pending.remove(&queued_output);
+            }
+            result.map_err(|error| anyhow!("AVIF thumbnail task join error: {}", error))?
+        });
+
+        if let Err(error) = self.runner.enqueue(task) {
+            log::warn!("Failed to queue AVIF thumbnail {}: {}", output.display(), error);
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.remove(&output);
+            }
+            return false;
+        }
+        true
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().map(|pending| pending.len()).unwrap_or(0)
+    }
+}
@@ -5,33 +5,40 @@ use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use tokio::task::JoinHandle;
 use tokio::time::{Duration, sleep};
 
-use crate::services::TaskDescriptor;
+use crate::services::task_descriptor::{TaskDescriptor, TaskInfo, TaskState};
 
 pub struct BackgroundTaskRunner {
-    parallelism: usize,
+    parallelism: Arc<AtomicUsize>,
+    active_workers: Arc<AtomicUsize>,
     queue: Arc<Mutex<VecDeque<TaskDescriptor>>>,
+    tracker: Arc<TaskTracker>,
     worker_handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     running_task_count: Arc<AtomicUsize>,
     queued_task_count: Arc<AtomicUsize>,
     accepting_tasks: Arc<AtomicBool>,
     running_workers: Arc<AtomicBool>,
     shutting_down: Arc<AtomicBool>,
+    draining: Arc<AtomicBool>,
 }
 
 impl BackgroundTaskRunner {
     const EMPTY_QUEUE_SLEEP_MILLISECONDS: u64 = 5;
+    const HISTORY_CAPACITY: usize = 500;
 
     pub fn new(parallelism: usize) -> Self {
         let worker_parallelism = parallelism.max(1);
         Self {
-            parallelism: worker_parallelism,
+            parallelism: Arc::new(AtomicUsize::new(worker_parallelism)),
+            active_workers: Arc::new(AtomicUsize::new(0)),
             queue: Arc::new(Mutex::new(VecDeque::new())),
+            tracker: Arc::new(TaskTracker::new(Self::HISTORY_CAPACITY)),
             worker_handles: Arc::new(Mutex::new(Vec::new())),
             running_task_count: Arc::new(AtomicUsize::new(0)),
             queued_task_count: Arc::new(AtomicUsize::new(0)),
             accepting_tasks: Arc::new(AtomicBool::new(true)),
             running_workers: Arc::new(AtomicBool::new(false)),
             shutting_down: Arc::new(AtomicBool::new(false)),
+            draining: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -41,37 +48,96 @@ impl BackgroundTaskRunner {
         }
 
         let mut queue = self.queue.lock().map_err(|_| anyhow!("Failed to lock task queue"))?;
+        self.tracker.track(TaskInfo::queued(&task));
         queue.push_back(task);
         self.queued_task_count.fetch_add(1, Ordering::SeqCst);
         Ok(())
     }
 
+    pub fn cancel(&self, task_id: Uuid) -> Result<bool> {
+        let mut queue = self.queue.lock().map_err(|_| anyhow!("Failed to lock task queue"))?;
+        let Some(position) = queue.iter().position(|task| task.id == task_id) else {
+            return Ok(false);
+        };
+
+        queue.remove(position);
+        self.queued_task_count.fetch_sub(1, Ordering::SeqCst);
+        self.tracker.finish(task_id, TaskState::Cancelled, None);
+        Ok(true)
+    }
+
+    pub fn tasks(&self, state: Option<TaskState>) -> Vec<TaskInfo> {
+        self.tracker
+            .snapshot()
+            .into_iter()
+            .filter(|task| state.map(|value| task.state == value).unwrap_or(true))
+            .collect()
+    }
+
+    pub fn task(&self, task_id: Uuid) -> Option<TaskInfo> {
+        self.tracker.snapshot().into_iter().find(|task| task.id == task_id)
+    }
+
     pub fn start(&self) -> Result<()> {
         if self.running_workers.swap(true, Ordering::SeqCst) {
             return Ok(());
         }
 
         self.shutting_down.store(false, Ordering::SeqCst);
+        self.draining.store(false, Ordering::SeqCst);
         self.accepting_tasks.store(true, Ordering::SeqCst);
 
         let mut handles = self.worker_handles.lock().map_err(|_| anyhow!("Failed to lock worker handle pool"))?;
 
-        for _ in 0..self.parallelism {
-            let worker = WorkerRuntime {
-                queue: Arc::clone(&self.queue),
-                running_task_count: Arc::clone(&self.running_task_count),
-                queued_task_count: Arc::clone(&self.queued_task_count),
-                shutting_down: Arc::clone(&self.shutting_down),
-            };
+        for _ in 0..self.parallelism() {
+            handles.push(self.spawn_worker());
+        }
 
-            handles.push(tokio::spawn(async move {
-                worker.run().await;
-            }));
+        Ok(())
+    }
+
+    pub fn parallelism(&self) -> usize {
+        self.parallelism.load(Ordering::SeqCst)
+    }
+
+    pub fn active_worker_count(&self) -> usize {
+        self.active_workers.load(Ordering::SeqCst)
+    }
+
+    pub fn set_parallelism(&self, parallelism: usize) -> Result<()> {
+        let parallelism = parallelism.max(1);
+        self.parallelism.store(parallelism, Ordering::SeqCst);
+        if !self.running_workers.load(Ordering::SeqCst) || self.shutting_down.load(Ordering::SeqCst) {
+            return Ok(());
         }
 
+        // Surplus workers retire themselves between tasks; only growth needs new workers here.
+        let mut handles = self.worker_handles.lock().map_err(|_| anyhow!("Failed to lock worker handle pool"))?;
+        handles.retain(|handle| !handle.is_finished());
+        for _ in self.active_worker_count()..parallelism {
+            handles.push(self.spawn_worker());
+        }
         Ok(())
     }
 
+    fn spawn_worker(&self) -> JoinHandle<()> {
+        self.active_workers.fetch_add(1, Ordering::SeqCst);
+        let worker = WorkerRuntime {
+            queue: Arc::clone(&self.queue),
+            tracker: Arc::clone(&self.tracker),
+            running_task_count: Arc::clone(&self.running_task_count),
+            queued_task_count: Arc::clone(&self.queued_task_count),
+            parallelism: Arc::clone(&self.parallelism),
+            active_workers: Arc::clone(&self.active_workers),
+            shutting_down: Arc::clone(&self.shutting_down),
+            draining: Arc::clone(&self.draining),
+        };
+
+        tokio::spawn(async move {
+            worker.run().await;
+        })
+    }
+
     pub async fn stop(&self) -> Result<()> {
         self.accepting_tasks.store(false, Ordering::SeqCst);
         self.shutting_down.store(true, Ordering::SeqCst);
@@ -85,10 +151,48 @@ impl BackgroundTaskRunner {
             let _ = handle.await;
         }
 
+        self.active_workers.store(0, Ordering::SeqCst);
         self.running_workers.store(false, Ordering::SeqCst);
         Ok(())
     }
 
+    pub async fn shutdown(&self, grace_period: Duration) -> Result<Vec<TaskDescriptor>> {
+        self.accepting_tasks.store(false, Ordering::SeqCst);
+        self.draining.store(true, Ordering::SeqCst);
+
+        let deadline = tokio::time::Instant::now() + grace_period;
+        while self.running_count() > 0 && tokio::time::Instant::now() < deadline {
+            sleep(Duration::from_millis(Self::EMPTY_QUEUE_SLEEP_MILLISECONDS)).await;
+        }
+
+        if self.running_count() > 0 {
+            log::warn!("BackgroundTaskRunner grace period elapsed with {} task(s) still running", self.running_count());
+        }
+
+        let pending: Vec<TaskDescriptor> = {
+            let mut queue = self.queue.lock().map_err(|_| anyhow!("Failed to lock task queue"))?;
+            self.queued_task_count.store(0, Ordering::SeqCst);
+            queue.drain(..).collect()
+        };
+        for task in &pending {
+            self.tracker.finish(task.id, TaskState::Cancelled, Some("runner shut down".to_string()));
+        }
+
+        self.shutting_down.store(true, Ordering::SeqCst);
+        let handles = {
+            let mut guard = self.worker_handles.lock().map_err(|_| anyhow!("Failed to lock worker handle pool"))?;
+            std::mem::take(&mut *guard)
+        };
+        for handle in handles {
+            handle.abort();
+            let _ = handle.await;
+        }
+
+        self.active_workers.store(0, Ordering::SeqCst);
+        self.running_workers.store(false, Ordering::SeqCst);
+        Ok(pending)
+    }
+
     pub fn running_count(&self) -> usize {
         self.running_task_count.load(Ordering::SeqCst)
     }
@@ -98,22 +202,85 @@ impl BackgroundTaskRunner {
     }
 }
 
+struct TaskTracker {
+    history_capacity: usize,
+    active: Mutex<HashMap<Uuid, TaskInfo>>,
+    history: Mutex<VecDeque<TaskInfo>>,
+}
+
+impl TaskTracker {
+    fn new(history_capacity: usize) -> Self {
+        Self { history_capacity, active: Mutex::new(HashMap::new()), history: Mutex::new(VecDeque::new()) }
+    }
+
+    fn track(&self, info: TaskInfo) {
+        if let Ok(mut active) = self.active.lock() {
+            active.insert(info.id, info);
+        }
+    }
+
+    fn start(&self, task_id: Uuid) {
+        if let Ok(mut active) = self.active.lock() {
+            if let Some(info) = active.get_mut(&task_id) {
+                info.state = TaskState::Running;
+                info.started_at = Some(Utc::now());
+            }
+        }
+    }
+
+    fn finish(&self, task_id: Uuid, state: TaskState, error: Option<String>) {
+        let Some(mut info) = self.active.lock().ok().and_then(|mut active| active.remove(&task_id)) else {
+            return;
+        };
+
+        info.state = state;
+        info.finished_at = Some(Utc::now());
+        info.last_error = error;
+
+        if let Ok(mut history) = self.history.lock() {
+            history.push_back(info);
+            while history.len() > self.history_capacity {
+                history.pop_front();
+            }
+        }
+    }
+
+    fn snapshot(&self) -> Vec<TaskInfo> {
+        let mut tasks: Vec<TaskInfo> =
+            self.active.lock().map(|active| active.values().cloned().collect()).unwrap_or_default();
+        if let Ok(history) = self.history.lock() {
+            tasks.extend(history.iter().rev().cloned());
+        }
+        tasks.sort_by(|left, right| right.enqueued_at.cmp(&left.enqueued_at));
+        tasks
+    }
+}
+
 struct WorkerRuntime {
     queue: Arc<Mutex<VecDeque<TaskDescriptor>>>,
+    tracker: Arc<TaskTracker>,
     running_task_count: Arc<AtomicUsize>,
     queued_task_count: Arc<AtomicUsize>,
+    parallelism: Arc<AtomicUsize>,
+    active_workers: Arc<AtomicUsize>,
     shutting_down: Arc<AtomicBool>,
+    draining: Arc<AtomicBool>,
 }
 
 impl WorkerRuntime {
     async fn run(&self) {
         loop {
+            if self.try_retire() {
+                return;
+            }
+
             if let Some(task) = self.try_take_next_task() {
                 self.execute_task(task).await;
                 continue;
             }
 
             if self.shutting_down.load(Ordering::SeqCst) && self.queued_task_count.load(Ordering::SeqCst) == 0 {
+                self.active_workers.fetch_sub(1, Ordering::SeqCst);
                 break;
             }
 
@@ -121,7 +288,17 @@ impl WorkerRuntime {
         }
     }
 
+    fn try_retire(&self) -> bool {
+        let active = self.active_workers.load(Ordering::SeqCst);
+        active > self.parallelism.load(Ordering::SeqCst)
+            && self.active_workers.compare_exchange(active, active - 1, Ordering::SeqCst, Ordering::SeqCst).is_ok()
+    }
+
     fn try_take_next_task(&self) -> Option<TaskDescriptor> {
+        if self.draining.load(Ordering::SeqCst) {
+            return None;
+        }
+
         let mut queue = self.queue.lock().ok()?;
         let task = queue.pop_front();
         if task.is_some() {
@@ -132,15 +309,21 @@ impl WorkerRuntime {
 
     async fn execute_task(&self, task: TaskDescriptor) {
         self.running_task_count.fetch_add(1, Ordering::SeqCst);
+        let task_id = task.id;
         let task_name = task.name.clone();
+        self.tracker.start(task_id);
         let join_result = tokio::spawn(async move { task.execute().await }).await;
         match join_result {
-            Ok(Ok(())) => {}
+            Ok(Ok(())) => {
+                self.tracker.finish(task_id, TaskState::Completed, None);
+            }
             Ok(Err(error)) => {
                 log::error!("Background task '{}' failed: {}", task_name, error);
+                self.tracker.finish(task_id, TaskState::Failed, Some(error.to_string()));
             }
             Err(error) => {
                 log::error!("Background task '{}' panicked: {}", task_name, error);
+                self.tracker.finish(task_id, TaskState::Failed, Some(error.to_string()));
             }
         }
         self.running_task_count.fetch_sub(1, Ordering::SeqCst);
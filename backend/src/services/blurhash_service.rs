@@ -0,0 +1,115 @@
+use crate::models::setting_consts::SettingConsts;
+use crate::prelude::*;
+use anyhow::{Context, Result, anyhow};
+
+// Placeholders are computed from the stored thumbnail rather than the original, so neither the
+// pipeline nor the backfill has to decode a RAW file for them.
+pub struct BlurhashService {
+    photos: Arc<Repository<Photo>>,
+    storages: Arc<Repository<StorageLocation>>,
+    path_cache: Arc<StoragePathCache>,
+}
+
+impl BlurhashService {
+    pub const TASK_NAME: &'static str = "backfill-blurhash";
+    const SAMPLE_SIZE: u32 = 32;
+    const PAGE_SIZE: u32 = 200;
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photos: services.get::<Repository<Photo>>(),
+            storages: services.get::<Repository<StorageLocation>>(),
+            path_cache: services.get::<StoragePathCache>(),
+        }
+    }
+
+    pub fn encode_file(path: &Path) -> Result<String> {
+        let image = image::open(path).with_context(|| format!("failed to decode {}", path.display()))?;
+        let sample = image.thumbnail(Self::SAMPLE_SIZE, Self::SAMPLE_SIZE).to_rgb8();
+        Blurhash::encode(sample.width(), sample.height(), sample.as_raw()).ok_or_else(|| anyhow!("empty image"))
+    }
+
+    // Runs on the blocking pool; a thumbnail that cannot be decoded just leaves the placeholder empty.
+    pub async fn compute(path: PathBuf) -> Option<String> {
+        let display = path.display().to_string();
+        match tokio::task::spawn_blocking(move || Self::encode_file(&path)).await {
+            Ok(Ok(blurhash)) => Some(blurhash),
+            Ok(Err(error)) => {
+                log::warn!("Failed to compute blurhash for {}: {:?}", display, error);
+                None
+            }
+            Err(error) => {
+                log::warn!("Blurhash computation for {} did not finish: {:?}", display, error);
+                None
+            }
+        }
+    }
+
+    pub async fn backfill(&self) -> Result<BlurhashBackfillSummary> {
+        let mut summary = BlurhashBackfillSummary::default();
+        let mut roots = HashMap::<Uuid, Option<PathBuf>>::new();
+        let mut after = None;
+
+        loop {
+            let page = self
+                .photos
+                .blurhash_candidates(after.as_ref(), Self::PAGE_SIZE)
+                .await
+                .map_err(|e| anyhow!("{:?}", e))?;
+            if page.is_empty() {
+                break;
+            }
+            summary.scanned += page.len();
+
+            for candidate in &page {
+                if !roots.contains_key(&candidate.storage_id) {
+                    let root = self
+                        .path_cache
+                        .root(&self.storages, candidate.storage_id)
+                        .await
+                        .map_err(|e| anyhow!("{:?}", e))?;
+                    roots.insert(candidate.storage_id, root.map(|root| root.join(SettingConsts::THUMBNAIL_FOLDER)));
+                }
+                let Some(Some(root)) = roots.get(&candidate.storage_id).cloned() else {
+                    summary.missing += 1;
+                    continue;
+                };
+
+                let hash = candidate.hash.clone();
+                let encoded = tokio::task::spawn_blocking(move || {
+                    Self::thumbnail_path(&root, &hash).map(|thumbnail| {
+                        Self::encode_file(&thumbnail).map_err(|error| {
+                            log::warn!("Failed to compute blurhash for {}: {:?}", thumbnail.display(), error)
+                        })
+                    })
+                })
+                .await
+                .context("blurhash backfill join error")?;
+
+                match encoded {
+                    None => summary.missing += 1,
+                    Some(Err(_)) => summary.failed += 1,
+                    Some(Ok(blurhash)) => {
+                        self.photos
+                            .set_blurhash(candidate.storage_id, &candidate.hash, &blurhash)
+                            .await
+                            .map_err(|e| anyhow!("{:?}", e))?;
+                        summary.updated += 1;
+                    }
+                }
+            }
+
+            after = page.last().cloned();
+        }
+
+        Ok(summary)
+    }
+
+    // The thumbnail format can change between imports, so take whichever base format is on disk.
+    fn thumbnail_path(root: &Path, hash: &str) -> Option<PathBuf> {
+        ThumbnailFormat::BASE
+            .iter()
+            .map(|format| FileService::hash_path(root, hash, format.extension()))
+            .find(|path| path.is_file())
+    }
+}
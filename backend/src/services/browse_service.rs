@@ -3,18 +3,26 @@ use anyhow::{Result, anyhow};
 use sqlx::{PgPool, Row};
 
 use crate::entities::photo_browse::{BrowseNodeType, BrowseOptions, BrowsePhoto, BrowseResponse, StorageFolder};
-use crate::entities::photo_cursor::PhotoCursor;
+use crate::entities::photo_cursor::{InvalidCursor, PhotoCursor};
 use crate::models::browse_dimension_sql_adapter::{BrowseDimensionSqlAdapter, SqlParam};
 #[cfg(feature = "postgres")]
 use crate::repositories::postgres_extensions::PostgresExtensions;
+use crate::services::cursor_signer::CursorSigner;
 
 pub struct BrowseService {
     pool: Arc<PgPool>,
+    signer: Arc<CursorSigner>,
 }
 
 impl BrowseService {
-    pub fn new(pool: Arc<PgPool>) -> Self {
-        Self { pool }
+    pub const CURSOR_MISMATCH: &'static str = "cursor does not match browse sort";
+
+    pub fn new(pool: Arc<PgPool>, signer: Arc<CursorSigner>) -> Self {
+        Self { pool, signer }
+    }
+
+    pub fn decode_cursor(&self, encoded: &str) -> std::result::Result<PhotoCursor, InvalidCursor> {
+        PhotoCursor::decode(encoded, &self.signer)
     }
 
     pub async fn browse(
@@ -35,7 +43,7 @@ impl BrowseService {
         );
         let depth = path_segments.len();
         if depth > options.dimensions.len() {
-            return Err(anyhow!("invalid browse path depth"));
+            return Err(BrowsePathError::TooDeep.into());
         }
 
         if depth < options.dimensions.len() {
@@ -52,17 +60,7 @@ impl BrowseService {
         options: &BrowseOptions,
         depth: usize,
     ) -> Result<BrowseResponse> {
-        let mut where_clauses = vec!["p.storage_id = $1".to_string()];
-        let mut params: Vec<SqlParam> = Vec::new();
-        let mut param_index = 2usize;
-
-        for (index, segment) in path_segments.iter().enumerate() {
-            let adapter = BrowseDimensionSqlAdapter::new(options.dimensions[index].clone());
-            let param = adapter.parse_segment_value(segment)?;
-            where_clauses.push(adapter.filter_clause(param_index));
-            params.push(param);
-            param_index += 1;
-        }
+        let (where_clauses, params, _) = Self::build_filters(path_segments, options)?;
 
         let target_adapter = BrowseDimensionSqlAdapter::new(options.dimensions[depth].clone());
         let (folder_select, group_expr) = target_adapter.group_select();
@@ -84,6 +82,7 @@ impl BrowseService {
             query = match param {
                 SqlParam::Int(value) => query.bind(value),
                 SqlParam::String(value) => query.bind(value),
+                SqlParam::TextList(values) => query.bind(values),
             };
         }
 
@@ -129,40 +128,27 @@ impl BrowseService {
         page_size: i64,
         cursor: Option<PhotoCursor>,
     ) -> Result<BrowseResponse> {
-        let mut where_clauses = vec!["p.storage_id = $1".to_string()];
-        let mut params: Vec<SqlParam> = Vec::new();
-        let mut param_index = 2usize;
-
-        for (index, segment) in path_segments.iter().enumerate() {
-            let adapter = BrowseDimensionSqlAdapter::new(options.dimensions[index].clone());
-            let param = adapter.parse_segment_value(segment)?;
-            where_clauses.push(adapter.filter_clause(param_index));
-            params.push(param);
-            param_index += 1;
-        }
+        let (mut where_clauses, params, mut param_index) = Self::build_filters(path_segments, options)?;
 
         let order_dir = BrowseDimensionSqlAdapter::order_direction(&options.sort_direction);
+        let (sort_expr, sort_cast) = BrowseDimensionSqlAdapter::sort_expression(options.sort);
 
+        let scope = self.cursor_scope(storage_id, path_segments, options);
         let start = std::time::Instant::now();
-        let mut cursor_values: Option<(DateTime<Utc>, Uuid)> = None;
+        let mut cursor_values: Option<(String, Uuid)> = None;
         if let Some(cursor_value) = cursor {
-            let condition = if order_dir == "DESC" {
-                format!(
-                    "(p.sort_date < ${} OR (p.sort_date = ${} AND p.id < ${}))",
-                    param_index,
-                    param_index,
-                    param_index + 1
-                )
-            } else {
-                format!(
-                    "(p.sort_date > ${} OR (p.sort_date = ${} AND p.id > ${}))",
-                    param_index,
-                    param_index,
-                    param_index + 1
-                )
-            };
-            where_clauses.push(condition);
-            cursor_values = Some((cursor_value.sort_date, cursor_value.id));
+            if !cursor_value.matches(options.sort, options.sort_direction)
+                || cursor_value.scope.as_deref() != Some(scope.as_str())
+            {
+                return Err(anyhow!(Self::CURSOR_MISMATCH));
+            }
+            let comparison = if order_dir == "DESC" { "<" } else { ">" };
+            where_clauses.push(format!(
+                "({sort_expr} {comparison} ${key}::{sort_cast} OR ({sort_expr} = ${key}::{sort_cast} AND p.id {comparison} ${id}))",
+                key = param_index,
+                id = param_index + 1
+            ));
+            cursor_values = Some((cursor_value.sort_key(), cursor_value.id));
             param_index += 2;
         }
 
@@ -171,10 +157,11 @@ impl BrowseService {
             "SELECT p.id, p.storage_id, p.name AS file_name, p.name, p.format, p.hash, p.size, p.created_at,
                     p.updated_at, p.date_imported, p.date_taken, p.year, p.month_day, p.metadata_extracted,
                     p.artist, p.make, p.model, p.lens_make, p.lens_model, p.exposure_time, p.iso, p.focal_length,
-                    p.label, p.rating, p.flagged, p.is_raw, p.width, p.height, p.orientation, p.day_date, p.sort_date
+                    p.label, p.rating, p.flagged, p.is_raw, p.width, p.height, p.orientation, p.day_date, p.sort_date,
+                    ({sort_expr})::text AS sort_key
              FROM photos p
              WHERE {}
-             ORDER BY p.sort_date {order_dir}, p.id {order_dir}
+             ORDER BY {sort_expr} {order_dir}, p.id {order_dir}
              LIMIT ${}",
             where_clauses.join(" AND "),
             param_index
@@ -193,10 +180,11 @@ impl BrowseService {
             query = match param {
                 SqlParam::Int(value) => query.bind(value),
                 SqlParam::String(value) => query.bind(value),
+                SqlParam::TextList(values) => query.bind(values),
             };
         }
-        if let Some((cursor_date, cursor_id)) = cursor_values {
-            query = query.bind(cursor_date).bind(cursor_id);
+        if let Some((cursor_key, cursor_id)) = cursor_values {
+            query = query.bind(cursor_key).bind(cursor_id);
         }
         query = query.bind(normalized_size + 1);
 
@@ -206,9 +194,10 @@ impl BrowseService {
         let has_next = rows.len() as i64 > normalized_size;
         let rows = if has_next { rows.into_iter().take(normalized_size as usize).collect::<Vec<_>>() } else { rows };
 
-        let mut entries = Vec::<(BrowsePhoto, DateTime<Utc>)>::new();
+        let mut entries = Vec::<(BrowsePhoto, String)>::new();
         for row in rows {
             let sort_date: DateTime<Utc> = row.try_get("sort_date")?;
+            let sort_key: String = row.try_get("sort_key")?;
             let photo = BrowsePhoto {
                 id: row.try_get("id")?,
                 storage_id: row.try_get("storage_id")?,
@@ -242,11 +231,16 @@ impl BrowseService {
                 day_date: row.try_get("day_date")?,
                 sort_date: sort_date.clone(),
             };
-            entries.push((photo, sort_date));
+            entries.push((photo, sort_key));
         }
 
         let next_cursor = if has_next {
-            entries.last().map(|(photo, sort_date)| PhotoCursor { sort_date: sort_date.clone(), id: photo.id }.encode())
+            entries.last().map(|(photo, sort_key)| {
+                PhotoCursor::new(photo.sort_date, photo.id)
+                    .with_sort(options.sort, options.sort_direction, sort_key.clone())
+                    .with_scope(scope.clone())
+                    .encode(&self.signer)
+            })
         } else {
             None
         };
@@ -256,4 +250,52 @@ impl BrowseService {
 
         Ok(BrowseResponse { node_type: BrowseNodeType::Photos, folders: None, photos: Some(photos), next_cursor })
     }
+
+    pub fn cursor_scope(&self, storage_id: &Uuid, path_segments: &[String], options: &BrowseOptions) -> String {
+        let storage = storage_id.to_string();
+        let path = path_segments.join("/");
+        let dimensions = format!("{:?}", options.dimensions);
+        let sort = format!("{:?}/{:?}", options.sort, options.sort_direction);
+        self.signer.scope(&[
+            &storage,
+            &path,
+            &dimensions,
+            &sort,
+            options.filter.as_deref().unwrap_or_default(),
+            options.search.as_deref().unwrap_or_default(),
+        ])
+    }
+
+    fn build_filters(path_segments: &[String], options: &BrowseOptions) -> Result<(Vec<String>, Vec<SqlParam>, usize)> {
+        let mut where_clauses = vec!["p.storage_id = $1".to_string()];
+        let mut params: Vec<SqlParam> = Vec::new();
+        let mut param_index = 2usize;
+
+        for (index, segment) in path_segments.iter().enumerate() {
+            let adapter = BrowseDimensionSqlAdapter::new(options.dimensions[index].clone());
+            let param =
+                adapter.parse_segment_value(segment).map_err(|_| BrowsePathError::InvalidSegment(segment.clone()))?;
+            where_clauses.push(adapter.filter_clause(param_index));
+            params.push(param);
+            param_index += 1;
+        }
+
+        let name_patterns = options.name_patterns()?;
+        if !name_patterns.is_empty() {
+            where_clauses.push(format!("p.name ILIKE ANY(${}::text[])", param_index));
+            params.push(SqlParam::TextList(name_patterns));
+            param_index += 1;
+        }
+
+        if let Some(search) = options.search_pattern() {
+            where_clauses.push(format!(
+                "(p.name ILIKE ${index} OR p.title ILIKE ${index} OR p.caption ILIKE ${index})",
+                index = param_index
+            ));
+            params.push(SqlParam::String(search));
+            param_index += 1;
+        }
+
+        Ok((where_clauses, params, param_index))
+    }
 }
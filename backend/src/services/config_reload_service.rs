@@ -0,0 +1,253 @@
+use crate::prelude::*;
+use serde_json::Value as JsonValue;
+use std::sync::RwLock;
+use tokio::sync::Mutex as AsyncMutex;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigReloadPlan {
+    pub applied: Vec<String>,
+    pub restart_required: Vec<String>,
+}
+
+impl ConfigReloadPlan {
+    fn record(&mut self, key: &str, changed: bool, hot: bool) {
+        if !changed {
+            return;
+        }
+        if hot {
+            self.applied.push(key.to_string());
+        } else {
+            self.restart_required.push(key.to_string());
+        }
+    }
+}
+
+pub struct ConfigReloadService {
+    services: Arc<ServiceProvider>,
+    config_file: PathBuf,
+    current: RwLock<Arc<AppConfig>>,
+    last_result: RwLock<Option<ConfigReloadResult>>,
+    lock: AsyncMutex<()>,
+}
+
+impl ConfigReloadService {
+    pub const DEFAULT_CONFIG_FILE: &'static str = "web.config.json";
+    // Read by nimble_web while building the app, so they never show up in AppConfig.
+    const RESTART_ONLY_KEYS: [&'static str; 4] =
+        ["postgres.url", "postgres.poolSize", "postgres.timeout", "jwt.expirationMinutes"];
+
+    pub fn new(services: Arc<ServiceProvider>, config_file: impl Into<PathBuf>) -> Self {
+        let current = services.get::<AppConfig>();
+        Self {
+            services,
+            config_file: config_file.into(),
+            current: RwLock::new(current),
+            last_result: RwLock::new(None),
+            lock: AsyncMutex::new(()),
+        }
+    }
+
+    pub fn config_file(&self) -> &Path {
+        &self.config_file
+    }
+
+    pub fn current(&self) -> Arc<AppConfig> {
+        self.current.read().map(|current| Arc::clone(&current)).unwrap_or_else(|_| self.services.get::<AppConfig>())
+    }
+
+    pub fn last_result(&self) -> Option<ConfigReloadResult> {
+        self.last_result.read().ok().and_then(|last| last.clone())
+    }
+
+    pub async fn reload(&self) -> ConfigReloadResult {
+        let _guard = self.lock.lock().await;
+        let result = self.try_reload().await.unwrap_or_else(ConfigReloadResult::failed);
+        if let Ok(mut last) = self.last_result.write() {
+            *last = Some(result.clone());
+        }
+        result
+    }
+
+    async fn try_reload(&self) -> Result<ConfigReloadResult, String> {
+        let contents = tokio::fs::read_to_string(&self.config_file)
+            .await
+            .map_err(|err| format!("failed to read {}: {}", self.config_file.display(), err))?;
+        let file_values = Self::flatten(&contents)?;
+
+        // Keys missing from the file fall back to the running configuration, which also carries env overrides.
+        let configuration = self.services.get::<Configuration>();
+        let lookup = |key: &str| {
+            file_values
+                .get(&key.to_ascii_lowercase())
+                .cloned()
+                .or_else(|| configuration.get(key).map(ToString::to_string))
+        };
+        let next = AppConfig::from_lookup(&lookup).map_err(|err| err.to_string())?;
+
+        let current = self.current();
+        let mut plan = Self::plan(&current, &next);
+        for key in Self::RESTART_ONLY_KEYS {
+            if lookup(key) != configuration.get(key).map(ToString::to_string) {
+                plan.restart_required.push(key.to_string());
+            }
+        }
+
+        let effective = Arc::new(Self::merge(&current, &next));
+        self.apply(&current, &effective)?;
+        if let Ok(mut guard) = self.current.write() {
+            *guard = effective;
+        }
+
+        Ok(ConfigReloadResult {
+            reloaded_at: Utc::now(),
+            success: true,
+            applied: plan.applied,
+            restart_required: plan.restart_required,
+            warnings: next.warnings,
+            error: None,
+        })
+    }
+
+    pub fn plan(current: &AppConfig, next: &AppConfig) -> ConfigReloadPlan {
+        let mut plan = ConfigReloadPlan::default();
+
+        plan.record("logging.level", current.log_level != next.log_level, true);
+        plan.record(
+            "logging.requestBodyOnError",
+            current.log_request_body_on_error != next.log_request_body_on_error,
+            true,
+        );
+        plan.record("logging.requestBodyMaxBytes", current.request_body_max_bytes != next.request_body_max_bytes, true);
+        plan.record(
+            "upload.maxFileSizeBytes",
+            current.upload_max_file_size_bytes != next.upload_max_file_size_bytes,
+            true,
+        );
+        plan.record(
+            "preview.maxConcurrentExtractions",
+            current.preview_max_concurrent_extractions != next.preview_max_concurrent_extractions,
+            true,
+        );
+        plan.record(
+            "preview.extractionTimeoutSeconds",
+            current.preview_extraction_timeout_seconds != next.preview_extraction_timeout_seconds,
+            true,
+        );
+        plan.record("background.parallelism", current.background_parallelism != next.background_parallelism, true);
+        plan.record("metrics.token", current.metrics_token != next.metrics_token, true);
+        plan.record("metadata.exiftoolPath", current.exiftool_path != next.exiftool_path, true);
+
+        plan.record("app.environment", current.environment != next.environment, false);
+        plan.record("app.secretsFile", current.secrets_file != next.secrets_file, false);
+        plan.record("jwt.secret", current.jwt_secret != next.jwt_secret, false);
+        plan.record("jwt.issuer", current.jwt_issuer != next.jwt_issuer, false);
+        plan.record("cursor.secret", current.cursor_secret != next.cursor_secret, false);
+        plan.record("encryption.key", current.encryption_key != next.encryption_key, false);
+        plan.record("eventbus.capacity", current.eventbus_capacity != next.eventbus_capacity, false);
+        plan.record("upload.chunkSizeBytes", current.upload_chunk_size_bytes != next.upload_chunk_size_bytes, false);
+        plan.record(
+            "upload.sessionTtlMinutes",
+            current.upload_session_ttl_minutes != next.upload_session_ttl_minutes,
+            false,
+        );
+        plan.record("storage.diskRefreshSeconds", current.disk_refresh_seconds != next.disk_refresh_seconds, false);
+        plan.record("storage.allowSymlinkEscape", current.allow_symlink_escape != next.allow_symlink_escape, false);
+        plan.record("thumbnail.basePath", current.thumbnail_base_path != next.thumbnail_base_path, false);
+        plan.record("albums.autoGapHours", current.album_auto_gap_hours != next.album_auto_gap_hours, false);
+        plan.record("pipeline.maxAttempts", current.pipeline_max_attempts != next.pipeline_max_attempts, false);
+        plan.record(
+            "pipeline.jobRetentionDays",
+            current.pipeline_job_retention_days != next.pipeline_job_retention_days,
+            false,
+        );
+        plan.record("shutdown.graceSeconds", current.shutdown_grace_seconds != next.shutdown_grace_seconds, false);
+        plan.record(
+            "database.startupRetries",
+            current.database_startup_retries != next.database_startup_retries,
+            false,
+        );
+        plan.record("database.retryDelayMs", current.database_retry_delay_ms != next.database_retry_delay_ms, false);
+        plan.record("email.smtpHost", current.smtp_host != next.smtp_host, false);
+        plan.record("email.smtpPort", current.smtp_port != next.smtp_port, false);
+        plan.record("email.smtpUsername", current.smtp_username != next.smtp_username, false);
+        plan.record("email.smtpPassword", current.smtp_password != next.smtp_password, false);
+        plan.record("email.from", current.smtp_from != next.smtp_from, false);
+        plan.record("email.startTls", current.smtp_starttls != next.smtp_starttls, false);
+        plan.record("live.enabled", current.live_enabled != next.live_enabled, false);
+        plan.record("live.address", current.live_address != next.live_address, false);
+        plan.record(
+            "live.maxConnectionsPerUser",
+            current.live_max_connections_per_user != next.live_max_connections_per_user,
+            false,
+        );
+
+        plan
+    }
+
+    pub fn merge(current: &AppConfig, next: &AppConfig) -> AppConfig {
+        AppConfig {
+            log_level: next.log_level,
+            log_request_body_on_error: next.log_request_body_on_error,
+            request_body_max_bytes: next.request_body_max_bytes,
+            upload_max_file_size_bytes: next.upload_max_file_size_bytes,
+            preview_max_concurrent_extractions: next.preview_max_concurrent_extractions,
+            preview_extraction_timeout_seconds: next.preview_extraction_timeout_seconds,
+            background_parallelism: next.background_parallelism,
+            metrics_token: next.metrics_token.clone(),
+            exiftool_path: next.exiftool_path.clone(),
+            warnings: next.warnings.clone(),
+            ..current.clone()
+        }
+    }
+
+    pub fn flatten(contents: &str) -> Result<HashMap<String, String>, String> {
+        let value: JsonValue = serde_json::from_str(contents).map_err(|err| format!("invalid JSON: {}", err))?;
+        let mut values = HashMap::new();
+        Self::flatten_into(&value, "", &mut values);
+        Ok(values)
+    }
+
+    fn flatten_into(value: &JsonValue, prefix: &str, values: &mut HashMap<String, String>) {
+        match value {
+            JsonValue::Object(map) => {
+                for (key, child) in map {
+                    let key = key.to_ascii_lowercase();
+                    let path = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                    Self::flatten_into(child, &path, values);
+                }
+            }
+            JsonValue::Null => {}
+            JsonValue::String(text) => {
+                values.insert(prefix.to_string(), text.clone());
+            }
+            other => {
+                values.insert(prefix.to_string(), other.to_string());
+            }
+        }
+    }
+
+    fn apply(&self, previous: &AppConfig, config: &AppConfig) -> Result<(), String> {
+        if previous.log_level != config.log_level {
+            // Without an explicit level, the filter chosen by env_logger at startup takes over again.
+            log::set_max_level(config.log_level.unwrap_or(log::LevelFilter::Trace));
+        }
+        if previous.upload_max_file_size_bytes != config.upload_max_file_size_bytes {
+            self.services.get::<PhotoUploadService>().set_max_file_size(config.upload_max_file_size_bytes);
+            self.services.get::<SyncService>().set_max_file_size(config.upload_max_file_size_bytes);
+        }
+        if previous.preview_max_concurrent_extractions != config.preview_max_concurrent_extractions
+            || previous.preview_extraction_timeout_seconds != config.preview_extraction_timeout_seconds
+        {
+            let coordinator = self.services.get::<PreviewCoordinator>();
+            coordinator.set_max_concurrent_extractions(config.preview_max_concurrent_extractions);
+            coordinator.set_acquire_timeout(std::time::Duration::from_secs(config.preview_extraction_timeout_seconds));
+        }
+        if previous.background_parallelism != config.background_parallelism {
+            self.services
+                .get::<BackgroundTaskRunner>()
+                .set_parallelism(config.background_parallelism)
+                .map_err(|err| format!("failed to resize background runner: {}", err))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use crate::prelude::*;
+use std::sync::RwLock;
+
+#[derive(Default)]
+struct CacheState {
+    policy: Option<Arc<CorsPolicy>>,
+    generation: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct CorsPolicyCache {
+    state: Arc<RwLock<CacheState>>,
+}
+
+impl CorsPolicyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn policy(
+        &self,
+        settings: &SettingService,
+        environment: AppEnvironment,
+    ) -> Result<Arc<CorsPolicy>, PipelineError> {
+        let generation = {
+            let state = self.state.read().map_err(|_| PipelineError::message("cors policy cache poisoned"))?;
+            if let Some(policy) = state.policy.as_ref() {
+                return Ok(Arc::clone(policy));
+            }
+            state.generation
+        };
+
+        let policy = Arc::new(settings.cors_policy(environment).await?);
+
+        if let Ok(mut state) = self.state.write() {
+            if state.generation == generation {
+                state.policy = Some(Arc::clone(&policy));
+            }
+        }
+
+        Ok(policy)
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.state.read().map(|state| state.policy.is_some()).unwrap_or(false)
+    }
+
+    pub fn invalidate(&self) {
+        if let Ok(mut state) = self.state.write() {
+            state.policy = None;
+            state.generation = state.generation.wrapping_add(1);
+        }
+    }
+}
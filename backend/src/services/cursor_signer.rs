@@ -0,0 +1,50 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Clone)]
+pub struct CursorSigner {
+    key: Vec<u8>,
+}
+
+impl CursorSigner {
+    pub const MAC_LENGTH: usize = 16;
+    const KEY_CONTEXT: &'static [u8] = b"nimble-photos/cursor";
+    const SCOPE_LENGTH: usize = 8;
+
+    pub fn new(secret: &str) -> Self {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(Self::KEY_CONTEXT);
+        Self { key: mac.finalize().into_bytes().to_vec() }
+    }
+
+    pub fn sign(&self, payload: &[u8]) -> Vec<u8> {
+        let mut mac = self.mac();
+        mac.update(payload);
+        mac.finalize().into_bytes()[..Self::MAC_LENGTH].to_vec()
+    }
+
+    pub fn verify(&self, payload: &[u8], signature: &[u8]) -> bool {
+        if signature.len() != Self::MAC_LENGTH {
+            return false;
+        }
+        let mut mac = self.mac();
+        mac.update(payload);
+        mac.verify_truncated_left(signature).is_ok()
+    }
+
+    pub fn scope(&self, parts: &[&str]) -> String {
+        let mut mac = self.mac();
+        mac.update(b"scope");
+        for part in parts {
+            mac.update(&(part.len() as u64).to_le_bytes());
+            mac.update(part.as_bytes());
+        }
+        mac.finalize().into_bytes()[..Self::SCOPE_LENGTH].iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length")
+    }
+}
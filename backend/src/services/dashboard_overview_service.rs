@@ -0,0 +1,39 @@
+use crate::prelude::*;
+
+// The overview is informational, so a minute-old snapshot is fine and keeps dashboard refreshes
+// from re-running the aggregates.
+pub struct DashboardOverviewService {
+    photos: Arc<Repository<Photo>>,
+    cache: Mutex<Option<(Instant, Arc<DashboardOverview>)>>,
+}
+
+impl DashboardOverviewService {
+    pub const CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self { photos: services.get::<Repository<Photo>>(), cache: Mutex::new(None) }
+    }
+
+    pub async fn overview(&self) -> Result<Arc<DashboardOverview>, PipelineError> {
+        if let Some(overview) = self.cached() {
+            return Ok(overview);
+        }
+
+        let now = Utc::now();
+        let totals = self.photos.dashboard_totals(now).await?;
+        let storage = self.photos.storage_usage().await?;
+        let overview = Arc::new(DashboardOverview { totals, storage, last_backup_at: None, generated_at: now });
+
+        let mut cache = self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *cache = Some((Instant::now(), Arc::clone(&overview)));
+        Ok(overview)
+    }
+
+    fn cached(&self) -> Option<Arc<DashboardOverview>> {
+        let cache = self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cache
+            .as_ref()
+            .filter(|(cached_at, _)| cached_at.elapsed() < Self::CACHE_TTL)
+            .map(|(_, overview)| Arc::clone(overview))
+    }
+}
@@ -0,0 +1,128 @@
+use crate::prelude::*;
+use anyhow::{Result, anyhow};
+use sqlx::PgPool;
+use std::future::Future;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration as StdDuration;
+use tokio::time::{sleep, timeout};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartupRetryPolicy {
+    pub retries: u32,
+    pub base_delay: StdDuration,
+    pub max_delay: StdDuration,
+}
+
+impl StartupRetryPolicy {
+    const MAX_DELAY_SECONDS: u64 = 30;
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            retries: config.database_startup_retries,
+            base_delay: StdDuration::from_millis(config.database_retry_delay_ms),
+            max_delay: StdDuration::from_secs(Self::MAX_DELAY_SECONDS),
+        }
+    }
+
+    pub fn delay_for(&self, attempt: u32) -> StdDuration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    pub async fn run<T, F, Fut>(&self, operation: &str, mut run: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match run().await {
+                Ok(value) => return Ok(value),
+                Err(error) if attempt < self.retries => {
+                    attempt += 1;
+                    let delay = self.delay_for(attempt);
+                    log::warn!(
+                        "{} failed (retry {}/{}), retrying in {:?}: {}",
+                        operation,
+                        attempt,
+                        self.retries,
+                        delay,
+                        error
+                    );
+                    sleep(delay).await;
+                }
+                Err(error) => {
+                    return Err(error.context(format!("{} failed after {} attempt(s)", operation, attempt + 1)));
+                }
+            }
+        }
+    }
+}
+
+pub struct DatabaseHealthService {
+    pool: Arc<PgPool>,
+    migrated: AtomicBool,
+    last_error: RwLock<Option<String>>,
+}
+
+impl DatabaseHealthService {
+    const PING_TIMEOUT_SECONDS: u64 = 2;
+
+    pub fn new(pool: Arc<PgPool>) -> Self {
+        Self { pool, migrated: AtomicBool::new(false), last_error: RwLock::new(None) }
+    }
+
+    pub async fn ping(&self) -> Result<()> {
+        timeout(
+            StdDuration::from_secs(Self::PING_TIMEOUT_SECONDS),
+            sqlx::query("SELECT 1").execute(self.pool.as_ref()),
+        )
+        .await
+        .map_err(|_| anyhow!("database ping timed out"))?
+        .map_err(|err| anyhow!("database ping failed: {}", err))?;
+        Ok(())
+    }
+
+    pub fn is_migrated(&self) -> bool {
+        self.migrated.load(Ordering::SeqCst)
+    }
+
+    pub fn mark_migrated(&self) {
+        self.migrated.store(true, Ordering::SeqCst);
+        self.set_last_error(None);
+    }
+
+    pub fn record_error(&self, error: &str) {
+        self.set_last_error(Some(error.to_string()));
+    }
+
+    pub async fn readiness(&self) -> DatabaseReadinessDto {
+        let migrated = self.is_migrated();
+        let ping = if self.pool.is_closed() { Err(anyhow!("database pool is closed")) } else { self.ping().await };
+        let error = match &ping {
+            Ok(()) if migrated => None,
+            Ok(()) => self.last_error().or_else(|| Some("database migrations have not completed".to_string())),
+            Err(err) => Some(err.to_string()),
+        };
+
+        DatabaseReadinessDto {
+            ready: migrated && ping.is_ok(),
+            migrated,
+            reachable: ping.is_ok(),
+            pool_size: self.pool.size(),
+            idle_connections: self.pool.num_idle(),
+            error,
+        }
+    }
+
+    fn last_error(&self) -> Option<String> {
+        self.last_error.read().ok().and_then(|error| error.clone())
+    }
+
+    fn set_last_error(&self, error: Option<String>) {
+        if let Ok(mut last_error) = self.last_error.write() {
+            *last_error = error;
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use crate::prelude::*;
+use std::sync::RwLock;
+
+#[derive(Default)]
+struct CacheState {
+    profile: Option<Arc<DerivativeProfile>>,
+    generation: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct DerivativeProfileCache {
+    state: Arc<RwLock<CacheState>>,
+}
+
+impl DerivativeProfileCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn profile(&self, settings: &SettingService) -> Result<Arc<DerivativeProfile>, PipelineError> {
+        let generation = {
+            let state = self.state.read().map_err(|_| PipelineError::message("derivative profile cache poisoned"))?;
+            if let Some(profile) = state.profile.as_ref() {
+                return Ok(Arc::clone(profile));
+            }
+            state.generation
+        };
+
+        let profile = Arc::new(settings.derivative_profile().await?);
+
+        if let Ok(mut state) = self.state.write() {
+            if state.generation == generation {
+                state.profile = Some(Arc::clone(&profile));
+            }
+        }
+
+        Ok(profile)
+    }
+
+    // Falls back to the defaults when settings are unavailable, e.g. in pipelines wired without them.
+    pub async fn resolve(services: &ServiceProvider) -> Arc<DerivativeProfile> {
+        let (Some(cache), Some(settings)) = (services.resolve::<Self>(), services.resolve::<SettingService>()) else {
+            return Arc::new(DerivativeProfile::default());
+        };
+        cache.profile(&settings).await.unwrap_or_else(|error| {
+            log::warn!("Using default derivative profile: {:?}", error);
+            Arc::new(DerivativeProfile::default())
+        })
+    }
+
+    pub fn invalidate(&self) {
+        if let Ok(mut state) = self.state.write() {
+            state.profile = None;
+            state.generation = state.generation.wrapping_add(1);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use sysinfo::Disks;
+use tokio::task;
+use tokio::time::Duration;
+
+type DiskLoader = Arc<dyn Fn() -> Vec<DiskInfo> + Send + Sync>;
+
+struct DiskSnapshot {
+    disks: Vec<DiskInfo>,
+    refreshed_at: Instant,
+}
+
+#[derive(Clone)]
+pub struct DiskInfoService {
+    snapshot: Arc<Mutex<Option<DiskSnapshot>>>,
+    refreshing: Arc<AtomicBool>,
+    refresh_interval: Duration,
+    loader: DiskLoader,
+}
+
+impl DiskInfoService {
+    pub const DEFAULT_REFRESH_INTERVAL_SECONDS: u64 = 30;
+
+    pub fn new(refresh_interval: Duration) -> Self {
+        Self::with_loader(refresh_interval, Self::load_disks)
+    }
+
+    pub fn with_loader<F>(refresh_interval: Duration, loader: F) -> Self
+    where
+        F: Fn() -> Vec<DiskInfo> + Send + Sync + 'static,
+    {
+        Self {
+            snapshot: Arc::new(Mutex::new(None)),
+            refreshing: Arc::new(AtomicBool::new(false)),
+            refresh_interval,
+            loader: Arc::new(loader),
+        }
+    }
+
+    pub async fn disks(&self) -> Vec<DiskInfo> {
+        let cached = self.snapshot.lock().ok().and_then(|snapshot| {
+            snapshot.as_ref().map(|snapshot| (snapshot.disks.clone(), snapshot.refreshed_at.elapsed()))
+        });
+
+        match cached {
+            Some((disks, age)) => {
+                if age >= self.refresh_interval {
+                    self.refresh_in_background();
+                }
+                disks
+            }
+            None => self.refresh().await,
+        }
+    }
+
+    pub async fn refresh(&self) -> Vec<DiskInfo> {
+        let loader = Arc::clone(&self.loader);
+        let disks = task::spawn_blocking(move || loader()).await.unwrap_or_else(|err| {
+            log::warn!("Disk refresh task failed: {:?}", err);
+            Vec::new()
+        });
+        self.store(disks.clone());
+        disks
+    }
+
+    pub fn find_disk(path: &str, disks: &[DiskInfo]) -> Option<DiskInfo> {
+        let path_lower = path.to_ascii_lowercase();
+        disks
+            .iter()
+            .filter(|disk| !disk.mount_point.is_empty())
+            .filter(|disk| path_lower.starts_with(&disk.mount_point.to_ascii_lowercase()))
+            .max_by_key(|disk| disk.mount_point.len())
+            .cloned()
+    }
+
+    fn refresh_in_background(&self) {
+        if self.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            service.refresh().await;
+            service.refreshing.store(false, Ordering::Release);
+        });
+    }
+
+    fn store(&self, disks: Vec<DiskInfo>) {
+        if let Ok(mut snapshot) = self.snapshot.lock() {
+            *snapshot = Some(DiskSnapshot { disks, refreshed_at: Instant::now() });
+        }
+    }
+
+    fn load_disks() -> Vec<DiskInfo> {
+        let disks = Disks::new_with_refreshed_list();
+
+        let mut items = disks
+            .list()
+            .iter()
+            .filter(|disk| !disk.is_removable())
+            .map(|disk| DiskInfo {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point: disk.mount_point().to_string_lossy().to_string(),
+                total_bytes: disk.total_space(),
+                available_bytes: disk.available_space(),
+            })
+            .collect::<Vec<_>>();
+
+        items.sort_by_key(|disk| Self::disk_sort_key(&disk.mount_point));
+        items
+    }
+
+    fn disk_sort_key(mount_point: &str) -> (u8, String) {
+        let normalized = mount_point.trim().to_ascii_lowercase();
+        let bytes = normalized.as_bytes();
+        if bytes.len() >= 2 && bytes[1] == b':' {
+            return (0, normalized);
+        }
+        (1, normalized)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::prelude::*;
+use anyhow::{Context, Result, anyhow};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+#[derive(Clone)]
+pub struct EmailService {
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: Option<Mailbox>,
+}
+
+impl EmailService {
+    pub fn disabled() -> Self {
+        Self { transport: None, from: None }
+    }
+
+    // A missing or broken SMTP section disables sending rather than failing startup.
+    pub fn from_config(config: &AppConfig) -> Self {
+        match Self::build(config) {
+            Ok(Some(service)) => service,
+            Ok(None) => Self::disabled(),
+            Err(error) => {
+                log::warn!("Email delivery disabled: {:#}", error);
+                Self::disabled()
+            }
+        }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.transport.is_some() && self.from.is_some()
+    }
+
+    pub async fn send(&self, message: &EmailMessage) -> Result<()> {
+        let (Some(transport), Some(from)) = (&self.transport, &self.from) else {
+            return Err(anyhow!("SMTP is not configured"));
+        };
+        let to = message.to.parse::<Mailbox>().with_context(|| format!("invalid recipient '{}'", message.to))?;
+        let email = Message::builder()
+            .from(from.clone())
+            .to(to)
+            .subject(&message.subject)
+            .multipart(MultiPart::alternative_plain_html(message.text.clone(), message.html.clone()))
+            .context("failed to build email")?;
+        transport.send(email).await.with_context(|| format!("failed to send email to {}", message.to))?;
+        Ok(())
+    }
+
+    fn build(config: &AppConfig) -> Result<Option<Self>> {
+        let (Some(host), Some(from)) = (&config.smtp_host, &config.smtp_from) else {
+            return Ok(None);
+        };
+        let from = from.parse::<Mailbox>().with_context(|| format!("email.from '{}' is not a valid address", from))?;
+        let mut builder = if config.smtp_starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .with_context(|| format!("email.smtpHost '{}' is not valid", host))?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+        };
+        builder = builder.port(config.smtp_port);
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(Some(Self { transport: Some(builder.build()), from: Some(from) }))
+    }
+}
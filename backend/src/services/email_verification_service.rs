@@ -0,0 +1,69 @@
+use crate::prelude::*;
+
+pub struct EmailVerificationService {
+    auth: Arc<AuthService>,
+    email: Arc<EmailService>,
+    settings: Arc<SettingService>,
+    throttle: ResendThrottle,
+}
+
+impl EmailVerificationService {
+    pub const RESEND_COOLDOWN_SECONDS: i64 = 60;
+    const DEFAULT_TITLE: &'static str = "Nimble Photos";
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            auth: services.get::<AuthService>(),
+            email: services.get::<EmailService>(),
+            settings: services.get::<SettingService>(),
+            throttle: ResendThrottle::new(Duration::seconds(Self::RESEND_COOLDOWN_SECONDS)),
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.email.is_configured()
+    }
+
+    // Sent right after sign-up. Returns whether the account still has to verify; admins never do.
+    pub async fn start(&self, email: &str, base_url: &str) -> Result<bool, PipelineError> {
+        let Some(token) = self.auth.reissue_verification_token(email).await? else {
+            return Ok(false);
+        };
+        self.throttle.try_acquire(email, Utc::now());
+        if self.is_available() {
+            self.deliver(email, base_url, &token).await;
+        } else {
+            log::warn!("Email verification is required but SMTP is not configured; {} cannot verify", email);
+        }
+        Ok(true)
+    }
+
+    // Returns false while the address is cooling down. Unknown and already verified addresses count
+    // as sent so the answer doesn't reveal who has an account.
+    pub async fn resend(&self, email: &str, base_url: &str) -> Result<bool, PipelineError> {
+        if !self.throttle.try_acquire(email, Utc::now()) {
+            return Ok(false);
+        }
+        if let Some(token) = self.auth.reissue_verification_token(email).await? {
+            self.deliver(email, base_url, &token).await;
+        }
+        Ok(true)
+    }
+
+    async fn deliver(&self, email: &str, base_url: &str, token: &str) {
+        let site_title = match self.settings.site_title().await {
+            Ok(title) if !title.is_empty() => title,
+            _ => Self::DEFAULT_TITLE.to_string(),
+        };
+        let content = VerificationEmail::new(&site_title, base_url, token);
+        let message = EmailMessage {
+            to: email.to_string(),
+            subject: content.subject(),
+            text: content.render_text(),
+            html: content.render_html(),
+        };
+        if let Err(error) = self.email.send(&message).await {
+            log::warn!("Verification email to {} failed: {:#}", email, error);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::prelude::*;
+
+pub struct EmbedService {
+    photos: Arc<Repository<Photo>>,
+    albums: Arc<Repository<Album>>,
+    tags: Arc<Repository<Tag>>,
+    settings: Arc<SettingService>,
+}
+
+impl EmbedService {
+    const DEFAULT_TITLE: &'static str = "Nimble Photos";
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photos: services.get::<Repository<Photo>>(),
+            albums: services.get::<Repository<Album>>(),
+            tags: services.get::<Repository<Tag>>(),
+            settings: services.get::<SettingService>(),
+        }
+    }
+
+    pub async fn is_enabled(&self) -> Result<bool, PipelineError> {
+        self.settings.is_site_public().await
+    }
+
+    // Hidden photos resolve to None exactly like missing ones, so embeds cannot be used to probe for them.
+    pub async fn photo(&self, target: &EmbedTarget, base_url: &str) -> Result<Option<PhotoEmbed>, PipelineError> {
+        let photo = match target {
+            EmbedTarget::Photo(id) => self.photos.get(id).await.map_err(|_| PipelineError::message("data error"))?,
+            EmbedTarget::SharedPhoto(hash) => Photo::select_by_hash(self.photos.find_by_hash_all(hash).await?, None),
+            EmbedTarget::Album(_) => None,
+        };
+        let Some(photo) = photo else {
+            return Ok(None);
+        };
+        let Some(hash) = photo.hash.clone() else {
+            return Ok(None);
+        };
+        if self.tags.is_hash_admin_only(&hash).await? {
+            return Ok(None);
+        }
+
+        let site_title = self.site_title().await?;
+        Ok(Some(PhotoEmbed::new(&site_title, base_url, &photo, &hash)))
+    }
+
+    pub async fn album(&self, album_id: Uuid, base_url: &str) -> Result<Option<AlbumEmbed>, PipelineError> {
+        let album = self.albums.get(&album_id).await.map_err(|_| PipelineError::message("data error"))?;
+        let Some(album) = album else {
+            return Ok(None);
+        };
+
+        let cover = match album.thumbnail_hash.as_deref().and_then(HashService::normalize) {
+            Some(hash) => self.photo(&EmbedTarget::SharedPhoto(hash), base_url).await?,
+            None => None,
+        };
+        Ok(Some(AlbumEmbed {
+            site_title: self.site_title().await?,
+            base_url: base_url.to_string(),
+            album_id: album.id,
+            name: album.name,
+            cover,
+        }))
+    }
+
+    async fn site_title(&self) -> Result<String, PipelineError> {
+        let title = self.settings.site_title().await?;
+        Ok(if title.is_empty() { Self::DEFAULT_TITLE.to_string() } else { title })
+    }
+}
@@ -13,6 +13,10 @@ pub struct EncryptService {
 impl EncryptService {
     pub fn new(config: &Configuration) -> Result<Self> {
         let key_b64 = config.get("encryption.key").ok_or_else(|| anyhow!("encryption key not configured"))?;
+        Self::from_key(key_b64)
+    }
+
+    pub fn from_key(key_b64: &str) -> Result<Self> {
         let key_bytes = STANDARD.decode(key_b64)?;
         if key_bytes.len() != 32 {
             return Err(anyhow!("encryption key must be 32 bytes"));
@@ -0,0 +1,203 @@
+use crate::prelude::*;
+use anyhow::{Result, anyhow};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCandidate {
+    pub id: Uuid,
+    pub hash: Option<String>,
+    #[serde(alias = "taken_at")]
+    pub taken_at: DateTime<Utc>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl EventCandidate {
+    fn location(&self) -> Option<(f64, f64)> {
+        match (self.latitude, self.longitude) {
+            (Some(lat), Some(lon)) if lat != 0.0 || lon != 0.0 => Some((lat, lon)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EventClusterOptions {
+    pub gap: Duration,
+    pub max_distance_km: Option<f64>,
+    pub min_photos: usize,
+}
+
+impl Default for EventClusterOptions {
+    fn default() -> Self {
+        Self {
+            gap: Duration::hours(EventAlbumService::DEFAULT_GAP_HOURS),
+            max_distance_km: None,
+            min_photos: EventAlbumService::DEFAULT_MIN_PHOTOS,
+        }
+    }
+}
+
+impl EventClusterOptions {
+    pub fn with_request(mut self, request: &AutoAlbumRequest) -> Self {
+        if let Some(hours) = request.gap_hours.filter(|hours| hours.is_finite() && *hours > 0.0) {
+            self.gap = Duration::seconds((hours * 3600.0) as i64);
+        }
+        if let Some(distance) = request.max_distance_km.filter(|distance| distance.is_finite() && *distance > 0.0) {
+            self.max_distance_km = Some(distance);
+        }
+        if let Some(min_photos) = request.min_photos {
+            self.min_photos = min_photos.max(1);
+        }
+        self
+    }
+}
+
+pub struct EventAlbumService {
+    photo_repo: Arc<Repository<Photo>>,
+    album_repo: Arc<Repository<Album>>,
+    album_photo_repo: Arc<Repository<AlbumPhoto>>,
+    options: EventClusterOptions,
+}
+
+impl EventAlbumService {
+    pub const DEFAULT_GAP_HOURS: i64 = 6;
+    pub const DEFAULT_MIN_PHOTOS: usize = 3;
+    pub const DRAFT_CATEGORY: &'static str = "auto-draft";
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+
+    pub fn new(services: Arc<ServiceProvider>, options: EventClusterOptions) -> Self {
+        Self {
+            photo_repo: services.get::<Repository<Photo>>(),
+            album_repo: services.get::<Repository<Album>>(),
+            album_photo_repo: services.get::<Repository<AlbumPhoto>>(),
+            options,
+        }
+    }
+
+    pub async fn generate(&self, request: &AutoAlbumRequest) -> Result<AutoAlbumResponse> {
+        let options = self.options.with_request(request);
+        let candidates = self
+            .photo_repo
+            .photos_without_album()
+            .await
+            .map_err(|err| anyhow!("failed to load photos without album: {:?}", err))?;
+        let scanned = candidates.len();
+
+        let mut albums = Vec::new();
+        for cluster in Self::cluster(candidates, &options) {
+            let mut proposal = Self::proposal(&cluster);
+            if !request.dry_run {
+                proposal.album_id = Some(self.persist(&proposal).await?);
+            }
+            albums.push(proposal);
+        }
+
+        log::info!(
+            "Auto album generation scanned {} photos and {} {} albums",
+            scanned,
+            if request.dry_run { "proposed" } else { "created" },
+            albums.len()
+        );
+        Ok(AutoAlbumResponse { dry_run: request.dry_run, scanned, albums })
+    }
+
+    pub fn cluster(mut candidates: Vec<EventCandidate>, options: &EventClusterOptions) -> Vec<Vec<EventCandidate>> {
+        candidates.sort_by(|left, right| left.taken_at.cmp(&right.taken_at).then(left.id.cmp(&right.id)));
+
+        let mut clusters: Vec<Vec<EventCandidate>> = Vec::new();
+        let mut current: Vec<EventCandidate> = Vec::new();
+        let mut last_location: Option<(f64, f64)> = None;
+
+        for candidate in candidates {
+            let splits = current.last().is_some_and(|previous| {
+                let gap_exceeded = candidate.taken_at - previous.taken_at > options.gap;
+                let moved_away = match (options.max_distance_km, last_location, candidate.location()) {
+                    (Some(limit), Some(from), Some(to)) => Self::distance_km(from, to) > limit,
+                    _ => false,
+                };
+                gap_exceeded || moved_away
+            });
+
+            if splits {
+                clusters.push(std::mem::take(&mut current));
+                last_location = None;
+            }
+            if let Some(location) = candidate.location() {
+                last_location = Some(location);
+            }
+            current.push(candidate);
+        }
+        if !current.is_empty() {
+            clusters.push(current);
+        }
+
+        clusters.into_iter().filter(|cluster| cluster.len() >= options.min_photos).collect()
+    }
+
+    pub fn title(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+        let (start, end) = (start.date_naive(), end.date_naive());
+        if start == end {
+            start.format("%-d %b %Y").to_string()
+        } else if start.year() == end.year() && start.month() == end.month() {
+            format!("{}–{}", start.format("%-d"), end.format("%-d %b %Y"))
+        } else if start.year() == end.year() {
+            format!("{} – {}", start.format("%-d %b"), end.format("%-d %b %Y"))
+        } else {
+            format!("{} – {}", start.format("%-d %b %Y"), end.format("%-d %b %Y"))
+        }
+    }
+
+    fn proposal(cluster: &[EventCandidate]) -> AutoAlbumProposal {
+        let start = cluster.first().map(|photo| photo.taken_at).unwrap_or_else(Utc::now);
+        let end = cluster.last().map(|photo| photo.taken_at).unwrap_or(start);
+        AutoAlbumProposal {
+            album_id: None,
+            title: Self::title(start, end),
+            start,
+            end,
+            photo_count: cluster.len(),
+            thumbnail_hash: cluster.iter().find_map(|photo| photo.hash.clone()),
+            photo_ids: cluster.iter().map(|photo| photo.id).collect(),
+        }
+    }
+
+    async fn persist(&self, proposal: &AutoAlbumProposal) -> Result<Uuid> {
+        let album = Album {
+            id: Uuid::new_v4(),
+            parent_id: None,
+            name: proposal.title.clone(),
+            create_date: Some(Utc::now()),
+            description: None,
+            category: Some(Self::DRAFT_CATEGORY.to_string()),
+            kind: AlbumKind::Manual,
+            thumbnail_hash: proposal.thumbnail_hash.clone(),
+            sort_order: 0,
+            image_count: Some(proposal.photo_count as i64),
+            version: 1,
+            start_date: None,
+            end_date: None,
+            dates_overridden: false,
+            location_name: None,
+            reset_dates: false,
+        };
+        let album_id = album.id;
+        self.album_repo.insert(album).await.map_err(|err| anyhow!("failed to create album: {:?}", err))?;
+        self.album_photo_repo
+            .add_photos_to_album(album_id, &proposal.photo_ids)
+            .await
+            .map_err(|err| anyhow!("failed to add album photos: {:?}", err))?;
+        self.album_repo
+            .refresh_album_dates(album_id)
+            .await
+            .map_err(|err| anyhow!("failed to derive album dates: {:?}", err))?;
+        Ok(album_id)
+    }
+
+    fn distance_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+        let (lat1, lon1) = (from.0.to_radians(), from.1.to_radians());
+        let (lat2, lon2) = (to.0.to_radians(), to.1.to_radians());
+        let a = ((lat2 - lat1) / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+        2.0 * Self::EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+}
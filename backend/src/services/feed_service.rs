@@ -0,0 +1,145 @@
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::prelude::*;
+
+pub struct FeedService {
+    photos: Arc<Repository<Photo>>,
+    albums: Arc<Repository<Album>>,
+    settings: Arc<SettingService>,
+    cache: Mutex<HashMap<String, (Instant, Arc<FeedDocument>)>>,
+}
+
+impl FeedService {
+    pub const PHOTOS_PATH: &'static str = "/feeds/photos.atom";
+    pub const ALBUMS_PATH: &'static str = "/feeds/albums.atom";
+    pub const ENTRY_LIMIT: u32 = 50;
+    pub const CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+    // Keys include the requested host, so cap them to stop spoofed Host headers from growing the cache.
+    const MAX_CACHED_FEEDS: usize = 16;
+    const DEFAULT_TITLE: &'static str = "Nimble Photos";
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photos: services.get::<Repository<Photo>>(),
+            albums: services.get::<Repository<Album>>(),
+            settings: services.get::<SettingService>(),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_feed_path(path: &str) -> bool {
+        path == Self::PHOTOS_PATH || path == Self::ALBUMS_PATH
+    }
+
+    pub async fn document(
+        &self,
+        path: &str,
+        base_url: &str,
+        enclosure_type: &str,
+    ) -> Result<Arc<FeedDocument>, PipelineError> {
+        let key = format!("{}|{}|{}", path, base_url, enclosure_type);
+        if let Some(document) = self.cached(&key) {
+            return Ok(document);
+        }
+
+        let site_title = self.settings.site_title().await?;
+        let site_title = if site_title.is_empty() { Self::DEFAULT_TITLE.to_string() } else { site_title };
+        let feed = if path == Self::ALBUMS_PATH {
+            let albums = self.albums.latest_albums(Self::ENTRY_LIMIT).await?;
+            Self::album_feed(&site_title, base_url, &albums, enclosure_type)
+        } else {
+            let photos = self.photos.latest_public_photos(Self::ENTRY_LIMIT).await?;
+            Self::photo_feed(&site_title, base_url, &photos, enclosure_type)
+        };
+
+        let document = Arc::new(feed.document());
+        self.store(key, Arc::clone(&document));
+        Ok(document)
+    }
+
+    pub fn photo_feed(site_title: &str, base_url: &str, photos: &[FeedPhoto], enclosure_type: &str) -> AtomFeed {
+        let entries = photos
+            .iter()
+            .map(|photo| {
+                let title = photo.title.as_deref().map(str::trim).filter(|title| !title.is_empty());
+                let taken = photo.date_taken.map(|taken| format!("Taken {}", taken.format("%Y-%m-%d %H:%M UTC")));
+                let summary = [photo.caption.as_deref().map(str::trim).map(str::to_string), taken]
+                    .into_iter()
+                    .flatten()
+                    .filter(|line| !line.is_empty())
+                    .collect::<Vec<_>>();
+                AtomEntry {
+                    id: format!("urn:uuid:{}", photo.id),
+                    title: title.unwrap_or(&photo.name).to_string(),
+                    updated: photo.published,
+                    published: Some(photo.published),
+                    summary: (!summary.is_empty()).then(|| summary.join("\n")),
+                    link: format!("{}/api/photos/preview/{}", base_url, photo.hash),
+                    enclosure: Some(AtomLink {
+                        href: format!("{}/api/photos/thumbnail/{}", base_url, photo.hash),
+                        content_type: Some(enclosure_type.to_string()),
+                    }),
+                }
+            })
+            .collect();
+
+        AtomFeed {
+            id: format!("{}{}", base_url, Self::PHOTOS_PATH),
+            title: format!("{} - Latest photos", site_title),
+            self_link: format!("{}{}", base_url, Self::PHOTOS_PATH),
+            alternate_link: format!("{}/", base_url),
+            entries,
+        }
+    }
+
+    pub fn album_feed(site_title: &str, base_url: &str, albums: &[FeedAlbum], enclosure_type: &str) -> AtomFeed {
+        let entries = albums
+            .iter()
+            .map(|album| {
+                let count = format!("{} photo{}", album.photo_count, if album.photo_count == 1 { "" } else { "s" });
+                let summary = match album.description.as_deref().map(str::trim).filter(|text| !text.is_empty()) {
+                    Some(description) => format!("{}\n{}", description, count),
+                    None => count,
+                };
+                AtomEntry {
+                    id: format!("urn:uuid:{}", album.id),
+                    title: album.name.clone(),
+                    updated: album.create_date,
+                    published: Some(album.create_date),
+                    summary: Some(summary),
+                    link: format!("{}/albums/{}", base_url, album.id),
+                    enclosure: album.thumbnail_hash.as_ref().map(|hash| AtomLink {
+                        href: format!("{}/api/photos/thumbnail/{}", base_url, hash),
+                        content_type: Some(enclosure_type.to_string()),
+                    }),
+                }
+            })
+            .collect();
+
+        AtomFeed {
+            id: format!("{}{}", base_url, Self::ALBUMS_PATH),
+            title: format!("{} - Albums", site_title),
+            self_link: format!("{}{}", base_url, Self::ALBUMS_PATH),
+            alternate_link: format!("{}/albums", base_url),
+            entries,
+        }
+    }
+
+    fn cached(&self, key: &str) -> Option<Arc<FeedDocument>> {
+        let cache = self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cache
+            .get(key)
+            .filter(|(cached_at, _)| cached_at.elapsed() < Self::CACHE_TTL)
+            .map(|(_, document)| Arc::clone(document))
+    }
+
+    fn store(&self, key: String, document: Arc<FeedDocument>) {
+        let mut cache = self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cache.retain(|_, (cached_at, _)| cached_at.elapsed() < Self::CACHE_TTL);
+        if cache.len() >= Self::MAX_CACHED_FEEDS {
+            cache.clear();
+        }
+        cache.insert(key, (Instant::now(), document));
+    }
+}
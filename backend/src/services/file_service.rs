@@ -51,6 +51,12 @@ impl FileService {
     }
 
     pub fn path_for_hash<P: AsRef<Path>>(&self, base: P, hash: &str, extension: &str) -> PathBuf {
-        base.as_ref().join(&hash[0..2]).join(&hash[2..4]).join(format!("{}.{extension}", hash))
+        Self::hash_path(base, hash, extension)
+    }
+
+    pub fn hash_path<P: AsRef<Path>>(base: P, hash: &str, extension: &str) -> PathBuf {
+        let first = hash.get(0..2).unwrap_or_default();
+        let second = hash.get(2..4).unwrap_or_default();
+        base.as_ref().join(first).join(second).join(format!("{}.{extension}", hash))
     }
 }
@@ -0,0 +1,150 @@
+use crate::prelude::*;
+use crate::services::image_process_constants::ImageProcessKeys;
+use crate::services::photo_upload_service::StoredUploadFile;
+use anyhow::{Result, anyhow};
+use tokio::task;
+
+pub struct FolderImportService {
+    photo_repo: Arc<Repository<Photo>>,
+    jobs: Option<Arc<Repository<PipelineJob>>>,
+    guard: Arc<StoragePathGuard>,
+    image_pipeline: Arc<ImageProcessPipeline>,
+}
+
+impl FolderImportService {
+    const EXISTING_LOOKUP_CHUNK: usize = 500;
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photo_repo: services.get::<Repository<Photo>>(),
+            jobs: services.resolve::<Repository<PipelineJob>>(),
+            guard: services.get::<StoragePathGuard>(),
+            image_pipeline: services.get::<ImageProcessPipeline>(),
+        }
+    }
+
+    pub async fn import_folder(
+        &self,
+        storage: &StorageLocation,
+        request: &FolderImportRequest,
+    ) -> Result<FolderImportResponse> {
+        let root = storage.normalized_path();
+        let folder = self.guard.resolve(&root, &request.path)?;
+        if !folder.is_dir() {
+            return Err(BrowsePathError::NotFound.into());
+        }
+
+        let recursive = request.recursive;
+        let scan_root = root.clone();
+        let files = task::spawn_blocking(move || Self::scan_folder(&scan_root, &folder, recursive))
+            .await
+            .map_err(|err| anyhow!("folder scan task failed: {}", err))??;
+        let scanned = files.len();
+
+        let existing = self.existing_paths(storage, &files).await?;
+        let pending =
+            files.into_iter().filter(|file| !existing.contains(&Self::photo_path(&root, file))).collect::<Vec<_>>();
+
+        let batch_id = Uuid::new_v4();
+        let queued = if pending.is_empty() {
+            0
+        } else {
+            self.image_pipeline.enqueue_in_place_batch(storage.clone(), pending, batch_id, request.format).await?
+        };
+        log::info!(
+            "Folder import {} ({}) on storage {}: scanned {}, already imported {}, queued {}",
+            batch_id,
+            request.format.as_str(),
+            storage.id,
+            scanned,
+            scanned - queued,
+            queued
+        );
+
+        Ok(FolderImportResponse { batch_id, scanned, already_imported: scanned - queued, queued })
+    }
+
+    pub async fn batch_status(&self, batch_id: Uuid) -> Result<Option<ImportBatchStatus>> {
+        let Some(jobs) = &self.jobs else {
+            return Ok(None);
+        };
+
+        let items = jobs
+            .all(
+                QueryBuilder::<PipelineJob>::new()
+                    .filter("batch_id", FilterOperator::Eq, Value::Uuid(batch_id))
+                    .build(),
+            )
+            .await
+            .map_err(|err| anyhow!("failed to load import batch {}: {:?}", batch_id, err))?;
+        if items.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(ImportBatchStatus::from_jobs(batch_id, &items)))
+    }
+
+    pub fn scan_folder(root: &Path, folder: &Path, recursive: bool) -> Result<Vec<StoredUploadFile>> {
+        let canonical_root = root.canonicalize()?;
+        let mut files = Vec::new();
+        let mut pending = vec![folder.to_path_buf()];
+
+        while let Some(directory) = pending.pop() {
+            let mut entries = fs::read_dir(&directory)?.filter_map(|entry| entry.ok()).collect::<Vec<_>>();
+            entries.sort_by_key(|entry| entry.file_name());
+
+            for entry in entries {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with('.') {
+                    continue;
+                }
+
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                let path = entry.path();
+                if file_type.is_dir() {
+                    if recursive {
+                        pending.push(path);
+                    }
+                    continue;
+                }
+
+                // Symlinks are skipped rather than followed so an import cannot read files outside the storage root.
+                if !file_type.is_file() || !ImageProcessKeys::is_supported_image(&path) {
+                    continue;
+                }
+
+                let byte_size = entry.metadata().map(|metadata| metadata.len() as usize).unwrap_or(0);
+                let relative = path.strip_prefix(&canonical_root).unwrap_or(&path);
+                files.push(StoredUploadFile {
+                    file_name: name,
+                    relative_path: relative.to_string_lossy().replace('\\', "/"),
+                    byte_size,
+                    content_type: None,
+                });
+            }
+        }
+
+        Ok(files)
+    }
+
+    async fn existing_paths(&self, storage: &StorageLocation, files: &[StoredUploadFile]) -> Result<HashSet<String>> {
+        let root = storage.normalized_path();
+        let mut existing = HashSet::new();
+        for chunk in files.chunks(Self::EXISTING_LOOKUP_CHUNK) {
+            let paths = chunk.iter().map(|file| Value::String(Self::photo_path(&root, file))).collect::<Vec<_>>();
+            let query = QueryBuilder::<Photo>::new()
+                .filter("storage_id", FilterOperator::Eq, Value::Uuid(storage.id))
+                .filter("path", FilterOperator::In, Value::List(paths))
+                .build();
+            let photos =
+                self.photo_repo.all(query).await.map_err(|err| anyhow!("failed to load existing photos: {:?}", err))?;
+            existing.extend(photos.into_iter().map(|photo| photo.path));
+        }
+        Ok(existing)
+    }
+
+    fn photo_path(root: &Path, file: &StoredUploadFile) -> String {
+        root.join(Path::new(&file.relative_path)).to_string_lossy().to_string()
+    }
+}
@@ -0,0 +1,234 @@
+use crate::prelude::*;
+use anyhow::{Context, Result, anyhow, bail};
+use image::ImageFormat;
+use std::io::Cursor;
+
+const JPEG_SOI: [u8; 2] = [0xFF, 0xD8];
+const JPEG_APP1: u8 = 0xE1;
+const JPEG_SOS: u8 = 0xDA;
+const JPEG_EOI: u8 = 0xD9;
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+const XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const XMP_GPS_MARKER: &[u8] = b"exif:GPS";
+const GPS_IFD_TAG: u16 = 0x8825;
+const IFD_ENTRY_SIZE: usize = 12;
+
+#[derive(Clone, Debug, Default)]
+pub struct GpsScrubService;
+
+impl GpsScrubService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Admins get their own originals untouched unless they ask; everyone else follows the setting,
+    // and may only ask for more scrubbing, never less.
+    pub fn should_strip(setting_enabled: bool, is_admin: bool, requested: Option<bool>) -> bool {
+        if is_admin {
+            return requested.unwrap_or(false);
+        }
+        setting_enabled || requested.unwrap_or(false)
+    }
+
+    pub fn output_extension(source: &Path) -> &'static str {
+        let extension = source.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => "png",
+            "webp" => "webp",
+            "tif" | "tiff" => "tiff",
+            "bmp" => "bmp",
+            _ => "jpg",
+        }
+    }
+
+    pub fn is_fresh(source: &Path, scrubbed: &Path) -> bool {
+        let modified = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+        match (modified(source), modified(scrubbed)) {
+            (Some(source), Some(scrubbed)) => scrubbed >= source,
+            _ => false,
+        }
+    }
+
+    // Writes a copy of `source` without location data; the source file itself is never modified.
+    pub fn scrub_to(&self, source: &Path, output: &Path) -> Result<PathBuf> {
+        let bytes = std::fs::read(source).with_context(|| format!("failed to read {}", source.display()))?;
+        let scrubbed = self.scrub_bytes(&bytes, Self::output_extension(source))?;
+
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let partial = output.with_extension(format!("{}.part", Self::output_extension(source)));
+        std::fs::write(&partial, scrubbed)?;
+        std::fs::rename(&partial, output)?;
+        Ok(output.to_path_buf())
+    }
+
+    pub fn scrub_bytes(&self, bytes: &[u8], extension: &str) -> Result<Vec<u8>> {
+        if bytes.starts_with(&JPEG_SOI) {
+            match Self::strip_jpeg_gps(bytes) {
+                Ok(stripped) => return Ok(stripped),
+                Err(error) => log::warn!("Falling back to re-encoding JPEG without GPS: {}", error),
+            }
+        }
+        Self::reencode(bytes, extension)
+    }
+
+    // Copies the JPEG segment by segment, removing the GPS IFD from EXIF and dropping XMP packets with GPS.
+    pub fn strip_jpeg_gps(bytes: &[u8]) -> Result<Vec<u8>> {
+        if !bytes.starts_with(&JPEG_SOI) {
+            bail!("not a JPEG file");
+        }
+
+        let mut output = Vec::with_capacity(bytes.len());
+        output.extend_from_slice(&JPEG_SOI);
+        let mut offset = JPEG_SOI.len();
+        while offset + 4 <= bytes.len() {
+            if bytes[offset] != 0xFF {
+                bail!("invalid JPEG marker at offset {}", offset);
+            }
+            let marker = bytes[offset + 1];
+            if marker == 0xFF {
+                offset += 1;
+                continue;
+            }
+            if marker == JPEG_SOS || marker == JPEG_EOI {
+                break;
+            }
+
+            let length = u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]) as usize;
+            let end = offset + 2 + length;
+            if length < 2 || end > bytes.len() {
+                bail!("truncated JPEG segment at offset {}", offset);
+            }
+
+            let payload = &bytes[offset + 4..end];
+            if marker == JPEG_APP1 && payload.starts_with(EXIF_HEADER) {
+                let mut segment = bytes[offset..end].to_vec();
+                Self::remove_gps_ifd(&mut segment[4 + EXIF_HEADER.len()..])?;
+                output.extend_from_slice(&segment);
+            } else if marker == JPEG_APP1
+                && payload.starts_with(XMP_HEADER)
+                && payload.windows(XMP_GPS_MARKER.len()).any(|window| window == XMP_GPS_MARKER)
+            {
+                log::debug!("Dropping XMP packet with GPS data");
+            } else {
+                output.extend_from_slice(&bytes[offset..end]);
+            }
+            offset = end;
+        }
+
+        output.extend_from_slice(&bytes[offset..]);
+        Ok(output)
+    }
+
+    fn remove_gps_ifd(tiff: &mut [u8]) -> Result<()> {
+        let mut tiff = TiffBuffer::new(tiff)?;
+        let ifd0 = tiff.read_u32(4)? as usize;
+        let count = tiff.read_u16(ifd0)? as usize;
+        let entries = ifd0 + 2;
+
+        let mut gps_index = None;
+        for index in 0..count {
+            if tiff.read_u16(entries + index * IFD_ENTRY_SIZE)? == GPS_IFD_TAG {
+                gps_index = Some(index);
+                break;
+            }
+        }
+        let Some(index) = gps_index else {
+            return Ok(());
+        };
+
+        let entry = entries + index * IFD_ENTRY_SIZE;
+        let gps_offset = tiff.read_u32(entry + 8)? as usize;
+        tiff.clear_ifd(gps_offset)?;
+
+        // Shift the remaining entries and the next-IFD pointer over the GPS entry.
+        let end = entries + count * IFD_ENTRY_SIZE + 4;
+        if end > tiff.data.len() {
+            bail!("EXIF IFD0 is truncated");
+        }
+        tiff.data.copy_within(entry + IFD_ENTRY_SIZE..end, entry);
+        tiff.data[end - IFD_ENTRY_SIZE..end].fill(0);
+        tiff.write_u16(ifd0, (count - 1) as u16)
+    }
+
+    fn reencode(bytes: &[u8], extension: &str) -> Result<Vec<u8>> {
+        let format = ImageFormat::from_extension(extension).unwrap_or(ImageFormat::Jpeg);
+        let image = image::load_from_memory(bytes).map_err(|error| anyhow!("cannot decode image: {}", error))?;
+        let image = if format == ImageFormat::Jpeg { image::DynamicImage::ImageRgb8(image.to_rgb8()) } else { image };
+
+        let mut output = Cursor::new(Vec::new());
+        image.write_to(&mut output, format)?;
+        Ok(output.into_inner())
+    }
+}
+
+struct TiffBuffer<'a> {
+    data: &'a mut [u8],
+    little_endian: bool,
+}
+
+impl<'a> TiffBuffer<'a> {
+    fn new(data: &'a mut [u8]) -> Result<Self> {
+        let little_endian = match data.get(0..2) {
+            Some(b"II") => true,
+            Some(b"MM") => false,
+            _ => bail!("invalid TIFF byte order"),
+        };
+        Ok(Self { data, little_endian })
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N]> {
+        self.data
+            .get(offset..offset + N)
+            .and_then(|slice| slice.try_into().ok())
+            .ok_or_else(|| anyhow!("EXIF offset {} is out of bounds", offset))
+    }
+
+    fn read_u16(&self, offset: usize) -> Result<u16> {
+        let bytes = self.bytes::<2>(offset)?;
+        Ok(if self.little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    }
+
+    fn read_u32(&self, offset: usize) -> Result<u32> {
+        let bytes = self.bytes::<4>(offset)?;
+        Ok(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn write_u16(&mut self, offset: usize, value: u16) -> Result<()> {
+        let bytes = if self.little_endian { value.to_le_bytes() } else { value.to_be_bytes() };
+        self.data
+            .get_mut(offset..offset + 2)
+            .ok_or_else(|| anyhow!("EXIF offset {} is out of bounds", offset))?
+            .copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    // Zeroes the IFD and any values stored outside it so no coordinates survive in the segment.
+    fn clear_ifd(&mut self, offset: usize) -> Result<()> {
+        let count = self.read_u16(offset)? as usize;
+        for index in 0..count {
+            let entry = offset + 2 + index * IFD_ENTRY_SIZE;
+            let size = Self::type_size(self.read_u16(entry + 2)?).saturating_mul(self.read_u32(entry + 4)? as usize);
+            if size > 4 {
+                let value = self.read_u32(entry + 8)? as usize;
+                let end = value.saturating_add(size).min(self.data.len());
+                if value < end {
+                    self.data[value..end].fill(0);
+                }
+            }
+        }
+        let end = (offset + 2 + count * IFD_ENTRY_SIZE + 4).min(self.data.len());
+        self.data[offset..end].fill(0);
+        Ok(())
+    }
+
+    fn type_size(field_type: u16) -> usize {
+        match field_type {
+            3 | 8 => 2,
+            4 | 9 | 11 => 4,
+            5 | 10 | 12 => 8,
+            _ => 1,
+        }
+    }
+}
@@ -5,10 +5,21 @@ use xxhash_rust::xxh3::Xxh3;
 pub struct HashService;
 
 impl HashService {
+    pub const HASH_LENGTH: usize = 16;
+
     pub fn new() -> Self {
         Self {}
     }
 
+    pub fn is_valid(hash: &str) -> bool {
+        hash.len() == Self::HASH_LENGTH && hash.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    pub fn normalize(hash: &str) -> Option<String> {
+        let hash = hash.trim();
+        Self::is_valid(hash).then(|| hash.to_ascii_lowercase())
+    }
+
     pub fn compute(&self, data: &[u8], file_size: usize) -> String {
         const CHUNK: usize = 64 * 1024;
         let len = file_size;
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result, bail};
+
+use crate::prelude::*;
+
+// Suggests tags for a photo from its preview. Implementations only suggest; users accept the
+// suggestions before they become tags.
+#[async_trait]
+pub trait ImageAnalyzer: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    async fn analyze(&self, preview_path: &Path) -> Result<Vec<TagSuggestion>>;
+}
+
+pub struct NoopImageAnalyzer;
+
+#[async_trait]
+impl ImageAnalyzer for NoopImageAnalyzer {
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    fn enabled(&self) -> bool {
+        false
+    }
+
+    async fn analyze(&self, _preview_path: &Path) -> Result<Vec<TagSuggestion>> {
+        Ok(Vec::new())
+    }
+}
+
+// POSTs the preview JPEG to an external inference service. The service answers with either
+// `[{"name": "dog", "confidence": 0.93}]` or `{"tags": [...]}`.
+pub struct HttpImageAnalyzer {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpImageAnalyzer {
+    pub fn new(config: &ImageAnalyzerConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .context("failed to build image analyzer HTTP client")?;
+        Ok(Self { client, url: config.url.clone() })
+    }
+
+    pub fn parse_response(body: &[u8]) -> Result<Vec<TagSuggestion>> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum AnalyzerResponse {
+            List(Vec<TagSuggestion>),
+            Wrapped { tags: Vec<TagSuggestion> },
+        }
+
+        let response =
+            serde_json::from_slice::<AnalyzerResponse>(body).context("unexpected image analyzer response")?;
+        Ok(match response {
+            AnalyzerResponse::List(tags) | AnalyzerResponse::Wrapped { tags } => tags,
+        })
+    }
+}
+
+#[async_trait]
+impl ImageAnalyzer for HttpImageAnalyzer {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    async fn analyze(&self, preview_path: &Path) -> Result<Vec<TagSuggestion>> {
+        let preview = tokio::fs::read(preview_path)
+            .await
+            .with_context(|| format!("failed to read preview {}", preview_path.display()))?;
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, SettingConsts::PREVIEW_CONTENT_TYPE)
+            .body(preview)
+            .send()
+            .await
+            .context("image analyzer request failed")?;
+        if !response.status().is_success() {
+            bail!("image analyzer answered {}", response.status());
+        }
+        let body = response.bytes().await.context("failed to read image analyzer response")?;
+        Self::parse_response(&body)
+    }
+}
+
+// Picks the analyzer for each photo: one registered in-process wins, otherwise the HTTP analyzer
+// when ml.analyzerUrl is set, otherwise nothing runs.
+pub struct ImageAnalyzerRegistry {
+    settings: Arc<SettingService>,
+    registered: std::sync::RwLock<Option<Arc<dyn ImageAnalyzer>>>,
+}
+
+impl ImageAnalyzerRegistry {
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self { settings: services.get::<SettingService>(), registered: std::sync::RwLock::new(None) }
+    }
+
+    pub fn register(&self, analyzer: Arc<dyn ImageAnalyzer>) {
+        log::info!("Registered image analyzer '{}'", analyzer.name());
+        *self.registered.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(analyzer);
+    }
+
+    // The confidence threshold applies to every analyzer, so it is returned alongside it.
+    pub async fn resolve(&self) -> Result<(Arc<dyn ImageAnalyzer>, f32)> {
+        let settings_error = |err: PipelineError| anyhow::anyhow!("failed to read image analyzer settings: {:?}", err);
+        let config = self.settings.image_analyzer().await.map_err(settings_error)?;
+        let min_confidence = match &config {
+            Some(config) => config.min_confidence,
+            None => self.settings.ml_min_confidence().await.map_err(settings_error)?,
+        };
+
+        let registered = self.registered.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+        if let Some(analyzer) = registered {
+            return Ok((analyzer, min_confidence));
+        }
+        match config {
+            Some(config) => Ok((Arc::new(HttpImageAnalyzer::new(&config)?), min_confidence)),
+            None => Ok((Arc::new(NoopImageAnalyzer), min_confidence)),
+        }
+    }
+}
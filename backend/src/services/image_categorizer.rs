@@ -114,3 +114,20 @@ impl ImageCategorizer for TemplateCategorizer {
         Ok(CategorizeResult { final_path, hash })
     }
 }
+
+pub struct InPlaceCategorizer;
+
+impl ImageCategorizer for InPlaceCategorizer {
+    fn name(&self) -> &'static str {
+        "in-place"
+    }
+
+    fn categorize(&self, request: &CategorizeRequest<'_>) -> Result<CategorizeResult> {
+        if !request.source_file().is_file() {
+            return Err(anyhow!("source file {} not found", request.source_file().display()));
+        }
+
+        let hash = request.properties().get_by_alias::<String>(ImageProcessKeys::HASH).cloned();
+        Ok(CategorizeResult { final_path: request.source_file().to_path_buf(), hash })
+    }
+}
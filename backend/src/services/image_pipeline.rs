@@ -1,13 +1,15 @@
 pub use super::image_process_context::ImageProcessContext;
 use super::image_process_step::ImageProcessStep;
-use crate::entities::StorageLocation;
+use crate::entities::{PipelineJob, StorageLocation};
 use crate::services::background_task_runner::BackgroundTaskRunner;
 use crate::services::event_bus_service::EventBusService;
 use crate::services::image_process_constants::ImageProcessKeys;
 use crate::services::image_process_steps::{
-    CategorizeImageStep, ComputeHashStep, ExtractExifStep, GeneratePreviewStep, GenerateThumbnailStep,
-    PersistMetadataStep,
+    AnalyzeImageStep, ApplyPeopleStep, ApplyTakeoutMetadataStep, ApplyXmpKeywordsStep, CategorizeImageStep,
+    ComputeHashStep, ExtractExifStep, GeneratePreviewStep, GenerateThumbnailStep, PersistMetadataStep,
+    ReadTakeoutSidecarStep, ReadXmpSidecarStep,
 };
+use crate::services::metrics_service::MetricsService;
 use crate::services::photo_upload_service::StoredUploadFile;
 use crate::services::task_descriptor::TaskDescriptor;
 
@@ -30,13 +32,20 @@ impl ImageProcessPipelineContext {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ImageProcessPayload {
     pub storage: StorageLocation,
     pub relative_path: String,
     pub file_name: String,
     pub byte_size: usize,
     pub content_type: Option<String>,
+    #[serde(default)]
+    pub batch_id: Option<Uuid>,
+    #[serde(default)]
+    pub in_place: bool,
+    #[serde(default)]
+    pub import_format: ImportFormat,
 }
 
 impl ImageProcessPayload {
@@ -53,9 +62,23 @@ impl ImageProcessPayload {
             file_name,
             byte_size,
             content_type,
+            batch_id: None,
+            in_place: false,
+            import_format: ImportFormat::Native,
         }
     }
 
+    pub fn with_batch(mut self, batch_id: Option<Uuid>, in_place: bool) -> Self {
+        self.batch_id = batch_id;
+        self.in_place = in_place;
+        self
+    }
+
+    pub fn with_import_format(mut self, import_format: ImportFormat) -> Self {
+        self.import_format = import_format;
+        self
+    }
+
     pub fn from_upload(storage: StorageLocation, file: StoredUploadFile) -> Self {
         log::debug!(
             "Creating ImageProcessPayload for storage {} file {} {}",
@@ -69,6 +92,9 @@ impl ImageProcessPayload {
             file_name: file.file_name,
             byte_size: file.byte_size,
             content_type: file.content_type,
+            batch_id: None,
+            in_place: false,
+            import_format: ImportFormat::Native,
         }
     }
 
@@ -81,6 +107,29 @@ impl ImageProcessPayload {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncProcessStatus {
+    Completed,
+    Skipped,
+    Failed,
+    Deferred,
+}
+
+#[derive(Clone, Debug)]
+pub struct SyncProcessedFile {
+    pub file_name: String,
+    pub status: SyncProcessStatus,
+    pub photo: Option<Photo>,
+    pub has_thumbnail: bool,
+}
+
+impl SyncProcessedFile {
+    fn deferred(file_name: String) -> Self {
+        Self { file_name, status: SyncProcessStatus::Deferred, photo: None, has_thumbnail: false }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct DerivativeProcessPayload {
     pub storage: StorageLocation,
@@ -99,22 +148,37 @@ pub struct ImageProcessPipeline {
     services: Arc<ServiceProvider>,
     thumbnail_step: Arc<GenerateThumbnailStep>,
     preview_step: Arc<GeneratePreviewStep>,
+    metrics: Option<Arc<MetricsService>>,
+    jobs: Option<Arc<Repository<PipelineJob>>>,
+    max_attempts: i32,
+    job_retention: Duration,
 }
 
 impl ImageProcessPipeline {
+    const KIND_IMPORT: &'static str = "import";
+    const KIND_DERIVATIVES: &'static str = "derivatives";
+    const NO_REQUEST_TAG: &'static str = "-";
+
     pub fn new(context: ImageProcessPipelineContext) -> Self {
         let runner = context.get_service::<BackgroundTaskRunner>();
         let event_bus = context.get_service::<EventBusService>();
         let thumbnail_step = Arc::new(GenerateThumbnailStep::new(context.services.clone()));
         let preview_step = Arc::new(GeneratePreviewStep::new(context.services.clone()));
+        let app_config = context.services.resolve::<AppConfig>().unwrap_or_default();
 
         let steps: Vec<Arc<dyn ImageProcessStep>> = vec![
             Arc::new(ComputeHashStep::new(context.services.clone())),
             Arc::new(ExtractExifStep::new(context.services.clone())),
+            Arc::new(ReadXmpSidecarStep::new(context.services.clone())),
+            Arc::new(ReadTakeoutSidecarStep::new(context.services.clone())),
             thumbnail_step.clone(),
             preview_step.clone(),
             Arc::new(CategorizeImageStep::new(context.services.clone())),
             Arc::new(PersistMetadataStep::new(context.services.clone())),
+            Arc::new(ApplyXmpKeywordsStep::new(context.services.clone())),
+            Arc::new(ApplyPeopleStep::new(context.services.clone())),
+            Arc::new(ApplyTakeoutMetadataStep::new(context.services.clone())),
+            Arc::new(AnalyzeImageStep::new(context.services.clone())),
         ];
 
         Self {
@@ -124,17 +188,169 @@ impl ImageProcessPipeline {
             services: Arc::clone(&context.services),
             thumbnail_step,
             preview_step,
+            metrics: context.services.resolve::<MetricsService>(),
+            jobs: context.services.resolve::<Repository<PipelineJob>>(),
+            max_attempts: app_config.pipeline_max_attempts,
+            job_retention: Duration::days(app_config.pipeline_job_retention_days),
         }
     }
 
-    pub fn enqueue_files(&self, storage: StorageLocation, files: Vec<StoredUploadFile>) -> Result<()> {
+    pub async fn enqueue_files(&self, storage: StorageLocation, files: Vec<StoredUploadFile>) -> Result<()> {
+        self.enqueue_files_for_request(storage, files, None, None).await
+    }
+
+    pub async fn enqueue_files_for_request(
+        &self,
+        storage: StorageLocation,
+        files: Vec<StoredUploadFile>,
+        batch_id: Option<Uuid>,
+        request_id: Option<String>,
+    ) -> Result<()> {
         for file in files {
-            let request = ImageProcessPayload::from_upload(storage.clone(), file);
-            self.enqueue_request(request)?;
+            let request = ImageProcessPayload::from_upload(storage.clone(), file).with_batch(batch_id, false);
+            let job = self.record_job(&request).await?;
+            self.enqueue_request(request, job, request_id.clone())?;
         }
         Ok(())
     }
 
+    // Runs each file inline until the deadline; the file in flight at the deadline keeps running
+    // detached and the rest are queued, so nothing is dropped half-processed.
+    pub async fn process_now(
+        &self,
+        storage: StorageLocation,
+        files: Vec<StoredUploadFile>,
+        batch_id: Uuid,
+        request_id: Option<String>,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<SyncProcessedFile>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let request_tag = request_id.clone().unwrap_or_else(|| Self::NO_REQUEST_TAG.to_string());
+        let mut processed = Vec::with_capacity(files.len());
+        let mut files = files.into_iter();
+
+        while let Some(file) = files.next() {
+            let file_name = file.file_name.clone();
+            let request = ImageProcessPayload::from_upload(storage.clone(), file).with_batch(Some(batch_id), false);
+            let job = self.record_job(&request).await?;
+            let pipeline = self.clone();
+            let tag = request_tag.clone();
+            let mut handle = tokio::spawn(async move { pipeline.run_job(request, job, tag).await });
+
+            let (status, photo) = match tokio::time::timeout_at(deadline, &mut handle).await {
+                Ok(Ok(Ok(Some(photo)))) => (SyncProcessStatus::Completed, Some(photo)),
+                Ok(Ok(Ok(None))) => (SyncProcessStatus::Skipped, None),
+                Ok(Ok(Err(_))) => (SyncProcessStatus::Failed, None),
+                Ok(Err(error)) => {
+                    log::error!("[request_id={}] Image process task aborted: {:?}", request_tag, error);
+                    (SyncProcessStatus::Failed, None)
+                }
+                Err(_) => {
+                    processed.push(SyncProcessedFile::deferred(file_name));
+                    let remaining: Vec<StoredUploadFile> = files.collect();
+                    log::warn!(
+                        "[request_id={}] Synchronous processing timed out after {:?}; deferring {} file(s)",
+                        request_tag,
+                        timeout,
+                        remaining.len() + 1
+                    );
+                    processed.extend(remaining.iter().map(|file| SyncProcessedFile::deferred(file.file_name.clone())));
+                    self.enqueue_files_for_request(storage, remaining, Some(batch_id), request_id).await?;
+                    break;
+                }
+            };
+            let has_thumbnail = photo.as_ref().and_then(|photo| photo.hash.as_deref()).is_some_and(|hash| {
+                let thumbnail_root = storage.normalized_path().join(SettingConsts::THUMBNAIL_FOLDER);
+                ThumbnailFormat::BASE
+                    .iter()
+                    .any(|format| FileService::hash_path(&thumbnail_root, hash, format.extension()).exists())
+            });
+            processed.push(SyncProcessedFile { file_name, status, photo, has_thumbnail });
+        }
+
+        Ok(processed)
+    }
+
+    pub async fn enqueue_in_place_batch(
+        &self,
+        storage: StorageLocation,
+        files: Vec<StoredUploadFile>,
+        batch_id: Uuid,
+        import_format: ImportFormat,
+    ) -> Result<usize> {
+        let mut queued = 0;
+        for file in files {
+            let request = ImageProcessPayload::from_upload(storage.clone(), file)
+                .with_batch(Some(batch_id), true)
+                .with_import_format(import_format);
+            let job = self.record_job(&request).await?;
+            self.enqueue_request(request, job, None)?;
+            queued += 1;
+        }
+        Ok(queued)
+    }
+
+    pub async fn resume_pending_jobs(&self) -> Result<usize> {
+        let Some(jobs) = &self.jobs else {
+            return Ok(0);
+        };
+        self.prune_finished_jobs(jobs).await;
+
+        let pending_states = vec![
+            Value::String(PipelineJob::STATE_QUEUED.to_string()),
+            Value::String(PipelineJob::STATE_RUNNING.to_string()),
+        ];
+        let pending = jobs
+            .all(
+                QueryBuilder::<PipelineJob>::new()
+                    .filter("state", FilterOperator::In, Value::List(pending_states))
+                    .sort_asc("created_at")
+                    .build(),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to load pending pipeline jobs: {:?}", e))?;
+
+        let storage_repo = self.services.get::<Repository<StorageLocation>>();
+        let mut storages: HashMap<Uuid, Option<StorageLocation>> = HashMap::new();
+        let mut resumed = 0;
+        for mut job in pending {
+            if job.attempts_exhausted(self.max_attempts) {
+                log::warn!("Pipeline job {} gave up after {} attempt(s)", job.id, job.attempts);
+                job.mark_failed(&format!("gave up after {} attempts", job.attempts));
+                Self::save_job(jobs, job).await;
+                continue;
+            }
+            if !storages.contains_key(&job.storage_id) {
+                let storage = storage_repo.get(&job.storage_id).await.ok().flatten();
+                storages.insert(job.storage_id, storage);
+            }
+
+            let Some(storage) = storages.get(&job.storage_id).cloned().flatten() else {
+                log::warn!("Pipeline job {} references missing storage {}", job.id, job.storage_id);
+                job.mark_failed("storage not found");
+                Self::save_job(jobs, job).await;
+                continue;
+            };
+
+            let request = ImageProcessPayload::new(
+                storage,
+                job.relative_path.clone(),
+                job.file_name.clone(),
+                job.byte_size.max(0) as usize,
+                job.content_type.clone(),
+            )
+            .with_batch(job.batch_id, job.in_place)
+            .with_import_format(job.format());
+            self.enqueue_request(request, Some(job), None)?;
+            resumed += 1;
+        }
+
+        if resumed > 0 {
+            log::info!("Re-enqueued {} pending pipeline job(s)", resumed);
+        }
+        Ok(resumed)
+    }
+
     pub fn enqueue_derivative_batch(&self, requests: Vec<DerivativeProcessPayload>) -> Result<()> {
         for request in requests {
             self.enqueue_derivative_request(request)?;
@@ -143,29 +359,146 @@ impl ImageProcessPipeline {
     }
 
     pub async fn process(&self, request: ImageProcessPayload) -> Result<()> {
-        self.run_steps(request).await
+        self.run_steps(request).await.map(|_| ())
     }
 
-    fn enqueue_request(&self, request: ImageProcessPayload) -> Result<()> {
-        let pipeline = self.clone();
-        let task_name = format!("image-process-{}-{}", request.storage.id, request.file_name);
-        self.runner.enqueue(TaskDescriptor::new(task_name, async move {
-            let completion = json!({
-                "storageId": request.storage.id,
-                "storagePath": request.storage.path,
-                "fileName": request.file_name,
-                "relativePath": request.relative_path,
-            });
+    pub fn spool_pending(&self, tasks: Vec<TaskDescriptor>) -> Result<usize> {
+        let mut by_storage: HashMap<Uuid, Vec<ImageProcessPayload>> = HashMap::new();
+        for task in tasks {
+            let Some(payload) = task.payload else {
+                log::debug!("Skipping background task '{}' without a spool payload", task.name);
+                continue;
+            };
+            match serde_json::from_value::<ImageProcessPayload>(payload) {
+                Ok(request) => by_storage.entry(request.storage.id).or_default().push(request),
+                Err(error) => log::warn!("Dropping background task '{}': {}", task.name, error),
+            }
+        }
 
-            if let Err(error) = pipeline.run_steps(request).await {
-                pipeline.emit_images_processed_if_idle(completion);
-                log::error!("Image process pipeline failed: {:?}", error);
-                return Err(error);
+        let mut spooled = 0;
+        for requests in by_storage.into_values() {
+            let spool_path = Self::spool_path(&requests[0].storage);
+            let mut pending = Self::read_spool(&spool_path)?;
+            spooled += requests.len();
+            pending.extend(requests);
+            std::fs::write(&spool_path, serde_json::to_vec_pretty(&pending)?)?;
+            log::info!("Spooled {} pending image process request(s) to {}", pending.len(), spool_path.display());
+        }
+        Ok(spooled)
+    }
+
+    pub async fn restore_spooled(&self, storages: &[StorageLocation]) -> Result<usize> {
+        let mut restored = 0;
+        for storage in storages {
+            let spool_path = Self::spool_path(storage);
+            if !spool_path.exists() {
+                continue;
             }
 
-            pipeline.emit_images_processed_if_idle(completion);
-            Ok(())
-        }))
+            for mut request in Self::read_spool(&spool_path)? {
+                request.storage = storage.clone();
+                let job = self.record_job(&request).await?;
+                self.enqueue_request(request, job, None)?;
+                restored += 1;
+            }
+            std::fs::remove_file(&spool_path)?;
+        }
+
+        if restored > 0 {
+            log::info!("Re-enqueued {} spooled image process request(s)", restored);
+        }
+        Ok(restored)
+    }
+
+    fn spool_path(storage: &StorageLocation) -> PathBuf {
+        storage.normalized_path().join(SettingConsts::PENDING_IMPORTS_FILE)
+    }
+
+    fn read_spool(path: &Path) -> Result<Vec<ImageProcessPayload>> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read(path)?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    async fn record_job(&self, request: &ImageProcessPayload) -> Result<Option<PipelineJob>> {
+        let Some(jobs) = &self.jobs else {
+            return Ok(None);
+        };
+
+        let job = PipelineJob::queued(
+            request.storage.id,
+            &request.relative_path,
+            &request.file_name,
+            request.byte_size,
+            request.content_type.clone(),
+        )
+        .with_batch(request.batch_id, request.in_place)
+        .with_import_format(request.import_format);
+        let saved = jobs.insert(job).await.map_err(|e| anyhow::anyhow!("failed to record pipeline job: {:?}", e))?;
+        Ok(Some(saved))
+    }
+
+    async fn prune_finished_jobs(&self, jobs: &Repository<PipelineJob>) {
+        let finished_states = vec![
+            Value::String(PipelineJob::STATE_COMPLETED.to_string()),
+            Value::String(PipelineJob::STATE_FAILED.to_string()),
+        ];
+        let finished = match jobs
+            .all(
+                QueryBuilder::<PipelineJob>::new()
+                    .filter("state", FilterOperator::In, Value::List(finished_states))
+                    .build(),
+            )
+            .await
+        {
+            Ok(finished) => finished,
+            Err(error) => {
+                log::warn!("Failed to load finished pipeline jobs: {:?}", error);
+                return;
+            }
+        };
+
+        let cutoff = Utc::now() - self.job_retention;
+        let mut pruned = 0;
+        for job in finished.into_iter().filter(|job| job.is_finished_before(cutoff)) {
+            match jobs.delete(&job.id).await {
+                Ok(_) => pruned += 1,
+                Err(error) => log::warn!("Failed to prune pipeline job {}: {:?}", job.id, error),
+            }
+        }
+        if pruned > 0 {
+            log::info!("Pruned {} finished pipeline job(s)", pruned);
+        }
+    }
+
+    async fn save_job(jobs: &Repository<PipelineJob>, job: PipelineJob) {
+        let job_id = job.id;
+        if let Err(error) = jobs.update(job).await {
+            log::error!("Failed to update pipeline job {}: {:?}", job_id, error);
+        }
+    }
+
+    fn enqueue_request(
+        &self,
+        request: ImageProcessPayload,
+        job: Option<PipelineJob>,
+        request_id: Option<String>,
+    ) -> Result<()> {
+        let pipeline = self.clone();
+        let request_tag = request_id.unwrap_or_else(|| Self::NO_REQUEST_TAG.to_string());
+        let task_name = format!("image-process-{}-{}", request.storage.id, request.file_name);
+        let payload = if job.is_none() { Some(serde_json::to_value(&request)?) } else { None };
+        let task =
+            TaskDescriptor::new(
+                task_name,
+                async move { pipeline.run_job(request, job, request_tag).await.map(|_| ()) },
+            );
+        match payload {
+            Some(payload) => self.runner.enqueue(task.with_payload(payload)),
+            None => self.runner.enqueue(task),
+        }
     }
 
     fn enqueue_derivative_request(&self, request: DerivativeProcessPayload) -> Result<()> {
@@ -181,17 +514,62 @@ impl ImageProcessPipeline {
             });
 
             if let Err(error) = pipeline.run_derivative_steps(request).await {
+                pipeline.record_outcome(Self::KIND_DERIVATIVES, false);
                 pipeline.emit_images_processed_if_idle(completion);
                 log::error!("Image derivative pipeline failed: {:?}", error);
                 return Err(error);
             }
 
+            pipeline.record_outcome(Self::KIND_DERIVATIVES, true);
             pipeline.emit_images_processed_if_idle(completion);
             Ok(())
         }))
     }
 
-    async fn run_steps(&self, request: ImageProcessPayload) -> Result<()> {
+    async fn run_job(
+        &self,
+        request: ImageProcessPayload,
+        job: Option<PipelineJob>,
+        request_tag: String,
+    ) -> Result<Option<Photo>> {
+        let mut job = job;
+        if let (Some(jobs), Some(job)) = (&self.jobs, job.as_mut()) {
+            job.mark_running();
+            Self::save_job(jobs, job.clone()).await;
+        }
+
+        let completion = json!({
+            "storageId": request.storage.id,
+            "storagePath": request.storage.path,
+            "fileName": request.file_name,
+            "relativePath": request.relative_path,
+        });
+
+        let photo = match self.run_steps(request).await {
+            Ok(photo) => photo,
+            Err(error) => {
+                if let (Some(jobs), Some(mut job)) = (&self.jobs, job) {
+                    job.mark_failed(&error.to_string());
+                    Self::save_job(jobs, job).await;
+                }
+                self.record_outcome(Self::KIND_IMPORT, false);
+                self.emit_images_processed_if_idle(completion);
+                log::error!("[request_id={}] Image process pipeline failed: {:?}", request_tag, error);
+                return Err(error);
+            }
+        };
+
+        log::debug!("[request_id={}] Image process pipeline completed: {}", request_tag, completion);
+        if let (Some(jobs), Some(mut job)) = (&self.jobs, job) {
+            job.mark_completed();
+            Self::save_job(jobs, job).await;
+        }
+        self.record_outcome(Self::KIND_IMPORT, true);
+        self.emit_images_processed_if_idle(completion);
+        Ok(photo)
+    }
+
+    async fn run_steps(&self, request: ImageProcessPayload) -> Result<Option<Photo>> {
         log::trace!("Starting pipeline for storage {} file {}", request.storage.id, request.file_name);
 
         let mut context = ImageProcessContext::new(request, self.services.clone());
@@ -205,15 +583,11 @@ impl ImageProcessPipeline {
                 break;
             }
         }
-        Ok(())
+        Ok(context.get_by_alias::<Photo>(ImageProcessKeys::PERSISTED_PHOTO).cloned())
     }
 
     async fn run_derivative_steps(&self, request: DerivativeProcessPayload) -> Result<()> {
-        log::trace!(
-            "Starting derivative pipeline for storage {} file {}",
-            request.storage.id,
-            request.file_name
-        );
+        log::trace!("Starting derivative pipeline for storage {} file {}", request.storage.id, request.file_name);
 
         let payload = ImageProcessPayload::new(
             request.storage.clone(),
@@ -236,6 +610,13 @@ impl ImageProcessPipeline {
         Ok(())
     }
 
+    fn record_outcome(&self, kind: &str, succeeded: bool) {
+        if let Some(metrics) = &self.metrics {
+            let name = if succeeded { MetricNames::IMAGES_PROCESSED_TOTAL } else { MetricNames::IMAGES_FAILED_TOTAL };
+            metrics.increment(name, &[("kind", kind)]);
+        }
+    }
+
     fn emit_images_processed_if_idle(&self, last_completed: JsonValue) {
         if self.runner.queued_count() != 0 || self.runner.running_count() != 1 {
             return;
@@ -8,8 +8,12 @@ impl ImageProcessKeys {
     pub const RAW_EXTENSIONS: [&'static str; 10] =
         ["cr2", "cr3", "nef", "arw", "dng", "orf", "raf", "rw2", "pef", "srw"];
 
+    pub const IMAGE_EXTENSIONS: [&'static str; 10] =
+        ["jpg", "jpeg", "png", "webp", "gif", "bmp", "tif", "tiff", "heic", "heif"];
+
     pub const THUMBNAIL_FORMAT_EXTENSION: &'static str = "webp";
     pub const THUMBNAIL_PATH: &'static str = "thumbnail_path";
+    pub const BLURHASH: &'static str = "blurhash";
     pub const PREVIEW_FORMAT_EXTENSION: &'static str = "jpg";
     pub const PREVIEW_PATH: &'static str = "preview_path";
 
@@ -19,4 +23,18 @@ impl ImageProcessKeys {
     pub const HASH: &'static str = "hash";
     pub const WORKING_DIRECTORY: &'static str = "working_directory";
     pub const FINAL_PATH: &'static str = "final_path";
+    pub const PERSISTED_PHOTO: &'static str = "persisted_photo";
+    pub const TAKEOUT_SIDECAR: &'static str = "takeout_sidecar";
+    pub const TAKEOUT_ALBUM: &'static str = "takeout_album";
+    pub const XMP_SIDECAR: &'static str = "xmp_sidecar";
+    pub const EMBEDDED_XMP: &'static str = "embedded_xmp";
+
+    pub fn is_supported_image(path: &std::path::Path) -> bool {
+        path.extension().and_then(|ext| ext.to_str()).is_some_and(|extension| {
+            Self::IMAGE_EXTENSIONS
+                .iter()
+                .chain(Self::RAW_EXTENSIONS.iter())
+                .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+        })
+    }
 }
@@ -2,16 +2,21 @@ use super::image_process_context::ImageProcessContext;
 use super::image_process_step::ImageProcessStep;
 use crate::entities::{exif::ExifModel, photo::Photo};
 use crate::models::setting_consts::SettingConsts;
+use crate::repositories::album_extensions::{AlbumExtensions, AlbumPhotoExtensions};
 use crate::repositories::photo_repo::PhotoRepositoryExtensions;
+use crate::repositories::tag_extensions::TagRepositoryExtensions;
+use crate::repositories::tag_suggestion_extensions::TagSuggestionRepositoryExtensions;
 use crate::services::exif_service::ExifService;
 use crate::services::hash_service::HashService;
-use crate::services::image_categorizer::{CategorizeRequest, ImageCategorizer, TemplateCategorizer};
+use crate::services::image_categorizer::{
+    CategorizeRequest, ImageCategorizer, InPlaceCategorizer, TemplateCategorizer,
+};
 use crate::services::image_process_constants::ImageProcessKeys;
-use crate::services::{PreviewExtractor, ThumbnailExtractor};
+use crate::services::{BlurhashService, ImageAnalyzerRegistry, PreviewExtractor, ThumbnailExtractor};
 
 use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
-use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use nimble_web::Repository;
 use nimble_web::ServiceProvider;
 use std::path::{Path, PathBuf};
@@ -88,17 +93,107 @@ impl ImageProcessStep for ExtractExifStep {
     }
 }
 
+pub(super) struct ReadTakeoutSidecarStep {}
+
+impl ReadTakeoutSidecarStep {
+    pub(super) fn new(_services: Arc<ServiceProvider>) -> Self {
+        Self {}
+    }
+
+    fn apply_location(exif: &mut ExifModel, location: TakeoutLocation) {
+        exif.gps_latitude = Some(location.latitude);
+        exif.gps_latitude_ref = Some(if location.latitude < 0.0 { "S" } else { "N" }.to_string());
+        exif.gps_longitude = Some(location.longitude);
+        exif.gps_longitude_ref = Some(if location.longitude < 0.0 { "W" } else { "E" }.to_string());
+        if let Some(altitude) = location.altitude {
+            exif.gps_altitude = Some(altitude);
+            exif.gps_altitude_ref = Some(if altitude < 0.0 { "1" } else { "0" }.to_string());
+        }
+    }
+}
+
+#[async_trait]
+impl ImageProcessStep for ReadTakeoutSidecarStep {
+    async fn execute(&self, context: &mut ImageProcessContext) -> Result<()> {
+        if !context.payload().import_format.is_takeout() {
+            return Ok(());
+        }
+
+        let source = context.source_path().to_path_buf();
+        let (sidecar, album) =
+            task::spawn_blocking(move || (TakeoutSidecar::load(&source), TakeoutAlbum::load(&source)))
+                .await
+                .context("takeout sidecar task join error")?;
+
+        if let Some(album) = album {
+            context.insert::<TakeoutAlbum>(ImageProcessKeys::TAKEOUT_ALBUM, album);
+        }
+        let Some(sidecar) = sidecar else {
+            log::debug!("No Takeout sidecar next to {}", context.source_path().display());
+            return Ok(());
+        };
+
+        // The camera's own timestamp wins; the sidecar only fills in photos that have none.
+        let exif_date = context
+            .get_by_alias::<Option<DateTime<Utc>>>(ImageProcessKeys::EXIF_DATE_TAKEN)
+            .and_then(|value| *value)
+            .or_else(|| context.get::<ExifModel>().and_then(|exif| exif.get_date_taken()));
+        if exif_date.is_none() {
+            if let Some(date_taken) = sidecar.date_taken() {
+                context.insert::<Option<DateTime<Utc>>>(ImageProcessKeys::EXIF_DATE_TAKEN, Some(date_taken));
+            }
+        }
+
+        if let (Some(location), Some(exif)) = (sidecar.location(), context.get_mut::<ExifModel>()) {
+            Self::apply_location(exif, location);
+        }
+
+        context.insert::<TakeoutSidecar>(ImageProcessKeys::TAKEOUT_SIDECAR, sidecar);
+        Ok(())
+    }
+}
+
+pub(super) struct ReadXmpSidecarStep {}
+
+impl ReadXmpSidecarStep {
+    pub(super) fn new(_services: Arc<ServiceProvider>) -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl ImageProcessStep for ReadXmpSidecarStep {
+    async fn execute(&self, context: &mut ImageProcessContext) -> Result<()> {
+        let source = context.source_path().to_path_buf();
+        let (sidecar, embedded) =
+            task::spawn_blocking(move || (XmpSidecar::load(&source), XmpSidecar::load_embedded(&source)))
+                .await
+                .context("xmp sidecar task join error")?;
+        if let Some(sidecar) = sidecar {
+            log::debug!("Read XMP sidecar for {}", context.source_path().display());
+            context.insert::<XmpSidecar>(ImageProcessKeys::XMP_SIDECAR, sidecar);
+        }
+        // EXIF already covers the rest of the embedded packet; only its face regions are used.
+        if let Some(embedded) = embedded.filter(|embedded| !embedded.people.is_empty()) {
+            context.insert::<XmpSidecar>(ImageProcessKeys::EMBEDDED_XMP, embedded);
+        }
+        Ok(())
+    }
+}
+
 pub(super) struct ComputeHashStep {
     services: Arc<ServiceProvider>,
     hash_service: Arc<HashService>,
     photo_repo: Arc<Repository<Photo>>,
+    exif_repo: Arc<Repository<ExifModel>>,
 }
 
 impl ComputeHashStep {
     pub(super) fn new(services: Arc<ServiceProvider>) -> Self {
         let hash_service = services.get::<HashService>();
         let photo_repo = services.get::<Repository<Photo>>();
-        Self { services, hash_service, photo_repo }
+        let exif_repo = services.get::<Repository<ExifModel>>();
+        Self { services, hash_service, photo_repo, exif_repo }
     }
 }
 
@@ -114,14 +209,18 @@ impl ImageProcessStep for ComputeHashStep {
             .context("hash compute join error")?
             .context("hash compute failed")?;
 
-        if self.photo_repo.find_by_hash(&hash).await?.is_some() {
-            log::info!(
-                "Photo with hash {} already exists. Stopping pipeline for {}",
-                hash,
-                context.source_path().display()
-            );
-            context.set_can_continue(false);
-            return Ok(());
+        if !self.photo_repo.find_by_hash_all(&hash).await?.is_empty() {
+            let storage_id = context.payload().storage.id;
+            if self.photo_repo.find_incomplete_import(&self.exif_repo, storage_id, &hash).await?.is_none() {
+                log::info!(
+                    "Photo with hash {} already exists. Stopping pipeline for {}",
+                    hash,
+                    context.source_path().display()
+                );
+                context.set_can_continue(false);
+                return Ok(());
+            }
+            log::info!("Resuming interrupted import of hash {} for {}", hash, context.source_path().display());
         }
 
         context.insert::<String>(ImageProcessKeys::HASH, hash.clone());
@@ -132,21 +231,31 @@ impl ImageProcessStep for ComputeHashStep {
 
 pub(super) struct GenerateThumbnailStep {
     services: Arc<ServiceProvider>,
-    extractor: Arc<ThumbnailExtractor>,
 }
 
 impl GenerateThumbnailStep {
     pub(super) fn new(services: Arc<ServiceProvider>) -> Self {
-        let extractor = services.get::<ThumbnailExtractor>();
-        Self { services, extractor }
+        Self { services }
     }
 
-    fn output_file(&self, root: &Path, hash: &str) -> PathBuf {
-        root.join(&hash[0..2]).join(&hash[2..4]).join(format!(
-            "{}.{}",
-            hash,
-            ImageProcessKeys::THUMBNAIL_FORMAT_EXTENSION
-        ))
+    fn output_file(&self, root: &Path, hash: &str, format: ThumbnailFormat) -> PathBuf {
+        FileService::hash_path(root, hash, format.extension())
+    }
+}
+
+// Best effort: a missing record only means the derivative is treated as built with the defaults.
+async fn record_derivative_profile(
+    services: &ServiceProvider,
+    storage_id: Uuid,
+    hash: &str,
+    kind: &str,
+    profile: &str,
+) {
+    let Some(photo_repo) = services.resolve::<Repository<Photo>>() else {
+        return;
+    };
+    if let Err(error) = photo_repo.record_derivative_profile(storage_id, hash, kind, profile).await {
+        log::warn!("Failed to record {} profile for {}: {:?}", kind, hash, error);
     }
 }
 
@@ -154,11 +263,13 @@ impl GenerateThumbnailStep {
 impl ImageProcessStep for GenerateThumbnailStep {
     async fn execute(&self, context: &mut ImageProcessContext) -> Result<()> {
         let thumbnail_root = context.payload().storage.normalized_path().join(SettingConsts::THUMBNAIL_FOLDER);
-        let hash = context.get_by_alias::<String>(ImageProcessKeys::HASH).ok_or_else(|| anyhow!("hash not found"))?;
+        let hash =
+            context.get_by_alias::<String>(ImageProcessKeys::HASH).cloned().ok_or_else(|| anyhow!("hash not found"))?;
+        let profile = DerivativeProfileCache::resolve(&self.services).await;
 
-        let output_path = self.output_file(&thumbnail_root, hash);
+        let output_path = self.output_file(&thumbnail_root, &hash, profile.thumbnail_format);
 
-        let extractor = Arc::clone(&self.extractor);
+        let extractor = ThumbnailExtractor::from_profile(&profile);
         let source = context.source_path().to_path_buf();
         let output = output_path.clone();
         task::spawn_blocking(move || {
@@ -168,6 +279,19 @@ impl ImageProcessStep for GenerateThumbnailStep {
         .await
         .context("thumbnail generation join error")??;
 
+        let signature = profile.thumbnail_signature();
+        let storage_id = context.payload().storage.id;
+        record_derivative_profile(&self.services, storage_id, &hash, DerivativeProfile::KIND_THUMBNAIL, &signature)
+            .await;
+
+        if let (true, Some(avif)) = (profile.thumbnail_avif, self.services.resolve::<AvifThumbnailService>()) {
+            let avif_path = self.output_file(&thumbnail_root, &hash, ThumbnailFormat::Avif);
+            avif.schedule(output_path.clone(), avif_path);
+        }
+
+        if let Some(blurhash) = BlurhashService::compute(output_path.clone()).await {
+            context.insert::<String>(ImageProcessKeys::BLURHASH, blurhash);
+        }
         context.insert::<PathBuf>(ImageProcessKeys::THUMBNAIL_PATH, output_path.clone());
         log::debug!("Thumbnail generation complete, output path: {}", output_path.display());
 
@@ -177,33 +301,29 @@ impl ImageProcessStep for GenerateThumbnailStep {
 
 pub(super) struct GeneratePreviewStep {
     services: Arc<ServiceProvider>,
-    extractor: Arc<PreviewExtractor>,
 }
 
 impl GeneratePreviewStep {
     pub(super) fn new(services: Arc<ServiceProvider>) -> Self {
-        let extractor = services.get::<PreviewExtractor>();
-        Self { services, extractor }
+        Self { services }
     }
 
     fn output_file(&self, root: &Path, hash: &str) -> PathBuf {
-        root.join(&hash[0..2]).join(&hash[2..4]).join(format!(
-            "{}.{}",
-            hash,
-            ImageProcessKeys::PREVIEW_FORMAT_EXTENSION
-        ))
+        FileService::hash_path(root, hash, ImageProcessKeys::PREVIEW_FORMAT_EXTENSION)
     }
 }
 
 #[async_trait]
 impl ImageProcessStep for GeneratePreviewStep {
     async fn execute(&self, context: &mut ImageProcessContext) -> Result<()> {
-        let preview_root = context.payload().storage.normalized_path().join(".previews");
-        let hash = context.get_by_alias::<String>(ImageProcessKeys::HASH).ok_or_else(|| anyhow!("hash not found"))?;
+        let preview_root = context.payload().storage.normalized_path().join(SettingConsts::PREVIEW_FOLDER);
+        let hash =
+            context.get_by_alias::<String>(ImageProcessKeys::HASH).cloned().ok_or_else(|| anyhow!("hash not found"))?;
+        let profile = DerivativeProfileCache::resolve(&self.services).await;
 
-        let output_path = self.output_file(&preview_root, hash);
+        let output_path = self.output_file(&preview_root, &hash);
 
-        let extractor = Arc::clone(&self.extractor);
+        let extractor = PreviewExtractor::from_profile(&profile);
         let source = context.source_path().to_path_buf();
         let output = output_path.clone();
         task::spawn_blocking(move || {
@@ -213,6 +333,10 @@ impl ImageProcessStep for GeneratePreviewStep {
         .await
         .context("preview generation join error")??;
 
+        let signature = profile.preview_signature();
+        let storage_id = context.payload().storage.id;
+        record_derivative_profile(&self.services, storage_id, &hash, DerivativeProfile::KIND_PREVIEW, &signature).await;
+
         context.insert::<PathBuf>(ImageProcessKeys::PREVIEW_PATH, output_path.clone());
         log::debug!("Preview generation complete, output path: {}", output_path.display());
 
@@ -242,7 +366,11 @@ impl ImageProcessStep for CategorizeImageStep {
             working_directory.map(|dir| dir.display().to_string()).unwrap_or_else(|| "none".to_string())
         );
 
-        let categorizer = TemplateCategorizer::new(category_template);
+        let categorizer: Box<dyn ImageCategorizer> = if context.payload().in_place {
+            Box::new(InPlaceCategorizer)
+        } else {
+            Box::new(TemplateCategorizer::new(category_template))
+        };
         let request = CategorizeRequest::new(context.source_path(), context.properties());
         let final_path = categorizer.categorize(&request)?.final_path;
 
@@ -266,6 +394,46 @@ impl PersistMetadataStep {
         let exif_repo = services.get::<Repository<ExifModel>>();
         Self { services, photo_repo, exif_repo }
     }
+
+    // Rules never fail ingestion; the photo is kept untagged and the error logged.
+    async fn apply_tag_rules(&self, photo: &Photo, exif: &ExifModel) {
+        let Some(rules) = self.services.resolve::<TagRuleService>() else {
+            return;
+        };
+        match rules.apply_to_photo(photo, Some(exif)).await {
+            Ok(applied) if !applied.is_empty() => log::debug!("Tag rules applied {:?} to {}", applied, photo.path),
+            Ok(_) => {}
+            Err(error) => log::warn!("Failed to apply tag rules to {}: {:?}", photo.path, error),
+        }
+    }
+
+    // Stacking is best-effort; a failure leaves the photo stand-alone.
+    async fn stack_with_partner(&self, mut photo: Photo) -> Photo {
+        let partner = match self.photo_repo.find_stack_partner(&photo).await {
+            Ok(Some(partner)) => partner,
+            Ok(None) => return photo,
+            Err(error) => {
+                log::warn!("Failed to look up stack partner for {}: {:?}", photo.path, error);
+                return photo;
+            }
+        };
+
+        let preference = match self.services.resolve::<SettingService>() {
+            Some(settings) => settings.stack_representative().await.unwrap_or_default(),
+            None => StackRepresentative::default(),
+        };
+        let members = [photo.clone(), partner];
+        let Some(representative) = PhotoStack::representative(&members, preference).map(|member| member.id) else {
+            return photo;
+        };
+        let member_ids = members.iter().map(|member| member.id).collect::<Vec<_>>();
+
+        match self.photo_repo.link_stack(&member_ids, representative).await {
+            Ok(()) => photo.stack_id = Some(representative),
+            Err(error) => log::warn!("Failed to stack {} with its pair: {:?}", photo.path, error),
+        }
+        photo
+    }
 }
 
 #[async_trait]
@@ -288,12 +456,12 @@ impl ImageProcessStep for PersistMetadataStep {
             .get_by_alias::<Option<DateTime<Utc>>>(ImageProcessKeys::EXIF_DATE_TAKEN)
             .and_then(|value| *value)
             .or_else(|| exif.get_date_taken());
-        let sort_date = date_taken.unwrap_or(now);
-        let day_date: NaiveDate = sort_date.date_naive();
-        let year = Some(sort_date.year());
-        let month_day = Some(sort_date.format("%m-%d").to_string());
+        // Edits made in Lightroom or digiKam live in the sidecar and override what the camera wrote.
+        let xmp = context.get_by_alias::<XmpSidecar>(ImageProcessKeys::XMP_SIDECAR);
+        let takeout_caption =
+            context.get_by_alias::<TakeoutSidecar>(ImageProcessKeys::TAKEOUT_SIDECAR).and_then(TakeoutSidecar::caption);
 
-        let photo = Photo {
+        let mut photo = Photo {
             id: Uuid::new_v4(),
             storage_id: context.payload().storage.id,
             path: final_path.to_string_lossy().to_string(),
@@ -309,8 +477,8 @@ impl ImageProcessStep for PersistMetadataStep {
             updated_at: Some(now),
             date_imported: Some(now),
             date_taken,
-            year,
-            month_day,
+            year: None,
+            month_day: None,
             metadata_extracted: Some(true),
             artist: exif.artist.clone(),
             make: exif.make.clone(),
@@ -321,8 +489,8 @@ impl ImageProcessStep for PersistMetadataStep {
             iso: exif.get_iso(),
             aperture: exif.get_aperture(),
             focal_length: exif.focal_length,
-            label: exif.label.clone(),
-            rating: exif.rating,
+            label: xmp.and_then(|xmp| xmp.label.clone()).or_else(|| exif.label.clone()),
+            rating: xmp.and_then(|xmp| xmp.rating).or(PhotoRating::normalize(exif.rating)),
             flagged: exif.flagged,
             is_raw: Some(
                 ImageProcessKeys::RAW_EXTENSIONS.iter().any(|candidate| candidate.eq_ignore_ascii_case(&extension)),
@@ -330,27 +498,328 @@ impl ImageProcessStep for PersistMetadataStep {
             width: exif.get_width(),
             height: exif.get_height(),
             orientation: exif.orientation,
-            day_date,
-            sort_date,
+            day_date: now.date_naive(),
+            sort_date: now,
+            title: xmp.and_then(|xmp| xmp.title.clone()),
+            caption: xmp.and_then(|xmp| xmp.description.clone()).or(takeout_caption),
+            stack_id: None,
+            blurhash: context.get_by_alias::<String>(ImageProcessKeys::BLURHASH).cloned(),
+            stack_count: None,
         };
-
-        let saved_photo =
-            self.photo_repo.insert(photo).await.map_err(|err| anyhow!("failed to insert photo: {:?}", err))?;
-        log::debug!("Photo metadata persisted with ID: {:?}", saved_photo.id);
+        photo.refresh_timeline_dates();
 
         let mut metadata = exif.clone();
         metadata.id = Uuid::new_v4();
-        metadata.image_id = saved_photo.id;
-        metadata.hash = hash;
+        metadata.hash = hash.clone();
+
+        // A retry finishes the photo an interrupted import left behind instead of adding a second row.
+        let incomplete = self.photo_repo.find_incomplete_import(&self.exif_repo, photo.storage_id, &hash).await?;
+        let saved_photo = match incomplete {
+            Some(existing) => {
+                metadata.image_id = existing.id;
+                self.exif_repo
+                    .insert(metadata)
+                    .await
+                    .map_err(|err| anyhow!("failed to insert exif metadata: {:?}", err))?;
+                existing
+            }
+            None => self
+                .photo_repo
+                .insert_photo_with_exif(&self.exif_repo, photo, metadata)
+                .await
+                .map_err(|err| anyhow!("failed to persist photo: {:?}", err))?,
+        };
+        log::debug!("Photo metadata persisted with ID: {:?}", saved_photo.id);
 
-        let _ = self
-            .exif_repo
-            .insert(metadata)
-            .await
-            .map_err(|err| anyhow!("failed to insert exif metadata: {:?}", err))?;
+        let saved_photo = self.stack_with_partner(saved_photo).await;
+        self.apply_tag_rules(&saved_photo, exif).await;
+        if let Some(live) = self.services.resolve::<LiveUpdateService>() {
+            live.publish(LiveEvent::PhotoAdded {
+                photo_id: saved_photo.id,
+                day: saved_photo.day_date,
+                hash: saved_photo.hash.clone(),
+            });
+        }
 
         log::debug!("Processed image {} into storage {}", saved_photo.name, saved_photo.path);
+        context.insert::<Photo>(ImageProcessKeys::PERSISTED_PHOTO, saved_photo);
+
+        Ok(())
+    }
+}
+
+pub(super) struct ApplyXmpKeywordsStep {
+    photo_repo: Arc<Repository<Photo>>,
+    tag_repo: Arc<Repository<Tag>>,
+}
+
+impl ApplyXmpKeywordsStep {
+    pub(super) fn new(services: Arc<ServiceProvider>) -> Self {
+        Self { photo_repo: services.get::<Repository<Photo>>(), tag_repo: services.get::<Repository<Tag>>() }
+    }
+
+    async fn tag_keywords(&self, photo: &Photo, keywords: &[String]) -> Result<()> {
+        let refs = keywords.iter().cloned().map(TagRef::Name).collect::<Vec<_>>();
+        let tag_ids = self
+            .tag_repo
+            .resolve_tag_ids(&refs, Tag::VISIBILITY_PUBLIC)
+            .await
+            .map_err(|err| anyhow!("failed to resolve keyword tags: {:?}", err))?;
+        self.photo_repo
+            .add_photo_tags_bulk(&[photo.id], &tag_ids)
+            .await
+            .map_err(|err| anyhow!("failed to tag keywords: {:?}", err))
+    }
+}
+
+#[async_trait]
+impl ImageProcessStep for ApplyXmpKeywordsStep {
+    async fn execute(&self, context: &mut ImageProcessContext) -> Result<()> {
+        let Some(photo) = context.get_by_alias::<Photo>(ImageProcessKeys::PERSISTED_PHOTO) else {
+            return Ok(());
+        };
+        let Some(xmp) = context.get_by_alias::<XmpSidecar>(ImageProcessKeys::XMP_SIDECAR) else {
+            return Ok(());
+        };
+        if xmp.keywords.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(error) = self.tag_keywords(photo, &xmp.keywords).await {
+            log::warn!("Failed to apply XMP keywords to {}: {:?}", photo.path, error);
+        }
+        Ok(())
+    }
+}
+
+// Records named faces from the sidecar, the image's own XMP and a Takeout export. Earlier sources win
+// on names; later ones only add people or fill in missing regions.
+pub(super) struct ApplyPeopleStep {
+    photo_repo: Arc<Repository<Photo>>,
+}
+
+impl ApplyPeopleStep {
+    pub(super) fn new(services: Arc<ServiceProvider>) -> Self {
+        Self { photo_repo: services.get::<Repository<Photo>>() }
+    }
+}
+
+#[async_trait]
+impl ImageProcessStep for ApplyPeopleStep {
+    async fn execute(&self, context: &mut ImageProcessContext) -> Result<()> {
+        let Some(photo) = context.get_by_alias::<Photo>(ImageProcessKeys::PERSISTED_PHOTO) else {
+            return Ok(());
+        };
+
+        let mut sources = Vec::new();
+        if let Some(xmp) = context.get_by_alias::<XmpSidecar>(ImageProcessKeys::XMP_SIDECAR) {
+            sources.push((PhotoPerson::SOURCE_XMP, xmp.people.clone()));
+        }
+        if let Some(embedded) = context.get_by_alias::<XmpSidecar>(ImageProcessKeys::EMBEDDED_XMP) {
+            sources.push((PhotoPerson::SOURCE_EMBEDDED, embedded.people.clone()));
+        }
+        if let Some(takeout) = context.get_by_alias::<TakeoutSidecar>(ImageProcessKeys::TAKEOUT_SIDECAR) {
+            let people = takeout.people().iter().filter_map(|name| PersonMention::new(name, None)).collect();
+            sources.push((PhotoPerson::SOURCE_TAKEOUT, people));
+        }
+
+        for (source, people) in sources {
+            if people.is_empty() {
+                continue;
+            }
+            if let Err(error) = self.photo_repo.add_photo_people(photo.id, &people, source).await {
+                log::warn!("Failed to record {} people for {}: {:?}", source, photo.path, error);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Asks the configured analyzer for tag suggestions. The request runs detached from the import so a
+// slow or unavailable inference service never delays or fails it; suggestions wait for a user to
+// accept them.
+pub(super) struct AnalyzeImageStep {
+    photo_repo: Arc<Repository<Photo>>,
+    registry: Arc<ImageAnalyzerRegistry>,
+    slots: Arc<tokio::sync::Semaphore>,
+}
+
+impl AnalyzeImageStep {
+    const MAX_CONCURRENT_ANALYSES: usize = 2;
+
+    pub(super) fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photo_repo: services.get::<Repository<Photo>>(),
+            registry: services.get::<ImageAnalyzerRegistry>(),
+            slots: Arc::new(tokio::sync::Semaphore::new(Self::MAX_CONCURRENT_ANALYSES)),
+        }
+    }
+}
 
+#[async_trait]
+impl ImageProcessStep for AnalyzeImageStep {
+    async fn execute(&self, context: &mut ImageProcessContext) -> Result<()> {
+        let (Some(photo), Some(preview_path)) = (
+            context.get_by_alias::<Photo>(ImageProcessKeys::PERSISTED_PHOTO),
+            context.get_by_alias::<PathBuf>(ImageProcessKeys::PREVIEW_PATH),
+        ) else {
+            return Ok(());
+        };
+
+        let (analyzer, min_confidence) = match self.registry.resolve().await {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                log::warn!("Skipping image analysis for {}: {:?}", photo.path, error);
+                return Ok(());
+            }
+        };
+        if !analyzer.enabled() {
+            return Ok(());
+        }
+
+        let photo_id = photo.id;
+        let photo_path = photo.path.clone();
+        let preview_path = preview_path.clone();
+        let photo_repo = Arc::clone(&self.photo_repo);
+        let slots = Arc::clone(&self.slots);
+        // The HTTP analyzer enforces its own timeout; this also bounds analyzers registered in-process.
+        let limit = std::time::Duration::from_secs_f64(ImageAnalyzerConfig::TIMEOUT_RANGE.1);
+        tokio::spawn(async move {
+            let Ok(_permit) = slots.acquire_owned().await else {
+                return;
+            };
+            let suggestions = match tokio::time::timeout(limit, analyzer.analyze(&preview_path)).await {
+                Ok(Ok(suggestions)) => TagSuggestion::normalize(suggestions, min_confidence),
+                Ok(Err(error)) => {
+                    log::warn!("Image analyzer '{}' failed for {}: {:?}", analyzer.name(), photo_path, error);
+                    return;
+                }
+                Err(_) => {
+                    log::warn!("Image analyzer '{}' timed out for {}", analyzer.name(), photo_path);
+                    return;
+                }
+            };
+            if let Err(error) = photo_repo.save_tag_suggestions(photo_id, analyzer.name(), &suggestions).await {
+                log::warn!("Failed to store tag suggestions for {}: {:?}", photo_path, error);
+            }
+        });
+        Ok(())
+    }
+}
+
+// Turns the people and album information from a Takeout export into tags and albums. Like tag
+// rules, failures here are logged and never undo the import.
+pub(super) struct ApplyTakeoutMetadataStep {
+    photo_repo: Arc<Repository<Photo>>,
+    tag_repo: Arc<Repository<Tag>>,
+    album_repo: Arc<Repository<Album>>,
+    album_photo_repo: Arc<Repository<AlbumPhoto>>,
+    // Photos from one album folder import in parallel; without this each could create the album.
+    album_lock: tokio::sync::Mutex<()>,
+}
+
+impl ApplyTakeoutMetadataStep {
+    pub(super) fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photo_repo: services.get::<Repository<Photo>>(),
+            tag_repo: services.get::<Repository<Tag>>(),
+            album_repo: services.get::<Repository<Album>>(),
+            album_photo_repo: services.get::<Repository<AlbumPhoto>>(),
+            album_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    async fn tag_people(&self, photo: &Photo, people: Vec<String>) -> Result<()> {
+        let refs = people.into_iter().map(TagRef::Name).collect::<Vec<_>>();
+        let tag_ids = self
+            .tag_repo
+            .resolve_tag_ids(&refs, Tag::VISIBILITY_PUBLIC)
+            .await
+            .map_err(|err| anyhow!("failed to resolve people tags: {:?}", err))?;
+        self.photo_repo
+            .add_photo_tags_bulk(&[photo.id], &tag_ids)
+            .await
+            .map_err(|err| anyhow!("failed to tag people: {:?}", err))
+    }
+
+    async fn add_to_album(&self, photo: &Photo, album: &TakeoutAlbum) -> Result<()> {
+        let _guard = self.album_lock.lock().await;
+        let existing = self
+            .album_repo
+            .all(
+                QueryBuilder::<Album>::new()
+                    .filter("name", FilterOperator::Eq, Value::String(album.title.clone()))
+                    .build(),
+            )
+            .await
+            .map_err(|err| anyhow!("failed to look up album '{}': {:?}", album.title, err))?;
+
+        let album_id = match existing.into_iter().find(|candidate| matches!(candidate.kind, AlbumKind::Manual)) {
+            Some(existing) => existing.id,
+            None => {
+                let created = Album {
+                    id: Uuid::new_v4(),
+                    parent_id: None,
+                    name: album.title.clone(),
+                    create_date: Some(Utc::now()),
+                    description: album.description.clone().filter(|text| !text.trim().is_empty()),
+                    category: None,
+                    kind: AlbumKind::Manual,
+                    thumbnail_hash: photo.hash.clone(),
+                    sort_order: 0,
+                    image_count: None,
+                    version: 1,
+                    start_date: None,
+                    end_date: None,
+                    dates_overridden: false,
+                    location_name: None,
+                    reset_dates: false,
+                };
+                let album_id = created.id;
+                self.album_repo
+                    .insert(created)
+                    .await
+                    .map_err(|err| anyhow!("failed to create album '{}': {:?}", album.title, err))?;
+                album_id
+            }
+        };
+
+        self.album_photo_repo
+            .add_photos_to_album(album_id, &[photo.id])
+            .await
+            .map_err(|err| anyhow!("failed to add photo to album '{}': {:?}", album.title, err))?;
+        self.album_repo
+            .refresh_album_dates(album_id)
+            .await
+            .map_err(|err| anyhow!("failed to refresh album dates: {:?}", err))
+    }
+}
+
+#[async_trait]
+impl ImageProcessStep for ApplyTakeoutMetadataStep {
+    async fn execute(&self, context: &mut ImageProcessContext) -> Result<()> {
+        if !context.payload().import_format.is_takeout() {
+            return Ok(());
+        }
+        let Some(photo) = context.get_by_alias::<Photo>(ImageProcessKeys::PERSISTED_PHOTO) else {
+            return Ok(());
+        };
+
+        let people = context
+            .get_by_alias::<TakeoutSidecar>(ImageProcessKeys::TAKEOUT_SIDECAR)
+            .map(TakeoutSidecar::people)
+            .unwrap_or_default();
+        if !people.is_empty() {
+            if let Err(error) = self.tag_people(photo, people).await {
+                log::warn!("Failed to apply Takeout people to {}: {:?}", photo.path, error);
+            }
+        }
+
+        if let Some(album) = context.get_by_alias::<TakeoutAlbum>(ImageProcessKeys::TAKEOUT_ALBUM) {
+            if let Err(error) = self.add_to_album(photo, album).await {
+                log::warn!("Failed to add {} to Takeout album '{}': {:?}", photo.path, album.title, error);
+            }
+        }
         Ok(())
     }
 }
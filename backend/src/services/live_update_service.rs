@@ -0,0 +1,310 @@
+use std::sync::Mutex;
+use std::time::Instant;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+
+use crate::prelude::*;
+
+pub struct LiveConnectionRegistry {
+    max_per_user: usize,
+    active: Mutex<HashMap<Uuid, usize>>,
+}
+
+impl LiveConnectionRegistry {
+    pub fn new(max_per_user: usize) -> Self {
+        Self { max_per_user: max_per_user.max(1), active: Mutex::new(HashMap::new()) }
+    }
+
+    // The slot is released when the returned guard is dropped.
+    pub fn try_acquire(self: &Arc<Self>, user_id: Uuid) -> Option<LiveConnectionGuard> {
+        let mut active = self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let count = active.entry(user_id).or_insert(0);
+        if *count >= self.max_per_user {
+            return None;
+        }
+        *count += 1;
+        Some(LiveConnectionGuard { registry: Arc::clone(self), user_id })
+    }
+
+    pub fn active(&self, user_id: Uuid) -> usize {
+        let active = self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        active.get(&user_id).copied().unwrap_or_default()
+    }
+
+    fn release(&self, user_id: Uuid) {
+        let mut active = self.active.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(count) = active.get_mut(&user_id) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                active.remove(&user_id);
+            }
+        }
+    }
+}
+
+pub struct LiveConnectionGuard {
+    registry: Arc<LiveConnectionRegistry>,
+    user_id: Uuid,
+}
+
+impl LiveConnectionGuard {
+    pub fn user_id(&self) -> Uuid {
+        self.user_id
+    }
+}
+
+impl Drop for LiveConnectionGuard {
+    fn drop(&mut self) {
+        self.registry.release(self.user_id);
+    }
+}
+
+// Browsers cannot set headers on a WebSocket handshake, so the client trades its bearer token
+// for a short-lived single-use ticket and passes that in the query string instead.
+pub struct LiveTicketStore {
+    ttl: Duration,
+    tickets: Mutex<HashMap<String, (Uuid, LiveAudience, DateTime<Utc>)>>,
+}
+
+impl LiveTicketStore {
+    pub const DEFAULT_TTL_SECONDS: i64 = 30;
+
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, tickets: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn issue(&self, user_id: Uuid, audience: LiveAudience, now: DateTime<Utc>) -> (String, DateTime<Utc>) {
+        let ticket = Uuid::new_v4().simple().to_string();
+        let expires_at = now + self.ttl;
+        let mut tickets = self.tickets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        tickets.retain(|_, (_, _, expiry)| *expiry > now);
+        tickets.insert(ticket.clone(), (user_id, audience, expires_at));
+        (ticket, expires_at)
+    }
+
+    pub fn redeem(&self, ticket: &str, now: DateTime<Utc>) -> Option<(Uuid, LiveAudience)> {
+        let mut tickets = self.tickets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        tickets
+            .remove(ticket)
+            .filter(|(_, _, expires_at)| *expires_at > now)
+            .map(|(user_id, audience, _)| (user_id, audience))
+    }
+}
+
+#[derive(Clone)]
+pub struct LiveUpdateService {
+    services: Arc<ServiceProvider>,
+    event_bus: Arc<EventBusService>,
+    revoked: Arc<RevokedSubjectRegistry>,
+    registry: Arc<LiveConnectionRegistry>,
+    tickets: Arc<LiveTicketStore>,
+    address: Option<String>,
+}
+
+impl LiveUpdateService {
+    pub const PATH: &'static str = "/api/ws";
+    pub const HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+    pub const HEARTBEAT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(75);
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        let config = services.get::<AppConfig>();
+        Self {
+            event_bus: services.get::<EventBusService>(),
+            revoked: services.get::<RevokedSubjectRegistry>(),
+            registry: Arc::new(LiveConnectionRegistry::new(config.live_max_connections_per_user)),
+            tickets: Arc::new(LiveTicketStore::new(Duration::seconds(LiveTicketStore::DEFAULT_TTL_SECONDS))),
+            address: config.live_enabled.then(|| config.live_address.clone()),
+            services,
+        }
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    pub fn issue_ticket(&self, user_id: Uuid, audience: LiveAudience) -> (String, DateTime<Utc>) {
+        self.tickets.issue(user_id, audience, Utc::now())
+    }
+
+    pub fn publish(&self, event: LiveEvent) {
+        let Some(topic) = event.topic() else {
+            return;
+        };
+        match serde_json::to_value(&event) {
+            Ok(payload) => {
+                self.event_bus.emit(topic, payload);
+            }
+            Err(error) => log::warn!("Failed to serialize live event '{}': {}", topic, error),
+        }
+    }
+
+    pub fn is_stale(last_seen: Instant, now: Instant) -> bool {
+        now.saturating_duration_since(last_seen) > Self::HEARTBEAT_TIMEOUT
+    }
+
+    pub fn ticket_from_query(query: &str) -> Option<String> {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("ticket="))
+            .map(str::to_string)
+            .filter(|ticket| !ticket.is_empty())
+    }
+
+    pub fn start(&self) {
+        let Some(address) = self.address.clone() else {
+            log::info!("Live updates are disabled");
+            return;
+        };
+        let service = self.clone();
+        tokio::spawn(async move {
+            let listener = match TcpListener::bind(&address).await {
+                Ok(listener) => listener,
+                Err(error) => {
+                    log::error!("Failed to bind live updates on {}: {}", address, error);
+                    return;
+                }
+            };
+            log::info!("Live updates listening on ws://{}{}", address, Self::PATH);
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        let service = service.clone();
+                        tokio::spawn(async move {
+                            if let Err(error) = service.serve(stream).await {
+                                log::debug!("Live connection from {} ended: {}", peer, error);
+                            }
+                        });
+                    }
+                    Err(error) => log::warn!("Failed to accept live connection: {}", error),
+                }
+            }
+        });
+    }
+
+    async fn serve(&self, stream: TcpStream) -> anyhow::Result<()> {
+        let mut connection = None;
+        let socket = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+            connection = Some(self.authorize(request)?);
+            Ok(response)
+        })
+        .await?;
+        let Some((connection, audience)) = connection else {
+            return Ok(());
+        };
+        let subject = connection.user_id().to_string();
+        log::debug!("Live connection opened for {}", connection.user_id());
+
+        let (mut sink, mut stream) = socket.split();
+        let mut events = self.event_bus.subscribe();
+        let mut heartbeat = tokio::time::interval(Self::HEARTBEAT_INTERVAL);
+        let mut subscription = LiveSubscription::default();
+        let mut last_seen = Instant::now();
+
+        loop {
+            tokio::select! {
+                message = stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        last_seen = Instant::now();
+                        match serde_json::from_str::<LiveClientMessage>(&text) {
+                            Ok(LiveClientMessage::Subscribe(next)) => subscription = next,
+                            Ok(LiveClientMessage::Unsubscribe) => subscription = LiveSubscription::default(),
+                            Err(error) => log::debug!("Ignoring live message from {}: {}", connection.user_id(), error),
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => last_seen = Instant::now(),
+                    Some(Err(error)) => return Err(error.into()),
+                },
+                event = events.recv() => {
+                    if self.revoked.is_revoked(&subject) {
+                        log::debug!("Closing live connection for revoked user {}", connection.user_id());
+                        break;
+                    }
+                    let live = match event {
+                        Ok(event) => LiveEvent::from_app_event(&event),
+                        Err(RecvError::Lagged(skipped)) => Some(LiveEvent::Resync { skipped }),
+                        Err(RecvError::Closed) => break,
+                    };
+                    if let Some(live) = live.filter(|live| subscription.matches(live)) {
+                        if self.visible_to(audience, &live).await {
+                            sink.send(Message::text(serde_json::to_string(&live)?)).await?;
+                        }
+                    }
+                }
+                _ = heartbeat.tick() => {
+                    if self.revoked.is_revoked(&subject) {
+                        log::debug!("Closing live connection for revoked user {}", connection.user_id());
+                        break;
+                    }
+                    if Self::is_stale(last_seen, Instant::now()) {
+                        log::debug!("Dropping unresponsive live connection for {}", connection.user_id());
+                        break;
+                    }
+                    sink.send(Message::Ping(Default::default())).await?;
+                }
+            }
+        }
+
+        let _ = sink.close().await;
+        Ok(())
+    }
+
+    // Photo events go out to every socket, so each one is checked against that socket's audience before
+    // it is sent. Lookup failures drop the event rather than risk leaking a hidden photo.
+    async fn visible_to(&self, audience: LiveAudience, event: &LiveEvent) -> bool {
+        let Some(photo_id) = event.photo_id() else {
+            return true;
+        };
+        if audience == LiveAudience::Admin {
+            return true;
+        }
+        let Some(tag_repo) = self.services.resolve::<Repository<Tag>>() else {
+            return false;
+        };
+        let Ok(tags) = tag_repo.get_photo_tags(photo_id).await else {
+            return false;
+        };
+        let viewer_hidden_tags = match audience {
+            LiveAudience::Viewer => {
+                let Some(settings) = self.services.resolve::<SettingService>() else {
+                    return false;
+                };
+                match settings.viewer_hidden_tags().await {
+                    Ok(hidden) => hidden,
+                    Err(_) => return false,
+                }
+            }
+            _ => HashSet::new(),
+        };
+        audience.can_see(&tags, &viewer_hidden_tags)
+    }
+
+    fn authorize(&self, request: &Request) -> Result<(LiveConnectionGuard, LiveAudience), ErrorResponse> {
+        if request.uri().path() != Self::PATH {
+            return Err(Self::reject(StatusCode::NOT_FOUND, "Not found"));
+        }
+        let (user_id, audience) = request
+            .uri()
+            .query()
+            .and_then(Self::ticket_from_query)
+            .and_then(|ticket| self.tickets.redeem(&ticket, Utc::now()))
+            .filter(|(user_id, _)| !self.revoked.is_revoked(&user_id.to_string()))
+            .ok_or_else(|| Self::reject(StatusCode::UNAUTHORIZED, "Invalid or expired ticket"))?;
+        let guard = self
+            .registry
+            .try_acquire(user_id)
+            .ok_or_else(|| Self::reject(StatusCode::TOO_MANY_REQUESTS, "Too many live connections"))?;
+        Ok((guard, audience))
+    }
+
+    fn reject(status: StatusCode, message: &str) -> ErrorResponse {
+        let mut response = ErrorResponse::new(Some(message.to_string()));
+        *response.status_mut() = status;
+        response
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct MetricKey {
+    name: String,
+    labels: Vec<(String, String)>,
+}
+
+impl MetricKey {
+    fn new(name: &str, labels: &[(&str, &str)]) -> Self {
+        let mut labels: Vec<(String, String)> =
+            labels.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        labels.sort();
+        Self { name: name.to_string(), labels }
+    }
+
+    fn render_labels(&self, extra: Option<(&str, &str)>) -> String {
+        let mut pairs: Vec<String> = self
+            .labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, MetricsService::escape_label(value)))
+            .collect();
+        if let Some((key, value)) = extra {
+            pairs.push(format!("{}=\"{}\"", key, value));
+        }
+
+        if pairs.is_empty() { String::new() } else { format!("{{{}}}", pairs.join(",")) }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    bucket_counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self { bucket_counts: vec![0; MetricsService::HISTOGRAM_BUCKETS.len()], sum: 0.0, count: 0 }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (index, bound) in MetricsService::HISTOGRAM_BUCKETS.iter().enumerate() {
+            if value <= *bound {
+                self.bucket_counts[index] += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Default)]
+struct MetricsRegistry {
+    counters: BTreeMap<MetricKey, u64>,
+    gauges: BTreeMap<MetricKey, f64>,
+    histograms: BTreeMap<MetricKey, Histogram>,
+}
+
+#[derive(Clone)]
+pub struct MetricsService {
+    registry: Arc<Mutex<MetricsRegistry>>,
+}
+
+impl MetricsService {
+    pub const CONTENT_TYPE: &'static str = "text/plain; version=0.0.4";
+    const HISTOGRAM_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+    pub fn new() -> Self {
+        Self { registry: Arc::new(Mutex::new(MetricsRegistry::default())) }
+    }
+
+    pub fn increment(&self, name: &str, labels: &[(&str, &str)]) {
+        if let Ok(mut registry) = self.registry.lock() {
+            *registry.counters.entry(MetricKey::new(name, labels)).or_insert(0) += 1;
+        }
+    }
+
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Ok(mut registry) = self.registry.lock() {
+            registry.gauges.insert(MetricKey::new(name, labels), value);
+        }
+    }
+
+    pub fn observe(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if let Ok(mut registry) = self.registry.lock() {
+            registry.histograms.entry(MetricKey::new(name, labels)).or_insert_with(Histogram::new).observe(value);
+        }
+    }
+
+    pub fn counter_value(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        self.registry
+            .lock()
+            .ok()
+            .and_then(|registry| registry.counters.get(&MetricKey::new(name, labels)).copied())
+            .unwrap_or(0)
+    }
+
+    pub fn render(&self) -> String {
+        let Ok(registry) = self.registry.lock() else {
+            return String::new();
+        };
+
+        let mut output = String::new();
+        let mut current_name: Option<&str> = None;
+        for (key, value) in &registry.counters {
+            Self::write_type(&mut output, &mut current_name, &key.name, "counter");
+            let _ = writeln!(output, "{}{} {}", key.name, key.render_labels(None), value);
+        }
+
+        current_name = None;
+        for (key, value) in &registry.gauges {
+            Self::write_type(&mut output, &mut current_name, &key.name, "gauge");
+            let _ = writeln!(output, "{}{} {}", key.name, key.render_labels(None), value);
+        }
+
+        current_name = None;
+        for (key, histogram) in &registry.histograms {
+            Self::write_type(&mut output, &mut current_name, &key.name, "histogram");
+            for (bound, count) in Self::HISTOGRAM_BUCKETS.iter().zip(&histogram.bucket_counts) {
+                let bound = bound.to_string();
+                let _ = writeln!(output, "{}_bucket{} {}", key.name, key.render_labels(Some(("le", &bound))), count);
+            }
+            let _ =
+                writeln!(output, "{}_bucket{} {}", key.name, key.render_labels(Some(("le", "+Inf"))), histogram.count);
+            let _ = writeln!(output, "{}_sum{} {}", key.name, key.render_labels(None), histogram.sum);
+            let _ = writeln!(output, "{}_count{} {}", key.name, key.render_labels(None), histogram.count);
+        }
+
+        output
+    }
+
+    fn write_type<'a>(output: &mut String, current_name: &mut Option<&'a str>, name: &'a str, kind: &str) {
+        if *current_name != Some(name) {
+            let _ = writeln!(output, "# TYPE {} {}", name, kind);
+            *current_name = Some(name);
+        }
+    }
+
+    fn escape_label(value: &str) -> String {
+        value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+    }
+}
+
+impl Default for MetricsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,58 +3,138 @@ mod image_process_context;
 mod image_process_step;
 
 pub mod admin_user_service;
+pub mod album_membership_cache;
+pub mod api_doc_service;
+pub mod asset_relocation_service;
+pub mod audit_service;
+pub mod avif_thumbnail_service;
 pub mod auth_service;
 pub mod background_task_runner;
+pub mod blurhash_service;
 pub mod browse_service;
+pub mod config_reload_service;
+pub mod cors_policy_cache;
+pub mod derivative_profile_cache;
+pub mod cursor_signer;
+pub mod dashboard_overview_service;
+pub mod database_health_service;
+pub mod disk_info_service;
+pub mod email_service;
+pub mod email_verification_service;
+pub mod embed_service;
 pub mod encrypt_service;
 pub mod event_bus_service;
+pub mod event_album_service;
 pub mod exif_service;
+pub mod feed_service;
 pub mod file_service;
+pub mod folder_import_service;
+pub mod gps_scrub_service;
 pub mod hash_service;
 pub mod id_generation_service;
+pub mod image_analyzer;
+pub mod live_update_service;
+pub mod metrics_service;
+pub mod notification_digest_service;
+pub mod notification_service;
 pub mod image_categorizer;
 pub mod image_pipeline;
 pub mod image_process_steps;
+pub mod photo_path_repair_service;
 pub mod photo_service;
 pub mod photo_upload_service;
+pub mod preview_cache_service;
+pub mod preview_coordinator;
 pub mod preview_extractor;
+pub mod preview_warmer_service;
+pub mod revoked_subject_registry;
 pub mod setting_service;
+pub mod setup_service;
+pub mod storage_path_cache;
+pub mod storage_path_guard;
+pub mod startup_secrets;
 pub mod storage_service;
 pub mod sync_service;
+pub mod tag_rule_service;
+pub mod two_factor_service;
 pub mod task_descriptor;
 pub mod thumbnail_extractor;
+pub mod upload_session_service;
+pub mod xmp_sidecar_service;
 
-pub use admin_user_service::AdminUserService;
+pub use admin_user_service::{AdminUserFilter, AdminUserService};
+pub use album_membership_cache::{AlbumMembershipCache, AlbumMembershipStamp};
+pub use api_doc_service::{ApiAnnotation, ApiDocService, ApiOperation};
+pub use asset_relocation_service::AssetRelocationService;
+pub use audit_service::{AuditLogFilter, AuditService};
+pub use avif_thumbnail_service::AvifThumbnailService;
 pub use auth_service::AuthService;
 pub use background_task_runner::BackgroundTaskRunner;
+pub use blurhash_service::BlurhashService;
 pub use browse_service::BrowseService;
+pub use config_reload_service::{ConfigReloadPlan, ConfigReloadService};
+pub use cors_policy_cache::CorsPolicyCache;
+pub use derivative_profile_cache::DerivativeProfileCache;
+pub use cursor_signer::CursorSigner;
+pub use dashboard_overview_service::DashboardOverviewService;
+pub use database_health_service::{DatabaseHealthService, StartupRetryPolicy};
+pub use disk_info_service::DiskInfoService;
+pub use email_service::{EmailMessage, EmailService};
+pub use email_verification_service::EmailVerificationService;
+pub use embed_service::EmbedService;
 pub use encrypt_service::EncryptService;
 pub use event_bus_service::AppEvent;
 pub use event_bus_service::EventBusService;
+pub use event_album_service::{EventAlbumService, EventCandidate, EventClusterOptions};
 pub use exif_service::ExifService;
+pub use feed_service::FeedService;
 pub use file_service::FileService;
+pub use folder_import_service::FolderImportService;
+pub use gps_scrub_service::GpsScrubService;
 pub use hash_service::HashService;
 pub use id_generation_service::IdGenerationService;
+pub use image_analyzer::{HttpImageAnalyzer, ImageAnalyzer, ImageAnalyzerRegistry, NoopImageAnalyzer};
+pub use live_update_service::{LiveConnectionGuard, LiveConnectionRegistry, LiveTicketStore, LiveUpdateService};
 pub use image_categorizer::{
-    CategorizeRequest, CategorizeResult, ImageCategorizer, TemplateCategorizer,
+    CategorizeRequest, CategorizeResult, ImageCategorizer, InPlaceCategorizer, TemplateCategorizer,
 };
 pub use image_pipeline::ImageProcessPipeline;
 pub use image_pipeline::ImageProcessPipelineContext;
+pub use image_pipeline::{SyncProcessStatus, SyncProcessedFile};
+pub use metrics_service::MetricsService;
+pub use notification_digest_service::NotificationDigestService;
+pub use notification_service::NotificationService;
+pub use photo_path_repair_service::PhotoPathRepairService;
 pub use photo_service::PhotoService;
 pub use photo_upload_service::PhotoUploadService;
+pub use photo_upload_service::PersistedUploads;
+pub use photo_upload_service::RejectedUploadFile;
 pub use photo_upload_service::StoredUploadFile;
+pub use preview_cache_service::PreviewCacheService;
+pub use preview_coordinator::{PreviewBusy, PreviewCoordinator};
 pub use preview_extractor::PreviewExtractor;
+pub use preview_warmer_service::PreviewWarmerService;
+pub use revoked_subject_registry::RevokedSubjectRegistry;
 pub use setting_service::SettingKeys;
 pub use setting_service::SettingService;
+pub use setup_service::{SetupError, SetupService};
+pub use storage_path_cache::StoragePathCache;
+pub use storage_path_guard::StoragePathGuard;
+pub use startup_secrets::{AppEnvironment, SecretSource, StartupSecrets};
 pub use storage_service::StorageService;
 pub use sync_service::SyncService;
-pub use task_descriptor::TaskDescriptor;
+pub use tag_rule_service::TagRuleService;
+pub use two_factor_service::{TwoFactorError, TwoFactorService};
+pub use task_descriptor::{TaskDescriptor, TaskInfo, TaskState};
 pub use thumbnail_extractor::ThumbnailExtractor;
+pub use upload_session_service::{UploadSession, UploadSessionError, UploadSessionService};
+pub use xmp_sidecar_service::XmpSidecarService;
 
 use std::sync::Arc;
 
 use crate::entities::{
-    setting::Setting, user::User, user_settings::UserSettings,
+    album_comment::AlbumComment, audit_log::AuditLog, photo::Photo, photo_comment::PhotoComment, setting::Setting,
+    storage_location::StorageLocation, user::User, user_settings::UserSettings,
 };
 use nimble_web::AppBuilder;
 use nimble_web::Configuration;
@@ -66,43 +146,35 @@ use sqlx::PgPool;
 pub fn register_services(builder: &mut AppBuilder) -> &mut AppBuilder {
     builder.register_singleton(|provider| {
         let config = provider.get::<Configuration>();
-        EncryptService::new(&config).expect("Failed to create EncryptService")
+        AppConfig::from_configuration(&config).expect("Invalid configuration")
     });
     builder.register_singleton(|provider| {
-        let capacity = provider
-            .get::<Configuration>()
-            .get("eventbus.capacity")
-            .and_then(|value| value.parse::<usize>().ok())
-            .filter(|value| *value > 0)
-            .unwrap_or(256);
-        EventBusService::new(capacity)
+        let config = provider.get::<AppConfig>();
+        StartupSecrets::from_config(&config).expect("Failed to resolve startup secrets")
+    });
+    builder.register_singleton(|provider| {
+        let secrets = provider.get::<StartupSecrets>();
+        EncryptService::from_key(&secrets.encryption_key).expect("Failed to create EncryptService")
+    });
+    builder.register_singleton(|provider| {
+        EventBusService::new(provider.get::<AppConfig>().eventbus_capacity)
     });
     builder.register_singleton(|_| IdGenerationService::new());
+    builder.register_singleton(|_| MetricsService::new());
+    builder.register_singleton(|_| {
+        ApiDocService::from_routes(crate::controllers::endpoint_registry().routes().iter()).with_default_annotations()
+    });
     builder.register_singleton(|provider| PhotoService::new(Arc::clone(&provider)));
     builder.register_singleton(|_| ExifService::new());
     builder.register_singleton(|_| HashService::new());
     builder.register_singleton(|_| FileService::new());
+    builder.register_singleton(|_| GpsScrubService::new());
     builder.register_singleton(|provider| {
-        let config = provider.get::<Configuration>();
-        let max_file_size = config
-            .get("upload.max_file_size_bytes")
-            .or_else(|| config.get("upload.maxFileSizeBytes"))
-            .and_then(|value| value.parse::<u64>().ok())
-            .filter(|value| *value > 0)
-            .unwrap_or(64 * 1024 * 1024);
-        PhotoUploadService::new(max_file_size)
+        PhotoUploadService::new(provider.get::<AppConfig>().upload_max_file_size_bytes)
     });
     builder.register_singleton(|provider| {
         log::info!("Initializing BackgroundTaskRunner...");
-        let configuration = provider.get::<Configuration>();
-        let default_parallelism = std::thread::available_parallelism()
-            .map(|value| value.get())
-            .unwrap_or(4);
-        let configured_parallelism = configuration
-            .get("background.parallelism")
-            .and_then(|value| value.parse::<usize>().ok())
-            .filter(|value| *value > 0)
-            .unwrap_or(default_parallelism);
+        let configured_parallelism = provider.get::<AppConfig>().background_parallelism;
         let runner = BackgroundTaskRunner::new(configured_parallelism);
         runner
             .start()
@@ -113,8 +185,33 @@ pub fn register_services(builder: &mut AppBuilder) -> &mut AppBuilder {
         );
         runner
     });
+    builder.register_singleton(|provider| {
+        let refresh_seconds = provider.get::<AppConfig>().disk_refresh_seconds;
+        DiskInfoService::new(std::time::Duration::from_secs(refresh_seconds))
+    });
+    builder.register_singleton(|provider| {
+        StoragePathGuard::new(provider.get::<AppConfig>().allow_symlink_escape)
+    });
+    builder.register_singleton(|_| StoragePathCache::new());
+    builder.register_singleton(|_| AlbumMembershipCache::new());
+    builder.register_singleton(|_| CorsPolicyCache::new());
+    builder.register_singleton(|_| DerivativeProfileCache::new());
+    builder.register_singleton(|_| RevokedSubjectRegistry::new());
+    builder.register_singleton(|provider| {
+        ConfigReloadService::new(
+            Arc::clone(&provider),
+            ConfigReloadService::DEFAULT_CONFIG_FILE,
+        )
+    });
     builder.register_singleton(|_| ThumbnailExtractor::new());
     builder.register_singleton(|_| PreviewExtractor::new());
+    builder.register_singleton(|provider| {
+        let config = provider.get::<AppConfig>();
+        PreviewCoordinator::with_limits(
+            config.preview_max_concurrent_extractions,
+            std::time::Duration::from_secs(config.preview_extraction_timeout_seconds),
+        )
+    });
     builder.register_singleton(|provider| {
         let configuration = provider.get::<Configuration>().as_ref().clone();
         ImageProcessPipeline::new(ImageProcessPipelineContext::new(
@@ -123,25 +220,30 @@ pub fn register_services(builder: &mut AppBuilder) -> &mut AppBuilder {
         ))
     });
     builder.register_singleton(|provider| {
-        let config = provider.get::<Configuration>();
-        let secret = config
-            .get("jwt.secret")
-            .unwrap_or("super-secret-key-123")
-            .to_string();
-        let issuer = config.get("jwt.issuer").unwrap_or("nimble").to_string();
+        let secret = provider.get::<StartupSecrets>().jwt_secret.clone();
+        let issuer = provider.get::<AppConfig>().jwt_issuer.clone();
 
         let service = JwtTokenService::new(secret, issuer);
         Arc::new(service) as Arc<dyn TokenService>
     });
+    builder.register_singleton(|provider| {
+        let repo = provider.get::<Repository<TwoFactor>>();
+        let encrypt = provider.get::<EncryptService>();
+        TwoFactorService::new(repo, (*encrypt).clone())
+    });
     builder.register_singleton(|provider| {
         let repo = provider.get::<Repository<User>>();
         let settings_repo = provider.get::<Repository<UserSettings>>();
+        let sessions = provider.get::<Repository<RefreshSession>>();
+        let two_factor = provider.get::<TwoFactorService>();
         let encrypt = provider.get::<EncryptService>();
         let tokens = provider.get::<Arc<dyn TokenService>>();
 
         AuthService::new(
             repo,
             settings_repo,
+            sessions,
+            two_factor,
             (*encrypt).clone(),
             tokens.as_ref().clone(),
         )
@@ -150,19 +252,113 @@ pub fn register_services(builder: &mut AppBuilder) -> &mut AppBuilder {
         let settings_repo = provider.get::<Repository<Setting>>();
         SettingService::new(settings_repo)
     });
+    builder.register_singleton(|provider| {
+        let secret = provider
+            .get::<AppConfig>()
+            .cursor_secret
+            .clone()
+            .unwrap_or_else(|| provider.get::<StartupSecrets>().jwt_secret.clone());
+        CursorSigner::new(&secret)
+    });
+    builder.register_singleton(|provider| {
+        DatabaseHealthService::new(provider.get::<PgPool>())
+    });
     builder.register_singleton(|provider| {
         let pool = provider.get::<PgPool>();
-        BrowseService::new(pool)
+        let signer = provider.get::<CursorSigner>();
+        BrowseService::new(pool, signer)
     });
     builder.register_singleton(|provider| {
         let repo = provider.get::<Repository<User>>();
-        AdminUserService::new(repo)
+        let settings_repo = provider.get::<Repository<UserSettings>>();
+        let photo_comments = provider.get::<Repository<PhotoComment>>();
+        let album_comments = provider.get::<Repository<AlbumComment>>();
+        AdminUserService::new(repo, settings_repo, photo_comments, album_comments)
     });
+    builder.register_singleton(|provider| {
+        let repo = provider.get::<Repository<AuditLog>>();
+        let runner = provider.get::<BackgroundTaskRunner>();
+        AuditService::new(repo, runner)
+    });
+    builder.register_singleton(|provider| AvifThumbnailService::new(provider.get::<BackgroundTaskRunner>()));
     builder.register_singleton(|provider| {
         SyncService::new(Arc::clone(&provider))
     });
+    builder.register_singleton(|provider| {
+        AssetRelocationService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        BlurhashService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        let photos = provider.get::<Repository<Photo>>();
+        let storages = provider.get::<Repository<StorageLocation>>();
+        PhotoPathRepairService::new(photos, storages)
+    });
+    builder.register_singleton(|provider| {
+        SetupService::new(Arc::clone(&provider))
+    });
     builder.register_singleton(|provider| {
         StorageService::new(Arc::clone(&provider))
     });
+    builder.register_singleton(|provider| {
+        FolderImportService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        TagRuleService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        EmailService::from_config(&provider.get::<AppConfig>())
+    });
+    builder.register_singleton(|provider| {
+        NotificationDigestService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        EmailVerificationService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        PreviewWarmerService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        PreviewCacheService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        DashboardOverviewService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        NotificationService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        LiveUpdateService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        FeedService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        EmbedService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        XmpSidecarService::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        ImageAnalyzerRegistry::new(Arc::clone(&provider))
+    });
+    builder.register_singleton(|provider| {
+        let gap_hours = provider.get::<AppConfig>().album_auto_gap_hours;
+        let options = EventClusterOptions {
+            gap: chrono::Duration::hours(gap_hours),
+            ..EventClusterOptions::default()
+        };
+        EventAlbumService::new(Arc::clone(&provider), options)
+    });
+    builder.register_singleton(|provider| {
+        let config = provider.get::<AppConfig>();
+        let service = UploadSessionService::new(
+            config.upload_chunk_size_bytes,
+            std::time::Duration::from_secs(config.upload_session_ttl_minutes * 60),
+        );
+        service.start_sweeper(provider.get::<Repository<StorageLocation>>());
+        service
+    });
     builder
 }
@@ -0,0 +1,233 @@
+use crate::prelude::*;
+
+#[derive(Clone)]
+pub struct NotificationDigestService {
+    photos: Arc<Repository<Photo>>,
+    users: Arc<Repository<User>>,
+    user_settings: Arc<Repository<UserSettings>>,
+    runs: Arc<Repository<DigestRun>>,
+    settings: Arc<SettingService>,
+    email: Arc<EmailService>,
+    runner: Arc<BackgroundTaskRunner>,
+    // Serializes sends so an overlapping check can never mail the same window twice.
+    sending: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl NotificationDigestService {
+    pub const TASK_NAME: &'static str = "notification-digest";
+    const CHECK_INTERVAL_SECONDS: u64 = 5 * 60;
+    const FIRST_WINDOW_HOURS: i64 = 24;
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photos: services.get::<Repository<Photo>>(),
+            users: services.get::<Repository<User>>(),
+            user_settings: services.get::<Repository<UserSettings>>(),
+            runs: services.get::<Repository<DigestRun>>(),
+            settings: services.get::<SettingService>(),
+            email: services.get::<EmailService>(),
+            runner: services.get::<BackgroundTaskRunner>(),
+            sending: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    pub fn start_scheduler(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(Self::CHECK_INTERVAL_SECONDS));
+            loop {
+                interval.tick().await;
+                match service.is_due(Utc::now()).await {
+                    Ok(true) => service.enqueue_daily(),
+                    Ok(false) => {}
+                    Err(error) => log::warn!("Notification digest check failed: {:?}", error),
+                }
+            }
+        });
+    }
+
+    pub async fn is_due(&self, now: DateTime<Utc>) -> Result<bool, PipelineError> {
+        if !self.settings.email_summary_enabled().await? || !self.email.is_configured() {
+            return Ok(false);
+        }
+        let schedule = self.settings.digest_schedule().await?;
+        let last = self.last_daily_run().await?;
+        Ok(schedule.is_due(now, last.map(|run| run.sent_at)))
+    }
+
+    // Sends the scheduled digest; returns None when another run already covered today.
+    pub async fn send_daily(&self) -> Result<Option<DigestRun>, PipelineError> {
+        let _guard = self.sending.lock().await;
+        let now = Utc::now();
+        if !self.is_due(now).await? {
+            return Ok(None);
+        }
+
+        let last = self.last_daily_run().await?;
+        let summary = self.summarize(last.map(|run| run.sent_at), now).await?;
+        let recipients = if summary.is_empty() { Vec::new() } else { self.opted_in_addresses().await? };
+        let delivered = self.deliver(&summary, &recipients).await;
+
+        // Recorded even when nothing went out, so the window advances and a restart does not resend.
+        let run = self.record(DigestRun::KIND_DAILY, &summary, delivered).await?;
+        log::info!(
+            "Sent notification digest to {} of {} recipient(s): {} photo(s), {} comment(s), {} album(s)",
+            delivered,
+            recipients.len(),
+            summary.photos,
+            summary.comments,
+            summary.albums
+        );
+        Ok(Some(run))
+    }
+
+    // Mails the pending digest to one admin without moving the daily window.
+    pub async fn send_test(&self, address: &str) -> Result<DigestRun, PipelineError> {
+        if !self.email.is_configured() {
+            return Err(PipelineError::message("SMTP is not configured"));
+        }
+        let _guard = self.sending.lock().await;
+        let last = self.last_daily_run().await?;
+        let summary = self.summarize(last.map(|run| run.sent_at), Utc::now()).await?;
+        self.email
+            .send(&Self::message(&summary, address))
+            .await
+            .map_err(|e| PipelineError::message(&format!("{:#}", e)))?;
+        self.record(DigestRun::KIND_TEST, &summary, 1).await
+    }
+
+    fn enqueue_daily(&self) {
+        let service = self.clone();
+        let task = TaskDescriptor::new(Self::TASK_NAME, async move {
+            service.send_daily().await.map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            Ok(())
+        });
+        if let Err(error) = self.runner.enqueue(task) {
+            log::warn!("Failed to queue notification digest: {:?}", error);
+        }
+    }
+
+    async fn deliver(&self, summary: &DigestSummary, recipients: &[String]) -> usize {
+        let mut delivered = 0;
+        for address in recipients {
+            match self.email.send(&Self::message(summary, address)).await {
+                Ok(()) => delivered += 1,
+                Err(error) => log::warn!("Notification digest to {} failed: {:#}", address, error),
+            }
+        }
+        delivered
+    }
+
+    fn message(summary: &DigestSummary, address: &str) -> EmailMessage {
+        EmailMessage {
+            to: address.to_string(),
+            subject: summary.subject(),
+            text: summary.render_text(),
+            html: summary.render_html(),
+        }
+    }
+
+    async fn record(&self, kind: &str, summary: &DigestSummary, recipients: usize) -> Result<DigestRun, PipelineError> {
+        self.runs
+            .insert(DigestRun::new(kind, summary, recipients))
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to record digest run: {:?}", e)))
+    }
+
+    async fn last_daily_run(&self) -> Result<Option<DigestRun>, PipelineError> {
+        let query = QueryBuilder::<DigestRun>::new()
+            .filter("kind", FilterOperator::Eq, Value::String(DigestRun::KIND_DAILY.to_string()))
+            .sort_desc("sent_at")
+            .page(1, 1)
+            .build();
+        let page = self
+            .runs
+            .query(query)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to load digest runs: {:?}", e)))?;
+        Ok(page.items.into_iter().next())
+    }
+
+    async fn opted_in_addresses(&self) -> Result<Vec<String>, PipelineError> {
+        let query =
+            QueryBuilder::<UserSettings>::new().filter("email_digest", FilterOperator::Eq, Value::Bool(true)).build();
+        let settings = self
+            .user_settings
+            .all(query)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to load user settings: {:?}", e)))?;
+        if settings.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = settings.iter().map(|settings| Value::Uuid(settings.user_id)).collect::<Vec<_>>();
+        let query = QueryBuilder::<User>::new().filter("id", FilterOperator::In, Value::List(ids)).build();
+        let users = self
+            .users
+            .all(query)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to load users: {:?}", e)))?;
+        Ok(users.into_iter().map(|user| user.email).collect())
+    }
+
+    async fn summarize(
+        &self,
+        since: Option<DateTime<Utc>>,
+        until: DateTime<Utc>,
+    ) -> Result<DigestSummary, PipelineError> {
+        let since = since.unwrap_or(until - chrono::Duration::hours(Self::FIRST_WINDOW_HOURS));
+        let site_title = self.settings.site_title().await?;
+        let (photos, comments, albums) = self.count_activity(since, until).await?;
+        Ok(DigestSummary { site_title, since, until, photos, comments, albums })
+    }
+
+    async fn count_activity(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<(i64, i64, i64), PipelineError> {
+        #[cfg(feature = "postgres")]
+        {
+            #[derive(Deserialize)]
+            struct ActivityRow {
+                photos: i64,
+                comments: i64,
+                albums: i64,
+            }
+
+            // Photos hidden by admin-only tags, and comments on them, are left out of the counts.
+            let sql = r#"
+                SELECT
+                    (SELECT count(*) FROM photos_public_visible p
+                        WHERE p.created_at > $1 AND p.created_at <= $2) AS photos,
+                    (SELECT count(*) FROM photo_comments c
+                        JOIN photos_public_visible p ON p.id = c.photo_id
+                        WHERE c.created_at > $1 AND c.created_at <= $2)
+                    + (SELECT count(*) FROM album_comments c
+                        WHERE NOT c.hidden AND c.created_at > $1 AND c.created_at <= $2) AS comments,
+                    (SELECT count(*) FROM albums a
+                        WHERE a.create_date > $1 AND a.create_date <= $2) AS albums
+            "#;
+            let row = self
+                .photos
+                .raw_query::<ActivityRow>(sql, &[Value::DateTime(since), Value::DateTime(until)])
+                .await
+                .map_err(|e| PipelineError::message(&format!("failed to count digest activity: {:?}", e)))?
+                .into_iter()
+                .next();
+            return Ok(row.map(|row| (row.photos, row.comments, row.albums)).unwrap_or((0, 0, 0)));
+        }
+
+        #[cfg(not(feature = "postgres"))]
+        {
+            // Comment and album counts need the SQL views; only photos are counted here.
+            let photos = self
+                .photos
+                .all(QueryBuilder::<Photo>::new().build())
+                .await
+                .map_err(|e| PipelineError::message(&format!("failed to count digest activity: {:?}", e)))?;
+            let photos = photos.iter().filter(|photo| photo.created_at.is_some_and(|at| at > since && at <= until));
+            Ok((photos.count() as i64, 0, 0))
+        }
+    }
+}
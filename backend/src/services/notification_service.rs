@@ -0,0 +1,166 @@
+use crate::prelude::*;
+use anyhow::anyhow;
+
+#[derive(Clone)]
+pub struct NotificationService {
+    notifications: Arc<Repository<Notification>>,
+    photo_comments: Arc<Repository<PhotoComment>>,
+    album_comments: Arc<Repository<AlbumComment>>,
+    runner: Arc<BackgroundTaskRunner>,
+}
+
+impl NotificationService {
+    const TASK_NAME_PREFIX: &'static str = "notify";
+    const EXCERPT_LENGTH: usize = 120;
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            notifications: services.get::<Repository<Notification>>(),
+            photo_comments: services.get::<Repository<PhotoComment>>(),
+            album_comments: services.get::<Repository<AlbumComment>>(),
+            runner: services.get::<BackgroundTaskRunner>(),
+        }
+    }
+
+    // Everyone who commented on the same photo before, except the new comment's author.
+    pub fn notify_photo_comment(&self, comment: &PhotoComment) {
+        let service = self.clone();
+        let comment = comment.clone();
+        self.enqueue(Notification::KIND_PHOTO_COMMENT, async move {
+            let query = QueryBuilder::<PhotoComment>::new()
+                .filter("photo_id", FilterOperator::Eq, Value::Uuid(comment.photo_id))
+                .build();
+            let previous = service.photo_comments.all(query).await.map_err(|e| anyhow!("{:?}", e))?;
+            let recipients =
+                Self::recipients(comment.user_id, previous.iter().filter(|c| c.id != comment.id).map(|c| c.user_id));
+            let payload = json!({
+                "photoId": comment.photo_id,
+                "commentId": comment.id,
+                "authorName": comment.user_display_name,
+                "excerpt": Self::excerpt(comment.body.as_deref().unwrap_or_default()),
+            });
+            service.insert_all(&recipients, Notification::KIND_PHOTO_COMMENT, payload).await
+        });
+    }
+
+    pub fn notify_album_comment(&self, comment: &AlbumComment) {
+        let service = self.clone();
+        let comment = comment.clone();
+        self.enqueue(Notification::KIND_ALBUM_COMMENT, async move {
+            let query = QueryBuilder::<AlbumComment>::new()
+                .filter("album_id", FilterOperator::Eq, Value::Uuid(comment.album_id))
+                .build();
+            let previous = service.album_comments.all(query).await.map_err(|e| anyhow!("{:?}", e))?;
+            let recipients =
+                Self::recipients(comment.user_id, previous.iter().filter(|c| c.id != comment.id).map(|c| c.user_id));
+            let payload = json!({
+                "albumId": comment.album_id,
+                "commentId": comment.id,
+                "authorName": comment.user_display_name,
+                "excerpt": Self::excerpt(comment.body.as_deref().unwrap_or_default()),
+            });
+            service.insert_all(&recipients, Notification::KIND_ALBUM_COMMENT, payload).await
+        });
+    }
+
+    pub fn notify_comment_hidden(&self, comment: &AlbumComment, hidden_by: Option<Uuid>) {
+        let Some(author) = comment.user_id.filter(|author| Some(*author) != hidden_by) else {
+            return;
+        };
+        let service = self.clone();
+        let payload = json!({
+            "albumId": comment.album_id,
+            "commentId": comment.id,
+            "excerpt": Self::excerpt(comment.body.as_deref().unwrap_or_default()),
+        });
+        self.enqueue(Notification::KIND_COMMENT_HIDDEN, async move {
+            service.insert_all(&[author], Notification::KIND_COMMENT_HIDDEN, payload).await
+        });
+    }
+
+    pub async fn list(
+        &self,
+        recipient_id: Uuid,
+        unread_only: bool,
+        page: u32,
+        page_size: u32,
+    ) -> Result<NotificationPageDto, PipelineError> {
+        let notifications = self.notifications.notifications_for(recipient_id, unread_only, page, page_size).await?;
+        let unread =
+            if unread_only { notifications.total } else { self.notifications.unread_count(recipient_id).await? };
+        Ok(NotificationPageDto { items: notifications.items, total: notifications.total, page, page_size, unread })
+    }
+
+    // Returns None when the notification does not exist or belongs to someone else.
+    pub async fn mark_read(
+        &self,
+        recipient_id: Uuid,
+        notification_id: Uuid,
+    ) -> Result<Option<Notification>, PipelineError> {
+        let notification = self
+            .notifications
+            .get(&notification_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to load notification: {:?}", e)))?;
+        let Some(mut notification) = notification.filter(|notification| notification.recipient_id == recipient_id)
+        else {
+            return Ok(None);
+        };
+        if notification.is_read() {
+            return Ok(Some(notification));
+        }
+
+        notification.read_at = Some(Utc::now());
+        let saved = self
+            .notifications
+            .update(notification)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to update notification: {:?}", e)))?;
+        Ok(Some(saved))
+    }
+
+    pub async fn mark_all_read(&self, recipient_id: Uuid) -> Result<u64, PipelineError> {
+        self.notifications.mark_all_read(recipient_id).await
+    }
+
+    pub fn recipients(author: Option<Uuid>, previous: impl Iterator<Item = Option<Uuid>>) -> Vec<Uuid> {
+        let mut recipients = Vec::new();
+        for user_id in previous.flatten() {
+            if Some(user_id) != author && !recipients.contains(&user_id) {
+                recipients.push(user_id);
+            }
+        }
+        recipients
+    }
+
+    pub fn excerpt(body: &str) -> String {
+        let body = body.trim();
+        if body.chars().count() <= Self::EXCERPT_LENGTH {
+            return body.to_string();
+        }
+        let mut excerpt = body.chars().take(Self::EXCERPT_LENGTH).collect::<String>();
+        excerpt.push('…');
+        excerpt
+    }
+
+    // Generation runs on the background runner so comment requests never wait on it.
+    fn enqueue<F>(&self, kind: &str, work: F)
+    where
+        F: std::future::Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let task = TaskDescriptor::new(format!("{}:{}", Self::TASK_NAME_PREFIX, kind), work);
+        if let Err(error) = self.runner.enqueue(task) {
+            log::warn!("Notification queue unavailable, dropping '{}' notifications: {}", kind, error);
+        }
+    }
+
+    async fn insert_all(&self, recipients: &[Uuid], kind: &str, payload: JsonValue) -> anyhow::Result<()> {
+        for recipient in recipients {
+            self.notifications
+                .insert(Notification::new(*recipient, kind, payload.clone()))
+                .await
+                .map_err(|e| anyhow!("failed to save notification for {}: {:?}", recipient, e))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::prelude::*;
+
+pub struct PhotoPathRepairService {
+    photos: Arc<Repository<Photo>>,
+    storages: Arc<Repository<StorageLocation>>,
+}
+
+impl PhotoPathRepairService {
+    const SAMPLE_SIZE: u32 = 25;
+    const MAX_MISSING_SAMPLES: usize = 10;
+
+    pub fn new(photos: Arc<Repository<Photo>>, storages: Arc<Repository<StorageLocation>>) -> Self {
+        Self { photos, storages }
+    }
+
+    pub fn validate(request: &PhotoPathRepairRequest) -> Result<(), PipelineError> {
+        if request.old_prefix.trim().is_empty() || request.new_prefix.trim().is_empty() {
+            return Err(PipelineError::message("oldPrefix and newPrefix are required"));
+        }
+        if request.old_prefix == request.new_prefix {
+            return Err(PipelineError::message("oldPrefix and newPrefix must differ"));
+        }
+        Ok(())
+    }
+
+    pub fn rewrite(path: &str, old_prefix: &str, new_prefix: &str) -> Option<String> {
+        path.strip_prefix(old_prefix).map(|rest| format!("{}{}", new_prefix, rest))
+    }
+
+    // Only a random sample is checked on disk; the update itself is a single statement over every match.
+    pub async fn repair(&self, request: &PhotoPathRepairRequest) -> Result<PhotoPathRepairResult, PipelineError> {
+        Self::validate(request)?;
+        self.storages
+            .get(&request.storage_id)
+            .await
+            .map_err(|_| PipelineError::message("failed to load storage location"))?
+            .ok_or_else(|| PipelineError::message("storage location not found"))?;
+
+        let matched = self.photos.count_path_prefix(request.storage_id, &request.old_prefix).await?;
+        let sample = self.photos.sample_path_prefix(request.storage_id, &request.old_prefix, Self::SAMPLE_SIZE).await?;
+        let missing: Vec<String> = sample
+            .iter()
+            .filter_map(|path| Self::rewrite(path, &request.old_prefix, &request.new_prefix))
+            .filter(|path| !Path::new(path).exists())
+            .collect();
+
+        let mut result = PhotoPathRepairResult {
+            dry_run: request.dry_run,
+            matched,
+            sampled: sample.len(),
+            missing: missing.len(),
+            missing_samples: missing.into_iter().take(Self::MAX_MISSING_SAMPLES).collect(),
+            ..Default::default()
+        };
+        if request.dry_run || result.missing > 0 || matched == 0 {
+            return Ok(result);
+        }
+
+        result.updated =
+            self.photos.rewrite_path_prefix(request.storage_id, &request.old_prefix, &request.new_prefix).await?;
+        log::info!(
+            "Rewrote {} photo path(s) in storage {} from '{}' to '{}'",
+            result.updated,
+            request.storage_id,
+            request.old_prefix,
+            request.new_prefix
+        );
+        Ok(result)
+    }
+}
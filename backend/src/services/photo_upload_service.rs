@@ -1,12 +1,14 @@
 use crate::prelude::*;
+use crate::services::image_process_constants::ImageProcessKeys;
 use anyhow::{Context, Result, anyhow};
 use bytes::Bytes;
 use futures_util::{StreamExt, TryStreamExt, stream};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::fs::{self, File};
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 pub struct PhotoUploadService {
-    max_file_size: u64,
+    max_file_size: AtomicU64,
 }
 
 #[derive(Clone, Debug)]
@@ -17,14 +19,51 @@ pub struct StoredUploadFile {
     pub content_type: Option<String>,
 }
 
+#[derive(Clone, Debug)]
+pub struct RejectedUploadFile {
+    pub file_name: String,
+    pub byte_size: usize,
+    pub content_type: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PersistedUploads {
+    pub saved: Vec<StoredUploadFile>,
+    pub rejected: Vec<RejectedUploadFile>,
+}
+
+impl PersistedUploads {
+    pub fn is_empty(&self) -> bool {
+        self.saved.is_empty() && self.rejected.is_empty()
+    }
+}
+
 impl PhotoUploadService {
     const FILES_FIELD_NAME: &'static str = "files";
-    const TEMP_FOLDER_NAME: &'static str = ".temp";
+    pub const TEMP_FOLDER_NAME: &'static str = ".temp";
     const UNKNOWN_FILE_BASENAME: &'static str = "upload";
     const DEFAULT_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+    const VALIDATION_HEAD_BYTES: usize = 256 * 1024;
+
+    pub const REJECT_UNSUPPORTED_TYPE: &'static str = "unsupported file type";
+    pub const REJECT_UNKNOWN_CONTENT: &'static str = "file content is not a recognized image";
+    pub const REJECT_CORRUPT_HEADER: &'static str = "image header is corrupt or truncated";
 
     pub fn new(max_file_size: u64) -> Self {
-        Self { max_file_size: if max_file_size == 0 { Self::DEFAULT_MAX_FILE_SIZE } else { max_file_size } }
+        Self { max_file_size: AtomicU64::new(Self::effective_max_file_size(max_file_size)) }
+    }
+
+    pub fn max_file_size(&self) -> u64 {
+        self.max_file_size.load(Ordering::SeqCst)
+    }
+
+    pub fn set_max_file_size(&self, max_file_size: u64) {
+        self.max_file_size.store(Self::effective_max_file_size(max_file_size), Ordering::SeqCst);
+    }
+
+    fn effective_max_file_size(max_file_size: u64) -> u64 {
+        if max_file_size == 0 { Self::DEFAULT_MAX_FILE_SIZE } else { max_file_size }
     }
 
     pub async fn persist_multipart_to_storage_temp(
@@ -32,7 +71,7 @@ impl PhotoUploadService {
         content_type: &str,
         body_bytes: Vec<u8>,
         storage_path: &Path,
-    ) -> Result<Vec<StoredUploadFile>> {
+    ) -> Result<PersistedUploads> {
         let boundary = multer::parse_boundary(content_type)?;
         let body_stream = stream::once(async move { Ok::<Bytes, std::io::Error>(Bytes::from(body_bytes)) });
         let mut multipart = multer::Multipart::new(body_stream, boundary);
@@ -40,7 +79,7 @@ impl PhotoUploadService {
         let temp_folder = storage_path.join(Self::TEMP_FOLDER_NAME);
         fs::create_dir_all(&temp_folder).await?;
 
-        let mut saved_files = Vec::<StoredUploadFile>::new();
+        let mut uploads = PersistedUploads::default();
         while let Some(field) = multipart.next_field().await? {
             if field.name() != Some(Self::FILES_FIELD_NAME) {
                 continue;
@@ -50,12 +89,29 @@ impl PhotoUploadService {
                 field.file_name().map(ToString::to_string).unwrap_or_else(|| Self::UNKNOWN_FILE_BASENAME.to_string());
             let content_type = field.content_type().map(|value| value.to_string());
             let sanitized_name = Self::sanitize_file_name(&incoming_name);
+
+            // Only the head is buffered for validation; the rest streams to disk to keep memory usage flat.
+            let mut stream = field.into_stream();
+            let (head, complete) = Self::read_head(&mut stream).await?;
+            if head.is_empty() {
+                continue;
+            }
+            if let Some(reason) = Self::rejection_reason(&sanitized_name, &head, complete) {
+                let byte_size = head.len() + Self::drain(stream).await?;
+                log::info!("Rejected upload '{}': {}", sanitized_name, reason);
+                uploads.rejected.push(RejectedUploadFile {
+                    file_name: sanitized_name,
+                    byte_size,
+                    content_type,
+                    reason,
+                });
+                continue;
+            }
+
             let (final_file_name, absolute_file_path) =
                 self.allocate_unique_path(&temp_folder, &sanitized_name).await?;
-
-            // Stream each multipart field directly to disk to keep memory usage flat.
             let bytes_written = self
-                .write_stream_to_file(field.into_stream(), &absolute_file_path)
+                .write_stream_to_file(head, stream, &absolute_file_path)
                 .await
                 .with_context(|| format!("failed to persist upload '{}'", absolute_file_path.display()))?;
 
@@ -66,7 +122,7 @@ impl PhotoUploadService {
 
             log::debug!("Stored upload '{}' ({} bytes)", final_file_name, bytes_written);
 
-            saved_files.push(StoredUploadFile {
+            uploads.saved.push(StoredUploadFile {
                 file_name: final_file_name.clone(),
                 relative_path: format!("{}/{}", Self::TEMP_FOLDER_NAME, final_file_name),
                 byte_size: bytes_written as usize,
@@ -74,25 +130,106 @@ impl PhotoUploadService {
             });
         }
 
-        Ok(saved_files)
+        Ok(uploads)
+    }
+
+    pub fn rejection_reason(file_name: &str, head: &[u8], complete: bool) -> Option<String> {
+        if !ImageProcessKeys::is_supported_image(Path::new(file_name)) {
+            return Some(Self::REJECT_UNSUPPORTED_TYPE.to_string());
+        }
+        let Some(signature) = ImageSignature::detect(head) else {
+            return Some(Self::REJECT_UNKNOWN_CONTENT.to_string());
+        };
+        if !signature.has_decodable_header() {
+            return None;
+        }
+
+        match imagesize::blob_size(head) {
+            Ok(size) if size.width > 0 && size.height > 0 => None,
+            // The dimensions live past the sampled head; leave the rest to the pipeline.
+            Err(imagesize::ImageError::IoError(_)) if !complete => None,
+            _ => Some(Self::REJECT_CORRUPT_HEADER.to_string()),
+        }
+    }
+
+    pub async fn file_rejection_reason(file_name: &str, path: &Path) -> Result<Option<String>> {
+        let file = File::open(path).await?;
+        let size = file.metadata().await?.len();
+        let mut head = Vec::with_capacity(Self::VALIDATION_HEAD_BYTES.min(size as usize));
+        file.take(Self::VALIDATION_HEAD_BYTES as u64).read_to_end(&mut head).await?;
+        Ok(Self::rejection_reason(file_name, &head, head.len() as u64 == size))
+    }
+
+    // Moves an already assembled file into the storage temp folder so it can be handed to the pipeline.
+    pub async fn adopt_temp_file(
+        &self,
+        source: &Path,
+        file_name: &str,
+        content_type: Option<String>,
+        storage_path: &Path,
+    ) -> Result<StoredUploadFile> {
+        let temp_folder = storage_path.join(Self::TEMP_FOLDER_NAME);
+        fs::create_dir_all(&temp_folder).await?;
+
+        let sanitized_name = Self::sanitize_file_name(file_name);
+        let (final_file_name, absolute_file_path) = self.allocate_unique_path(&temp_folder, &sanitized_name).await?;
+        fs::rename(source, &absolute_file_path)
+            .await
+            .with_context(|| format!("failed to move upload into '{}'", absolute_file_path.display()))?;
+        let byte_size = fs::metadata(&absolute_file_path).await?.len();
+
+        Ok(StoredUploadFile {
+            relative_path: format!("{}/{}", Self::TEMP_FOLDER_NAME, final_file_name),
+            file_name: final_file_name,
+            byte_size: byte_size as usize,
+            content_type,
+        })
+    }
+
+    async fn read_head<S>(stream: &mut S) -> Result<(Vec<u8>, bool)>
+    where
+        S: futures_util::Stream<Item = Result<Bytes, multer::Error>> + Unpin,
+    {
+        let mut head = Vec::new();
+        while head.len() < Self::VALIDATION_HEAD_BYTES {
+            match stream.next().await {
+                Some(chunk) => head.extend_from_slice(&chunk?),
+                None => return Ok((head, true)),
+            }
+        }
+        Ok((head, false))
+    }
+
+    async fn drain<S>(mut stream: S) -> Result<usize>
+    where
+        S: futures_util::Stream<Item = Result<Bytes, multer::Error>> + Unpin,
+    {
+        let mut drained = 0usize;
+        while let Some(chunk) = stream.next().await {
+            drained += chunk?.len();
+        }
+        Ok(drained)
     }
 
-    async fn write_stream_to_file<S>(&self, mut stream: S, path: &Path) -> Result<u64>
+    async fn write_stream_to_file<S>(&self, head: Vec<u8>, mut stream: S, path: &Path) -> Result<u64>
     where
         S: futures_util::Stream<Item = Result<Bytes, multer::Error>> + Unpin,
     {
         let mut file = File::create_new(path).await?;
         let mut bytes_written = 0u64;
+        let max_file_size = self.max_file_size();
 
+        let head = stream::iter(std::iter::once(Ok::<Bytes, multer::Error>(Bytes::from(head))));
+        let mut stream = head.chain(stream);
         while let Some(chunk) = stream.next().await {
             let chunk = chunk?;
             bytes_written =
                 bytes_written.checked_add(chunk.len() as u64).ok_or_else(|| anyhow!("uploaded file size overflow"))?;
 
-            if bytes_written > self.max_file_size {
+            if bytes_written > max_file_size {
                 drop(file);
                 let _ = fs::remove_file(path).await;
-                return Err(anyhow!("uploaded file exceeds max allowed size of {} bytes", self.max_file_size));
+                return Err(anyhow!("uploaded file exceeds max allowed size of {} bytes", max_file_size));
             }
 
             file.write_all(&chunk).await?;
@@ -0,0 +1,211 @@
+use crate::models::setting_consts::SettingConsts;
+use crate::prelude::*;
+use std::time::SystemTime;
+
+// Keeps each storage's `.previews` folder within its budget. Thumbnails are small and every grid needs
+// them, so only previews are evicted; a removed preview is regenerated on its next view.
+#[derive(Clone)]
+pub struct PreviewCacheService {
+    storages: Arc<Repository<StorageLocation>>,
+    storage_paths: Arc<StoragePathCache>,
+    settings: Arc<SettingService>,
+    coordinator: Arc<PreviewCoordinator>,
+    runner: Arc<BackgroundTaskRunner>,
+    accessed: Arc<Mutex<HashMap<PathBuf, SystemTime>>>,
+    pruning: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl PreviewCacheService {
+    pub const TASK_NAME: &'static str = "prune-preview-cache";
+    const CHECK_INTERVAL_SECONDS: u64 = 60 * 60;
+    const MAX_TRACKED: usize = 100_000;
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            storages: services.get::<Repository<StorageLocation>>(),
+            storage_paths: services.get::<StoragePathCache>(),
+            settings: services.get::<SettingService>(),
+            coordinator: services.get::<PreviewCoordinator>(),
+            runner: services.get::<BackgroundTaskRunner>(),
+            accessed: Arc::new(Mutex::new(HashMap::new())),
+            pruning: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    // File access times are unreliable under noatime and relatime mounts, so serves are tracked here.
+    // Previews not served since the last restart fall back to their modification time.
+    pub fn record_access(&self, path: &Path) {
+        if let Ok(mut accessed) = self.accessed.lock() {
+            if accessed.len() >= Self::MAX_TRACKED && !accessed.contains_key(path) {
+                accessed.clear();
+            }
+            accessed.insert(path.to_path_buf(), SystemTime::now());
+        }
+    }
+
+    pub fn start_scheduler(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(Self::CHECK_INTERVAL_SECONDS));
+            loop {
+                interval.tick().await;
+                service.enqueue_run();
+            }
+        });
+    }
+
+    fn enqueue_run(&self) {
+        let service = self.clone();
+        let task = TaskDescriptor::new(Self::TASK_NAME, async move {
+            service.prune_all().await.map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            Ok(())
+        });
+        if let Err(error) = self.runner.enqueue(task) {
+            log::warn!("Failed to queue preview cache pruning: {:?}", error);
+        }
+    }
+
+    pub async fn prune_all(&self) -> Result<Vec<PreviewCacheReport>, PipelineError> {
+        let budgets = self.settings.preview_cache_budgets().await?;
+        let mut reports = Vec::new();
+        for storage in self.storages.load_storages().await? {
+            let max_bytes = budgets.max_bytes(storage.id);
+            if max_bytes == 0 {
+                continue;
+            }
+            if let Some(root) = self.preview_root(storage.id).await? {
+                let report = self.prune_root(storage.id, root, max_bytes).await?;
+                if report.evicted_files > 0 {
+                    log::info!(
+                        "Evicted {} preview(s), {} bytes, from storage {}",
+                        report.evicted_files,
+                        report.evicted_bytes,
+                        storage.id
+                    );
+                }
+                reports.push(report);
+            }
+        }
+        Ok(reports)
+    }
+
+    pub async fn prune(&self, storage_id: Uuid) -> Result<Option<PreviewCacheReport>, PipelineError> {
+        let max_bytes = self.settings.preview_cache_budgets().await?.max_bytes(storage_id);
+        let Some(root) = self.preview_root(storage_id).await? else {
+            return Ok(None);
+        };
+        self.prune_root(storage_id, root, max_bytes).await.map(Some)
+    }
+
+    pub async fn report(&self, storage_id: Uuid) -> Result<Option<PreviewCacheReport>, PipelineError> {
+        let max_bytes = self.settings.preview_cache_budgets().await?.max_bytes(storage_id);
+        let Some(root) = self.preview_root(storage_id).await? else {
+            return Ok(None);
+        };
+        let files = Self::scan(root).await?;
+        Ok(Some(PreviewCacheReport {
+            storage_id,
+            max_bytes,
+            files: files.len() as u64,
+            bytes: files.iter().map(|file| file.bytes).sum(),
+            ..PreviewCacheReport::default()
+        }))
+    }
+
+    async fn preview_root(&self, storage_id: Uuid) -> Result<Option<PathBuf>, PipelineError> {
+        let root = self.storage_paths.root(&self.storages, storage_id).await?;
+        Ok(root.map(|root| root.join(SettingConsts::PREVIEW_FOLDER)))
+    }
+
+    async fn prune_root(
+        &self,
+        storage_id: Uuid,
+        root: PathBuf,
+        max_bytes: u64,
+    ) -> Result<PreviewCacheReport, PipelineError> {
+        let _running = self.pruning.lock().await;
+        let mut files = Self::scan(root).await?;
+        if let Ok(accessed) = self.accessed.lock() {
+            for file in &mut files {
+                if let Some(last_access) = accessed.get(&file.path) {
+                    file.last_access = file.last_access.max(*last_access);
+                }
+            }
+        }
+        let mut report = PreviewCacheReport {
+            storage_id,
+            max_bytes,
+            files: files.len() as u64,
+            bytes: files.iter().map(|file| file.bytes).sum(),
+            ..PreviewCacheReport::default()
+        };
+
+        let plan = PreviewCache::eviction_plan(files, max_bytes, |file| self.is_protected(file));
+        let service = self.clone();
+        // Protection is checked again right before each removal, so a serve that started while the
+        // plan was made keeps its file.
+        let removed = tokio::task::spawn_blocking(move || {
+            plan.into_iter()
+                .filter(|file| !service.is_protected(file))
+                .filter(|file| match std::fs::remove_file(&file.path) {
+                    Ok(()) => true,
+                    Err(error) => {
+                        log::warn!("Failed to evict preview {}: {:?}", file.path.display(), error);
+                        false
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|e| PipelineError::message(&format!("preview eviction failed: {:?}", e)))?;
+
+        if let Ok(mut accessed) = self.accessed.lock() {
+            for file in &removed {
+                accessed.remove(&file.path);
+            }
+        }
+        report.evicted_files = removed.len() as u64;
+        report.evicted_bytes = removed.iter().map(|file| file.bytes).sum();
+        report.files -= report.evicted_files;
+        report.bytes -= report.evicted_bytes;
+        Ok(report)
+    }
+
+    // Freshly generated files count as accessed too: they are usually about to be served.
+    fn is_protected(&self, file: &PreviewCacheFile) -> bool {
+        let served = self.accessed.lock().ok().and_then(|accessed| accessed.get(&file.path).copied());
+        let last_access = served.map_or(file.last_access, |served| served.max(file.last_access));
+        let recent = last_access.elapsed().map(|age| age < PreviewCache::SERVE_GRACE).unwrap_or(true);
+        recent || self.coordinator.is_in_flight(&file.path)
+    }
+
+    // Stats on network shares can be slow, so the walk runs off the event loop.
+    async fn scan(root: PathBuf) -> Result<Vec<PreviewCacheFile>, PipelineError> {
+        tokio::task::spawn_blocking(move || {
+            let mut files = Vec::new();
+            let mut pending = vec![root];
+            while let Some(directory) = pending.pop() {
+                let Ok(entries) = std::fs::read_dir(&directory) else {
+                    continue;
+                };
+                for entry in entries.flatten() {
+                    let Ok(metadata) = entry.metadata() else {
+                        continue;
+                    };
+                    if metadata.is_dir() {
+                        pending.push(entry.path());
+                    } else if metadata.is_file() {
+                        files.push(PreviewCacheFile {
+                            path: entry.path(),
+                            bytes: metadata.len(),
+                            last_access: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                        });
+                    }
+                }
+            }
+            files
+        })
+        .await
+        .map_err(|e| PipelineError::message(&format!("preview cache scan failed: {:?}", e)))
+    }
+}
@@ -0,0 +1,158 @@
+use crate::prelude::*;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::{Notify, Semaphore};
+use tokio::task;
+use tokio::time::{Duration, timeout};
+
+type InFlightMap = Arc<Mutex<HashMap<PathBuf, Arc<Notify>>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreviewBusy;
+
+#[derive(Clone)]
+pub struct PreviewCoordinator {
+    in_flight: InFlightMap,
+    extraction_slots: Arc<Semaphore>,
+    max_concurrent_extractions: Arc<AtomicUsize>,
+    acquire_timeout_millis: Arc<AtomicU64>,
+}
+
+impl PreviewCoordinator {
+    pub const RETRY_AFTER_SECONDS: u64 = 5;
+    const DEFAULT_ACQUIRE_TIMEOUT_SECONDS: u64 = 10;
+
+    pub fn new() -> Self {
+        let default_concurrency = std::thread::available_parallelism().map(|value| value.get()).unwrap_or(4);
+        Self::with_limits(default_concurrency, Duration::from_secs(Self::DEFAULT_ACQUIRE_TIMEOUT_SECONDS))
+    }
+
+    pub fn with_limits(max_concurrent_extractions: usize, acquire_timeout: Duration) -> Self {
+        let max_concurrent_extractions = max_concurrent_extractions.max(1);
+        Self {
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            extraction_slots: Arc::new(Semaphore::new(max_concurrent_extractions)),
+            max_concurrent_extractions: Arc::new(AtomicUsize::new(max_concurrent_extractions)),
+            acquire_timeout_millis: Arc::new(AtomicU64::new(acquire_timeout.as_millis() as u64)),
+        }
+    }
+
+    pub fn max_concurrent_extractions(&self) -> usize {
+        self.max_concurrent_extractions.load(Ordering::SeqCst)
+    }
+
+    pub fn acquire_timeout(&self) -> Duration {
+        Duration::from_millis(self.acquire_timeout_millis.load(Ordering::SeqCst))
+    }
+
+    pub fn set_acquire_timeout(&self, acquire_timeout: Duration) {
+        self.acquire_timeout_millis.store(acquire_timeout.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    pub fn set_max_concurrent_extractions(&self, max_concurrent_extractions: usize) {
+        let next = max_concurrent_extractions.max(1);
+        let previous = self.max_concurrent_extractions.swap(next, Ordering::SeqCst);
+        if next > previous {
+            self.extraction_slots.add_permits(next - previous);
+            return;
+        }
+
+        let surplus = previous - next;
+        let forgotten = self.extraction_slots.forget_permits(surplus);
+        let outstanding = surplus - forgotten;
+        if outstanding == 0 {
+            return;
+        }
+
+        // Slots held by running extractions are reclaimed as those extractions finish.
+        let slots = Arc::clone(&self.extraction_slots);
+        tokio::spawn(async move {
+            if let Ok(permits) = slots.acquire_many_owned(outstanding as u32).await {
+                permits.forget();
+            }
+        });
+    }
+
+    pub fn in_flight_count(&self) -> usize {
+        self.in_flight.lock().map(|in_flight| in_flight.len()).unwrap_or(0)
+    }
+
+    pub fn is_in_flight(&self, output_path: &Path) -> bool {
+        self.in_flight.lock().map(|in_flight| in_flight.contains_key(output_path)).unwrap_or(true)
+    }
+
+    pub fn running_extractions(&self) -> usize {
+        self.max_concurrent_extractions().saturating_sub(self.extraction_slots.available_permits())
+    }
+
+    pub async fn generate<F>(&self, output_path: &Path, extract: F) -> Result<Option<PathBuf>, PreviewBusy>
+    where
+        F: FnOnce() -> Option<PathBuf> + Send + 'static,
+    {
+        if output_path.exists() {
+            return Ok(Some(output_path.to_path_buf()));
+        }
+
+        let waiter = {
+            let Ok(mut in_flight) = self.in_flight.lock() else {
+                return Ok(None);
+            };
+            match in_flight.get(output_path) {
+                Some(notify) => Some(Arc::clone(notify)),
+                None => {
+                    in_flight.insert(output_path.to_path_buf(), Arc::new(Notify::new()));
+                    None
+                }
+            }
+        };
+
+        if let Some(notify) = waiter {
+            let notified = notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_waiting_on(output_path, &notify) {
+                notified.await;
+            }
+            return Ok(output_path.exists().then(|| output_path.to_path_buf()));
+        }
+
+        let _guard = InFlightGuard { in_flight: Arc::clone(&self.in_flight), key: output_path.to_path_buf() };
+        let permit = match timeout(self.acquire_timeout(), Arc::clone(&self.extraction_slots).acquire_owned()).await {
+            Ok(Ok(permit)) => permit,
+            _ => {
+                log::warn!("Preview extraction slots exhausted; rejecting {}", output_path.display());
+                return Err(PreviewBusy);
+            }
+        };
+
+        let generated = task::spawn_blocking(move || {
+            let _permit = permit;
+            extract()
+        })
+        .await
+        .ok()
+        .flatten()
+        .filter(|path| path.exists());
+        Ok(generated)
+    }
+
+    fn is_waiting_on(&self, output_path: &Path, notify: &Arc<Notify>) -> bool {
+        self.in_flight
+            .lock()
+            .map(|in_flight| in_flight.get(output_path).is_some_and(|current| Arc::ptr_eq(current, notify)))
+            .unwrap_or(false)
+    }
+}
+
+struct InFlightGuard {
+    in_flight: InFlightMap,
+    key: PathBuf,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        let notify = self.in_flight.lock().ok().and_then(|mut in_flight| in_flight.remove(&self.key));
+        if let Some(notify) = notify {
+            notify.notify_waiters();
+        }
+    }
+}
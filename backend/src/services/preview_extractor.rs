@@ -1,20 +1,27 @@
 use super::image_process_constants::{PREVIEW_FORMAT_EXTENSION, RAW_EXTENSIONS};
 use crate::prelude::*;
 use anyhow::{Result, anyhow};
-use image::{ImageFormat, ImageReader, imageops::FilterType};
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat, ImageReader, imageops, imageops::FilterType};
 use rawthumb::{ExportConfig, ThumbnailExporter};
 
 const PREVIEW_MAX_BORDER: u32 = 1920;
+const PREVIEW_JPEG_QUALITY: u8 = 75;
 
 #[derive(Clone, Debug)]
 pub struct PreviewExtractor {
     max_border: u32,
+    quality: u8,
     output_path: Option<PathBuf>,
 }
 
 impl PreviewExtractor {
     pub fn new() -> Self {
-        Self { max_border: PREVIEW_MAX_BORDER, output_path: None }
+        Self { max_border: PREVIEW_MAX_BORDER, quality: PREVIEW_JPEG_QUALITY, output_path: None }
+    }
+
+    pub fn from_profile(profile: &DerivativeProfile) -> Self {
+        Self::new().with_max_border(profile.preview_max_dimension).with_quality(profile.preview_jpeg_quality)
     }
 
     pub fn with_max_border(mut self, max_border: u32) -> Self {
@@ -22,6 +29,11 @@ impl PreviewExtractor {
         self
     }
 
+    pub fn with_quality(mut self, quality: u8) -> Self {
+        self.quality = quality.clamp(1, 100);
+        self
+    }
+
     pub fn with_output_path<P: AsRef<Path>>(mut self, output_path: P) -> Self {
         self.output_path = Some(output_path.as_ref().to_path_buf());
         self
@@ -43,10 +55,41 @@ impl PreviewExtractor {
         self.max_border
     }
 
+    pub fn quality(&self) -> u8 {
+        self.quality
+    }
+
     pub fn output_format_extension() -> &'static str {
         PREVIEW_FORMAT_EXTENSION
     }
 
+    // Composites the watermark into the bottom-right corner of an existing preview.
+    pub fn apply_watermark<P: AsRef<Path>, Q: AsRef<Path>>(
+        preview_path: P,
+        watermark: &PreviewWatermark,
+        output_path: Q,
+    ) -> Result<PathBuf> {
+        let destination = output_path.as_ref().to_path_buf();
+        let mut preview = ImageReader::open(preview_path.as_ref())?.with_guessed_format()?.decode()?.to_rgba8();
+        let mark = ImageReader::open(&watermark.image_path)?.with_guessed_format()?.decode()?;
+
+        let max_width = ((preview.width() as f32 * PreviewWatermark::MAX_WIDTH_RATIO) as u32).max(1);
+        let mark = if mark.width() > max_width { mark.resize(max_width, u32::MAX, FilterType::Lanczos3) } else { mark };
+        let mut mark = mark.to_rgba8();
+        for pixel in mark.pixels_mut() {
+            pixel[3] = (pixel[3] as f32 * watermark.opacity).round() as u8;
+        }
+
+        let margin = (preview.width().min(preview.height()) as f32 * PreviewWatermark::MARGIN_RATIO) as i64;
+        let x = (preview.width() as i64 - mark.width() as i64 - margin).max(0);
+        let y = (preview.height() as i64 - mark.height() as i64 - margin).max(0);
+        imageops::overlay(&mut preview, &mark, x, y);
+
+        Self::ensure_parent_directory(&destination)?;
+        DynamicImage::ImageRgba8(preview).to_rgb8().save_with_format(&destination, ImageFormat::Jpeg)?;
+        Ok(destination)
+    }
+
     fn generate_to_file(&self, input_path: &Path, output_path: &Path) -> Result<()> {
         Self::ensure_parent_directory(output_path)?;
 
@@ -71,6 +114,7 @@ impl PreviewExtractor {
         RAW_EXTENSIONS.iter().any(|candidate| candidate.eq_ignore_ascii_case(extension))
     }
 
+    // Embedded RAW previews are already JPEG and are written as-is.
     fn generate_raw_image(&self, input_path: &Path, output_path: &Path) -> Result<()> {
         let exporter_config = ExportConfig::default().with_auto_rotate(true).with_max_border(Some(self.max_border));
         let exporter = ThumbnailExporter::new_with_config(exporter_config);
@@ -81,8 +125,9 @@ impl PreviewExtractor {
 
     fn generate_standard_image(&self, input_path: &Path, output_path: &Path) -> Result<()> {
         let image = ImageReader::open(input_path)?.with_guessed_format()?.decode()?;
-        let resized = image.resize(self.max_border, self.max_border, FilterType::Lanczos3);
-        resized.save_with_format(output_path, ImageFormat::Jpeg)?;
+        let resized = image.resize(self.max_border, self.max_border, FilterType::Lanczos3).to_rgb8();
+        let writer = std::io::BufWriter::new(fs::File::create(output_path)?);
+        resized.write_with_encoder(JpegEncoder::new_with_quality(writer, self.quality))?;
         Ok(())
     }
 }
@@ -0,0 +1,221 @@
+use crate::prelude::*;
+
+// Generates missing previews overnight so the first open of a RAW file does not wait on extraction.
+// It goes through the same coordinator as on-demand previews, one photo at a time, so viewers keep
+// the remaining extraction slots.
+#[derive(Clone)]
+pub struct PreviewWarmerService {
+    services: Arc<ServiceProvider>,
+    photos: Arc<Repository<Photo>>,
+    storages: Arc<Repository<StorageLocation>>,
+    storage_paths: Arc<StoragePathCache>,
+    settings: Arc<SettingService>,
+    coordinator: Arc<PreviewCoordinator>,
+    runner: Arc<BackgroundTaskRunner>,
+    status: Arc<Mutex<PreviewWarmerStatus>>,
+}
+
+impl PreviewWarmerService {
+    pub const TASK_NAME: &'static str = "preview-warmer";
+    const CHECK_INTERVAL_SECONDS: u64 = 15 * 60;
+    const PAGE_SIZE: u32 = 200;
+    const EXISTS_CHUNK_SIZE: usize = 32;
+    const BUSY_RETRIES: u32 = 3;
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photos: services.get::<Repository<Photo>>(),
+            storages: services.get::<Repository<StorageLocation>>(),
+            storage_paths: services.get::<StoragePathCache>(),
+            settings: services.get::<SettingService>(),
+            coordinator: services.get::<PreviewCoordinator>(),
+            runner: services.get::<BackgroundTaskRunner>(),
+            status: Arc::new(Mutex::new(PreviewWarmerStatus::default())),
+            services,
+        }
+    }
+
+    pub fn start_scheduler(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(Self::CHECK_INTERVAL_SECONDS));
+            loop {
+                interval.tick().await;
+                if !service.status().is_due(Utc::now()) {
+                    continue;
+                }
+                match service.settings.preview_warm_scope().await {
+                    Ok(PreviewWarmScope::None) => {}
+                    Ok(_) => service.enqueue_run(),
+                    Err(error) => log::warn!("Preview warm-up check failed: {:?}", error),
+                }
+            }
+        });
+    }
+
+    pub fn status(&self) -> PreviewWarmerStatus {
+        self.status.lock().map(|status| status.clone()).unwrap_or_default()
+    }
+
+    fn enqueue_run(&self) {
+        let service = self.clone();
+        let task = TaskDescriptor::new(Self::TASK_NAME, async move {
+            service.run().await.map_err(|e| anyhow::anyhow!("{:?}", e))?;
+            Ok(())
+        });
+        if let Err(error) = self.runner.enqueue(task) {
+            log::warn!("Failed to queue preview warm-up: {:?}", error);
+        }
+    }
+
+    pub async fn run(&self) -> Result<PreviewWarmerStatus, PipelineError> {
+        let scope = self.settings.preview_warm_scope().await?;
+        if scope == PreviewWarmScope::None {
+            return Ok(self.status());
+        }
+        let max_per_run = self.settings.preview_warm_max_per_run().await?;
+        let now = Utc::now();
+        {
+            let mut status =
+                self.status.lock().map_err(|_| PipelineError::message("preview warmer status poisoned"))?;
+            if status.running {
+                return Ok(status.clone());
+            }
+            *status =
+                PreviewWarmerStatus { scope, running: true, started_at: Some(now), ..PreviewWarmerStatus::default() };
+        }
+
+        let result = self.warm(scope.since(now), max_per_run).await;
+        self.update(|status| {
+            status.running = false;
+            status.finished_at = Some(Utc::now());
+        });
+
+        let status = self.status();
+        log::info!(
+            "Preview warm-up ({}) scanned {} photo(s): {} generated, {} failed, {} left for later",
+            scope.as_str(),
+            status.scanned,
+            status.generated,
+            status.failed,
+            status.remaining
+        );
+        result.map(|_| status)
+    }
+
+    async fn warm(&self, since: Option<DateTime<Utc>>, max_per_run: u64) -> Result<(), PipelineError> {
+        let profile = DerivativeProfileCache::resolve(&self.services).await;
+        let signature = profile.preview_signature();
+        let mut roots = HashMap::<Uuid, Option<PathBuf>>::new();
+        let mut attempted = 0u64;
+        let mut offset = 0u32;
+
+        loop {
+            let page = self.photos.preview_warm_candidates(since, Self::PAGE_SIZE, offset).await?;
+            if page.is_empty() {
+                break;
+            }
+            offset += page.len() as u32;
+            let scanned = page.len() as u64;
+
+            let mut targets = Vec::with_capacity(page.len());
+            for candidate in page {
+                if !roots.contains_key(&candidate.storage_id) {
+                    let root = self.storage_paths.root(&self.storages, candidate.storage_id).await?;
+                    roots.insert(candidate.storage_id, root.map(|root| root.join(SettingConsts::PREVIEW_FOLDER)));
+                }
+                if let Some(Some(root)) = roots.get(&candidate.storage_id) {
+                    let output = FileService::hash_path(root, &candidate.hash, SettingConsts::PREVIEW_FORMAT);
+                    targets.push((candidate, output));
+                }
+            }
+
+            let missing = Self::missing_previews(targets).await?;
+            self.update(|status| status.scanned += scanned);
+
+            for (candidate, output) in missing {
+                if attempted >= max_per_run {
+                    self.update(|status| status.remaining += 1);
+                    continue;
+                }
+                attempted += 1;
+                match self.generate(&candidate, &output, &profile).await {
+                    Some(true) => {
+                        if let Err(error) = self
+                            .photos
+                            .record_derivative_profile(
+                                candidate.storage_id,
+                                &candidate.hash,
+                                DerivativeProfile::KIND_PREVIEW,
+                                &signature,
+                            )
+                            .await
+                        {
+                            log::warn!("Failed to record preview profile for {}: {:?}", candidate.hash, error);
+                        }
+                        self.update(|status| status.generated += 1);
+                    }
+                    Some(false) => self.update(|status| status.failed += 1),
+                    None => self.update(|status| status.remaining += 1),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Stats on network shares can be slow, so they run off the event loop a chunk at a time.
+    async fn missing_previews(
+        targets: Vec<(PreviewWarmCandidate, PathBuf)>,
+    ) -> Result<Vec<(PreviewWarmCandidate, PathBuf)>, PipelineError> {
+        let mut missing = Vec::new();
+        for chunk in targets.chunks(Self::EXISTS_CHUNK_SIZE) {
+            let chunk = chunk.to_vec();
+            let found = tokio::task::spawn_blocking(move || {
+                chunk.into_iter().filter(|(_, output)| !output.exists()).collect::<Vec<_>>()
+            })
+            .await
+            .map_err(|e| PipelineError::message(&format!("preview check failed: {:?}", e)))?;
+            missing.extend(found);
+        }
+        Ok(missing)
+    }
+
+    // None when the coordinator stayed busy with viewer requests; the photo is picked up next night.
+    async fn generate(
+        &self,
+        candidate: &PreviewWarmCandidate,
+        output: &Path,
+        profile: &DerivativeProfile,
+    ) -> Option<bool> {
+        for _ in 0..Self::BUSY_RETRIES {
+            let extractor = PreviewExtractor::from_profile(profile);
+            let source = PathBuf::from(&candidate.path);
+            let target = output.to_path_buf();
+            let generated = self
+                .coordinator
+                .generate(output, move || {
+                    extractor
+                        .extract_to(&source, &target)
+                        .map_err(|error| {
+                            log::warn!("Failed to pre-generate preview for {}: {:?}", source.display(), error)
+                        })
+                        .ok()
+                })
+                .await;
+            match generated {
+                Ok(path) => return Some(path.is_some()),
+                Err(PreviewBusy) => {
+                    tokio::time::sleep(std::time::Duration::from_secs(PreviewCoordinator::RETRY_AFTER_SECONDS)).await
+                }
+            }
+        }
+        None
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut PreviewWarmerStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            apply(&mut status);
+        }
+    }
+}
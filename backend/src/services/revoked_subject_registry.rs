@@ -0,0 +1,23 @@
+use crate::prelude::*;
+use std::sync::RwLock;
+
+#[derive(Clone, Default)]
+pub struct RevokedSubjectRegistry {
+    subjects: Arc<RwLock<HashSet<String>>>,
+}
+
+impl RevokedSubjectRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn revoke(&self, user_id: Uuid) {
+        if let Ok(mut subjects) = self.subjects.write() {
+            subjects.insert(user_id.to_string());
+        }
+    }
+
+    pub fn is_revoked(&self, subject: &str) -> bool {
+        self.subjects.read().map(|subjects| subjects.contains(subject)).unwrap_or(false)
+    }
+}
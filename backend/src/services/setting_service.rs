@@ -13,14 +13,42 @@ impl SettingKeys {
     pub const SITE_ALLOW_REGISTRATION: &'static str = "site.allowRegistration";
     pub const SITE_ALLOW_COMMENTS: &'static str = "site.allowComments";
     pub const SECURITY_ROLE_PERMISSIONS: &'static str = "security.rolePermissions";
+    pub const SECURITY_REQUIRE_EMAIL_VERIFICATION: &'static str = "security.requireEmailVerification";
+    pub const SECURITY_TOKENS_BEFORE_VERIFICATION: &'static str = "security.tokensBeforeVerification";
     pub const PHOTO_MANAGE_UPLOADS_ENABLED: &'static str = "photo.manage.uploadsEnabled";
     pub const PHOTO_MANAGE_VIEWER_HIDDEN_TAGS: &'static str = "photo.manage.viewerHiddenTags";
+    pub const PHOTO_MANAGE_SYNC_UPLOAD_MAX_FILES: &'static str = "photo.manage.syncUploadMaxFiles";
+    pub const PHOTO_MANAGE_SYNC_UPLOAD_TIMEOUT_SECONDS: &'static str = "photo.manage.syncUploadTimeoutSeconds";
+    pub const PHOTO_MANAGE_STACK_REPRESENTATIVE: &'static str = "photo.manage.stackRepresentative";
     pub const CLIENT_APPROVAL_POLICY: &'static str = "client.approvalPolicy";
     pub const EXPERIENCE_GRID_COLUMNS: &'static str = "experience.gridColumns";
     pub const EXPERIENCE_DEFAULT_VIEW: &'static str = "experience.defaultView";
     pub const EXPERIENCE_TIPS_ENABLED: &'static str = "experience.tipsEnabled";
     pub const NOTIFICATIONS_EMAIL_SUMMARY: &'static str = "notifications.emailSummary";
     pub const NOTIFICATIONS_DAILY_DIGEST_HOUR: &'static str = "notifications.dailyDigestHour";
+    pub const CORS_ALLOWED_ORIGINS: &'static str = "cors.allowedOrigins";
+    pub const CORS_ALLOW_CREDENTIALS: &'static str = "cors.allowCredentials";
+    pub const CORS_ALLOWED_HEADERS: &'static str = "cors.allowedHeaders";
+    pub const CORS_MAX_AGE_SECONDS: &'static str = "cors.maxAgeSeconds";
+    pub const CORS_STRICT: &'static str = "cors.strict";
+    pub const ASSETS_WEB_ROOT: &'static str = "assets.webRoot";
+    pub const PREVIEW_MAX_DIMENSION: &'static str = "preview.maxDimension";
+    pub const PREVIEW_JPEG_QUALITY: &'static str = "preview.jpegQuality";
+    pub const THUMBNAIL_MAX_DIMENSION: &'static str = "thumbnail.maxDimension";
+    pub const THUMBNAIL_FORMAT: &'static str = "thumbnail.format";
+    pub const THUMBNAIL_ENABLE_AVIF: &'static str = "thumbnail.enableAvif";
+    pub const PREVIEW_WATERMARK_ENABLED: &'static str = "preview.watermark.enabled";
+    pub const PREVIEW_WATERMARK_IMAGE_PATH: &'static str = "preview.watermark.imagePath";
+    pub const PREVIEW_WATERMARK_OPACITY: &'static str = "preview.watermark.opacity";
+    pub const PRIVACY_STRIP_GPS_ON_DOWNLOAD: &'static str = "privacy.stripGpsOnDownload";
+    pub const PRIVACY_COORDINATE_PRECISION_FOR_VIEWERS: &'static str = "privacy.coordinatePrecisionForViewers";
+    pub const PREVIEW_PREGENERATE_SCOPE: &'static str = "preview.pregenerate.scope";
+    pub const PREVIEW_PREGENERATE_MAX_PER_RUN: &'static str = "preview.pregenerate.maxPerRun";
+    pub const PREVIEW_CACHE_MAX_BYTES: &'static str = "preview.cache.maxBytes";
+    pub const XMP_WRITE_BACK: &'static str = "xmp.writeBack";
+    pub const ML_ANALYZER_URL: &'static str = "ml.analyzerUrl";
+    pub const ML_ANALYZER_TIMEOUT_SECONDS: &'static str = "ml.analyzerTimeoutSeconds";
+    pub const ML_MIN_CONFIDENCE: &'static str = "ml.minConfidence";
 }
 
 pub struct SettingService {
@@ -32,6 +60,8 @@ impl SettingService {
     const ACTION_DASHBOARD_ACCESS: &'static str = "dashboard.access";
     const ACTION_SETTINGS_GENERAL_UPDATE: &'static str = "settings.general.update";
     const ACTION_PHOTOS_UPLOAD: &'static str = "photos.upload";
+    const ACTION_PHOTOS_TAG: &'static str = "photos.tag";
+    const ACTION_PHOTOS_EDIT: &'static str = "photos.edit";
     const ACTION_COMMENTS_CREATE: &'static str = "comments.create";
 
     pub fn new(repository: Arc<Repository<Setting>>) -> Self {
@@ -84,13 +114,12 @@ impl SettingService {
         Ok(def.to_dto(current_value, updated_at))
     }
 
-    pub async fn update(&self, key: &str, value: JsonValue) -> Result<SettingDto, PipelineError> {
-        let def =
-            self.definitions.iter().find(|d| d.key == key).ok_or_else(|| PipelineError::message("Unknown setting"))?;
+    pub fn validate(&self, key: &str, value: &JsonValue) -> Result<(), PipelineError> {
+        self.definition(key, value).map(|_| ())
+    }
 
-        if !def.value_type.matches(&value) {
-            return Err(PipelineError::message("Invalid value type for setting"));
-        }
+    pub async fn update(&self, key: &str, value: JsonValue) -> Result<SettingDto, PipelineError> {
+        let def = self.definition(key, &value)?;
 
         let serialized = serde_json::to_string(&value).map_err(|err| {
             let msg = format!("Failed to serialize setting value: {err}");
@@ -132,6 +161,43 @@ impl SettingService {
         Ok(def.to_dto(parsed_value, saved.updated_at))
     }
 
+    fn definition(&self, key: &str, value: &JsonValue) -> Result<&SettingDefinition, PipelineError> {
+        let def =
+            self.definitions.iter().find(|d| d.key == key).ok_or_else(|| PipelineError::message("Unknown setting"))?;
+
+        if !def.value_type.matches(value) {
+            return Err(PipelineError::message("Invalid value type for setting"));
+        }
+        if let Some(options) = &def.options {
+            if !options.iter().any(|option| &option.value == value) {
+                return Err(PipelineError::message("Value is not one of the allowed options"));
+            }
+        }
+        if key == SettingKeys::PREVIEW_CACHE_MAX_BYTES && PreviewCacheBudgets::parse(value).is_none() {
+            return Err(PipelineError::message("Value must map storage ids to a byte limit of 0 or more"));
+        }
+        if let (Some((min, max)), Some(number)) = (Self::number_range(key), value.as_f64()) {
+            if number < min || number > max {
+                return Err(PipelineError::message(&format!("Value must be between {} and {}", min, max)));
+            }
+        }
+        Ok(def)
+    }
+
+    fn number_range(key: &str) -> Option<(f64, f64)> {
+        match key {
+            SettingKeys::PREVIEW_MAX_DIMENSION => Some(DerivativeProfile::PREVIEW_DIMENSION_RANGE),
+            SettingKeys::PREVIEW_JPEG_QUALITY => Some(DerivativeProfile::JPEG_QUALITY_RANGE),
+            SettingKeys::THUMBNAIL_MAX_DIMENSION => Some(DerivativeProfile::THUMBNAIL_DIMENSION_RANGE),
+            SettingKeys::PREVIEW_WATERMARK_OPACITY => Some((0.0, 1.0)),
+            SettingKeys::NOTIFICATIONS_DAILY_DIGEST_HOUR => Some(DigestSchedule::HOUR_RANGE),
+            SettingKeys::ML_ANALYZER_TIMEOUT_SECONDS => Some(ImageAnalyzerConfig::TIMEOUT_RANGE),
+            SettingKeys::ML_MIN_CONFIDENCE => Some((0.0, 1.0)),
+            SettingKeys::PREVIEW_PREGENERATE_MAX_PER_RUN => Some(PreviewWarmerStatus::MAX_PER_RUN_RANGE),
+            _ => None,
+        }
+    }
+
     pub async fn is_site_public(&self) -> Result<bool, PipelineError> {
         self.get_bool_setting(SettingKeys::SITE_PUBLIC).await
     }
@@ -140,6 +206,14 @@ impl SettingService {
         self.get_bool_setting(SettingKeys::SITE_ALLOW_REGISTRATION).await
     }
 
+    pub async fn is_email_verification_required(&self) -> Result<bool, PipelineError> {
+        self.get_bool_setting(SettingKeys::SECURITY_REQUIRE_EMAIL_VERIFICATION).await
+    }
+
+    pub async fn issues_tokens_before_verification(&self) -> Result<bool, PipelineError> {
+        self.get_bool_setting(SettingKeys::SECURITY_TOKENS_BEFORE_VERIFICATION).await
+    }
+
     pub async fn is_site_initialized(&self) -> Result<bool, PipelineError> {
         self.get_bool_setting(SettingKeys::SITE_INITIALIZED).await
     }
@@ -148,6 +222,109 @@ impl SettingService {
         self.get_bool_setting(SettingKeys::PHOTO_MANAGE_UPLOADS_ENABLED).await
     }
 
+    pub async fn sync_upload_max_files(&self) -> Result<usize, PipelineError> {
+        Ok(self.get_number_setting(SettingKeys::PHOTO_MANAGE_SYNC_UPLOAD_MAX_FILES).await?.max(0.0) as usize)
+    }
+
+    pub async fn sync_upload_timeout(&self) -> Result<std::time::Duration, PipelineError> {
+        let seconds = self.get_number_setting(SettingKeys::PHOTO_MANAGE_SYNC_UPLOAD_TIMEOUT_SECONDS).await?;
+        Ok(std::time::Duration::from_secs_f64(seconds.max(0.0)))
+    }
+
+    pub async fn stack_representative(&self) -> Result<StackRepresentative, PipelineError> {
+        let setting = self.get(SettingKeys::PHOTO_MANAGE_STACK_REPRESENTATIVE).await?;
+        Ok(setting.value.as_str().and_then(StackRepresentative::parse).unwrap_or_default())
+    }
+
+    pub async fn strip_gps_on_download(&self) -> Result<bool, PipelineError> {
+        self.get_bool_setting(SettingKeys::PRIVACY_STRIP_GPS_ON_DOWNLOAD).await
+    }
+
+    pub async fn coordinate_precision_for_viewers(&self) -> Result<CoordinatePrecision, PipelineError> {
+        let setting = self.get(SettingKeys::PRIVACY_COORDINATE_PRECISION_FOR_VIEWERS).await?;
+        Ok(setting.value.as_str().and_then(CoordinatePrecision::parse).unwrap_or_default())
+    }
+
+    pub async fn preview_warm_scope(&self) -> Result<PreviewWarmScope, PipelineError> {
+        let setting = self.get(SettingKeys::PREVIEW_PREGENERATE_SCOPE).await?;
+        Ok(setting.value.as_str().and_then(PreviewWarmScope::parse).unwrap_or_default())
+    }
+
+    pub async fn preview_warm_max_per_run(&self) -> Result<u64, PipelineError> {
+        let (min, max) = PreviewWarmerStatus::MAX_PER_RUN_RANGE;
+        Ok(self.get_number_setting(SettingKeys::PREVIEW_PREGENERATE_MAX_PER_RUN).await?.clamp(min, max).round() as u64)
+    }
+
+    pub async fn preview_cache_budgets(&self) -> Result<PreviewCacheBudgets, PipelineError> {
+        let setting = self.get(SettingKeys::PREVIEW_CACHE_MAX_BYTES).await?;
+        Ok(PreviewCacheBudgets::parse(&setting.value).unwrap_or_default())
+    }
+
+    pub async fn xmp_write_back(&self) -> Result<bool, PipelineError> {
+        self.get_bool_setting(SettingKeys::XMP_WRITE_BACK).await
+    }
+
+    // None while no analyzer URL is configured, which keeps tag suggestions switched off.
+    pub async fn image_analyzer(&self) -> Result<Option<ImageAnalyzerConfig>, PipelineError> {
+        let setting = self.get(SettingKeys::ML_ANALYZER_URL).await?;
+        let url = setting.value.as_str().unwrap_or_default().trim().to_string();
+        if url.is_empty() {
+            return Ok(None);
+        }
+        let (min, max) = ImageAnalyzerConfig::TIMEOUT_RANGE;
+        let timeout = self.get_number_setting(SettingKeys::ML_ANALYZER_TIMEOUT_SECONDS).await?.clamp(min, max);
+        Ok(Some(ImageAnalyzerConfig {
+            url,
+            timeout: std::time::Duration::from_secs_f64(timeout),
+            min_confidence: self.ml_min_confidence().await?,
+        }))
+    }
+
+    pub async fn ml_min_confidence(&self) -> Result<f32, PipelineError> {
+        Ok(self.get_number_setting(SettingKeys::ML_MIN_CONFIDENCE).await?.clamp(0.0, 1.0) as f32)
+    }
+
+    pub async fn derivative_profile(&self) -> Result<DerivativeProfile, PipelineError> {
+        let defaults = DerivativeProfile::default();
+        let clamp = |value: f64, (min, max): (f64, f64)| value.clamp(min, max).round();
+        let format = self.get(SettingKeys::THUMBNAIL_FORMAT).await?;
+
+        Ok(DerivativeProfile {
+            preview_max_dimension: clamp(
+                self.get_number_setting(SettingKeys::PREVIEW_MAX_DIMENSION).await?,
+                DerivativeProfile::PREVIEW_DIMENSION_RANGE,
+            ) as u32,
+            preview_jpeg_quality: clamp(
+                self.get_number_setting(SettingKeys::PREVIEW_JPEG_QUALITY).await?,
+                DerivativeProfile::JPEG_QUALITY_RANGE,
+            ) as u8,
+            thumbnail_max_dimension: clamp(
+                self.get_number_setting(SettingKeys::THUMBNAIL_MAX_DIMENSION).await?,
+                DerivativeProfile::THUMBNAIL_DIMENSION_RANGE,
+            ) as u32,
+            thumbnail_format: format
+                .value
+                .as_str()
+                .and_then(ThumbnailFormat::parse)
+                .filter(|format| ThumbnailFormat::BASE.contains(format))
+                .unwrap_or(defaults.thumbnail_format),
+            thumbnail_avif: self.get_bool_setting(SettingKeys::THUMBNAIL_ENABLE_AVIF).await?,
+        })
+    }
+
+    pub async fn preview_watermark(&self) -> Result<Option<PreviewWatermark>, PipelineError> {
+        if !self.get_bool_setting(SettingKeys::PREVIEW_WATERMARK_ENABLED).await? {
+            return Ok(None);
+        }
+        let setting = self.get(SettingKeys::PREVIEW_WATERMARK_IMAGE_PATH).await?;
+        let image_path = setting.value.as_str().unwrap_or_default().trim().to_string();
+        if image_path.is_empty() {
+            return Ok(None);
+        }
+        let opacity = self.get_number_setting(SettingKeys::PREVIEW_WATERMARK_OPACITY).await?;
+        Ok(Some(PreviewWatermark::new(image_path, opacity)))
+    }
+
     pub async fn viewer_hidden_tags(&self) -> Result<HashSet<String>, PipelineError> {
         let tags = self.get_string_array_setting(SettingKeys::PHOTO_MANAGE_VIEWER_HIDDEN_TAGS).await?;
         Ok(tags.into_iter().map(|tag| tag.to_lowercase()).collect())
@@ -161,6 +338,14 @@ impl SettingService {
         self.is_action_allowed(roles, Self::ACTION_PHOTOS_UPLOAD).await
     }
 
+    pub async fn can_tag_photos(&self, roles: &HashSet<String>) -> Result<bool, PipelineError> {
+        self.is_action_allowed(roles, Self::ACTION_PHOTOS_TAG).await
+    }
+
+    pub async fn can_edit_photos(&self, roles: &HashSet<String>) -> Result<bool, PipelineError> {
+        self.is_action_allowed(roles, Self::ACTION_PHOTOS_EDIT).await
+    }
+
     pub async fn can_create_comments(&self, roles: &HashSet<String>) -> Result<bool, PipelineError> {
         self.is_action_allowed(roles, Self::ACTION_COMMENTS_CREATE).await
     }
@@ -185,6 +370,34 @@ impl SettingService {
         Ok(false)
     }
 
+    pub async fn cors_policy(&self, environment: AppEnvironment) -> Result<CorsPolicy, PipelineError> {
+        let origins = self.get_string_array_setting(SettingKeys::CORS_ALLOWED_ORIGINS).await?;
+        let allow_credentials = self.get_bool_setting(SettingKeys::CORS_ALLOW_CREDENTIALS).await?;
+        let headers = self.get_string_array_setting(SettingKeys::CORS_ALLOWED_HEADERS).await?;
+        let max_age = self.get_number_setting(SettingKeys::CORS_MAX_AGE_SECONDS).await?.max(0.0) as u64;
+        let strict = self.get_bool_setting(SettingKeys::CORS_STRICT).await?;
+
+        Ok(CorsPolicy::new(environment, origins, allow_credentials, headers, max_age, strict))
+    }
+
+    pub async fn site_title(&self) -> Result<String, PipelineError> {
+        let setting = self.get(SettingKeys::SITE_TITLE).await?;
+        Ok(setting.value.as_str().unwrap_or_default().trim().to_string())
+    }
+
+    pub async fn email_summary_enabled(&self) -> Result<bool, PipelineError> {
+        self.get_bool_setting(SettingKeys::NOTIFICATIONS_EMAIL_SUMMARY).await
+    }
+
+    pub async fn digest_schedule(&self) -> Result<DigestSchedule, PipelineError> {
+        Ok(DigestSchedule::new(self.get_number_setting(SettingKeys::NOTIFICATIONS_DAILY_DIGEST_HOUR).await?))
+    }
+
+    pub async fn web_root(&self) -> Result<String, PipelineError> {
+        let setting = self.get(SettingKeys::ASSETS_WEB_ROOT).await?;
+        Ok(setting.value.as_str().unwrap_or_default().trim().to_string())
+    }
+
     pub async fn client_approval_policy(&self) -> Result<String, PipelineError> {
         let setting = self.get(SettingKeys::CLIENT_APPROVAL_POLICY).await?;
         let policy = setting.value.as_str().unwrap_or("auto").trim().to_ascii_lowercase();
@@ -208,6 +421,25 @@ impl SettingService {
         Ok(self.definition_default_bool(key))
     }
 
+    async fn get_number_setting(&self, key: &str) -> Result<f64, PipelineError> {
+        let owned_key = key.to_string();
+        let entry = self.repository.get(&owned_key).await.map_err(|e| {
+            let msg = format!("Failed to load setting {}: {:?}", owned_key, e);
+            PipelineError::message(&msg)
+        })?;
+
+        if let Some(parsed) = entry.and_then(|stored| Self::parse_value(&stored.value)).and_then(|json| json.as_f64()) {
+            return Ok(parsed);
+        }
+
+        Ok(self
+            .definitions
+            .iter()
+            .find(|def| def.key == key)
+            .and_then(|def| def.default_value.as_f64())
+            .unwrap_or_default())
+    }
+
     fn definition_default_bool(&self, key: &str) -> bool {
         self.definitions.iter().find(|def| def.key == key).and_then(|def| def.default_value.as_bool()).unwrap_or(false)
     }
@@ -429,7 +661,7 @@ impl SettingService {
             SettingDefinition {
                 key: SettingKeys::SECURITY_ROLE_PERMISSIONS,
                 label: "Role permissions",
-                description: "JSON map for role-based actions. Actions: dashboard.access, settings.general.update, photos.upload, comments.create.",
+                description: "JSON map for role-based actions. Actions: dashboard.access, settings.general.update, photos.upload, photos.tag, photos.edit, comments.create.",
                 section: SettingSection::Security,
                 group: SettingSection::Security.slug(),
                 value_type: SettingValueType::Json,
@@ -439,17 +671,41 @@ impl SettingService {
                         "dashboard.access": true,
                         "settings.general.update": true,
                         "photos.upload": true,
+                        "photos.tag": true,
+                        "photos.edit": true,
                         "comments.create": true
                     },
                     "viewer": {
                         "dashboard.access": false,
                         "settings.general.update": false,
                         "photos.upload": false,
+                        "photos.tag": false,
+                        "photos.edit": false,
                         "comments.create": false
                     }
                 }),
                 options: None,
             },
+            SettingDefinition {
+                key: SettingKeys::SECURITY_REQUIRE_EMAIL_VERIFICATION,
+                label: "Require email verification",
+                description: "Accounts must confirm their email address before they can sign in. The admin created during setup is verified automatically.",
+                section: SettingSection::Security,
+                group: SettingSection::Security.slug(),
+                value_type: SettingValueType::Boolean,
+                default_value: json!(false),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::SECURITY_TOKENS_BEFORE_VERIFICATION,
+                label: "Sign in right after registering",
+                description: "When verification is required, still sign new accounts in from the register screen so they can look around until that session ends. Turn off to make them verify first.",
+                section: SettingSection::Security,
+                group: SettingSection::Security.slug(),
+                value_type: SettingValueType::Boolean,
+                default_value: json!(true),
+                options: None,
+            },
             SettingDefinition {
                 key: SettingKeys::PHOTO_MANAGE_UPLOADS_ENABLED,
                 label: "Upload photos",
@@ -470,6 +726,39 @@ impl SettingService {
                 default_value: json!([]),
                 options: None,
             },
+            SettingDefinition {
+                key: SettingKeys::PHOTO_MANAGE_SYNC_UPLOAD_MAX_FILES,
+                label: "Synchronous upload limit",
+                description: "Uploads with ?sync=true and at most this many files are processed before responding.",
+                section: SettingSection::PhotoManage,
+                group: SettingSection::PhotoManage.slug(),
+                value_type: SettingValueType::Number,
+                default_value: json!(10),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PHOTO_MANAGE_SYNC_UPLOAD_TIMEOUT_SECONDS,
+                label: "Synchronous upload timeout",
+                description: "Seconds a synchronous upload may spend processing; remaining files continue in the background.",
+                section: SettingSection::PhotoManage,
+                group: SettingSection::PhotoManage.slug(),
+                value_type: SettingValueType::Number,
+                default_value: json!(30),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PHOTO_MANAGE_STACK_REPRESENTATIVE,
+                label: "Stack cover",
+                description: "Which file of a RAW+JPEG pair is shown in photo listings and the timeline.",
+                section: SettingSection::PhotoManage,
+                group: "stacking",
+                value_type: SettingValueType::String,
+                default_value: json!("jpeg"),
+                options: Some(vec![
+                    SettingOption { label: "JPEG", value: json!("jpeg") },
+                    SettingOption { label: "RAW", value: json!("raw") },
+                ]),
+            },
             SettingDefinition {
                 key: SettingKeys::CLIENT_APPROVAL_POLICY,
                 label: "Client approval policy",
@@ -517,10 +806,127 @@ impl SettingService {
                 default_value: json!(true),
                 options: None,
             },
+            SettingDefinition {
+                key: SettingKeys::PREVIEW_MAX_DIMENSION,
+                label: "Preview size",
+                description: "Longest edge of generated previews in pixels (256-8192).",
+                section: SettingSection::PhotoManage,
+                group: "derivatives",
+                value_type: SettingValueType::Number,
+                default_value: json!(1920),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PREVIEW_JPEG_QUALITY,
+                label: "Preview quality",
+                description: "JPEG quality of generated previews (1-100).",
+                section: SettingSection::PhotoManage,
+                group: "derivatives",
+                value_type: SettingValueType::Number,
+                default_value: json!(75),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PREVIEW_PREGENERATE_SCOPE,
+                label: "Pre-generate previews",
+                description: "Nightly, generate missing previews so the first open of a photo is fast. Recent covers photos added in the last 30 days.",
+                section: SettingSection::PhotoManage,
+                group: "derivatives",
+                value_type: SettingValueType::String,
+                default_value: json!("recent"),
+                options: Some(vec![
+                    SettingOption { label: "None", value: json!("none") },
+                    SettingOption { label: "Recent", value: json!("recent") },
+                    SettingOption { label: "All", value: json!("all") },
+                ]),
+            },
+            SettingDefinition {
+                key: SettingKeys::PREVIEW_PREGENERATE_MAX_PER_RUN,
+                label: "Previews per night",
+                description: "Most previews the nightly run generates; the rest wait for the next night.",
+                section: SettingSection::PhotoManage,
+                group: "derivatives",
+                value_type: SettingValueType::Number,
+                default_value: json!(PreviewWarmerStatus::DEFAULT_MAX_PER_RUN),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PREVIEW_CACHE_MAX_BYTES,
+                label: "Preview cache size",
+                description: "JSON map from storage id to the most bytes its .previews folder may hold; \"*\" covers storages without an entry and 0 means unlimited. The least recently viewed previews are removed first and regenerated on demand.",
+                section: SettingSection::PhotoManage,
+                group: "derivatives",
+                value_type: SettingValueType::Json,
+                default_value: json!({}),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::THUMBNAIL_MAX_DIMENSION,
+                label: "Thumbnail size",
+                description: "Longest edge of generated thumbnails in pixels (64-1024).",
+                section: SettingSection::PhotoManage,
+                group: "derivatives",
+                value_type: SettingValueType::Number,
+                default_value: json!(400),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::THUMBNAIL_FORMAT,
+                label: "Thumbnail format",
+                description: "Image format of generated thumbnails.",
+                section: SettingSection::PhotoManage,
+                group: "derivatives",
+                value_type: SettingValueType::String,
+                default_value: json!("webp"),
+                options: Some(vec![
+                    SettingOption { label: "WebP", value: json!("webp") },
+                    SettingOption { label: "JPEG", value: json!("jpeg") },
+                ]),
+            },
+            SettingDefinition {
+                key: SettingKeys::THUMBNAIL_ENABLE_AVIF,
+                label: "AVIF thumbnails",
+                description: "Also generate AVIF thumbnails in the background and serve them to browsers that accept AVIF.",
+                section: SettingSection::PhotoManage,
+                group: "derivatives",
+                value_type: SettingValueType::Boolean,
+                default_value: json!(false),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PREVIEW_WATERMARK_ENABLED,
+                label: "Watermark public previews",
+                description: "Overlay a watermark on previews served to visitors who are not signed in.",
+                section: SettingSection::Experience,
+                group: "watermark",
+                value_type: SettingValueType::Boolean,
+                default_value: json!(false),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PREVIEW_WATERMARK_IMAGE_PATH,
+                label: "Watermark image",
+                description: "Path to a PNG watermark on the server; transparent areas stay transparent.",
+                section: SettingSection::Experience,
+                group: "watermark",
+                value_type: SettingValueType::String,
+                default_value: json!(""),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PREVIEW_WATERMARK_OPACITY,
+                label: "Watermark opacity",
+                description: "Watermark opacity between 0 and 1.",
+                section: SettingSection::Experience,
+                group: "watermark",
+                value_type: SettingValueType::Number,
+                default_value: json!(0.35),
+                options: None,
+            },
             SettingDefinition {
                 key: SettingKeys::NOTIFICATIONS_EMAIL_SUMMARY,
                 label: "Email summaries",
-                description: "Send a weekly recap of new photos",
+                description: "Email a daily digest of new photos, comments and albums to users who opt in",
                 section: SettingSection::Notifications,
                 group: SettingSection::Notifications.slug(),
                 value_type: SettingValueType::Boolean,
@@ -537,6 +943,132 @@ impl SettingService {
                 default_value: json!(18),
                 options: None,
             },
+            SettingDefinition {
+                key: SettingKeys::CORS_ALLOWED_ORIGINS,
+                label: "Allowed origins",
+                description: "Origins allowed to call the API from a browser, e.g. https://photos.example.com. Use * for any origin. When empty, development allows every origin and production allows none.",
+                section: SettingSection::Security,
+                group: "cors",
+                value_type: SettingValueType::Json,
+                default_value: json!([]),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::CORS_ALLOW_CREDENTIALS,
+                label: "Allow credentials",
+                description: "Let allowed origins send cookies and authorization headers.",
+                section: SettingSection::Security,
+                group: "cors",
+                value_type: SettingValueType::Boolean,
+                default_value: json!(false),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::CORS_ALLOWED_HEADERS,
+                label: "Allowed headers",
+                description: "Request headers allowed on cross-origin calls.",
+                section: SettingSection::Security,
+                group: "cors",
+                value_type: SettingValueType::Json,
+                default_value: json!(["authorization", "content-type", "x-request-id", "x-session-id"]),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::CORS_MAX_AGE_SECONDS,
+                label: "Preflight max age",
+                description: "Seconds browsers may cache a preflight response.",
+                section: SettingSection::Security,
+                group: "cors",
+                value_type: SettingValueType::Number,
+                default_value: json!(600),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::CORS_STRICT,
+                label: "Reject unlisted origins",
+                description: "Reject cross-origin requests from origins that are not allowed instead of only omitting the CORS headers.",
+                section: SettingSection::Security,
+                group: "cors",
+                value_type: SettingValueType::Boolean,
+                default_value: json!(false),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::ASSETS_WEB_ROOT,
+                label: "Web root",
+                description: "Directory with the built web frontend. Leave empty to serve only the API.",
+                section: SettingSection::Security,
+                group: "assets",
+                value_type: SettingValueType::String,
+                default_value: json!("www"),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PRIVACY_STRIP_GPS_ON_DOWNLOAD,
+                label: "Strip GPS on download",
+                description: "Remove location data from original files downloaded by anyone other than an admin.",
+                section: SettingSection::Security,
+                group: "privacy",
+                value_type: SettingValueType::Boolean,
+                default_value: json!(true),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::PRIVACY_COORDINATE_PRECISION_FOR_VIEWERS,
+                label: "Location precision for viewers",
+                description: "Round GPS coordinates shown to anyone other than an admin, on the map and in photo details. Two decimal places is about 1 km.",
+                section: SettingSection::Security,
+                group: "privacy",
+                value_type: SettingValueType::String,
+                default_value: json!(CoordinatePrecision::EXACT),
+                options: Some(vec![
+                    SettingOption { label: "Exact", value: json!(CoordinatePrecision::EXACT) },
+                    SettingOption { label: "About 10 m", value: json!("4") },
+                    SettingOption { label: "About 100 m", value: json!("3") },
+                    SettingOption { label: "About 1 km", value: json!("2") },
+                    SettingOption { label: "About 10 km", value: json!("1") },
+                ]),
+            },
+            SettingDefinition {
+                key: SettingKeys::XMP_WRITE_BACK,
+                label: "Write XMP sidecars",
+                description: "Update or create the .xmp sidecar next to a photo when its tags or caption change, so Lightroom and digiKam see the edits.",
+                section: SettingSection::Security,
+                group: "sidecars",
+                value_type: SettingValueType::Boolean,
+                default_value: json!(false),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::ML_ANALYZER_URL,
+                label: "Image analyzer URL",
+                description: "External inference endpoint that receives each new preview as image/jpeg and answers with suggested tags. Leave empty to disable suggestions.",
+                section: SettingSection::PhotoManage,
+                group: "ml",
+                value_type: SettingValueType::String,
+                default_value: json!(""),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::ML_ANALYZER_TIMEOUT_SECONDS,
+                label: "Image analyzer timeout",
+                description: "Seconds to wait for the image analyzer before giving up on a photo.",
+                section: SettingSection::PhotoManage,
+                group: "ml",
+                value_type: SettingValueType::Number,
+                default_value: json!(ImageAnalyzerConfig::DEFAULT_TIMEOUT_SECONDS),
+                options: None,
+            },
+            SettingDefinition {
+                key: SettingKeys::ML_MIN_CONFIDENCE,
+                label: "Minimum suggestion confidence",
+                description: "Suggestions scored below this value (0 to 1) are discarded.",
+                section: SettingSection::PhotoManage,
+                group: "ml",
+                value_type: SettingValueType::Number,
+                default_value: json!(ImageAnalyzerConfig::DEFAULT_MIN_CONFIDENCE),
+                options: None,
+            },
         ]
     }
 
@@ -0,0 +1,187 @@
+use crate::prelude::*;
+use std::fs;
+use tokio::sync::Mutex as AsyncMutex;
+
+#[derive(Debug)]
+pub enum SetupError {
+    AlreadyInitialized,
+    Invalid(PipelineError),
+    Failed(PipelineError),
+}
+
+struct PreparedStorage {
+    location: StorageLocation,
+    created_dir: Option<PathBuf>,
+}
+
+pub struct SetupService {
+    auth: Arc<AuthService>,
+    users: Arc<Repository<User>>,
+    user_settings: Arc<Repository<UserSettings>>,
+    storages: Arc<Repository<StorageLocation>>,
+    settings: Arc<SettingService>,
+    jwt_secret_configured: bool,
+    lock: AsyncMutex<()>,
+}
+
+impl SetupService {
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        let secrets = services.get::<StartupSecrets>();
+        Self {
+            auth: services.get::<AuthService>(),
+            users: services.get::<Repository<User>>(),
+            user_settings: services.get::<Repository<UserSettings>>(),
+            storages: services.get::<Repository<StorageLocation>>(),
+            settings: services.get::<SettingService>(),
+            jwt_secret_configured: secrets.jwt_source == SecretSource::Configured,
+            lock: AsyncMutex::new(()),
+        }
+    }
+
+    pub async fn status(&self) -> Result<SetupStatusDto, PipelineError> {
+        let has_admin = self.auth.has_admin_user().await?;
+        let has_storage = !self.storages.is_empty().await?;
+        Ok(SetupStatusDto::new(has_admin, has_storage, self.jwt_secret_configured))
+    }
+
+    pub async fn initialize(
+        &self,
+        request: SetupInitializeRequest,
+        client: &SessionClient,
+    ) -> Result<SetupInitializeResponse, SetupError> {
+        let _guard = self.lock.lock().await;
+        if self.auth.has_admin_user().await.map_err(SetupError::Failed)? {
+            return Err(SetupError::AlreadyInitialized);
+        }
+
+        Self::validate_admin(&request.admin).map_err(SetupError::Invalid)?;
+        let core_settings = self.core_settings(&request.settings).map_err(SetupError::Invalid)?;
+        let storage = self.prepare_storage(&request.storage).await.map_err(SetupError::Invalid)?;
+
+        let storage_id = storage.location.id;
+        if let Err(err) = self.storages.insert(storage.location.clone()).await {
+            Self::remove_created_dir(&storage);
+            return Err(SetupError::Failed(PipelineError::message(&format!("failed to save storage: {:?}", err))));
+        }
+
+        let admin = &request.admin;
+        let user_id = match self
+            .auth
+            .create_user(admin.email.trim(), &admin.password, admin.display_name.trim(), "admin")
+            .await
+        {
+            Ok(user_id) => user_id,
+            Err(err) => {
+                self.rollback(None, &storage).await;
+                return Err(SetupError::Failed(err));
+            }
+        };
+
+        for (key, value) in core_settings {
+            if let Err(err) = self.settings.update(key, value).await {
+                self.rollback(Some(user_id), &storage).await;
+                return Err(SetupError::Failed(err));
+            }
+        }
+
+        let tokens = self.auth.issue_tokens(user_id, client).await.map_err(SetupError::Failed)?;
+        log::info!("Setup completed: admin {} and storage {} created.", user_id, storage_id);
+        Ok(SetupInitializeResponse { user_id, storage_id, tokens })
+    }
+
+    fn validate_admin(admin: &SetupAdminRequest) -> Result<(), PipelineError> {
+        let email = admin.email.trim().should_not_empty("Email")?;
+        if !email.contains('@') {
+            return Err(PipelineError::message("Email is invalid"));
+        }
+        admin.display_name.trim().should_not_empty("Display name")?;
+        admin.password.as_str().should_not_empty("Password")?;
+        if admin.password != admin.confirm_password {
+            return Err(PipelineError::message("Passwords do not match"));
+        }
+        Ok(())
+    }
+
+    fn core_settings(&self, request: &SetupSettingsRequest) -> Result<Vec<(&'static str, JsonValue)>, PipelineError> {
+        let mut values = vec![(SettingKeys::SITE_INITIALIZED, json!(true))];
+        if let Some(title) = request.site_title.as_deref().map(str::trim).filter(|title| !title.is_empty()) {
+            values.push((SettingKeys::SITE_TITLE, json!(title)));
+        }
+        if let Some(public) = request.site_public {
+            values.push((SettingKeys::SITE_PUBLIC, json!(public)));
+        }
+        if let Some(allow) = request.allow_registration {
+            values.push((SettingKeys::SITE_ALLOW_REGISTRATION, json!(allow)));
+        }
+
+        for (key, value) in &values {
+            self.settings.validate(key, value)?;
+        }
+        Ok(values)
+    }
+
+    async fn prepare_storage(&self, request: &SetupStorageRequest) -> Result<PreparedStorage, PipelineError> {
+        let label = request.label.trim().should_not_empty("Storage label")?;
+        let path = PathBuf::from(request.path.trim().should_not_empty("Storage path")?);
+        if !path.is_absolute() {
+            return Err(PipelineError::message("Storage path must be absolute"));
+        }
+
+        let path_value = path.to_string_lossy().to_string();
+        if self.storages.exists_by_path(&path_value).await? {
+            return Err(PipelineError::message("Storage path already registered"));
+        }
+
+        let created_dir = if path.exists() {
+            if !path.is_dir() {
+                return Err(PipelineError::message("Storage path is not a directory"));
+            }
+            None
+        } else {
+            fs::create_dir_all(&path).map_err(|err| {
+                PipelineError::message(&format!("Failed to create storage path '{}': {}", path.display(), err))
+            })?;
+            Some(path.clone())
+        };
+
+        let location = StorageLocation {
+            id: Uuid::new_v4(),
+            label: label.to_string(),
+            path: path_value,
+            is_default: true,
+            is_readonly: false,
+            created_at: Utc::now().to_rfc3339(),
+            category_template: request
+                .category_template
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .unwrap_or("{year}/{date:%Y-%m-%d}/{fileName}")
+                .to_string(),
+        };
+        Ok(PreparedStorage { location, created_dir })
+    }
+
+    async fn rollback(&self, user_id: Option<Uuid>, storage: &PreparedStorage) {
+        if let Some(user_id) = user_id {
+            if let Err(err) = self.user_settings.delete(&user_id).await {
+                log::error!("Setup rollback failed to delete settings for {}: {:?}", user_id, err);
+            }
+            if let Err(err) = self.users.delete(&user_id).await {
+                log::error!("Setup rollback failed to delete user {}: {:?}", user_id, err);
+            }
+        }
+        if let Err(err) = self.storages.delete(&storage.location.id).await {
+            log::error!("Setup rollback failed to delete storage {}: {:?}", storage.location.id, err);
+        }
+        Self::remove_created_dir(storage);
+    }
+
+    fn remove_created_dir(storage: &PreparedStorage) {
+        if let Some(dir) = storage.created_dir.as_ref() {
+            if let Err(err) = fs::remove_dir(dir) {
+                log::warn!("Setup rollback could not remove {}: {}", dir.display(), err);
+            }
+        }
+    }
+}
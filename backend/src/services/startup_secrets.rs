@@ -0,0 +1,155 @@
+use crate::prelude::*;
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use rand::RngExt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretSource {
+    Configured,
+    Generated,
+    Default,
+}
+
+impl SecretSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Configured => "configured",
+            Self::Generated => "generated",
+            Self::Default => "default",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeneratedSecrets {
+    jwt_secret: Option<String>,
+    encryption_key: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StartupSecrets {
+    pub environment: AppEnvironment,
+    pub jwt_secret: String,
+    pub jwt_source: SecretSource,
+    pub encryption_key: String,
+    pub encryption_source: SecretSource,
+}
+
+impl StartupSecrets {
+    pub const DEFAULT_JWT_SECRETS: [&'static str; 2] = ["super-secret-key-123", "your_jwt_secret_key_here"];
+    pub const DEFAULT_ENCRYPTION_KEY: &'static str = "FMxHF3veLLoH25I7Hr9IOenHDKZwj6hcEYeQzTFww9s=";
+
+    pub fn from_config(config: &AppConfig) -> Result<Self> {
+        Self::resolve(
+            config.environment,
+            config.jwt_secret.as_deref(),
+            config.encryption_key.as_deref(),
+            &config.secrets_file,
+        )
+    }
+
+    pub fn resolve(
+        environment: AppEnvironment,
+        jwt_secret: Option<&str>,
+        encryption_key: Option<&str>,
+        secrets_file: &Path,
+    ) -> Result<Self> {
+        let jwt_secret = jwt_secret.map(str::trim).filter(|value| !value.is_empty());
+        let encryption_key = encryption_key.map(str::trim).filter(|value| !value.is_empty());
+        let jwt_is_default = jwt_secret.is_none_or(Self::is_default_jwt_secret);
+        let encryption_is_default = encryption_key.is_none_or(|key| key == Self::DEFAULT_ENCRYPTION_KEY);
+
+        if environment == AppEnvironment::Production {
+            let mut missing = Vec::new();
+            if jwt_is_default {
+                missing.push("jwt.secret");
+            }
+            if encryption_is_default {
+                missing.push("encryption.key");
+            }
+            if !missing.is_empty() {
+                return Err(anyhow!(
+                    "{} must be set to a non-default value when app.environment is production",
+                    missing.join(" and ")
+                ));
+            }
+        }
+
+        let needs_generated = jwt_is_default || encryption_key.is_none();
+        let mut generated =
+            if needs_generated { Self::load_generated(secrets_file)? } else { GeneratedSecrets::default() };
+        let mut dirty = false;
+
+        let (jwt_secret, jwt_source) = match jwt_secret {
+            Some(secret) if !jwt_is_default => (secret.to_string(), SecretSource::Configured),
+            _ => {
+                let secret = generated.jwt_secret.get_or_insert_with(|| {
+                    dirty = true;
+                    Self::random_secret()
+                });
+                (secret.clone(), SecretSource::Generated)
+            }
+        };
+
+        // An existing default key is kept in development because stored password hashes depend on it.
+        let (encryption_key, encryption_source) = match encryption_key {
+            Some(key) if key == Self::DEFAULT_ENCRYPTION_KEY => (key.to_string(), SecretSource::Default),
+            Some(key) => (key.to_string(), SecretSource::Configured),
+            None => {
+                let key = generated.encryption_key.get_or_insert_with(|| {
+                    dirty = true;
+                    Self::random_secret()
+                });
+                (key.clone(), SecretSource::Generated)
+            }
+        };
+
+        if dirty {
+            Self::save_generated(secrets_file, &generated)?;
+        }
+
+        Ok(Self { environment, jwt_secret, jwt_source, encryption_key, encryption_source })
+    }
+
+    pub fn is_default_jwt_secret(secret: &str) -> bool {
+        Self::DEFAULT_JWT_SECRETS.contains(&secret.trim())
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "environment={}, jwt.secret={}, encryption.key={}",
+            self.environment.as_str(),
+            self.jwt_source.as_str(),
+            self.encryption_source.as_str()
+        )
+    }
+
+    fn load_generated(path: &Path) -> Result<GeneratedSecrets> {
+        if !path.exists() {
+            return Ok(GeneratedSecrets::default());
+        }
+        let raw = fs::read_to_string(path).map_err(|err| anyhow!("failed to read {}: {}", path.display(), err))?;
+        serde_json::from_str(&raw).map_err(|err| anyhow!("failed to parse {}: {}", path.display(), err))
+    }
+
+    fn save_generated(path: &Path, secrets: &GeneratedSecrets) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|err| anyhow!("failed to create {}: {}", parent.display(), err))?;
+        }
+        let raw = serde_json::to_string_pretty(secrets)?;
+        fs::write(path, raw).map_err(|err| anyhow!("failed to write {}: {}", path.display(), err))?;
+        log::warn!("Generated development secrets stored in {}", path.display());
+        Ok(())
+    }
+
+    fn random_secret() -> String {
+        let mut bytes = [0u8; 32];
+        rand::rng().fill(&mut bytes);
+        STANDARD.encode(bytes)
+    }
+}
@@ -0,0 +1,71 @@
+use crate::prelude::*;
+use std::sync::RwLock;
+
+type StorageRoots = Arc<Vec<(Uuid, PathBuf)>>;
+
+#[derive(Default)]
+struct CacheState {
+    roots: Option<StorageRoots>,
+    generation: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct StoragePathCache {
+    state: Arc<RwLock<CacheState>>,
+}
+
+impl StoragePathCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn root(
+        &self,
+        repository: &Repository<StorageLocation>,
+        storage_id: Uuid,
+    ) -> Result<Option<PathBuf>, PipelineError> {
+        let roots = self.load(repository).await?;
+        Ok(roots.iter().find(|(id, _)| *id == storage_id).map(|(_, root)| root.clone()))
+    }
+
+    pub async fn roots(&self, repository: &Repository<StorageLocation>) -> Result<Vec<PathBuf>, PipelineError> {
+        let roots = self.load(repository).await?;
+        Ok(roots.iter().map(|(_, root)| root.clone()).collect())
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.state.read().map(|state| state.roots.is_some()).unwrap_or(false)
+    }
+
+    pub fn invalidate(&self) {
+        if let Ok(mut state) = self.state.write() {
+            state.roots = None;
+            state.generation = state.generation.wrapping_add(1);
+        }
+    }
+
+    async fn load(&self, repository: &Repository<StorageLocation>) -> Result<StorageRoots, PipelineError> {
+        let generation = {
+            let state = self.state.read().map_err(|_| PipelineError::message("storage path cache poisoned"))?;
+            if let Some(roots) = state.roots.as_ref() {
+                return Ok(Arc::clone(roots));
+            }
+            state.generation
+        };
+
+        let locations = repository
+            .all(Query::<StorageLocation>::new())
+            .await
+            .map_err(|_| PipelineError::message("failed to load storage settings"))?;
+        let roots: StorageRoots =
+            Arc::new(locations.iter().map(|location| (location.id, location.normalized_path())).collect());
+
+        if let Ok(mut state) = self.state.write() {
+            if state.generation == generation {
+                state.roots = Some(Arc::clone(&roots));
+            }
+        }
+
+        Ok(roots)
+    }
+}
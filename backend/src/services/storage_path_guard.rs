@@ -0,0 +1,34 @@
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StoragePathGuard {
+    allow_symlink_escape: bool,
+}
+
+impl StoragePathGuard {
+    pub fn new(allow_symlink_escape: bool) -> Self {
+        Self { allow_symlink_escape }
+    }
+
+    pub fn resolve(&self, root: &Path, relative: &str) -> Result<PathBuf, BrowsePathError> {
+        let segments = BrowsePath::segments(relative)?;
+        self.resolve_segments(root, &segments)
+    }
+
+    pub fn resolve_segments(&self, root: &Path, segments: &[String]) -> Result<PathBuf, BrowsePathError> {
+        let canonical_root = root.canonicalize().map_err(|_| BrowsePathError::NotFound)?;
+        let mut candidate = canonical_root.clone();
+        for segment in segments {
+            if segment == "." || segment == ".." || Path::new(segment).components().count() != 1 {
+                return Err(BrowsePathError::Traversal);
+            }
+            candidate.push(segment);
+        }
+
+        let resolved = candidate.canonicalize().map_err(|_| BrowsePathError::NotFound)?;
+        if !resolved.starts_with(&canonical_root) && !self.allow_symlink_escape {
+            return Err(BrowsePathError::OutsideRoot);
+        }
+        Ok(resolved)
+    }
+}
@@ -7,6 +7,7 @@ pub struct StorageService {
     photo_repo: Arc<Repository<Photo>>,
     file_service: Arc<FileService>,
     image_pipeline: Arc<ImageProcessPipeline>,
+    services: Arc<ServiceProvider>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -17,6 +18,10 @@ pub struct ScanStorageResponse {
     pub generated_thumbnail_count: usize,
     pub generated_preview_count: usize,
     pub skipped_count: usize,
+    // Derivatives built with different size, quality or format settings than the current ones.
+    pub stale_thumbnail_count: usize,
+    pub stale_preview_count: usize,
+    pub queued_avif_count: usize,
 }
 
 impl StorageService {
@@ -26,10 +31,11 @@ impl StorageService {
             photo_repo: services.get::<Repository<Photo>>(),
             file_service: services.get::<FileService>(),
             image_pipeline: services.get::<ImageProcessPipeline>(),
+            services,
         }
     }
 
-    pub async fn scan(&self, storage_id: Uuid) -> Result<ScanStorageResponse, PipelineError> {
+    pub async fn scan(&self, storage_id: Uuid, rebuild_stale: bool) -> Result<ScanStorageResponse, PipelineError> {
         let storage = self
             .storage_repo
             .get(&storage_id)
@@ -45,14 +51,23 @@ impl StorageService {
 
         log::info!("Starting scan for storage location with id: {}, {} photos found", storage_id, photos.len());
 
+        let profile = DerivativeProfileCache::resolve(&self.services).await;
+        let recorded_profiles = self.photo_repo.derivative_profiles(storage_id).await?;
+        let thumbnail_root = storage.normalized_path().join(SettingConsts::THUMBNAIL_FOLDER);
+        let preview_root = storage.normalized_path().join(SettingConsts::PREVIEW_FOLDER);
+
         let mut derivative_requests = Vec::<DerivativeProcessPayload>::new();
         let mut scanned_count = 0usize;
         let mut generated_thumbnail_count = 0usize;
         let mut generated_preview_count = 0usize;
         let mut skipped_count = 0usize;
+        let mut stale_thumbnail_count = 0usize;
+        let mut stale_preview_count = 0usize;
+        let mut queued_avif_count = 0usize;
+        let avif = if profile.thumbnail_avif { self.services.resolve::<AvifThumbnailService>() } else { None };
 
         for photo in photos {
-            let Some(hash) = photo.hash.as_deref().filter(|value| value.len() >= 4) else {
+            let Some(hash) = photo.hash.as_deref().filter(|value| HashService::is_valid(value)) else {
                 skipped_count += 1;
                 continue;
             };
@@ -64,19 +79,35 @@ impl StorageService {
                 continue;
             }
 
-            let thumbnail_path = self.file_service.path_for_hash(
-                storage.normalized_path().join(SettingConsts::THUMBNAIL_FOLDER),
-                hash,
-                SettingConsts::THUMBNAIL_FORMAT,
-            );
-            let preview_path = self.file_service.path_for_hash(
-                storage.normalized_path().join(SettingConsts::PREVIEW_FOLDER),
-                hash,
-                SettingConsts::PREVIEW_FORMAT,
-            );
-
-            let needs_thumbnail = !thumbnail_path.exists();
-            let needs_preview = !preview_path.exists();
+            let existing_thumbnail = profile
+                .thumbnail_format
+                .lookup_order()
+                .into_iter()
+                .map(|format| self.file_service.path_for_hash(&thumbnail_root, hash, format.extension()))
+                .find(|path| path.exists());
+            let has_thumbnail = existing_thumbnail.is_some();
+            let has_preview =
+                self.file_service.path_for_hash(&preview_root, hash, SettingConsts::PREVIEW_FORMAT).exists();
+
+            let recorded =
+                |kind: &str| recorded_profiles.get(&(hash.to_string(), kind.to_string())).map(String::as_str);
+            let stale_thumbnail = has_thumbnail
+                && profile.is_stale(DerivativeProfile::KIND_THUMBNAIL, recorded(DerivativeProfile::KIND_THUMBNAIL));
+            let stale_preview = has_preview
+                && profile.is_stale(DerivativeProfile::KIND_PREVIEW, recorded(DerivativeProfile::KIND_PREVIEW));
+            stale_thumbnail_count += stale_thumbnail as usize;
+            stale_preview_count += stale_preview as usize;
+
+            let needs_thumbnail = !has_thumbnail || (rebuild_stale && stale_thumbnail);
+            let needs_preview = !has_preview || (rebuild_stale && stale_preview);
+
+            // Regenerated thumbnails get their AVIF sibling from the thumbnail step instead.
+            if let (Some(avif), Some(existing), false) = (&avif, &existing_thumbnail, needs_thumbnail) {
+                let avif_path = existing.with_extension(ThumbnailFormat::Avif.extension());
+                if !avif_path.exists() && avif.schedule(existing.clone(), avif_path) {
+                    queued_avif_count += 1;
+                }
+            }
 
             if !needs_thumbnail && !needs_preview {
                 skipped_count += 1;
@@ -112,6 +143,9 @@ impl StorageService {
             generated_thumbnail_count,
             generated_preview_count,
             skipped_count,
+            stale_thumbnail_count,
+            stale_preview_count,
+            queued_avif_count,
         })
     }
 
@@ -5,6 +5,7 @@ use anyhow::{Result, anyhow};
 use bytes::Bytes;
 use futures_util::{StreamExt, TryStreamExt, stream};
 use serde_json::Value as JsonValue;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::fs::{self, File};
 use tokio::io::AsyncWriteExt;
 
@@ -13,7 +14,7 @@ pub struct SyncService {
     photo_repo: Arc<Repository<Photo>>,
     exif_repo: Arc<Repository<ExifModel>>,
     file_service: Arc<FileService>,
-    max_file_size: u64,
+    max_file_size: AtomicU64,
 }
 
 impl SyncService {
@@ -21,24 +22,25 @@ impl SyncService {
     const SYNC_FILE_FIELD_NAME: &'static str = "file";
     const FILES_FIELD_NAME: &'static str = "files";
     const UNKNOWN_FILE_BASENAME: &'static str = "upload";
-    const DEFAULT_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
 
     pub fn new(services: Arc<ServiceProvider>) -> Self {
-        let config = services.get::<Configuration>();
         Self {
             storage_repo: services.get::<Repository<StorageLocation>>(),
             photo_repo: services.get::<Repository<Photo>>(),
             exif_repo: services.get::<Repository<ExifModel>>(),
             file_service: services.get::<FileService>(),
-            max_file_size: config
-                .get("upload.max_file_size_bytes")
-                .or_else(|| config.get("upload.maxFileSizeBytes"))
-                .and_then(|value| value.parse::<u64>().ok())
-                .filter(|value| *value > 0)
-                .unwrap_or(Self::DEFAULT_MAX_FILE_SIZE),
+            max_file_size: AtomicU64::new(services.get::<AppConfig>().upload_max_file_size_bytes),
         }
     }
 
+    pub fn max_file_size(&self) -> u64 {
+        self.max_file_size.load(Ordering::SeqCst)
+    }
+
+    pub fn set_max_file_size(&self, max_file_size: u64) {
+        self.max_file_size.store(max_file_size, Ordering::SeqCst);
+    }
+
     pub async fn check_missing_files(&self, request: CheckFileRequest) -> Result<CheckFileResponse, PipelineError> {
         let storage_id =
             Uuid::parse_str(request.storage_id.trim()).map_err(|_| PipelineError::message("invalid storageId"))?;
@@ -195,12 +197,12 @@ impl SyncService {
             storage_id: item.storage_id.clone(),
             hash: item.hash.clone(),
             asset_kind: item.asset_kind.clone(),
-            file: UploadFileResponse {
-                file_name: photo.name.clone(),
-                relative_path: final_relative_path,
-                byte_size: saved_file.byte_size,
-                content_type: saved_file.content_type,
-            },
+            file: UploadFileResponse::accepted(
+                photo.name.clone(),
+                final_relative_path,
+                saved_file.byte_size,
+                saved_file.content_type,
+            ),
         })
     }
 
@@ -230,12 +232,12 @@ impl SyncService {
             storage_id: item.storage_id.clone(),
             hash: item.hash.clone(),
             asset_kind: item.asset_kind.clone(),
-            file: UploadFileResponse {
-                file_name: final_path.file_name().and_then(|value| value.to_str()).unwrap_or_default().to_string(),
-                relative_path: final_relative_path,
-                byte_size: saved_file.byte_size,
-                content_type: saved_file.content_type,
-            },
+            file: UploadFileResponse::accepted(
+                final_path.file_name().and_then(|value| value.to_str()).unwrap_or_default().to_string(),
+                final_relative_path,
+                saved_file.byte_size,
+                saved_file.content_type,
+            ),
         })
     }
 
@@ -332,16 +334,17 @@ impl SyncService {
     {
         let mut file = File::create_new(path).await?;
         let mut bytes_written = 0u64;
+        let max_file_size = self.max_file_size();
 
         while let Some(chunk) = stream.next().await {
             let chunk = chunk?;
             bytes_written =
                 bytes_written.checked_add(chunk.len() as u64).ok_or_else(|| anyhow!("uploaded file size overflow"))?;
 
-            if bytes_written > self.max_file_size {
+            if bytes_written > max_file_size {
                 drop(file);
                 let _ = fs::remove_file(path).await;
-                return Err(anyhow!("uploaded file exceeds max allowed size of {} bytes", self.max_file_size));
+                return Err(anyhow!("uploaded file exceeds max allowed size of {} bytes", max_file_size));
             }
 
             file.write_all(&chunk).await?;
@@ -470,7 +473,7 @@ impl SyncService {
         hash: &str,
         metadata: JsonValue,
     ) -> Result<ExifModel, PipelineError> {
-        let mut base_object = match existing {
+        let mut base_object = match &existing {
             Some(existing) => {
                 serde_json::to_value(existing).ok().and_then(|value| value.as_object().cloned()).unwrap_or_default()
             }
@@ -497,8 +500,12 @@ impl SyncService {
         base_object.insert("imageId".to_string(), json!(photo.id));
         base_object.insert("hash".to_string(), json!(hash));
 
-        serde_json::from_value(JsonValue::Object(base_object))
-            .map_err(|_| PipelineError::message("invalid metadata payload"))
+        let mut model: ExifModel = serde_json::from_value(JsonValue::Object(base_object))
+            .map_err(|_| PipelineError::message("invalid metadata payload"))?;
+        if let Some(existing) = &existing {
+            model.keep_edits_from(existing);
+        }
+        Ok(model)
     }
 
     fn apply_metadata_to_photo(&self, photo: &mut Photo, metadata: &ExifModel) {
@@ -515,7 +522,7 @@ impl SyncService {
         photo.aperture = metadata.get_aperture();
         photo.focal_length = metadata.focal_length;
         photo.label = metadata.label.clone();
-        photo.rating = metadata.rating;
+        photo.rating = PhotoRating::normalize(metadata.rating);
         photo.flagged = metadata.flagged;
         photo.width = metadata.get_width();
         photo.height = metadata.get_height();
@@ -523,10 +530,7 @@ impl SyncService {
 
         if let Some(date_taken) = metadata.get_date_taken() {
             photo.date_taken = Some(date_taken);
-            photo.year = Some(date_taken.year());
-            photo.month_day = Some(date_taken.format("%m-%d").to_string());
-            photo.day_date = date_taken.date_naive();
-            photo.sort_date = date_taken;
         }
+        photo.refresh_timeline_dates();
     }
 }
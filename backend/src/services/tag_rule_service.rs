@@ -0,0 +1,196 @@
+use crate::prelude::*;
+
+// A rule together with the tag it applies, resolved once per evaluation run.
+struct ResolvedRule {
+    rule: TagRule,
+    tag_ids: Vec<Uuid>,
+}
+
+pub struct TagRuleService {
+    rules: Arc<Repository<TagRule>>,
+    photos: Arc<Repository<Photo>>,
+    tags: Arc<Repository<Tag>>,
+    exifs: Arc<Repository<ExifModel>>,
+    progress: Arc<Mutex<TagRuleApplyProgress>>,
+}
+
+impl TagRuleService {
+    pub const TASK_NAME: &'static str = "apply-tag-rules";
+    const APPLY_BATCH_SIZE: u32 = 200;
+
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            rules: services.get::<Repository<TagRule>>(),
+            photos: services.get::<Repository<Photo>>(),
+            tags: services.get::<Repository<Tag>>(),
+            exifs: services.get::<Repository<ExifModel>>(),
+            progress: Arc::new(Mutex::new(TagRuleApplyProgress::default())),
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<TagRule>, PipelineError> {
+        let query = QueryBuilder::<TagRule>::new().sort_asc("created_at").build();
+        self.rules.all(query).await.map_err(|e| PipelineError::message(&format!("failed to load tag rules: {:?}", e)))
+    }
+
+    pub async fn create(
+        &self,
+        request: &CreateTagRuleRequest,
+        author: Option<Uuid>,
+        is_admin: bool,
+    ) -> Result<TagRule, PipelineError> {
+        let (field, operator) =
+            request.condition().ok_or_else(|| PipelineError::message("invalid tag rule condition"))?;
+        let rule = TagRule::new(field, operator, &request.value, &request.tag, author, is_admin);
+        self.rules.insert(rule).await.map_err(|e| PipelineError::message(&format!("failed to save tag rule: {:?}", e)))
+    }
+
+    pub async fn get(&self, rule_id: Uuid) -> Result<Option<TagRule>, PipelineError> {
+        self.rules.get(&rule_id).await.map_err(|e| PipelineError::message(&format!("failed to load tag rule: {:?}", e)))
+    }
+
+    pub async fn delete(&self, rule_id: Uuid) -> Result<(), PipelineError> {
+        self.rules
+            .delete(&rule_id)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to delete tag rule: {:?}", e)))?;
+        Ok(())
+    }
+
+    pub async fn is_admin_only_tag(&self, tag: &str) -> Result<bool, PipelineError> {
+        Ok(self.tags.admin_only_tag_names().await?.contains(&tag.trim().to_lowercase()))
+    }
+
+    // Runs at the end of ingestion; returns the names of the tags that were applied.
+    pub async fn apply_to_photo(&self, photo: &Photo, exif: Option<&ExifModel>) -> Result<Vec<String>, PipelineError> {
+        let matching = self.list().await?.into_iter().filter(|rule| rule.matches(photo, exif)).collect::<Vec<_>>();
+        if matching.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut applied = Vec::new();
+        for resolved in self.resolve(matching).await? {
+            self.photos.add_photo_tags_bulk(&[photo.id], &resolved.tag_ids).await?;
+            applied.push(resolved.rule.tag_name);
+        }
+        Ok(applied)
+    }
+
+    pub fn progress(&self) -> TagRuleApplyProgress {
+        self.progress.lock().map(|progress| progress.clone()).unwrap_or_default()
+    }
+
+    // Returns false when a run is already queued or in progress.
+    pub fn begin_apply(&self, task_id: Uuid) -> bool {
+        let Ok(mut progress) = self.progress.lock() else {
+            return false;
+        };
+        if progress.running {
+            return false;
+        }
+        *progress = TagRuleApplyProgress { task_id: Some(task_id), running: true, ..TagRuleApplyProgress::default() };
+        true
+    }
+
+    // Releases the run reserved by begin_apply when the task never reached the runner.
+    pub fn finish_unstarted(&self, error: &str) {
+        self.update_progress(|progress| {
+            progress.running = false;
+            progress.finished_at = Some(Utc::now());
+            progress.last_error = Some(error.to_string());
+        });
+    }
+
+    pub async fn apply_all(&self) -> Result<TagRuleApplyProgress, PipelineError> {
+        self.update_progress(|progress| progress.started_at = Some(Utc::now()));
+        let result = self.run_all().await;
+        self.update_progress(|progress| {
+            progress.running = false;
+            progress.finished_at = Some(Utc::now());
+            progress.last_error = result.as_ref().err().map(|error| format!("{:?}", error));
+        });
+        result.map(|_| self.progress())
+    }
+
+    async fn run_all(&self) -> Result<(), PipelineError> {
+        let rules = self.resolve(self.list().await?).await?;
+        if rules.is_empty() {
+            return Ok(());
+        }
+        let needs_exif = rules.iter().any(|resolved| resolved.rule.needs_exif());
+
+        let mut page = 1;
+        loop {
+            let query = QueryBuilder::<Photo>::new().sort_asc("id").page(page, Self::APPLY_BATCH_SIZE).build();
+            let batch = self
+                .photos
+                .query(query)
+                .await
+                .map_err(|e| PipelineError::message(&format!("failed to load photos: {:?}", e)))?;
+            if batch.items.is_empty() {
+                break;
+            }
+
+            let exifs = if needs_exif { self.exifs_for(&batch.items).await? } else { HashMap::new() };
+            let mut tagged = HashSet::new();
+            for resolved in &rules {
+                let photo_ids = batch
+                    .items
+                    .iter()
+                    .filter(|photo| resolved.rule.matches(photo, exifs.get(&photo.id)))
+                    .map(|photo| photo.id)
+                    .collect::<Vec<_>>();
+                if photo_ids.is_empty() {
+                    continue;
+                }
+                self.photos.add_photo_tags_bulk(&photo_ids, &resolved.tag_ids).await?;
+                tagged.extend(photo_ids);
+            }
+
+            let processed = batch.items.len() as u64;
+            self.update_progress(|progress| {
+                progress.total = batch.total;
+                progress.processed += processed;
+                progress.tagged += tagged.len() as u64;
+            });
+            page += 1;
+        }
+
+        Ok(())
+    }
+
+    // Drops rules whose tag became admin-only after a non-admin wrote them.
+    async fn resolve(&self, rules: Vec<TagRule>) -> Result<Vec<ResolvedRule>, PipelineError> {
+        let admin_only = self.tags.admin_only_tag_names().await?;
+        let mut resolved = Vec::with_capacity(rules.len());
+        for rule in rules {
+            if !rule.author_is_admin && admin_only.contains(&rule.tag_name.to_lowercase()) {
+                log::warn!("Skipping tag rule {}: '{}' is admin-only", rule.id, rule.tag_name);
+                continue;
+            }
+            let tag_ids =
+                self.tags.resolve_tag_ids(&[TagRef::Name(rule.tag_name.clone())], Tag::VISIBILITY_PUBLIC).await?;
+            if !tag_ids.is_empty() {
+                resolved.push(ResolvedRule { rule, tag_ids });
+            }
+        }
+        Ok(resolved)
+    }
+
+    async fn exifs_for(&self, photos: &[Photo]) -> Result<HashMap<Uuid, ExifModel>, PipelineError> {
+        let ids = photos.iter().map(|photo| Value::Uuid(photo.id)).collect::<Vec<_>>();
+        let query = QueryBuilder::<ExifModel>::new().filter("image_id", FilterOperator::In, Value::List(ids)).build();
+        let exifs = self
+            .exifs
+            .all(query)
+            .await
+            .map_err(|e| PipelineError::message(&format!("failed to load exif records: {:?}", e)))?;
+        Ok(exifs.into_iter().map(|exif| (exif.image_id, exif)).collect())
+    }
+
+    fn update_progress(&self, update: impl FnOnce(&mut TagRuleApplyProgress)) {
+        if let Ok(mut progress) = self.progress.lock() {
+            update(&mut progress);
+        }
+    }
+}
@@ -1,9 +1,16 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use std::future::Future;
 use std::pin::Pin;
+use std::str::FromStr;
+use uuid::Uuid;
 
 pub struct TaskDescriptor {
+    pub id: Uuid,
     pub name: String,
+    pub payload: Option<JsonValue>,
     task_future: Pin<Box<dyn Future<Output = Result<()>> + Send + 'static>>,
 }
 
@@ -12,10 +19,72 @@ impl TaskDescriptor {
     where
         F: Future<Output = Result<()>> + Send + 'static,
     {
-        Self { name: name.into(), task_future: Box::pin(task_future) }
+        Self { id: Uuid::new_v4(), name: name.into(), payload: None, task_future: Box::pin(task_future) }
+    }
+
+    pub fn with_payload(mut self, payload: JsonValue) -> Self {
+        self.payload = Some(payload);
+        self
     }
 
     pub async fn execute(self) -> Result<()> {
         self.task_future.await
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl TaskState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, TaskState::Completed | TaskState::Failed | TaskState::Cancelled)
+    }
+}
+
+impl FromStr for TaskState {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "queued" => Ok(TaskState::Queued),
+            "running" => Ok(TaskState::Running),
+            "completed" => Ok(TaskState::Completed),
+            "failed" => Ok(TaskState::Failed),
+            "cancelled" => Ok(TaskState::Cancelled),
+            other => Err(format!("unknown task state '{}'", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub state: TaskState,
+    pub enqueued_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl TaskInfo {
+    pub fn queued(task: &TaskDescriptor) -> Self {
+        Self {
+            id: task.id,
+            name: task.name.clone(),
+            state: TaskState::Queued,
+            enqueued_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+            last_error: None,
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 use anyhow::Result;
-use image::{ImageFormat, ImageReader, imageops::FilterType, load_from_memory};
+use image::{DynamicImage, ImageReader, imageops::FilterType, load_from_memory};
 use rawthumb::{ExportConfig, ThumbnailExporter};
 
 use super::image_process_constants::{RAW_EXTENSIONS, THUMBNAIL_FORMAT_EXTENSION};
@@ -10,11 +10,16 @@ const THUMBNAIL_MAX_BORDER: u32 = 400;
 #[derive(Clone, Debug)]
 pub struct ThumbnailExtractor {
     max_border: u32,
+    format: ThumbnailFormat,
 }
 
 impl ThumbnailExtractor {
     pub fn new() -> Self {
-        Self { max_border: THUMBNAIL_MAX_BORDER }
+        Self { max_border: THUMBNAIL_MAX_BORDER, format: ThumbnailFormat::Webp }
+    }
+
+    pub fn from_profile(profile: &DerivativeProfile) -> Self {
+        Self::new().with_max_border(profile.thumbnail_max_dimension).with_format(profile.thumbnail_format)
     }
 
     pub fn with_max_border(mut self, max_border: u32) -> Self {
@@ -22,6 +27,15 @@ impl ThumbnailExtractor {
         self
     }
 
+    pub fn with_format(mut self, format: ThumbnailFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn format(&self) -> ThumbnailFormat {
+        self.format
+    }
+
     pub fn extract_to<P: AsRef<Path>, Q: AsRef<Path>>(&self, input_path: P, output_path: Q) -> Result<PathBuf> {
         let destination = output_path.as_ref().to_path_buf();
         self.generate_to_file(input_path.as_ref(), &destination)?;
@@ -65,14 +79,21 @@ impl ThumbnailExtractor {
         let exporter = ThumbnailExporter::new_with_config(exporter_config);
         let thumbnail = exporter.export(input_path.to_string_lossy().as_ref())?;
         let image = load_from_memory(thumbnail.jpeg.as_ref())?;
-        image.save_with_format(output_path, ImageFormat::WebP)?;
-        Ok(())
+        self.save(image, output_path)
     }
 
     fn generate_standard_image(&self, input_path: &Path, output_path: &Path) -> Result<()> {
         let image = ImageReader::open(input_path)?.with_guessed_format()?.decode()?;
         let resized = image.resize(self.max_border, self.max_border, FilterType::Lanczos3);
-        resized.save_with_format(output_path, ImageFormat::WebP)?;
+        self.save(resized, output_path)
+    }
+
+    fn save(&self, image: DynamicImage, output_path: &Path) -> Result<()> {
+        let image = match self.format {
+            ThumbnailFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8()),
+            ThumbnailFormat::Webp => image,
+        };
+        image.save_with_format(output_path, self.format.image_format())?;
         Ok(())
     }
 }
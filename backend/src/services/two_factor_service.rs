@@ -0,0 +1,192 @@
+use crate::prelude::*;
+
+#[derive(Debug)]
+pub enum TwoFactorError {
+    AlreadyEnabled,
+    NotStarted,
+    InvalidCode,
+    InvalidChallenge,
+    LockedOut(DateTime<Utc>),
+    Failed(PipelineError),
+}
+
+struct PendingChallenge {
+    user_id: Uuid,
+    expires_at: DateTime<Utc>,
+}
+
+// TOTP sign-in. Challenges live in memory: they only bridge the few minutes between the password step
+// and the code step, and a restart just means signing in again.
+pub struct TwoFactorService {
+    repo: Arc<Repository<TwoFactor>>,
+    encrypt_service: EncryptService,
+    challenges: Mutex<HashMap<String, PendingChallenge>>,
+}
+
+impl TwoFactorService {
+    pub const CHALLENGE_TTL_SECONDS: i64 = 5 * 60;
+    // Six digits are easy to guess with unlimited tries. Misses are counted on the user's record, not the
+    // challenge, so signing in again with the password doesn't reset them.
+    pub const MAX_FAILED_CODES: i32 = 5;
+    pub const LOCKOUT_SECONDS: i64 = 15 * 60;
+    const MAX_PENDING_CHALLENGES: usize = 1_000;
+
+    pub fn new(repo: Arc<Repository<TwoFactor>>, encrypt_service: EncryptService) -> Self {
+        Self { repo, encrypt_service, challenges: Mutex::new(HashMap::new()) }
+    }
+
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool, PipelineError> {
+        Ok(self.find(user_id).await?.is_some_and(|record| record.enabled))
+    }
+
+    // Every call starts over with a fresh secret, replacing a setup that was never confirmed.
+    pub async fn setup(
+        &self,
+        user_id: Uuid,
+        issuer: &str,
+        account: &str,
+    ) -> Result<TwoFactorSetupResponse, TwoFactorError> {
+        let existing = self.find(user_id).await.map_err(TwoFactorError::Failed)?;
+        if existing.as_ref().is_some_and(|record| record.enabled) {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+
+        let totp = Totp::generate();
+        let secret = totp.to_base32();
+        let encrypted = self
+            .encrypt_service
+            .encrypt(&secret)
+            .map_err(|e| TwoFactorError::Failed(PipelineError::message(&e.to_string())))?;
+        let record = TwoFactor::new(user_id, encrypted);
+        let saved = match existing {
+            Some(_) => self.repo.update(record).await.map(|_| ()),
+            None => self.repo.insert(record).await.map(|_| ()),
+        };
+        saved.map_err(|_| TwoFactorError::Failed(PipelineError::message("failed to save two-factor secret")))?;
+
+        Ok(TwoFactorSetupResponse { otpauth_uri: totp.provisioning_uri(issuer, account), secret })
+    }
+
+    // Turns two-factor sign-in on once the authenticator proves it has the secret; returns the backup codes.
+    pub async fn enable(&self, user_id: Uuid, code: &str) -> Result<Vec<String>, TwoFactorError> {
+        let mut record = self.find(user_id).await.map_err(TwoFactorError::Failed)?.ok_or(TwoFactorError::NotStarted)?;
+        if record.enabled {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+        let step = self.totp(&record)?.verify(code, Utc::now()).ok_or(TwoFactorError::InvalidCode)?;
+
+        let backup_codes = BackupCode::generate();
+        record.set_backup_codes(&backup_codes);
+        record.enabled = true;
+        record.enabled_at = Some(Utc::now());
+        record.last_used_step = step;
+        self.save(record).await?;
+        Ok(backup_codes)
+    }
+
+    pub async fn disable(&self, user_id: Uuid) -> Result<bool, PipelineError> {
+        self.forget_challenges(user_id);
+        let Some(record) = self.find(user_id).await? else {
+            return Ok(false);
+        };
+        self.repo.delete(&user_id).await.map_err(|_| PipelineError::message("failed to disable two-factor"))?;
+        Ok(record.enabled)
+    }
+
+    pub fn challenge(&self, user_id: Uuid) -> TwoFactorChallengeResponse {
+        let now = Utc::now();
+        let challenge_token = Uuid::new_v4().to_string();
+        let expires_at = now + Duration::seconds(Self::CHALLENGE_TTL_SECONDS);
+        if let Ok(mut challenges) = self.challenges.lock() {
+            // One live challenge per user, and a hard cap so password-only logins can't grow the map.
+            challenges.retain(|_, challenge| challenge.expires_at > now && challenge.user_id != user_id);
+            while challenges.len() >= Self::MAX_PENDING_CHALLENGES {
+                let oldest =
+                    challenges.iter().min_by_key(|(_, challenge)| challenge.expires_at).map(|(token, _)| token.clone());
+                match oldest {
+                    Some(token) => challenges.remove(&token),
+                    None => break,
+                };
+            }
+            challenges.insert(challenge_token.clone(), PendingChallenge { user_id, expires_at });
+        }
+        TwoFactorChallengeResponse { two_factor_required: true, challenge_token, expires_at }
+    }
+
+    // Accepts a current TOTP code or an unused backup code; returns the user to issue tokens for.
+    pub async fn complete(&self, challenge_token: &str, code: &str) -> Result<Uuid, TwoFactorError> {
+        let now = Utc::now();
+        let user_id = self.challenge_user(challenge_token, now)?;
+        let record = self
+            .find(user_id)
+            .await
+            .map_err(TwoFactorError::Failed)?
+            .filter(|record| record.enabled)
+            .ok_or(TwoFactorError::InvalidChallenge)?;
+        if let Some(locked_until) = record.locked_until.filter(|locked_until| *locked_until > now) {
+            return Err(TwoFactorError::LockedOut(locked_until));
+        }
+
+        let accepted = match self.totp(&record)?.verify(code, now) {
+            Some(step) => self.repo.claim_two_factor_step(user_id, step, now).await,
+            None => self.repo.consume_two_factor_backup_code(user_id, code, now).await,
+        }
+        .map_err(TwoFactorError::Failed)?;
+        if !accepted {
+            let locked_until = self
+                .repo
+                .record_failed_two_factor_code(user_id, now, Self::MAX_FAILED_CODES, Self::LOCKOUT_SECONDS)
+                .await
+                .map_err(TwoFactorError::Failed)?;
+            return match locked_until {
+                Some(locked_until) => {
+                    self.forget_challenges(user_id);
+                    Err(TwoFactorError::LockedOut(locked_until))
+                }
+                None => Err(TwoFactorError::InvalidCode),
+            };
+        }
+        self.forget_challenges(user_id);
+        Ok(user_id)
+    }
+
+    fn challenge_user(&self, challenge_token: &str, now: DateTime<Utc>) -> Result<Uuid, TwoFactorError> {
+        let mut challenges = self.challenges.lock().map_err(|_| TwoFactorError::InvalidChallenge)?;
+        let challenge_token = challenge_token.trim();
+        match challenges.get(challenge_token) {
+            Some(challenge) if challenge.expires_at > now => Ok(challenge.user_id),
+            Some(_) => {
+                challenges.remove(challenge_token);
+                Err(TwoFactorError::InvalidChallenge)
+            }
+            None => Err(TwoFactorError::InvalidChallenge),
+        }
+    }
+
+    fn forget_challenges(&self, user_id: Uuid) {
+        if let Ok(mut challenges) = self.challenges.lock() {
+            challenges.retain(|_, challenge| challenge.user_id != user_id);
+        }
+    }
+
+    fn totp(&self, record: &TwoFactor) -> Result<Totp, TwoFactorError> {
+        let secret = self
+            .encrypt_service
+            .decrypt(&record.secret)
+            .map_err(|e| TwoFactorError::Failed(PipelineError::message(&e.to_string())))?;
+        Totp::from_base32(&secret)
+            .ok_or_else(|| TwoFactorError::Failed(PipelineError::message("stored two-factor secret is invalid")))
+    }
+
+    async fn find(&self, user_id: Uuid) -> Result<Option<TwoFactor>, PipelineError> {
+        self.repo.get(&user_id).await.map_err(|_| PipelineError::message("data error"))
+    }
+
+    async fn save(&self, record: TwoFactor) -> Result<(), TwoFactorError> {
+        self.repo
+            .update(record)
+            .await
+            .map(|_| ())
+            .map_err(|_| TwoFactorError::Failed(PipelineError::message("failed to save two-factor settings")))
+    }
+}
@@ -0,0 +1,319 @@
+use crate::prelude::*;
+use crate::services::image_process_constants::ImageProcessKeys;
+use std::collections::BTreeSet;
+use std::io::SeekFrom;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use xxhash_rust::xxh3::xxh3_64;
+
+#[derive(Debug)]
+pub enum UploadSessionError {
+    NotFound,
+    Invalid(String),
+    Incomplete(Vec<u32>),
+    Failed(anyhow::Error),
+}
+
+impl From<std::io::Error> for UploadSessionError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Failed(error.into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadSession {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub storage_id: Uuid,
+    pub storage_path: PathBuf,
+    pub file_name: String,
+    pub content_type: Option<String>,
+    pub total_size: u64,
+    pub chunk_size: u64,
+    pub received: BTreeSet<u32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UploadSession {
+    pub fn chunk_count(&self) -> u32 {
+        self.total_size.div_ceil(self.chunk_size) as u32
+    }
+
+    pub fn chunk_len(&self, index: u32) -> Option<u64> {
+        if index >= self.chunk_count() {
+            return None;
+        }
+        let offset = index as u64 * self.chunk_size;
+        Some(self.chunk_size.min(self.total_size - offset))
+    }
+
+    pub fn missing_chunks(&self) -> Vec<u32> {
+        (0..self.chunk_count()).filter(|index| !self.received.contains(index)).collect()
+    }
+
+    pub fn data_path(&self) -> PathBuf {
+        UploadSessionService::sessions_folder(&self.storage_path).join(format!("{}.part", self.id))
+    }
+
+    pub fn expires_at(&self, ttl: std::time::Duration) -> DateTime<Utc> {
+        self.updated_at + Duration::from_std(ttl).unwrap_or(Duration::zero())
+    }
+}
+
+#[derive(Clone)]
+pub struct UploadSessionService {
+    sessions: Arc<Mutex<HashMap<Uuid, UploadSession>>>,
+    chunk_size: u64,
+    ttl: std::time::Duration,
+}
+
+impl UploadSessionService {
+    pub const SESSIONS_FOLDER_NAME: &'static str = "uploads";
+    const SWEEP_INTERVAL_SECONDS: u64 = 300;
+
+    pub fn new(chunk_size: u64, ttl: std::time::Duration) -> Self {
+        Self { sessions: Arc::new(Mutex::new(HashMap::new())), chunk_size: chunk_size.max(1), ttl }
+    }
+
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    pub fn ttl(&self) -> std::time::Duration {
+        self.ttl
+    }
+
+    pub fn sessions_folder(storage_path: &Path) -> PathBuf {
+        storage_path.join(PhotoUploadService::TEMP_FOLDER_NAME).join(Self::SESSIONS_FOLDER_NAME)
+    }
+
+    pub async fn create(
+        &self,
+        owner_id: Uuid,
+        storage: &StorageLocation,
+        file_name: &str,
+        total_size: u64,
+        content_type: Option<String>,
+        max_file_size: u64,
+    ) -> Result<UploadSession, UploadSessionError> {
+        if total_size == 0 {
+            return Err(UploadSessionError::Invalid("totalSize must be greater than zero".to_string()));
+        }
+        if total_size > max_file_size {
+            return Err(UploadSessionError::Invalid(format!(
+                "file exceeds max allowed size of {} bytes",
+                max_file_size
+            )));
+        }
+        if !ImageProcessKeys::is_supported_image(Path::new(file_name)) {
+            return Err(UploadSessionError::Invalid(PhotoUploadService::REJECT_UNSUPPORTED_TYPE.to_string()));
+        }
+
+        let now = Utc::now();
+        let session = UploadSession {
+            id: Uuid::new_v4(),
+            owner_id,
+            storage_id: storage.id,
+            storage_path: PathBuf::from(&storage.path),
+            file_name: file_name.to_string(),
+            content_type,
+            total_size,
+            chunk_size: self.chunk_size,
+            received: BTreeSet::new(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        fs::create_dir_all(Self::sessions_folder(&session.storage_path)).await?;
+        let file = fs::File::create_new(session.data_path()).await?;
+        file.set_len(total_size).await?;
+
+        self.lock().insert(session.id, session.clone());
+        Ok(session)
+    }
+
+    pub fn get(&self, id: Uuid, owner_id: Uuid) -> Result<UploadSession, UploadSessionError> {
+        self.lock().get(&id).filter(|session| session.owner_id == owner_id).cloned().ok_or(UploadSessionError::NotFound)
+    }
+
+    // Chunks land at their own offset, so they may arrive in any order and be retried safely.
+    pub async fn write_chunk(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        index: u32,
+        bytes: &[u8],
+        checksum: Option<&str>,
+    ) -> Result<UploadSession, UploadSessionError> {
+        let session = self.get(id, owner_id)?;
+        let Some(expected_len) = session.chunk_len(index) else {
+            return Err(UploadSessionError::Invalid(format!(
+                "chunk index {} is out of range (0..{})",
+                index,
+                session.chunk_count()
+            )));
+        };
+        if bytes.len() as u64 != expected_len {
+            return Err(UploadSessionError::Invalid(format!(
+                "chunk {} must be {} bytes, got {}",
+                index,
+                expected_len,
+                bytes.len()
+            )));
+        }
+        if let Some(checksum) = checksum.map(str::trim).filter(|value| !value.is_empty()) {
+            let actual = format!("{:016x}", xxh3_64(bytes));
+            if !actual.eq_ignore_ascii_case(checksum) {
+                return Err(UploadSessionError::Invalid(format!("chunk {} checksum mismatch", index)));
+            }
+        }
+
+        let mut file = OpenOptions::new().write(true).open(session.data_path()).await?;
+        file.seek(SeekFrom::Start(index as u64 * session.chunk_size)).await?;
+        file.write_all(bytes).await?;
+        file.flush().await?;
+
+        let mut sessions = self.lock();
+        let session = sessions.get_mut(&id).ok_or(UploadSessionError::NotFound)?;
+        session.received.insert(index);
+        session.updated_at = Utc::now();
+        Ok(session.clone())
+    }
+
+    // Removes the session and returns the assembled file path; the caller owns the file from here on.
+    pub async fn complete(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        expected_hash: Option<&str>,
+    ) -> Result<(UploadSession, PathBuf), UploadSessionError> {
+        let session = self.get(id, owner_id)?;
+        let missing = session.missing_chunks();
+        if !missing.is_empty() {
+            return Err(UploadSessionError::Incomplete(missing));
+        }
+
+        let path = session.data_path();
+        let size = fs::metadata(&path).await?.len();
+        if size != session.total_size {
+            return Err(UploadSessionError::Invalid(format!(
+                "assembled file is {} bytes, expected {}",
+                size, session.total_size
+            )));
+        }
+
+        if let Some(raw) = expected_hash.map(str::trim).filter(|value| !value.is_empty()) {
+            let expected = HashService::normalize(raw)
+                .ok_or_else(|| UploadSessionError::Invalid(format!("invalid hash: {}", raw)))?;
+            let file_path = path.to_string_lossy().to_string();
+            let actual = tokio::task::spawn_blocking(move || HashService::new().compute_file(&file_path))
+                .await
+                .map_err(|error| UploadSessionError::Failed(error.into()))?
+                .map_err(UploadSessionError::Failed)?;
+            if actual != expected {
+                return Err(UploadSessionError::Invalid(format!(
+                    "file hash mismatch: expected {}, got {}",
+                    expected, actual
+                )));
+            }
+        }
+
+        match PhotoUploadService::file_rejection_reason(&session.file_name, &path).await {
+            Ok(None) => {}
+            Ok(Some(reason)) => {
+                self.discard(id).await;
+                return Err(UploadSessionError::Invalid(reason));
+            }
+            Err(error) => return Err(UploadSessionError::Failed(error)),
+        }
+
+        self.lock().remove(&id);
+        Ok((session, path))
+    }
+
+    pub async fn discard(&self, id: Uuid) {
+        let session = self.lock().remove(&id);
+        if let Some(session) = session {
+            let _ = fs::remove_file(session.data_path()).await;
+        }
+    }
+
+    // Drops idle sessions and any leftover part files, including ones orphaned by a restart.
+    pub async fn sweep(&self, storage_paths: &[PathBuf]) -> usize {
+        let now = Utc::now();
+        let expired = {
+            let mut sessions = self.lock();
+            let ids = sessions
+                .values()
+                .filter(|session| session.expires_at(self.ttl) <= now)
+                .map(|session| session.id)
+                .collect::<Vec<_>>();
+            ids.into_iter().filter_map(|id| sessions.remove(&id)).collect::<Vec<_>>()
+        };
+
+        let mut removed = 0;
+        for session in &expired {
+            if fs::remove_file(session.data_path()).await.is_ok() {
+                removed += 1;
+            }
+        }
+
+        let active = self.lock().keys().copied().collect::<HashSet<_>>();
+        for storage_path in storage_paths {
+            removed += self.sweep_orphans(&Self::sessions_folder(storage_path), &active).await;
+        }
+        removed
+    }
+
+    pub fn start_sweeper(&self, storages: Arc<Repository<StorageLocation>>) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(Self::SWEEP_INTERVAL_SECONDS));
+            loop {
+                interval.tick().await;
+                let storage_paths = match storages.load_storages().await {
+                    Ok(storages) => storages.into_iter().map(|storage| PathBuf::from(storage.path)).collect(),
+                    Err(error) => {
+                        log::warn!("Upload session sweep could not load storages: {:?}", error);
+                        Vec::new()
+                    }
+                };
+                let removed = service.sweep(&storage_paths).await;
+                if removed > 0 {
+                    log::info!("Removed {} stale upload session file(s)", removed);
+                }
+            }
+        });
+    }
+
+    async fn sweep_orphans(&self, folder: &Path, active: &HashSet<Uuid>) -> usize {
+        let Ok(mut entries) = fs::read_dir(folder).await else {
+            return 0;
+        };
+
+        let mut removed = 0;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let id = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| Uuid::parse_str(stem).ok());
+            if id.is_some_and(|id| active.contains(&id)) {
+                continue;
+            }
+            let idle = entry
+                .metadata()
+                .await
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|elapsed| elapsed >= self.ttl);
+            if idle && fs::remove_file(&path).await.is_ok() {
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, UploadSession>> {
+        self.sessions.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
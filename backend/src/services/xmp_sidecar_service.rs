@@ -0,0 +1,89 @@
+use tokio::task;
+
+use crate::prelude::*;
+
+// Mirrors tag and caption edits into .xmp sidecars so Lightroom and digiKam stay in sync. Writes
+// happen after the API has answered and failures are only logged; the database stays the source of truth.
+#[derive(Clone)]
+pub struct XmpSidecarService {
+    photos: Arc<Repository<Photo>>,
+    tags: Arc<Repository<Tag>>,
+    settings: Arc<SettingService>,
+}
+
+impl XmpSidecarService {
+    pub fn new(services: Arc<ServiceProvider>) -> Self {
+        Self {
+            photos: services.get::<Repository<Photo>>(),
+            tags: services.get::<Repository<Tag>>(),
+            settings: services.get::<SettingService>(),
+        }
+    }
+
+    pub fn schedule_write_back(&self, photo_ids: Vec<Uuid>) {
+        if photo_ids.is_empty() {
+            return;
+        }
+        let service = self.clone();
+        tokio::spawn(async move {
+            match service.settings.xmp_write_back().await {
+                Ok(true) => service.write_back(&photo_ids).await,
+                Ok(false) => {}
+                Err(error) => log::warn!("Failed to read XMP write-back setting: {:?}", error),
+            }
+        });
+    }
+
+    async fn write_back(&self, photo_ids: &[Uuid]) {
+        let photos = match self.photos.get_by_ids(photo_ids).await {
+            Ok(photos) => photos,
+            Err(error) => {
+                log::warn!("Failed to load photos for XMP write-back: {:?}", error);
+                return;
+            }
+        };
+
+        for photo in photos {
+            if let Err(error) = self.write_photo(&photo).await {
+                log::warn!("Failed to write XMP sidecar for {}: {:?}", photo.path, error);
+            }
+        }
+    }
+
+    async fn write_photo(&self, photo: &Photo) -> anyhow::Result<()> {
+        let tags = self.tags.get_photo_tags(photo.id).await.map_err(|err| anyhow::anyhow!("{:?}", err))?;
+        let keywords = tags.into_iter().map(|tag| tag.name).collect::<Vec<_>>();
+        let title = photo.title.clone();
+        let caption = photo.caption.clone();
+        let image_path = PathBuf::from(&photo.path);
+
+        task::spawn_blocking(move || Self::write_sidecar(&image_path, &keywords, title.as_deref(), caption.as_deref()))
+            .await?
+    }
+
+    // An existing sidecar keeps its name and every property we do not own; otherwise the
+    // "photo.jpg.xmp" form is created, which digiKam and darktable read by default.
+    fn write_sidecar(
+        image_path: &Path,
+        keywords: &[String],
+        title: Option<&str>,
+        caption: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let (path, content) = match XmpSidecar::find(image_path) {
+            Some(path) => {
+                let existing = std::fs::read_to_string(&path)?;
+                (path, XmpSidecar::update(&existing, keywords, title, caption)?)
+            }
+            None => {
+                let Some(file_name) = image_path.file_name().and_then(|name| name.to_str()) else {
+                    anyhow::bail!("photo path has no file name");
+                };
+                let path = image_path.with_file_name(format!("{}.{}", file_name, XmpSidecar::EXTENSION));
+                (path, XmpSidecar::render(keywords, title, caption)?)
+            }
+        };
+        std::fs::write(&path, content)?;
+        log::debug!("Wrote XMP sidecar {}", path.display());
+        Ok(())
+    }
+}
@@ -179,9 +179,18 @@ impl TestStep for LoginStep {
         response.assert_status(200)?;
 
         let payload: LoginResponse = response.json()?;
+        if payload.user.email != self.email || payload.user.roles.is_empty() {
+            return Err(TestError::msg(format!(
+                "login profile mismatch: email {}, roles {:?}",
+                payload.user.email, payload.user.roles
+            )));
+        }
+        let profile = serde_json::to_string(&payload.user)
+            .map_err(|e| TestError::msg(format!("failed to encode profile: {}", e)))?;
         bot.context.access_token = Some(payload.access_token.clone());
         bot.context
             .set_str("refresh_token", payload.refresh_token.clone());
+        bot.context.set_str("user_profile", profile);
 
         Ok(())
     }
@@ -261,6 +270,16 @@ impl TestStep for RefreshStep {
         response.assert_status(200)?;
 
         let payload: LoginResponse = response.json()?;
+        if let Some(profile) = bot.context.get_str("user_profile") {
+            let profile: UserProfileDto = serde_json::from_str(&profile)
+                .map_err(|e| TestError::msg(format!("failed to decode profile: {}", e)))?;
+            if payload.user != profile {
+                return Err(TestError::msg(format!(
+                    "refresh profile {:?} does not match login profile {:?}",
+                    payload.user, profile
+                )));
+            }
+        }
         bot.context.access_token = Some(payload.access_token.clone());
         bot.context
             .set_str("refresh_token", payload.refresh_token.clone());
@@ -422,3 +441,53 @@ impl TestStep for VerifyEmailStep {
         Ok(())
     }
 }
+
+pub struct PromoteAdminStep {
+    email: String,
+    password: String,
+}
+
+impl PromoteAdminStep {
+    pub fn new(email: String, password: String) -> Self {
+        Self { email, password }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for PromoteAdminStep {
+    fn name(&self) -> &'static str {
+        "promote-admin"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/test/auth/promote-admin"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let register = RegisterRequest {
+            email: self.email.clone(),
+            password: self.password.clone(),
+            confirm_password: self.password.clone(),
+            display_name: "TestBot Admin".to_string(),
+        };
+        let response = bot.post("/api/auth/register", &register).await?;
+        response.assert_status(200)?;
+
+        let login = LoginRequest {
+            email: self.email.clone(),
+            password: self.password.clone(),
+        };
+        let response = bot.post("/api/auth/login", &login).await?;
+        response.assert_status(200)?;
+
+        let response = bot
+            .post(self.endpoint(), &json!({ "email": self.email }))
+            .await?;
+        response.assert_status(200)?;
+
+        let tokens: LoginResponse = response.json()?;
+        bot.context.access_token = Some(tokens.access_token);
+        bot.log_info(format!("promote-admin returned status {}", response.status));
+        Ok(())
+    }
+}
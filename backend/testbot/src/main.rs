@@ -9,11 +9,16 @@ use tokio::time::sleep;
 mod album;
 mod auth;
 mod photo;
+mod storage;
+mod tag;
 use album::AlbumScenario;
 use auth::AuthScenario;
 use photo::PhotoScenario;
+use storage::StorageScenario;
+use tag::TagScenario;
 
 const DEFAULT_PORT: u16 = 7878;
+const SHUTDOWN_TIMEOUT_SECONDS: u64 = 45;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -55,6 +60,8 @@ async fn execute_testbot() -> Result<()> {
     bot.add_scenario(AuthScenario::new());
     bot.add_scenario(PhotoScenario::new());
     bot.add_scenario(AlbumScenario::new());
+    bot.add_scenario(TagScenario::new());
+    bot.add_scenario(StorageScenario::new());
 
     bot.run().await?;
     Ok(())
@@ -111,10 +118,44 @@ async fn start_hosting_application() -> Result<Child> {
 }
 
 fn shutdown_host(child: &mut Child) {
+    if request_termination(child) && wait_for_exit(child, Duration::from_secs(SHUTDOWN_TIMEOUT_SECONDS)) {
+        return;
+    }
+
+    log::warn!("Host did not exit after termination signal; killing it");
     let _ = child.kill();
     let _ = child.wait();
 }
 
+#[cfg(unix)]
+fn request_termination(child: &Child) -> bool {
+    Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn request_termination(_child: &Child) -> bool {
+    false
+}
+
+fn wait_for_exit(child: &mut Child, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                log::info!("Host exited with {}", status);
+                return true;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(200)),
+            Err(_) => return false,
+        }
+    }
+    false
+}
+
 fn init_logging() {
     let mut builder = env_logger::Builder::from_default_env();
     builder
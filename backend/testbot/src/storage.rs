@@ -0,0 +1,519 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use nimble_web::testbot::{AssertResponse, TestBot, TestError, TestResult, TestScenario, TestStep};
+
+use crate::auth::PromoteAdminStep;
+
+const SEEDED_PHOTOS: u32 = 5;
+const BROWSE_PAGE_SIZE: usize = 2;
+const CATEGORY_TEMPLATE: &str = "{year}/{fileName}";
+
+pub struct StorageScenario {
+    email: String,
+    password: String,
+    mount_point: PathBuf,
+    folder: String,
+}
+
+impl StorageScenario {
+    pub fn new() -> Self {
+        let nonce = Uuid::new_v4().simple().to_string();
+        Self {
+            email: format!("storage+{nonce}@example.com"),
+            password: "TestBotPass#1".to_string(),
+            mount_point: env::temp_dir(),
+            folder: format!("nimble-testbot-storage-{}", &nonce[..8]),
+        }
+    }
+
+    fn root(&self) -> PathBuf {
+        self.mount_point.join(&self.folder)
+    }
+}
+
+impl Drop for StorageScenario {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(self.root());
+    }
+}
+
+#[async_trait(?Send)]
+impl TestScenario for StorageScenario {
+    fn name(&self) -> &'static str {
+        "Storage endpoints"
+    }
+
+    fn steps(&self) -> Vec<Box<dyn TestStep>> {
+        vec![
+            Box::new(PromoteAdminStep::new(
+                self.email.clone(),
+                self.password.clone(),
+            )),
+            Box::new(CreateStorageStep::new(
+                self.mount_point.clone(),
+                self.folder.clone(),
+            )),
+            Box::new(ListStorageStep),
+            Box::new(UpdateTemplateStep),
+            Box::new(SetDefaultStep),
+            Box::new(SeedStoragePhotosStep),
+            Box::new(BrowseStorageStep),
+            Box::new(DeleteStorageStep::new(self.root())),
+        ]
+    }
+}
+
+fn storage_id(bot: &TestBot) -> Result<String, TestError> {
+    bot.context
+        .get_str("storage_id")
+        .map(|id| id.to_string())
+        .ok_or_else(|| TestError::msg("storage id missing"))
+}
+
+fn find_location<'a>(locations: &'a Value, id: &str) -> Result<&'a Value, TestError> {
+    locations
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .find(|item| item.get("id").and_then(Value::as_str) == Some(id))
+        })
+        .ok_or_else(|| TestError::msg(format!("storage {} missing from locations", id)))
+}
+
+// Windows mount points look like `C:\` while Linux ones look like `/`, so compare both
+// sides with forward slashes and, for drive letters, without case.
+fn normalize_path(path: &str) -> String {
+    let normalized = path.trim().replace('\\', "/");
+    let bytes = normalized.as_bytes();
+    if bytes.len() >= 2 && bytes[1] == b':' {
+        return normalized.to_ascii_lowercase();
+    }
+    normalized
+}
+
+fn expected_mount_point(path: &str, disks: &Value) -> Option<String> {
+    let path = normalize_path(path);
+    disks
+        .as_array()?
+        .iter()
+        .filter_map(|disk| disk.get("mountPoint").and_then(Value::as_str))
+        .map(normalize_path)
+        .filter(|mount_point| !mount_point.is_empty() && path.starts_with(mount_point.as_str()))
+        .max_by_key(|mount_point| mount_point.len())
+}
+
+fn photo_ids(response: &Value) -> Vec<String> {
+    response
+        .get("photos")
+        .and_then(Value::as_array)
+        .map(|photos| {
+            photos
+                .iter()
+                .filter_map(|photo| photo.get("id").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn first_folder(response: &Value) -> Result<String, TestError> {
+    response
+        .get("folders")
+        .and_then(Value::as_array)
+        .and_then(|folders| folders.first())
+        .and_then(|folder| folder.get("fullPath"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| TestError::msg(format!("browse returned no folders: {}", response)))
+}
+
+struct CreateStorageStep {
+    mount_point: PathBuf,
+    folder: String,
+}
+
+impl CreateStorageStep {
+    fn new(mount_point: PathBuf, folder: String) -> Self {
+        Self {
+            mount_point,
+            folder,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for CreateStorageStep {
+    fn name(&self) -> &'static str {
+        "create-storage"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/storage/locations"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        fs::create_dir_all(self.mount_point.join(&self.folder))
+            .map_err(|e| TestError::msg(format!("failed to create storage folder: {}", e)))?;
+
+        let payload = json!({
+            "label": "TestBot Storage",
+            "mountPoint": self.mount_point.to_string_lossy(),
+            "path": self.folder,
+            "isDefault": false,
+        });
+        let response = bot.post_auth(self.endpoint(), &payload).await?;
+        response.assert_status(200)?;
+
+        let created: Value = response.json()?;
+        let id = created
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| TestError::msg("create storage response missing id"))?
+            .to_string();
+
+        bot.context.set_str("storage_id", id);
+        bot.log_info(format!(
+            "create-storage returned status {}",
+            response.status
+        ));
+        Ok(())
+    }
+}
+
+struct ListStorageStep;
+
+#[async_trait(?Send)]
+impl TestStep for ListStorageStep {
+    fn name(&self) -> &'static str {
+        "list-storage"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/storage/locations"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let id = storage_id(bot)?;
+        let response = bot.get_auth(self.endpoint()).await?;
+        response.assert_status(200)?;
+        let locations: Value = response.json()?;
+
+        if let Some(previous) = locations.as_array().and_then(|items| {
+            items
+                .iter()
+                .find(|item| item.get("isDefault").and_then(Value::as_bool) == Some(true))
+        }) {
+            if let Some(previous_id) = previous
+                .get("id")
+                .and_then(Value::as_str)
+                .filter(|previous_id| *previous_id != id)
+            {
+                bot.context
+                    .set_str("previous_default_storage_id", previous_id.to_string());
+            }
+        }
+
+        let location = find_location(&locations, &id)?;
+        let path = location
+            .get("path")
+            .and_then(Value::as_str)
+            .ok_or_else(|| TestError::msg("storage location missing path"))?;
+
+        let response = bot.get_auth("/api/storage/disks").await?;
+        response.assert_status(200)?;
+        let disks: Value = response.json()?;
+
+        let expected = expected_mount_point(path, &disks);
+        let actual = location
+            .get("disk")
+            .and_then(|disk| disk.get("mountPoint"))
+            .and_then(Value::as_str)
+            .map(normalize_path);
+        if actual != expected {
+            return Err(TestError::msg(format!(
+                "expected disk {:?} for {}, got {:?}",
+                expected, path, actual
+            )));
+        }
+
+        bot.log_info(format!("list-storage found disk {:?}", actual));
+        Ok(())
+    }
+}
+
+struct UpdateTemplateStep;
+
+#[async_trait(?Send)]
+impl TestStep for UpdateTemplateStep {
+    fn name(&self) -> &'static str {
+        "update-storage-template"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/storage/locations/{id}"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let id = storage_id(bot)?;
+        let path = format!("/api/storage/locations/{}", id);
+        let payload = json!({ "categoryTemplate": CATEGORY_TEMPLATE });
+
+        let response = bot.put_auth(&path, &payload).await?;
+        response.assert_status(200)?;
+
+        let locations: Value = response.json()?;
+        let template = find_location(&locations, &id)?
+            .get("categoryTemplate")
+            .and_then(Value::as_str);
+        if template != Some(CATEGORY_TEMPLATE) {
+            return Err(TestError::msg(format!(
+                "expected category template {}, got {:?}",
+                CATEGORY_TEMPLATE, template
+            )));
+        }
+
+        bot.log_info(format!(
+            "update-storage-template returned status {}",
+            response.status
+        ));
+        Ok(())
+    }
+}
+
+struct SetDefaultStep;
+
+#[async_trait(?Send)]
+impl TestStep for SetDefaultStep {
+    fn name(&self) -> &'static str {
+        "set-default-storage"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/storage/locations/{id}/default"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let id = storage_id(bot)?;
+        let path = format!("/api/storage/locations/{}/default", id);
+
+        let response = bot.put_auth(&path, &json!({})).await?;
+        response.assert_status(200)?;
+
+        let locations: Value = response.json()?;
+        let defaults = locations
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter(|item| item.get("isDefault").and_then(Value::as_bool) == Some(true))
+                    .filter_map(|item| item.get("id").and_then(Value::as_str))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        if defaults != vec![id.as_str()] {
+            return Err(TestError::msg(format!(
+                "expected {} to be the only default storage, got {:?}",
+                id, defaults
+            )));
+        }
+
+        bot.log_info(format!(
+            "set-default-storage returned status {}",
+            response.status
+        ));
+        Ok(())
+    }
+}
+
+struct SeedStoragePhotosStep;
+
+#[async_trait(?Send)]
+impl TestStep for SeedStoragePhotosStep {
+    fn name(&self) -> &'static str {
+        "seed-storage-photos"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/test/photos/seed"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let payload = json!({ "storageId": storage_id(bot)?, "count": SEEDED_PHOTOS });
+        let response = bot.post_auth(self.endpoint(), &payload).await?;
+        response.assert_status(200)?;
+
+        let seeded: Value = response.json()?;
+        let count = seeded.as_array().map(Vec::len).unwrap_or(0);
+        if count != SEEDED_PHOTOS as usize {
+            return Err(TestError::msg(format!(
+                "expected {} seeded photos, got {}",
+                SEEDED_PHOTOS, count
+            )));
+        }
+
+        bot.log_info(format!(
+            "seed-storage-photos returned status {}",
+            response.status
+        ));
+        Ok(())
+    }
+}
+
+struct BrowseStorageStep;
+
+impl BrowseStorageStep {
+    async fn browse(
+        bot: &mut TestBot,
+        id: &str,
+        path: Option<&str>,
+        cursor: Option<&str>,
+    ) -> Result<Value, TestError> {
+        let mut url = format!("/api/storage/browse/{}?pageSize={}", id, BROWSE_PAGE_SIZE);
+        if let Some(path) = path {
+            url.push_str(&format!("&path={}", encode_query(path)));
+        }
+        if let Some(cursor) = cursor {
+            url.push_str(&format!("&cursor={}", encode_query(cursor)));
+        }
+
+        let response = bot.get_auth(&url).await?;
+        response.assert_status(200)?;
+        response.json()
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for BrowseStorageStep {
+    fn name(&self) -> &'static str {
+        "browse-storage"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/storage/browse/{storageId}"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let id = storage_id(bot)?;
+
+        let years = Self::browse(bot, &id, None, None).await?;
+        let year = first_folder(&years)?;
+        let days = Self::browse(bot, &id, Some(&year), None).await?;
+        let day = first_folder(&days)?;
+
+        let mut seen = Vec::<String>::new();
+        let mut cursor: Option<String> = None;
+        let mut pages = 0;
+        loop {
+            let page = Self::browse(bot, &id, Some(&day), cursor.as_deref()).await?;
+            let ids = photo_ids(&page);
+            if ids.len() > BROWSE_PAGE_SIZE {
+                return Err(TestError::msg(format!(
+                    "browse page returned {} photos, page size is {}",
+                    ids.len(),
+                    BROWSE_PAGE_SIZE
+                )));
+            }
+            if let Some(duplicate) = ids.iter().find(|photo_id| seen.contains(photo_id)) {
+                return Err(TestError::msg(format!(
+                    "photo {} returned on more than one page",
+                    duplicate
+                )));
+            }
+            seen.extend(ids);
+            pages += 1;
+
+            cursor = page
+                .get("nextCursor")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            if cursor.is_none() || pages > SEEDED_PHOTOS {
+                break;
+            }
+        }
+
+        if seen.len() != SEEDED_PHOTOS as usize || pages < 2 {
+            return Err(TestError::msg(format!(
+                "expected {} photos across several pages, got {} in {} pages",
+                SEEDED_PHOTOS,
+                seen.len(),
+                pages
+            )));
+        }
+
+        bot.log_info(format!(
+            "browse-storage paged {} photos in {} pages",
+            seen.len(),
+            pages
+        ));
+        Ok(())
+    }
+}
+
+struct DeleteStorageStep {
+    root: PathBuf,
+}
+
+impl DeleteStorageStep {
+    fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for DeleteStorageStep {
+    fn name(&self) -> &'static str {
+        "delete-storage"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/storage/locations/{id}"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let id = storage_id(bot)?;
+        let path = format!("/api/storage/locations/{}", id);
+        let response = bot.delete_auth(&path).await?;
+        response.assert_status(200)?;
+
+        let locations: Value = response.json()?;
+        if find_location(&locations, &id).is_ok() {
+            return Err(TestError::msg(format!("storage {} still listed", id)));
+        }
+
+        if let Some(previous) = bot
+            .context
+            .get_str("previous_default_storage_id")
+            .map(|value| value.to_string())
+        {
+            let path = format!("/api/storage/locations/{}/default", previous);
+            bot.put_auth(&path, &json!({})).await?.assert_status(200)?;
+        }
+
+        fs::remove_dir_all(&self.root)
+            .map_err(|e| TestError::msg(format!("failed to remove storage folder: {}", e)))?;
+
+        bot.log_info(format!(
+            "delete-storage returned status {}",
+            response.status
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use nimble_web::testbot::{AssertResponse, TestBot, TestError, TestResult, TestScenario, TestStep};
+
+use crate::auth::PromoteAdminStep;
+
+pub struct TagScenario {
+    email: String,
+    password: String,
+    public_tag: String,
+    hidden_tag: String,
+}
+
+impl TagScenario {
+    pub fn new() -> Self {
+        let nonce = Uuid::new_v4().simple().to_string();
+        Self {
+            email: format!("tags+{nonce}@example.com"),
+            password: "TestBotPass#1".to_string(),
+            public_tag: format!("testbot-public-{}", &nonce[..8]),
+            hidden_tag: format!("testbot-hidden-{}", &nonce[..8]),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestScenario for TagScenario {
+    fn name(&self) -> &'static str {
+        "Tag endpoints"
+    }
+
+    fn steps(&self) -> Vec<Box<dyn TestStep>> {
+        let tags = vec![self.public_tag.clone(), self.hidden_tag.clone()];
+        vec![
+            Box::new(PromoteAdminStep::new(
+                self.email.clone(),
+                self.password.clone(),
+            )),
+            Box::new(SeedPhotoStep),
+            Box::new(ReplaceTagsStep::new(tags.clone())),
+            Box::new(PhotoTagsStep::new(tags.clone())),
+            Box::new(GlobalTagsStep::new(tags)),
+            Box::new(HideTagStep::new(self.hidden_tag.clone())),
+            Box::new(AnonymousVisibilityStep::new(
+                self.public_tag.clone(),
+                self.hidden_tag.clone(),
+            )),
+            Box::new(AdminVisibilityStep::new(
+                self.public_tag.clone(),
+                self.hidden_tag.clone(),
+            )),
+        ]
+    }
+}
+
+fn photo_id(bot: &TestBot) -> Result<String, TestError> {
+    bot.context
+        .get_str("tag_photo_id")
+        .map(|id| id.to_string())
+        .ok_or_else(|| TestError::msg("tag photo id missing"))
+}
+
+fn photo_hash(bot: &TestBot) -> Result<String, TestError> {
+    bot.context
+        .get_str("tag_photo_hash")
+        .map(|hash| hash.to_string())
+        .ok_or_else(|| TestError::msg("tag photo hash missing"))
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn page_contains(page: &Value, id: &str) -> bool {
+    page.get("items")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .any(|item| item.get("id").and_then(Value::as_str) == Some(id))
+        })
+        .unwrap_or(false)
+}
+
+struct SeedPhotoStep;
+
+#[async_trait(?Send)]
+impl TestStep for SeedPhotoStep {
+    fn name(&self) -> &'static str {
+        "seed-photo"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/test/photos/seed"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let response = bot.post_auth(self.endpoint(), &json!({})).await?;
+        response.assert_status(200)?;
+
+        let seeded: Value = response.json()?;
+        let photo = seeded
+            .get(0)
+            .ok_or_else(|| TestError::msg("seed photo response is empty"))?;
+        let id = photo
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| TestError::msg("seeded photo missing id"))?
+            .to_string();
+        let hash = photo
+            .get("hash")
+            .and_then(Value::as_str)
+            .ok_or_else(|| TestError::msg("seeded photo missing hash"))?
+            .to_string();
+
+        bot.context.set_str("tag_photo_id", id);
+        bot.context.set_str("tag_photo_hash", hash);
+        bot.log_info(format!("seed-photo returned status {}", response.status));
+        Ok(())
+    }
+}
+
+struct ReplaceTagsStep {
+    tags: Vec<String>,
+}
+
+impl ReplaceTagsStep {
+    fn new(tags: Vec<String>) -> Self {
+        Self { tags }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for ReplaceTagsStep {
+    fn name(&self) -> &'static str {
+        "replace-photo-tags"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/photos/tags"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let payload = json!({
+            "photoIds": [photo_id(bot)?],
+            "tags": self.tags,
+            "mode": "replace",
+        });
+
+        let response = bot.put_auth(self.endpoint(), &payload).await?;
+        response.assert_status(200)?;
+
+        let body: Value = response.json()?;
+        if body.get("updated").and_then(Value::as_u64) != Some(1) {
+            return Err(TestError::msg(format!(
+                "expected one updated photo, got {}",
+                body
+            )));
+        }
+
+        bot.log_info(format!(
+            "replace-photo-tags returned status {}",
+            response.status
+        ));
+        Ok(())
+    }
+}
+
+struct PhotoTagsStep {
+    expected: Vec<String>,
+}
+
+impl PhotoTagsStep {
+    fn new(expected: Vec<String>) -> Self {
+        Self { expected }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for PhotoTagsStep {
+    fn name(&self) -> &'static str {
+        "photo-tags"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/photos/{id}/tags"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let path = format!("/api/photos/{}/tags", photo_id(bot)?);
+        let response = bot.get_auth(&path).await?;
+        response.assert_status(200)?;
+
+        let tags = string_list(&response.json()?);
+        let mut expected = self.expected.clone();
+        expected.sort();
+        if tags != expected {
+            return Err(TestError::msg(format!(
+                "expected photo tags {:?}, got {:?}",
+                expected, tags
+            )));
+        }
+
+        bot.log_info(format!("photo-tags returned status {}", response.status));
+        Ok(())
+    }
+}
+
+struct GlobalTagsStep {
+    expected: Vec<String>,
+}
+
+impl GlobalTagsStep {
+    fn new(expected: Vec<String>) -> Self {
+        Self { expected }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for GlobalTagsStep {
+    fn name(&self) -> &'static str {
+        "global-tags"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/photos/tags"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let response = bot.get_auth(self.endpoint()).await?;
+        response.assert_status(200)?;
+
+        let tags = string_list(&response.json()?);
+        if let Some(missing) = self.expected.iter().find(|tag| !tags.contains(tag)) {
+            return Err(TestError::msg(format!(
+                "tag {} missing from global tag list",
+                missing
+            )));
+        }
+
+        bot.log_info(format!("global-tags returned status {}", response.status));
+        Ok(())
+    }
+}
+
+struct HideTagStep {
+    tag: String,
+}
+
+impl HideTagStep {
+    fn new(tag: String) -> Self {
+        Self { tag }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for HideTagStep {
+    fn name(&self) -> &'static str {
+        "hide-tag"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/test/tags/visibility"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let payload = json!({ "name": self.tag, "visibility": 1 });
+        let response = bot.post_auth(self.endpoint(), &payload).await?;
+        response.assert_status(200)?;
+
+        bot.log_info(format!("hide-tag returned status {}", response.status));
+        Ok(())
+    }
+}
+
+struct AnonymousVisibilityStep {
+    public_tag: String,
+    hidden_tag: String,
+}
+
+impl AnonymousVisibilityStep {
+    fn new(public_tag: String, hidden_tag: String) -> Self {
+        Self {
+            public_tag,
+            hidden_tag,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for AnonymousVisibilityStep {
+    fn name(&self) -> &'static str {
+        "anonymous-hidden-tag-visibility"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/photos/{page}/{pageSize}"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let id = photo_id(bot)?;
+
+        let path = format!("/api/photos/1/50?tags={}", self.public_tag);
+        let response = bot.get(&path).await?;
+        if response.status == 200 && page_contains(&response.json()?, &id) {
+            return Err(TestError::msg(
+                "anonymous photos query returned a photo with an admin-only tag",
+            ));
+        }
+
+        let path = format!("/api/photos/thumbnail/{}", photo_hash(bot)?);
+        let response = bot.get(&path).await?;
+        if response.status == 200 {
+            return Err(TestError::msg(
+                "anonymous thumbnail request served a photo with an admin-only tag",
+            ));
+        }
+
+        let path = format!("/api/photos/{}/tags", id);
+        let response = bot.get(&path).await?;
+        if response.status == 200 && string_list(&response.json()?).contains(&self.hidden_tag) {
+            return Err(TestError::msg(
+                "anonymous tag list exposed an admin-only tag",
+            ));
+        }
+
+        bot.log_info("anonymous requests no longer see the hidden photo");
+        Ok(())
+    }
+}
+
+struct AdminVisibilityStep {
+    public_tag: String,
+    hidden_tag: String,
+}
+
+impl AdminVisibilityStep {
+    fn new(public_tag: String, hidden_tag: String) -> Self {
+        Self {
+            public_tag,
+            hidden_tag,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl TestStep for AdminVisibilityStep {
+    fn name(&self) -> &'static str {
+        "admin-hidden-tag-visibility"
+    }
+
+    fn endpoint(&self) -> &'static str {
+        "/api/photos/{page}/{pageSize}"
+    }
+
+    async fn run(&self, bot: &mut TestBot) -> TestResult {
+        let id = photo_id(bot)?;
+
+        let path = format!("/api/photos/1/50?tags={}", self.public_tag);
+        let response = bot.get_auth(&path).await?;
+        response.assert_status(200)?;
+        if !page_contains(&response.json()?, &id) {
+            return Err(TestError::msg(
+                "admin photos query is missing the photo with an admin-only tag",
+            ));
+        }
+
+        let path = format!("/api/photos/thumbnail/{}", photo_hash(bot)?);
+        let response = bot.get_auth(&path).await?;
+        response.assert_status(200)?;
+
+        let path = format!("/api/photos/{}/tags", id);
+        let response = bot.get_auth(&path).await?;
+        response.assert_status(200)?;
+        if !string_list(&response.json()?).contains(&self.hidden_tag) {
+            return Err(TestError::msg(
+                "admin tag list is missing the admin-only tag",
+            ));
+        }
+
+        bot.log_info("admin requests still see the hidden photo");
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use nimble_photos::dtos::{AccountContentAction, DeleteAccountRequest};
+use nimble_photos::services::RevokedSubjectRegistry;
+use uuid::Uuid;
+
+#[test]
+fn delete_account_request_defaults_to_anonymize() {
+    let request: DeleteAccountRequest = serde_json::from_str(r#"{"password":"secret"}"#).unwrap();
+
+    assert_eq!(request.password, "secret");
+    assert_eq!(request.content_action, AccountContentAction::Anonymize);
+    assert!(request.refresh_token.is_none());
+}
+
+#[test]
+fn delete_account_request_accepts_delete_action() {
+    let request: DeleteAccountRequest =
+        serde_json::from_str(r#"{"password":"secret","contentAction":"delete","refreshToken":"abc"}"#).unwrap();
+
+    assert_eq!(request.content_action, AccountContentAction::Delete);
+    assert_eq!(request.refresh_token.as_deref(), Some("abc"));
+}
+
+#[test]
+fn delete_account_request_rejects_unknown_action() {
+    let result = serde_json::from_str::<DeleteAccountRequest>(r#"{"password":"secret","contentAction":"archive"}"#);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn revoked_subjects_are_shared_between_clones() {
+    let registry = RevokedSubjectRegistry::new();
+    let shared = registry.clone();
+    let user_id = Uuid::new_v4();
+
+    assert!(!shared.is_revoked(&user_id.to_string()));
+    registry.revoke(user_id);
+
+    assert!(shared.is_revoked(&user_id.to_string()));
+    assert!(!shared.is_revoked(&Uuid::new_v4().to_string()));
+}
@@ -5,15 +5,25 @@ use nimble_web::Policy;
 #[test]
 fn routes_require_authenticated() {
     let routes = AdminUserController::routes();
-    assert_eq!(routes.len(), 2);
+    assert_eq!(routes.len(), 4);
 
     let list_route = &routes[0];
     assert_eq!(list_route.route.method(), "GET");
     assert_eq!(list_route.route.path(), "/api/admin/users");
     assert_eq!(list_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
 
-    let update_route = &routes[1];
+    let detail_route = &routes[1];
+    assert_eq!(detail_route.route.method(), "GET");
+    assert_eq!(detail_route.route.path(), "/api/admin/users/{id}");
+    assert_eq!(detail_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+
+    let update_route = &routes[2];
     assert_eq!(update_route.route.method(), "PUT");
     assert_eq!(update_route.route.path(), "/api/admin/users/{id}/roles");
     assert_eq!(update_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+
+    let delete_route = &routes[3];
+    assert_eq!(delete_route.route.method(), "DELETE");
+    assert_eq!(delete_route.route.path(), "/api/admin/users/{id}");
+    assert_eq!(delete_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
 }
@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use nimble_photos::dtos::{AdminUserDto, PhotoCommentDto};
+use nimble_photos::entities::{AlbumComment, PhotoComment, User, UserSettings};
+use nimble_photos::services::{AdminUserFilter, AdminUserService};
+use nimble_web::MemoryRepository;
+use nimble_web::Repository;
+use nimble_web::data::query::Query;
+use uuid::Uuid;
+
+struct Fixture {
+    service: AdminUserService,
+    users: MemoryRepository<User>,
+    settings: MemoryRepository<UserSettings>,
+    photo_comments: MemoryRepository<PhotoComment>,
+    album_comments: MemoryRepository<AlbumComment>,
+}
+
+fn user(id: Uuid, roles: &str) -> User {
+    User {
+        id,
+        email: format!("{}@example.com", id),
+        display_name: "Member".to_string(),
+        password_hash: String::new(),
+        created_at: Utc::now(),
+        reset_token: None,
+        reset_token_expires_at: None,
+        verification_token: None,
+        email_verified: true,
+        roles: Some(roles.to_string()),
+    }
+}
+
+fn settings(user_id: Uuid, display_name: &str, avatar_url: Option<&str>) -> UserSettings {
+    UserSettings {
+        user_id,
+        display_name: display_name.to_string(),
+        avatar_url: avatar_url.map(ToString::to_string),
+        theme: "light".to_string(),
+        language: "en".to_string(),
+        timezone: "UTC".to_string(),
+        email_digest: false,
+        created_at: Utc::now(),
+    }
+}
+
+fn fixture(member_id: Uuid) -> Fixture {
+    let users = MemoryRepository::<User>::new();
+    users.seed(vec![user(Uuid::new_v4(), "admin"), user(member_id, "viewer")]);
+    let settings_repo = MemoryRepository::<UserSettings>::new();
+    settings_repo.seed(vec![settings(member_id, "Member", None)]);
+    let photo_comments = MemoryRepository::<PhotoComment>::new();
+    photo_comments.seed(vec![
+        PhotoComment::new(Uuid::new_v4(), member_id, Some("Member".to_string()), Some("Nice".to_string())),
+        PhotoComment::new(Uuid::new_v4(), Uuid::new_v4(), Some("Other".to_string()), Some("Great".to_string())),
+    ]);
+    let album_comments = MemoryRepository::<AlbumComment>::new();
+    let mut album_comment = AlbumComment::new(Uuid::new_v4(), member_id, "Member".to_string(), "Trip".to_string());
+    album_comment.id = Uuid::new_v4();
+    album_comments.seed(vec![album_comment]);
+
+    let service = AdminUserService::new(
+        Arc::new(Repository::new(Box::new(users.clone()))),
+        Arc::new(Repository::new(Box::new(settings_repo.clone()))),
+        Arc::new(Repository::new(Box::new(photo_comments.clone()))),
+        Arc::new(Repository::new(Box::new(album_comments.clone()))),
+    );
+
+    Fixture { service, users, settings: settings_repo, photo_comments, album_comments }
+}
+
+async fn photo_comments(fixture: &Fixture) -> Vec<PhotoComment> {
+    Repository::new(Box::new(fixture.photo_comments.clone())).all(Query::new()).await.unwrap()
+}
+
+async fn album_comments(fixture: &Fixture) -> Vec<AlbumComment> {
+    Repository::new(Box::new(fixture.album_comments.clone())).all(Query::new()).await.unwrap()
+}
+
+#[tokio::test]
+async fn delete_user_anonymizes_comments_by_default() {
+    let member_id = Uuid::new_v4();
+    let fixture = fixture(member_id);
+
+    let result = fixture.service.delete_user(member_id, false).await.unwrap();
+
+    assert_eq!(result.comments_anonymized, 2);
+    assert_eq!(result.comments_deleted, 0);
+    let photo_comments = photo_comments(&fixture).await;
+    assert_eq!(photo_comments.len(), 2);
+    assert!(photo_comments.iter().all(|comment| comment.user_id != Some(member_id)));
+    let anonymized = photo_comments.iter().find(|comment| comment.user_id.is_none()).unwrap();
+    assert_eq!(anonymized.user_display_name.as_deref(), Some("Member"));
+    assert_eq!(album_comments(&fixture).await[0].user_id, None);
+    assert!(Repository::new(Box::new(fixture.users.clone())).get(&member_id).await.unwrap().is_none());
+    assert!(Repository::new(Box::new(fixture.settings.clone())).get(&member_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn delete_user_can_cascade_comment_deletion() {
+    let member_id = Uuid::new_v4();
+    let fixture = fixture(member_id);
+
+    let result = fixture.service.delete_user(member_id, true).await.unwrap();
+
+    assert_eq!(result.comments_deleted, 2);
+    assert_eq!(result.comments_anonymized, 0);
+    let photo_comments = photo_comments(&fixture).await;
+    assert_eq!(photo_comments.len(), 1);
+    assert_eq!(photo_comments[0].user_display_name.as_deref(), Some("Other"));
+    assert!(album_comments(&fixture).await.is_empty());
+}
+
+#[tokio::test]
+async fn list_users_filters_pages_and_counts_comments() {
+    let member_id = Uuid::new_v4();
+    let fixture = fixture(member_id);
+
+    let all = fixture.service.list_users(AdminUserFilter::default(), 1, 1).await.unwrap();
+    assert_eq!(all.total, 2);
+    assert_eq!(all.items.len(), 1);
+
+    let filter = AdminUserFilter { search: Some(member_id.to_string().to_uppercase()), role: None };
+    let found = fixture.service.list_users(filter, 1, 50).await.unwrap();
+    assert_eq!(found.total, 1);
+    assert_eq!(found.items[0].id, member_id);
+    assert_eq!(found.items[0].photo_comment_count, 1);
+    assert_eq!(found.items[0].album_comment_count, 1);
+    assert!(found.items[0].last_comment_at.is_some());
+
+    let admins = AdminUserFilter { search: None, role: Some("admin".to_string()) };
+    let admins = fixture.service.list_users(admins, 1, 50).await.unwrap();
+    assert_eq!(admins.total, 1);
+    assert_ne!(admins.items[0].id, member_id);
+    assert_eq!(admins.items[0].photo_comment_count, 0);
+}
+
+#[tokio::test]
+async fn get_user_returns_detail_or_none() {
+    let member_id = Uuid::new_v4();
+    let fixture = fixture(member_id);
+
+    let detail = fixture.service.get_user(member_id).await.unwrap().unwrap();
+    assert_eq!(detail.roles, vec!["viewer".to_string()]);
+    assert_eq!(detail.photo_comment_count, 1);
+    assert!(fixture.service.get_user(Uuid::new_v4()).await.unwrap().is_none());
+}
+
+#[test]
+fn admin_user_dto_omits_credentials_and_tokens() {
+    let mut account = user(Uuid::new_v4(), "viewer");
+    account.password_hash = "$argon2id$secret-hash".to_string();
+    account.reset_token = Some("reset-secret".to_string());
+    account.reset_token_expires_at = Some(Utc::now());
+    account.verification_token = Some("verify-secret".to_string());
+
+    let json = serde_json::to_value(AdminUserDto::from(account)).unwrap();
+    let object = json.as_object().unwrap();
+
+    for key in ["passwordHash", "password_hash", "resetToken", "resetTokenExpiresAt", "verificationToken"] {
+        assert!(!object.contains_key(key), "{} must not be serialized", key);
+    }
+    let raw = json.to_string();
+    assert!(!raw.contains("secret-hash"));
+    assert!(!raw.contains("reset-secret"));
+    assert!(!raw.contains("verify-secret"));
+}
+
+#[test]
+fn comment_dto_prefers_current_author_settings() {
+    let author_id = Uuid::new_v4();
+    let comment = PhotoComment::new(Uuid::new_v4(), author_id, Some("Old Name".to_string()), Some("Hi".to_string()));
+    let authors = HashMap::from([(author_id, settings(author_id, "New Name", Some("/avatars/new.png")))]);
+
+    let dto = PhotoCommentDto::from(comment).with_author(&authors);
+
+    assert_eq!(dto.user_display_name.as_deref(), Some("New Name"));
+    assert_eq!(dto.avatar_url.as_deref(), Some("/avatars/new.png"));
+}
+
+#[test]
+fn comment_dto_keeps_snapshot_when_author_is_gone() {
+    let mut comment =
+        PhotoComment::new(Uuid::new_v4(), Uuid::new_v4(), Some("Snapshot".to_string()), Some("Hi".to_string()));
+    comment.user_id = None;
+
+    let dto = PhotoCommentDto::from(comment).with_author(&HashMap::new());
+
+    assert_eq!(dto.user_id, None);
+    assert_eq!(dto.user_display_name.as_deref(), Some("Snapshot"));
+    assert_eq!(dto.avatar_url, None);
+}
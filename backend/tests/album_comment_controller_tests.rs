@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use nimble_photos::controllers::album_controller::AlbumController;
+use nimble_photos::dtos::AlbumCommentDto;
+use nimble_photos::entities::{AlbumComment, UserSettings};
+use nimble_web::AuthenticationMiddleware;
+use nimble_web::AuthorizationMiddleware;
+use nimble_web::Claims;
+use nimble_web::Configuration;
+use nimble_web::ControllerInvokerMiddleware;
+use nimble_web::DefaultRouter;
+use nimble_web::EndpointExecutionMiddleware;
+use nimble_web::EndpointRegistry;
+use nimble_web::HttpContext;
+use nimble_web::HttpRequest;
+use nimble_web::MemoryRepository;
+use nimble_web::Pipeline;
+use nimble_web::Repository;
+use nimble_web::RequestBody;
+use nimble_web::ResponseBody;
+use nimble_web::Router;
+use nimble_web::RoutingMiddleware;
+use nimble_web::ServiceContainer;
+use nimble_web::UserIdentity;
+use nimble_web::{JwtTokenService, TokenService};
+
+const TEST_USER_ID_STR: &str = "00000000-0000-0000-0000-000000000042";
+
+fn post_album_comment(settings: Vec<UserSettings>) -> AlbumCommentDto {
+    let mut registry = EndpointRegistry::new();
+    registry.register::<AlbumController>();
+
+    let mut router = DefaultRouter::new();
+    for route in registry.routes() {
+        router.add_route(route.clone());
+    }
+
+    let settings_repo = MemoryRepository::<UserSettings>::new();
+    settings_repo.seed(settings);
+    let comment_repo = MemoryRepository::<AlbumComment>::new();
+
+    let mut container = ServiceContainer::new();
+    container
+        .register_singleton::<Repository<UserSettings>, _>(move |_| Repository::new(Box::new(settings_repo.clone())));
+    container
+        .register_singleton::<Repository<AlbumComment>, _>(move |_| Repository::new(Box::new(comment_repo.clone())));
+    container.register_singleton::<Arc<dyn TokenService>, _>(move |_| {
+        let service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+        Arc::new(service) as Arc<dyn TokenService>
+    });
+    let services = container.build();
+
+    let token_service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+    let identity = UserIdentity::new(TEST_USER_ID_STR.to_string(), Claims::new());
+    let token = TokenService::create_access_token(&token_service, &identity).unwrap();
+
+    let album_id = Uuid::new_v4();
+    let mut request = HttpRequest::new("POST", &format!("/api/album/comments/{}", album_id));
+    let header_val = format!("Bearer {}", token);
+    request.headers_mut().insert("authorization", header_val.as_str());
+    request.set_body(RequestBody::Text("{\"comment\":\"Lovely trip\"}".to_string()));
+
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(RoutingMiddleware::new(router));
+    pipeline.add(AuthenticationMiddleware::new());
+    pipeline.add(AuthorizationMiddleware::new());
+    pipeline.add(ControllerInvokerMiddleware::new(Arc::new(registry)));
+    pipeline.add(EndpointExecutionMiddleware::new());
+
+    let result = pipeline.run(&mut context);
+    assert!(result.is_ok());
+    assert_eq!(context.response().status(), 200);
+
+    match context.response().body() {
+        ResponseBody::Text(json) => serde_json::from_str(json).unwrap(),
+        _ => panic!("Unexpected body type"),
+    }
+}
+
+#[test]
+fn album_comment_uses_commenter_display_name() {
+    let user_id = Uuid::parse_str(TEST_USER_ID_STR).unwrap();
+    let comment = post_album_comment(vec![UserSettings {
+        user_id,
+        display_name: "Trip Photographer".to_string(),
+        avatar_url: None,
+        theme: "light".to_string(),
+        language: "en".to_string(),
+        timezone: "UTC".to_string(),
+        email_digest: false,
+        created_at: chrono::Utc::now(),
+    }]);
+
+    assert_eq!(comment.user_id, Some(user_id));
+    assert_eq!(comment.user_display_name.as_deref(), Some("Trip Photographer"));
+    assert_eq!(comment.body, "Lovely trip");
+}
+
+#[test]
+fn album_comment_falls_back_to_anonymous_without_settings() {
+    let comment = post_album_comment(Vec::new());
+
+    assert_eq!(comment.user_display_name.as_deref(), Some("Anonymous"));
+}
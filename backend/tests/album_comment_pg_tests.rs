@@ -0,0 +1,86 @@
+#![cfg(feature = "postgres")]
+
+mod support;
+
+use chrono::{Duration, Utc};
+use nimble_photos::entities::AlbumComment;
+use nimble_photos::repositories::AlbumCommentExtensions;
+use nimble_web::Repository;
+use support::PgTestDatabase;
+use uuid::Uuid;
+
+async fn comment(
+    comments: &Repository<AlbumComment>,
+    album_id: Uuid,
+    author: Uuid,
+    body: &str,
+    hidden: bool,
+    age: i64,
+) {
+    let comment = AlbumComment {
+        id: Uuid::new_v4(),
+        created_at: Some(Utc::now() - Duration::minutes(age)),
+        hidden,
+        ..AlbumComment::new(album_id, author, "Author".to_string(), body.to_string())
+    };
+    comments.insert(comment).await.expect("comment should be inserted");
+}
+
+fn bodies(page: &nimble_web::Page<AlbumComment>) -> Vec<&str> {
+    page.items.iter().filter_map(|comment| comment.body.as_deref()).collect()
+}
+
+#[tokio::test]
+async fn hidden_comments_are_counted_only_for_admins_and_their_author() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let comments = database.repository::<AlbumComment>();
+    let album_id = Uuid::new_v4();
+    let author = Uuid::new_v4();
+    let other = Uuid::new_v4();
+    comment(&comments, album_id, other, "first", false, 50).await;
+    comment(&comments, album_id, author, "hidden by author", true, 40).await;
+    comment(&comments, album_id, other, "hidden by other", true, 30).await;
+    comment(&comments, album_id, author, "latest", false, 10).await;
+    comment(&comments, Uuid::new_v4(), author, "other album", false, 5).await;
+
+    let admin = comments.visible_album_comments(album_id, Some(other), true, 1, 10).await.unwrap();
+    assert_eq!(admin.total, 4);
+    assert_eq!(bodies(&admin), vec!["latest", "hidden by other", "hidden by author", "first"]);
+
+    let own = comments.visible_album_comments(album_id, Some(author), false, 1, 10).await.unwrap();
+    assert_eq!(own.total, 3);
+    assert_eq!(bodies(&own), vec!["latest", "hidden by author", "first"]);
+
+    let stranger = comments.visible_album_comments(album_id, Some(Uuid::new_v4()), false, 1, 10).await.unwrap();
+    assert_eq!(stranger.total, 2);
+    assert_eq!(bodies(&stranger), vec!["latest", "first"]);
+
+    let anonymous = comments.visible_album_comments(album_id, None, false, 1, 10).await.unwrap();
+    assert_eq!(anonymous.total, 2);
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn comment_pages_report_the_requested_page() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let comments = database.repository::<AlbumComment>();
+    let album_id = Uuid::new_v4();
+    let author = Uuid::new_v4();
+    for (age, body) in ["e", "d", "c", "b", "a"].iter().enumerate() {
+        comment(&comments, album_id, author, body, false, age as i64).await;
+    }
+    comment(&comments, album_id, Uuid::new_v4(), "hidden", true, 2).await;
+
+    let second = comments.visible_album_comments(album_id, None, false, 2, 2).await.unwrap();
+    assert_eq!((second.page, second.page_size, second.total), (2, 2, 5));
+    assert_eq!(bodies(&second), vec!["c", "b"]);
+
+    let past_end = comments.visible_album_comments(album_id, None, false, 4, 2).await.unwrap();
+    assert!(past_end.items.is_empty());
+    assert_eq!(past_end.total, 5);
+    database.drop().await;
+}
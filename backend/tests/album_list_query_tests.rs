@@ -0,0 +1,36 @@
+use nimble_photos::entities::SortDirection;
+use nimble_photos::models::{AlbumListQuery, AlbumSortField};
+
+#[test]
+fn album_list_query_defaults_to_newest_first() {
+    let query = AlbumListQuery::parse(None, None, None).expect("default query should parse");
+
+    assert_eq!(query.sort, AlbumSortField::CreateDate);
+    assert_eq!(query.direction(), SortDirection::Desc);
+    assert_eq!(query.search_pattern(), None);
+    assert_eq!(query.order_clause("m"), "m.create_date DESC NULLS LAST, m.id DESC");
+}
+
+#[test]
+fn album_list_query_parses_sort_fields() {
+    let by_title = AlbumListQuery::parse(None, Some("title"), None).unwrap();
+    let by_count = AlbumListQuery::parse(None, Some("photoCount"), Some("asc")).unwrap();
+
+    assert_eq!(by_title.order_clause("m"), "lower(m.name) ASC NULLS LAST, m.id ASC");
+    assert_eq!(by_count.order_clause("m"), "m.photo_count ASC NULLS LAST, m.id ASC");
+}
+
+#[test]
+fn album_list_query_escapes_search_wildcards() {
+    let query = AlbumListQuery::parse(Some("  Été 100%_ "), None, None).unwrap();
+
+    assert_eq!(query.search.as_deref(), Some("Été 100%_"));
+    assert_eq!(query.search_pattern().as_deref(), Some("%Été 100\\%\\_%"));
+    assert_eq!(AlbumListQuery::parse(Some("   "), None, None).unwrap().search, None);
+}
+
+#[test]
+fn album_list_query_rejects_unknown_sort() {
+    assert_eq!(AlbumListQuery::parse(None, Some("name; DROP TABLE albums"), None), Err(AlbumListQuery::INVALID_SORT));
+    assert_eq!(AlbumListQuery::parse(None, Some("title"), Some("up")), Err(AlbumListQuery::INVALID_DIRECTION));
+}
@@ -0,0 +1,44 @@
+use nimble_photos::services::{AlbumMembershipCache, AlbumMembershipStamp};
+use uuid::Uuid;
+
+fn ids(count: usize) -> Vec<Uuid> {
+    (0..count).map(|_| Uuid::new_v4()).collect()
+}
+
+#[test]
+fn cached_membership_is_returned_only_for_the_same_stamp() {
+    let cache = AlbumMembershipCache::new();
+    let album_id = Uuid::new_v4();
+    let stamp = AlbumMembershipStamp { version: 3, members: 4 };
+    let members = ids(4);
+
+    cache.put(album_id, stamp, members.clone());
+
+    assert_eq!(cache.get(album_id, stamp).as_deref(), Some(&members));
+    assert!(cache.get(album_id, AlbumMembershipStamp { version: 4, members: 4 }).is_none());
+    assert!(cache.get(album_id, AlbumMembershipStamp { version: 3, members: 5 }).is_none());
+    assert!(cache.get(Uuid::new_v4(), stamp).is_none());
+}
+
+#[test]
+fn invalidate_drops_the_album_entry() {
+    let cache = AlbumMembershipCache::new();
+    let album_id = Uuid::new_v4();
+    let stamp = AlbumMembershipStamp { version: 1, members: 2 };
+    cache.put(album_id, stamp, ids(2));
+
+    cache.invalidate(album_id);
+
+    assert!(cache.get(album_id, stamp).is_none());
+}
+
+#[test]
+fn page_ids_slices_at_most_page_size() {
+    let members = ids(5);
+
+    assert_eq!(AlbumMembershipCache::page_ids(&members, 1, 2), &members[0..2]);
+    assert_eq!(AlbumMembershipCache::page_ids(&members, 3, 2), &members[4..5]);
+    assert!(AlbumMembershipCache::page_ids(&members, 4, 2).is_empty());
+    assert_eq!(AlbumMembershipCache::page_ids(&members, 0, 10), &members[..]);
+    assert!(AlbumMembershipCache::page_ids(&members, 1, 0).is_empty());
+}
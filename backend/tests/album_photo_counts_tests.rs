@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+
+use nimble_photos::entities::AlbumPhoto;
+use nimble_photos::repositories::{AlbumPhotoCounts, AlbumPhotoExtensions};
+use nimble_web::Repository;
+use uuid::Uuid;
+
+#[cfg(not(feature = "postgres"))]
+#[tokio::test]
+async fn photo_counts_group_album_photos_in_memory() {
+    let album_photos = nimble_web::MemoryRepository::<AlbumPhoto>::new();
+    let first_album = Uuid::new_v4();
+    let second_album = Uuid::new_v4();
+    album_photos.seed(vec![
+        AlbumPhoto::new(first_album, Uuid::new_v4()),
+        AlbumPhoto::new(first_album, Uuid::new_v4()),
+        AlbumPhoto::new(second_album, Uuid::new_v4()),
+        AlbumPhoto::new(Uuid::new_v4(), Uuid::new_v4()),
+    ]);
+    let repository = Repository::new(Box::new(album_photos));
+
+    let counts = repository
+        .photo_counts(&[first_album, second_album, Uuid::new_v4()], &HashSet::new())
+        .await
+        .expect("album counts should load");
+
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts[&first_album], AlbumPhotoCounts { total: 2, visible: 2 });
+    assert_eq!(counts[&second_album], AlbumPhotoCounts { total: 1, visible: 1 });
+}
+
+#[cfg(feature = "postgres")]
+#[tokio::test]
+async fn photo_counts_report_visible_photos_per_album() {
+    use nimble_photos::dtos::TagRef;
+    use nimble_photos::entities::{Photo, Tag, ensure_supporting_schema};
+    use nimble_photos::repositories::TagRepositoryExtensions;
+    use nimble_web::PostgresProvider;
+    use nimble_web::data::query::Value;
+
+    let Some(url) = std::env::var("DATABASE_URL").ok() else {
+        return;
+    };
+    let Ok(pool) = sqlx::PgPool::connect(&url).await else {
+        return;
+    };
+    ensure_supporting_schema(&pool).await.expect("schema should be ensured");
+    let photos = Repository::<Photo>::new(Box::new(PostgresProvider::<Photo>::new(pool.clone())));
+    let tags = Repository::<Tag>::new(Box::new(PostgresProvider::<Tag>::new(pool.clone())));
+    let album_photos = Repository::<AlbumPhoto>::new(Box::new(PostgresProvider::<AlbumPhoto>::new(pool)));
+
+    let mixed_album = Uuid::new_v4();
+    let hidden_album = Uuid::new_v4();
+    let visible = Photo { id: Uuid::new_v4(), ..Photo::default() };
+    let private = Photo { id: Uuid::new_v4(), ..Photo::default() };
+    for photo in [&visible, &private] {
+        let photo = Photo {
+            name: format!("{}.jpg", photo.id),
+            path: format!("album-counts/{}.jpg", photo.id),
+            ..photo.clone()
+        };
+        photos.insert(photo).await.expect("photo should be inserted");
+    }
+    for (album_id, photo_id) in [(mixed_album, visible.id), (mixed_album, private.id), (hidden_album, private.id)] {
+        album_photos.insert(AlbumPhoto::new(album_id, photo_id)).await.expect("album photo should be inserted");
+    }
+    let hidden_tag = format!("Private {}", Uuid::new_v4());
+    tags.set_photo_tags(private.id, &[TagRef::Name(hidden_tag.clone())], true).await.expect("tag should be attached");
+
+    let counts = album_photos
+        .photo_counts(&[mixed_album, hidden_album], &HashSet::from([hidden_tag.to_lowercase()]))
+        .await
+        .expect("album counts should load");
+
+    assert_eq!(counts[&mixed_album], AlbumPhotoCounts { total: 2, visible: 1 });
+    assert_eq!(counts[&hidden_album], AlbumPhotoCounts { total: 1, visible: 0 });
+
+    for album_id in [mixed_album, hidden_album] {
+        album_photos.delete_by("album_id", Value::Uuid(album_id)).await.ok();
+    }
+    photos.delete(&visible.id).await.expect("photo should be deleted");
+    photos.delete(&private.id).await.expect("photo should be deleted");
+}
@@ -0,0 +1,62 @@
+use nimble_photos::models::AlbumPhotoOrder;
+use uuid::Uuid;
+
+fn ids(count: usize) -> Vec<Uuid> {
+    (0..count).map(|_| Uuid::new_v4()).collect()
+}
+
+#[test]
+fn validate_accepts_permutation_of_membership() {
+    let current = ids(3);
+    let requested = vec![current[2], current[0], current[1]];
+
+    assert_eq!(AlbumPhotoOrder::validate(&current, &requested), Ok(()));
+}
+
+#[test]
+fn validate_rejects_missing_duplicate_or_foreign_ids() {
+    let current = ids(3);
+
+    assert_eq!(AlbumPhotoOrder::validate(&current, &current[..2]), Err(AlbumPhotoOrder::NOT_A_PERMUTATION));
+    assert_eq!(
+        AlbumPhotoOrder::validate(&current, &[current[0], current[0], current[1]]),
+        Err(AlbumPhotoOrder::NOT_A_PERMUTATION)
+    );
+    assert_eq!(
+        AlbumPhotoOrder::validate(&current, &[current[0], current[1], Uuid::new_v4()]),
+        Err(AlbumPhotoOrder::NOT_A_PERMUTATION)
+    );
+}
+
+#[test]
+fn move_before_places_photo_ahead_of_anchor() {
+    let current = ids(4);
+
+    let forward = AlbumPhotoOrder::move_before(&current, current[0], Some(current[3])).expect("move should succeed");
+    let backward = AlbumPhotoOrder::move_before(&current, current[3], Some(current[1])).expect("move should succeed");
+
+    assert_eq!(forward, vec![current[1], current[2], current[0], current[3]]);
+    assert_eq!(backward, vec![current[0], current[3], current[1], current[2]]);
+}
+
+#[test]
+fn move_without_anchor_appends_and_self_anchor_keeps_order() {
+    let current = ids(3);
+
+    let appended = AlbumPhotoOrder::move_before(&current, current[0], None).expect("move should succeed");
+    let unchanged = AlbumPhotoOrder::move_before(&current, current[1], Some(current[1])).expect("move should succeed");
+
+    assert_eq!(appended, vec![current[1], current[2], current[0]]);
+    assert_eq!(unchanged, current);
+}
+
+#[test]
+fn move_rejects_ids_outside_album() {
+    let current = ids(2);
+
+    assert_eq!(AlbumPhotoOrder::move_before(&current, Uuid::new_v4(), None), Err(AlbumPhotoOrder::UNKNOWN_PHOTO));
+    assert_eq!(
+        AlbumPhotoOrder::move_before(&current, current[0], Some(Uuid::new_v4())),
+        Err(AlbumPhotoOrder::UNKNOWN_ANCHOR)
+    );
+}
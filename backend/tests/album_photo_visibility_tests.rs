@@ -0,0 +1,95 @@
+#![cfg(feature = "postgres")]
+
+use std::collections::HashSet;
+
+use nimble_photos::dtos::TagRef;
+use nimble_photos::entities::{AlbumPhoto, Photo, Tag, ensure_supporting_schema};
+use nimble_photos::repositories::{AlbumPhotoExtensions, PhotoRepositoryExtensions, TagRepositoryExtensions};
+use nimble_web::data::query::Value;
+use nimble_web::{PostgresProvider, Repository};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+struct Repositories {
+    photos: Repository<Photo>,
+    tags: Repository<Tag>,
+    album_photos: Repository<AlbumPhoto>,
+}
+
+async fn setup() -> Option<Repositories> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    let pool = PgPool::connect(&url).await.ok()?;
+    ensure_supporting_schema(&pool).await.ok()?;
+
+    Some(Repositories {
+        photos: Repository::<Photo>::new(Box::new(PostgresProvider::<Photo>::new(pool.clone()))),
+        tags: Repository::<Tag>::new(Box::new(PostgresProvider::<Tag>::new(pool.clone()))),
+        album_photos: Repository::<AlbumPhoto>::new(Box::new(PostgresProvider::<AlbumPhoto>::new(pool))),
+    })
+}
+
+async fn insert_album_photo(repos: &Repositories, album_id: Uuid) -> Photo {
+    let id = Uuid::new_v4();
+    let photo = Photo { id, name: format!("{}.jpg", id), path: format!("album-tests/{}.jpg", id), ..Photo::default() };
+    repos.photos.insert(photo.clone()).await.expect("photo should be inserted");
+    repos.album_photos.insert(AlbumPhoto::new(album_id, id)).await.expect("album photo should be inserted");
+    photo
+}
+
+fn ids(photos: &[Photo]) -> HashSet<Uuid> {
+    photos.iter().map(|photo| photo.id).collect()
+}
+
+#[tokio::test]
+async fn album_photos_hide_viewer_hidden_tags_from_viewers_only() {
+    let Some(repos) = setup().await else {
+        return;
+    };
+    let album_id = Uuid::new_v4();
+    let visible = insert_album_photo(&repos, album_id).await;
+    let private = insert_album_photo(&repos, album_id).await;
+    let hidden_tag = format!("Private {}", Uuid::new_v4());
+    repos
+        .tags
+        .set_photo_tags(private.id, &[TagRef::Name(hidden_tag.clone())], true)
+        .await
+        .expect("hidden tag should be attached");
+    let hidden_tags = HashSet::from([hidden_tag.to_lowercase()]);
+
+    let viewer_page = repos.photos.photos_in_album(album_id, 1, 50, &hidden_tags).await.expect("viewer page");
+    let admin_page = repos.photos.photos_in_album(album_id, 1, 50, &HashSet::new()).await.expect("admin page");
+
+    assert_eq!(viewer_page.total, 1);
+    assert_eq!(ids(&viewer_page.items), HashSet::from([visible.id]));
+    assert_eq!(admin_page.total, 2);
+    assert_eq!(ids(&admin_page.items), HashSet::from([visible.id, private.id]));
+
+    repos.album_photos.delete_by("album_id", Value::Uuid(album_id)).await.ok();
+    repos.photos.delete(&visible.id).await.expect("photo should be deleted");
+    repos.photos.delete(&private.id).await.expect("photo should be deleted");
+}
+
+#[tokio::test]
+async fn album_photos_follow_stored_order() {
+    let Some(repos) = setup().await else {
+        return;
+    };
+    let album_id = Uuid::new_v4();
+    let first = insert_album_photo(&repos, album_id).await;
+    let second = insert_album_photo(&repos, album_id).await;
+    let third = insert_album_photo(&repos, album_id).await;
+    let order = vec![third.id, first.id, second.id];
+
+    let updated = repos.album_photos.set_album_photo_order(album_id, &order).await.expect("order should be saved");
+    let stored = repos.album_photos.album_photo_order(album_id).await.expect("order should load");
+    let page = repos.photos.photos_in_album(album_id, 1, 50, &HashSet::new()).await.expect("album page");
+
+    assert_eq!(updated, 3);
+    assert_eq!(stored, order);
+    assert_eq!(page.items.iter().map(|photo| photo.id).collect::<Vec<_>>(), order);
+
+    repos.album_photos.delete_by("album_id", Value::Uuid(album_id)).await.ok();
+    for photo in [first, second, third] {
+        repos.photos.delete(&photo.id).await.expect("photo should be deleted");
+    }
+}
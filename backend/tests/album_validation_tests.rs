@@ -0,0 +1,82 @@
+use chrono::{TimeZone, Utc};
+use nimble_photos::entities::{Album, AlbumKind};
+use nimble_photos::repositories::Validate;
+use serde_json::json;
+use uuid::Uuid;
+
+fn album(name: &str) -> Album {
+    Album {
+        id: Uuid::new_v4(),
+        parent_id: None,
+        name: name.to_string(),
+        create_date: None,
+        description: None,
+        category: None,
+        kind: AlbumKind::Manual,
+        thumbnail_hash: None,
+        sort_order: 0,
+        image_count: None,
+        version: 1,
+        start_date: None,
+        end_date: None,
+        dates_overridden: false,
+        location_name: None,
+        reset_dates: false,
+    }
+}
+
+#[test]
+fn album_accepts_rich_metadata_within_limits() {
+    let mut album = album("Summer trip");
+    album.description = Some("## Day one\n\nWe walked along the *coast*.".to_string());
+    album.location_name = Some("Big Sur".to_string());
+    album.start_date = Some(Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap());
+    album.end_date = Some(Utc.with_ymd_and_hms(2024, 7, 14, 0, 0, 0).unwrap());
+
+    assert!(album.validate().is_ok());
+}
+
+#[test]
+fn album_rejects_blank_name_and_oversized_text() {
+    let mut album = album(" ");
+    album.description = Some("x".repeat(Album::MAX_DESCRIPTION_LENGTH + 1));
+    album.location_name = Some("x".repeat(Album::MAX_LOCATION_NAME_LENGTH + 1));
+
+    let errors = album.validate().unwrap_err();
+    let fields = errors.iter().map(|error| error.field.as_str()).collect::<Vec<_>>();
+
+    assert_eq!(fields, vec!["name", "description", "locationName"]);
+}
+
+#[test]
+fn album_rejects_end_date_before_start_date() {
+    let mut album = album("Backwards");
+    album.start_date = Some(Utc.with_ymd_and_hms(2024, 7, 14, 0, 0, 0).unwrap());
+    album.end_date = Some(Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap());
+
+    let errors = album.validate().unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "endDate");
+}
+
+#[test]
+fn album_reads_reset_flag_but_never_echoes_it() {
+    let parsed: Album = serde_json::from_value(json!({
+        "id": Uuid::new_v4(),
+        "name": "Reset me",
+        "kind": "manual",
+        "sortOrder": 0,
+        "startDate": "2024-07-01T00:00:00Z",
+        "locationName": "Lisbon",
+        "resetDates": true
+    }))
+    .unwrap();
+
+    assert!(parsed.reset_dates);
+    assert_eq!(parsed.location_name.as_deref(), Some("Lisbon"));
+
+    let value = serde_json::to_value(&parsed).unwrap();
+    assert!(value.get("resetDates").is_none());
+    assert_eq!(value["datesOverridden"], json!(false));
+}
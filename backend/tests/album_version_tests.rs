@@ -0,0 +1,130 @@
+#![cfg(feature = "postgres")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use nimble_photos::controllers::album_controller::AlbumController;
+use nimble_photos::entities::{Album, AlbumKind, AlbumPhoto, ensure_supporting_schema};
+use nimble_photos::repositories::AlbumExtensions;
+use nimble_web::{
+    AuthenticationMiddleware, AuthorizationMiddleware, Claims, Configuration, ControllerInvokerMiddleware,
+    DefaultRouter, EndpointExecutionMiddleware, EndpointRegistry, HttpContext, HttpRequest, JwtTokenService, Pipeline,
+    PostgresProvider, Repository, RequestBody, ResponseBody, Router, RoutingMiddleware, ServiceContainer, TokenService,
+    UserIdentity,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup() -> Option<PgPool> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    let pool = PgPool::connect(&url).await.ok()?;
+    ensure_supporting_schema(&pool).await.ok()?;
+    Some(pool)
+}
+
+fn album(id: Uuid) -> Album {
+    Album {
+        id,
+        parent_id: None,
+        name: format!("Version test {}", id),
+        create_date: Some(chrono::Utc::now()),
+        description: None,
+        category: None,
+        kind: AlbumKind::Manual,
+        thumbnail_hash: None,
+        sort_order: 0,
+        image_count: None,
+        version: 1,
+        start_date: None,
+        end_date: None,
+        dates_overridden: false,
+        location_name: None,
+        reset_dates: false,
+    }
+}
+
+async fn add_photos(pool: &PgPool, album_id: Uuid, version: i32) -> (u16, serde_json::Value) {
+    let mut registry = EndpointRegistry::new();
+    registry.register::<AlbumController>();
+    let mut router = DefaultRouter::new();
+    for route in registry.routes() {
+        router.add_route(route.clone());
+    }
+
+    let album_pool = pool.clone();
+    let album_photo_pool = pool.clone();
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<Repository<Album>, _>(move |_| {
+        Repository::new(Box::new(PostgresProvider::<Album>::new(album_pool.clone())))
+    });
+    container.register_singleton::<Repository<AlbumPhoto>, _>(move |_| {
+        Repository::new(Box::new(PostgresProvider::<AlbumPhoto>::new(album_photo_pool.clone())))
+    });
+    container.register_singleton::<Arc<dyn TokenService>, _>(move |_| {
+        Arc::new(JwtTokenService::new("secret".to_string(), "issuer".to_string())) as Arc<dyn TokenService>
+    });
+    let services = container.build();
+
+    let token_service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+    let identity = UserIdentity::new(Uuid::new_v4().to_string(), Claims::new());
+    let token = TokenService::create_access_token(&token_service, &identity).unwrap();
+
+    let mut request = HttpRequest::new("POST", &format!("/api/albums/{}/photos", album_id));
+    let header_val = format!("Bearer {}", token);
+    request.headers_mut().insert("authorization", header_val.as_str());
+    request.set_body(RequestBody::Text(serde_json::json!({ "photoIds": [], "version": version }).to_string()));
+
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+    let mut pipeline = Pipeline::new();
+    pipeline.add(RoutingMiddleware::new(router));
+    pipeline.add(AuthenticationMiddleware::new());
+    pipeline.add(AuthorizationMiddleware::new());
+    pipeline.add(ControllerInvokerMiddleware::new(Arc::new(registry)));
+    pipeline.add(EndpointExecutionMiddleware::new());
+    let _ = pipeline.run(&mut context);
+
+    let body = match context.response().body() {
+        ResponseBody::Text(json) => serde_json::from_str(json).unwrap_or_default(),
+        _ => serde_json::Value::Null,
+    };
+    (context.response().status(), body)
+}
+
+#[tokio::test]
+async fn bump_album_version_only_succeeds_for_current_version() {
+    let Some(pool) = setup().await else {
+        return;
+    };
+    let albums = Repository::<Album>::new(Box::new(PostgresProvider::<Album>::new(pool)));
+    let id = Uuid::new_v4();
+    albums.insert(album(id)).await.expect("album should be inserted");
+
+    let first = albums.bump_album_version(id, 1).await.expect("first bump");
+    let second = albums.bump_album_version(id, 1).await.expect("second bump");
+
+    assert_eq!(first, Some(2));
+    assert_eq!(second, None);
+
+    albums.delete(&id).await.expect("album should be deleted");
+}
+
+#[tokio::test]
+async fn interleaved_membership_edits_conflict() {
+    let Some(pool) = setup().await else {
+        return;
+    };
+    let albums = Repository::<Album>::new(Box::new(PostgresProvider::<Album>::new(pool.clone())));
+    let id = Uuid::new_v4();
+    albums.insert(album(id)).await.expect("album should be inserted");
+
+    let (first_status, first_body) = add_photos(&pool, id, 1).await;
+    let (second_status, second_body) = add_photos(&pool, id, 1).await;
+
+    assert_eq!(first_status, 200);
+    assert_eq!(first_body["version"], 2);
+    assert_eq!(second_status, 409);
+    assert_eq!(second_body["id"], id.to_string());
+    assert_eq!(second_body["version"], 2);
+
+    albums.delete(&id).await.expect("album should be deleted");
+}
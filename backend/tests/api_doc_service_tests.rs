@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use nimble_photos::controllers::auth_controller::AuthController;
+use nimble_photos::controllers::photo_controller::PhotoController;
+use nimble_photos::middlewares::ApiDocsMiddleware;
+use nimble_photos::models::{AppConfig, AppEnvironment};
+use nimble_photos::services::{ApiAnnotation, ApiDocService};
+use nimble_web::{Configuration, EndpointRegistry, HttpContext, HttpRequest, Pipeline, ResponseBody, ServiceContainer};
+
+fn doc_service() -> ApiDocService {
+    let mut registry = EndpointRegistry::new();
+    registry.register::<AuthController>();
+    registry.register::<PhotoController>();
+    ApiDocService::from_routes(registry.routes().iter())
+}
+
+fn request_docs(path: &str, environment: AppEnvironment) -> (u16, String) {
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<ApiDocService, _>(|_| doc_service().with_default_annotations());
+    container.register_singleton::<AppConfig, _>(move |_| AppConfig { environment, ..AppConfig::default() });
+    let services = container.build();
+
+    let request = HttpRequest::new("GET", path);
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(ApiDocsMiddleware::new());
+    let _ = pipeline.run(&mut context);
+
+    let body = match context.response().body() {
+        ResponseBody::Text(text) => text.clone(),
+        _ => String::new(),
+    };
+    (context.response().status(), body)
+}
+
+#[test]
+fn document_includes_registered_routes() {
+    let document = doc_service().document();
+
+    assert_eq!(document["openapi"], "3.0.3");
+    assert!(document["paths"]["/api/auth/login"]["post"].is_object());
+    assert!(document["paths"]["/api/photos/tags"]["put"].is_object());
+    assert_eq!(document["paths"]["/api/photos/comments/{id}"]["post"]["parameters"][0]["name"], "id");
+    assert_eq!(document["paths"]["/api/photos/comments/{id}"]["post"]["tags"][0], "photos");
+}
+
+#[test]
+fn document_marks_protected_routes_with_bearer_security() {
+    let document = doc_service().document();
+
+    assert_eq!(document["paths"]["/api/auth/me"]["get"]["security"][0]["bearerAuth"], serde_json::json!([]));
+    assert!(document["paths"]["/api/auth/login"]["post"].get("security").is_none());
+}
+
+#[test]
+fn annotations_add_summary_and_schemas() {
+    let document = doc_service().with_default_annotations().document();
+    let login = &document["paths"]["/api/auth/login"]["post"];
+
+    assert_eq!(login["summary"], "Sign in with email and password");
+    assert_eq!(
+        login["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/LoginRequest"
+    );
+    assert!(document["components"]["schemas"]["LoginRequest"]["properties"]["email"].is_object());
+    assert!(document["components"]["schemas"]["ApiErrorBody"].is_object());
+}
+
+#[test]
+fn add_operation_ignores_duplicates() {
+    let mut service = ApiDocService::new();
+    service.add_operation("get", "/api/things", None).add_operation("GET", "/api/things", None);
+    service.annotate("GET", "/api/things", ApiAnnotation::new("List things"));
+
+    assert_eq!(service.operations().len(), 1);
+    assert_eq!(service.document()["paths"]["/api/things"]["get"]["operationId"], "getThings");
+}
+
+#[test]
+fn route_template_prefers_literal_segments_and_ignores_unknown_paths() {
+    let mut service = ApiDocService::new();
+    service
+        .add_operation("GET", "/api/things/{id}", None)
+        .add_operation("GET", "/api/things/recent", None)
+        .add_operation("DELETE", "/api/things/{id}", None);
+
+    assert_eq!(service.route_template("GET", "/api/things/3f2b9c"), Some("/api/things/{id}"));
+    assert_eq!(service.route_template("GET", "/api/things/recent"), Some("/api/things/recent"));
+    assert_eq!(service.route_template("delete", "/api/things/abc/"), Some("/api/things/{id}"));
+    assert_eq!(service.route_template("GET", "/api/things/abc/extra"), None);
+    assert_eq!(service.route_template("POST", "/api/things/abc"), None);
+    assert_eq!(service.route_template("GET", "/assets/main.4f1c.js"), None);
+}
+
+#[test]
+fn openapi_json_is_served_in_development() {
+    let (status, body) = request_docs(ApiDocService::OPENAPI_PATH, AppEnvironment::Development);
+    let document: serde_json::Value = serde_json::from_str(&body).expect("document should be json");
+
+    assert_eq!(status, 200);
+    assert!(document["paths"].is_object());
+}
+
+#[test]
+fn docs_page_points_at_openapi_json() {
+    let (status, body) = request_docs(ApiDocService::DOCS_PATH, AppEnvironment::Development);
+
+    assert_eq!(status, 200);
+    assert!(body.contains(ApiDocService::OPENAPI_PATH));
+}
+
+#[test]
+fn docs_are_hidden_from_anonymous_users_in_production() {
+    let (status, _) = request_docs(ApiDocService::OPENAPI_PATH, AppEnvironment::Production);
+
+    assert_eq!(status, 404);
+}
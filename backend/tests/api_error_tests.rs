@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use nimble_photos::controllers::photo_controller::PhotoController;
+use nimble_photos::dtos::ApiErrorBody;
+use nimble_photos::entities::{ExifModel, Photo};
+use nimble_photos::middlewares::ErrorResponseMiddleware;
+use nimble_photos::models::ApiError;
+use nimble_web::{
+    AuthenticationMiddleware, AuthorizationMiddleware, Configuration, ControllerInvokerMiddleware, DefaultRouter,
+    EndpointExecutionMiddleware, EndpointRegistry, HttpContext, HttpRequest, MemoryRepository, Pipeline, PipelineError,
+    Repository, ResponseBody, Router, RoutingMiddleware, ServiceContainer,
+};
+
+fn get_metadata(photos: Vec<Photo>, id: &str, format_errors: bool) -> (u16, String) {
+    let mut registry = EndpointRegistry::new();
+    registry.register::<PhotoController>();
+
+    let mut router = DefaultRouter::new();
+    for route in registry.routes() {
+        router.add_route(route.clone());
+    }
+
+    let photo_repo = MemoryRepository::<Photo>::new();
+    photo_repo.seed(photos);
+
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<Repository<Photo>, _>(move |_| Repository::new(Box::new(photo_repo.clone())));
+    container.register_singleton::<Repository<ExifModel>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<ExifModel>::new()))
+    });
+    let services = container.build();
+
+    let request = HttpRequest::new("GET", &format!("/api/photos/metadata/{}", id));
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    if format_errors {
+        pipeline.add(ErrorResponseMiddleware::new());
+    }
+    pipeline.add(RoutingMiddleware::new(router));
+    pipeline.add(AuthenticationMiddleware::new());
+    pipeline.add(AuthorizationMiddleware::new());
+    pipeline.add(ControllerInvokerMiddleware::new(Arc::new(registry)));
+    pipeline.add(EndpointExecutionMiddleware::new());
+    let _ = pipeline.run(&mut context);
+
+    let response = match context.response().body() {
+        ResponseBody::Text(text) => text.clone(),
+        _ => String::new(),
+    };
+    (context.response().status(), response)
+}
+
+#[test]
+fn api_error_maps_variants_to_status_and_code() {
+    let cases = [
+        (ApiError::bad_request("bad"), 400, "bad_request"),
+        (ApiError::forbidden("no"), 403, "forbidden"),
+        (ApiError::not_found("gone"), 404, "not_found"),
+        (ApiError::conflict("again"), 409, "conflict"),
+        (ApiError::internal("boom"), 500, "internal"),
+        (ApiError::unavailable("down"), 503, "unavailable"),
+    ];
+
+    for (error, status, code) in cases {
+        assert_eq!(error.status(), status);
+        assert_eq!(error.code(), code);
+    }
+}
+
+#[test]
+fn api_error_converts_into_pipeline_error_with_message() {
+    let error: PipelineError = ApiError::not_found("Photo not found").into();
+
+    assert_eq!(error.message(), "Photo not found");
+}
+
+#[test]
+fn api_error_body_omits_missing_details() {
+    let body = ApiError::bad_request("Invalid uuid: abc").body();
+    let json = serde_json::to_value(&body).unwrap();
+
+    assert_eq!(json, serde_json::json!({ "code": "bad_request", "message": "Invalid uuid: abc" }));
+
+    let json = serde_json::to_value(body.with_details(serde_json::json!({ "field": "id" }))).unwrap();
+    assert_eq!(json["details"]["field"], "id");
+}
+
+#[test]
+fn api_error_from_status_falls_back_to_internal() {
+    assert_eq!(ApiError::from_status(404).status(), 404);
+    assert_eq!(ApiError::from_status(418).status(), 500);
+}
+
+#[test]
+fn missing_photo_metadata_returns_not_found() {
+    let (status, _) = get_metadata(Vec::new(), &Uuid::new_v4().to_string(), false);
+
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn invalid_photo_id_returns_bad_request() {
+    let (status, _) = get_metadata(Vec::new(), "not-a-uuid", false);
+
+    assert_eq!(status, 400);
+}
+
+#[test]
+fn existing_photo_metadata_returns_ok() {
+    let photo = Photo::default();
+    let (status, _) = get_metadata(vec![photo.clone()], &photo.id.to_string(), false);
+
+    assert_eq!(status, 200);
+}
+
+#[test]
+fn error_response_middleware_writes_json_body() {
+    let (status, body) = get_metadata(Vec::new(), "not-a-uuid", true);
+    let body: ApiErrorBody = serde_json::from_str(&body).expect("error body should be json");
+
+    assert_eq!(status, 400);
+    assert_eq!(body.code, "bad_request");
+    assert_eq!(body.message, "Invalid uuid: not-a-uuid");
+    assert!(body.details.is_none());
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use nimble_photos::models::{AppConfig, AppEnvironment};
+
+fn config(values: &[(&str, &str)]) -> Result<AppConfig, nimble_photos::models::AppConfigError> {
+    let values: HashMap<String, String> = values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    AppConfig::from_lookup(|key| values.get(key).cloned())
+}
+
+#[test]
+fn empty_configuration_uses_defaults() {
+    let config = config(&[]).unwrap();
+
+    assert_eq!(config.environment, AppEnvironment::Development);
+    assert_eq!(config.upload_max_file_size_bytes, AppConfig::DEFAULT_UPLOAD_MAX_FILE_SIZE_BYTES);
+    assert_eq!(config.jwt_issuer, "nimble");
+    assert_eq!(config.thumbnail_base_path, PathBuf::from("./.thumbnails"));
+    assert_eq!(config.pipeline_max_attempts, AppConfig::DEFAULT_PIPELINE_MAX_ATTEMPTS);
+    assert!(config.warnings.is_empty());
+}
+
+#[test]
+fn typed_values_are_parsed() {
+    let config = config(&[
+        ("app.environment", "production"),
+        ("background.parallelism", "3"),
+        ("storage.allowSymlinkEscape", "TRUE"),
+        ("albums.autoGapHours", "12"),
+        ("metrics.token", "  "),
+        ("metadata.exiftoolPath", "/usr/bin/exiftool"),
+    ])
+    .unwrap();
+
+    assert_eq!(config.environment, AppEnvironment::Production);
+    assert_eq!(config.background_parallelism, 3);
+    assert!(config.allow_symlink_escape);
+    assert_eq!(config.album_auto_gap_hours, 12);
+    assert_eq!(config.metrics_token, None);
+    assert_eq!(config.exiftool_path, Some(PathBuf::from("/usr/bin/exiftool")));
+}
+
+#[test]
+fn every_invalid_key_is_reported_at_once() {
+    let err = config(&[
+        ("app.environment", "staging"),
+        ("eventbus.capacity", "0"),
+        ("upload.maxFileSizeBytes", "lots"),
+        ("logging.requestBodyOnError", "maybe"),
+    ])
+    .unwrap_err();
+
+    assert_eq!(err.errors.len(), 4);
+    let message = err.to_string();
+    for key in ["app.environment", "eventbus.capacity", "upload.maxFileSizeBytes", "logging.requestBodyOnError"] {
+        assert!(message.contains(key), "{} missing from {}", key, message);
+    }
+}
+
+#[test]
+fn legacy_keys_still_apply_with_a_warning() {
+    let config = config(&[("upload.max_file_size_bytes", "1024"), ("thumbnail.base.path", "/srv/thumbs")]).unwrap();
+
+    assert_eq!(config.upload_max_file_size_bytes, 1024);
+    assert_eq!(config.thumbnail_base_path, PathBuf::from("/srv/thumbs"));
+    assert_eq!(config.warnings.len(), 2);
+    assert!(config.warnings[0].contains("upload.maxFileSizeBytes"));
+}
+
+#[test]
+fn canonical_key_wins_over_legacy_key() {
+    let config = config(&[("upload.maxFileSizeBytes", "2048"), ("upload.max_file_size_bytes", "1024")]).unwrap();
+
+    assert_eq!(config.upload_max_file_size_bytes, 2048);
+    assert!(config.warnings.is_empty());
+}
@@ -0,0 +1,49 @@
+use nimble_photos::services::{AssetRelocationService, FileService};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+const HASH: &str = "0123456789abcdef";
+
+#[test]
+fn asset_hash_accepts_hash_named_files_with_matching_extension() {
+    let upper = HASH.to_ascii_uppercase();
+
+    assert_eq!(
+        AssetRelocationService::asset_hash(Path::new(&format!("ab/{}.webp", HASH)), "webp"),
+        Some(HASH.to_string())
+    );
+    assert_eq!(
+        AssetRelocationService::asset_hash(Path::new(&format!("{}.WEBP", upper)), "webp"),
+        Some(HASH.to_string())
+    );
+}
+
+#[test]
+fn asset_hash_rejects_other_extensions_and_names() {
+    assert_eq!(AssetRelocationService::asset_hash(Path::new(&format!("{}.jpg", HASH)), "webp"), None);
+    assert_eq!(AssetRelocationService::asset_hash(Path::new("cover.webp"), "webp"), None);
+    assert_eq!(AssetRelocationService::asset_hash(Path::new(HASH), "webp"), None);
+}
+
+#[test]
+fn scan_assets_walks_the_hash_layout() {
+    let root = std::env::temp_dir().join(format!("nimble-relocation-{}", Uuid::new_v4()));
+    let thumbnail = FileService::hash_path(root.clone(), HASH, "webp");
+    fs::create_dir_all(thumbnail.parent().unwrap()).unwrap();
+    fs::write(&thumbnail, b"thumb").unwrap();
+    fs::write(root.join("notes.webp"), b"other").unwrap();
+    fs::write(root.join(format!("{}.jpg", HASH)), b"preview").unwrap();
+
+    let assets = AssetRelocationService::scan_assets(&root, "webp").unwrap();
+
+    assert_eq!(assets, vec![(thumbnail, HASH.to_string())]);
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn scan_assets_returns_nothing_for_missing_root() {
+    let root = std::env::temp_dir().join(format!("nimble-relocation-missing-{}", Uuid::new_v4()));
+
+    assert!(AssetRelocationService::scan_assets(&root, "webp").unwrap().is_empty());
+}
@@ -0,0 +1,14 @@
+use nimble_photos::controllers::audit_controller::AuditController;
+use nimble_web::Controller;
+use nimble_web::Policy;
+
+#[test]
+fn routes_require_authenticated() {
+    let routes = AuditController::routes();
+    assert_eq!(routes.len(), 1);
+
+    let list_route = &routes[0];
+    assert_eq!(list_route.route.method(), "GET");
+    assert_eq!(list_route.route.path(), "/api/admin/audit");
+    assert_eq!(list_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+}
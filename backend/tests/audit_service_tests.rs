@@ -0,0 +1,57 @@
+use nimble_photos::entities::AuditLog;
+use nimble_photos::models::{AuditActions, AuditTargets};
+use nimble_photos::services::{AuditLogFilter, AuditService, BackgroundTaskRunner};
+use nimble_web::{MemoryRepository, Repository};
+use std::sync::Arc;
+use tokio::time::{Duration, Instant, sleep};
+use uuid::Uuid;
+
+fn build_service(runner: Arc<BackgroundTaskRunner>) -> AuditService {
+    let provider = MemoryRepository::<AuditLog>::new();
+    AuditService::new(Arc::new(Repository::<AuditLog>::new(Box::new(provider))), runner)
+}
+
+async fn wait_for_entries(service: &AuditService, expected: usize, timeout: Duration) -> usize {
+    let started = Instant::now();
+    loop {
+        let total = service.list(AuditLogFilter::default(), 1, 50).await.expect("list audit entries").items.len();
+        if total >= expected || started.elapsed() >= timeout {
+            return total;
+        }
+        sleep(Duration::from_millis(5)).await;
+    }
+}
+
+#[tokio::test]
+async fn record_writes_entry_through_background_runner() {
+    let runner = Arc::new(BackgroundTaskRunner::new(1));
+    runner.start().expect("failed to start runner");
+    let service = build_service(Arc::clone(&runner));
+
+    let actor = Uuid::new_v4();
+    let target = Uuid::new_v4();
+    service.record(AuditLog::new(Some(actor), AuditActions::PHOTO_DELETE, AuditTargets::PHOTO, target)).await;
+
+    assert_eq!(wait_for_entries(&service, 1, Duration::from_secs(2)).await, 1);
+
+    let entry = service.list(AuditLogFilter::default(), 1, 50).await.unwrap().items.remove(0);
+    assert_eq!(entry.actor_user_id, Some(actor));
+    assert_eq!(entry.action, AuditActions::PHOTO_DELETE);
+    assert_eq!(entry.target_type, AuditTargets::PHOTO);
+    assert_eq!(entry.target_id, Some(target.to_string()));
+
+    runner.stop().await.expect("failed to stop runner");
+}
+
+#[tokio::test]
+async fn record_writes_inline_when_runner_is_stopped() {
+    let runner = Arc::new(BackgroundTaskRunner::new(1));
+    runner.start().expect("failed to start runner");
+    runner.stop().await.expect("failed to stop runner");
+    let service = build_service(runner);
+
+    service.record(AuditLog::new(None, AuditActions::STORAGE_DELETE, AuditTargets::STORAGE, Uuid::new_v4())).await;
+
+    let page = service.list(AuditLogFilter::default(), 1, 50).await.expect("list audit entries");
+    assert_eq!(page.items.len(), 1);
+}
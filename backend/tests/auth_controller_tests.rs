@@ -6,9 +6,9 @@ use uuid::Uuid;
 
 use nimble_photos::controllers::auth_controller::AuthController;
 use nimble_photos::dtos::user_profile_dto::UserProfileDto;
-use nimble_photos::entities::{user::User, user_settings::UserSettings};
-
-use nimble_photos::services::{AuthService, EncryptService};
+use nimble_photos::entities::{RefreshSession, Setting, TwoFactor, user::User, user_settings::UserSettings};
+use nimble_photos::middlewares::ErrorResponseMiddleware;
+use nimble_photos::services::{AuthService, EncryptService, SettingKeys, SettingService, TwoFactorService};
 use nimble_web::AuthenticationMiddleware;
 use nimble_web::AuthorizationMiddleware;
 use nimble_web::Configuration;
@@ -89,8 +89,7 @@ impl DataProvider<User> for InMemoryUserProvider {
 use nimble_web::Claims;
 use nimble_web::UserIdentity;
 
-#[test]
-fn login_returns_token() {
+fn run_login(require_verification: bool) -> HttpContext {
     let mut registry = EndpointRegistry::new();
     registry.register::<AuthController>();
 
@@ -125,6 +124,14 @@ fn login_returns_token() {
     }]);
 
     let settings_repo = MemoryRepository::<UserSettings>::new();
+    let site_settings = MemoryRepository::<Setting>::new();
+    if require_verification {
+        let settings = SettingService::new(Arc::new(Repository::new(Box::new(site_settings.clone()))));
+        tokio::runtime::Runtime::new()
+            .expect("runtime")
+            .block_on(settings.update(SettingKeys::SECURITY_REQUIRE_EMAIL_VERIFICATION, serde_json::json!(true)))
+            .unwrap();
+    }
 
     let mut container = ServiceContainer::new();
     let config_clone = config.clone();
@@ -132,6 +139,9 @@ fn login_returns_token() {
     container.register_singleton::<Repository<User>, _>(move |_| Repository::new(Box::new(user_repo.clone())));
     container
         .register_singleton::<Repository<UserSettings>, _>(move |_| Repository::new(Box::new(settings_repo.clone())));
+    container.register_singleton::<SettingService, _>(move |_| {
+        SettingService::new(Arc::new(Repository::new(Box::new(site_settings.clone()))))
+    });
     container.register_singleton::<EncryptService, _>(move |provider| {
         let config = provider.resolve::<Configuration>().unwrap();
         EncryptService::new(&config).unwrap()
@@ -145,22 +155,37 @@ fn login_returns_token() {
         let settings_repo = provider.resolve::<Repository<UserSettings>>().unwrap();
         let encrypt = provider.resolve::<EncryptService>().unwrap();
         let tokens = provider.resolve::<Arc<dyn TokenService>>().unwrap();
-        AuthService::new(repo.clone(), settings_repo.clone(), encrypt.as_ref().clone(), tokens.as_ref().clone())
+        let sessions = Arc::new(Repository::new(Box::new(MemoryRepository::<RefreshSession>::new())));
+        let two_factor = Repository::new(Box::new(MemoryRepository::<TwoFactor>::new()));
+        AuthService::new(
+            repo.clone(),
+            settings_repo.clone(),
+            sessions,
+            Arc::new(TwoFactorService::new(Arc::new(two_factor), encrypt.as_ref().clone())),
+            encrypt.as_ref().clone(),
+            tokens.as_ref().clone(),
+        )
     });
 
     let services = container.build();
     let mut context = HttpContext::new(request, services, config);
 
     let mut pipeline = Pipeline::new();
+    pipeline.add(ErrorResponseMiddleware::new());
     pipeline.add(RoutingMiddleware::new(router));
     pipeline.add(ControllerInvokerMiddleware::new(Arc::new(registry)));
     pipeline.add(EndpointExecutionMiddleware::new());
 
-    let result = pipeline.run(&mut context);
+    let _ = pipeline.run(&mut context);
+    context
+}
+
+#[test]
+fn login_returns_token() {
+    let context = run_login(false);
     if context.response().status() != 200 {
         println!("Body: {:?}", context.response().body());
     }
-    assert!(result.is_ok());
     assert_eq!(context.response().status(), 200);
 
     match context.response().body() {
@@ -168,6 +193,22 @@ fn login_returns_token() {
             let resp: serde_json::Value = serde_json::from_str(json).unwrap();
             assert!(resp.get("accessToken").is_some());
             assert!(resp.get("refreshToken").is_some());
+            assert!(resp["user"].get("roles").is_some());
+        }
+        _ => panic!("Unexpected body type"),
+    }
+}
+
+#[test]
+fn login_rejects_unverified_account_when_verification_is_required() {
+    let context = run_login(true);
+
+    assert_eq!(context.response().status(), 403);
+    match context.response().body() {
+        ResponseBody::Text(json) => {
+            let resp: serde_json::Value = serde_json::from_str(json).unwrap();
+            assert_eq!(resp["code"], "email_not_verified");
+            assert!(resp.get("accessToken").is_none());
         }
         _ => panic!("Unexpected body type"),
     }
@@ -206,6 +247,7 @@ fn me_returns_profile_when_authenticated_and_repos_registered() {
         theme: "dark".to_string(),
         language: "en".to_string(),
         timezone: "UTC".to_string(),
+        email_digest: false,
         created_at: chrono::Utc::now(),
     }]);
 
@@ -239,6 +281,11 @@ fn me_returns_profile_when_authenticated_and_repos_registered() {
         AuthService::new(
             repo.clone(), // already Arc
             settings_repo.clone(),
+            Arc::new(Repository::new(Box::new(MemoryRepository::<RefreshSession>::new()))),
+            Arc::new(TwoFactorService::new(
+                Arc::new(Repository::new(Box::new(MemoryRepository::<TwoFactor>::new()))),
+                encrypt.as_ref().clone(),
+            )),
             encrypt.as_ref().clone(),
             tokens.as_ref().clone(),
         )
@@ -272,6 +319,7 @@ fn me_returns_profile_when_authenticated_and_repos_registered() {
         id: Uuid::parse_str(TEST_USER_ID_STR).unwrap(),
         email: "me@example.com".to_string(),
         display_name: "Display Name".to_string(),
+        roles: Vec::new(),
         avatar_url: None,
         theme: "dark".to_string(),
         language: "en".to_string(),
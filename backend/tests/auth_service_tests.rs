@@ -13,8 +13,10 @@ use nimble_web::data::query::{Query, Value};
 use nimble_web::{JwtTokenService, TokenService};
 use uuid::Uuid;
 
-use nimble_photos::entities::{user::User, user_settings::UserSettings};
-use nimble_photos::services::{AuthService, EncryptService};
+use nimble_photos::dtos::LoginOutcome;
+use nimble_photos::entities::{RefreshSession, TwoFactor, user::User, user_settings::UserSettings};
+use nimble_photos::models::{SessionClient, Totp};
+use nimble_photos::services::{AuthService, EncryptService, TwoFactorError, TwoFactorService};
 
 const TEST_USER_ID_STR: &str = "00000000-0000-0000-0000-000000000002";
 
@@ -56,15 +58,14 @@ impl DataProvider<User> for InMemoryUserProvider {
     }
 
     async fn get_by(&self, column: &str, value: Value) -> DataResult<Option<User>> {
-        if column == "email" {
-            if let Value::String(email_val) = value {
-                let store = self.store.lock().unwrap();
-                for user in store.values() {
-                    if user.email == email_val {
-                        return Ok(Some(user.clone()));
-                    }
-                }
-            }
+        if let Value::String(expected) = value {
+            let store = self.store.lock().unwrap();
+            let found = store.values().find(|user| match column {
+                "email" => user.email == expected,
+                "verification_token" => user.verification_token.as_deref() == Some(expected.as_str()),
+                _ => false,
+            });
+            return Ok(found.cloned());
         }
         Ok(None)
     }
@@ -81,7 +82,20 @@ fn create_test_config() -> Configuration {
     Configuration::from_values(values)
 }
 
+fn sessions_repository() -> Repository<RefreshSession> {
+    Repository::new(Box::new(MemoryRepository::<RefreshSession>::new()))
+}
+
+fn two_factor_service(encrypt: &EncryptService) -> Arc<TwoFactorService> {
+    let repo = Repository::new(Box::new(MemoryRepository::<TwoFactor>::new()));
+    Arc::new(TwoFactorService::new(Arc::new(repo), encrypt.clone()))
+}
+
 fn create_auth_service() -> AuthService {
+    create_auth_service_with_two_factor().0
+}
+
+fn create_auth_service_with_two_factor() -> (AuthService, Arc<TwoFactorService>) {
     let config = create_test_config();
     println!("Config created with keys: {:?}", config.clone());
 
@@ -98,7 +112,16 @@ fn create_auth_service() -> AuthService {
     let settings_repo = MemoryRepository::<UserSettings>::new();
     let settings_repository = Repository::new(Box::new(settings_repo));
 
-    AuthService::new(Arc::new(repo), Arc::new(settings_repository), encrypt, tokens)
+    let two_factor = two_factor_service(&encrypt);
+    let service = AuthService::new(
+        Arc::new(repo),
+        Arc::new(settings_repository),
+        Arc::new(sessions_repository()),
+        Arc::clone(&two_factor),
+        encrypt,
+        tokens,
+    );
+    (service, two_factor)
 }
 
 #[test]
@@ -113,7 +136,7 @@ async fn register_creates_user_and_returns_tokens() {
     let email = "test@example.com";
     let password = "password123";
 
-    let result = service.register(email, password, "Test User").await;
+    let result = service.register(email, password, "Test User", &SessionClient::default()).await;
 
     assert!(result.is_ok());
     let response = result.unwrap();
@@ -127,7 +150,7 @@ async fn register_assigns_admin_role_to_first_user() {
     let email = "first@example.com";
     let password = "password123";
 
-    let response = service.register(email, password, "First User").await.unwrap();
+    let response = service.register(email, password, "First User", &SessionClient::default()).await.unwrap();
 
     let token_service = JwtTokenService::new("test-secret".to_string(), "test-issuer".to_string());
     let claims = token_service.validate_access_token(&response.access_token).unwrap();
@@ -140,9 +163,10 @@ async fn register_assigns_viewer_role_to_second_user() {
     let service = create_auth_service();
     let password = "password123";
 
-    service.register("first@example.com", password, "First User").await.unwrap();
+    service.register("first@example.com", password, "First User", &SessionClient::default()).await.unwrap();
 
-    let response = service.register("second@example.com", password, "Second User").await.unwrap();
+    let response =
+        service.register("second@example.com", password, "Second User", &SessionClient::default()).await.unwrap();
 
     let token_service = JwtTokenService::new("test-secret".to_string(), "test-issuer".to_string());
     let claims = token_service.validate_access_token(&response.access_token).unwrap();
@@ -157,11 +181,11 @@ async fn login_with_valid_credentials_returns_tokens() {
     let email = "test@example.com";
     let password = "password123";
 
-    service.register(email, password, "Test User").await.unwrap();
+    service.register(email, password, "Test User", &SessionClient::default()).await.unwrap();
 
-    let result = service.login(email, password).await;
+    let result = service.login(email, password, &SessionClient::default(), false).await;
     assert!(result.is_ok());
-    let response = result.unwrap();
+    let response = result.unwrap().into_tokens().unwrap();
     assert!(!response.access_token.is_empty());
     assert!(!response.refresh_token.is_empty());
 }
@@ -172,9 +196,9 @@ async fn login_with_invalid_email_returns_error() {
     let email = "test@example.com";
     let password = "password123";
 
-    service.register(email, password, "Test User").await.unwrap();
+    service.register(email, password, "Test User", &SessionClient::default()).await.unwrap();
 
-    let result = service.login("wrong@example.com", password).await;
+    let result = service.login("wrong@example.com", password, &SessionClient::default(), false).await;
 
     assert!(result.is_err());
 }
@@ -185,9 +209,9 @@ async fn login_with_invalid_password_returns_error() {
     let email = "test@example.com";
     let password = "password123";
 
-    service.register(email, password, "Test User").await.unwrap();
+    service.register(email, password, "Test User", &SessionClient::default()).await.unwrap();
 
-    let result = service.login(email, "wrongpassword").await;
+    let result = service.login(email, "wrongpassword", &SessionClient::default(), false).await;
 
     assert!(result.is_err());
 }
@@ -198,21 +222,186 @@ async fn refresh_with_valid_token_returns_new_tokens() {
     let email = "test@example.com";
     let password = "password123";
 
-    let register_response = service.register(email, password, "Test User").await.unwrap();
+    let register_response = service.register(email, password, "Test User", &SessionClient::default()).await.unwrap();
 
-    let result = service.refresh(&register_response.refresh_token).await;
+    let result = service.refresh(&register_response.refresh_token, false, false).await;
 
     assert!(result.is_ok());
     let response = result.unwrap();
     assert!(!response.access_token.is_empty());
     assert!(!response.refresh_token.is_empty());
+    assert_eq!(response.user, register_response.user);
+}
+
+#[tokio::test]
+async fn login_response_embeds_profile_and_roles() {
+    let service = create_auth_service();
+
+    service.register("first@example.com", "password123", "First User", &SessionClient::default()).await.unwrap();
+    service.register("second@example.com", "password123", "Second User", &SessionClient::default()).await.unwrap();
+
+    let response = service
+        .login("second@example.com", "password123", &SessionClient::default(), false)
+        .await
+        .unwrap()
+        .into_tokens()
+        .unwrap();
+
+    assert_eq!(response.user.email, "second@example.com");
+    assert_eq!(response.user.display_name, "Second User");
+    assert_eq!(response.user.roles, vec!["viewer".to_string()]);
+}
+
+#[tokio::test]
+async fn login_requires_verified_email_only_when_asked() {
+    let service = create_auth_service();
+    let client = SessionClient::default();
+
+    service.register("admin@example.com", "password123", "Admin", &client).await.unwrap();
+    service.register("viewer@example.com", "password123", "Viewer", &client).await.unwrap();
+
+    let rejected = service.login("viewer@example.com", "password123", &client, true).await.err().unwrap();
+    assert_eq!(rejected.message(), AuthService::EMAIL_NOT_VERIFIED);
+    assert!(service.login("viewer@example.com", "password123", &client, false).await.is_ok());
+    // The first account is the admin and never has to verify.
+    assert!(service.login("admin@example.com", "password123", &client, true).await.is_ok());
+
+    let wrong_password = service.login("viewer@example.com", "nope", &client, true).await.err().unwrap();
+    assert_eq!(wrong_password.message(), "invalid credentials");
+}
+
+#[tokio::test]
+async fn refresh_rechecks_email_verification() {
+    let service = create_auth_service();
+    let client = SessionClient::default();
+
+    let admin = service.register("admin@example.com", "password123", "Admin", &client).await.unwrap();
+    let viewer = service.register("viewer@example.com", "password123", "Viewer", &client).await.unwrap();
+
+    let rejected = service.refresh(&viewer.refresh_token, true, false).await.err().unwrap();
+    assert_eq!(rejected.message(), AuthService::EMAIL_NOT_VERIFIED);
+    // A fresh account is still inside the grace period when tokens are handed out before verification.
+    assert!(service.refresh(&viewer.refresh_token, true, true).await.is_ok());
+    assert!(service.refresh(&admin.refresh_token, true, false).await.is_ok());
+}
+
+#[tokio::test]
+async fn reissued_verification_token_unlocks_login() {
+    let service = create_auth_service();
+    let client = SessionClient::default();
+
+    service.register("admin@example.com", "password123", "Admin", &client).await.unwrap();
+    service.register("viewer@example.com", "password123", "Viewer", &client).await.unwrap();
+    let original = service.issue_verification_token("viewer@example.com").await.unwrap();
+
+    let token = service.reissue_verification_token("viewer@example.com").await.unwrap().unwrap();
+    assert_ne!(token, original);
+    assert!(service.verify_email(&original).await.is_err());
+    service.verify_email(&token).await.unwrap();
+
+    assert!(service.login("viewer@example.com", "password123", &client, true).await.is_ok());
+    assert_eq!(service.reissue_verification_token("viewer@example.com").await.unwrap(), None);
+    assert_eq!(service.reissue_verification_token("admin@example.com").await.unwrap(), None);
+    assert_eq!(service.reissue_verification_token("nobody@example.com").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn login_with_two_factor_returns_challenge_until_code_is_verified() {
+    let (service, two_factor) = create_auth_service_with_two_factor();
+    let client = SessionClient::default();
+    let registered = service.register("user@example.com", "password123", "User", &client).await.unwrap();
+    let user_id = registered.user.id;
+
+    let setup = two_factor.setup(user_id, "Nimble Photos", "user@example.com").await.unwrap();
+    assert!(setup.otpauth_uri.starts_with("otpauth://totp/Nimble%20Photos:user%40example.com?secret="));
+    let totp = Totp::from_base32(&setup.secret).unwrap();
+    let now = chrono::Utc::now();
+    // Enabling consumes the current step, so sign-in has to use the next one.
+    let backup_codes = two_factor.enable(user_id, &totp.code_at(now)).await.unwrap();
+    assert_eq!(backup_codes.len(), 10);
+
+    let challenge = match service.login("user@example.com", "password123", &client, false).await.unwrap() {
+        LoginOutcome::TwoFactorRequired(challenge) => challenge,
+        LoginOutcome::Tokens(_) => panic!("password alone must not issue tokens"),
+    };
+    assert!(two_factor.complete(&challenge.challenge_token, &totp.code_at(now)).await.is_err());
+    let next_code = totp.code_at(now + chrono::Duration::seconds(Totp::PERIOD_SECONDS));
+    assert_eq!(two_factor.complete(&challenge.challenge_token, &next_code).await.unwrap(), user_id);
+    // The challenge is single use.
+    assert!(two_factor.complete(&challenge.challenge_token, &next_code).await.is_err());
+}
+
+#[tokio::test]
+async fn wrong_codes_lock_two_factor_across_new_challenges() {
+    let (service, two_factor) = create_auth_service_with_two_factor();
+    let client = SessionClient::default();
+    let user_id = service.register("user@example.com", "password123", "User", &client).await.unwrap().user.id;
+    let setup = two_factor.setup(user_id, "Nimble Photos", "user@example.com").await.unwrap();
+    let totp = Totp::from_base32(&setup.secret).unwrap();
+    let now = chrono::Utc::now();
+    two_factor.enable(user_id, &totp.code_at(now)).await.unwrap();
+
+    // Each guess comes through a fresh password login, so a per-challenge limit would never kick in.
+    let mut last = None;
+    for _ in 0..TwoFactorService::MAX_FAILED_CODES {
+        let challenge = two_factor.challenge(user_id);
+        last = two_factor.complete(&challenge.challenge_token, "000000").await.err();
+    }
+    assert!(matches!(last, Some(TwoFactorError::LockedOut(_))));
+
+    let challenge = two_factor.challenge(user_id);
+    let next_code = totp.code_at(now + chrono::Duration::seconds(Totp::PERIOD_SECONDS));
+    let result = two_factor.complete(&challenge.challenge_token, &next_code).await;
+    assert!(matches!(result, Err(TwoFactorError::LockedOut(_))));
+}
+
+#[tokio::test]
+async fn new_challenge_replaces_the_previous_one_for_that_user() {
+    let (service, two_factor) = create_auth_service_with_two_factor();
+    let client = SessionClient::default();
+    let user_id = service.register("user@example.com", "password123", "User", &client).await.unwrap().user.id;
+    let setup = two_factor.setup(user_id, "Nimble Photos", "user@example.com").await.unwrap();
+    let totp = Totp::from_base32(&setup.secret).unwrap();
+    let now = chrono::Utc::now();
+    two_factor.enable(user_id, &totp.code_at(now)).await.unwrap();
+
+    let first = two_factor.challenge(user_id);
+    let second = two_factor.challenge(user_id);
+    let next_code = totp.code_at(now + chrono::Duration::seconds(Totp::PERIOD_SECONDS));
+
+    assert!(matches!(
+        two_factor.complete(&first.challenge_token, &next_code).await,
+        Err(TwoFactorError::InvalidChallenge)
+    ));
+    assert_eq!(two_factor.complete(&second.challenge_token, &next_code).await.unwrap(), user_id);
+}
+
+#[tokio::test]
+async fn backup_codes_work_once_and_admin_override_turns_two_factor_off() {
+    let (service, two_factor) = create_auth_service_with_two_factor();
+    let client = SessionClient::default();
+    let user_id = service.register("user@example.com", "password123", "User", &client).await.unwrap().user.id;
+    let setup = two_factor.setup(user_id, "Nimble Photos", "user@example.com").await.unwrap();
+    let code = Totp::from_base32(&setup.secret).unwrap().code_at(chrono::Utc::now());
+    let backup_codes = two_factor.enable(user_id, &code).await.unwrap();
+
+    let challenge = two_factor.challenge(user_id);
+    let typed = backup_codes[0].to_uppercase().replace('-', " ");
+    assert_eq!(two_factor.complete(&challenge.challenge_token, &typed).await.unwrap(), user_id);
+    let challenge = two_factor.challenge(user_id);
+    assert!(two_factor.complete(&challenge.challenge_token, &backup_codes[0]).await.is_err());
+
+    assert!(two_factor.disable(user_id).await.unwrap());
+    assert!(!two_factor.disable(user_id).await.unwrap());
+    let outcome = service.login("user@example.com", "password123", &client, false).await.unwrap();
+    assert!(outcome.into_tokens().is_some());
 }
 
 #[tokio::test]
 async fn refresh_with_invalid_token_returns_error() {
     let service = create_auth_service();
 
-    let result = service.refresh("invalid-token").await;
+    let result = service.refresh("invalid-token", false, false).await;
 
     assert!(result.is_err());
 }
@@ -223,7 +412,7 @@ async fn me_returns_user_for_valid_user_id() {
     let email = "test@example.com";
     let password = "password123";
 
-    service.register(email, password, "Test User").await.unwrap();
+    service.register(email, password, "Test User", &SessionClient::default()).await.unwrap();
 
     let config = create_test_config();
     let token_service = JwtTokenService::new("test-secret".to_string(), "test-issuer".to_string());
@@ -251,7 +440,14 @@ async fn me_returns_user_for_valid_user_id() {
     let encrypt = EncryptService::new(&config).unwrap();
     let settings_repo = MemoryRepository::<UserSettings>::new();
     let settings_repository = Repository::new(Box::new(settings_repo));
-    let service = AuthService::new(Arc::new(repo), Arc::new(settings_repository), encrypt, tokens);
+    let service = AuthService::new(
+        Arc::new(repo),
+        Arc::new(settings_repository),
+        Arc::new(sessions_repository()),
+        two_factor_service(&encrypt),
+        encrypt,
+        tokens,
+    );
 
     let result = service.me(&user.id.to_string()).await;
 
@@ -276,9 +472,87 @@ async fn logout_succeeds() {
     let email = "test@example.com";
     let password = "password123";
 
-    let register_response = service.register(email, password, "Test User").await.unwrap();
+    let register_response = service.register(email, password, "Test User", &SessionClient::default()).await.unwrap();
 
-    let result = service.logout(&register_response.refresh_token);
+    let result = service.logout(&register_response.refresh_token).await;
 
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn grant_role_adds_role_once_and_keeps_existing_roles() {
+    let service = create_auth_service();
+    let password = "password123";
+
+    service.register("first@example.com", password, "First User", &SessionClient::default()).await.unwrap();
+    service.register("second@example.com", password, "Second User", &SessionClient::default()).await.unwrap();
+
+    let user_id = service.grant_role("second@example.com", "admin").await.unwrap();
+    service.grant_role("second@example.com", "admin").await.unwrap();
+
+    let response = service.issue_tokens(user_id, &SessionClient::default()).await.unwrap();
+    let token_service = JwtTokenService::new("test-secret".to_string(), "test-issuer".to_string());
+    let claims = token_service.validate_access_token(&response.access_token).unwrap();
+
+    assert!(claims.roles().contains("admin"));
+    assert!(claims.roles().contains("viewer"));
+    assert_eq!(claims.roles().len(), 2);
+}
+
+#[tokio::test]
+async fn login_records_a_session_for_the_device() {
+    let service = create_auth_service();
+    let client = SessionClient::from_headers(
+        Some("Mozilla/5.0 (Windows NT 10.0; Win64; x64) Gecko/20100101 Firefox/128.0"),
+        Some("203.0.113.7, 10.0.0.1"),
+        None,
+    );
+
+    let response = service.register("user@example.com", "password123", "User", &client).await.unwrap();
+    let sessions = service.sessions(response.user.id).await.unwrap();
+
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].id, response.session_id);
+    assert_eq!(sessions[0].user_agent.as_deref(), Some("Firefox on Windows"));
+    assert_eq!(sessions[0].ip_address.as_deref(), Some("203.0.113.7"));
+    assert_ne!(sessions[0].token_hash, response.refresh_token);
+}
+
+#[tokio::test]
+async fn refresh_keeps_the_session_and_fails_once_revoked() {
+    let service = create_auth_service();
+    let response =
+        service.register("user@example.com", "password123", "User", &SessionClient::default()).await.unwrap();
+
+    let refreshed = service.refresh(&response.refresh_token, false, false).await.unwrap();
+    assert_eq!(refreshed.session_id, response.session_id);
+
+    assert!(service.revoke_session(response.user.id, response.session_id).await.unwrap());
+    assert!(service.refresh(&refreshed.refresh_token, false, false).await.is_err());
+}
+
+#[tokio::test]
+async fn revoking_other_sessions_keeps_the_current_one() {
+    let service = create_auth_service();
+    let client = SessionClient::default();
+    let first = service.register("user@example.com", "password123", "User", &client).await.unwrap();
+    service.login("user@example.com", "password123", &client, false).await.unwrap();
+    service.login("user@example.com", "password123", &client, false).await.unwrap();
+
+    let revoked = service.revoke_other_sessions(first.user.id, Some(first.session_id)).await.unwrap();
+    let remaining = service.sessions(first.user.id).await.unwrap();
+
+    assert_eq!(revoked, 2);
+    assert_eq!(remaining.iter().map(|session| session.id).collect::<Vec<_>>(), vec![first.session_id]);
+}
+
+#[tokio::test]
+async fn sessions_of_other_users_cannot_be_revoked() {
+    let service = create_auth_service();
+    let client = SessionClient::default();
+    let owner = service.register("owner@example.com", "password123", "Owner", &client).await.unwrap();
+    let other = service.register("other@example.com", "password123", "Other", &client).await.unwrap();
+
+    assert!(!service.revoke_session(other.user.id, owner.session_id).await.unwrap());
+    assert_eq!(service.sessions(owner.user.id).await.unwrap().len(), 1);
+}
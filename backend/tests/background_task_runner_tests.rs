@@ -1,4 +1,4 @@
-use nimble_photos::services::{BackgroundTaskRunner, TaskDescriptor};
+use nimble_photos::services::{BackgroundTaskRunner, TaskDescriptor, TaskState};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::sync::Barrier;
@@ -88,3 +88,118 @@ async fn stop_finishes_running_tasks_and_rejects_new_tasks() {
     let enqueue_after_stop = runner.enqueue(TaskDescriptor::new("rejected-task", async move { Ok(()) }));
     assert!(enqueue_after_stop.is_err());
 }
+
+#[tokio::test]
+async fn tasks_report_completed_and_failed_states() {
+    let runner = BackgroundTaskRunner::new(1);
+    runner.start().expect("failed to start runner");
+
+    let succeeding = TaskDescriptor::new("succeeding-task", async move { Ok(()) });
+    let succeeding_id = succeeding.id;
+    let failing = TaskDescriptor::new("failing-task", async move { Err(anyhow::anyhow!("boom")) });
+    let failing_id = failing.id;
+    runner.enqueue(succeeding).expect("failed to enqueue task");
+    runner.enqueue(failing).expect("failed to enqueue task");
+
+    runner.stop().await.expect("failed to stop runner");
+
+    let completed = runner.task(succeeding_id).expect("completed task should be tracked");
+    assert_eq!(completed.state, TaskState::Completed);
+    assert!(completed.started_at.is_some());
+
+    let failed = runner.task(failing_id).expect("failed task should be tracked");
+    assert_eq!(failed.state, TaskState::Failed);
+    assert_eq!(failed.last_error.as_deref(), Some("boom"));
+
+    assert_eq!(runner.tasks(Some(TaskState::Failed)).len(), 1);
+}
+
+#[tokio::test]
+async fn queued_tasks_can_be_cancelled_before_they_start() {
+    let runner = BackgroundTaskRunner::new(1);
+    let executed = Arc::new(AtomicUsize::new(0));
+    let executed_for_task = Arc::clone(&executed);
+    let task = TaskDescriptor::new("cancelled-task", async move {
+        executed_for_task.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    });
+    let task_id = task.id;
+    runner.enqueue(task).expect("failed to enqueue task");
+
+    assert_eq!(runner.tasks(Some(TaskState::Queued)).len(), 1);
+    assert!(runner.cancel(task_id).expect("cancel should succeed"));
+    assert!(!runner.cancel(task_id).expect("second cancel should not fail"));
+    assert_eq!(runner.queued_count(), 0);
+
+    runner.start().expect("failed to start runner");
+    runner.stop().await.expect("failed to stop runner");
+
+    assert_eq!(executed.load(Ordering::SeqCst), 0);
+    assert_eq!(runner.task(task_id).map(|info| info.state), Some(TaskState::Cancelled));
+}
+
+#[tokio::test]
+async fn shutdown_waits_for_running_tasks_and_returns_queued_ones() {
+    let runner = BackgroundTaskRunner::new(1);
+    let completed = Arc::new(AtomicUsize::new(0));
+    let started = Arc::new(Barrier::new(2));
+
+    let completed_for_task = Arc::clone(&completed);
+    let started_for_task = Arc::clone(&started);
+    runner
+        .enqueue(TaskDescriptor::new("running-task", async move {
+            started_for_task.wait().await;
+            sleep(Duration::from_millis(50)).await;
+            completed_for_task.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }))
+        .expect("failed to enqueue task");
+    runner
+        .enqueue(
+            TaskDescriptor::new("queued-task", async move { Ok(()) })
+                .with_payload(serde_json::json!({ "fileName": "queued.jpg" })),
+        )
+        .expect("failed to enqueue task");
+
+    runner.start().expect("failed to start runner");
+    started.wait().await;
+
+    let pending = runner.shutdown(Duration::from_secs(2)).await.expect("shutdown should succeed");
+
+    assert_eq!(completed.load(Ordering::SeqCst), 1);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].name, "queued-task");
+    assert_eq!(pending[0].payload, Some(serde_json::json!({ "fileName": "queued.jpg" })));
+    assert_eq!(runner.queued_count(), 0);
+    assert!(runner.enqueue(TaskDescriptor::new("late-task", async move { Ok(()) })).is_err());
+}
+
+#[tokio::test]
+async fn set_parallelism_grows_and_shrinks_running_workers() {
+    let runner = BackgroundTaskRunner::new(1);
+    runner.start().expect("failed to start runner");
+    assert_eq!(runner.active_worker_count(), 1);
+
+    runner.set_parallelism(3).expect("failed to grow runner");
+    assert_eq!(runner.parallelism(), 3);
+    assert_eq!(runner.active_worker_count(), 3);
+
+    runner.set_parallelism(1).expect("failed to shrink runner");
+    let started = Instant::now();
+    while runner.active_worker_count() > 1 && started.elapsed() < Duration::from_secs(2) {
+        sleep(Duration::from_millis(5)).await;
+    }
+    assert_eq!(runner.active_worker_count(), 1);
+
+    let completed_count = Arc::new(AtomicUsize::new(0));
+    let completed_count_for_task = Arc::clone(&completed_count);
+    runner
+        .enqueue(TaskDescriptor::new("after-resize", async move {
+            completed_count_for_task.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }))
+        .expect("failed to enqueue task");
+    assert!(wait_until_counter(&completed_count, 1, Duration::from_secs(2)).await);
+
+    runner.stop().await.expect("failed to stop runner");
+}
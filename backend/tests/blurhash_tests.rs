@@ -0,0 +1,42 @@
+use nimble_photos::models::Blurhash;
+
+fn solid(width: u32, height: u32, rgb: [u8; 3]) -> Vec<u8> {
+    (0..width * height).flat_map(|_| rgb).collect()
+}
+
+#[test]
+fn encodes_size_flag_and_length_for_default_components() {
+    let hash = Blurhash::encode(8, 6, &solid(8, 6, [128, 128, 128])).unwrap();
+
+    // 4x3 components: one size char, one max char, four DC chars and two per AC component.
+    assert_eq!(hash.len(), 4 + 2 * 11);
+    assert!(hash.starts_with('L'));
+}
+
+#[test]
+fn dc_component_keeps_the_average_color() {
+    let gray = Blurhash::encode(8, 6, &solid(8, 6, [128, 128, 128])).unwrap();
+    let red = Blurhash::encode(8, 6, &solid(8, 6, [255, 0, 0])).unwrap();
+
+    assert_eq!(&gray[2..6], "Eyb[");
+    assert_eq!(&red[2..6], "TI:j");
+}
+
+#[test]
+fn different_images_get_different_placeholders() {
+    let mut split = solid(8, 6, [0, 0, 0]);
+    for pixel in split.chunks_mut(3).step_by(2) {
+        pixel.copy_from_slice(&[255, 255, 255]);
+    }
+
+    let uniform = Blurhash::encode(8, 6, &solid(8, 6, [128, 128, 128])).unwrap();
+    let striped = Blurhash::encode(8, 6, &split).unwrap();
+    assert_ne!(uniform, striped);
+    assert_eq!(striped, Blurhash::encode(8, 6, &split).unwrap());
+}
+
+#[test]
+fn rejects_empty_or_mismatched_buffers() {
+    assert_eq!(Blurhash::encode(0, 0, &[]), None);
+    assert_eq!(Blurhash::encode(2, 2, &[0; 9]), None);
+}
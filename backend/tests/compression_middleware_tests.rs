@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use async_trait::async_trait;
+use flate2::read::GzDecoder;
+use nimble_photos::middlewares::CompressionMiddleware;
+use nimble_web::pipeline::middleware::Middleware;
+use nimble_web::pipeline::next::Next;
+use nimble_web::{Configuration, HttpContext, HttpRequest, Pipeline, PipelineError, ResponseBody, ServiceContainer};
+
+enum Payload {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+struct FixedResponse {
+    content_type: &'static str,
+    payload: Payload,
+}
+
+#[async_trait]
+impl Middleware for FixedResponse {
+    async fn handle(&self, context: &mut HttpContext, _next: Next<'_>) -> Result<(), PipelineError> {
+        let response = context.response_mut();
+        response.set_status(200);
+        response.headers_mut().insert("content-type", self.content_type);
+        response.set_body(match &self.payload {
+            Payload::Text(text) => ResponseBody::Text(text.clone()),
+            Payload::Bytes(bytes) => ResponseBody::Bytes(bytes.clone()),
+        });
+        Ok(())
+    }
+}
+
+fn run(accept_encoding: Option<&str>, content_type: &'static str, payload: Payload) -> HttpContext {
+    let mut request = HttpRequest::new("GET", "/api/photos/timeline/2024");
+    if let Some(value) = accept_encoding {
+        request.headers_mut().insert("accept-encoding", value);
+    }
+    let services = ServiceContainer::new().build();
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(CompressionMiddleware::new());
+    pipeline.add(FixedResponse { content_type, payload });
+    let _ = pipeline.run(&mut context);
+    context
+}
+
+fn timeline_payload() -> String {
+    let groups = (1..=12)
+        .map(|month| {
+            let photos = (0..50)
+                .map(|index| {
+                    serde_json::json!({
+                        "id": format!("00000000-0000-0000-0000-{:012}", month * 100 + index),
+                        "name": format!("IMG_{:04}.jpg", index),
+                        "width": 6000,
+                        "height": 4000,
+                        "dateTaken": format!("2024-{:02}-01T10:00:00Z", month),
+                        "storageId": "00000000-0000-0000-0000-000000000001"
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::json!({ "title": format!("2024-{:02}", month), "photos": photos })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&groups).unwrap()
+}
+
+#[test]
+fn large_json_payload_is_gzipped() {
+    let payload = timeline_payload();
+    let context = run(Some("gzip, deflate, br"), "application/json", Payload::Text(payload.clone()));
+    let response = context.response();
+
+    assert_eq!(response.headers().get("content-encoding"), Some("gzip"));
+    assert_eq!(response.headers().get("vary"), Some("Accept-Encoding"));
+    let ResponseBody::Bytes(compressed) = response.body() else {
+        panic!("expected a compressed body");
+    };
+    assert!(compressed.len() * 5 < payload.len(), "{} bytes compressed to {}", payload.len(), compressed.len());
+
+    let mut decoded = String::new();
+    GzDecoder::new(compressed.as_slice()).read_to_string(&mut decoded).unwrap();
+    assert_eq!(decoded, payload);
+}
+
+#[test]
+fn json_is_left_alone_without_accept_encoding() {
+    let payload = timeline_payload();
+    let context = run(None, "application/json", Payload::Text(payload.clone()));
+    let response = context.response();
+
+    assert_eq!(response.headers().get("content-encoding"), None);
+    assert_eq!(response.headers().get("vary"), Some("Accept-Encoding"));
+    assert_eq!(response.body(), &ResponseBody::Text(payload));
+}
+
+#[test]
+fn small_json_payload_is_not_compressed() {
+    let context = run(Some("gzip"), "application/json", Payload::Text("{\"ok\":true}".to_string()));
+
+    assert_eq!(context.response().headers().get("content-encoding"), None);
+}
+
+#[test]
+fn thumbnail_binary_response_is_untouched() {
+    let image = vec![0x52u8; 64 * 1024];
+    let context = run(Some("gzip"), "image/webp", Payload::Bytes(image.clone()));
+    let response = context.response();
+
+    assert_eq!(response.headers().get("content-encoding"), None);
+    assert_eq!(response.headers().get("vary"), None);
+    assert_eq!(response.body(), &ResponseBody::Bytes(image));
+}
+
+#[test]
+fn accept_encoding_respects_zero_quality() {
+    assert!(CompressionMiddleware::accepts_gzip(Some("br;q=1.0, gzip;q=0.8")));
+    assert!(CompressionMiddleware::accepts_gzip(Some("*")));
+    assert!(!CompressionMiddleware::accepts_gzip(Some("gzip;q=0")));
+    assert!(!CompressionMiddleware::accepts_gzip(Some("br")));
+    assert!(!CompressionMiddleware::accepts_gzip(None));
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use nimble_photos::models::{AppConfig, AppEnvironment};
+use nimble_photos::services::ConfigReloadService;
+
+#[test]
+fn flatten_lowercases_nested_keys_and_stringifies_values() {
+    let values = ConfigReloadService::flatten(
+        r#"{ "Upload": { "MaxFileSizeBytes": 1024 }, "Logging": { "Level": "debug", "RequestBodyOnError": true }, "Metrics": { "Token": null } }"#,
+    )
+    .unwrap();
+
+    assert_eq!(values.get("upload.maxfilesizebytes").map(String::as_str), Some("1024"));
+    assert_eq!(values.get("logging.level").map(String::as_str), Some("debug"));
+    assert_eq!(values.get("logging.requestbodyonerror").map(String::as_str), Some("true"));
+    assert!(!values.contains_key("metrics.token"));
+}
+
+#[test]
+fn flatten_rejects_invalid_json() {
+    assert!(ConfigReloadService::flatten("{ not json").is_err());
+}
+
+#[test]
+fn hot_keys_are_applied_and_the_rest_require_a_restart() {
+    let current = AppConfig::default();
+    let next = AppConfig {
+        log_level: Some(log::LevelFilter::Warn),
+        upload_max_file_size_bytes: 1024,
+        background_parallelism: current.background_parallelism + 1,
+        environment: AppEnvironment::Production,
+        jwt_secret: Some("rotated".to_string()),
+        ..current.clone()
+    };
+
+    let plan = ConfigReloadService::plan(&current, &next);
+
+    assert_eq!(plan.applied, vec!["logging.level", "upload.maxFileSizeBytes", "background.parallelism"]);
+    assert_eq!(plan.restart_required, vec!["app.environment", "jwt.secret"]);
+}
+
+#[test]
+fn unchanged_configuration_produces_an_empty_plan() {
+    let current = AppConfig::default();
+
+    let plan = ConfigReloadService::plan(&current, &current.clone());
+
+    assert!(plan.applied.is_empty());
+    assert!(plan.restart_required.is_empty());
+}
+
+#[test]
+fn merge_keeps_restart_only_values_from_the_running_config() {
+    let current = AppConfig::default();
+    let next = AppConfig {
+        preview_max_concurrent_extractions: 1,
+        thumbnail_base_path: PathBuf::from("/elsewhere"),
+        eventbus_capacity: 4,
+        ..current.clone()
+    };
+
+    let merged = ConfigReloadService::merge(&current, &next);
+
+    assert_eq!(merged.preview_max_concurrent_extractions, 1);
+    assert_eq!(merged.thumbnail_base_path, current.thumbnail_base_path);
+    assert_eq!(merged.eventbus_capacity, current.eventbus_capacity);
+}
+
+#[test]
+fn log_level_is_parsed_and_validated() {
+    let config = AppConfig::from_lookup(|key| (key == "logging.level").then(|| "WARN".to_string())).unwrap();
+    assert_eq!(config.log_level, Some(log::LevelFilter::Warn));
+
+    let error = AppConfig::from_lookup(|key| (key == "logging.level").then(|| "loud".to_string())).unwrap_err();
+    assert!(error.errors[0].starts_with("logging.level"));
+}
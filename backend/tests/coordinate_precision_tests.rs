@@ -0,0 +1,52 @@
+use nimble_photos::entities::ExifModel;
+use nimble_photos::models::CoordinatePrecision;
+
+#[test]
+fn parse_accepts_exact_and_decimal_places() {
+    assert_eq!(CoordinatePrecision::parse("exact"), Some(CoordinatePrecision::Exact));
+    assert_eq!(CoordinatePrecision::parse(" Exact "), Some(CoordinatePrecision::Exact));
+    assert_eq!(CoordinatePrecision::parse("2"), Some(CoordinatePrecision::Decimals(2)));
+    assert_eq!(CoordinatePrecision::parse("7"), None);
+    assert_eq!(CoordinatePrecision::parse("-1"), None);
+    assert_eq!(CoordinatePrecision::parse("km"), None);
+}
+
+#[test]
+fn admins_always_get_exact_positions() {
+    let precision = CoordinatePrecision::Decimals(2);
+
+    assert_eq!(precision.for_requester(true), CoordinatePrecision::Exact);
+    assert_eq!(precision.for_requester(false), precision);
+    assert_eq!(CoordinatePrecision::Exact.decimals(), None);
+    assert_eq!(precision.decimals(), Some(2));
+}
+
+#[test]
+fn apply_rounds_coordinates_and_drops_area_name() {
+    let mut exif = ExifModel {
+        gps_latitude: Some(47.620_493),
+        gps_longitude: Some(-122.349_275),
+        gps_area_information: Some("Space Needle".to_string()),
+        ..ExifModel::default()
+    };
+
+    CoordinatePrecision::Decimals(2).apply(&mut exif);
+
+    assert_eq!(exif.gps_latitude, Some(47.62));
+    assert_eq!(exif.gps_longitude, Some(-122.35));
+    assert_eq!(exif.gps_area_information, None);
+}
+
+#[test]
+fn exact_precision_leaves_exif_untouched() {
+    let mut exif = ExifModel {
+        gps_latitude: Some(47.620_493),
+        gps_area_information: Some("Space Needle".to_string()),
+        ..ExifModel::default()
+    };
+
+    CoordinatePrecision::Exact.apply(&mut exif);
+
+    assert_eq!(exif.gps_latitude, Some(47.620_493));
+    assert_eq!(exif.gps_area_information.as_deref(), Some("Space Needle"));
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use nimble_photos::entities::Setting;
+use nimble_photos::middlewares::CorsPolicyMiddleware;
+use nimble_photos::models::{AppConfig, AppEnvironment, CorsDecision, CorsPolicy};
+use nimble_photos::services::{CorsPolicyCache, SettingService};
+use nimble_web::pipeline::middleware::Middleware;
+use nimble_web::pipeline::next::Next;
+use nimble_web::{
+    Configuration, HttpContext, HttpRequest, MemoryRepository, Pipeline, PipelineError, Repository, ServiceContainer,
+};
+use serde_json::json;
+
+struct OkResponse;
+
+#[async_trait]
+impl Middleware for OkResponse {
+    async fn handle(&self, context: &mut HttpContext, _next: Next<'_>) -> Result<(), PipelineError> {
+        context.response_mut().set_status(200);
+        context.response_mut().headers_mut().insert("vary", "Accept-Encoding");
+        Ok(())
+    }
+}
+
+fn policy(environment: AppEnvironment, origins: &[&str], strict: bool) -> CorsPolicy {
+    CorsPolicy::new(
+        environment,
+        origins.iter().map(|origin| origin.to_string()).collect(),
+        false,
+        vec!["authorization".to_string(), "content-type".to_string()],
+        600,
+        strict,
+    )
+}
+
+fn settings(repo: &MemoryRepository<Setting>) -> SettingService {
+    SettingService::new(Arc::new(Repository::new(Box::new(repo.clone()))))
+}
+
+fn configure(values: &[(&str, serde_json::Value)]) -> MemoryRepository<Setting> {
+    let repo = MemoryRepository::<Setting>::new();
+    let service = settings(&repo);
+    block_on(async {
+        for (key, value) in values {
+            service.update(key, value.clone()).await.unwrap();
+        }
+    });
+    repo
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Runtime::new().expect("runtime").block_on(future)
+}
+
+fn run(repo: MemoryRepository<Setting>, cache: CorsPolicyCache, request: HttpRequest) -> HttpContext {
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<SettingService, _>(move |_| settings(&repo));
+    container.register_singleton::<CorsPolicyCache, _>(move |_| cache.clone());
+    container.register_singleton::<AppConfig, _>(|_| AppConfig {
+        environment: AppEnvironment::Production,
+        ..AppConfig::default()
+    });
+    let services = container.build();
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(CorsPolicyMiddleware::new());
+    pipeline.add(OkResponse);
+    let _ = pipeline.run(&mut context);
+    context
+}
+
+fn request(method: &str, origin: &str) -> HttpRequest {
+    let mut request = HttpRequest::new(method, "/api/photos/timeline/2024");
+    request.headers_mut().insert("origin", origin);
+    request.headers_mut().insert("host", "photos.example.com");
+    request
+}
+
+#[test]
+fn empty_origin_list_is_permissive_only_in_development() {
+    let development = policy(AppEnvironment::Development, &[], false);
+    let production = policy(AppEnvironment::Production, &[], false);
+
+    assert_eq!(
+        development.decide(Some("http://localhost:4200"), None),
+        CorsDecision::Allow("http://localhost:4200".to_string())
+    );
+    assert_eq!(production.decide(Some("http://localhost:4200"), None), CorsDecision::Skip);
+}
+
+#[test]
+fn listed_origins_are_normalized() {
+    let policy = policy(AppEnvironment::Production, &[" https://Photos.Example.com/ "], true);
+
+    assert!(policy.allows_origin("https://photos.example.com"));
+    assert!(!policy.allows_origin("https://evil.example.com"));
+}
+
+#[test]
+fn wildcard_origin_allows_any_origin() {
+    let policy = policy(AppEnvironment::Production, &["*"], true);
+
+    assert_eq!(
+        policy.decide(Some("https://other.example.com"), None),
+        CorsDecision::Allow("https://other.example.com".to_string())
+    );
+}
+
+#[test]
+fn credentials_are_dropped_when_any_origin_is_allowed() {
+    let headers = vec!["authorization".to_string()];
+    let wildcard =
+        CorsPolicy::new(AppEnvironment::Production, vec!["*".to_string()], true, headers.clone(), 600, false);
+    let development = CorsPolicy::new(AppEnvironment::Development, Vec::new(), true, headers.clone(), 600, false);
+    let listed = CorsPolicy::new(
+        AppEnvironment::Production,
+        vec!["https://app.example.com".to_string()],
+        true,
+        headers,
+        600,
+        false,
+    );
+
+    assert!(!wildcard.allow_credentials);
+    assert!(!development.allow_credentials);
+    assert!(listed.allow_credentials);
+}
+
+#[test]
+fn strict_mode_rejects_unlisted_origins_but_allows_same_origin() {
+    let policy = policy(AppEnvironment::Production, &["https://app.example.com"], true);
+
+    assert_eq!(policy.decide(Some("https://evil.example.com"), Some("photos.example.com")), CorsDecision::Reject);
+    assert_eq!(policy.decide(Some("https://photos.example.com"), Some("photos.example.com")), CorsDecision::Skip);
+    assert_eq!(policy.decide(None, Some("photos.example.com")), CorsDecision::Skip);
+}
+
+#[test]
+fn preflight_reflects_configured_max_age() {
+    let repo =
+        configure(&[("cors.allowedOrigins", json!(["https://app.example.com"])), ("cors.maxAgeSeconds", json!(1200))]);
+
+    let mut request = request("OPTIONS", "https://app.example.com");
+    request.headers_mut().insert("access-control-request-method", "PUT");
+    let context = run(repo, CorsPolicyCache::new(), request);
+    let headers = context.response().headers();
+
+    assert_eq!(context.response().status(), 204);
+    assert_eq!(headers.get("access-control-allow-origin"), Some("https://app.example.com"));
+    assert_eq!(headers.get("access-control-max-age"), Some("1200"));
+    assert_eq!(headers.get("access-control-allow-headers"), Some("authorization, content-type, x-request-id"));
+}
+
+#[test]
+fn allowed_origin_merges_vary_header() {
+    let repo = configure(&[
+        ("cors.allowedOrigins", json!(["https://app.example.com"])),
+        ("cors.allowCredentials", json!(true)),
+    ]);
+
+    let context = run(repo, CorsPolicyCache::new(), request("GET", "https://app.example.com"));
+    let headers = context.response().headers();
+
+    assert_eq!(context.response().status(), 200);
+    assert_eq!(headers.get("access-control-allow-credentials"), Some("true"));
+    assert_eq!(headers.get("vary"), Some("Accept-Encoding, Origin"));
+}
+
+#[test]
+fn strict_mode_rejects_request_from_unlisted_origin() {
+    let repo = configure(&[("cors.allowedOrigins", json!(["https://app.example.com"])), ("cors.strict", json!(true))]);
+
+    let context = run(repo, CorsPolicyCache::new(), request("GET", "https://evil.example.com"));
+
+    assert_eq!(context.response().status(), 403);
+    assert!(context.response().headers().get("access-control-allow-origin").is_none());
+}
+
+#[test]
+fn cache_reloads_origins_after_invalidation() {
+    let settings = settings(&MemoryRepository::<Setting>::new());
+    let cache = CorsPolicyCache::new();
+
+    block_on(async {
+        let before = cache.policy(&settings, AppEnvironment::Production).await.unwrap();
+        assert!(!before.allows_origin("https://app.example.com"));
+
+        settings.update("cors.allowedOrigins", json!(["https://app.example.com"])).await.unwrap();
+        let stale = cache.policy(&settings, AppEnvironment::Production).await.unwrap();
+        assert!(!stale.allows_origin("https://app.example.com"));
+
+        cache.invalidate();
+        let reloaded = cache.policy(&settings, AppEnvironment::Production).await.unwrap();
+        assert!(reloaded.allows_origin("https://app.example.com"));
+    });
+}
@@ -5,7 +5,7 @@ use nimble_web::Policy;
 #[test]
 fn routes_require_authenticated() {
     let routes = DashboardController::routes();
-    assert_eq!(routes.len(), 4);
+    assert_eq!(routes.len(), 6);
 
     let list_route = &routes[0];
     assert_eq!(list_route.route.method(), "GET");
@@ -26,4 +26,14 @@ fn routes_require_authenticated() {
     assert_eq!(upload_route.route.method(), "POST");
     assert_eq!(upload_route.route.path(), "/api/dashboard/settings/logo/upload");
     assert_eq!(upload_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+
+    let stats_route = &routes[4];
+    assert_eq!(stats_route.route.method(), "GET");
+    assert_eq!(stats_route.route.path(), "/api/dashboard/stats");
+    assert_eq!(stats_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+
+    let overview_route = &routes[5];
+    assert_eq!(overview_route.route.method(), "GET");
+    assert_eq!(overview_route.route.path(), "/api/dashboard/overview");
+    assert_eq!(overview_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
 }
@@ -0,0 +1,44 @@
+use chrono::{TimeZone, Utc};
+use nimble_photos::dtos::{DashboardOverview, DashboardTotals, StorageUsage};
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn totals_read_snake_case_query_columns() {
+    let row = json!({
+        "total_photos": 120,
+        "total_albums": 4,
+        "total_users": 3,
+        "total_tags": 17,
+        "added_last_7_days": 5,
+        "added_last_30_days": 40,
+        "pending_jobs": 2
+    });
+
+    let totals: DashboardTotals = serde_json::from_value(row).unwrap();
+
+    assert_eq!(totals.total_photos, 120);
+    assert_eq!(totals.added_last_7_days, 5);
+    assert_eq!(totals.added_last_30_days, 40);
+    assert_eq!(totals.pending_jobs, 2);
+}
+
+#[test]
+fn overview_serializes_flat_camel_case_payload() {
+    let storage_id = Uuid::new_v4();
+    let overview = DashboardOverview {
+        totals: DashboardTotals { total_photos: 10, total_users: 1, ..DashboardTotals::default() },
+        storage: vec![StorageUsage { storage_id, label: "Main".to_string(), photo_count: 10, bytes: 2048 }],
+        last_backup_at: None,
+        generated_at: Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap(),
+    };
+
+    let value = serde_json::to_value(&overview).unwrap();
+
+    assert_eq!(value["totalPhotos"], 10);
+    assert_eq!(value["totalUsers"], 1);
+    assert_eq!(value["addedLast7Days"], 0);
+    assert_eq!(value["lastBackupAt"], serde_json::Value::Null);
+    assert_eq!(value["storage"][0]["storageId"], storage_id.to_string());
+    assert_eq!(value["storage"][0]["bytes"], 2048);
+}
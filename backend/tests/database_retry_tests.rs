@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use nimble_photos::models::AppConfig;
+use nimble_photos::repositories::ReadRetry;
+use nimble_photos::services::StartupRetryPolicy;
+
+fn policy(retries: u32) -> StartupRetryPolicy {
+    StartupRetryPolicy { retries, base_delay: Duration::from_millis(1), max_delay: Duration::from_millis(4) }
+}
+
+#[test]
+fn startup_delay_backs_off_exponentially_up_to_the_cap() {
+    let policy = policy(10);
+
+    assert_eq!(policy.delay_for(1), Duration::from_millis(1));
+    assert_eq!(policy.delay_for(2), Duration::from_millis(2));
+    assert_eq!(policy.delay_for(3), Duration::from_millis(4));
+    assert_eq!(policy.delay_for(30), Duration::from_millis(4));
+}
+
+#[test]
+fn startup_policy_reads_database_settings() {
+    let config = AppConfig::from_lookup(|key| match key {
+        "database.startupRetries" => Some("3".to_string()),
+        "database.retryDelayMs" => Some("250".to_string()),
+        _ => None,
+    })
+    .unwrap();
+
+    let policy = StartupRetryPolicy::from_config(&config);
+
+    assert_eq!(policy.retries, 3);
+    assert_eq!(policy.base_delay, Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn startup_policy_retries_until_the_operation_succeeds() {
+    let attempts = AtomicU32::new(0);
+
+    let result = policy(3)
+        .run("connect", || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 { Err(anyhow!("connection refused")) } else { Ok(42) }
+        })
+        .await;
+
+    assert_eq!(result.unwrap(), 42);
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn startup_policy_gives_up_after_the_configured_retries() {
+    let attempts = AtomicU32::new(0);
+
+    let result: anyhow::Result<()> = policy(2)
+        .run("connect", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow!("connection refused"))
+        })
+        .await;
+
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    assert!(result.unwrap_err().to_string().contains("after 3 attempt(s)"));
+}
+
+#[test]
+fn connection_errors_are_recognized() {
+    assert!(ReadRetry::is_connection_error("PoolTimedOut"));
+    assert!(ReadRetry::is_connection_error(
+        "Io(Os { code: 104, kind: ConnectionReset, message: \"Connection reset by peer\" })"
+    ));
+    assert!(!ReadRetry::is_connection_error("Database(PgDatabaseError { code: \"42P01\" })"));
+}
+
+#[tokio::test]
+async fn reads_are_retried_once_on_connection_loss_only() {
+    let attempts = AtomicU32::new(0);
+    let result: Result<u32, String> = ReadRetry::once("read", || async {
+        if attempts.fetch_add(1, Ordering::SeqCst) == 0 { Err("PoolTimedOut".to_string()) } else { Ok(7) }
+    })
+    .await;
+    assert_eq!(result, Ok(7));
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+    let attempts = AtomicU32::new(0);
+    let result: Result<u32, String> = ReadRetry::once("read", || async {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        Err("syntax error".to_string())
+    })
+    .await;
+    assert!(result.is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+}
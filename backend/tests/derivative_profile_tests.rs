@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use image::{ImageBuffer, ImageFormat, Rgb};
+use nimble_photos::entities::Setting;
+use nimble_photos::models::{DerivativeProfile, ThumbnailFormat};
+use nimble_photos::services::{
+    AvifThumbnailService, DerivativeProfileCache, PreviewExtractor, SettingService, ThumbnailExtractor,
+};
+use nimble_web::{MemoryRepository, Repository};
+use serde_json::json;
+use uuid::Uuid;
+
+fn settings(repo: &MemoryRepository<Setting>) -> SettingService {
+    SettingService::new(Arc::new(Repository::new(Box::new(repo.clone()))))
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Runtime::new().expect("runtime").block_on(future)
+}
+
+fn source_image(dir: &std::path::Path) -> std::path::PathBuf {
+    let source = dir.join("source.png");
+    ImageBuffer::<Rgb<u8>, Vec<u8>>::from_fn(800, 600, |x, y| Rgb([(x % 255) as u8, (y % 255) as u8, 90]))
+        .save(&source)
+        .unwrap();
+    source
+}
+
+#[test]
+fn unconfigured_settings_yield_the_default_profile() {
+    let settings = settings(&MemoryRepository::<Setting>::new());
+
+    let profile = block_on(settings.derivative_profile()).unwrap();
+
+    assert_eq!(profile, DerivativeProfile::default());
+}
+
+#[test]
+fn settings_are_validated_against_their_ranges() {
+    let settings = settings(&MemoryRepository::<Setting>::new());
+
+    block_on(async {
+        assert!(settings.update("preview.maxDimension", json!(100)).await.is_err());
+        assert!(settings.update("preview.jpegQuality", json!(101)).await.is_err());
+        assert!(settings.update("thumbnail.maxDimension", json!(2048)).await.is_err());
+        assert!(settings.update("thumbnail.format", json!("png")).await.is_err());
+
+        settings.update("preview.maxDimension", json!(2560)).await.unwrap();
+        settings.update("preview.jpegQuality", json!(90)).await.unwrap();
+        settings.update("thumbnail.format", json!("jpeg")).await.unwrap();
+
+        let profile = settings.derivative_profile().await.unwrap();
+        assert_eq!(profile.preview_max_dimension, 2560);
+        assert_eq!(profile.preview_jpeg_quality, 90);
+        assert_eq!(profile.thumbnail_max_dimension, 400);
+        assert_eq!(profile.thumbnail_format, ThumbnailFormat::Jpeg);
+    });
+}
+
+#[test]
+fn cache_reloads_profile_after_invalidation() {
+    let settings = settings(&MemoryRepository::<Setting>::new());
+    let cache = DerivativeProfileCache::new();
+
+    block_on(async {
+        assert_eq!(cache.profile(&settings).await.unwrap().preview_jpeg_quality, 75);
+
+        settings.update("preview.jpegQuality", json!(60)).await.unwrap();
+        assert_eq!(cache.profile(&settings).await.unwrap().preview_jpeg_quality, 75);
+
+        cache.invalidate();
+        assert_eq!(cache.profile(&settings).await.unwrap().preview_jpeg_quality, 60);
+    });
+}
+
+#[test]
+fn derivatives_built_with_other_parameters_are_stale() {
+    let defaults = DerivativeProfile::default();
+    let custom = DerivativeProfile { thumbnail_format: ThumbnailFormat::Jpeg, ..DerivativeProfile::default() };
+
+    assert!(!defaults.is_stale(DerivativeProfile::KIND_THUMBNAIL, None));
+    assert!(!defaults.is_stale(DerivativeProfile::KIND_PREVIEW, Some("1920px-q75")));
+    assert!(defaults.is_stale(DerivativeProfile::KIND_PREVIEW, Some("1920px-q90")));
+
+    assert!(custom.is_stale(DerivativeProfile::KIND_THUMBNAIL, None));
+    assert!(!custom.is_stale(DerivativeProfile::KIND_THUMBNAIL, Some(&custom.thumbnail_signature())));
+    assert!(!custom.is_stale(DerivativeProfile::KIND_PREVIEW, None));
+}
+
+#[test]
+fn thumbnail_lookup_prefers_the_configured_format() {
+    assert_eq!(ThumbnailFormat::Jpeg.lookup_order(), vec![ThumbnailFormat::Jpeg, ThumbnailFormat::Webp]);
+    assert_eq!(ThumbnailFormat::parse(" JPG "), Some(ThumbnailFormat::Jpeg));
+    assert_eq!(ThumbnailFormat::parse("gif"), None);
+}
+
+#[test]
+fn avif_is_negotiated_only_when_enabled_and_accepted() {
+    let enabled = DerivativeProfile { thumbnail_avif: true, ..DerivativeProfile::default() };
+    let browser = Some("image/avif,image/webp,image/apng,image/*,*/*;q=0.8");
+
+    assert_eq!(
+        enabled.thumbnail_candidates(browser),
+        vec![ThumbnailFormat::Avif, ThumbnailFormat::Webp, ThumbnailFormat::Jpeg]
+    );
+    assert_eq!(enabled.thumbnail_candidates(Some("image/webp,*/*")).first(), Some(&ThumbnailFormat::Webp));
+    assert_eq!(enabled.thumbnail_candidates(Some("image/avif;q=0, image/webp")).first(), Some(&ThumbnailFormat::Webp));
+    assert_eq!(enabled.thumbnail_candidates(None).first(), Some(&ThumbnailFormat::Webp));
+    assert_eq!(DerivativeProfile::default().thumbnail_candidates(browser).first(), Some(&ThumbnailFormat::Webp));
+}
+
+#[test]
+fn avif_sibling_is_encoded_from_the_thumbnail() {
+    let dir = std::env::temp_dir().join(format!("nimble-avif-thumbnail-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let thumbnail =
+        ThumbnailExtractor::new().with_max_border(64).extract_to(source_image(&dir), dir.join("t.webp")).unwrap();
+    let output = dir.join("ab").join("t.avif");
+
+    AvifThumbnailService::encode(&thumbnail, &output).unwrap();
+
+    let bytes = std::fs::read(&output).unwrap();
+    assert_eq!(&bytes[4..12], b"ftypavif");
+    assert!(!output.with_extension("avif.partial").exists());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn extractors_follow_the_profile() {
+    let dir = std::env::temp_dir().join(format!("nimble-derivative-profile-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let source = source_image(&dir);
+    let profile = DerivativeProfile {
+        preview_max_dimension: 300,
+        thumbnail_max_dimension: 100,
+        thumbnail_format: ThumbnailFormat::Jpeg,
+        ..DerivativeProfile::default()
+    };
+
+    let thumbnail = ThumbnailExtractor::from_profile(&profile).extract_to(&source, dir.join("thumb.jpg")).unwrap();
+    let bytes = std::fs::read(&thumbnail).unwrap();
+    assert_eq!(image::guess_format(&bytes).unwrap(), ImageFormat::Jpeg);
+    assert_eq!(image::load_from_memory(&bytes).unwrap().width(), 100);
+
+    let low = PreviewExtractor::from_profile(&DerivativeProfile { preview_jpeg_quality: 10, ..profile.clone() })
+        .extract_to(&source, dir.join("low.jpg"))
+        .unwrap();
+    let high = PreviewExtractor::from_profile(&DerivativeProfile { preview_jpeg_quality: 95, ..profile })
+        .extract_to(&source, dir.join("high.jpg"))
+        .unwrap();
+    assert_eq!(image::open(&low).unwrap().width(), 300);
+    assert!(std::fs::metadata(&low).unwrap().len() < std::fs::metadata(&high).unwrap().len());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
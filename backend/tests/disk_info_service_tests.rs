@@ -0,0 +1,77 @@
+use nimble_photos::entities::DiskInfo;
+use nimble_photos::services::DiskInfoService;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn counting_service(refresh_interval: Duration) -> (DiskInfoService, Arc<AtomicUsize>) {
+    let loads = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&loads);
+    let service = DiskInfoService::with_loader(refresh_interval, move || {
+        let generation = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        vec![DiskInfo {
+            name: format!("disk-{}", generation),
+            mount_point: "/".to_string(),
+            total_bytes: 100,
+            available_bytes: 50,
+        }]
+    });
+    (service, loads)
+}
+
+#[tokio::test]
+async fn disks_are_served_from_cache_within_refresh_interval() {
+    let (service, loads) = counting_service(Duration::from_secs(60));
+
+    let first = service.disks().await;
+    let second = service.disks().await;
+
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+    assert_eq!(first[0].name, "disk-1");
+    assert_eq!(second[0].name, "disk-1");
+}
+
+#[tokio::test]
+async fn explicit_refresh_reloads_immediately() {
+    let (service, loads) = counting_service(Duration::from_secs(60));
+    service.disks().await;
+
+    let refreshed = service.refresh().await;
+    let cached = service.disks().await;
+
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+    assert_eq!(refreshed[0].name, "disk-2");
+    assert_eq!(cached[0].name, "disk-2");
+}
+
+#[tokio::test]
+async fn stale_cache_is_returned_while_refreshing_in_background() {
+    let (service, loads) = counting_service(Duration::ZERO);
+    service.disks().await;
+
+    let stale = service.disks().await;
+    for _ in 0..50 {
+        if loads.load(Ordering::SeqCst) >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    assert_eq!(stale[0].name, "disk-1");
+    assert!(loads.load(Ordering::SeqCst) >= 2);
+}
+
+#[test]
+fn find_disk_prefers_longest_mount_point() {
+    let disk = |mount_point: &str| DiskInfo {
+        name: mount_point.to_string(),
+        mount_point: mount_point.to_string(),
+        total_bytes: 0,
+        available_bytes: 0,
+    };
+    let disks = vec![disk("/"), disk("/mnt/photos")];
+
+    let found = DiskInfoService::find_disk("/mnt/photos/2024", &disks);
+
+    assert_eq!(found.map(|disk| disk.mount_point), Some("/mnt/photos".to_string()));
+}
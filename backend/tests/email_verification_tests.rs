@@ -0,0 +1,32 @@
+use chrono::{Duration, Utc};
+use nimble_photos::models::{ApiError, ResendThrottle, VerificationEmail};
+
+#[test]
+fn resend_throttle_allows_one_email_per_address_per_cooldown() {
+    let throttle = ResendThrottle::new(Duration::seconds(60));
+    let now = Utc::now();
+
+    assert!(throttle.try_acquire("user@example.com", now));
+    assert!(!throttle.try_acquire(" USER@example.com ", now + Duration::seconds(30)));
+    assert!(throttle.try_acquire("other@example.com", now + Duration::seconds(30)));
+    assert!(throttle.try_acquire("user@example.com", now + Duration::seconds(60)));
+}
+
+#[test]
+fn verification_email_links_to_the_site_and_escapes_the_title() {
+    let email = VerificationEmail::new("Tom & Jerry's <Photos>", "https://photos.example.com/", "abc-123");
+
+    assert_eq!(email.link, "https://photos.example.com/verify-email?token=abc-123");
+    assert_eq!(email.subject(), "Confirm your email for Tom & Jerry's <Photos>");
+    assert!(email.render_text().contains("https://photos.example.com/verify-email?token=abc-123"));
+    assert!(email.render_html().contains("Tom &amp; Jerry&#39;s &lt;Photos&gt;"));
+}
+
+#[test]
+fn unverified_and_throttled_errors_have_their_own_codes() {
+    let unverified = ApiError::email_not_verified("Verify your email address before signing in");
+    let throttled = ApiError::too_many_requests("Slow down");
+
+    assert_eq!((unverified.status(), unverified.code()), (403, "email_not_verified"));
+    assert_eq!((throttled.status(), throttled.code()), (429, "too_many_requests"));
+}
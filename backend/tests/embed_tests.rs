@@ -0,0 +1,147 @@
+use nimble_photos::controllers::AssetsController;
+use nimble_photos::dtos::OEmbedResponse;
+use nimble_photos::entities::Photo;
+use nimble_photos::models::{AlbumEmbed, DerivativeProfile, EmbedTarget, PhotoEmbed};
+use uuid::Uuid;
+
+const BASE_URL: &str = "https://photos.example.com";
+const HASH: &str = "0123456789abcdef";
+
+fn photo(width: u32, height: u32, orientation: Option<u16>) -> Photo {
+    Photo {
+        id: Uuid::new_v4(),
+        name: "IMG_0001.jpg".to_string(),
+        hash: Some(HASH.to_string()),
+        width: Some(width),
+        height: Some(height),
+        orientation,
+        ..Photo::default()
+    }
+}
+
+fn embed(width: u32, height: u32) -> PhotoEmbed {
+    PhotoEmbed::new("My Photos", BASE_URL, &photo(width, height, None), HASH)
+}
+
+#[test]
+fn parse_accepts_same_host_photo_and_album_urls() {
+    let id = Uuid::new_v4();
+
+    assert_eq!(
+        EmbedTarget::parse("https://photos.example.com/p/0123456789ABCDEF", "photos.example.com"),
+        Some(EmbedTarget::SharedPhoto(HASH.to_string()))
+    );
+    assert_eq!(
+        EmbedTarget::parse(&format!("http://photos.example.com/photo/{}?x=1", id), "photos.example.com"),
+        Some(EmbedTarget::Photo(id))
+    );
+    assert_eq!(
+        EmbedTarget::parse(
+            &format!("https://photos.example.com/albums/{}/photo/{}", Uuid::new_v4(), id),
+            "photos.example.com"
+        ),
+        Some(EmbedTarget::Photo(id))
+    );
+    assert_eq!(
+        EmbedTarget::parse(&format!("https://photos.example.com/albums/{}#top", id), "photos.example.com"),
+        Some(EmbedTarget::Album(id))
+    );
+}
+
+#[test]
+fn parse_rejects_other_hosts_and_unknown_paths() {
+    let id = Uuid::new_v4();
+
+    assert_eq!(EmbedTarget::parse(&format!("https://evil.example.com/photo/{}", id), "photos.example.com"), None);
+    assert_eq!(EmbedTarget::parse(&format!("ftp://photos.example.com/photo/{}", id), "photos.example.com"), None);
+    assert_eq!(EmbedTarget::parse("https://photos.example.com/p/not-a-hash", "photos.example.com"), None);
+    assert_eq!(EmbedTarget::parse("https://photos.example.com/settings", "photos.example.com"), None);
+}
+
+#[test]
+fn fit_within_preserves_aspect_ratio() {
+    assert_eq!(PhotoEmbed::fit_within(4000, 3000, 1920, 1920), (1920, 1440));
+    assert_eq!(PhotoEmbed::fit_within(4000, 3000, 800, 300), (400, 300));
+    assert_eq!(PhotoEmbed::fit_within(640, 480, 1920, 1920), (640, 480));
+}
+
+#[test]
+fn rotated_orientation_swaps_dimensions() {
+    let embed = PhotoEmbed::new("My Photos", BASE_URL, &photo(4000, 3000, Some(6)), HASH);
+
+    assert_eq!((embed.width, embed.height), (Some(3000), Some(4000)));
+}
+
+#[test]
+fn oembed_returns_preview_sized_to_profile() {
+    let response = embed(4000, 3000).oembed(&DerivativeProfile::default(), None, None);
+
+    assert_eq!(response.kind, OEmbedResponse::TYPE_PHOTO);
+    assert_eq!(response.url.as_deref(), Some("https://photos.example.com/api/photos/preview/0123456789abcdef"));
+    assert_eq!((response.width, response.height), (Some(1920), Some(1440)));
+    assert_eq!((response.thumbnail_width, response.thumbnail_height), (Some(400), Some(300)));
+    assert_eq!(response.provider_url, "https://photos.example.com/");
+}
+
+#[test]
+fn oembed_falls_back_to_thumbnail_within_max_size() {
+    let response = embed(4000, 3000).oembed(&DerivativeProfile::default(), Some(500), Some(500));
+
+    assert_eq!(response.url.as_deref(), Some("https://photos.example.com/api/photos/thumbnail/0123456789abcdef"));
+    assert_eq!((response.width, response.height), (Some(400), Some(300)));
+}
+
+#[test]
+fn oembed_without_dimensions_is_a_link() {
+    let mut photo = photo(1, 1, None);
+    photo.width = None;
+    let response =
+        PhotoEmbed::new("My Photos", BASE_URL, &photo, HASH).oembed(&DerivativeProfile::default(), None, None);
+
+    assert_eq!(response.kind, OEmbedResponse::TYPE_LINK);
+    assert!(response.url.is_none());
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["type"], "link");
+    assert!(json.get("width").is_none());
+}
+
+#[test]
+fn share_page_escapes_title_and_links_oembed() {
+    let mut photo = photo(4000, 3000, None);
+    photo.title = Some("Fish & <Chips>".to_string());
+    let html = PhotoEmbed::new("My Photos", BASE_URL, &photo, HASH).render_page(&DerivativeProfile::default());
+
+    assert!(html.contains("<meta property=\"og:title\" content=\"Fish &amp; &lt;Chips&gt;\">"));
+    assert!(html.contains("<meta property=\"og:image:width\" content=\"1920\">"));
+    assert!(html.contains("/api/oembed?format=json&amp;url=https%3A%2F%2Fphotos.example.com%2Fp%2F0123456789abcdef"));
+    assert!(!html.contains("<Chips>"));
+}
+
+#[test]
+fn album_oembed_uses_cover_thumbnail() {
+    let album = AlbumEmbed {
+        site_title: "My Photos".to_string(),
+        base_url: BASE_URL.to_string(),
+        album_id: Uuid::new_v4(),
+        name: "Holiday".to_string(),
+        cover: Some(embed(4000, 3000)),
+    };
+    let response = album.oembed(&DerivativeProfile::default());
+
+    assert_eq!(response.kind, OEmbedResponse::TYPE_LINK);
+    assert_eq!(response.title, "Holiday");
+    assert_eq!(
+        response.thumbnail_url.as_deref(),
+        Some("https://photos.example.com/api/photos/thumbnail/0123456789abcdef")
+    );
+
+    let uncovered = AlbumEmbed { cover: None, ..album }.oembed(&DerivativeProfile::default());
+    assert!(uncovered.thumbnail_url.is_none());
+}
+
+#[test]
+fn share_hash_only_matches_share_paths() {
+    assert_eq!(AssetsController::share_hash("/p/0123456789ABCDEF"), Some(HASH.to_string()));
+    assert_eq!(AssetsController::share_hash("/p/short"), None);
+    assert_eq!(AssetsController::share_hash("/photo/0123456789abcdef"), None);
+}
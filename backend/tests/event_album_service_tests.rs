@@ -0,0 +1,103 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use nimble_photos::dtos::AutoAlbumRequest;
+use nimble_photos::services::{EventAlbumService, EventCandidate, EventClusterOptions};
+use uuid::Uuid;
+
+fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+}
+
+fn candidate(taken_at: DateTime<Utc>, location: Option<(f64, f64)>) -> EventCandidate {
+    EventCandidate {
+        id: Uuid::new_v4(),
+        hash: Some("0123456789abcdef".to_string()),
+        taken_at,
+        latitude: location.map(|(lat, _)| lat),
+        longitude: location.map(|(_, lon)| lon),
+    }
+}
+
+fn options(min_photos: usize) -> EventClusterOptions {
+    EventClusterOptions { min_photos, ..EventClusterOptions::default() }
+}
+
+#[test]
+fn splits_clusters_on_time_gap() {
+    let photos = vec![
+        candidate(at(2024, 5, 3, 10), None),
+        candidate(at(2024, 5, 3, 12), None),
+        candidate(at(2024, 5, 3, 20), None),
+        candidate(at(2024, 5, 3, 23), None),
+    ];
+
+    let clusters = EventAlbumService::cluster(photos, &options(1));
+
+    assert_eq!(clusters.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2]);
+}
+
+#[test]
+fn sorts_candidates_before_clustering() {
+    let photos = vec![
+        candidate(at(2024, 5, 3, 12), None),
+        candidate(at(2024, 5, 4, 12), None),
+        candidate(at(2024, 5, 3, 10), None),
+    ];
+
+    let clusters = EventAlbumService::cluster(photos, &options(1));
+
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[0][0].taken_at, at(2024, 5, 3, 10));
+}
+
+#[test]
+fn splits_clusters_on_distance_when_enabled() {
+    let paris = Some((48.8566, 2.3522));
+    let berlin = Some((52.52, 13.405));
+    let photos = vec![
+        candidate(at(2024, 5, 3, 10), paris),
+        candidate(at(2024, 5, 3, 11), None),
+        candidate(at(2024, 5, 3, 12), berlin),
+    ];
+
+    let without_distance = EventAlbumService::cluster(photos.clone(), &options(1));
+    let with_distance =
+        EventAlbumService::cluster(photos, &EventClusterOptions { max_distance_km: Some(50.0), ..options(1) });
+
+    assert_eq!(without_distance.len(), 1);
+    assert_eq!(with_distance.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+}
+
+#[test]
+fn drops_clusters_below_minimum_size() {
+    let photos = vec![
+        candidate(at(2024, 5, 3, 10), None),
+        candidate(at(2024, 5, 3, 11), None),
+        candidate(at(2024, 5, 3, 12), None),
+        candidate(at(2024, 6, 1, 12), None),
+    ];
+
+    let clusters = EventAlbumService::cluster(photos, &options(3));
+
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0].len(), 3);
+}
+
+#[test]
+fn request_overrides_default_options() {
+    let request =
+        AutoAlbumRequest { dry_run: true, gap_hours: Some(1.5), max_distance_km: Some(10.0), min_photos: Some(0) };
+
+    let options = EventClusterOptions::default().with_request(&request);
+
+    assert_eq!(options.gap, Duration::minutes(90));
+    assert_eq!(options.max_distance_km, Some(10.0));
+    assert_eq!(options.min_photos, 1);
+}
+
+#[test]
+fn titles_reflect_date_range() {
+    assert_eq!(EventAlbumService::title(at(2024, 5, 3, 8), at(2024, 5, 3, 20)), "3 May 2024");
+    assert_eq!(EventAlbumService::title(at(2024, 5, 3, 8), at(2024, 5, 7, 20)), "3–7 May 2024");
+    assert_eq!(EventAlbumService::title(at(2024, 4, 28, 8), at(2024, 5, 2, 20)), "28 Apr – 2 May 2024");
+    assert_eq!(EventAlbumService::title(at(2023, 12, 28, 8), at(2024, 1, 2, 20)), "28 Dec 2023 – 2 Jan 2024");
+}
@@ -0,0 +1,93 @@
+use chrono::{TimeZone, Utc};
+use nimble_photos::dtos::{FeedAlbum, FeedPhoto};
+use nimble_photos::middlewares::FeedMiddleware;
+use nimble_photos::models::{AtomFeed, FeedDocument};
+use nimble_photos::services::FeedService;
+use uuid::Uuid;
+
+fn photo(name: &str, title: Option<&str>, day: u32) -> FeedPhoto {
+    FeedPhoto {
+        id: Uuid::new_v4(),
+        hash: format!("hash{}", day),
+        name: name.to_string(),
+        title: title.map(str::to_string),
+        caption: Some("Beach & <sunset>".to_string()),
+        date_taken: Some(Utc.with_ymd_and_hms(2026, 2, day, 18, 30, 0).unwrap()),
+        published: Utc.with_ymd_and_hms(2026, 3, day, 9, 0, 0).unwrap(),
+    }
+}
+
+#[test]
+fn photo_feed_links_preview_and_thumbnail() {
+    let photos = vec![photo("IMG_0002.jpg", Some("Sunset"), 2), photo("IMG_0001.jpg", None, 1)];
+    let feed = FeedService::photo_feed("My <Photos>", "https://photos.example.com", &photos, "image/webp");
+
+    assert_eq!(feed.self_link, "https://photos.example.com/feeds/photos.atom");
+    assert_eq!(feed.updated(), photos[0].published);
+    assert_eq!(feed.entries[0].title, "Sunset");
+    assert_eq!(feed.entries[1].title, "IMG_0001.jpg");
+    assert_eq!(feed.entries[0].link, "https://photos.example.com/api/photos/preview/hash2");
+
+    let xml = feed.render();
+    assert!(xml.contains("<title>My &lt;Photos&gt; - Latest photos</title>"));
+    assert!(xml.contains(
+        "<link rel=\"enclosure\" type=\"image/webp\" href=\"https://photos.example.com/api/photos/thumbnail/hash2\"/>"
+    ));
+    assert!(xml.contains("<summary>Beach &amp; &lt;sunset&gt;\nTaken 2026-02-02 18:30 UTC</summary>"));
+    assert!(xml.contains(&format!("<id>urn:uuid:{}</id>", photos[0].id)));
+}
+
+#[test]
+fn album_feed_skips_missing_covers() {
+    let album = FeedAlbum {
+        id: Uuid::new_v4(),
+        name: "Holiday".to_string(),
+        description: None,
+        create_date: Utc.with_ymd_and_hms(2026, 3, 5, 12, 0, 0).unwrap(),
+        thumbnail_hash: None,
+        photo_count: 1,
+    };
+    let feed = FeedService::album_feed("Nimble", "http://localhost", &[album.clone()], "image/jpeg");
+
+    assert_eq!(feed.entries[0].link, format!("http://localhost/albums/{}", album.id));
+    assert_eq!(feed.entries[0].summary.as_deref(), Some("1 photo"));
+    assert!(feed.entries[0].enclosure.is_none());
+    assert!(!feed.render().contains("rel=\"enclosure\""));
+}
+
+#[test]
+fn empty_feed_has_a_stable_document() {
+    let feed = FeedService::photo_feed("Nimble", "http://localhost", &[], "image/jpeg");
+    assert_eq!(feed.document(), feed.document());
+    assert_eq!(feed.document().last_modified_header(), "Thu, 01 Jan 1970 00:00:00 GMT");
+}
+
+#[test]
+fn conditional_requests_match_etag_and_date() {
+    let document = FeedDocument::new("<feed/>".to_string(), Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap());
+    let last_modified = document.last_modified_header();
+    assert_eq!(last_modified, "Mon, 02 Mar 2026 09:00:00 GMT");
+
+    assert!(document.is_not_modified(Some(&document.etag), None));
+    assert!(document.is_not_modified(Some(&format!("\"other\", W/{}", document.etag)), None));
+    assert!(!document.is_not_modified(Some("\"other\""), Some(&last_modified)));
+
+    assert!(document.is_not_modified(None, Some(&last_modified)));
+    assert!(!document.is_not_modified(None, Some("Sun, 01 Mar 2026 09:00:00 GMT")));
+    assert!(!document.is_not_modified(None, Some("yesterday")));
+    assert!(!document.is_not_modified(None, None));
+
+    let changed = FeedDocument::new("<feed></feed>".to_string(), document.last_modified);
+    assert_ne!(changed.etag, document.etag);
+    assert_eq!(AtomFeed::CONTENT_TYPE, "application/atom+xml; charset=utf-8");
+}
+
+#[test]
+fn base_url_prefers_forwarded_headers() {
+    assert_eq!(FeedMiddleware::base_url(None, Some("photos.local:5151")), "http://photos.local:5151");
+    assert_eq!(
+        FeedMiddleware::base_url(Some("https, http"), Some("photos.example.com, proxy")),
+        "https://photos.example.com"
+    );
+    assert_eq!(FeedMiddleware::base_url(Some("javascript"), Some("evil\"><x")), "http://localhost");
+}
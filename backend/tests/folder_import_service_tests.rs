@@ -0,0 +1,117 @@
+use chrono::Utc;
+use nimble_photos::dtos::{FolderImportRequest, ImportBatchStatus};
+use nimble_photos::entities::PipelineJob;
+use nimble_photos::services::FolderImportService;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+fn fixture_root() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("nimble-folder-import-{}", Uuid::new_v4()));
+    for file in [
+        "2019/holiday/IMG_0001.JPG",
+        "2019/holiday/IMG_0002.nef",
+        "2019/holiday/notes.txt",
+        "2019/cover.png",
+        ".thumbnails/ab/cd/abcd.webp",
+        "2019/.hidden/IMG_0003.jpg",
+    ] {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).expect("fixture directory should be created");
+        fs::write(&path, b"photo").expect("fixture file should be written");
+    }
+    root.canonicalize().expect("fixture root should resolve")
+}
+
+fn relative_paths(root: &Path, folder: &Path, recursive: bool) -> Vec<String> {
+    let mut paths = FolderImportService::scan_folder(root, folder, recursive)
+        .expect("scan should succeed")
+        .into_iter()
+        .map(|file| file.relative_path)
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn scan_folder_finds_images_recursively_and_skips_hidden_entries() {
+    let root = fixture_root();
+
+    let paths = relative_paths(&root, &root, true);
+
+    assert_eq!(
+        paths,
+        vec![
+            "2019/cover.png".to_string(),
+            "2019/holiday/IMG_0001.JPG".to_string(),
+            "2019/holiday/IMG_0002.nef".to_string(),
+        ]
+    );
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn scan_folder_honours_non_recursive_requests() {
+    let root = fixture_root();
+
+    let paths = relative_paths(&root, &root.join("2019"), false);
+
+    assert_eq!(paths, vec!["2019/cover.png".to_string()]);
+    let _ = fs::remove_dir_all(root);
+}
+
+#[cfg(unix)]
+#[test]
+fn scan_folder_does_not_follow_symlinks() {
+    let root = fixture_root();
+    let outside = fixture_root();
+    std::os::unix::fs::symlink(outside.join("2019/cover.png"), root.join("2019/linked.png"))
+        .expect("symlink should be created");
+    std::os::unix::fs::symlink(outside.join("2019/holiday"), root.join("2019/escape"))
+        .expect("symlink should be created");
+
+    let paths = relative_paths(&root, &root, true);
+
+    assert_eq!(
+        paths,
+        vec![
+            "2019/cover.png".to_string(),
+            "2019/holiday/IMG_0001.JPG".to_string(),
+            "2019/holiday/IMG_0002.nef".to_string(),
+        ]
+    );
+    let _ = fs::remove_dir_all(root);
+    let _ = fs::remove_dir_all(outside);
+}
+
+#[test]
+fn folder_import_request_defaults_to_recursive() {
+    let request: FolderImportRequest = serde_json::from_str(r#"{"path":"2019"}"#).unwrap();
+
+    assert_eq!(request.path, "2019");
+    assert!(request.recursive);
+}
+
+#[test]
+fn import_batch_status_counts_jobs_by_state() {
+    let batch_id = Uuid::new_v4();
+    let storage_id = Uuid::new_v4();
+    let job = |state: &str| {
+        let mut job = PipelineJob::queued(storage_id, "2019/a.jpg", "a.jpg", 5, None).with_batch(Some(batch_id), true);
+        job.state = state.to_string();
+        job.updated_at = Utc::now();
+        job
+    };
+    let jobs = vec![
+        job(PipelineJob::STATE_QUEUED),
+        job(PipelineJob::STATE_RUNNING),
+        job(PipelineJob::STATE_COMPLETED),
+        job(PipelineJob::STATE_COMPLETED),
+        job(PipelineJob::STATE_FAILED),
+    ];
+
+    let status = ImportBatchStatus::from_jobs(batch_id, &jobs);
+
+    assert_eq!(status, ImportBatchStatus { batch_id, total: 5, queued: 1, running: 1, completed: 2, failed: 1 });
+    assert!(jobs.iter().all(|job| job.in_place && job.batch_id == Some(batch_id)));
+}
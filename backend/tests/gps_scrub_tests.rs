@@ -0,0 +1,107 @@
+use exif::{In, Reader, Tag};
+use image::{ImageFormat, RgbImage};
+use nimble_photos::services::GpsScrubService;
+use std::io::Cursor;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("nimble-gps-scrub-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Little-endian TIFF with Make in IFD0 and a GPS IFD holding latitude.
+fn exif_tiff() -> Vec<u8> {
+    let mut tiff = Vec::new();
+    tiff.extend_from_slice(b"II");
+    tiff.extend_from_slice(&42u16.to_le_bytes());
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+
+    let entry = |tiff: &mut Vec<u8>, tag: u16, field_type: u16, count: u32, value: [u8; 4]| {
+        tiff.extend_from_slice(&tag.to_le_bytes());
+        tiff.extend_from_slice(&field_type.to_le_bytes());
+        tiff.extend_from_slice(&count.to_le_bytes());
+        tiff.extend_from_slice(&value);
+    };
+
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    entry(&mut tiff, 0x010F, 2, 6, 38u32.to_le_bytes());
+    entry(&mut tiff, 0x8825, 4, 1, 44u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    tiff.extend_from_slice(b"Canon\0");
+
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    entry(&mut tiff, 0x0001, 2, 2, *b"N\0\0\0");
+    entry(&mut tiff, 0x0002, 5, 3, 74u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    for (numerator, denominator) in [(47u32, 1u32), (36, 1), (1234, 100)] {
+        tiff.extend_from_slice(&numerator.to_le_bytes());
+        tiff.extend_from_slice(&denominator.to_le_bytes());
+    }
+    tiff
+}
+
+fn jpeg_with_gps() -> Vec<u8> {
+    let mut encoded = Cursor::new(Vec::new());
+    RgbImage::from_pixel(8, 8, image::Rgb([120, 80, 40])).write_to(&mut encoded, ImageFormat::Jpeg).unwrap();
+    let encoded = encoded.into_inner();
+
+    let mut payload = b"Exif\0\0".to_vec();
+    payload.extend_from_slice(&exif_tiff());
+    let mut jpeg = encoded[..2].to_vec();
+    jpeg.extend_from_slice(&[0xFF, 0xE1]);
+    jpeg.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+    jpeg.extend_from_slice(&payload);
+    jpeg.extend_from_slice(&encoded[2..]);
+    jpeg
+}
+
+fn read_exif(bytes: &[u8]) -> exif::Exif {
+    Reader::new().read_from_container(&mut Cursor::new(bytes)).unwrap()
+}
+
+#[test]
+fn scrubbed_download_drops_gps_and_keeps_the_original() {
+    let dir = temp_dir();
+    let source = dir.join("original.jpg");
+    let original = jpeg_with_gps();
+    std::fs::write(&source, &original).unwrap();
+    assert!(read_exif(&original).get_field(Tag::GPSLatitude, In::PRIMARY).is_some());
+
+    let output = dir.join("scrubbed").join("original.jpg");
+    GpsScrubService::new().scrub_to(&source, &output).unwrap();
+
+    let scrubbed = std::fs::read(&output).unwrap();
+    let exif = read_exif(&scrubbed);
+    assert!(exif.fields().all(|field| field.ifd_num != In::PRIMARY || field.tag != Tag::GPSInfoIFDPointer));
+    assert!(exif.get_field(Tag::GPSLatitude, In::PRIMARY).is_none());
+    assert!(exif.get_field(Tag::GPSLatitudeRef, In::PRIMARY).is_none());
+    assert_eq!(exif.get_field(Tag::Make, In::PRIMARY).unwrap().display_value().to_string(), "\"Canon\"");
+    assert!(image::load_from_memory(&scrubbed).is_ok());
+
+    assert_eq!(std::fs::read(&source).unwrap(), original);
+    assert!(GpsScrubService::is_fresh(&source, &output));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn non_jpeg_files_are_reencoded() {
+    let mut png = Cursor::new(Vec::new());
+    RgbImage::from_pixel(4, 4, image::Rgb([1, 2, 3])).write_to(&mut png, ImageFormat::Png).unwrap();
+
+    let scrubbed = GpsScrubService::new().scrub_bytes(png.get_ref(), "png").unwrap();
+    assert_eq!(image::guess_format(&scrubbed).unwrap(), ImageFormat::Png);
+    assert!(GpsScrubService::new().scrub_bytes(b"not an image", "png").is_err());
+}
+
+#[test]
+fn admins_bypass_scrubbing_unless_they_ask() {
+    assert!(!GpsScrubService::should_strip(true, true, None));
+    assert!(GpsScrubService::should_strip(true, true, Some(true)));
+    assert!(GpsScrubService::should_strip(true, false, None));
+    assert!(GpsScrubService::should_strip(true, false, Some(false)));
+    assert!(!GpsScrubService::should_strip(false, false, None));
+    assert!(GpsScrubService::should_strip(false, false, Some(true)));
+}
@@ -1,4 +1,4 @@
-use nimble_photos::services::HashService;
+use nimble_photos::services::{FileService, HashService};
 use std::fs;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -13,13 +13,12 @@ fn compute_is_stable_for_same_input() {
     let service = HashService::new();
     let data = b"hash-service-stability-check".to_vec();
     let file_size = data.len();
-    let file_date = UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
 
-    let first = service.compute(&data, file_size, file_date);
-    let second = service.compute(&data, file_size, file_date);
+    let first = service.compute(&data, file_size);
+    let second = service.compute(&data, file_size);
 
     assert_eq!(first, second);
-    assert!(!first.is_empty());
+    assert_eq!(first.len(), HashService::HASH_LENGTH);
 }
 
 #[test]
@@ -30,7 +29,7 @@ fn compute_file_matches_compute_for_same_file_metadata() {
 
     fs::write(&path, &data).expect("failed to create temp test file");
     let metadata = fs::metadata(&path).expect("failed to load temp file metadata");
-    let expected = service.compute(&data, metadata.len() as usize, metadata.modified().unwrap());
+    let expected = service.compute(&data, metadata.len() as usize);
 
     let actual = service.compute_file(path.to_str().expect("invalid temp file path")).expect("compute_file failed");
 
@@ -48,3 +47,20 @@ fn compute_file_returns_error_for_missing_path() {
 
     assert!(result.is_err());
 }
+
+#[test]
+fn normalize_accepts_only_full_length_hex_hashes() {
+    assert_eq!(HashService::normalize("0123456789ABCDEF").as_deref(), Some("0123456789abcdef"));
+    assert_eq!(HashService::normalize("0123456789abcdef").as_deref(), Some("0123456789abcdef"));
+    assert!(HashService::normalize("abcd").is_none());
+    assert!(HashService::normalize("0123456789abcdef00").is_none());
+    assert!(HashService::normalize("0123456789abcdeg").is_none());
+    assert!(HashService::normalize("../../etc/passwd").is_none());
+}
+
+#[test]
+fn hash_path_shards_by_hash_prefix() {
+    let path = FileService::hash_path("/data/.thumbnails", "0123456789abcdef", "webp");
+
+    assert_eq!(path, PathBuf::from("/data/.thumbnails/01/23/0123456789abcdef.webp"));
+}
@@ -1,6 +1,8 @@
 use chrono::{TimeZone, Utc};
 use nimble_photos::models::property_map::PropertyMap;
-use nimble_photos::services::image_categorizer::{CategorizeRequest, ImageCategorizer, TemplateCategorizer};
+use nimble_photos::services::image_categorizer::{
+    CategorizeRequest, ImageCategorizer, InPlaceCategorizer, TemplateCategorizer,
+};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -92,3 +94,22 @@ fn template_categorizer_requires_working_directory() {
 
     assert!(result.is_err());
 }
+
+#[test]
+fn in_place_categorizer_leaves_source_file_untouched() {
+    let root = unique_temp_dir("in-place");
+    let source = root.join("2019").join("holiday").join("IMG_0001.jpg");
+    write_test_file(&source, b"photo");
+
+    let mut properties = PropertyMap::new();
+    properties.insert::<PathBuf>(root.clone()).alias(WORKING_DIRECTORY);
+    properties.insert::<String>("abcdef".to_string()).alias(HASH);
+
+    let result = InPlaceCategorizer.categorize(&CategorizeRequest::new(&source, &properties)).unwrap();
+
+    assert_eq!(result.final_path, source);
+    assert_eq!(result.hash.as_deref(), Some("abcdef"));
+    assert!(source.exists());
+    assert!(InPlaceCategorizer.categorize(&CategorizeRequest::new(&root.join("missing.jpg"), &properties)).is_err());
+    let _ = fs::remove_dir_all(root);
+}
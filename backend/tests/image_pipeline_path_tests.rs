@@ -9,6 +9,7 @@ fn make_storage(path: PathBuf) -> StorageLocation {
         label: "Primary".to_string(),
         path: path.to_string_lossy().to_string(),
         is_default: true,
+        is_readonly: false,
         created_at: "2026-02-17T00:00:00Z".to_string(),
         category_template: "{year}/{date:%Y-%m-%d}/{fileName}".to_string(),
     }
@@ -17,13 +18,13 @@ fn make_storage(path: PathBuf) -> StorageLocation {
 #[test]
 fn source_path_joins_storage_path_and_relative_path() {
     let root = std::env::temp_dir().join("nimble-image-path-test-source");
-    let payload = ImageProcessPayload {
-        storage: make_storage(root.clone()),
-        relative_path: "temp/abcd1234.jpg".to_string(),
-        file_name: "abcd1234.jpg".to_string(),
-        byte_size: 42,
-        content_type: Some("image/jpeg".to_string()),
-    };
+    let payload = ImageProcessPayload::new(
+        make_storage(root.clone()),
+        "temp/abcd1234.jpg".to_string(),
+        "abcd1234.jpg".to_string(),
+        42,
+        Some("image/jpeg".to_string()),
+    );
 
     assert_eq!(payload.source_path(), root.join("temp").join("abcd1234.jpg"));
 }
@@ -31,13 +32,13 @@ fn source_path_joins_storage_path_and_relative_path() {
 #[test]
 fn working_directory_matches_storage_normalized_path() {
     let root = std::env::temp_dir().join("nimble-image-path-test-workdir");
-    let payload = ImageProcessPayload {
-        storage: make_storage(root.clone()),
-        relative_path: "temp/file.jpg".to_string(),
-        file_name: "file.jpg".to_string(),
-        byte_size: 42,
-        content_type: None,
-    };
+    let payload = ImageProcessPayload::new(
+        make_storage(root.clone()),
+        "temp/file.jpg".to_string(),
+        "file.jpg".to_string(),
+        42,
+        None,
+    );
 
     assert_eq!(payload.working_directory(), root);
 }
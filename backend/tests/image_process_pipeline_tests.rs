@@ -1,12 +1,15 @@
 use chrono::Utc;
 use image::{ImageBuffer, Rgb};
-use nimble_photos::entities::StorageLocation;
+use nimble_photos::entities::{PipelineJob, StorageLocation};
 use nimble_photos::entities::{exif::ExifModel, photo::Photo};
+use nimble_photos::models::AppConfig;
 use nimble_photos::services::background_task_runner::BackgroundTaskRunner;
 use nimble_photos::services::exif_service::ExifService;
 use nimble_photos::services::file_service::FileService;
 use nimble_photos::services::hash_service::HashService;
-use nimble_photos::services::image_pipeline::{ImageProcessPayload, ImageProcessPipeline, ImageProcessPipelineContext};
+use nimble_photos::services::image_pipeline::{
+    ImageProcessPayload, ImageProcessPipeline, ImageProcessPipelineContext, SyncProcessStatus,
+};
 use nimble_photos::services::photo_upload_service::StoredUploadFile;
 use nimble_photos::services::{PreviewExtractor, ThumbnailExtractor};
 use nimble_web::Configuration;
@@ -19,6 +22,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
 fn unique_temp_dir(name: &str) -> PathBuf {
@@ -133,8 +137,8 @@ async fn pipeline_processes_uploaded_file_and_persists_metadata() {
     assert_eq!(exif_models[0].image_id, photo.id, "exif metadata must reference the photo");
 }
 
-#[test]
-fn enqueue_uploaded_files_schedules_task_for_each_file() {
+#[tokio::test]
+async fn enqueue_uploaded_files_schedules_task_for_each_file() {
     let storage_root = std::env::temp_dir().join("pipeline-enqueue");
     let thumbnail_root = storage_root.join("thumbnails");
     let preview_root = storage_root.join("previews");
@@ -175,8 +179,202 @@ fn enqueue_uploaded_files_schedules_task_for_each_file() {
     ];
     let file_count = files.len();
 
-    pipeline.enqueue_files(storage, files).expect("enqueue should succeed");
+    pipeline.enqueue_files(storage, files).await.expect("enqueue should succeed");
 
     let runner = provider.get::<BackgroundTaskRunner>();
     assert_eq!(runner.queued_count(), file_count);
 }
+
+#[tokio::test]
+async fn enqueue_uploaded_files_records_durable_job_per_file() {
+    let storage_root = unique_temp_dir("pipeline-jobs");
+    let thumbnail_root = storage_root.join("thumbnails");
+    let preview_root = storage_root.join("previews");
+
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<BackgroundTaskRunner, _>(|_| BackgroundTaskRunner::new(2));
+    container.register_singleton::<HashService, _>(|_| HashService::new());
+    container.register_singleton::<ExifService, _>(|_| ExifService::new());
+    container.register_singleton::<ThumbnailExtractor, _>(|_| ThumbnailExtractor::new());
+    container.register_singleton::<PreviewExtractor, _>(|_| PreviewExtractor::new());
+    container
+        .register_singleton::<Repository<Photo>, _>(|_| Repository::new(Box::new(MemoryRepository::<Photo>::new())));
+    container.register_singleton::<Repository<ExifModel>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<ExifModel>::new()))
+    });
+    container.register_singleton::<Repository<PipelineJob>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<PipelineJob>::new()))
+    });
+    container.register_singleton::<FileService, _>(|_| FileService::new());
+    let provider = Arc::new(container.build());
+
+    let pipeline = ImageProcessPipeline::new(ImageProcessPipelineContext::new(
+        Arc::clone(&provider),
+        test_configuration(&thumbnail_root, &preview_root),
+    ));
+
+    let storage = create_storage(Uuid::new_v4(), "Jobs", &storage_root);
+    let files = vec![StoredUploadFile {
+        file_name: "durable.jpg".to_string(),
+        relative_path: "temp/durable.jpg".to_string(),
+        byte_size: 2048,
+        content_type: Some("image/jpeg".to_string()),
+    }];
+
+    pipeline.enqueue_files(storage.clone(), files).await.expect("enqueue should succeed");
+
+    let job_repo = provider.get::<Repository<PipelineJob>>();
+    let jobs =
+        job_repo.query(QueryBuilder::<PipelineJob>::new().page(1, 10).build()).await.expect("job query failed").items;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].storage_id, storage.id);
+    assert_eq!(jobs[0].relative_path, "temp/durable.jpg");
+    assert_eq!(jobs[0].state, PipelineJob::STATE_QUEUED);
+    assert_eq!(jobs[0].attempts, 0);
+    assert!(jobs[0].is_pending());
+}
+
+#[tokio::test]
+async fn process_now_returns_persisted_photo_for_each_file() {
+    let storage_root = unique_temp_dir("pipeline-sync");
+    let temp_file = storage_root.join("temp").join("sync.jpg");
+    write_test_image(&temp_file);
+    let file_size = fs::metadata(&temp_file).expect("metadata missing").len() as usize;
+
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<BackgroundTaskRunner, _>(|_| BackgroundTaskRunner::new(2));
+    container.register_singleton::<HashService, _>(|_| HashService::new());
+    container.register_singleton::<ExifService, _>(|_| ExifService::new());
+    container.register_singleton::<ThumbnailExtractor, _>(|_| ThumbnailExtractor::new());
+    container.register_singleton::<PreviewExtractor, _>(|_| PreviewExtractor::new());
+    container
+        .register_singleton::<Repository<Photo>, _>(|_| Repository::new(Box::new(MemoryRepository::<Photo>::new())));
+    container.register_singleton::<Repository<ExifModel>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<ExifModel>::new()))
+    });
+    container.register_singleton::<FileService, _>(|_| FileService::new());
+    let provider = Arc::new(container.build());
+
+    let pipeline = ImageProcessPipeline::new(ImageProcessPipelineContext::new(
+        Arc::clone(&provider),
+        test_configuration(&storage_root.join("thumbnails"), &storage_root.join("previews")),
+    ));
+
+    let storage = create_storage(Uuid::new_v4(), "Sync", &storage_root);
+    let files = vec![StoredUploadFile {
+        file_name: "sync.jpg".to_string(),
+        relative_path: "temp/sync.jpg".to_string(),
+        byte_size: file_size,
+        content_type: Some("image/jpeg".to_string()),
+    }];
+
+    let processed = pipeline
+        .process_now(storage, files, Uuid::new_v4(), None, Duration::from_secs(30))
+        .await
+        .expect("synchronous processing failed");
+
+    let photos = query_photos(&provider.get::<Repository<Photo>>()).await;
+    assert_eq!(processed.len(), 1);
+    assert_eq!(processed[0].status, SyncProcessStatus::Completed);
+    assert!(processed[0].has_thumbnail, "thumbnail should be generated inline");
+    assert_eq!(processed[0].photo.as_ref().map(|photo| photo.id), photos.first().map(|photo| photo.id));
+}
+
+#[tokio::test]
+async fn process_now_defers_remaining_files_when_the_deadline_passes() {
+    let storage_root = unique_temp_dir("pipeline-sync-timeout");
+
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<BackgroundTaskRunner, _>(|_| BackgroundTaskRunner::new(2));
+    container.register_singleton::<HashService, _>(|_| HashService::new());
+    container.register_singleton::<ExifService, _>(|_| ExifService::new());
+    container.register_singleton::<ThumbnailExtractor, _>(|_| ThumbnailExtractor::new());
+    container.register_singleton::<PreviewExtractor, _>(|_| PreviewExtractor::new());
+    container
+        .register_singleton::<Repository<Photo>, _>(|_| Repository::new(Box::new(MemoryRepository::<Photo>::new())));
+    container.register_singleton::<Repository<ExifModel>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<ExifModel>::new()))
+    });
+    container.register_singleton::<Repository<PipelineJob>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<PipelineJob>::new()))
+    });
+    container.register_singleton::<FileService, _>(|_| FileService::new());
+    let provider = Arc::new(container.build());
+
+    let pipeline = ImageProcessPipeline::new(ImageProcessPipelineContext::new(
+        Arc::clone(&provider),
+        test_configuration(&storage_root.join("thumbnails"), &storage_root.join("previews")),
+    ));
+
+    let storage = create_storage(Uuid::new_v4(), "SyncTimeout", &storage_root);
+    let files = ["slow.cr2", "next.jpg"]
+        .iter()
+        .map(|name| StoredUploadFile {
+            file_name: name.to_string(),
+            relative_path: format!("temp/{}", name),
+            byte_size: 1024,
+            content_type: None,
+        })
+        .collect();
+    let batch_id = Uuid::new_v4();
+
+    let processed = pipeline
+        .process_now(storage, files, batch_id, None, Duration::ZERO)
+        .await
+        .expect("synchronous processing failed");
+
+    let jobs = provider
+        .get::<Repository<PipelineJob>>()
+        .query(QueryBuilder::<PipelineJob>::new().page(1, 10).build())
+        .await
+        .expect("job query failed")
+        .items;
+    assert_eq!(processed.iter().map(|file| file.status).collect::<Vec<_>>(), vec![SyncProcessStatus::Deferred; 2]);
+    assert_eq!(jobs.len(), 2, "every deferred file should keep a durable job");
+    assert!(jobs.iter().all(|job| job.batch_id == Some(batch_id)));
+}
+
+#[tokio::test]
+async fn resume_gives_up_on_exhausted_jobs_and_prunes_old_finished_ones() {
+    let storage_root = unique_temp_dir("pipeline-resume");
+
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<BackgroundTaskRunner, _>(|_| BackgroundTaskRunner::new(2));
+    container.register_singleton::<Repository<PipelineJob>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<PipelineJob>::new()))
+    });
+    container.register_singleton::<Repository<StorageLocation>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<StorageLocation>::new()))
+    });
+    let provider = Arc::new(container.build());
+
+    let pipeline = ImageProcessPipeline::new(ImageProcessPipelineContext::new(
+        Arc::clone(&provider),
+        test_configuration(&storage_root.join("thumbnails"), &storage_root.join("previews")),
+    ));
+
+    let job_repo = provider.get::<Repository<PipelineJob>>();
+    let mut exhausted = PipelineJob::queued(Uuid::new_v4(), "temp/crash.jpg", "crash.jpg", 1024, None);
+    for _ in 0..AppConfig::DEFAULT_PIPELINE_MAX_ATTEMPTS {
+        exhausted.mark_running();
+    }
+    let mut stale = PipelineJob::queued(Uuid::new_v4(), "temp/old.jpg", "old.jpg", 1024, None);
+    stale.mark_completed();
+    stale.updated_at = Utc::now() - chrono::Duration::days(AppConfig::DEFAULT_PIPELINE_JOB_RETENTION_DAYS + 1);
+    let mut recent = PipelineJob::queued(Uuid::new_v4(), "temp/new.jpg", "new.jpg", 1024, None);
+    recent.mark_completed();
+    for job in [exhausted.clone(), stale.clone(), recent.clone()] {
+        job_repo.insert(job).await.expect("job insert failed");
+    }
+
+    let resumed = pipeline.resume_pending_jobs().await.expect("resume should succeed");
+
+    let jobs =
+        job_repo.query(QueryBuilder::<PipelineJob>::new().page(1, 10).build()).await.expect("job query failed").items;
+    let gave_up = jobs.iter().find(|job| job.id == exhausted.id).expect("exhausted job should be kept");
+    assert_eq!(resumed, 0);
+    assert_eq!(gave_up.state, PipelineJob::STATE_FAILED);
+    assert!(jobs.iter().all(|job| job.id != stale.id), "old finished jobs should be pruned");
+    assert!(jobs.iter().any(|job| job.id == recent.id));
+    assert_eq!(provider.get::<BackgroundTaskRunner>().queued_count(), 0);
+}
@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDate, TimeZone, Utc};
+
+use nimble_photos::entities::Tag;
+use nimble_photos::models::{EventNames, LiveAudience, LiveClientMessage, LiveEvent, LiveSubscription};
+use nimble_photos::services::{AppEvent, LiveConnectionRegistry, LiveTicketStore, LiveUpdateService};
+use serde_json::json;
+use uuid::Uuid;
+
+fn album_comment(album_id: Uuid) -> LiveEvent {
+    LiveEvent::CommentAdded { comment_id: Uuid::new_v4(), photo_id: None, album_id: Some(album_id) }
+}
+
+#[test]
+fn photo_added_serializes_with_day_bucket_and_hash() {
+    let photo_id = Uuid::new_v4();
+    let event = LiveEvent::PhotoAdded {
+        photo_id,
+        day: NaiveDate::from_ymd_opt(2026, 3, 14).unwrap(),
+        hash: Some("abc123".to_string()),
+    };
+
+    let value = serde_json::to_value(&event).unwrap();
+    assert_eq!(value, json!({ "type": "photoAdded", "photoId": photo_id, "day": "2026-03-14", "hash": "abc123" }));
+    assert_eq!(event.topic(), Some(EventNames::PHOTO_ADDED));
+}
+
+#[test]
+fn only_live_topics_are_forwarded_from_the_bus() {
+    let album_id = Uuid::new_v4();
+    let event = LiveEvent::AlbumUpdated { album_id };
+    let published = AppEvent::new(EventNames::ALBUM_UPDATED, serde_json::to_value(&event).unwrap());
+    assert_eq!(LiveEvent::from_app_event(&published), Some(event));
+
+    let internal = AppEvent::new(EventNames::IMAGES_PROCESSED, json!({ "queuedCount": 0 }));
+    assert_eq!(LiveEvent::from_app_event(&internal), None);
+
+    let mismatched = AppEvent::new(EventNames::PHOTO_DELETED, json!({ "type": "albumUpdated", "albumId": album_id }));
+    assert_eq!(LiveEvent::from_app_event(&mismatched), None);
+}
+
+#[test]
+fn subscriptions_filter_by_album_and_event_type() {
+    let album_id = Uuid::new_v4();
+    let deleted = LiveEvent::PhotoDeleted { photo_id: Uuid::new_v4() };
+
+    let everything = LiveSubscription::default();
+    assert!(everything.matches(&deleted));
+    assert!(everything.matches(&album_comment(Uuid::new_v4())));
+
+    let message = json!({ "type": "subscribe", "albums": [album_id] });
+    let LiveClientMessage::Subscribe(only_album) = serde_json::from_value(message).unwrap() else {
+        panic!("expected a subscribe message");
+    };
+    assert!(only_album.matches(&album_comment(album_id)));
+    assert!(only_album.matches(&LiveEvent::AlbumUpdated { album_id }));
+    assert!(!only_album.matches(&album_comment(Uuid::new_v4())));
+    assert!(!only_album.matches(&deleted));
+    assert!(only_album.matches(&LiveEvent::Resync { skipped: 3 }));
+
+    let message = json!({ "type": "subscribe", "events": ["photoDeleted"] });
+    let LiveClientMessage::Subscribe(only_deletes) = serde_json::from_value(message).unwrap() else {
+        panic!("expected a subscribe message");
+    };
+    assert!(only_deletes.matches(&deleted));
+    assert!(!only_deletes.matches(&LiveEvent::AlbumUpdated { album_id }));
+}
+
+#[test]
+fn photo_events_follow_the_audience_visibility_rules() {
+    let private = Tag::new("Private", Tag::VISIBILITY_HIDDEN);
+    let family = Tag::new("Family", Tag::VISIBILITY_PUBLIC);
+    let viewer_hidden = HashSet::from(["family".to_string()]);
+
+    assert!(LiveAudience::Admin.can_see(&[private.clone()], &viewer_hidden));
+    assert!(!LiveAudience::Member.can_see(&[private.clone()], &HashSet::new()));
+    assert!(LiveAudience::Member.can_see(&[family.clone()], &HashSet::new()));
+    assert!(!LiveAudience::Viewer.can_see(&[family.clone()], &viewer_hidden));
+    assert!(LiveAudience::Viewer.can_see(&[], &viewer_hidden));
+    assert_eq!(LiveAudience::new(true, true), LiveAudience::Admin);
+    assert_eq!(LiveAudience::new(false, true), LiveAudience::Viewer);
+    assert_eq!(LiveEvent::PhotoDeleted { photo_id: Uuid::new_v4() }.photo_id(), None);
+}
+
+#[test]
+fn connections_are_capped_per_user() {
+    let registry = Arc::new(LiveConnectionRegistry::new(2));
+    let (ann, bob) = (Uuid::new_v4(), Uuid::new_v4());
+
+    let first = registry.try_acquire(ann).unwrap();
+    let _second = registry.try_acquire(ann).unwrap();
+    assert!(registry.try_acquire(ann).is_none());
+    assert!(registry.try_acquire(bob).is_some());
+
+    drop(first);
+    assert_eq!(registry.active(ann), 1);
+    assert!(registry.try_acquire(ann).is_some());
+}
+
+#[test]
+fn tickets_are_single_use_and_expire() {
+    let store = LiveTicketStore::new(chrono::Duration::seconds(30));
+    let user_id = Uuid::new_v4();
+    let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+
+    let (ticket, expires_at) = store.issue(user_id, LiveAudience::Viewer, now);
+    assert_eq!(expires_at, now + chrono::Duration::seconds(30));
+    assert_eq!(store.redeem(&ticket, now), Some((user_id, LiveAudience::Viewer)));
+    assert_eq!(store.redeem(&ticket, now), None);
+
+    let (late, _) = store.issue(user_id, LiveAudience::Member, now);
+    assert_eq!(store.redeem(&late, now + chrono::Duration::seconds(31)), None);
+    assert_eq!(store.redeem("unknown", now), None);
+}
+
+#[test]
+fn handshake_query_and_heartbeat_helpers() {
+    assert_eq!(LiveUpdateService::ticket_from_query("foo=1&ticket=abc"), Some("abc".to_string()));
+    assert_eq!(LiveUpdateService::ticket_from_query("ticket="), None);
+    assert_eq!(LiveUpdateService::ticket_from_query("token=abc"), None);
+
+    let last_seen = Instant::now();
+    assert!(!LiveUpdateService::is_stale(last_seen, last_seen + LiveUpdateService::HEARTBEAT_INTERVAL));
+    assert!(LiveUpdateService::is_stale(
+        last_seen,
+        last_seen + LiveUpdateService::HEARTBEAT_TIMEOUT + Duration::from_secs(1)
+    ));
+}
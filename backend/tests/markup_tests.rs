@@ -0,0 +1,14 @@
+use nimble_photos::models::Markup;
+
+#[test]
+fn escape_covers_text_and_both_attribute_quote_styles() {
+    assert_eq!(
+        Markup::escape("Fish & <Chips> \"fresh\" 'daily'"),
+        "Fish &amp; &lt;Chips&gt; &quot;fresh&quot; &#39;daily&#39;"
+    );
+}
+
+#[test]
+fn escape_drops_control_characters_but_keeps_whitespace() {
+    assert_eq!(Markup::escape("line\u{0}one\n\tline\u{1b}two\r"), "lineone\n\tlinetwo\r");
+}
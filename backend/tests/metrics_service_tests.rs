@@ -0,0 +1,41 @@
+use nimble_photos::models::MetricNames;
+use nimble_photos::services::MetricsService;
+
+#[test]
+fn counters_render_in_prometheus_text_format() {
+    let metrics = MetricsService::new();
+    metrics.increment(MetricNames::IMAGES_PROCESSED_TOTAL, &[("kind", "import")]);
+    metrics.increment(MetricNames::IMAGES_PROCESSED_TOTAL, &[("kind", "import")]);
+    metrics.increment(MetricNames::IMAGES_FAILED_TOTAL, &[("kind", "import")]);
+
+    assert_eq!(metrics.counter_value(MetricNames::IMAGES_PROCESSED_TOTAL, &[("kind", "import")]), 2);
+
+    let output = metrics.render();
+    assert!(output.contains("# TYPE nimble_images_processed_total counter"));
+    assert!(output.contains("nimble_images_processed_total{kind=\"import\"} 2"));
+    assert!(output.contains("nimble_images_failed_total{kind=\"import\"} 1"));
+}
+
+#[test]
+fn histograms_render_cumulative_buckets_sum_and_count() {
+    let metrics = MetricsService::new();
+    metrics.observe(MetricNames::PREVIEW_GENERATION_SECONDS, &[], 0.2);
+    metrics.observe(MetricNames::PREVIEW_GENERATION_SECONDS, &[], 3.0);
+
+    let output = metrics.render();
+    assert!(output.contains("# TYPE nimble_preview_generation_seconds histogram"));
+    assert!(output.contains("nimble_preview_generation_seconds_bucket{le=\"0.1\"} 0"));
+    assert!(output.contains("nimble_preview_generation_seconds_bucket{le=\"0.25\"} 1"));
+    assert!(output.contains("nimble_preview_generation_seconds_bucket{le=\"5\"} 2"));
+    assert!(output.contains("nimble_preview_generation_seconds_bucket{le=\"+Inf\"} 2"));
+    assert!(output.contains("nimble_preview_generation_seconds_sum 3.2"));
+    assert!(output.contains("nimble_preview_generation_seconds_count 2"));
+}
+
+#[test]
+fn label_values_are_escaped() {
+    let metrics = MetricsService::new();
+    metrics.set_gauge(MetricNames::BACKGROUND_QUEUE_DEPTH, &[("source", "a\"b")], 3.0);
+
+    assert!(metrics.render().contains("nimble_background_queue_depth{source=\"a\\\"b\"} 3"));
+}
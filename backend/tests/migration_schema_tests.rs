@@ -36,3 +36,40 @@ async fn ensure_supporting_schema_creates_tag_tables() {
     assert!(table_exists(&pool, "tags").await, "tags table missing");
     assert!(table_exists(&pool, "photo_tags").await, "photo_tags table missing");
 }
+
+#[tokio::test]
+async fn ensure_supporting_schema_imports_legacy_storage_locations() {
+    let Some(pool) = setup_pool().await else {
+        return;
+    };
+    if !table_exists(&pool, "settings").await || !table_exists(&pool, "storages").await {
+        return;
+    }
+
+    let path = format!("/legacy-storage/{}", uuid::Uuid::new_v4());
+    let legacy = serde_json::json!([{ "id": "legacy-1", "label": "Legacy", "path": path, "isDefault": false }]);
+    sqlx::query(
+        "INSERT INTO settings (key, value_type, value, group_name) VALUES ('storage.locations', 'json', $1, 'storage')
+         ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value",
+    )
+    .bind(legacy.to_string())
+    .execute(&pool)
+    .await
+    .expect("legacy setting should be inserted");
+
+    ensure_supporting_schema(&pool).await.expect("supporting schema migration failed");
+
+    let imported: Option<String> = sqlx::query_scalar("SELECT label FROM storages WHERE path = $1")
+        .bind(&path)
+        .fetch_optional(&pool)
+        .await
+        .expect("storage lookup failed");
+    let remaining: i64 = sqlx::query_scalar("SELECT count(*) FROM settings WHERE key = 'storage.locations'")
+        .fetch_one(&pool)
+        .await
+        .expect("setting lookup failed");
+
+    assert_eq!(imported.as_deref(), Some("Legacy"));
+    assert_eq!(remaining, 0);
+    sqlx::query("DELETE FROM storages WHERE path = $1").bind(&path).execute(&pool).await.expect("cleanup failed");
+}
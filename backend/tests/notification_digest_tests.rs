@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeZone, Utc};
+use nimble_photos::dtos::UpdateUserSettingsRequest;
+use nimble_photos::entities::UserSettings;
+use nimble_photos::models::{AppConfig, DigestSchedule, DigestSummary};
+use nimble_photos::services::EmailService;
+use uuid::Uuid;
+
+fn at(day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap()
+}
+
+fn summary(photos: i64, comments: i64, albums: i64) -> DigestSummary {
+    DigestSummary {
+        site_title: "Nimble <Photos>".to_string(),
+        since: at(1, 18),
+        until: at(2, 18),
+        photos,
+        comments,
+        albums,
+    }
+}
+
+#[test]
+fn digest_waits_for_the_configured_hour() {
+    let schedule = DigestSchedule::new(18.0);
+
+    assert!(!schedule.is_due(at(2, 17), None));
+    assert!(schedule.is_due(at(2, 18), None));
+    assert!(schedule.is_due(at(2, 23), Some(at(1, 18))));
+}
+
+#[test]
+fn digest_is_sent_once_per_day() {
+    let schedule = DigestSchedule::new(6.0);
+
+    assert!(!schedule.is_due(at(2, 9), Some(at(2, 6))));
+    assert!(schedule.is_due(at(3, 6), Some(at(2, 6))));
+}
+
+#[test]
+fn digest_hour_is_clamped() {
+    assert_eq!(DigestSchedule::new(30.0).hour, 23);
+    assert_eq!(DigestSchedule::new(-2.0).hour, 0);
+    assert_eq!(DigestSchedule::new(f64::NAN).hour, 0);
+}
+
+#[test]
+fn summary_renders_counts_as_text_and_html() {
+    let summary = summary(12, 3, 1);
+
+    let text = summary.render_text();
+    assert!(text.contains("- New photos: 12"));
+    assert!(text.contains("- New comments: 3"));
+    assert!(text.contains("- New albums: 1"));
+    assert!(text.contains("2026-03-01 18:00 UTC"));
+
+    let html = summary.render_html();
+    assert!(html.contains("<h2>Nimble &lt;Photos&gt;</h2>"));
+    assert!(html.contains("<li>New photos: <strong>12</strong></li>"));
+    assert_eq!(summary.subject(), "Nimble <Photos> daily digest for 2026-03-02");
+}
+
+#[test]
+fn empty_summary_says_so() {
+    let summary = summary(0, 0, 0);
+
+    assert!(summary.is_empty());
+    assert!(summary.render_text().contains("Nothing new this time."));
+    assert!(summary.render_html().contains("<p>Nothing new this time.</p>"));
+}
+
+#[test]
+fn email_is_disabled_without_smtp_settings() {
+    let config = |values: &[(&str, &str)]| {
+        let values: HashMap<String, String> = values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        AppConfig::from_lookup(|key| values.get(key).cloned()).unwrap()
+    };
+
+    let defaults = config(&[]);
+    assert_eq!(defaults.smtp_port, AppConfig::DEFAULT_SMTP_PORT);
+    assert!(defaults.smtp_starttls);
+    assert!(!EmailService::from_config(&defaults).is_configured());
+
+    let invalid_from = config(&[("email.smtpHost", "smtp.example.com"), ("email.from", "not an address")]);
+    assert!(!EmailService::from_config(&invalid_from).is_configured());
+
+    let configured = config(&[
+        ("email.smtpHost", "smtp.example.com"),
+        ("email.smtpPort", "2525"),
+        ("email.from", "Nimble <photos@example.com>"),
+        ("email.startTls", "false"),
+    ]);
+    assert_eq!(configured.smtp_port, 2525);
+    assert!(EmailService::from_config(&configured).is_configured());
+}
+
+#[test]
+fn users_can_opt_in_to_the_digest() {
+    let mut settings = UserSettings::new(Uuid::new_v4(), "Ann".to_string());
+    assert!(!settings.email_digest);
+
+    let request = UpdateUserSettingsRequest { email_digest: Some(true), ..UpdateUserSettingsRequest::default() };
+    request.apply(&mut settings).unwrap();
+    assert!(settings.email_digest);
+
+    UpdateUserSettingsRequest::default().apply(&mut settings).unwrap();
+    assert!(settings.email_digest);
+}
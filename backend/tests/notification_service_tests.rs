@@ -0,0 +1,57 @@
+use nimble_photos::controllers::NotificationController;
+use nimble_photos::entities::Notification;
+use nimble_photos::services::NotificationService;
+use nimble_web::{Controller, Policy};
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn previous_commenters_are_notified_once_without_the_author() {
+    let author = Uuid::new_v4();
+    let first = Uuid::new_v4();
+    let second = Uuid::new_v4();
+
+    let recipients = NotificationService::recipients(
+        Some(author),
+        [Some(first), Some(author), None, Some(second), Some(first)].into_iter(),
+    );
+
+    assert_eq!(recipients, vec![first, second]);
+}
+
+#[test]
+fn anonymous_authors_notify_every_previous_commenter() {
+    let first = Uuid::new_v4();
+
+    assert_eq!(NotificationService::recipients(None, [Some(first), None].into_iter()), vec![first]);
+    assert!(NotificationService::recipients(None, std::iter::empty()).is_empty());
+}
+
+#[test]
+fn long_comments_are_shortened_to_an_excerpt() {
+    assert_eq!(NotificationService::excerpt("  nice shot  "), "nice shot");
+
+    let excerpt = NotificationService::excerpt(&"é".repeat(300));
+    assert_eq!(excerpt.chars().count(), 121);
+    assert!(excerpt.ends_with('…'));
+}
+
+#[test]
+fn new_notifications_are_unread() {
+    let notification = Notification::new(Uuid::new_v4(), Notification::KIND_PHOTO_COMMENT, json!({ "photoId": 1 }));
+
+    assert!(!notification.is_read());
+    assert_eq!(notification.kind, "photoComment");
+}
+
+#[test]
+fn notification_routes_require_authentication() {
+    let routes = NotificationController::routes();
+    let paths = routes.iter().map(|route| (route.route.method(), route.route.path())).collect::<Vec<_>>();
+
+    assert!(paths.contains(&("GET", "/api/notifications")));
+    assert!(paths.contains(&("POST", "/api/notifications/{id}/read")));
+    assert!(paths.contains(&("POST", "/api/notifications/read-all")));
+    assert!(paths.contains(&("POST", "/api/admin/notifications/test-digest")));
+    assert!(routes.iter().all(|route| route.endpoint.metadata().policy() == Some(&Policy::Authenticated)));
+}
@@ -0,0 +1,193 @@
+use nimble_photos::dtos::{
+    BatchEditPhotosRequest, FieldError, LoginRequest, RegisterRequest, UpdatePhotoCaptionPayload,
+    UpdatePhotoRatingPayload, UpdatePhotoTagsPayload,
+};
+use nimble_photos::entities::{CreateStoragePayload, Photo, UpdateStoragePayload};
+use nimble_photos::models::ApiError;
+use nimble_photos::repositories::Validate;
+
+fn storage_payload(label: &str, template: Option<&str>) -> CreateStoragePayload {
+    CreateStoragePayload {
+        label: label.to_string(),
+        mount_point: "/mnt/photos".to_string(),
+        path: "library".to_string(),
+        is_default: None,
+        category_template: template.map(str::to_string),
+    }
+}
+
+fn fields(errors: &[FieldError]) -> Vec<&str> {
+    errors.iter().map(|error| error.field.as_str()).collect()
+}
+
+#[test]
+fn create_storage_payload_accepts_valid_input() {
+    assert!(storage_payload("Main", Some("{year}/{fileName}")).validate().is_ok());
+    assert!(storage_payload("Main", None).validate().is_ok());
+}
+
+#[test]
+fn create_storage_payload_reports_blank_label_and_bad_template() {
+    let errors = storage_payload("  ", Some("{bogus}")).validate().unwrap_err();
+
+    assert_eq!(fields(&errors), vec!["label", "categoryTemplate"]);
+    assert_eq!(errors[0].message, "Storage label should not be empty");
+}
+
+#[test]
+fn update_storage_payload_only_checks_present_fields() {
+    let payload =
+        UpdateStoragePayload { label: None, path: Some(" ".to_string()), is_default: None, category_template: None };
+
+    let errors = payload.validate().unwrap_err();
+
+    assert_eq!(fields(&errors), vec!["path"]);
+}
+
+#[test]
+fn register_request_collects_every_field_error() {
+    let payload: RegisterRequest = serde_json::from_value(serde_json::json!({
+        "email": "not-an-email",
+        "password": "secret",
+        "confirmPassword": "different",
+        "displayName": ""
+    }))
+    .unwrap();
+
+    let errors = payload.validate().unwrap_err();
+
+    assert_eq!(fields(&errors), vec!["email", "confirmPassword", "displayName"]);
+}
+
+#[test]
+fn login_request_requires_credentials() {
+    let errors = LoginRequest::default().validate().unwrap_err();
+
+    assert_eq!(fields(&errors), vec!["email", "password"]);
+}
+
+#[test]
+fn update_photo_tags_payload_rejects_invalid_ids() {
+    let payload: UpdatePhotoTagsPayload =
+        serde_json::from_value(serde_json::json!({ "photoIds": ["nope"], "tags": ["sunset"] })).unwrap();
+
+    let errors = payload.validate().unwrap_err();
+
+    assert_eq!(errors, vec![FieldError::new("photoIds", "invalid photo id: nope")]);
+}
+
+#[test]
+fn update_photo_caption_payload_enforces_lengths() {
+    let payload = UpdatePhotoCaptionPayload {
+        title: Some("t".repeat(UpdatePhotoCaptionPayload::MAX_TITLE_LENGTH + 1)),
+        caption: Some("c".repeat(UpdatePhotoCaptionPayload::MAX_CAPTION_LENGTH + 1)),
+    };
+
+    let errors = payload.validate().unwrap_err();
+
+    assert_eq!(fields(&errors), vec!["title", "caption"]);
+    assert!(UpdatePhotoCaptionPayload::default().validate().is_err());
+}
+
+#[test]
+fn update_photo_caption_payload_clears_empty_fields_and_keeps_missing_ones() {
+    let mut photo = Photo {
+        title: Some("Old title".to_string()),
+        caption: Some("Old caption".to_string()),
+        updated_at: None,
+        ..Photo::default()
+    };
+    let payload: UpdatePhotoCaptionPayload =
+        serde_json::from_value(serde_json::json!({ "caption": "  Grandma's 80th birthday " })).unwrap();
+
+    payload.apply(&mut photo);
+    assert_eq!(photo.title.as_deref(), Some("Old title"));
+    assert_eq!(photo.caption.as_deref(), Some("Grandma's 80th birthday"));
+    assert!(photo.updated_at.is_some());
+
+    let clear: UpdatePhotoCaptionPayload = serde_json::from_value(serde_json::json!({ "title": "" })).unwrap();
+    clear.apply(&mut photo);
+    assert_eq!(photo.title, None);
+    assert_eq!(photo.caption.as_deref(), Some("Grandma's 80th birthday"));
+}
+
+#[test]
+fn batch_edit_request_requires_photos_and_changes() {
+    let payload: BatchEditPhotosRequest = serde_json::from_value(serde_json::json!({ "photoIds": [] })).unwrap();
+
+    let errors = payload.validate().unwrap_err();
+
+    assert_eq!(fields(&errors), vec!["photoIds", "set"]);
+}
+
+#[test]
+fn batch_edit_request_rejects_blank_tags_and_long_captions() {
+    let payload: BatchEditPhotosRequest = serde_json::from_value(serde_json::json!({
+        "photoIds": [uuid::Uuid::new_v4()],
+        "set": {
+            "caption": "c".repeat(UpdatePhotoCaptionPayload::MAX_CAPTION_LENGTH + 1),
+            "addTags": ["beach"],
+            "removeTags": [" "]
+        }
+    }))
+    .unwrap();
+
+    let errors = payload.validate().unwrap_err();
+
+    assert_eq!(fields(&errors), vec!["set.caption", "set.tags"]);
+}
+
+#[test]
+fn batch_edit_request_accepts_date_only_change() {
+    let payload: BatchEditPhotosRequest = serde_json::from_value(serde_json::json!({
+        "photoIds": [uuid::Uuid::new_v4()],
+        "set": { "dateTaken": "2020-02-29T10:00:00Z" }
+    }))
+    .unwrap();
+
+    assert!(payload.validate().is_ok());
+    assert!(payload.set.has_field_changes());
+    assert!(!payload.set.has_tag_changes());
+}
+
+#[test]
+fn invalid_api_error_lists_field_errors_in_details() {
+    let error = ApiError::invalid(vec![FieldError::new("label", "Storage label should not be empty")]);
+    let body = serde_json::to_value(error.body()).unwrap();
+
+    assert_eq!(error.status(), 400);
+    assert_eq!(body["code"], "validation_failed");
+    assert_eq!(body["message"], "Storage label should not be empty");
+    assert_eq!(
+        body["details"],
+        serde_json::json!([{ "field": "label", "message": "Storage label should not be empty" }])
+    );
+}
+
+#[test]
+fn rating_payload_accepts_zero_to_five() {
+    for rating in 0..=5 {
+        assert!(UpdatePhotoRatingPayload { rating }.validate().is_ok());
+    }
+
+    let errors = UpdatePhotoRatingPayload { rating: 6 }.validate().unwrap_err();
+    assert_eq!(fields(&errors), vec!["rating"]);
+}
+
+#[test]
+fn batch_edit_request_validates_rating() {
+    let payload: BatchEditPhotosRequest = serde_json::from_value(serde_json::json!({
+        "photoIds": [uuid::Uuid::new_v4()],
+        "set": { "rating": 4 }
+    }))
+    .unwrap();
+    assert!(payload.validate().is_ok());
+    assert!(payload.set.has_field_changes());
+
+    let payload: BatchEditPhotosRequest = serde_json::from_value(serde_json::json!({
+        "photoIds": [uuid::Uuid::new_v4()],
+        "set": { "rating": 9 }
+    }))
+    .unwrap();
+    assert_eq!(fields(&payload.validate().unwrap_err()), vec!["set.rating"]);
+}
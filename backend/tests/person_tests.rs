@@ -0,0 +1,99 @@
+use nimble_photos::dtos::{RenamePersonPayload, UpdatePhotoPeoplePayload};
+use nimble_photos::models::{PersonMention, PersonRegion, PhotoFilter, TagMatch};
+use nimble_photos::repositories::Validate;
+
+fn approx(region: PersonRegion, expected: (f32, f32, f32, f32)) {
+    let actual = (region.x, region.y, region.width, region.height);
+    let close = |a: f32, b: f32| (a - b).abs() < 1e-5;
+    assert!(
+        close(actual.0, expected.0)
+            && close(actual.1, expected.1)
+            && close(actual.2, expected.2)
+            && close(actual.3, expected.3),
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn region_is_clamped_to_the_image() {
+    approx(PersonRegion::new(-0.1, 0.8, 0.3, 0.4).unwrap(), (0.0, 0.8, 0.2, 0.2));
+    assert!(PersonRegion::new(0.2, 0.2, 0.0, 0.1).is_none());
+    assert!(PersonRegion::new(1.2, 0.2, 0.1, 0.1).is_none());
+    assert!(PersonRegion::new(f32::NAN, 0.2, 0.1, 0.1).is_none());
+}
+
+#[test]
+fn mwg_center_is_converted_to_top_left() {
+    approx(PersonRegion::from_center(0.5, 0.4, 0.2, 0.3).unwrap(), (0.4, 0.25, 0.2, 0.3));
+}
+
+#[test]
+fn windows_rectangle_is_parsed() {
+    approx(PersonRegion::parse_rectangle("0.1, 0.2, 0.3, 0.4").unwrap(), (0.1, 0.2, 0.3, 0.4));
+    assert!(PersonRegion::parse_rectangle("0.1, 0.2, 0.3").is_none());
+    assert!(PersonRegion::parse_rectangle("a, b, c, d").is_none());
+}
+
+#[test]
+fn names_are_normalized_like_tags() {
+    assert_eq!(
+        PersonMention::normalize_name("  Anna   Maria "),
+        Some(("Anna Maria".to_string(), "anna maria".to_string()))
+    );
+    assert_eq!(PersonMention::normalize_name("   "), None);
+}
+
+#[test]
+fn dedup_keeps_first_spelling_and_fills_region() {
+    let region = PersonRegion::new(0.1, 0.1, 0.2, 0.2);
+    let mentions = vec![
+        PersonMention { name: "Anna".to_string(), region: None },
+        PersonMention { name: " anna ".to_string(), region },
+        PersonMention {
+            name: "Bob".to_string(),
+            region: Some(PersonRegion { x: 2.0, y: 0.0, width: 0.1, height: 0.1 }),
+        },
+        PersonMention { name: "".to_string(), region: None },
+    ];
+
+    let unique = PersonMention::dedup(mentions);
+    assert_eq!(unique.len(), 2);
+    assert_eq!(unique[0].name, "Anna");
+    assert_eq!(unique[0].region, region);
+    assert_eq!(unique[1].name, "Bob");
+    assert_eq!(unique[1].region, None);
+}
+
+#[test]
+fn people_payload_is_validated() {
+    let payload: UpdatePhotoPeoplePayload = serde_json::from_str(
+        r#"{ "people": [{ "name": "Anna", "region": { "x": 0.1, "y": 0.1, "width": 0.2, "height": 0.2 } }, { "name": "Bob" }] }"#,
+    )
+    .unwrap();
+    assert!(payload.validate().is_ok());
+    assert_eq!(payload.mentions().len(), 2);
+
+    let blank: UpdatePhotoPeoplePayload = serde_json::from_str(r#"{ "people": [{ "name": " " }] }"#).unwrap();
+    assert!(blank.validate().is_err());
+
+    let empty: UpdatePhotoPeoplePayload = serde_json::from_str("{}").unwrap();
+    assert!(empty.validate().is_ok());
+}
+
+#[test]
+fn rename_payload_requires_both_names() {
+    let payload = RenamePersonPayload { from: "Anna".to_string(), to: " ".to_string() };
+    let errors = payload.validate().unwrap_err();
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn person_filter_is_normalized() {
+    let filter = PhotoFilter::new(&[], TagMatch::Any).with_person(Some("  ANNA  Maria "));
+    assert_eq!(filter.person.as_deref(), Some("anna maria"));
+
+    let filter = PhotoFilter::new(&[], TagMatch::Any).with_person(Some(" "));
+    assert_eq!(filter.person, None);
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use nimble_photos::controllers::photo_controller::PhotoController;
+use nimble_photos::dtos::PhotoCommentDto;
+use nimble_photos::entities::{Photo, PhotoComment, Setting, UserSettings};
+use nimble_photos::services::SettingService;
+use nimble_web::{
+    AuthenticationMiddleware, AuthorizationMiddleware, Claims, Configuration, ControllerInvokerMiddleware,
+    DefaultRouter, EndpointExecutionMiddleware, EndpointRegistry, HttpContext, HttpRequest, JwtTokenService,
+    MemoryRepository, Pipeline, Repository, RequestBody, ResponseBody, Router, RoutingMiddleware, ServiceContainer,
+    TokenService, UserIdentity,
+};
+
+const TEST_USER_ID_STR: &str = "00000000-0000-0000-0000-000000000042";
+
+fn post_photo_comment(photos: Vec<Photo>, photo_id: Uuid, body: serde_json::Value) -> (u16, String) {
+    let mut registry = EndpointRegistry::new();
+    registry.register::<PhotoController>();
+
+    let mut router = DefaultRouter::new();
+    for route in registry.routes() {
+        router.add_route(route.clone());
+    }
+
+    let photo_repo = MemoryRepository::<Photo>::new();
+    photo_repo.seed(photos);
+    let comment_repo = MemoryRepository::<PhotoComment>::new();
+
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<Repository<Photo>, _>(move |_| Repository::new(Box::new(photo_repo.clone())));
+    container
+        .register_singleton::<Repository<PhotoComment>, _>(move |_| Repository::new(Box::new(comment_repo.clone())));
+    container.register_singleton::<Repository<UserSettings>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<UserSettings>::new()))
+    });
+    container.register_singleton::<SettingService, _>(|_| {
+        SettingService::new(Arc::new(Repository::new(Box::new(MemoryRepository::<Setting>::new()))))
+    });
+    container.register_singleton::<Arc<dyn TokenService>, _>(move |_| {
+        let service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+        Arc::new(service) as Arc<dyn TokenService>
+    });
+    let services = container.build();
+
+    let token_service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+    let identity = UserIdentity::new(TEST_USER_ID_STR.to_string(), Claims::new().add_role("admin"));
+    let token = TokenService::create_access_token(&token_service, &identity).unwrap();
+
+    let mut request = HttpRequest::new("POST", &format!("/api/photos/comments/{}", photo_id));
+    let header_val = format!("Bearer {}", token);
+    request.headers_mut().insert("authorization", header_val.as_str());
+    request.set_body(RequestBody::Text(body.to_string()));
+
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(RoutingMiddleware::new(router));
+    pipeline.add(AuthenticationMiddleware::new());
+    pipeline.add(AuthorizationMiddleware::new());
+    pipeline.add(ControllerInvokerMiddleware::new(Arc::new(registry)));
+    pipeline.add(EndpointExecutionMiddleware::new());
+    let _ = pipeline.run(&mut context);
+
+    let response = match context.response().body() {
+        ResponseBody::Text(text) => text.clone(),
+        _ => String::new(),
+    };
+    (context.response().status(), response)
+}
+
+#[test]
+fn photo_comment_ignores_spoofed_user_id() {
+    let photo = Photo::default();
+    let spoofed = Uuid::new_v4();
+
+    let (status, body) = post_photo_comment(
+        vec![photo.clone()],
+        photo.id,
+        serde_json::json!({ "comment": "Nice light", "userId": spoofed, "userDisplayName": "Someone Else" }),
+    );
+    let comment: PhotoCommentDto = serde_json::from_str(&body).expect("comment should be returned");
+
+    let expected = Some(Uuid::parse_str(TEST_USER_ID_STR).unwrap());
+    assert_eq!(status, 200);
+    assert_eq!(comment.user_id, expected);
+    assert_ne!(comment.user_display_name.as_deref(), Some("Someone Else"));
+}
+
+#[test]
+fn photo_comment_requires_existing_photo() {
+    let (status, _) = post_photo_comment(Vec::new(), Uuid::new_v4(), serde_json::json!({ "comment": "Nice light" }));
+
+    assert_eq!(status, 404);
+}
+
+#[test]
+fn photo_comment_rejects_blank_comment() {
+    let photo = Photo::default();
+
+    let (status, _) = post_photo_comment(vec![photo.clone()], photo.id, serde_json::json!({ "comment": "   " }));
+
+    assert_eq!(status, 400);
+}
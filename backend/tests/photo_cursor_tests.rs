@@ -0,0 +1,63 @@
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use nimble_photos::entities::photo_browse::{BrowseSort, SortDirection};
+use nimble_photos::entities::photo_cursor::{InvalidCursor, PhotoCursor};
+use nimble_photos::services::CursorSigner;
+use uuid::Uuid;
+
+fn cursor() -> PhotoCursor {
+    PhotoCursor::new(chrono::Utc::now(), Uuid::new_v4())
+        .with_sort(BrowseSort::Size, SortDirection::Desc, "2048".to_string())
+        .with_scope("0011223344556677".to_string())
+}
+
+#[test]
+fn photo_cursor_round_trips_with_signature() {
+    let signer = CursorSigner::new("cursor-secret");
+    let original = cursor();
+
+    let decoded = PhotoCursor::decode(&original.encode(&signer), &signer).unwrap();
+
+    assert_eq!(decoded.id, original.id);
+    assert!(decoded.matches(BrowseSort::Size, SortDirection::Desc));
+    assert!(!decoded.matches(BrowseSort::Size, SortDirection::Asc));
+    assert!(!decoded.matches(BrowseSort::Name, SortDirection::Desc));
+    assert_eq!(decoded.sort_key(), "2048");
+    assert_eq!(decoded.scope.as_deref(), Some("0011223344556677"));
+}
+
+#[test]
+fn photo_cursor_rejects_tampered_payload() {
+    let signer = CursorSigner::new("cursor-secret");
+    let encoded = cursor().encode(&signer);
+    let (_, signature) = encoded.split_once('.').unwrap();
+
+    let mut forged = cursor();
+    forged.key = Some("0".to_string());
+    let forged_payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&forged).unwrap());
+
+    assert_eq!(PhotoCursor::decode(&format!("{}.{}", forged_payload, signature), &signer).unwrap_err(), InvalidCursor);
+}
+
+#[test]
+fn photo_cursor_rejects_foreign_or_unsigned_cursors() {
+    let signer = CursorSigner::new("cursor-secret");
+    let encoded = cursor().encode(&CursorSigner::new("another-secret"));
+    let legacy = base64::engine::general_purpose::STANDARD
+        .encode(format!(r#"{{"dateTaken":"2026-01-25T10:00:00Z","id":"{}"}}"#, Uuid::new_v4()));
+
+    assert_eq!(PhotoCursor::decode(&encoded, &signer).unwrap_err(), InvalidCursor);
+    assert_eq!(PhotoCursor::decode(&legacy, &signer).unwrap_err(), InvalidCursor);
+    assert_eq!(PhotoCursor::decode("not-a-cursor", &signer).unwrap_err(), InvalidCursor);
+    assert_eq!(PhotoCursor::decode("", &signer).unwrap_err(), InvalidCursor);
+}
+
+#[test]
+fn cursor_signer_scopes_are_stable_and_distinct() {
+    let signer = CursorSigner::new("cursor-secret");
+
+    assert_eq!(signer.scope(&["a", "b"]), signer.scope(&["a", "b"]));
+    assert_ne!(signer.scope(&["ab", ""]), signer.scope(&["a", "b"]));
+    assert_ne!(signer.scope(&["a", "b"]), CursorSigner::new("other").scope(&["a", "b"]));
+    assert_eq!(signer.scope(&["a"]).len(), 16);
+}
@@ -0,0 +1,63 @@
+use nimble_photos::dtos::{
+    HasPreviewRequest, PhotoExistsRequest, PhotoExistsResponse, PhotoHashExists, PhotoHashLocation,
+};
+use nimble_photos::repositories::Validate;
+use uuid::Uuid;
+
+fn request(hashes: &[&str]) -> PhotoExistsRequest {
+    PhotoExistsRequest { hashes: hashes.iter().map(|hash| hash.to_string()).collect() }
+}
+
+#[test]
+fn exists_request_normalizes_and_dedupes_hashes() {
+    let payload = request(&[" 00112233AABBCCDD ", "00112233aabbccdd", "ffeeddccbbaa9988"]);
+
+    assert!(payload.validate().is_ok());
+    assert_eq!(payload.normalized_hashes(), vec!["00112233aabbccdd", "ffeeddccbbaa9988"]);
+}
+
+#[test]
+fn exists_request_rejects_empty_invalid_and_oversized_payloads() {
+    assert!(request(&[]).validate().is_err());
+    assert!(request(&["not-a-hash"]).validate().is_err());
+
+    let hashes = vec!["00112233aabbccdd"; PhotoExistsRequest::MAX_HASHES + 1];
+    let errors = request(&hashes).validate().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "hashes");
+}
+
+#[test]
+fn exists_response_keeps_request_order_and_groups_storages() {
+    let first = Uuid::new_v4();
+    let second = Uuid::new_v4();
+    let locations = vec![
+        PhotoHashLocation { hash: "bbbbbbbbbbbbbbbb".to_string(), storage_id: first },
+        PhotoHashLocation { hash: "bbbbbbbbbbbbbbbb".to_string(), storage_id: second },
+        PhotoHashLocation { hash: "bbbbbbbbbbbbbbbb".to_string(), storage_id: first },
+    ];
+
+    let response = PhotoExistsResponse::from_locations(
+        vec!["aaaaaaaaaaaaaaaa".to_string(), "bbbbbbbbbbbbbbbb".to_string()],
+        locations,
+    );
+
+    assert_eq!(
+        response.results,
+        vec![
+            PhotoHashExists { hash: "aaaaaaaaaaaaaaaa".to_string(), exists: false, storage_ids: Vec::new() },
+            PhotoHashExists { hash: "bbbbbbbbbbbbbbbb".to_string(), exists: true, storage_ids: vec![first, second] },
+        ]
+    );
+}
+
+#[test]
+fn has_preview_request_is_capped_and_deduped() {
+    let payload = HasPreviewRequest { hashes: vec!["AABBCCDDEEFF0011".to_string(), "aabbccddeeff0011".to_string()] };
+    assert!(payload.validate().is_ok());
+    assert_eq!(payload.normalized_hashes(), vec!["aabbccddeeff0011"]);
+
+    let oversized =
+        HasPreviewRequest { hashes: vec!["aabbccddeeff0011".to_string(); HasPreviewRequest::MAX_HASHES + 1] };
+    assert!(oversized.validate().is_err());
+}
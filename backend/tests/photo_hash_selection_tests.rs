@@ -0,0 +1,46 @@
+use nimble_photos::entities::Photo;
+use std::fs;
+use uuid::Uuid;
+
+fn photo(storage_id: Uuid, path: &str) -> Photo {
+    Photo {
+        id: Uuid::new_v4(),
+        storage_id,
+        path: path.to_string(),
+        hash: Some("abcdef0123456789".to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn select_by_hash_only_returns_the_requested_storage() {
+    let first = Uuid::new_v4();
+    let second = Uuid::new_v4();
+    let candidates = vec![photo(first, "/missing/a.jpg"), photo(second, "/missing/b.jpg")];
+
+    let selected = Photo::select_by_hash(candidates.clone(), Some(second)).expect("photo in requested storage");
+    assert_eq!(selected.storage_id, second);
+    assert!(Photo::select_by_hash(candidates, Some(Uuid::new_v4())).is_none());
+}
+
+#[test]
+fn select_by_hash_prefers_photo_with_existing_source() {
+    let source = std::env::temp_dir().join(format!("nimble-hash-select-{}.jpg", Uuid::new_v4()));
+    fs::write(&source, b"jpeg").unwrap();
+    let candidates = vec![photo(Uuid::new_v4(), "/missing/a.jpg"), photo(Uuid::new_v4(), &source.to_string_lossy())];
+
+    let selected = Photo::select_by_hash(candidates, None).expect("photo selected");
+
+    assert_eq!(selected.path, source.to_string_lossy());
+    let _ = fs::remove_file(source);
+}
+
+#[test]
+fn select_by_hash_falls_back_to_first_candidate() {
+    let candidates = vec![photo(Uuid::new_v4(), "/missing/a.jpg"), photo(Uuid::new_v4(), "/missing/b.jpg")];
+
+    let selected = Photo::select_by_hash(candidates, None).expect("photo selected");
+
+    assert_eq!(selected.path, "/missing/a.jpg");
+    assert!(Photo::select_by_hash(Vec::new(), None).is_none());
+}
@@ -0,0 +1,51 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use nimble_photos::entities::Photo;
+use nimble_photos::entities::photo_hooks::PhotoHooks;
+use uuid::Uuid;
+
+fn stored_photo() -> Photo {
+    Photo { storage_id: Uuid::new_v4(), hash: Some("0123456789abcdef".to_string()), ..Photo::default() }
+}
+
+#[test]
+fn normalize_lowercases_format_and_derives_day_date() {
+    let taken = Utc.with_ymd_and_hms(2024, 5, 3, 22, 15, 0).unwrap();
+    let mut photo = Photo { format: Some(" .JPG ".to_string()), date_taken: Some(taken), ..stored_photo() };
+
+    PhotoHooks::normalize(&mut photo);
+
+    assert_eq!(photo.format.as_deref(), Some("jpg"));
+    assert_eq!(photo.day_date, NaiveDate::from_ymd_opt(2024, 5, 3).unwrap());
+    assert_eq!(photo.sort_date, taken);
+}
+
+#[test]
+fn normalize_falls_back_to_created_at() {
+    let created = Utc.with_ymd_and_hms(2023, 1, 9, 8, 0, 0).unwrap();
+    let mut photo =
+        Photo { format: Some("   ".to_string()), date_taken: None, created_at: Some(created), ..stored_photo() };
+
+    PhotoHooks::normalize(&mut photo);
+
+    assert_eq!(photo.format, None);
+    assert_eq!(photo.day_date, NaiveDate::from_ymd_opt(2023, 1, 9).unwrap());
+}
+
+#[test]
+fn validate_update_keeps_the_file_location_fixed() {
+    let current = stored_photo();
+
+    let cleared_hash = Photo { hash: Some(" ".to_string()), ..current.clone() };
+    let cleared_storage = Photo { storage_id: Uuid::nil(), ..current.clone() };
+    let renamed = Photo { name: "renamed.jpg".to_string(), ..current.clone() };
+    let moved = Photo { path: "/elsewhere/renamed.jpg".to_string(), ..current.clone() };
+    let rehashed = Photo { hash: Some("ffff".to_string()), ..current.clone() };
+    let restored = Photo { storage_id: Uuid::new_v4(), ..current.clone() };
+
+    assert_eq!(PhotoHooks::validate_update(&current, &cleared_hash), Err(PhotoHooks::HASH_REQUIRED));
+    assert_eq!(PhotoHooks::validate_update(&current, &cleared_storage), Err(PhotoHooks::STORAGE_REQUIRED));
+    assert_eq!(PhotoHooks::validate_update(&current, &moved), Err(PhotoHooks::LOCATION_IMMUTABLE));
+    assert_eq!(PhotoHooks::validate_update(&current, &rehashed), Err(PhotoHooks::LOCATION_IMMUTABLE));
+    assert_eq!(PhotoHooks::validate_update(&current, &restored), Err(PhotoHooks::LOCATION_IMMUTABLE));
+    assert_eq!(PhotoHooks::validate_update(&current, &renamed), Ok(()));
+}
@@ -0,0 +1,75 @@
+use nimble_photos::dtos::{FieldError, UpdatePhotoMetadataRequest};
+use nimble_photos::entities::ExifModel;
+use nimble_photos::models::ExifTool;
+use nimble_photos::repositories::Validate;
+use serde_json::json;
+
+fn request(value: serde_json::Value) -> UpdatePhotoMetadataRequest {
+    serde_json::from_value(value).unwrap()
+}
+
+fn fields(errors: &[FieldError]) -> Vec<&str> {
+    errors.iter().map(|error| error.field.as_str()).collect()
+}
+
+#[test]
+fn metadata_request_requires_a_change_and_paired_coordinates() {
+    assert_eq!(fields(&request(json!({})).validate().unwrap_err()), vec!["metadata"]);
+
+    let errors = request(json!({ "gpsLatitude": 95.0 })).validate().unwrap_err();
+
+    assert_eq!(fields(&errors), vec!["gps", "gpsLatitude"]);
+}
+
+#[test]
+fn metadata_request_marks_edited_fields_and_sets_gps_refs() {
+    let mut exif = ExifModel { make: Some("Unknown".to_string()), ..ExifModel::default() };
+
+    request(json!({ "lensModel": " Helios 44-2 ", "make": "", "gpsLatitude": -33.86, "gpsLongitude": 151.21 }))
+        .apply(&mut exif);
+
+    assert_eq!(exif.make, None);
+    assert_eq!(exif.lens_model.as_deref(), Some("Helios 44-2"));
+    assert_eq!(exif.gps_latitude, Some(-33.86));
+    assert_eq!(exif.gps_latitude_ref.as_deref(), Some("S"));
+    assert_eq!(exif.gps_longitude_ref.as_deref(), Some("E"));
+    assert_eq!(exif.edited_fields, vec!["make", "lensModel", "gps"]);
+}
+
+#[test]
+fn metadata_request_builds_exiftool_assignments() {
+    let tags = request(json!({ "model": "X100V", "gpsLatitude": 40.5, "gpsLongitude": -73.25 })).exiftool_tags();
+
+    let expected = [
+        ("Model", "X100V"),
+        ("GPSLatitude", "40.5"),
+        ("GPSLatitudeRef", "N"),
+        ("GPSLongitude", "73.25"),
+        ("GPSLongitudeRef", "W"),
+    ]
+    .map(|(tag, value)| (tag.to_string(), value.to_string()));
+    assert_eq!(tags, expected.to_vec());
+}
+
+#[test]
+fn re_extracted_metadata_keeps_manual_edits() {
+    let mut stored = ExifModel { lens_model: Some("Helios 44-2".to_string()), ..ExifModel::default() };
+    stored.mark_edited(ExifModel::FIELD_LENS_MODEL);
+    let mut fresh =
+        ExifModel { make: Some("Sony".to_string()), lens_model: Some("----".to_string()), ..ExifModel::default() };
+
+    fresh.keep_edits_from(&stored);
+
+    assert_eq!(fresh.make.as_deref(), Some("Sony"));
+    assert_eq!(fresh.lens_model.as_deref(), Some("Helios 44-2"));
+    assert_eq!(fresh.edited_fields, vec!["lensModel"]);
+}
+
+#[test]
+fn exiftool_write_fails_without_binary() {
+    let tool = ExifTool::with_exe_path("/nonexistent/exiftool");
+
+    let result = tool.write_tags("/tmp/photo.jpg", &[("Make".to_string(), "Canon".to_string())]);
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use nimble_photos::controllers::photo_controller::PhotoController;
+use nimble_photos::dtos::PhotoPathRepairRequest;
+use nimble_photos::entities::{Photo, StorageLocation};
+use nimble_photos::services::PhotoPathRepairService;
+use nimble_web::{
+    AuthenticationMiddleware, AuthorizationMiddleware, Claims, Configuration, ControllerInvokerMiddleware,
+    DefaultRouter, EndpointExecutionMiddleware, EndpointRegistry, HttpContext, HttpRequest, JwtTokenService,
+    MemoryRepository, Pipeline, Repository, RequestBody, Router, RoutingMiddleware, ServiceContainer, TokenService,
+    UserIdentity,
+};
+
+fn request(old_prefix: &str, new_prefix: &str) -> PhotoPathRepairRequest {
+    PhotoPathRepairRequest {
+        storage_id: Uuid::new_v4(),
+        old_prefix: old_prefix.to_string(),
+        new_prefix: new_prefix.to_string(),
+        dry_run: true,
+    }
+}
+
+fn post_repair(role: &str, body: serde_json::Value) -> u16 {
+    let mut registry = EndpointRegistry::new();
+    registry.register::<PhotoController>();
+
+    let mut router = DefaultRouter::new();
+    for route in registry.routes() {
+        router.add_route(route.clone());
+    }
+
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<PhotoPathRepairService, _>(|_| {
+        PhotoPathRepairService::new(
+            Arc::new(Repository::new(Box::new(MemoryRepository::<Photo>::new()))),
+            Arc::new(Repository::new(Box::new(MemoryRepository::<StorageLocation>::new()))),
+        )
+    });
+    container.register_singleton::<Arc<dyn TokenService>, _>(move |_| {
+        let service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+        Arc::new(service) as Arc<dyn TokenService>
+    });
+    let services = container.build();
+
+    let token_service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+    let identity = UserIdentity::new(Uuid::new_v4().to_string(), Claims::new().add_role(role));
+    let token = TokenService::create_access_token(&token_service, &identity).unwrap();
+
+    let mut request = HttpRequest::new("POST", "/api/admin/photos/repair-paths");
+    let header_val = format!("Bearer {}", token);
+    request.headers_mut().insert("authorization", header_val.as_str());
+    request.set_body(RequestBody::Text(body.to_string()));
+
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(RoutingMiddleware::new(router));
+    pipeline.add(AuthenticationMiddleware::new());
+    pipeline.add(AuthorizationMiddleware::new());
+    pipeline.add(ControllerInvokerMiddleware::new(Arc::new(registry)));
+    pipeline.add(EndpointExecutionMiddleware::new());
+    let _ = pipeline.run(&mut context);
+
+    context.response().status()
+}
+
+#[test]
+fn rewrite_replaces_only_the_leading_prefix() {
+    assert_eq!(
+        PhotoPathRepairService::rewrite("/mnt/old/2024/a.jpg", "/mnt/old", "/mnt/new"),
+        Some("/mnt/new/2024/a.jpg".to_string())
+    );
+    assert_eq!(PhotoPathRepairService::rewrite("/srv/mnt/old/a.jpg", "/mnt/old", "/mnt/new"), None);
+}
+
+#[test]
+fn validate_rejects_empty_or_identical_prefixes() {
+    assert!(PhotoPathRepairService::validate(&request("/mnt/old", "/mnt/new")).is_ok());
+    assert!(PhotoPathRepairService::validate(&request(" ", "/mnt/new")).is_err());
+    assert!(PhotoPathRepairService::validate(&request("/mnt/old", "")).is_err());
+    assert!(PhotoPathRepairService::validate(&request("/mnt/old", "/mnt/old")).is_err());
+}
+
+#[test]
+fn repair_request_defaults_to_dry_run() {
+    let payload: PhotoPathRepairRequest = serde_json::from_value(serde_json::json!({
+        "storageId": Uuid::new_v4(),
+        "oldPrefix": "/mnt/old",
+        "newPrefix": "/mnt/new",
+    }))
+    .unwrap();
+
+    assert!(payload.dry_run);
+}
+
+#[test]
+fn repair_paths_requires_admin() {
+    let body = serde_json::json!({ "storageId": Uuid::new_v4(), "oldPrefix": "/a", "newPrefix": "/b" });
+
+    assert_eq!(post_repair("viewer", body), 403);
+}
+
+#[test]
+fn repair_paths_rejects_identical_prefixes() {
+    let body = serde_json::json!({ "storageId": Uuid::new_v4(), "oldPrefix": "/a", "newPrefix": "/a" });
+
+    assert_eq!(post_repair("admin", body), 400);
+}
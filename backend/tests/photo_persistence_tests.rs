@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use nimble_photos::entities::{ExifModel, Photo};
+use nimble_photos::repositories::PhotoRepositoryExtensions;
+use nimble_web::data::provider::{DataError, DataProvider, DataResult};
+use nimble_web::data::query::Query;
+use nimble_web::{MemoryRepository, Page, QueryBuilder, Repository};
+use uuid::Uuid;
+
+struct RejectingExifProvider;
+
+#[async_trait]
+impl DataProvider<ExifModel> for RejectingExifProvider {
+    async fn create(&self, _e: ExifModel) -> DataResult<ExifModel> {
+        Err(DataError::Provider("connection reset by peer".to_string()))
+    }
+    async fn get(&self, _id: &Uuid) -> DataResult<Option<ExifModel>> {
+        Ok(None)
+    }
+    async fn update(&self, e: ExifModel) -> DataResult<ExifModel> {
+        Ok(e)
+    }
+    async fn delete(&self, _id: &Uuid) -> DataResult<bool> {
+        Ok(true)
+    }
+    async fn query(&self, _q: Query<ExifModel>) -> DataResult<Page<ExifModel>> {
+        Ok(Page::new(Vec::new(), 0, 1, 10))
+    }
+}
+
+fn photo(storage_id: Uuid, hash: &str) -> Photo {
+    Photo { id: Uuid::new_v4(), storage_id, hash: Some(hash.to_string()), ..Photo::default() }
+}
+
+async fn photo_count(photos: &Repository<Photo>) -> usize {
+    photos.query(QueryBuilder::<Photo>::new().page(1, 10).build()).await.expect("photo query failed").items.len()
+}
+
+#[tokio::test]
+async fn insert_photo_with_exif_links_exif_to_the_saved_photo() {
+    let photos = Repository::new(Box::new(MemoryRepository::<Photo>::new()));
+    let exifs = Repository::new(Box::new(MemoryRepository::<ExifModel>::new()));
+
+    let saved = photos
+        .insert_photo_with_exif(&exifs, photo(Uuid::new_v4(), "abc"), ExifModel::default())
+        .await
+        .expect("photo and exif should be inserted");
+
+    let stored = exifs.query(QueryBuilder::<ExifModel>::new().page(1, 10).build()).await.unwrap().items;
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].image_id, saved.id);
+}
+
+#[tokio::test]
+async fn failed_exif_insert_removes_the_photo_again() {
+    let photos = Repository::new(Box::new(MemoryRepository::<Photo>::new()));
+    let exifs = Repository::new(Box::new(RejectingExifProvider));
+
+    let result = photos.insert_photo_with_exif(&exifs, photo(Uuid::new_v4(), "abc"), ExifModel::default()).await;
+
+    assert!(result.is_err());
+    assert_eq!(photo_count(&photos).await, 0);
+}
+
+#[tokio::test]
+async fn incomplete_import_is_the_photo_in_the_same_storage_without_exif() {
+    let photos = Repository::new(Box::new(MemoryRepository::<Photo>::new()));
+    let exifs = Repository::new(Box::new(MemoryRepository::<ExifModel>::new()));
+    let storage_id = Uuid::new_v4();
+
+    let complete = photos
+        .insert_photo_with_exif(&exifs, photo(storage_id, "abc"), ExifModel::default())
+        .await
+        .expect("photo and exif should be inserted");
+    assert!(photos.find_incomplete_import(&exifs, storage_id, "abc").await.unwrap().is_none());
+
+    let orphan = photos.insert(photo(storage_id, "abc")).await.expect("photo should be inserted");
+    photos.insert(photo(Uuid::new_v4(), "abc")).await.expect("photo should be inserted");
+
+    let found = photos.find_incomplete_import(&exifs, storage_id, "abc").await.unwrap();
+    assert_eq!(found.map(|photo| photo.id), Some(orphan.id));
+    assert_ne!(orphan.id, complete.id);
+    assert!(photos.find_incomplete_import(&exifs, Uuid::new_v4(), "abc").await.unwrap().is_none());
+}
@@ -0,0 +1,73 @@
+use chrono::{TimeZone, Utc};
+use nimble_photos::models::{PhotoFilter, PhotoSort, TagMatch};
+use nimble_photos::repositories::PhotoQueryBuilder;
+use std::collections::HashSet;
+
+fn tags(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn unfiltered_query_only_collapses_stacks() {
+    let builder = PhotoQueryBuilder::new(&PhotoFilter::default(), &HashSet::new()).unwrap();
+
+    assert!(builder.params().is_empty());
+    assert_eq!(
+        builder.count_sql(),
+        "SELECT count(*) AS total FROM photos p WHERE (p.stack_id IS NULL OR p.stack_id = p.id)"
+    );
+}
+
+#[test]
+fn filters_bind_parameters_in_clause_order() {
+    let from = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let filter = PhotoFilter::new(&tags(&["Beach", "sunset"]), TagMatch::All)
+        .with_min_rating(Some(3))
+        .with_person(Some("Alice"))
+        .with_date_range(Some(from), Some(to));
+    let hidden = HashSet::from(["private".to_string()]);
+
+    let builder = PhotoQueryBuilder::new(&filter, &hidden).unwrap();
+    let sql = builder.where_clause();
+
+    assert_eq!(builder.params().len(), 6);
+    assert!(sql.contains("COALESCE(p.rating, 0) >= $1"));
+    assert!(sql.contains("fpp.name_norm = $2::text"));
+    assert!(sql.contains("p.sort_date >= $3::timestamptz"));
+    assert!(sql.contains("p.sort_date < $4::timestamptz"));
+    assert!(sql.contains("jsonb_array_elements_text($5::jsonb)"));
+    assert!(sql.contains(") >= 2"));
+    assert!(sql.contains("NOT EXISTS"));
+    assert!(sql.contains("jsonb_array_elements_text($6::jsonb)"));
+}
+
+#[test]
+fn any_match_needs_one_tag_and_zero_rating_adds_no_clause() {
+    let filter = PhotoFilter::new(&tags(&["beach", "Sunset"]), TagMatch::Any).with_min_rating(Some(0));
+
+    let builder = PhotoQueryBuilder::new(&filter, &HashSet::new()).unwrap();
+    let sql = builder.where_clause();
+
+    assert_eq!(builder.params().len(), 1);
+    assert!(sql.contains(") >= 1"));
+    assert!(!sql.contains("rating"));
+    assert!(!sql.contains("NOT EXISTS"));
+}
+
+#[test]
+fn page_and_count_share_the_same_filter() {
+    let filter = PhotoFilter::new(&tags(&["beach"]), TagMatch::Any).with_min_rating(Some(4));
+    let hidden = HashSet::from(["private".to_string()]);
+    let builder = PhotoQueryBuilder::new(&filter, &hidden).unwrap();
+    let where_clause = builder.where_clause();
+    let count_sql = builder.count_sql();
+
+    let (page_sql, params) = builder.page_sql(PhotoSort::default(), 3, 25);
+
+    assert!(count_sql.ends_with(&where_clause));
+    assert!(page_sql.contains(&where_clause));
+    assert!(page_sql.contains("LIMIT $4 OFFSET $5"));
+    assert!(page_sql.contains("(SELECT count(*) FROM visible) AS total"));
+    assert_eq!(params.len(), 5);
+}
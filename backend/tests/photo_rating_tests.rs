@@ -0,0 +1,49 @@
+use nimble_photos::models::{PhotoFilter, PhotoRating, TagMatch, TimelineZone};
+
+#[test]
+fn parse_min_accepts_ratings_in_range() {
+    assert_eq!(PhotoRating::parse_min(None), Ok(None));
+    assert_eq!(PhotoRating::parse_min(Some(" ")), Ok(None));
+    assert_eq!(PhotoRating::parse_min(Some("0")), Ok(Some(0)));
+    assert_eq!(PhotoRating::parse_min(Some(" 3 ")), Ok(Some(3)));
+    assert_eq!(PhotoRating::parse_min(Some("5")), Ok(Some(5)));
+}
+
+#[test]
+fn parse_min_rejects_out_of_range_values() {
+    for raw in ["6", "-1", "2.5", "five"] {
+        assert_eq!(PhotoRating::parse_min(Some(raw)), Err(PhotoRating::INVALID_RATING));
+    }
+}
+
+#[test]
+fn normalize_drops_invalid_camera_ratings() {
+    assert_eq!(PhotoRating::normalize(Some(4)), Some(4));
+    assert_eq!(PhotoRating::normalize(Some(255)), None);
+    assert_eq!(PhotoRating::normalize(None), None);
+}
+
+#[test]
+fn min_clause_treats_unrated_as_zero() {
+    assert_eq!(PhotoRating::min_clause("p", 6), "COALESCE(p.rating, 0) >= $6");
+}
+
+#[test]
+fn photo_filter_normalizes_tags() {
+    let tags = vec![" Beach ".to_string(), "beach".to_string(), "".to_string(), "Dogs".to_string()];
+    let filter = PhotoFilter::new(&tags, TagMatch::All).with_min_rating(Some(3));
+
+    assert_eq!(filter.normalized_tags(), vec!["beach", "dogs"]);
+    assert_eq!(filter.min_rating, Some(3));
+}
+
+#[test]
+fn filtered_days_page_adds_condition_to_day_scan() {
+    let zone = TimelineZone::utc();
+    let clause = PhotoRating::min_clause("p", 4);
+
+    let filtered = zone.filtered_days_page_sql(Some(&clause));
+
+    assert!(filtered.contains("WHERE p.day_date IS NOT NULL AND COALESCE(p.rating, 0) >= $4)"));
+    assert_eq!(zone.filtered_days_page_sql(None), zone.days_page_sql());
+}
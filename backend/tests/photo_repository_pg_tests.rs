@@ -0,0 +1,310 @@
+#![cfg(feature = "postgres")]
+
+mod support;
+
+use chrono::{TimeZone, Utc};
+use nimble_photos::dtos::{BatchPhotoChanges, TagRef};
+use nimble_photos::entities::{AlbumPhoto, ExifModel, Photo, Tag, ensure_supporting_schema};
+use nimble_photos::models::{PhotoFilter, PhotoSort, TagMatch, TimelineZone};
+use nimble_photos::repositories::{PhotoRepositoryExtensions, RepositoryError, TagRepositoryExtensions};
+use nimble_photos::services::AlbumMembershipCache;
+use nimble_web::Repository;
+use std::collections::HashSet;
+use support::PgTestDatabase;
+use uuid::Uuid;
+
+async fn insert_photo(photos: &Repository<Photo>, name: &str, taken: (i32, u32, u32, u32)) -> Photo {
+    let (year, month, day, hour) = taken;
+    let mut photo = Photo {
+        id: Uuid::new_v4(),
+        name: name.to_string(),
+        path: format!("pg-tests/{name}"),
+        date_taken: Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).single(),
+        ..Photo::default()
+    };
+    photo.refresh_timeline_dates();
+    photos.insert(photo.clone()).await.expect("photo should be inserted");
+    photo
+}
+
+async fn tag_photo(tags: &Repository<Tag>, photo: &Photo, names: &[&str]) {
+    let refs: Vec<TagRef> = names.iter().map(|name| TagRef::Name(name.to_string())).collect();
+    tags.set_photo_tags(photo.id, &refs, true).await.expect("photo tags should be set");
+}
+
+fn names(photos: &[Photo]) -> Vec<String> {
+    let mut names: Vec<String> = photos.iter().map(|photo| photo.name.clone()).collect();
+    names.sort();
+    names
+}
+
+fn hidden(names: &[&str]) -> HashSet<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[tokio::test]
+async fn timeline_groups_photos_by_day_newest_first() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let photos = database.repository::<Photo>();
+    let zone = TimelineZone::utc();
+    insert_photo(&photos, "morning.jpg", (2024, 5, 2, 8)).await;
+    insert_photo(&photos, "evening.jpg", (2024, 5, 2, 20)).await;
+    insert_photo(&photos, "new-year-eve.jpg", (2023, 12, 31, 23)).await;
+
+    let years = photos.get_years(&zone).await.expect("years should load");
+    let days = photos.get_days(1, 10, &zone, None).await.expect("days should load");
+    let groups = photos.photos_for_days(days.clone(), 1, &zone, None).await.expect("groups should load");
+
+    assert_eq!(years, vec![2024, 2023]);
+    assert_eq!(days, vec!["2024-05-02".to_string(), "2023-12-31".to_string()]);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].title, "2024-05-02");
+    assert_eq!(groups[0].photos.total, 2);
+    assert_eq!(groups[0].photos.items.len(), 1);
+    assert_eq!(groups[0].photos.items[0].name, "evening.jpg");
+    assert_eq!(groups[1].title, "2023-12-31");
+    assert_eq!(groups[1].photos.total, 1);
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn tag_filter_matches_any_or_all_requested_tags() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let photos = database.repository::<Photo>();
+    let tags = database.repository::<Tag>();
+    let both = insert_photo(&photos, "both.jpg", (2024, 1, 1, 9)).await;
+    let beach = insert_photo(&photos, "beach.jpg", (2024, 1, 2, 9)).await;
+    let sunset = insert_photo(&photos, "sunset.jpg", (2024, 1, 3, 9)).await;
+    insert_photo(&photos, "untagged.jpg", (2024, 1, 4, 9)).await;
+    tag_photo(&tags, &both, &["Beach", "Sunset"]).await;
+    tag_photo(&tags, &beach, &["Beach"]).await;
+    tag_photo(&tags, &sunset, &["Sunset"]).await;
+    let filter = vec!["beach".to_string(), "SUNSET".to_string(), "Beach".to_string()];
+    let sort = PhotoSort::default();
+
+    let any = photos.filter_photos_by_tags(&filter, TagMatch::Any, 1, 10, sort, &HashSet::new()).await.unwrap();
+    let all = photos.filter_photos_by_tags(&filter, TagMatch::All, 1, 10, sort, &HashSet::new()).await.unwrap();
+
+    assert_eq!(any.total, 3);
+    assert_eq!(names(&any.items), vec!["beach.jpg", "both.jpg", "sunset.jpg"]);
+    assert_eq!(all.total, 1);
+    assert_eq!(names(&all.items), vec!["both.jpg"]);
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn filtered_totals_match_the_photos_across_all_pages() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let photos = database.repository::<Photo>();
+    let tags = database.repository::<Tag>();
+    for day in 1..=7 {
+        let photo = insert_photo(&photos, &format!("count-{day}.jpg"), (2024, 8, day, 9)).await;
+        let names: &[&str] = if day % 2 == 0 { &["Beach"] } else { &["Beach", "Private"] };
+        tag_photo(&tags, &photo, names).await;
+    }
+    let from = Utc.with_ymd_and_hms(2024, 8, 2, 0, 0, 0).single();
+    let to = Utc.with_ymd_and_hms(2024, 8, 7, 0, 0, 0).single();
+    let beach = vec!["beach".to_string()];
+    let filters = [
+        (PhotoFilter::default(), HashSet::new()),
+        (PhotoFilter::new(&beach, TagMatch::Any), hidden(&["private"])),
+        (PhotoFilter::default().with_date_range(from, to), HashSet::new()),
+        (PhotoFilter::new(&beach, TagMatch::All).with_date_range(from, to), hidden(&["private"])),
+    ];
+    let sort = PhotoSort::default();
+
+    for (filter, hidden_tags) in filters {
+        let count = photos.count_photos(&filter, &hidden_tags).await.expect("count should load");
+        let mut seen = 0;
+        let mut page = 1;
+        loop {
+            let loaded = photos.query_photos(&filter, page, 2, sort, &hidden_tags).await.expect("page should load");
+            assert_eq!(loaded.total, count);
+            if loaded.items.is_empty() {
+                break;
+            }
+            seen += loaded.items.len() as u64;
+            page += 1;
+        }
+        assert_eq!(seen, count);
+    }
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn hidden_and_admin_only_tags_remove_photos_from_pages() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let photos = database.repository::<Photo>();
+    let tags = database.repository::<Tag>();
+    let private = insert_photo(&photos, "private.jpg", (2024, 2, 1, 9)).await;
+    let secret = insert_photo(&photos, "secret.jpg", (2024, 2, 2, 9)).await;
+    let public = insert_photo(&photos, "public.jpg", (2024, 2, 3, 9)).await;
+    tag_photo(&tags, &private, &["Private", "Family"]).await;
+    tag_photo(&tags, &secret, &["Vault", "Family"]).await;
+    tag_photo(&tags, &public, &["Family"]).await;
+    tags.set_tag_visibility(&[TagRef::Name("Vault".to_string())], Tag::VISIBILITY_HIDDEN)
+        .await
+        .expect("tag visibility should update");
+    let sort = PhotoSort::default();
+
+    let mut hidden_tags = hidden(&["private"]);
+    let viewer = photos.get_photos_page(1, 10, sort, &hidden_tags).await.expect("viewer page should load");
+    hidden_tags.extend(tags.admin_only_tag_names().await.expect("admin-only tags should load"));
+    let family = vec!["family".to_string()];
+    let guest = photos
+        .filter_photos_by_tags(&family, TagMatch::Any, 1, 10, sort, &hidden_tags)
+        .await
+        .expect("page should load");
+    let admin = photos
+        .filter_photos_by_tags(&family, TagMatch::Any, 1, 10, sort, &HashSet::new())
+        .await
+        .expect("page should load");
+
+    assert_eq!(names(&viewer.items), vec!["public.jpg", "secret.jpg"]);
+    assert_eq!(guest.total, 1);
+    assert_eq!(names(&guest.items), vec!["public.jpg"]);
+    assert_eq!(admin.total, 3);
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn get_by_ids_returns_dimensions_swapped_for_rotated_exif() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let photos = database.repository::<Photo>();
+    let exifs = database.repository::<ExifModel>();
+    let portrait = insert_photo(&photos, "portrait.jpg", (2024, 3, 1, 9)).await;
+    let landscape = insert_photo(&photos, "landscape.jpg", (2024, 3, 2, 9)).await;
+    for (photo, orientation) in [(&portrait, 6), (&landscape, 1)] {
+        let exif = ExifModel {
+            id: Uuid::new_v4(),
+            image_id: photo.id,
+            hash: photo.id.simple().to_string(),
+            orientation: Some(orientation),
+            pixel_x_dimension: Some(4000),
+            pixel_y_dimension: Some(3000),
+            ..ExifModel::default()
+        };
+        exifs.insert(exif).await.expect("exif should be inserted");
+    }
+
+    // Re-run the EXIF denormalization migration so it backfills the rows seeded above.
+    sqlx::query("DELETE FROM schema_migrations WHERE id = 5").execute(&database.pool).await.unwrap();
+    ensure_supporting_schema(&database.pool).await.expect("supporting schema should re-apply");
+    let loaded = photos.get_by_ids(&[portrait.id, landscape.id]).await.expect("photos should load by id");
+    let dimensions = |id: Uuid| {
+        let photo = loaded.iter().find(|photo| photo.id == id).expect("photo should be returned");
+        (photo.orientation, photo.width, photo.height)
+    };
+
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(dimensions(portrait.id), (Some(6), Some(3000), Some(4000)));
+    assert_eq!(dimensions(landscape.id), (Some(1), Some(4000), Some(3000)));
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn get_by_ids_keeps_requested_order() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let photos = database.repository::<Photo>();
+    let first = insert_photo(&photos, "first.jpg", (2024, 4, 1, 9)).await;
+    let second = insert_photo(&photos, "second.jpg", (2024, 4, 2, 9)).await;
+    let third = insert_photo(&photos, "third.jpg", (2024, 4, 3, 9)).await;
+
+    let requested = [third.id, first.id, Uuid::new_v4(), second.id];
+    let loaded = photos.get_by_ids(&requested).await.expect("photos should load by id");
+
+    let ids: Vec<Uuid> = loaded.iter().map(|photo| photo.id).collect();
+    assert_eq!(ids, vec![third.id, first.id, second.id]);
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn album_member_pages_never_exceed_page_size() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let photos = database.repository::<Photo>();
+    let album_photos = database.repository::<AlbumPhoto>();
+    let album_id = Uuid::new_v4();
+    for day in 1..=5 {
+        let photo = insert_photo(&photos, &format!("album-{day}.jpg"), (2024, 6, day, 9)).await;
+        album_photos.insert(AlbumPhoto::new(album_id, photo.id)).await.expect("album photo should be inserted");
+    }
+
+    let members = photos.album_member_ids(album_id).await.expect("members should load");
+    let last_page = AlbumMembershipCache::page_ids(&members, 3, 2);
+    let loaded = photos.get_by_ids(AlbumMembershipCache::page_ids(&members, 1, 2)).await.unwrap();
+    let sql_page = photos.photos_in_album(album_id, 1, 2, &HashSet::new()).await.unwrap();
+
+    assert_eq!(members.len(), 5);
+    assert_eq!(last_page, &members[4..]);
+    assert_eq!(loaded.iter().map(|photo| photo.id).collect::<Vec<_>>(), members[..2].to_vec());
+    assert_eq!(sql_page.items.iter().map(|photo| photo.id).collect::<Vec<_>>(), members[..2].to_vec());
+    assert_eq!(sql_page.total, 5);
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn bulk_field_update_sets_caption_and_realigns_timeline_columns() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let photos = database.repository::<Photo>();
+    let first = insert_photo(&photos, "first.jpg", (2024, 6, 1, 9)).await;
+    let second = insert_photo(&photos, "second.jpg", (2024, 6, 2, 9)).await;
+    let untouched = insert_photo(&photos, "untouched.jpg", (2024, 6, 3, 9)).await;
+    let taken = Utc.with_ymd_and_hms(1999, 12, 31, 18, 30, 0).unwrap();
+    let changes = BatchPhotoChanges {
+        caption: Some(" Millennium eve ".to_string()),
+        date_taken: Some(taken),
+        ..BatchPhotoChanges::default()
+    };
+
+    let mut updated = photos.update_photo_fields_bulk(&[first.id, second.id], &changes).await.unwrap();
+    updated.sort();
+    let loaded = photos.get_by_ids(&[first.id, second.id, untouched.id]).await.unwrap();
+    let find = |id: Uuid| loaded.iter().find(|photo| photo.id == id).expect("photo should be returned");
+
+    let mut expected = vec![first.id, second.id];
+    expected.sort();
+    assert_eq!(updated, expected);
+    for id in [first.id, second.id] {
+        let photo = find(id);
+        assert_eq!(photo.caption.as_deref(), Some("Millennium eve"));
+        assert_eq!(photo.date_taken, Some(taken));
+        assert_eq!(photo.sort_date, taken);
+        assert_eq!(photo.year, Some(1999));
+        assert_eq!(photo.month_day.as_deref(), Some("12-31"));
+    }
+    assert_eq!(find(untouched.id).caption, None);
+    assert_eq!(find(untouched.id).year, Some(2024));
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn duplicate_tag_insert_is_classified_as_conflict() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let tags = database.repository::<Tag>();
+    tags.insert(Tag::new("Beach", 0)).await.expect("tag should be inserted");
+
+    let error = tags.insert(Tag::new("beach", 0)).await.expect_err("duplicate tag should be rejected");
+    let classified = RepositoryError::classify(&format!("{:?}", error));
+
+    assert_eq!(classified, RepositoryError::UniqueViolation { constraint: Some("ux_tags_name_norm".to_string()) });
+    assert_eq!(classified.api_error().status(), 409);
+    database.drop().await;
+}
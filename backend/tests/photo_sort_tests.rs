@@ -0,0 +1,31 @@
+use nimble_photos::entities::SortDirection;
+use nimble_photos::models::{PhotoSort, PhotoSortField};
+
+#[test]
+fn photo_sort_defaults_to_date_taken_descending() {
+    let sort = PhotoSort::parse(None, None).expect("default sort should parse");
+
+    assert_eq!(sort, PhotoSort::default());
+    assert_eq!(sort.field, PhotoSortField::DateTaken);
+    assert_eq!(sort.direction, SortDirection::Desc);
+    assert_eq!(sort.order_clause("p"), "p.sort_date DESC NULLS LAST, p.id DESC");
+}
+
+#[test]
+fn photo_sort_parses_fields_and_directions() {
+    let oldest_first = PhotoSort::parse(Some("dateTaken"), Some("asc")).unwrap();
+    let largest = PhotoSort::parse(Some("size"), None).unwrap();
+    let by_name = PhotoSort::parse(Some("NAME"), None).unwrap();
+    let imported = PhotoSort::parse(Some("dateImported"), Some("DESC")).unwrap();
+
+    assert_eq!(oldest_first.order_clause("v"), "v.sort_date ASC NULLS LAST, v.id ASC");
+    assert_eq!(largest.order_clause("v"), "v.size DESC NULLS LAST, v.id DESC");
+    assert_eq!(by_name.order_clause("v"), "lower(v.name) ASC NULLS LAST, v.id ASC");
+    assert_eq!(imported.order_clause("v"), "COALESCE(v.date_imported, v.created_at) DESC NULLS LAST, v.id DESC");
+}
+
+#[test]
+fn photo_sort_rejects_unknown_values() {
+    assert_eq!(PhotoSort::parse(Some("size; DROP TABLE photos"), None), Err(PhotoSort::INVALID_SORT));
+    assert_eq!(PhotoSort::parse(Some("size"), Some("sideways")), Err(PhotoSort::INVALID_DIRECTION));
+}
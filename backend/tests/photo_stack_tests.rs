@@ -0,0 +1,59 @@
+use chrono::{TimeZone, Utc};
+use nimble_photos::entities::Photo;
+use nimble_photos::models::{PhotoStack, StackRepresentative};
+
+fn shot(name: &str, is_raw: bool) -> Photo {
+    Photo {
+        path: format!("/photos/2024/trip/{}", name),
+        name: name.to_string(),
+        is_raw: Some(is_raw),
+        date_taken: Some(Utc.with_ymd_and_hms(2024, 5, 1, 10, 30, 0).unwrap()),
+        ..Photo::default()
+    }
+}
+
+#[test]
+fn raw_and_jpeg_of_the_same_shot_are_a_pair() {
+    let raw = shot("IMG_0042.CR3", true);
+    let jpeg = shot("img_0042.jpg", false);
+
+    assert!(PhotoStack::is_pair(&raw, &jpeg));
+    assert!(PhotoStack::is_pair(&jpeg, &raw));
+}
+
+#[test]
+fn pairs_require_matching_name_time_directory_and_kind() {
+    let raw = shot("IMG_0042.CR3", true);
+
+    assert!(!PhotoStack::is_pair(&raw, &shot("IMG_0043.jpg", false)));
+    assert!(!PhotoStack::is_pair(&raw, &shot("IMG_0042.dng", true)));
+    assert!(!PhotoStack::is_pair(
+        &raw,
+        &Photo { path: "/photos/other/IMG_0042.jpg".into(), ..shot("IMG_0042.jpg", false) }
+    ));
+    assert!(!PhotoStack::is_pair(&raw, &Photo { date_taken: None, ..shot("IMG_0042.jpg", false) }));
+    assert!(!PhotoStack::is_pair(
+        &raw,
+        &Photo {
+            date_taken: Some(Utc.with_ymd_and_hms(2024, 5, 1, 10, 30, 1).unwrap()),
+            ..shot("IMG_0042.jpg", false)
+        }
+    ));
+}
+
+#[test]
+fn representative_follows_the_preference() {
+    let members = [shot("IMG_0042.CR3", true), shot("IMG_0042.JPG", false)];
+
+    assert_eq!(PhotoStack::representative(&members, StackRepresentative::Jpeg).unwrap().id, members[1].id);
+    assert_eq!(PhotoStack::representative(&members, StackRepresentative::Raw).unwrap().id, members[0].id);
+    assert!(PhotoStack::representative(&[], StackRepresentative::Jpeg).is_none());
+}
+
+#[test]
+fn representative_setting_parses_known_values() {
+    assert_eq!(StackRepresentative::parse(" RAW "), Some(StackRepresentative::Raw));
+    assert_eq!(StackRepresentative::parse("jpg"), Some(StackRepresentative::Jpeg));
+    assert_eq!(StackRepresentative::parse("heic"), None);
+    assert_eq!(StackRepresentative::default(), StackRepresentative::Jpeg);
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use nimble_photos::entities::photo::Photo;
+
+fn at(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+}
+
+fn photo(name: &str, date_taken: Option<DateTime<Utc>>, created_at: DateTime<Utc>) -> Photo {
+    let mut photo = Photo { name: name.to_string(), date_taken, created_at: Some(created_at), ..Photo::default() };
+    photo.refresh_timeline_dates();
+    photo
+}
+
+fn group_by_day(mut photos: Vec<Photo>) -> Vec<(NaiveDate, Vec<String>)> {
+    photos.sort_by(|left, right| right.sort_date.cmp(&left.sort_date));
+    let mut groups: Vec<(NaiveDate, Vec<String>)> = Vec::new();
+    for photo in photos {
+        match groups.last_mut() {
+            Some((day, names)) if *day == photo.day_date => names.push(photo.name),
+            _ => groups.push((photo.day_date, vec![photo.name])),
+        }
+    }
+    groups
+}
+
+#[test]
+fn undated_photos_are_grouped_by_created_day() {
+    let undated = photo("undated.jpg", None, at(2024, 3, 2, 9));
+
+    assert_eq!(undated.day_date, NaiveDate::from_ymd_opt(2024, 3, 2).unwrap());
+    assert_eq!(undated.sort_date, at(2024, 3, 2, 9));
+    assert_eq!(undated.year, Some(2024));
+    assert_eq!(undated.month_day.as_deref(), Some("03-02"));
+}
+
+#[test]
+fn day_and_sort_date_share_the_same_basis() {
+    let photos = vec![
+        photo("dated-late.jpg", Some(at(2024, 3, 1, 18)), at(2024, 5, 1, 8)),
+        photo("undated.jpg", None, at(2024, 3, 1, 12)),
+        photo("dated-early.jpg", Some(at(2024, 3, 1, 6)), at(2024, 5, 1, 8)),
+        photo("other-day.jpg", Some(at(2024, 2, 28, 10)), at(2024, 5, 1, 8)),
+    ];
+
+    for photo in &photos {
+        assert_eq!(photo.day_date, photo.sort_date.date_naive(), "{} has a mismatched day", photo.name);
+    }
+
+    let groups = group_by_day(photos);
+
+    assert_eq!(
+        groups,
+        vec![
+            (
+                NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                vec!["dated-late.jpg".to_string(), "undated.jpg".to_string(), "dated-early.jpg".to_string()],
+            ),
+            (NaiveDate::from_ymd_opt(2024, 2, 28).unwrap(), vec!["other-day.jpg".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn updating_date_taken_moves_photo_to_new_day() {
+    let mut photo = photo("moved.jpg", None, at(2024, 6, 10, 9));
+    photo.date_taken = Some(at(2023, 12, 31, 23));
+    photo.refresh_timeline_dates();
+
+    assert_eq!(photo.day_date, NaiveDate::from_ymd_opt(2023, 12, 31).unwrap());
+    assert_eq!(photo.year, Some(2023));
+}
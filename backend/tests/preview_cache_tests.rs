@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use nimble_photos::models::{PreviewCache, PreviewCacheBudgets, PreviewCacheFile};
+use serde_json::json;
+use uuid::Uuid;
+
+fn file(name: &str, bytes: u64, age_minutes: u64) -> PreviewCacheFile {
+    PreviewCacheFile {
+        path: PathBuf::from(format!("/storage/.previews/{name}.jpg")),
+        bytes,
+        last_access: SystemTime::now() - Duration::from_secs(age_minutes * 60),
+    }
+}
+
+fn names(files: &[PreviewCacheFile]) -> Vec<String> {
+    files.iter().map(|file| file.path.file_stem().unwrap().to_string_lossy().to_string()).collect()
+}
+
+#[test]
+fn budgets_fall_back_to_the_wildcard_entry() {
+    let storage = Uuid::new_v4();
+    let budgets = PreviewCacheBudgets::parse(&json!({ "*": 1000, storage.to_string(): 50 })).unwrap();
+
+    assert_eq!(budgets.max_bytes(storage), 50);
+    assert_eq!(budgets.max_bytes(Uuid::new_v4()), 1000);
+    assert_eq!(PreviewCacheBudgets::parse(&json!({})).unwrap().max_bytes(storage), 0);
+}
+
+#[test]
+fn budgets_reject_bad_keys_and_negative_limits() {
+    assert!(PreviewCacheBudgets::parse(&json!({ "not-a-uuid": 10 })).is_none());
+    assert!(PreviewCacheBudgets::parse(&json!({ "*": -1 })).is_none());
+    assert!(PreviewCacheBudgets::parse(&json!([10])).is_none());
+}
+
+#[test]
+fn eviction_removes_least_recently_accessed_until_within_budget() {
+    let files = vec![file("recent", 40, 5), file("oldest", 40, 300), file("older", 40, 120)];
+
+    let evicted = PreviewCache::eviction_plan(files, 50, |_| false);
+
+    assert_eq!(names(&evicted), vec!["oldest", "older"]);
+}
+
+#[test]
+fn eviction_skips_protected_files_and_unlimited_budgets() {
+    let files = vec![file("serving", 40, 300), file("idle", 40, 120), file("recent", 40, 5)];
+
+    let evicted = PreviewCache::eviction_plan(files.clone(), 80, |file| file.path.ends_with("serving.jpg"));
+    assert_eq!(names(&evicted), vec!["idle"]);
+
+    assert!(PreviewCache::eviction_plan(files, 0, |_| false).is_empty());
+}
@@ -0,0 +1,128 @@
+use nimble_photos::services::{PreviewBusy, PreviewCoordinator};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn unique_temp_dir(name: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let dir = std::env::temp_dir().join(format!("nimble_photos_preview_coordinator_{}_{}", name, nanos));
+    fs::create_dir_all(&dir).expect("failed to create temp dir");
+    dir
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_requests_for_same_preview_extract_once() {
+    let coordinator = PreviewCoordinator::new();
+    let output_path = unique_temp_dir("dedup").join("preview.jpg");
+    let extractions = Arc::new(AtomicUsize::new(0));
+
+    let mut handles = Vec::new();
+    for _ in 0..10 {
+        let coordinator = coordinator.clone();
+        let output_path = output_path.clone();
+        let extractions = Arc::clone(&extractions);
+        handles.push(tokio::spawn(async move {
+            let target = output_path.clone();
+            coordinator
+                .generate(&output_path, move || {
+                    extractions.fetch_add(1, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(100));
+                    fs::write(&target, b"preview").ok()?;
+                    Some(target)
+                })
+                .await
+        }));
+    }
+
+    for handle in handles {
+        let generated = handle.await.expect("preview task panicked");
+        assert_eq!(generated, Ok(Some(output_path.clone())));
+    }
+
+    assert_eq!(extractions.load(Ordering::SeqCst), 1);
+    assert_eq!(coordinator.in_flight_count(), 0);
+}
+
+#[tokio::test]
+async fn waiters_receive_none_when_extraction_fails() {
+    let coordinator = PreviewCoordinator::new();
+    let output_path = unique_temp_dir("failure").join("preview.jpg");
+
+    let leader = {
+        let coordinator = coordinator.clone();
+        let output_path = output_path.clone();
+        tokio::spawn(async move {
+            coordinator
+                .generate(&output_path, || {
+                    std::thread::sleep(Duration::from_millis(50));
+                    None
+                })
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let waiter = coordinator.generate(&output_path, || panic!("waiter must not extract")).await;
+
+    assert_eq!(waiter, Ok(None));
+    assert_eq!(leader.await.expect("leader panicked"), Ok(None));
+    assert_eq!(coordinator.in_flight_count(), 0);
+}
+
+#[tokio::test]
+async fn extraction_is_rejected_when_slots_stay_busy() {
+    let coordinator = PreviewCoordinator::with_limits(1, Duration::from_millis(20));
+    let root = unique_temp_dir("busy");
+
+    let slow = {
+        let coordinator = coordinator.clone();
+        let output_path = root.join("slow.jpg");
+        tokio::spawn(async move {
+            coordinator
+                .generate(&output_path, || {
+                    std::thread::sleep(Duration::from_millis(200));
+                    None
+                })
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(coordinator.running_extractions(), 1);
+
+    let rejected = coordinator.generate(&root.join("other.jpg"), || panic!("busy request must not extract")).await;
+
+    assert_eq!(rejected, Err(PreviewBusy));
+    assert_eq!(slow.await.expect("slow extraction panicked"), Ok(None));
+    assert_eq!(coordinator.running_extractions(), 0);
+}
+
+#[tokio::test]
+async fn extraction_limit_can_be_raised_at_runtime() {
+    let coordinator = PreviewCoordinator::with_limits(1, Duration::from_millis(20));
+    let root = unique_temp_dir("resize");
+    coordinator.set_max_concurrent_extractions(2);
+    coordinator.set_acquire_timeout(Duration::from_millis(50));
+
+    let slow = {
+        let coordinator = coordinator.clone();
+        let output_path = root.join("slow.jpg");
+        tokio::spawn(async move {
+            coordinator
+                .generate(&output_path, || {
+                    std::thread::sleep(Duration::from_millis(200));
+                    None
+                })
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let second = coordinator.generate(&root.join("other.jpg"), || None).await;
+
+    assert_eq!(second, Ok(None));
+    assert_eq!(coordinator.max_concurrent_extractions(), 2);
+    assert_eq!(coordinator.acquire_timeout(), Duration::from_millis(50));
+    assert_eq!(slow.await.expect("slow extraction panicked"), Ok(None));
+}
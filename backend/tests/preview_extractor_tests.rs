@@ -1,4 +1,5 @@
-use image::{ImageBuffer, ImageReader, Rgb};
+use image::{ImageBuffer, ImageReader, Rgb, Rgba};
+use nimble_photos::models::PreviewWatermark;
 use nimble_photos::services::PreviewExtractor;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -96,3 +97,39 @@ fn preview_extractor_extract_uses_configured_output_path() {
     assert_eq!(generated_path, output);
     assert!(generated_path.exists());
 }
+
+#[test]
+fn preview_extractor_watermarks_the_bottom_right_corner() {
+    let context = PreviewExtractorTestContext::new();
+    let preview = context.output_path(PreviewExtractorTestContext::DEFAULT_PREVIEW_FILE_NAME);
+    ImageBuffer::from_pixel(400, 300, Rgb([0u8, 0, 0])).save(&preview).expect("failed to save preview");
+    let mark_path = context.output_path("watermark.png");
+    ImageBuffer::from_pixel(200, 50, Rgba([255u8, 255, 255, 255])).save(&mark_path).expect("failed to save watermark");
+
+    let watermark = PreviewWatermark::new(&mark_path, 0.5);
+    let output = watermark.variant_path(&preview, "abcd");
+    PreviewExtractor::apply_watermark(&preview, &watermark, &output).expect("watermarking failed");
+
+    let image =
+        ImageReader::open(&output).expect("failed to open image").decode().expect("failed to decode").into_rgb8();
+    assert_eq!(image.dimensions(), (400, 300));
+    let corner = image.get_pixel(380, 285)[0];
+    assert!((100..=160).contains(&corner), "expected a half-opaque watermark, got {}", corner);
+    assert!(image.get_pixel(10, 10)[0] < 10);
+    assert!(ImageReader::open(&preview).unwrap().decode().unwrap().into_rgb8().get_pixel(380, 285)[0] < 10);
+}
+
+#[test]
+fn preview_watermark_cache_key_follows_settings() {
+    let context = PreviewExtractorTestContext::new();
+    let mark_path = context.output_path("watermark.png");
+    ImageBuffer::from_pixel(4, 4, Rgba([255u8, 255, 255, 255])).save(&mark_path).expect("failed to save watermark");
+
+    let watermark = PreviewWatermark::new(&mark_path, 0.5);
+    assert_eq!(watermark.cache_key(), PreviewWatermark::new(&mark_path, 0.5).cache_key());
+    assert_ne!(watermark.cache_key(), PreviewWatermark::new(&mark_path, 0.8).cache_key());
+    assert_eq!(PreviewWatermark::new(&mark_path, 3.0).opacity, 1.0);
+
+    let variant = watermark.variant_path(Path::new("/previews/ab/cd/abcd.jpg"), "abcd");
+    assert_eq!(variant, Path::new("/previews/ab/cd").join(format!("abcd.wm-{}.jpg", watermark.cache_key())));
+}
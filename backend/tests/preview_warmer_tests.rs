@@ -0,0 +1,37 @@
+use chrono::{TimeZone, Utc};
+use nimble_photos::models::{PreviewWarmScope, PreviewWarmerStatus};
+
+#[test]
+fn scope_parses_setting_values() {
+    assert_eq!(PreviewWarmScope::parse(" ALL "), Some(PreviewWarmScope::All));
+    assert_eq!(PreviewWarmScope::parse("recent"), Some(PreviewWarmScope::Recent));
+    assert_eq!(PreviewWarmScope::parse("none"), Some(PreviewWarmScope::None));
+    assert_eq!(PreviewWarmScope::parse("weekly"), None);
+}
+
+#[test]
+fn only_recent_scope_limits_by_upload_date() {
+    let now = Utc.with_ymd_and_hms(2025, 5, 31, 3, 0, 0).unwrap();
+
+    assert_eq!(PreviewWarmScope::Recent.since(now), Some(Utc.with_ymd_and_hms(2025, 5, 1, 3, 0, 0).unwrap()));
+    assert_eq!(PreviewWarmScope::All.since(now), None);
+}
+
+#[test]
+fn run_is_due_once_per_night() {
+    let night = Utc.with_ymd_and_hms(2025, 5, 31, PreviewWarmerStatus::NIGHTLY_HOUR, 10, 0).unwrap();
+    let evening_before = Utc.with_ymd_and_hms(2025, 5, 30, 22, 0, 0).unwrap();
+    let fresh = PreviewWarmerStatus::default();
+
+    assert!(fresh.is_due(night));
+    assert!(!fresh.is_due(Utc.with_ymd_and_hms(2025, 5, 31, 1, 0, 0).unwrap()));
+
+    let ran_yesterday = PreviewWarmerStatus { started_at: Some(evening_before), ..PreviewWarmerStatus::default() };
+    assert!(ran_yesterday.is_due(night));
+
+    let ran_tonight = PreviewWarmerStatus { started_at: Some(night), ..PreviewWarmerStatus::default() };
+    assert!(!ran_tonight.is_due(night + chrono::Duration::hours(2)));
+
+    let running = PreviewWarmerStatus { running: true, ..PreviewWarmerStatus::default() };
+    assert!(!running.is_due(night));
+}
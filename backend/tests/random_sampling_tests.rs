@@ -0,0 +1,22 @@
+use nimble_photos::models::RandomSampling;
+
+#[test]
+fn small_libraries_use_full_random_order() {
+    assert_eq!(RandomSampling::choose(0, 5), RandomSampling::Full);
+    assert_eq!(RandomSampling::choose(RandomSampling::ROW_THRESHOLD, 5), RandomSampling::Full);
+    assert_eq!(RandomSampling::Full.table_clause("photos", "p"), "photos p");
+}
+
+#[test]
+fn large_libraries_use_table_sample() {
+    let sampling = RandomSampling::choose(1_000_000, 5);
+
+    assert_eq!(sampling, RandomSampling::Sample(0.025));
+    assert_eq!(sampling.table_clause("photos", "p"), "photos p TABLESAMPLE SYSTEM (0.0250)");
+}
+
+#[test]
+fn sample_percentage_is_bounded() {
+    assert_eq!(RandomSampling::choose(100_000_000, 1), RandomSampling::Sample(0.01));
+    assert_eq!(RandomSampling::choose(30_000, 6), RandomSampling::Full);
+}
@@ -0,0 +1,17 @@
+use chrono::NaiveDate;
+use nimble_photos::dtos::PhotoWithTags;
+use nimble_photos::entities::Photo;
+
+#[test]
+fn photo_with_tags_reads_import_day_from_row() {
+    let mut row = serde_json::to_value(Photo::default()).expect("photo should serialize");
+    row["tags"] = serde_json::json!(["family"]);
+    row["imported_on"] = serde_json::json!("2026-10-03");
+
+    let item: PhotoWithTags = serde_json::from_value(row).expect("row should deserialize");
+    let json = serde_json::to_value(&item).expect("item should serialize");
+
+    assert_eq!(item.tags, vec!["family".to_string()]);
+    assert_eq!(item.imported_on, NaiveDate::from_ymd_opt(2026, 10, 3));
+    assert_eq!(json["importedOn"], "2026-10-03");
+}
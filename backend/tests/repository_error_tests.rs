@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use nimble_photos::controllers::ApiResultExtensions;
+use nimble_photos::dtos::ApiErrorBody;
+use nimble_photos::middlewares::ErrorResponseMiddleware;
+use nimble_photos::repositories::{RepositoryError, RepositoryResultExtensions};
+use nimble_web::pipeline::middleware::Middleware;
+use nimble_web::pipeline::next::Next;
+use nimble_web::{Configuration, HttpContext, HttpRequest, Pipeline, PipelineError, ResponseBody, ServiceContainer};
+
+const DUPLICATE_TAG: &str = "Provider(\"error returned from database: duplicate key value violates unique constraint \\\"ux_tags_name_norm\\\"\")";
+
+struct FailingHandler {
+    status: Option<u16>,
+    message: &'static str,
+    from_repository: bool,
+}
+
+#[async_trait]
+impl Middleware for FailingHandler {
+    async fn handle(&self, context: &mut HttpContext, _next: Next<'_>) -> Result<(), PipelineError> {
+        if let Some(status) = self.status {
+            context.response_mut().set_status(status);
+        }
+        if self.from_repository {
+            let failed: Result<(), String> = Err(self.message.to_string());
+            return failed.or_repository_error("insert_tag").or_fail(context);
+        }
+        Err(PipelineError::message(self.message))
+    }
+}
+
+fn run_failing(status: Option<u16>, message: &'static str) -> (u16, String) {
+    run_pipeline(FailingHandler { status, message, from_repository: false })
+}
+
+fn run_repository_failing(message: &'static str) -> (u16, String) {
+    run_pipeline(FailingHandler { status: None, message, from_repository: true })
+}
+
+fn run_pipeline(handler: FailingHandler) -> (u16, String) {
+    let services = ServiceContainer::new().build();
+    let request = HttpRequest::new("POST", "/api/tags");
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(ErrorResponseMiddleware::new());
+    pipeline.add(handler);
+    let _ = pipeline.run(&mut context);
+
+    let body = match context.response().body() {
+        ResponseBody::Text(text) => text.clone(),
+        _ => String::new(),
+    };
+    (context.response().status(), body)
+}
+
+#[test]
+fn classify_reads_constraint_from_escaped_provider_message() {
+    assert_eq!(
+        RepositoryError::classify(DUPLICATE_TAG),
+        RepositoryError::UniqueViolation { constraint: Some("ux_tags_name_norm".to_string()) }
+    );
+    assert_eq!(
+        RepositoryError::classify(
+            "insert or update on table \"photo_tags\" violates foreign key constraint \"photo_tags_tag_id_fkey\""
+        ),
+        RepositoryError::ForeignKeyViolation { constraint: Some("photo_tags_tag_id_fkey".to_string()) }
+    );
+}
+
+#[test]
+fn classify_recognises_missing_rows_and_connection_failures() {
+    assert_eq!(RepositoryError::classify("Provider(\"no rows returned by a query\")"), RepositoryError::NotFound);
+    assert_eq!(RepositoryError::classify("Provider(\"PoolTimedOut\")"), RepositoryError::Connection);
+    assert_eq!(RepositoryError::classify("Provider(\"PoolClosed\")"), RepositoryError::Connection);
+    assert_eq!(RepositoryError::classify("column \"nope\" does not exist"), RepositoryError::Other);
+}
+
+#[test]
+fn repository_errors_map_to_statuses() {
+    let unique = RepositoryError::UniqueViolation { constraint: Some("ux_tags_name_norm".to_string()) };
+    assert_eq!(unique.api_error().status(), 409);
+    assert_eq!(unique.api_error().message(), "A tag with this name already exists");
+    assert_eq!(RepositoryError::ForeignKeyViolation { constraint: None }.api_error().status(), 409);
+    assert_eq!(RepositoryError::NotFound.api_error().status(), 404);
+    assert_eq!(RepositoryError::Connection.api_error().status(), 503);
+    assert_eq!(RepositoryError::Other.api_error().status(), 500);
+    assert_eq!(RepositoryError::Forbidden("no").api_error().status(), 403);
+}
+
+#[test]
+fn error_response_middleware_does_not_read_status_from_plain_messages() {
+    let (status, body) = run_failing(None, DUPLICATE_TAG);
+    let parsed: ApiErrorBody = serde_json::from_str(&body).expect("error body should be json");
+
+    assert_eq!(status, 500);
+    assert_eq!(parsed.code, "internal");
+    assert!(!body.contains("ux_tags_name_norm"));
+}
+
+#[test]
+fn error_response_middleware_maps_duplicate_key_to_conflict_without_sql() {
+    let (status, body) = run_repository_failing(DUPLICATE_TAG);
+    let parsed: ApiErrorBody = serde_json::from_str(&body).expect("error body should be json");
+
+    assert_eq!(status, 409);
+    assert_eq!(parsed.code, "conflict");
+    assert!(!body.contains("duplicate key"));
+    assert!(!body.contains("ux_tags_name_norm"));
+}
+
+#[test]
+fn error_response_middleware_hides_unclassified_provider_errors() {
+    let (status, body) = run_repository_failing("Provider(\"syntax error at or near \\\"SELEC\\\"\")");
+    let parsed: ApiErrorBody = serde_json::from_str(&body).expect("error body should be json");
+
+    assert_eq!(status, 500);
+    assert_eq!(parsed.code, "internal");
+    assert!(!body.contains("SELEC"));
+}
+
+#[test]
+fn error_response_middleware_keeps_client_status_set_by_handler() {
+    let (status, _) = run_failing(Some(404), DUPLICATE_TAG);
+
+    assert_eq!(status, 404);
+}
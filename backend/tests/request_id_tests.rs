@@ -0,0 +1,21 @@
+use nimble_photos::models::RequestId;
+use uuid::Uuid;
+
+#[test]
+fn from_header_honors_valid_incoming_id() {
+    let request_id = RequestId::from_header(Some(" trace-123_abc.1 "));
+    assert_eq!(request_id.as_str(), "trace-123_abc.1");
+}
+
+#[test]
+fn from_header_generates_id_when_missing_or_invalid() {
+    let missing = RequestId::from_header(None);
+    assert!(Uuid::parse_str(missing.as_str()).is_ok());
+
+    let invalid = RequestId::from_header(Some("bad id\nwith injection"));
+    assert!(Uuid::parse_str(invalid.as_str()).is_ok());
+
+    let oversized = "a".repeat(129);
+    let too_long = RequestId::from_header(Some(&oversized));
+    assert!(Uuid::parse_str(too_long.as_str()).is_ok());
+}
@@ -0,0 +1,30 @@
+use nimble_photos::middlewares::RequestLoggingMiddleware;
+
+#[test]
+fn auth_and_setup_bodies_are_never_logged() {
+    let body = br#"{"email":"a@example.com","password":"hunter2"}"#;
+
+    assert_eq!(RequestLoggingMiddleware::loggable_body("/api/auth/login", body, 2048), None);
+    assert_eq!(RequestLoggingMiddleware::loggable_body("/api/setup/admin", body, 2048), None);
+}
+
+#[test]
+fn credential_fields_are_redacted_in_other_bodies() {
+    let body = br#"{"name":"Ann","password":"hunter2","confirmPassword":"hunter2","nested":{"code":"123456","refreshToken":"abc"}}"#;
+
+    let logged =
+        RequestLoggingMiddleware::loggable_body("/api/admin/users", body, 2048).expect("body should be logged");
+
+    assert!(logged.contains("\"name\":\"Ann\""));
+    assert!(!logged.contains("hunter2"));
+    assert!(!logged.contains("123456"));
+    assert!(!logged.contains("abc"));
+    assert!(logged.contains("[redacted]"));
+}
+
+#[test]
+fn non_json_bodies_are_truncated_on_a_char_boundary() {
+    let logged = RequestLoggingMiddleware::loggable_body("/api/photos", "héllo".as_bytes(), 2).expect("body is logged");
+
+    assert_eq!(logged, "h");
+}
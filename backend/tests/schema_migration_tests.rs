@@ -0,0 +1,61 @@
+#![cfg(feature = "postgres")]
+
+use nimble_photos::entities::{Migration, SchemaMigrator, ensure_supporting_schema};
+use sqlx::PgPool;
+
+async fn setup_pool() -> Option<PgPool> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    PgPool::connect(&url).await.ok()
+}
+
+#[test]
+fn builtin_migrations_are_strictly_ordered() {
+    let migrator = SchemaMigrator::builtin();
+    let ids = migrator.ids();
+
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(migrator.latest_version(), *ids.last().unwrap());
+}
+
+#[test]
+fn out_of_order_migrations_are_rejected() {
+    let migrations = vec![Migration::sql(2, "second", &["SELECT 1"]), Migration::sql(1, "first", &["SELECT 1"])];
+
+    assert!(SchemaMigrator::new(migrations).is_err());
+}
+
+#[test]
+fn database_ahead_of_binary_is_refused() {
+    let migrator = SchemaMigrator::builtin();
+    let mut applied = migrator.ids();
+    assert!(migrator.check_compatible(&applied).is_ok());
+
+    applied.push(migrator.latest_version() + 1);
+    let error = migrator.check_compatible(&applied).unwrap_err();
+    assert!(error.to_string().contains("ahead of this binary"));
+}
+
+#[test]
+fn only_unapplied_migrations_are_pending() {
+    let migrator = SchemaMigrator::builtin();
+
+    let pending: Vec<i64> = migrator.pending(&[1, 2]).iter().map(|migration| migration.id).collect();
+
+    assert!(!pending.contains(&1) && !pending.contains(&2));
+    assert_eq!(pending.len(), migrator.ids().len() - 2);
+}
+
+#[tokio::test]
+async fn all_migrations_apply_and_are_recorded_once() {
+    let Some(pool) = setup_pool().await else {
+        return;
+    };
+
+    ensure_supporting_schema(&pool).await.expect("schema migrations failed");
+    let migrator = SchemaMigrator::builtin();
+    let applied = SchemaMigrator::applied_versions(&pool).await.expect("schema_migrations unreadable");
+    assert!(migrator.ids().iter().all(|id| applied.contains(id)));
+
+    let reapplied = migrator.run(&pool).await.expect("second run failed");
+    assert!(reapplied.is_empty());
+}
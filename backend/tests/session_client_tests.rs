@@ -0,0 +1,33 @@
+use nimble_photos::models::SessionClient;
+
+#[test]
+fn user_agent_summary_names_browser_and_platform() {
+    let summarize = |agent: &str| SessionClient::summarize_user_agent(agent);
+
+    assert_eq!(
+        summarize(
+            "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0 Safari/537.36 Edg/126.0"
+        )
+        .as_deref(),
+        Some("Edge on macOS")
+    );
+    assert_eq!(
+        summarize(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1"
+        )
+        .as_deref(),
+        Some("Safari on iOS")
+    );
+    assert_eq!(summarize("curl/8.4.0").as_deref(), Some("curl/8.4.0"));
+    assert_eq!(summarize("  "), None);
+}
+
+#[test]
+fn ip_address_prefers_the_original_forwarded_client() {
+    let forwarded = SessionClient::from_headers(None, Some(" 198.51.100.4 , 10.0.0.2"), Some("10.0.0.2"));
+    let direct = SessionClient::from_headers(None, None, Some("192.0.2.10"));
+
+    assert_eq!(forwarded.ip_address.as_deref(), Some("198.51.100.4"));
+    assert_eq!(direct.ip_address.as_deref(), Some("192.0.2.10"));
+    assert_eq!(SessionClient::from_headers(None, None, None), SessionClient::default());
+}
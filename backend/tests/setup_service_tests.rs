@@ -0,0 +1,28 @@
+use nimble_photos::dtos::SetupStatusDto;
+
+#[test]
+fn fresh_instance_reports_every_step_incomplete() {
+    let status = SetupStatusDto::new(false, false, false);
+
+    assert!(!status.complete);
+    assert_eq!(status.incomplete_steps, vec!["admin", "storage", "jwtSecret"]);
+}
+
+#[test]
+fn completed_steps_are_not_reported() {
+    let status = SetupStatusDto::new(true, false, true);
+    assert_eq!(status.incomplete_steps, vec!["storage"]);
+
+    let status = SetupStatusDto::new(true, true, true);
+    assert!(status.complete);
+    assert!(status.incomplete_steps.is_empty());
+}
+
+#[test]
+fn status_serializes_as_camel_case() {
+    let json = serde_json::to_value(SetupStatusDto::new(true, true, false)).unwrap();
+
+    assert_eq!(json["hasAdmin"], true);
+    assert_eq!(json["jwtSecretConfigured"], false);
+    assert_eq!(json["incompleteSteps"], serde_json::json!(["jwtSecret"]));
+}
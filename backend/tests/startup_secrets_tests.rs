@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use nimble_photos::models::AppEnvironment;
+use nimble_photos::services::{SecretSource, StartupSecrets};
+use uuid::Uuid;
+
+const REAL_KEY: &str = "q2jH0Uu0mTQn3mU7pWZl2m0dYkq5cS8b1yXJ0m0o3rE=";
+
+fn secrets_file() -> PathBuf {
+    std::env::temp_dir().join(format!("nimble-secrets-{}", Uuid::new_v4())).join("secrets.json")
+}
+
+#[test]
+fn environment_defaults_to_development() {
+    assert_eq!(AppEnvironment::parse(None), Some(AppEnvironment::Development));
+    assert_eq!(AppEnvironment::parse(Some("Production")), Some(AppEnvironment::Production));
+    assert_eq!(AppEnvironment::parse(Some("staging")), None);
+}
+
+#[test]
+fn production_rejects_missing_or_default_secrets() {
+    let file = secrets_file();
+
+    let err = StartupSecrets::resolve(AppEnvironment::Production, None, Some(REAL_KEY), &file).unwrap_err();
+    assert!(err.to_string().contains("jwt.secret"));
+
+    let err = StartupSecrets::resolve(
+        AppEnvironment::Production,
+        Some("super-secret-key-123"),
+        Some(StartupSecrets::DEFAULT_ENCRYPTION_KEY),
+        &file,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("jwt.secret and encryption.key"));
+    assert!(!file.exists());
+}
+
+#[test]
+fn production_accepts_configured_secrets() {
+    let secrets =
+        StartupSecrets::resolve(AppEnvironment::Production, Some("real-secret"), Some(REAL_KEY), &secrets_file())
+            .unwrap();
+
+    assert_eq!(secrets.jwt_secret, "real-secret");
+    assert_eq!(secrets.jwt_source, SecretSource::Configured);
+    assert_eq!(secrets.encryption_source, SecretSource::Configured);
+}
+
+#[test]
+fn development_generates_and_reuses_secrets() {
+    let file = secrets_file();
+
+    let first =
+        StartupSecrets::resolve(AppEnvironment::Development, Some("your_jwt_secret_key_here"), None, &file).unwrap();
+    let second = StartupSecrets::resolve(AppEnvironment::Development, None, None, &file).unwrap();
+
+    assert_eq!(first.jwt_source, SecretSource::Generated);
+    assert_eq!(first.encryption_source, SecretSource::Generated);
+    assert_eq!(first.jwt_secret, second.jwt_secret);
+    assert_eq!(first.encryption_key, second.encryption_key);
+    assert!(file.exists());
+
+    let _ = std::fs::remove_dir_all(file.parent().unwrap());
+}
+
+#[test]
+fn development_keeps_default_encryption_key() {
+    let secrets = StartupSecrets::resolve(
+        AppEnvironment::Development,
+        Some("real-secret"),
+        Some(StartupSecrets::DEFAULT_ENCRYPTION_KEY),
+        &secrets_file(),
+    )
+    .unwrap();
+
+    assert_eq!(secrets.encryption_source, SecretSource::Default);
+    assert_eq!(secrets.summary(), "environment=development, jwt.secret=configured, encryption.key=default");
+}
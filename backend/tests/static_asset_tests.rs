@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use nimble_photos::controllers::AssetsController;
+use nimble_photos::entities::Setting;
+use nimble_photos::middlewares::StaticFileMiddleware;
+use nimble_photos::services::SettingService;
+use nimble_web::{Configuration, HttpContext, HttpRequest, MemoryRepository, Pipeline, Repository, ServiceContainer};
+use uuid::Uuid;
+
+fn web_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("nimble-web-root-{}-{}", name, Uuid::new_v4()));
+    std::fs::create_dir_all(root.join("assets")).unwrap();
+    std::fs::write(root.join("index.html"), "<!doctype html><app-root></app-root>").unwrap();
+    std::fs::write(root.join("assets").join("main.3f9a1c2b.js"), "console.log('app');").unwrap();
+    std::fs::write(root.join("favicon.ico"), [0u8; 4]).unwrap();
+    root
+}
+
+fn run(middleware: StaticFileMiddleware, web_root: Option<&str>, path: &str) -> HttpContext {
+    let repo = MemoryRepository::<Setting>::new();
+    let settings = SettingService::new(Arc::new(Repository::new(Box::new(repo.clone()))));
+    if let Some(value) = web_root {
+        tokio::runtime::Runtime::new()
+            .expect("runtime")
+            .block_on(settings.update("assets.webRoot", serde_json::json!(value)))
+            .unwrap();
+    }
+
+    let mut container = ServiceContainer::new();
+    container.register_singleton::<SettingService, _>(move |_| {
+        SettingService::new(Arc::new(Repository::new(Box::new(repo.clone()))))
+    });
+    let request = HttpRequest::new("GET", path);
+    let mut context = HttpContext::new(request, container.build(), Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(middleware);
+    let _ = pipeline.run(&mut context);
+    context
+}
+
+#[test]
+fn resolves_exact_files_and_directory_index() {
+    let root = web_root("exact");
+
+    assert_eq!(
+        AssetsController::web_asset_path(&root, "/assets/main.3f9a1c2b.js").unwrap(),
+        Some(root.join("assets").join("main.3f9a1c2b.js"))
+    );
+    assert_eq!(AssetsController::web_asset_path(&root, "/").unwrap(), Some(root.join("index.html")));
+}
+
+#[test]
+fn unknown_client_routes_fall_back_to_index() {
+    let root = web_root("spa");
+
+    assert_eq!(AssetsController::web_asset_path(&root, "/albums/2024").unwrap(), Some(root.join("index.html")));
+    assert_eq!(AssetsController::web_asset_path(&root, "/assets/missing.js").unwrap(), None);
+    assert_eq!(AssetsController::web_asset_path(&root, "/api/photos/unknown").unwrap(), None);
+}
+
+#[test]
+fn rejects_directory_traversal() {
+    let root = web_root("traversal");
+
+    assert!(AssetsController::web_asset_path(&root, "/../secrets.json").is_err());
+    assert!(AssetsController::web_asset_path(&root, "/assets/..\\..\\secrets.json").is_err());
+}
+
+#[test]
+fn cache_headers_depend_on_file_kind() {
+    assert_eq!(AssetsController::cache_control(Path::new("index.html")), "no-cache");
+    assert_eq!(AssetsController::cache_control(Path::new("chunk-FFHMD2TL.js")), "public, max-age=31536000, immutable");
+    assert_eq!(AssetsController::cache_control(Path::new("main.3f9a1c2b.css")), "public, max-age=31536000, immutable");
+    assert_eq!(AssetsController::cache_control(Path::new("favicon.ico")), "public, max-age=3600");
+    assert_eq!(AssetsController::content_type(Path::new("main.js")), "text/javascript; charset=utf-8");
+}
+
+#[test]
+fn middleware_serves_spa_fallback_with_no_cache() {
+    let root = web_root("middleware");
+    let context = run(StaticFileMiddleware::default(), Some(root.to_str().unwrap()), "/photos/123");
+
+    assert_eq!(context.response().status(), 200);
+    assert_eq!(context.response().headers().get("cache-control"), Some("no-cache"));
+    assert_eq!(context.response().headers().get("content-type"), Some("text/html; charset=utf-8"));
+}
+
+#[test]
+fn middleware_is_disabled_when_web_root_is_empty() {
+    let root = web_root("disabled");
+    let context = run(StaticFileMiddleware::default(), Some(""), "/");
+
+    assert!(root.join("index.html").exists());
+    assert_eq!(context.response().headers().get("cache-control"), None);
+}
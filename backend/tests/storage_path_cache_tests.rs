@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use nimble_photos::entities::StorageLocation;
+use nimble_photos::services::StoragePathCache;
+use nimble_web::MemoryRepository;
+use nimble_web::Repository;
+
+fn storage(path: &str) -> StorageLocation {
+    StorageLocation {
+        id: Uuid::new_v4(),
+        label: "Primary".to_string(),
+        path: path.to_string(),
+        is_default: false,
+        is_readonly: false,
+        created_at: "2026-02-17T00:00:00Z".to_string(),
+        category_template: "{year}/{date:%Y-%m-%d}/{fileName}".to_string(),
+    }
+}
+
+fn repository(storages: Vec<StorageLocation>) -> Arc<Repository<StorageLocation>> {
+    let provider = MemoryRepository::<StorageLocation>::new();
+    provider.seed(storages);
+    Arc::new(Repository::new(Box::new(provider)))
+}
+
+#[tokio::test]
+async fn cache_serves_roots_until_invalidated() {
+    let first = storage("/photos/first");
+    let repository = repository(vec![first.clone()]);
+    let cache = StoragePathCache::new();
+
+    assert_eq!(cache.root(&repository, first.id).await.unwrap(), Some(PathBuf::from("/photos/first")));
+    assert!(cache.is_loaded());
+
+    let second = storage("/photos/second");
+    repository.insert(second.clone()).await.expect("storage should be inserted");
+    assert_eq!(cache.root(&repository, second.id).await.unwrap(), None);
+
+    cache.invalidate();
+    assert!(!cache.is_loaded());
+    assert_eq!(cache.root(&repository, second.id).await.unwrap(), Some(PathBuf::from("/photos/second")));
+    assert_eq!(cache.roots(&repository).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn cache_returns_none_for_unknown_storage() {
+    let repository = repository(vec![storage("/photos/first")]);
+    let cache = StoragePathCache::new();
+
+    assert_eq!(cache.root(&repository, Uuid::new_v4()).await.unwrap(), None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn invalidation_during_concurrent_reads_drops_deleted_storage() {
+    let kept = storage("/photos/kept");
+    let deleted = storage("/photos/deleted");
+    let repository = repository(vec![kept.clone(), deleted.clone()]);
+    let cache = StoragePathCache::new();
+    cache.roots(&repository).await.unwrap();
+
+    let readers = (0..32)
+        .map(|_| {
+            let cache = cache.clone();
+            let repository = Arc::clone(&repository);
+            let kept_id = kept.id;
+            tokio::spawn(async move {
+                for _ in 0..50 {
+                    let root = cache.root(&repository, kept_id).await.expect("read should succeed");
+                    assert_eq!(root, Some(PathBuf::from("/photos/kept")));
+                    tokio::task::yield_now().await;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    repository.delete(&deleted.id).await.expect("storage should be deleted");
+    cache.invalidate();
+
+    for _ in 0..50 {
+        assert_eq!(cache.root(&repository, deleted.id).await.unwrap(), None);
+        tokio::task::yield_now().await;
+    }
+
+    for reader in readers {
+        reader.await.expect("reader should not panic");
+    }
+
+    assert_eq!(cache.root(&repository, deleted.id).await.unwrap(), None);
+    assert_eq!(cache.roots(&repository).await.unwrap(), vec![PathBuf::from("/photos/kept")]);
+}
@@ -0,0 +1,80 @@
+use nimble_photos::entities::photo_browse::BrowseRequest;
+use nimble_photos::models::{BrowsePath, BrowsePathError};
+use nimble_photos::services::StoragePathGuard;
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+fn fixture_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("nimble-path-guard-{}-{}", name, Uuid::new_v4()));
+    fs::create_dir_all(root.join("2026").join("trip")).expect("fixture directories should be created");
+    root
+}
+
+fn request(path: &str) -> BrowseRequest {
+    BrowseRequest { path: Some(path.to_string()), ..Default::default() }
+}
+
+#[test]
+fn browse_path_rejects_parent_segments() {
+    assert_eq!(BrowsePath::segments("2026/../secrets"), Err(BrowsePathError::Traversal));
+    assert_eq!(BrowsePath::segments("./2026"), Err(BrowsePathError::Traversal));
+    assert_eq!(request("%2e%2e/etc").path_segments(), Err(BrowsePathError::Traversal));
+    assert_eq!(request("2026%2F%2E%2E").path_segments(), Err(BrowsePathError::Traversal));
+}
+
+#[test]
+fn browse_path_rejects_absolute_paths() {
+    assert_eq!(BrowsePath::segments("/etc/passwd"), Err(BrowsePathError::Absolute));
+    assert_eq!(BrowsePath::segments("C:\\Windows"), Err(BrowsePathError::Absolute));
+    assert_eq!(BrowsePath::segments("\\\\server\\share"), Err(BrowsePathError::Absolute));
+    assert_eq!(request("%2Fetc%2Fpasswd").path_segments(), Err(BrowsePathError::Absolute));
+}
+
+#[test]
+fn browse_path_rejects_backslash_segments() {
+    assert_eq!(
+        BrowsePath::segments("2026/..\\secrets"),
+        Err(BrowsePathError::InvalidSegment("..\\secrets".to_string()))
+    );
+}
+
+#[test]
+fn browse_path_keeps_regular_segments() {
+    assert_eq!(
+        BrowsePath::segments("2026/Fujifilm X100V/2026-01-25/").unwrap(),
+        vec!["2026".to_string(), "Fujifilm X100V".to_string(), "2026-01-25".to_string()]
+    );
+    assert!(BrowsePath::segments("").unwrap().is_empty());
+}
+
+#[test]
+fn storage_path_guard_resolves_inside_root() {
+    let root = fixture_root("inside");
+    let guard = StoragePathGuard::default();
+
+    let resolved = guard.resolve(&root, "2026/trip").unwrap();
+
+    assert_eq!(resolved, root.canonicalize().unwrap().join("2026").join("trip"));
+    assert_eq!(guard.resolve(&root, "2026/../..").unwrap_err(), BrowsePathError::Traversal);
+    assert_eq!(guard.resolve(&root, "missing").unwrap_err(), BrowsePathError::NotFound);
+    let _ = fs::remove_dir_all(root);
+}
+
+#[cfg(unix)]
+#[test]
+fn storage_path_guard_rejects_symlinks_escaping_root() {
+    let root = fixture_root("symlink");
+    let outside = fixture_root("outside");
+    std::os::unix::fs::symlink(&outside, root.join("escape")).expect("symlink should be created");
+    std::os::unix::fs::symlink(root.join("2026"), root.join("inside")).expect("symlink should be created");
+
+    let strict = StoragePathGuard::new(false);
+    let relaxed = StoragePathGuard::new(true);
+
+    assert_eq!(strict.resolve(&root, "escape/2026").unwrap_err(), BrowsePathError::OutsideRoot);
+    assert_eq!(strict.resolve(&root, "inside/trip").unwrap(), root.canonicalize().unwrap().join("2026").join("trip"));
+    assert_eq!(relaxed.resolve(&root, "escape/2026").unwrap(), outside.canonicalize().unwrap().join("2026"));
+    let _ = fs::remove_dir_all(root);
+    let _ = fs::remove_dir_all(outside);
+}
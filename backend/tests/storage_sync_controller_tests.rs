@@ -1,5 +1,6 @@
 use nimble_photos::controllers::storage_controller::StorageController;
 use nimble_photos::entities::{ExifModel, Photo, StorageLocation};
+use nimble_photos::models::AppConfig;
 use nimble_photos::services::{
     BackgroundTaskRunner, EventBusService, ExifService, FileService, HashService, ImageProcessPipeline,
     ImageProcessPipelineContext, PhotoUploadService, PreviewExtractor, SyncService, ThumbnailExtractor,
@@ -57,6 +58,7 @@ fn build_app(storage_seed: Vec<StorageLocation>, photo_seed: Vec<Photo>, exif_se
         let configuration = provider.get::<nimble_web::Configuration>().as_ref().clone();
         ImageProcessPipeline::new(ImageProcessPipelineContext::new(provider, configuration))
     });
+    builder.register_singleton(|_| AppConfig::default());
     builder.register_singleton(|provider| SyncService::new(provider));
 
     builder.build()
@@ -121,6 +123,11 @@ fn sample_photo(storage_id: Uuid, hash: &str, size: i64) -> Photo {
         orientation: None,
         day_date: chrono::NaiveDate::from_ymd_opt(2026, 1, 1).expect("date"),
         sort_date: chrono::Utc::now(),
+        title: None,
+        caption: None,
+        stack_id: None,
+        blurhash: None,
+        stack_count: None,
     }
 }
 
@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+use nimble_photos::entities::migrate_entities;
+use nimble_web::{AppBuilder, PostgresProvider, Repository};
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+use uuid::Uuid;
+
+// Each harness owns a throwaway schema so tests can run in parallel against one DATABASE_URL.
+pub struct PgTestDatabase {
+    pub pool: PgPool,
+    admin: PgPool,
+    schema: String,
+}
+
+impl PgTestDatabase {
+    pub const SCHEMA_PREFIX: &'static str = "nimble_test_";
+
+    pub async fn create() -> Option<Self> {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            eprintln!("DATABASE_URL is not set, skipping Postgres repository test");
+            return None;
+        };
+
+        let admin = PgPool::connect(&url).await.expect("DATABASE_URL should be reachable");
+        let schema = format!("{}{}", Self::SCHEMA_PREFIX, Uuid::new_v4().simple());
+        sqlx::query(&format!("CREATE SCHEMA {schema}")).execute(&admin).await.expect("test schema should be created");
+
+        let search_path = format!("SET search_path TO {schema}, public");
+        let pool = PgPoolOptions::new()
+            .max_connections(4)
+            .after_connect(move |connection, _| {
+                let search_path = search_path.clone();
+                Box::pin(async move {
+                    sqlx::query(&search_path).execute(connection).await?;
+                    Ok(())
+                })
+            })
+            .connect(&url)
+            .await
+            .expect("test pool should connect");
+
+        let database = Self { pool, admin, schema };
+        database.migrate().await;
+        Some(database)
+    }
+
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    pub fn repository<E>(&self) -> Repository<E>
+    where
+        E: nimble_web::data::postgres::PostgresEntity,
+    {
+        Repository::<E>::new(Box::new(PostgresProvider::<E>::new(self.pool.clone())))
+    }
+
+    pub async fn drop(self) {
+        self.pool.close().await;
+        sqlx::query(&format!("DROP SCHEMA IF EXISTS {} CASCADE", self.schema))
+            .execute(&self.admin)
+            .await
+            .expect("test schema should be dropped");
+    }
+
+    async fn migrate(&self) {
+        let mut builder = AppBuilder::new();
+        let pool = self.pool.clone();
+        builder.register_singleton(move |_| pool.clone());
+        let app = builder.build();
+        migrate_entities(&app).await.expect("entities should migrate into the test schema");
+    }
+}
@@ -0,0 +1,20 @@
+use nimble_photos::models::TagMatch;
+
+#[test]
+fn tag_match_defaults_to_any() {
+    assert_eq!(TagMatch::parse(None), Ok(TagMatch::Any));
+    assert_eq!(TagMatch::parse(Some("  ")), Ok(TagMatch::Any));
+    assert!(!TagMatch::default().requires_all());
+}
+
+#[test]
+fn tag_match_parses_all_case_insensitively() {
+    assert_eq!(TagMatch::parse(Some("ALL")), Ok(TagMatch::All));
+    assert_eq!(TagMatch::parse(Some("any")), Ok(TagMatch::Any));
+    assert!(TagMatch::All.requires_all());
+}
+
+#[test]
+fn tag_match_rejects_unknown_values() {
+    assert_eq!(TagMatch::parse(Some("some")), Err(TagMatch::INVALID_MATCH));
+}
@@ -0,0 +1,51 @@
+use nimble_photos::dtos::{TagRef, TagUpdateMode, UpdatePhotoTagsPayload};
+use nimble_photos::entities::Tag;
+use uuid::Uuid;
+
+#[test]
+fn tag_ref_parses_uuid_strings_as_ids() {
+    let id = Uuid::new_v4();
+
+    match TagRef::parse(&format!(" {} ", id)) {
+        TagRef::Id(parsed) => assert_eq!(parsed, id),
+        other => panic!("expected id, got {:?}", other),
+    }
+}
+
+#[test]
+fn tag_ref_treats_other_values_as_names() {
+    match TagRef::parse("  Sunset ") {
+        TagRef::Name(name) => assert_eq!(name, "Sunset"),
+        other => panic!("expected name, got {:?}", other),
+    }
+    assert!(matches!(TagRef::parse("42"), TagRef::Name(_)));
+}
+
+#[test]
+fn tag_new_normalizes_name() {
+    let tag = Tag::new("  Beach Trip ", Tag::VISIBILITY_HIDDEN);
+
+    assert_eq!(tag.name, "Beach Trip");
+    assert_eq!(tag.name_norm, "beach trip");
+    assert_eq!(tag.visibility, Tag::VISIBILITY_HIDDEN);
+}
+
+#[test]
+fn update_photo_tags_payload_defaults_to_additive_mode() {
+    let payload: UpdatePhotoTagsPayload =
+        serde_json::from_str(r#"{"photoIds":["a"],"tags":["vacation"]}"#).expect("payload should parse");
+
+    assert_eq!(payload.mode, TagUpdateMode::Add);
+}
+
+#[test]
+fn update_photo_tags_payload_reads_explicit_mode() {
+    let remove: UpdatePhotoTagsPayload =
+        serde_json::from_str(r#"{"photoIds":[],"tags":[],"mode":"remove"}"#).expect("payload should parse");
+    let replace: UpdatePhotoTagsPayload =
+        serde_json::from_str(r#"{"photoIds":[],"tags":[],"mode":"replace"}"#).expect("payload should parse");
+
+    assert_eq!(remove.mode, TagUpdateMode::Remove);
+    assert_eq!(replace.mode, TagUpdateMode::Replace);
+    assert!(serde_json::from_str::<UpdatePhotoTagsPayload>(r#"{"photoIds":[],"tags":[],"mode":"wipe"}"#).is_err());
+}
@@ -0,0 +1,245 @@
+#![cfg(feature = "postgres")]
+
+use std::collections::HashSet;
+
+use nimble_photos::dtos::TagRef;
+use nimble_photos::entities::{Photo, Tag, ensure_supporting_schema};
+use nimble_photos::repositories::{PhotoRepositoryExtensions, TagRepositoryExtensions};
+use nimble_web::{PostgresProvider, Repository};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn setup() -> Option<(PgPool, Repository<Photo>, Repository<Tag>)> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    let pool = PgPool::connect(&url).await.ok()?;
+    ensure_supporting_schema(&pool).await.ok()?;
+
+    let photos = Repository::<Photo>::new(Box::new(PostgresProvider::<Photo>::new(pool.clone())));
+    let tags = Repository::<Tag>::new(Box::new(PostgresProvider::<Tag>::new(pool.clone())));
+    Some((pool, photos, tags))
+}
+
+async fn insert_photo(photos: &Repository<Photo>) -> Photo {
+    let id = Uuid::new_v4();
+    let photo = Photo { id, name: format!("{}.jpg", id), path: format!("tag-tests/{}.jpg", id), ..Photo::default() };
+    photos.insert(photo.clone()).await.expect("photo should be inserted");
+    photo
+}
+
+fn tag_names(tags: &[Tag]) -> Vec<String> {
+    tags.iter().map(|tag| tag.name.clone()).collect()
+}
+
+#[tokio::test]
+async fn set_photo_tags_by_name_creates_tags_and_reads_them_back() {
+    let Some((_pool, photos, tags)) = setup().await else {
+        return;
+    };
+    let photo = insert_photo(&photos).await;
+    let sunset = format!("Sunset {}", Uuid::new_v4());
+
+    tags.set_photo_tags(photo.id, &[TagRef::Name(sunset.clone()), TagRef::Name(sunset.to_uppercase())], false)
+        .await
+        .expect("tags should be set by name");
+    let assigned = tags.get_photo_tags(photo.id).await.expect("photo tags should load");
+
+    assert_eq!(tag_names(&assigned), vec![sunset]);
+    photos.delete(&photo.id).await.expect("photo should be deleted");
+}
+
+#[tokio::test]
+async fn set_photo_tags_by_id_reuses_existing_tags_and_skips_unknown_ids() {
+    let Some((_pool, photos, tags)) = setup().await else {
+        return;
+    };
+    let first = insert_photo(&photos).await;
+    let second = insert_photo(&photos).await;
+    let beach = format!("Beach {}", Uuid::new_v4());
+    let hike = format!("Hike {}", Uuid::new_v4());
+
+    tags.set_photo_tags(first.id, &[TagRef::Name(beach.clone())], false).await.expect("tags should be set by name");
+    let beach_id = tags.get_photo_tags(first.id).await.expect("photo tags should load")[0].id;
+
+    tags.set_photo_tags(
+        second.id,
+        &[TagRef::Id(beach_id), TagRef::Id(Uuid::new_v4()), TagRef::Name(hike.clone())],
+        false,
+    )
+    .await
+    .expect("tags should be set by id");
+    let assigned = tags.get_photo_tags(second.id).await.expect("photo tags should load");
+    let by_id = tags.get_tags_by_ids(&[beach_id]).await.expect("tags should load by id");
+
+    assert_eq!(tag_names(&assigned), vec![beach.clone(), hike]);
+    assert_eq!(tag_names(&by_id), vec![beach]);
+    photos.delete(&first.id).await.expect("photo should be deleted");
+    photos.delete(&second.id).await.expect("photo should be deleted");
+}
+
+#[tokio::test]
+async fn bulk_add_and_remove_keep_unrelated_tags() {
+    let Some((_pool, photos, tags)) = setup().await else {
+        return;
+    };
+    let first = insert_photo(&photos).await;
+    let second = insert_photo(&photos).await;
+    let family = format!("Family {}", Uuid::new_v4());
+    let vacation = format!("Vacation {}", Uuid::new_v4());
+
+    tags.set_photo_tags(first.id, &[TagRef::Name(family.clone())], false).await.expect("tags should be set by name");
+    let vacation_ids =
+        tags.resolve_tag_ids(&[TagRef::Name(vacation.clone())], Tag::VISIBILITY_PUBLIC).await.expect("tag resolves");
+    let photo_ids = photos
+        .get_by_ids(&[first.id, second.id, Uuid::new_v4()])
+        .await
+        .expect("photos should load")
+        .into_iter()
+        .map(|photo| photo.id)
+        .collect::<Vec<_>>();
+
+    photos.add_photo_tags_bulk(&photo_ids, &vacation_ids).await.expect("bulk add should succeed");
+    let first_after_add = tags.get_photo_tags(first.id).await.expect("photo tags should load");
+    let second_after_add = tags.get_photo_tags(second.id).await.expect("photo tags should load");
+
+    photos.remove_photo_tags_bulk(&photo_ids, &vacation_ids).await.expect("bulk remove should succeed");
+    let first_after_remove = tags.get_photo_tags(first.id).await.expect("photo tags should load");
+
+    assert_eq!(photo_ids.len(), 2);
+    assert_eq!(tag_names(&first_after_add), vec![family.clone(), vacation.clone()]);
+    assert_eq!(tag_names(&second_after_add), vec![vacation]);
+    assert_eq!(tag_names(&first_after_remove), vec![family]);
+    photos.delete(&first.id).await.expect("photo should be deleted");
+    photos.delete(&second.id).await.expect("photo should be deleted");
+}
+
+#[tokio::test]
+async fn non_admin_cannot_detach_or_attach_hidden_tags() {
+    let Some((_pool, photos, tags)) = setup().await else {
+        return;
+    };
+    let photo = insert_photo(&photos).await;
+    let hidden = Tag::new(&format!("Private {}", Uuid::new_v4()), Tag::VISIBILITY_HIDDEN);
+    tags.insert(hidden.clone()).await.expect("hidden tag should be inserted");
+    let public = format!("Public {}", Uuid::new_v4());
+
+    tags.set_photo_tags(photo.id, &[TagRef::Id(hidden.id)], true).await.expect("admin may attach hidden tags");
+    let explicit = tags.set_photo_tags(photo.id, &[TagRef::Id(hidden.id)], false).await;
+    let hidden_blocked = tags.includes_hidden_tags(&[hidden.id]).await.expect("hidden tag check should run");
+    let public_ids =
+        tags.resolve_tag_ids(&[TagRef::Name(public.clone())], Tag::VISIBILITY_PUBLIC).await.expect("tag resolves");
+    let public_allowed = !tags.includes_hidden_tags(&public_ids).await.expect("hidden tag check should run");
+
+    assert!(explicit.is_err_and(|e| format!("{:?}", e).contains(Tag::HIDDEN_TAG_FORBIDDEN)));
+    assert!(hidden_blocked);
+    assert!(public_allowed);
+
+    tags.set_photo_tags(photo.id, &[TagRef::Name(public.clone())], true).await.expect("admin may detach hidden tags");
+    let after_admin = tags.get_photo_tags(photo.id).await.expect("photo tags should load");
+    assert_eq!(tag_names(&after_admin), vec![public]);
+    photos.delete(&photo.id).await.expect("photo should be deleted");
+    tags.delete(&hidden.id).await.expect("hidden tag should be deleted");
+}
+
+#[tokio::test]
+async fn non_admin_replace_keeps_hidden_tags_attached() {
+    let Some((_pool, photos, tags)) = setup().await else {
+        return;
+    };
+    let first = insert_photo(&photos).await;
+    let second = insert_photo(&photos).await;
+    let hidden = Tag::new(&format!("Private {}", Uuid::new_v4()), Tag::VISIBILITY_HIDDEN);
+    tags.insert(hidden.clone()).await.expect("hidden tag should be inserted");
+    let beach = format!("Beach {}", Uuid::new_v4());
+    let public = format!("Public {}", Uuid::new_v4());
+
+    for photo in [&first, &second] {
+        tags.set_photo_tags(photo.id, &[TagRef::Id(hidden.id), TagRef::Name(beach.clone())], true)
+            .await
+            .expect("admin may attach hidden tags");
+    }
+    tags.set_photo_tags(first.id, &[TagRef::Name(public.clone())], false).await.expect("non-admin replace succeeds");
+    let public_ids =
+        tags.resolve_tag_ids(&[TagRef::Name(public.clone())], Tag::VISIBILITY_PUBLIC).await.expect("tag resolves");
+    photos.replace_photo_tags_bulk(&[second.id], &public_ids, true).await.expect("bulk replace succeeds");
+    let first_after = tags.get_photo_tags(first.id).await.expect("photo tags should load");
+    let second_after = tags.get_photo_tags(second.id).await.expect("photo tags should load");
+
+    assert_eq!(tag_names(&first_after), vec![hidden.name.clone(), public.clone()]);
+    assert_eq!(tag_names(&second_after), vec![hidden.name.clone(), public]);
+    photos.delete(&first.id).await.expect("photo should be deleted");
+    photos.delete(&second.id).await.expect("photo should be deleted");
+    tags.delete(&hidden.id).await.expect("hidden tag should be deleted");
+}
+
+#[tokio::test]
+async fn hidden_copies_hide_every_photo_sharing_the_hash_from_non_admins() {
+    let Some((_pool, photos, tags)) = setup().await else {
+        return;
+    };
+    let hash = Uuid::new_v4().simple().to_string();
+    let mut copies = Vec::new();
+    for _ in 0..2 {
+        let id = Uuid::new_v4();
+        let photo = Photo {
+            id,
+            hash: Some(hash.clone()),
+            name: format!("{}.jpg", id),
+            path: format!("tag-tests/{}.jpg", id),
+            ..Photo::default()
+        };
+        photos.insert(photo.clone()).await.expect("photo should be inserted");
+        copies.push(photo);
+    }
+
+    let before = photos.find_visible_by_hash(&hash, false).await.expect("visible photos should load");
+    let hidden = Tag::new(&format!("Private {}", Uuid::new_v4()), Tag::VISIBILITY_HIDDEN);
+    tags.insert(hidden.clone()).await.expect("hidden tag should be inserted");
+    tags.set_photo_tags(copies[0].id, &[TagRef::Id(hidden.id)], true).await.expect("admin may attach hidden tags");
+    let viewer = photos.find_visible_by_hash(&hash, false).await.expect("visible photos should load");
+    let admin = photos.find_visible_by_hash(&hash, true).await.expect("all photos should load");
+    let viewer_locations =
+        photos.find_hash_locations(std::slice::from_ref(&hash), false).await.expect("locations should load");
+    let admin_locations =
+        photos.find_hash_locations(std::slice::from_ref(&hash), true).await.expect("locations should load");
+
+    assert_eq!(before.len(), 2);
+    assert!(viewer.is_empty());
+    assert_eq!(admin.len(), 2);
+    assert!(viewer_locations.is_empty());
+    assert_eq!(admin_locations.len(), 1);
+    for photo in copies {
+        photos.delete(&photo.id).await.expect("photo should be deleted");
+    }
+    tags.delete(&hidden.id).await.expect("hidden tag should be deleted");
+}
+
+#[tokio::test]
+async fn random_photos_skip_admin_only_photos_for_non_admins() {
+    let Some((_pool, photos, tags)) = setup().await else {
+        return;
+    };
+    let id = Uuid::new_v4();
+    let photo = Photo {
+        id,
+        hash: Some(id.simple().to_string()),
+        name: format!("{}.jpg", id),
+        path: format!("tag-tests/{}.jpg", id),
+        ..Photo::default()
+    };
+    photos.insert(photo.clone()).await.expect("photo should be inserted");
+    let hidden = Tag::new(&format!("Private {}", Uuid::new_v4()), Tag::VISIBILITY_HIDDEN);
+    let marker = format!("Marker {}", Uuid::new_v4());
+    tags.insert(hidden.clone()).await.expect("hidden tag should be inserted");
+    tags.set_photo_tags(photo.id, &[TagRef::Id(hidden.id), TagRef::Name(marker.clone())], true)
+        .await
+        .expect("admin may attach hidden tags");
+
+    let filter = [marker];
+    let viewer = photos.random_photos(50, &filter, &HashSet::new(), false).await.expect("random photos should load");
+    let admin = photos.random_photos(50, &filter, &HashSet::new(), true).await.expect("random photos should load");
+
+    assert!(viewer.is_empty());
+    assert_eq!(admin.iter().map(|random| random.id).collect::<Vec<_>>(), vec![photo.id]);
+    photos.delete(&photo.id).await.expect("photo should be deleted");
+    tags.delete(&hidden.id).await.expect("hidden tag should be deleted");
+}
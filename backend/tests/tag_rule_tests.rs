@@ -0,0 +1,75 @@
+use nimble_photos::dtos::CreateTagRuleRequest;
+use nimble_photos::entities::{ExifModel, Photo, TagRule};
+use nimble_photos::models::{TagRuleField, TagRuleOperator};
+use nimble_photos::repositories::Validate;
+
+fn rule(field: TagRuleField, operator: TagRuleOperator, value: &str, tag: &str) -> TagRule {
+    TagRule::new(field, operator, value, tag, None, false)
+}
+
+fn request(field: &str, operator: &str, value: &str) -> CreateTagRuleRequest {
+    CreateTagRuleRequest {
+        field: field.to_string(),
+        operator: operator.to_string(),
+        value: value.to_string(),
+        tag: "drone".to_string(),
+    }
+}
+
+#[test]
+fn text_rules_match_case_insensitively() {
+    let photo = Photo { make: Some("DJI".to_string()), model: Some("Mavic 3 Pro".to_string()), ..Photo::default() };
+
+    assert!(rule(TagRuleField::Make, TagRuleOperator::Equals, "dji", "drone").matches(&photo, None));
+    assert!(rule(TagRuleField::Model, TagRuleOperator::Contains, "MAVIC", "drone").matches(&photo, None));
+    assert!(rule(TagRuleField::Model, TagRuleOperator::StartsWith, "mavic", "drone").matches(&photo, None));
+    assert!(!rule(TagRuleField::Make, TagRuleOperator::NotEquals, "dji", "drone").matches(&photo, None));
+}
+
+#[test]
+fn numeric_rules_compare_values() {
+    let wildlife = rule(TagRuleField::FocalLength, TagRuleOperator::GreaterOrEqual, "300", "wildlife");
+
+    assert!(wildlife.matches(&Photo { focal_length: Some(300.0), ..Photo::default() }, None));
+    assert!(wildlife.matches(&Photo { focal_length: Some(600.0), ..Photo::default() }, None));
+    assert!(!wildlife.matches(&Photo { focal_length: Some(70.0), ..Photo::default() }, None));
+}
+
+#[test]
+fn missing_values_never_match() {
+    let photo = Photo::default();
+
+    assert!(!rule(TagRuleField::Make, TagRuleOperator::NotEquals, "canon", "other").matches(&photo, None));
+    assert!(!rule(TagRuleField::FocalLength35mm, TagRuleOperator::GreaterThan, "0", "x").matches(&photo, None));
+}
+
+#[test]
+fn exif_only_fields_read_the_exif_record() {
+    let wide = rule(TagRuleField::FocalLength35mm, TagRuleOperator::LessOrEqual, "24", "wide");
+    let exif = ExifModel { focal_length_in_35mm_film: Some(16), ..ExifModel::default() };
+
+    assert!(wide.needs_exif());
+    assert!(wide.matches(&Photo::default(), Some(&exif)));
+}
+
+#[test]
+fn operators_accept_symbols_and_names() {
+    assert_eq!(TagRuleOperator::parse(">="), Some(TagRuleOperator::GreaterOrEqual));
+    assert_eq!(TagRuleOperator::parse("startsWith"), Some(TagRuleOperator::StartsWith));
+    assert_eq!(TagRuleField::parse("focallength"), Some(TagRuleField::FocalLength));
+    assert_eq!(TagRuleOperator::parse("like"), None);
+}
+
+#[test]
+fn requests_are_validated_against_the_field_type() {
+    assert!(request("make", "eq", "DJI").validate().is_ok());
+    assert!(request("focalLength", ">=", "300").validate().is_ok());
+    assert!(request("isRaw", "eq", "true").validate().is_ok());
+
+    assert!(request("shutter", "eq", "1").validate().is_err());
+    assert!(request("focalLength", "contains", "300").validate().is_err());
+    assert!(request("focalLength", "gte", "long").validate().is_err());
+    assert!(request("make", "gt", "DJI").validate().is_err());
+    assert!(request("isRaw", "gt", "true").validate().is_err());
+    assert!(request("make", "eq", " ").validate().is_err());
+}
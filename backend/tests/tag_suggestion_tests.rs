@@ -0,0 +1,45 @@
+use nimble_photos::dtos::AcceptTagSuggestionsPayload;
+use nimble_photos::models::TagSuggestion;
+use nimble_photos::repositories::Validate;
+use nimble_photos::services::HttpImageAnalyzer;
+
+#[test]
+fn normalize_keeps_best_score_per_name() {
+    let suggestions = vec![
+        TagSuggestion::new(" Dog ", 0.7),
+        TagSuggestion::new("dog", 0.9),
+        TagSuggestion::new("Beach", 1.4),
+        TagSuggestion::new("Cat", 0.2),
+        TagSuggestion::new("  ", 0.99),
+        TagSuggestion::new("Tree", f32::NAN),
+    ];
+
+    let normalized = TagSuggestion::normalize(suggestions, 0.5);
+    assert_eq!(normalized, vec![TagSuggestion::new("Beach", 1.0), TagSuggestion::new("Dog", 0.9)]);
+}
+
+#[test]
+fn analyzer_response_accepts_list_and_wrapped_forms() {
+    let list = HttpImageAnalyzer::parse_response(
+        br#"[{ "name": "dog", "confidence": 0.8 }, { "label": "sky", "score": 0.6 }]"#,
+    )
+    .unwrap();
+    assert_eq!(list, vec![TagSuggestion::new("dog", 0.8), TagSuggestion::new("sky", 0.6)]);
+
+    let wrapped = HttpImageAnalyzer::parse_response(br#"{ "tags": [{ "tag": "beach", "confidence": 0.5 }] }"#).unwrap();
+    assert_eq!(wrapped, vec![TagSuggestion::new("beach", 0.5)]);
+
+    assert!(HttpImageAnalyzer::parse_response(b"<html>").is_err());
+}
+
+#[test]
+fn accept_payload_requires_a_tag() {
+    let payload: AcceptTagSuggestionsPayload = serde_json::from_str(r#"{ "tags": ["dog"] }"#).unwrap();
+    assert!(payload.validate().is_ok());
+
+    let blank: AcceptTagSuggestionsPayload = serde_json::from_str(r#"{ "tags": [" "] }"#).unwrap();
+    assert!(blank.validate().is_err());
+
+    let empty: AcceptTagSuggestionsPayload = serde_json::from_str("{}").unwrap();
+    assert!(empty.validate().is_err());
+}
@@ -0,0 +1,141 @@
+use chrono::{TimeZone, Utc};
+use nimble_photos::dtos::FolderImportRequest;
+use nimble_photos::entities::PipelineJob;
+use nimble_photos::models::{ImportFormat, TakeoutAlbum, TakeoutSidecar};
+use std::fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+const FULL_SIDECAR: &str = r#"{
+  "title": "IMG_20200913_122639.jpg",
+  "description": "  Picnic at the lake  ",
+  "imageViews": "12",
+  "creationTime": { "timestamp": "1600100000", "formatted": "Sep 14, 2020, 4:13:20 PM UTC" },
+  "photoTakenTime": { "timestamp": "1600000000", "formatted": "Sep 13, 2020, 12:26:40 PM UTC" },
+  "geoData": { "latitude": 47.6062, "longitude": -122.3321, "altitude": 52.5, "latitudeSpan": 0.0, "longitudeSpan": 0.0 },
+  "geoDataExif": { "latitude": 0.0, "longitude": 0.0, "altitude": 0.0, "latitudeSpan": 0.0, "longitudeSpan": 0.0 },
+  "people": [{ "name": "Alice" }, { "name": "Bob" }, { "name": "alice" }, { "name": " " }],
+  "url": "https://photos.google.com/photo/abc",
+  "googlePhotosOrigin": { "mobileUpload": { "deviceType": "ANDROID_PHONE" } },
+  "favorited": true
+}"#;
+
+const SPARSE_SIDECAR: &str = r#"{
+  "title": "scan.png",
+  "description": "",
+  "photoTakenTime": { "timestamp": "0" },
+  "geoData": { "latitude": 0.0, "longitude": 0.0, "altitude": 0.0 },
+  "geoDataExif": { "latitude": -33.8688, "longitude": 151.2093, "altitude": 0.0 }
+}"#;
+
+const ALBUM_METADATA: &str = r#"{
+  "title": " Summer 2020 ",
+  "description": "Road trip",
+  "access": "protected",
+  "date": { "timestamp": "1600000000" }
+}"#;
+
+fn fixture_folder(files: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("nimble-takeout-{}", Uuid::new_v4()));
+    fs::create_dir_all(&root).expect("fixture directory should be created");
+    for (name, content) in files {
+        fs::write(root.join(name), content).expect("fixture file should be written");
+    }
+    root
+}
+
+#[test]
+fn parses_full_sidecar_and_ignores_unknown_fields() {
+    let sidecar = TakeoutSidecar::parse(FULL_SIDECAR.as_bytes()).expect("sidecar should parse");
+
+    assert_eq!(sidecar.date_taken(), Some(Utc.with_ymd_and_hms(2020, 9, 13, 12, 26, 40).unwrap()));
+    assert_eq!(sidecar.caption().as_deref(), Some("Picnic at the lake"));
+    assert_eq!(sidecar.people(), vec!["Alice".to_string(), "Bob".to_string()]);
+
+    let location = sidecar.location().expect("location should be present");
+    assert_eq!((location.latitude, location.longitude, location.altitude), (47.6062, -122.3321, Some(52.5)));
+}
+
+#[test]
+fn sparse_sidecar_skips_zero_values_and_falls_back_to_exif_geo_data() {
+    let sidecar = TakeoutSidecar::parse(SPARSE_SIDECAR.as_bytes()).expect("sidecar should parse");
+
+    assert_eq!(sidecar.date_taken(), None);
+    assert_eq!(sidecar.caption(), None);
+    assert!(sidecar.people().is_empty());
+
+    let location = sidecar.location().expect("exif geo data should be used");
+    assert_eq!((location.latitude, location.longitude, location.altitude), (-33.8688, 151.2093, None));
+}
+
+#[test]
+fn numeric_timestamps_are_accepted() {
+    let sidecar = TakeoutSidecar::parse(br#"{ "photoTakenTime": { "timestamp": 1600000000 } }"#).unwrap();
+
+    assert_eq!(sidecar.date_taken(), Some(Utc.with_ymd_and_hms(2020, 9, 13, 12, 26, 40).unwrap()));
+}
+
+#[test]
+fn candidate_names_cover_duplicates_edits_and_truncation() {
+    let names = TakeoutSidecar::candidate_names("IMG_0001(2).jpg");
+    assert!(names.contains(&"IMG_0001(2).jpg.json".to_string()));
+    assert!(names.contains(&"IMG_0001.jpg(2).json".to_string()));
+
+    let names = TakeoutSidecar::candidate_names("IMG_0001-edited.jpg");
+    assert!(names.contains(&"IMG_0001.jpg.json".to_string()));
+    assert!(names.contains(&"IMG_0001.jpg.supplemental-metadata.json".to_string()));
+
+    let long = format!("{}.jpg", "a".repeat(60));
+    let names = TakeoutSidecar::candidate_names(&long);
+    assert!(names.contains(&format!("{}.json", "a".repeat(46))));
+}
+
+#[test]
+fn load_finds_sidecar_next_to_image() {
+    let folder = fixture_folder(&[
+        ("IMG_0001.jpg", "photo"),
+        ("IMG_0001.jpg.supplemental-metadata.json", FULL_SIDECAR),
+        ("IMG_0002.jpg", "photo"),
+        ("IMG_0003.jpg", "photo"),
+        ("IMG_0003.jpg.json", "{ not json"),
+    ]);
+
+    let sidecar = TakeoutSidecar::load(&folder.join("IMG_0001.jpg")).expect("sidecar should load");
+    assert_eq!(sidecar.caption().as_deref(), Some("Picnic at the lake"));
+    assert!(TakeoutSidecar::load(&folder.join("IMG_0002.jpg")).is_none());
+    assert!(TakeoutSidecar::load(&folder.join("IMG_0003.jpg")).is_none());
+}
+
+#[test]
+fn album_metadata_is_read_from_the_image_folder() {
+    let album_folder = fixture_folder(&[("metadata.json", ALBUM_METADATA), ("IMG_0001.jpg", "photo")]);
+    let year_folder = fixture_folder(&[("IMG_0001.jpg", "photo")]);
+
+    let album = TakeoutAlbum::load(&album_folder.join("IMG_0001.jpg")).expect("album should load");
+    assert_eq!(album.title, "Summer 2020");
+    assert_eq!(album.description.as_deref(), Some("Road trip"));
+    assert!(TakeoutAlbum::load(&year_folder.join("IMG_0001.jpg")).is_none());
+    assert!(TakeoutAlbum::parse(br#"{ "title": "  " }"#).is_none());
+}
+
+#[test]
+fn import_request_accepts_takeout_format() {
+    let request: FolderImportRequest = serde_json::from_str(r#"{ "path": "Takeout", "format": "takeout" }"#).unwrap();
+    assert_eq!(request.format, ImportFormat::Takeout);
+
+    let request: FolderImportRequest = serde_json::from_str(r#"{ "path": "2019" }"#).unwrap();
+    assert_eq!(request.format, ImportFormat::Native);
+}
+
+#[test]
+fn pipeline_job_round_trips_import_format() {
+    let job = PipelineJob::queued(Uuid::new_v4(), "Takeout/a.jpg", "a.jpg", 5, None)
+        .with_import_format(ImportFormat::Takeout);
+    assert_eq!(job.import_format.as_deref(), Some("takeout"));
+    assert_eq!(job.format(), ImportFormat::Takeout);
+
+    let native =
+        PipelineJob::queued(Uuid::new_v4(), "a.jpg", "a.jpg", 5, None).with_import_format(ImportFormat::Native);
+    assert_eq!(native.import_format, None);
+    assert_eq!(native.format(), ImportFormat::Native);
+}
@@ -0,0 +1,29 @@
+use nimble_photos::controllers::task_controller::TaskController;
+use nimble_web::Controller;
+use nimble_web::Policy;
+
+#[test]
+fn routes_require_authenticated() {
+    let routes = TaskController::routes();
+    assert_eq!(routes.len(), 4);
+
+    let list_route = &routes[0];
+    assert_eq!(list_route.route.method(), "GET");
+    assert_eq!(list_route.route.path(), "/api/admin/tasks");
+    assert_eq!(list_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+
+    let cancel_route = &routes[1];
+    assert_eq!(cancel_route.route.method(), "DELETE");
+    assert_eq!(cancel_route.route.path(), "/api/admin/tasks/{id}");
+    assert_eq!(cancel_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+
+    let relocate_route = &routes[2];
+    assert_eq!(relocate_route.route.method(), "POST");
+    assert_eq!(relocate_route.route.path(), "/api/admin/tasks/relocate-assets");
+    assert_eq!(relocate_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+
+    let blurhash_route = &routes[3];
+    assert_eq!(blurhash_route.route.method(), "POST");
+    assert_eq!(blurhash_route.route.path(), "/api/admin/tasks/backfill-blurhash");
+    assert_eq!(blurhash_route.endpoint.metadata().policy(), Some(&Policy::Authenticated));
+}
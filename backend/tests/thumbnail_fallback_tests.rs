@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use nimble_photos::controllers::photo_controller::PhotoController;
+use nimble_photos::entities::{Photo, StorageLocation};
+use nimble_photos::services::{FileService, PreviewCoordinator, ThumbnailExtractor};
+use nimble_web::AuthenticationMiddleware;
+use nimble_web::Claims;
+use nimble_web::Configuration;
+use nimble_web::ControllerInvokerMiddleware;
+use nimble_web::DefaultRouter;
+use nimble_web::EndpointExecutionMiddleware;
+use nimble_web::EndpointRegistry;
+use nimble_web::HttpContext;
+use nimble_web::HttpRequest;
+use nimble_web::MemoryRepository;
+use nimble_web::Pipeline;
+use nimble_web::Repository;
+use nimble_web::Router;
+use nimble_web::RoutingMiddleware;
+use nimble_web::ServiceContainer;
+use nimble_web::UserIdentity;
+use nimble_web::{JwtTokenService, TokenService};
+
+const HASH: &str = "abcdef0123456789";
+
+fn storage(root: &Path) -> StorageLocation {
+    StorageLocation {
+        id: Uuid::new_v4(),
+        label: "Primary".to_string(),
+        path: root.to_string_lossy().to_string(),
+        is_default: true,
+        is_readonly: false,
+        created_at: "2026-02-17T00:00:00Z".to_string(),
+        category_template: "{year}/{date:%Y-%m-%d}/{fileName}".to_string(),
+    }
+}
+
+fn thumbnail_path(root: &Path) -> PathBuf {
+    root.join(".thumbnails").join(&HASH[0..2]).join(&HASH[2..4]).join(format!("{}.webp", HASH))
+}
+
+fn photo(storage: &StorageLocation, root: &Path) -> Photo {
+    Photo {
+        storage_id: storage.id,
+        hash: Some(HASH.to_string()),
+        path: root.join("missing.jpg").to_string_lossy().to_string(),
+        name: "missing.jpg".to_string(),
+        ..Photo::default()
+    }
+}
+
+// Admins skip the hidden-tag SQL, which the in-memory repository cannot run.
+fn request_thumbnail(storage: StorageLocation, photos: Vec<Photo>) -> u16 {
+    let mut registry = EndpointRegistry::new();
+    registry.register::<PhotoController>();
+
+    let mut router = DefaultRouter::new();
+    for route in registry.routes() {
+        router.add_route(route.clone());
+    }
+
+    let storage_id = storage.id;
+    let storage_repo = MemoryRepository::<StorageLocation>::new();
+    storage_repo.seed(vec![storage]);
+    let photo_repo = MemoryRepository::<Photo>::new();
+    photo_repo.seed(photos);
+
+    let mut container = ServiceContainer::new();
+    container
+        .register_singleton::<Repository<StorageLocation>, _>(move |_| Repository::new(Box::new(storage_repo.clone())));
+    container.register_singleton::<Repository<Photo>, _>(move |_| Repository::new(Box::new(photo_repo.clone())));
+    container.register_singleton::<FileService, _>(|_| FileService::new());
+    container.register_singleton::<ThumbnailExtractor, _>(|_| ThumbnailExtractor::new());
+    container.register_singleton::<PreviewCoordinator, _>(|_| PreviewCoordinator::new());
+    container.register_singleton::<Arc<dyn TokenService>, _>(move |_| {
+        let service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+        Arc::new(service) as Arc<dyn TokenService>
+    });
+    let services = container.build();
+
+    let token_service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+    let identity = UserIdentity::new(Uuid::new_v4().to_string(), Claims::new().add_role("admin"));
+    let token = TokenService::create_access_token(&token_service, &identity).unwrap();
+
+    let mut request = HttpRequest::new("GET", &format!("/api/photos/thumbnail/{}/{}", storage_id, HASH));
+    request.headers_mut().insert("authorization", format!("Bearer {}", token).as_str());
+    let mut context = HttpContext::new(request, services, Configuration::from_values(HashMap::new()));
+
+    let mut pipeline = Pipeline::new();
+    pipeline.add(RoutingMiddleware::new(router));
+    pipeline.add(AuthenticationMiddleware::new());
+    pipeline.add(ControllerInvokerMiddleware::new(Arc::new(registry)));
+    pipeline.add(EndpointExecutionMiddleware::new());
+
+    let _ = pipeline.run(&mut context);
+    context.response().status()
+}
+
+fn fixture_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("nimble-thumbnail-fallback-{}-{}", name, Uuid::new_v4()));
+    std::fs::create_dir_all(&root).expect("fixture root should be created");
+    root
+}
+
+#[test]
+fn storage_thumbnail_is_served_when_present() {
+    let root = fixture_root("present");
+    let thumb = thumbnail_path(&root);
+    std::fs::create_dir_all(thumb.parent().unwrap()).unwrap();
+    std::fs::write(&thumb, b"webp").unwrap();
+    let storage = storage(&root);
+    let photo = photo(&storage, &root);
+
+    assert_eq!(request_thumbnail(storage, vec![photo]), 200);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn storage_thumbnail_miss_without_photo_returns_not_found() {
+    let root = fixture_root("no-photo");
+
+    assert_eq!(request_thumbnail(storage(&root), Vec::new()), 404);
+    assert!(!thumbnail_path(&root).exists());
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn storage_thumbnail_miss_with_missing_source_returns_not_found() {
+    let root = fixture_root("no-source");
+    let storage = storage(&root);
+    let photo = photo(&storage, &root);
+
+    assert_eq!(request_thumbnail(storage, vec![photo]), 404);
+    assert!(!thumbnail_path(&root).exists());
+    let _ = std::fs::remove_dir_all(root);
+}
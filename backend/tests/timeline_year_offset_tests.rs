@@ -0,0 +1,134 @@
+#![cfg(feature = "postgres")]
+
+use nimble_photos::models::{TimelineGranularity, TimelineZone};
+use sqlx::PgConnection;
+use sqlx::pool::PoolConnection;
+use sqlx::{PgPool, Postgres};
+
+async fn setup_connection() -> Option<PoolConnection<Postgres>> {
+    let url = std::env::var("DATABASE_URL").ok()?;
+    let pool = PgPool::connect(&url).await.ok()?;
+    let mut connection = pool.acquire().await.ok()?;
+
+    sqlx::query("CREATE TEMP TABLE photos (day_date DATE, sort_date TIMESTAMPTZ NOT NULL)")
+        .execute(&mut *connection)
+        .await
+        .expect("temp photos table should be created");
+
+    Some(connection)
+}
+
+async fn seed(connection: &mut PgConnection, day_date: Option<&str>, sort_date: &str) {
+    sqlx::query("INSERT INTO photos (day_date, sort_date) VALUES ($1::date, $2::timestamptz)")
+        .bind(day_date)
+        .bind(sort_date)
+        .execute(connection)
+        .await
+        .expect("photo row should be inserted");
+}
+
+async fn seed_library(connection: &mut PgConnection) {
+    seed(connection, Some("2025-03-03"), "2025-03-03T10:00:00Z").await;
+    seed(connection, Some("2025-01-01"), "2025-01-01T08:00:00Z").await;
+    seed(connection, Some("2025-01-01"), "2025-01-01T09:00:00Z").await;
+    // Undated photos: created_at stands in for date_taken, so they still carry a day.
+    seed(connection, Some("2024-12-31"), "2024-12-31T20:00:00Z").await;
+    seed(connection, Some("2024-06-15"), "2024-06-15T12:00:00Z").await;
+    seed(connection, Some("2024-01-01"), "2024-01-01T00:30:00Z").await;
+    seed(connection, Some("2023-07-04"), "2023-07-04T18:00:00Z").await;
+    // Legacy row that never had its timeline day backfilled.
+    seed(connection, None, "2022-05-05T05:00:00Z").await;
+}
+
+async fn year_offset(connection: &mut PgConnection, zone: &TimelineZone, year: i64) -> i64 {
+    sqlx::query_scalar(&zone.year_offset_sql())
+        .bind(year)
+        .bind(zone.as_str())
+        .fetch_one(connection)
+        .await
+        .expect("year offset query failed")
+}
+
+async fn days_page(connection: &mut PgConnection, zone: &TimelineZone, limit: i64, offset: i64) -> Vec<String> {
+    sqlx::query_scalar(&zone.days_page_sql())
+        .bind(limit)
+        .bind(offset)
+        .bind(zone.as_str())
+        .fetch_all(connection)
+        .await
+        .expect("days page query failed")
+}
+
+#[tokio::test]
+async fn year_offset_lands_on_first_day_of_year_in_utc() {
+    let Some(mut connection) = setup_connection().await else {
+        return;
+    };
+    seed_library(&mut connection).await;
+    let zone = TimelineZone::utc();
+
+    let offset = year_offset(&mut connection, &zone, 2024).await;
+    let before = days_page(&mut connection, &zone, 1, offset - 1).await;
+    let page = days_page(&mut connection, &zone, 3, offset).await;
+
+    assert_eq!(offset, 2);
+    assert_eq!(before, vec!["2025-01-01".to_string()]);
+    assert_eq!(page, vec!["2024-12-31".to_string(), "2024-06-15".to_string(), "2024-01-01".to_string()]);
+}
+
+#[tokio::test]
+async fn year_offset_ignores_undated_legacy_rows() {
+    let Some(mut connection) = setup_connection().await else {
+        return;
+    };
+    seed_library(&mut connection).await;
+    let zone = TimelineZone::utc();
+
+    let oldest = year_offset(&mut connection, &zone, 2023).await;
+    let past_end = year_offset(&mut connection, &zone, 2020).await;
+    let all_days = days_page(&mut connection, &zone, 100, 0).await;
+
+    assert_eq!(oldest, 5);
+    assert_eq!(past_end, 6);
+    assert_eq!(all_days.len(), 6);
+    assert_eq!(all_days.last().map(String::as_str), Some("2023-07-04"));
+}
+
+#[tokio::test]
+async fn year_offset_follows_viewer_time_zone() {
+    let Some(mut connection) = setup_connection().await else {
+        return;
+    };
+    seed_library(&mut connection).await;
+    let zone = TimelineZone::from_name(Some("Asia/Tokyo"));
+
+    let offset = year_offset(&mut connection, &zone, 2024).await;
+    let before = days_page(&mut connection, &zone, 1, offset - 1).await;
+    let page = days_page(&mut connection, &zone, 1, offset).await;
+
+    assert_eq!(offset, 2);
+    assert_eq!(before, vec!["2025-01-01".to_string()]);
+    assert_eq!(page, vec!["2024-06-15".to_string()]);
+}
+
+#[tokio::test]
+async fn month_granularity_counts_months_before_the_year() {
+    let Some(mut connection) = setup_connection().await else {
+        return;
+    };
+    seed_library(&mut connection).await;
+    seed(&mut connection, Some("2025-03-10"), "2025-03-10T10:00:00Z").await;
+    seed(&mut connection, Some("2025-03-20"), "2025-03-20T10:00:00Z").await;
+    let zone = TimelineZone::utc();
+
+    let by_day = year_offset(&mut connection, &zone, 2024).await;
+    let by_month: i64 = sqlx::query_scalar(&zone.granular_year_offset_sql(TimelineGranularity::Month))
+        .bind(2024_i64)
+        .bind(zone.as_str())
+        .fetch_one(&mut *connection)
+        .await
+        .expect("month offset query failed");
+
+    assert_eq!(by_day, 4);
+    assert_eq!(by_month, 2);
+}
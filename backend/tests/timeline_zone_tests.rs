@@ -0,0 +1,33 @@
+use nimble_photos::models::{TimelineGranularity, TimelineZone};
+
+#[test]
+fn timeline_zone_accepts_iana_names() {
+    let zone = TimelineZone::from_name(Some("Asia/Tokyo"));
+
+    assert_eq!(zone.as_str(), "Asia/Tokyo");
+    assert!(!zone.is_utc());
+    assert_eq!(zone.day_expression("p"), "(p.sort_date AT TIME ZONE tz.name)::date");
+}
+
+#[test]
+fn timeline_zone_falls_back_to_utc_for_invalid_names() {
+    assert!(TimelineZone::from_name(None).is_utc());
+    assert!(TimelineZone::from_name(Some("")).is_utc());
+    assert!(TimelineZone::from_name(Some("Asia/Tokyo'; DROP TABLE photos; --")).is_utc());
+}
+
+#[test]
+fn timeline_zone_uses_precomputed_day_for_utc() {
+    let zone = TimelineZone::from_name(Some("Etc/UTC"));
+
+    assert!(zone.is_utc());
+    assert_eq!(zone.day_expression("c"), "c.day_date");
+}
+
+#[test]
+fn timeline_granularity_defaults_to_day() {
+    assert_eq!(TimelineGranularity::parse(None), Ok(TimelineGranularity::Day));
+    assert_eq!(TimelineGranularity::parse(Some(" Month ")), Ok(TimelineGranularity::Month));
+    assert_eq!(TimelineGranularity::parse(Some("week")), Err(TimelineGranularity::INVALID_GRANULARITY));
+    assert_eq!(TimelineGranularity::Month.period_expression("day_date"), "date_trunc('month', day_date)::date");
+}
@@ -0,0 +1,64 @@
+use chrono::{TimeZone, Utc};
+use nimble_photos::entities::TwoFactor;
+use nimble_photos::models::{BackupCode, Totp};
+use uuid::Uuid;
+
+fn rfc_6238_secret() -> Totp {
+    Totp::from_bytes(b"12345678901234567890")
+}
+
+#[test]
+fn codes_match_the_rfc_6238_sha1_vectors() {
+    let totp = rfc_6238_secret();
+
+    assert_eq!(totp.code_at(Utc.timestamp_opt(59, 0).unwrap()), "287082");
+    assert_eq!(totp.code_at(Utc.timestamp_opt(1_111_111_109, 0).unwrap()), "081804");
+    assert_eq!(totp.code_at(Utc.timestamp_opt(2_000_000_000, 0).unwrap()), "279037");
+}
+
+#[test]
+fn base32_round_trips_and_ignores_case_and_padding() {
+    let totp = rfc_6238_secret();
+
+    assert_eq!(totp.to_base32(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+    assert_eq!(Totp::from_base32("gezd gnbv gy3t qojq gezd gnbv gy3t qojq"), Some(totp.clone()));
+    assert_eq!(Totp::from_base32("MZXW6==="), Some(Totp::from_bytes(b"foo")));
+    assert_eq!(Totp::from_base32("not base32!"), None);
+    assert_eq!(Totp::generate().to_base32().len(), 32);
+}
+
+#[test]
+fn verify_allows_one_step_of_drift_and_reports_the_step() {
+    let totp = rfc_6238_secret();
+    let now = Utc.timestamp_opt(1_111_111_109, 0).unwrap();
+    let step = Totp::step(now);
+
+    assert_eq!(totp.verify("081804", now), Some(step));
+    assert_eq!(totp.verify(&totp.code_for_step(step - 1), now), Some(step - 1));
+    assert_eq!(totp.verify(&totp.code_for_step(step + 1), now), Some(step + 1));
+    assert_eq!(totp.verify(&totp.code_for_step(step + 2), now), None);
+    assert_eq!(totp.verify("08180", now), None);
+}
+
+#[test]
+fn provisioning_uri_names_issuer_and_account() {
+    let uri = rfc_6238_secret().provisioning_uri("My Photos", "me@example.com");
+
+    assert_eq!(
+        uri,
+        "otpauth://totp/My%20Photos:me%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=My%20Photos&algorithm=SHA1&digits=6&period=30"
+    );
+}
+
+#[test]
+fn backup_codes_are_stored_hashed_and_consumed_once() {
+    let codes = BackupCode::generate();
+    let mut record = TwoFactor::new(Uuid::new_v4(), "encrypted".to_string());
+    record.set_backup_codes(&codes);
+
+    assert_eq!(codes.len(), BackupCode::COUNT);
+    assert!(!record.backup_codes.contains(&codes[0]));
+    assert!(record.consume_backup_code(&codes[0].to_uppercase()));
+    assert!(!record.consume_backup_code(&codes[0]));
+    assert_eq!(record.backup_code_hashes().len(), BackupCode::COUNT - 1);
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "postgres")]
+
+mod support;
+
+use chrono::Utc;
+use nimble_photos::entities::TwoFactor;
+use nimble_photos::repositories::TwoFactorExtensions;
+use nimble_photos::services::TwoFactorService;
+use nimble_web::Repository;
+use support::PgTestDatabase;
+use uuid::Uuid;
+
+async fn enabled_record(repo: &Repository<TwoFactor>, codes: &[String]) -> Uuid {
+    let user_id = Uuid::new_v4();
+    let mut record = TwoFactor::new(user_id, "encrypted".to_string());
+    record.enabled = true;
+    record.set_backup_codes(codes);
+    repo.insert(record).await.expect("two-factor record should be inserted");
+    user_id
+}
+
+#[tokio::test]
+async fn parallel_attempts_spend_a_code_or_step_only_once() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let repo = database.repository::<TwoFactor>();
+    let codes = vec!["AAAA-BBBB".to_string(), "CCCC-DDDD".to_string()];
+    let user_id = enabled_record(&repo, &codes).await;
+    let now = Utc::now();
+
+    let (first, second) = tokio::join!(
+        repo.consume_two_factor_backup_code(user_id, &codes[0], now),
+        repo.consume_two_factor_backup_code(user_id, &codes[0], now)
+    );
+    let (step_first, step_second) =
+        tokio::join!(repo.claim_two_factor_step(user_id, 42, now), repo.claim_two_factor_step(user_id, 42, now));
+    let remaining = repo.get(&user_id).await.expect("record should load").expect("record exists");
+
+    assert_ne!(first.expect("first consume"), second.expect("second consume"));
+    assert_ne!(step_first.expect("first claim"), step_second.expect("second claim"));
+    assert_eq!(remaining.backup_code_hashes().len(), 1);
+    assert_eq!(remaining.last_used_step, 42);
+    database.drop().await;
+}
+
+#[tokio::test]
+async fn parallel_failures_all_count_towards_the_lockout() {
+    let Some(database) = PgTestDatabase::create().await else {
+        return;
+    };
+    let repo = database.repository::<TwoFactor>();
+    let user_id = enabled_record(&repo, &[]).await;
+    let now = Utc::now();
+    let max = TwoFactorService::MAX_FAILED_CODES;
+
+    let attempts =
+        (0..max).map(|_| repo.record_failed_two_factor_code(user_id, now, max, TwoFactorService::LOCKOUT_SECONDS));
+    let results = futures_util::future::join_all(attempts).await;
+    let locked = results.into_iter().filter(|result| result.as_ref().expect("failure recorded").is_some()).count();
+    let claimed = repo.claim_two_factor_step(user_id, 7, now).await.expect("claim should run");
+
+    assert_eq!(locked, 1);
+    assert!(!claimed);
+    database.drop().await;
+}
@@ -0,0 +1,121 @@
+use image::{ImageFormat, RgbImage};
+use nimble_photos::entities::StorageLocation;
+use nimble_photos::services::{HashService, UploadSessionError, UploadSessionService};
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+use xxhash_rust::xxh3::xxh3_64;
+
+const CHUNK_SIZE: u64 = 64;
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+fn storage() -> StorageLocation {
+    let root = std::env::temp_dir().join(format!("nimble-upload-session-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&root).unwrap();
+    StorageLocation {
+        id: Uuid::new_v4(),
+        label: "Uploads".to_string(),
+        path: root.to_string_lossy().to_string(),
+        is_default: false,
+        is_readonly: false,
+        created_at: "2026-10-01".to_string(),
+        category_template: "{year}/{fileName}".to_string(),
+    }
+}
+
+fn jpeg() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    RgbImage::new(16, 16).write_to(&mut Cursor::new(&mut bytes), ImageFormat::Jpeg).unwrap();
+    bytes
+}
+
+#[tokio::test]
+async fn chunks_can_arrive_out_of_order_and_complete_with_hash() {
+    let storage = storage();
+    let service = UploadSessionService::new(CHUNK_SIZE, Duration::from_secs(3600));
+    let owner = Uuid::new_v4();
+    let bytes = jpeg();
+
+    let session = service.create(owner, &storage, "photo.jpg", bytes.len() as u64, None, MAX_FILE_SIZE).await.unwrap();
+    let chunks = bytes.chunks(CHUNK_SIZE as usize).enumerate().collect::<Vec<_>>();
+    assert_eq!(session.chunk_count() as usize, chunks.len());
+
+    for (index, chunk) in chunks.iter().rev() {
+        let checksum = format!("{:016x}", xxh3_64(chunk));
+        service.write_chunk(session.id, owner, *index as u32, chunk, Some(&checksum)).await.unwrap();
+    }
+    assert!(service.get(session.id, owner).unwrap().missing_chunks().is_empty());
+
+    let path = session.data_path();
+    let hash = HashService::new().compute(&bytes, bytes.len());
+    let (_, assembled) = service.complete(session.id, owner, Some(&hash)).await.unwrap();
+
+    assert_eq!(assembled, path);
+    assert_eq!(std::fs::read(&assembled).unwrap(), bytes);
+    assert!(matches!(service.get(session.id, owner), Err(UploadSessionError::NotFound)));
+}
+
+#[tokio::test]
+async fn chunk_with_wrong_length_or_checksum_is_rejected() {
+    let storage = storage();
+    let service = UploadSessionService::new(CHUNK_SIZE, Duration::from_secs(3600));
+    let owner = Uuid::new_v4();
+    let session = service.create(owner, &storage, "photo.jpg", 100, None, MAX_FILE_SIZE).await.unwrap();
+
+    let short = service.write_chunk(session.id, owner, 0, &[0u8; 10], None).await;
+    assert!(matches!(short, Err(UploadSessionError::Invalid(_))));
+
+    let mismatch = service.write_chunk(session.id, owner, 1, &[0u8; 36], Some("0000000000000000")).await;
+    assert!(matches!(mismatch, Err(UploadSessionError::Invalid(_))));
+
+    let out_of_range = service.write_chunk(session.id, owner, 2, &[0u8; 36], None).await;
+    assert!(matches!(out_of_range, Err(UploadSessionError::Invalid(_))));
+}
+
+#[tokio::test]
+async fn complete_reports_missing_chunks_and_hides_other_owners() {
+    let storage = storage();
+    let service = UploadSessionService::new(CHUNK_SIZE, Duration::from_secs(3600));
+    let owner = Uuid::new_v4();
+    let session = service.create(owner, &storage, "photo.jpg", 200, None, MAX_FILE_SIZE).await.unwrap();
+    service.write_chunk(session.id, owner, 1, &[0u8; 64], None).await.unwrap();
+
+    match service.complete(session.id, owner, None).await {
+        Err(UploadSessionError::Incomplete(missing)) => assert_eq!(missing, vec![0, 2, 3]),
+        other => panic!("expected incomplete upload, got {:?}", other.map(|(session, _)| session.id)),
+    }
+    assert!(matches!(service.get(session.id, Uuid::new_v4()), Err(UploadSessionError::NotFound)));
+}
+
+#[tokio::test]
+async fn create_rejects_oversized_and_unsupported_files() {
+    let storage = storage();
+    let service = UploadSessionService::new(CHUNK_SIZE, Duration::from_secs(3600));
+    let owner = Uuid::new_v4();
+
+    let oversized = service.create(owner, &storage, "photo.jpg", MAX_FILE_SIZE + 1, None, MAX_FILE_SIZE).await;
+    assert!(matches!(oversized, Err(UploadSessionError::Invalid(_))));
+
+    let unsupported = service.create(owner, &storage, "notes.txt", 10, None, MAX_FILE_SIZE).await;
+    assert!(matches!(unsupported, Err(UploadSessionError::Invalid(_))));
+}
+
+#[tokio::test]
+async fn sweep_removes_expired_sessions_and_orphaned_parts() {
+    let storage = storage();
+    let service = UploadSessionService::new(CHUNK_SIZE, Duration::ZERO);
+    let owner = Uuid::new_v4();
+    let session = service.create(owner, &storage, "photo.jpg", 100, None, MAX_FILE_SIZE).await.unwrap();
+
+    let root = PathBuf::from(&storage.path);
+    let orphan = UploadSessionService::sessions_folder(&root).join(format!("{}.part", Uuid::new_v4()));
+    std::fs::write(&orphan, b"left over").unwrap();
+
+    let removed = service.sweep(&[root]).await;
+
+    assert_eq!(removed, 2);
+    assert!(!session.data_path().exists());
+    assert!(!orphan.exists());
+    assert!(matches!(service.get(session.id, owner), Err(UploadSessionError::NotFound)));
+}
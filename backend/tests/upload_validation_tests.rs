@@ -0,0 +1,53 @@
+use image::{ImageFormat, RgbImage};
+use nimble_photos::models::ImageSignature;
+use nimble_photos::services::PhotoUploadService;
+use std::io::Cursor;
+
+fn encode(format: ImageFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    RgbImage::new(4, 3).write_to(&mut Cursor::new(&mut bytes), format).unwrap();
+    bytes
+}
+
+#[test]
+fn signature_detects_common_formats() {
+    assert_eq!(ImageSignature::detect(&encode(ImageFormat::Jpeg)), Some(ImageSignature::Jpeg));
+    assert_eq!(ImageSignature::detect(&encode(ImageFormat::Png)), Some(ImageSignature::Png));
+    assert_eq!(ImageSignature::detect(b"II*\0\x08\0\0\0"), Some(ImageSignature::Tiff));
+    assert_eq!(ImageSignature::detect(b"\0\0\0\x18ftypheic\0\0\0\0"), Some(ImageSignature::Heif));
+    assert_eq!(ImageSignature::detect(b"hello world"), None);
+}
+
+#[test]
+fn valid_image_is_accepted() {
+    assert_eq!(PhotoUploadService::rejection_reason("photo.jpg", &encode(ImageFormat::Jpeg), true), None);
+    assert_eq!(PhotoUploadService::rejection_reason("photo.PNG", &encode(ImageFormat::Png), true), None);
+}
+
+#[test]
+fn unsupported_extension_is_rejected() {
+    assert_eq!(
+        PhotoUploadService::rejection_reason("notes.txt", b"plain text", true).as_deref(),
+        Some(PhotoUploadService::REJECT_UNSUPPORTED_TYPE)
+    );
+}
+
+#[test]
+fn unknown_content_is_rejected() {
+    assert_eq!(
+        PhotoUploadService::rejection_reason("photo.jpg", b"definitely not an image", true).as_deref(),
+        Some(PhotoUploadService::REJECT_UNKNOWN_CONTENT)
+    );
+}
+
+#[test]
+fn truncated_header_is_rejected_only_when_complete() {
+    let png = encode(ImageFormat::Png);
+    let truncated = &png[..12];
+
+    assert_eq!(
+        PhotoUploadService::rejection_reason("photo.png", truncated, true).as_deref(),
+        Some(PhotoUploadService::REJECT_CORRUPT_HEADER)
+    );
+    assert_eq!(PhotoUploadService::rejection_reason("photo.png", truncated, false), None);
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use nimble_photos::controllers::photo_controller::PhotoController;
+use nimble_photos::controllers::user_settings_controller::UserSettingsController;
+use nimble_photos::dtos::{PhotoCommentDto, UserSettingsDto};
+use nimble_photos::entities::{Photo, PhotoComment, Setting, User, UserSettings};
+use nimble_photos::services::SettingService;
+use nimble_web::{
+    AuthenticationMiddleware, AuthorizationMiddleware, Claims, Configuration, ControllerInvokerMiddleware,
+    DefaultRouter, EndpointExecutionMiddleware, EndpointRegistry, HttpContext, HttpRequest, JwtTokenService,
+    MemoryRepository, Pipeline, Repository, RequestBody, ResponseBody, Router, RoutingMiddleware, ServiceContainer,
+    ServiceProvider, TokenService, UserIdentity,
+};
+
+const USER_ID_STR: &str = "00000000-0000-0000-0000-000000000042";
+
+fn user_id() -> Uuid {
+    Uuid::parse_str(USER_ID_STR).unwrap()
+}
+
+fn services(photos: Vec<Photo>) -> Arc<ServiceProvider> {
+    let settings_repo = MemoryRepository::<UserSettings>::new();
+    settings_repo.seed(vec![UserSettings::new(user_id(), "Original Name".to_string())]);
+    let photo_repo = MemoryRepository::<Photo>::new();
+    photo_repo.seed(photos);
+
+    let mut container = ServiceContainer::new();
+    container
+        .register_singleton::<Repository<UserSettings>, _>(move |_| Repository::new(Box::new(settings_repo.clone())));
+    container.register_singleton::<Repository<User>, _>(|_| Repository::new(Box::new(MemoryRepository::<User>::new())));
+    container.register_singleton::<Repository<Photo>, _>(move |_| Repository::new(Box::new(photo_repo.clone())));
+    container.register_singleton::<Repository<PhotoComment>, _>(|_| {
+        Repository::new(Box::new(MemoryRepository::<PhotoComment>::new()))
+    });
+    container.register_singleton::<SettingService, _>(|_| {
+        SettingService::new(Arc::new(Repository::new(Box::new(MemoryRepository::<Setting>::new()))))
+    });
+    container.register_singleton::<Arc<dyn TokenService>, _>(move |_| {
+        Arc::new(JwtTokenService::new("secret".to_string(), "issuer".to_string())) as Arc<dyn TokenService>
+    });
+    container.build()
+}
+
+fn send(
+    services: &Arc<ServiceProvider>,
+    method: &str,
+    path: &str,
+    body: serde_json::Value,
+    role: &str,
+) -> (u16, String) {
+    let mut registry = EndpointRegistry::new();
+    registry.register::<UserSettingsController>();
+    registry.register::<PhotoController>();
+    let mut router = DefaultRouter::new();
+    for route in registry.routes() {
+        router.add_route(route.clone());
+    }
+
+    let token_service = JwtTokenService::new("secret".to_string(), "issuer".to_string());
+    let identity = UserIdentity::new(USER_ID_STR.to_string(), Claims::new().add_role(role));
+    let token = TokenService::create_access_token(&token_service, &identity).unwrap();
+
+    let mut request = HttpRequest::new(method, path);
+    let header_val = format!("Bearer {}", token);
+    request.headers_mut().insert("authorization", header_val.as_str());
+    request.set_body(RequestBody::Text(body.to_string()));
+
+    let mut context = HttpContext::new(request, Arc::clone(services), Configuration::from_values(HashMap::new()));
+    let mut pipeline = Pipeline::new();
+    pipeline.add(RoutingMiddleware::new(router));
+    pipeline.add(AuthenticationMiddleware::new());
+    pipeline.add(AuthorizationMiddleware::new());
+    pipeline.add(ControllerInvokerMiddleware::new(Arc::new(registry)));
+    pipeline.add(EndpointExecutionMiddleware::new());
+    let _ = pipeline.run(&mut context);
+
+    let response = match context.response().body() {
+        ResponseBody::Text(text) => text.clone(),
+        _ => String::new(),
+    };
+    (context.response().status(), response)
+}
+
+#[test]
+fn user_updates_own_settings_and_new_comments_use_new_name() {
+    let photo = Photo::default();
+    let services = services(vec![photo.clone()]);
+
+    let (status, body) = send(
+        &services,
+        "PUT",
+        "/api/users/me/settings",
+        serde_json::json!({ "displayName": "  Renamed  ", "theme": "Dark", "timezone": "Europe/Paris" }),
+        "viewer",
+    );
+    let settings: UserSettingsDto = serde_json::from_str(&body).expect("settings should be returned");
+
+    assert_eq!(status, 200);
+    assert_eq!(settings.user_id, user_id());
+    assert_eq!(settings.display_name, "Renamed");
+    assert_eq!(settings.theme, "dark");
+    assert_eq!(settings.timezone, "Europe/Paris");
+
+    let (status, body) = send(
+        &services,
+        "POST",
+        &format!("/api/photos/comments/{}", photo.id),
+        serde_json::json!({ "comment": "Lovely" }),
+        "admin",
+    );
+    let comment: PhotoCommentDto = serde_json::from_str(&body).expect("comment should be returned");
+
+    assert_eq!(status, 200);
+    assert_eq!(comment.user_display_name.as_deref(), Some("Renamed"));
+}
+
+#[test]
+fn non_admin_cannot_update_other_users_settings() {
+    let services = services(Vec::new());
+
+    let (status, _) = send(
+        &services,
+        "PUT",
+        &format!("/api/users/{}/settings", Uuid::new_v4()),
+        serde_json::json!({ "displayName": "Hijacked" }),
+        "viewer",
+    );
+
+    assert_eq!(status, 403);
+}
+
+#[test]
+fn invalid_preferences_are_rejected() {
+    let services = services(Vec::new());
+
+    let (theme_status, _) =
+        send(&services, "PUT", "/api/users/me/settings", serde_json::json!({ "theme": "neon" }), "viewer");
+    let (language_status, _) =
+        send(&services, "PUT", "/api/users/me/settings", serde_json::json!({ "language": "xx" }), "viewer");
+    let (timezone_status, _) =
+        send(&services, "PUT", "/api/users/me/settings", serde_json::json!({ "timezone": "Mars; DROP" }), "viewer");
+
+    assert_eq!(theme_status, 400);
+    assert_eq!(language_status, 400);
+    assert_eq!(timezone_status, 400);
+}
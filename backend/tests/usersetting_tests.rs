@@ -29,6 +29,7 @@ fn user_settings_and_profile_dto_conversion() {
         theme: "dark".to_string(),
         language: "en".to_string(),
         timezone: "UTC".to_string(),
+        email_digest: false,
         created_at: Utc::now(),
     };
 
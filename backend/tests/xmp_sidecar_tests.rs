@@ -0,0 +1,336 @@
+use nimble_photos::models::{PersonRegion, XmpSidecar};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+// Written by Lightroom Classic 13 for a rated, labelled and keyworded photo.
+const LIGHTROOM_SIDECAR: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/" x:xmptk="Adobe XMP Core 7.0-c000 1.000000, 0000/00/00-00:00:00        ">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmlns:tiff="http://ns.adobe.com/tiff/1.0/"
+    xmlns:exif="http://ns.adobe.com/exif/1.0/"
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:lr="http://ns.adobe.com/lightroom/1.0/"
+    xmlns:crs="http://ns.adobe.com/camera-raw-settings/1.0/"
+   xmp:ModifyDate="2023-06-11T18:42:07.00+02:00"
+   xmp:Rating="4"
+   xmp:Label="Red"
+   tiff:Make="FUJIFILM"
+   tiff:Model="X-T4"
+   exif:DateTimeOriginal="2023-06-10T09:15:32.00+02:00"
+   crs:Version="15.3"
+   crs:Exposure2012="+0.35">
+   <dc:title>
+    <rdf:Alt>
+     <rdf:li xml:lang="x-default">Morning at the harbour</rdf:li>
+    </rdf:Alt>
+   </dc:title>
+   <dc:description>
+    <rdf:Alt>
+     <rdf:li xml:lang="x-default">Fishing boats &amp; fog before sunrise</rdf:li>
+     <rdf:li xml:lang="de-DE">Fischerboote und Nebel</rdf:li>
+    </rdf:Alt>
+   </dc:description>
+   <dc:subject>
+    <rdf:Bag>
+     <rdf:li>harbour</rdf:li>
+     <rdf:li>Boats</rdf:li>
+     <rdf:li>fog</rdf:li>
+     <rdf:li>boats</rdf:li>
+    </rdf:Bag>
+   </dc:subject>
+   <lr:hierarchicalSubject>
+    <rdf:Bag>
+     <rdf:li>Places|Harbour</rdf:li>
+    </rdf:Bag>
+   </lr:hierarchicalSubject>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+"#;
+
+// Written by digiKam 8 into "photo.jpg.xmp", with rating and keywords as child elements.
+const DIGIKAM_SIDECAR: &str = r#"<?xpacket begin="﻿" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/" x:xmptk="XMP Core 4.4.0-Exiv2">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmlns:digiKam="http://www.digikam.org/ns/1.0/"
+    xmlns:MicrosoftPhoto="http://ns.microsoft.com/photo/1.0/"
+    xmlns:dc="http://purl.org/dc/elements/1.1/">
+   <xmp:Rating>3</xmp:Rating>
+   <MicrosoftPhoto:Rating>50</MicrosoftPhoto:Rating>
+   <digiKam:TagsList>
+    <rdf:Seq>
+     <rdf:li>Family/Anna</rdf:li>
+     <rdf:li>Holiday</rdf:li>
+    </rdf:Seq>
+   </digiKam:TagsList>
+   <dc:subject>
+    <rdf:Bag>
+     <rdf:li>Anna</rdf:li>
+     <rdf:li>Holiday</rdf:li>
+    </rdf:Bag>
+   </dc:subject>
+   <dc:description>
+    <rdf:Alt>
+     <rdf:li xml:lang="x-default">Anna on the beach</rdf:li>
+    </rdf:Alt>
+   </dc:description>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>
+"#;
+
+const REJECTED_SIDECAR: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/" xmp:Rating="-1"/>
+ </rdf:RDF>
+</x:xmpmeta>
+"#;
+
+fn fixture_folder(files: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("nimble-xmp-{}", Uuid::new_v4()));
+    fs::create_dir_all(&root).expect("fixture directory should be created");
+    for (name, content) in files {
+        fs::write(root.join(name), content).expect("fixture file should be written");
+    }
+    root
+}
+
+#[test]
+fn parse_reads_lightroom_attributes_and_elements() {
+    let sidecar = XmpSidecar::parse(LIGHTROOM_SIDECAR).expect("lightroom sidecar should parse");
+
+    assert_eq!(sidecar.rating, Some(4));
+    assert_eq!(sidecar.label.as_deref(), Some("Red"));
+    assert_eq!(sidecar.title.as_deref(), Some("Morning at the harbour"));
+    assert_eq!(sidecar.description.as_deref(), Some("Fishing boats & fog before sunrise"));
+    assert_eq!(sidecar.keywords, vec!["harbour", "Boats", "fog"]);
+}
+
+#[test]
+fn parse_reads_digikam_child_elements() {
+    let sidecar = XmpSidecar::parse(DIGIKAM_SIDECAR).expect("digikam sidecar should parse");
+
+    assert_eq!(sidecar.rating, Some(3));
+    assert_eq!(sidecar.label, None);
+    assert_eq!(sidecar.title, None);
+    assert_eq!(sidecar.description.as_deref(), Some("Anna on the beach"));
+    assert_eq!(sidecar.keywords, vec!["Anna", "Holiday"]);
+}
+
+#[test]
+fn parse_ignores_rejected_rating() {
+    let sidecar = XmpSidecar::parse(REJECTED_SIDECAR).expect("sidecar should parse");
+
+    assert_eq!(sidecar.rating, None);
+    assert!(sidecar.keywords.is_empty());
+}
+
+#[test]
+fn parse_rejects_malformed_xml() {
+    assert!(XmpSidecar::parse("<x:xmpmeta><rdf:RDF></x:xmpmeta>").is_err());
+    assert!(XmpSidecar::parse("<html><body>not xmp</body></html>").is_err());
+    assert!(XmpSidecar::parse("").is_err());
+}
+
+#[test]
+fn candidate_paths_prefer_full_file_name() {
+    let candidates = XmpSidecar::candidate_paths(Path::new("/photos/IMG_0001.CR3"));
+
+    assert_eq!(candidates.first(), Some(&PathBuf::from("/photos/IMG_0001.CR3.xmp")));
+    assert!(candidates.contains(&PathBuf::from("/photos/IMG_0001.xmp")));
+}
+
+#[test]
+fn load_finds_lightroom_sidecar_next_to_image() {
+    let root = fixture_folder(&[("IMG_0001.jpg", ""), ("IMG_0001.xmp", LIGHTROOM_SIDECAR)]);
+
+    let sidecar = XmpSidecar::load(&root.join("IMG_0001.jpg")).expect("sidecar should load");
+    assert_eq!(sidecar.rating, Some(4));
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn load_skips_malformed_sidecar() {
+    let root = fixture_folder(&[("broken.jpg", ""), ("broken.jpg.xmp", "<x:xmpmeta><rdf:RDF>")]);
+
+    assert!(XmpSidecar::load(&root.join("broken.jpg")).is_none());
+    assert!(XmpSidecar::load(&root.join("missing.jpg")).is_none());
+
+    let _ = fs::remove_dir_all(root);
+}
+
+#[test]
+fn update_replaces_keywords_and_caption_but_keeps_other_properties() {
+    let keywords = vec!["harbour".to_string(), "Sunrise & Fog".to_string()];
+    let updated = XmpSidecar::update(LIGHTROOM_SIDECAR, &keywords, Some("Harbour"), Some("New caption"))
+        .expect("sidecar should update");
+
+    let sidecar = XmpSidecar::parse(&updated).expect("updated sidecar should parse");
+    assert_eq!(sidecar.keywords, keywords);
+    assert_eq!(sidecar.title.as_deref(), Some("Harbour"));
+    assert_eq!(sidecar.description.as_deref(), Some("New caption"));
+    assert_eq!(sidecar.rating, Some(4));
+    assert_eq!(sidecar.label.as_deref(), Some("Red"));
+    assert!(updated.contains("Places|Harbour"));
+    assert!(updated.contains("crs:Exposure2012=\"+0.35\""));
+    assert!(!updated.contains("Fischerboote"));
+}
+
+#[test]
+fn update_clears_removed_caption() {
+    let updated =
+        XmpSidecar::update(DIGIKAM_SIDECAR, &["Anna".to_string()], None, None).expect("sidecar should update");
+
+    let sidecar = XmpSidecar::parse(&updated).expect("updated sidecar should parse");
+    assert_eq!(sidecar.keywords, vec!["Anna"]);
+    assert_eq!(sidecar.description, None);
+    assert_eq!(sidecar.rating, Some(3));
+    assert!(updated.contains("Family/Anna"));
+}
+
+#[test]
+fn update_expands_empty_description() {
+    let updated = XmpSidecar::update(REJECTED_SIDECAR, &["reject".to_string()], None, Some("Blurry"))
+        .expect("sidecar should update");
+
+    let sidecar = XmpSidecar::parse(&updated).expect("updated sidecar should parse");
+    assert_eq!(sidecar.keywords, vec!["reject"]);
+    assert_eq!(sidecar.description.as_deref(), Some("Blurry"));
+    assert!(updated.contains("xmp:Rating=\"-1\""));
+}
+
+#[test]
+fn render_creates_parseable_sidecar() {
+    let rendered =
+        XmpSidecar::render(&["a <b>".to_string()], Some("Title"), Some("Caption")).expect("sidecar should render");
+
+    let sidecar = XmpSidecar::parse(&rendered).expect("rendered sidecar should parse");
+    assert_eq!(sidecar.keywords, vec!["a <b>"]);
+    assert_eq!(sidecar.title.as_deref(), Some("Title"));
+    assert_eq!(sidecar.description.as_deref(), Some("Caption"));
+}
+
+// Lightroom face regions: MWG centre-based areas with the name on a nested rdf:Description.
+const MWG_REGIONS_SIDECAR: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:mwg-rs="http://www.metadataworkinggroup.com/schemas/regions/"
+    xmlns:stDim="http://ns.adobe.com/xap/1.0/sType/Dimensions#"
+    xmlns:stArea="http://ns.adobe.com/xmp/sType/Area#">
+   <mwg-rs:Regions rdf:parseType="Resource">
+    <mwg-rs:AppliedToDimensions stDim:w="6000" stDim:h="4000" stDim:unit="pixel"/>
+    <mwg-rs:RegionList>
+     <rdf:Bag>
+      <rdf:li>
+       <rdf:Description mwg-rs:Name="Anna" mwg-rs:Type="Face">
+        <mwg-rs:Area stArea:x="0.5" stArea:y="0.4" stArea:w="0.2" stArea:h="0.3" stArea:unit="normalized"/>
+       </rdf:Description>
+      </rdf:li>
+      <rdf:li rdf:parseType="Resource">
+       <mwg-rs:Name>Bob</mwg-rs:Name>
+       <mwg-rs:Type>Face</mwg-rs:Type>
+       <mwg-rs:Area rdf:parseType="Resource">
+        <stArea:x>0.2</stArea:x>
+        <stArea:y>0.2</stArea:y>
+        <stArea:w>0.1</stArea:w>
+        <stArea:h>0.1</stArea:h>
+        <stArea:unit>normalized</stArea:unit>
+       </mwg-rs:Area>
+      </rdf:li>
+      <rdf:li>
+       <rdf:Description mwg-rs:Name="Rex" mwg-rs:Type="Pet"/>
+      </rdf:li>
+     </rdf:Bag>
+    </mwg-rs:RegionList>
+   </mwg-rs:Regions>
+   <dc:subject>
+    <rdf:Bag>
+     <rdf:li>family</rdf:li>
+    </rdf:Bag>
+   </dc:subject>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+"#;
+
+// Windows Photo Gallery regions: top-left rectangles as "x, y, w, h".
+const MP_REGIONS_SIDECAR: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:MP="http://ns.microsoft.com/photo/1.2/"
+    xmlns:MPRI="http://ns.microsoft.com/photo/1.2/t/RegionInfo#"
+    xmlns:MPReg="http://ns.microsoft.com/photo/1.2/t/Region#">
+   <MP:RegionInfo rdf:parseType="Resource">
+    <MPRI:Regions>
+     <rdf:Bag>
+      <rdf:li MPReg:Rectangle="0.1, 0.2, 0.3, 0.4" MPReg:PersonDisplayName="Carol"/>
+      <rdf:li MPReg:PersonDisplayName="carol"/>
+      <rdf:li MPReg:Rectangle="0.5, 0.5, 0.1, 0.1"/>
+     </rdf:Bag>
+    </MPRI:Regions>
+   </MP:RegionInfo>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+"#;
+
+fn assert_region(region: Option<PersonRegion>, expected: (f32, f32, f32, f32)) {
+    let region = region.expect("region should be present");
+    let actual = [region.x, region.y, region.width, region.height];
+    let expected = [expected.0, expected.1, expected.2, expected.3];
+    assert!(actual.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-5), "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn parse_reads_mwg_face_regions() {
+    let sidecar = XmpSidecar::parse(MWG_REGIONS_SIDECAR).expect("sidecar should parse");
+
+    let names = sidecar.people.iter().map(|person| person.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["Anna", "Bob"]);
+    assert_region(sidecar.people[0].region, (0.4, 0.25, 0.2, 0.3));
+    assert_region(sidecar.people[1].region, (0.15, 0.15, 0.1, 0.1));
+    assert_eq!(sidecar.keywords, vec!["family"]);
+}
+
+#[test]
+fn parse_reads_windows_photo_gallery_regions() {
+    let sidecar = XmpSidecar::parse(MP_REGIONS_SIDECAR).expect("sidecar should parse");
+
+    assert_eq!(sidecar.people.len(), 1);
+    assert_eq!(sidecar.people[0].name, "Carol");
+    assert_region(sidecar.people[0].region, (0.1, 0.2, 0.3, 0.4));
+}
+
+#[test]
+fn update_keeps_face_regions() {
+    let updated = XmpSidecar::update(MWG_REGIONS_SIDECAR, &["family".to_string(), "beach".to_string()], None, None)
+        .expect("sidecar should update");
+
+    let sidecar = XmpSidecar::parse(&updated).expect("updated sidecar should parse");
+    assert_eq!(sidecar.keywords, vec!["family", "beach"]);
+    assert_eq!(sidecar.people.len(), 2);
+}
+
+#[test]
+fn load_embedded_reads_packet_inside_image() {
+    let mut image = vec![0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x10];
+    image.extend_from_slice(b"http://ns.adobe.com/xap/1.0/\0");
+    image.extend_from_slice(MP_REGIONS_SIDECAR.as_bytes());
+    image.extend_from_slice(&[0xFF, 0xD9]);
+    let root = std::env::temp_dir().join(format!("nimble-xmp-{}", Uuid::new_v4()));
+    fs::create_dir_all(&root).expect("fixture directory should be created");
+    fs::write(root.join("IMG_0002.jpg"), image).expect("fixture file should be written");
+
+    let embedded = XmpSidecar::load_embedded(&root.join("IMG_0002.jpg")).expect("embedded packet should load");
+    assert_eq!(embedded.people.len(), 1);
+    assert!(XmpSidecar::load_embedded(&root.join("missing.jpg")).is_none());
+
+    let _ = fs::remove_dir_all(root);
+}
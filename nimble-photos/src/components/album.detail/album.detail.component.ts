@@ -245,7 +245,7 @@ export class AlbumDetailComponent implements OnInit {
 
     private performRemoval(album: Album, photosToRemove: Photo[]) {
         const photoIds = photosToRemove.map(photo => photo.id);
-        this.photoService.removePhotosFromAlbum(album.id, photoIds).subscribe({
+        this.photoService.removePhotosFromAlbum(album.id, photoIds, album.version).subscribe({
             next: () => {
                 this.fetchAlbum(album.id!);
                 this.selectionService.clearSelection();
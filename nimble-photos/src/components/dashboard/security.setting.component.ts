@@ -36,6 +36,7 @@ export class SecuritySettingComponent {
         { key: 'dashboard.access', label: 'Dashboard access' },
         { key: 'settings.general.update', label: 'Update general settings' },
         { key: 'photos.upload', label: 'Upload photos' },
+        { key: 'photos.edit', label: 'Edit photo titles and captions' },
         { key: 'comments.create', label: 'Create comments' },
     ] as const;
 
@@ -107,7 +107,7 @@ export class HeaderActionsComponent {
                         this.router.navigate(['/albums', album.id]);
                         return;
                     }
-                    this.photoService.addPhotosToAlbum(album.id, photoIds).subscribe({
+                    this.photoService.addPhotosToAlbum(album.id, photoIds, album.version).subscribe({
                         next: () => {
                             this.selectionService.clearSelection();
                             this.router.navigate(['/albums', album.id]);
@@ -147,7 +147,7 @@ export class HeaderActionsComponent {
 
         const targetAlbum = result;
         const photoIds = photos.map(photo => photo.id);
-        this.photoService.addPhotosToAlbum(targetAlbum.id!, photoIds).subscribe({
+        this.photoService.addPhotosToAlbum(targetAlbum.id!, photoIds, targetAlbum.version).subscribe({
             next: () => {
                 this.selectionService.clearSelection();
                 this.router.navigate(['/albums', targetAlbum.id]);
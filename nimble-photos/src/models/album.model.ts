@@ -1,12 +1,18 @@
-export interface AlbumModel {
-    id: string;
-    parentId?: string | null;
-    name: string;
-    createDate?: string | null;
+export interface AlbumModel {
+    id: string;
+    parentId?: string | null;
+    name: string;
+    createDate?: string | null;
     description?: string | null;
     category?: string | null;
     kind?: string | null;
     thumbnailHash?: string | null;
     sortOrder?: number | null;
     imageCount?: number | null;
+    version?: number | null;
+    startDate?: string | null;
+    endDate?: string | null;
+    datesOverridden?: boolean;
+    locationName?: string | null;
+    resetDates?: boolean;
 }
@@ -38,3 +38,11 @@ export interface ResetPasswordRequest {
 export interface VerifyEmailRequest {
     token: string;
 }
+
+export type AccountContentAction = 'anonymize' | 'delete';
+
+export interface DeleteAccountRequest {
+    password: string;
+    contentAction: AccountContentAction;
+    refreshToken?: string;
+}
@@ -90,6 +90,7 @@ export interface PhotoMetadata {
   photographicSensitivity?: number;
   interopIndex?: string;
   interopVersion?: string;
+  editedFields?: string[];
 }
 
 export interface Photo {
@@ -97,6 +98,8 @@ export interface Photo {
   storageId: string;
   path: string;
   name: string;
+  title?: string | null;
+  caption?: string | null;
   tags?: string[];
   format?: string;
   hash?: string;
@@ -109,6 +112,8 @@ export interface Photo {
   sortDate?: Date;
   metadataExtracted?: boolean;
   isRaw?: boolean;
+  stackId?: string | null;
+  stackCount?: number;
   width: number;
   height: number;
   metadata?: PhotoMetadata;
@@ -117,8 +122,9 @@ export interface Photo {
 export interface PhotoComment {
   id: string;
   photoId: string;
-  userId: string;
+  userId: string | null;
   userDisplayName?: string;
+  avatarUrl?: string | null;
   body: string;
   createdAt: string;
 }
@@ -126,8 +132,9 @@ export interface PhotoComment {
 export interface AlbumComment {
   id: string;
   albumId: string;
-  userId: string;
+  userId: string | null;
   userDisplayName?: string;
+  avatarUrl?: string | null;
   body: string;
   createdAt: string;
   hidden: boolean;
@@ -158,6 +165,7 @@ export interface Album {
   thumbnailHash?: string;
   sortOrder: number;
   imageCount?: number;
+  version?: number;
   photos?: PagedPhotos;
 }
 
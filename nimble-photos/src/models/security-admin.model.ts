@@ -5,6 +5,16 @@ export interface AdminDashboardUser {
     createdAt: string;
     emailVerified: boolean;
     roles: string[];
+    photoCommentCount?: number;
+    albumCommentCount?: number;
+    lastCommentAt?: string | null;
+}
+
+export interface AdminUserQuery {
+    page?: number;
+    pageSize?: number;
+    q?: string;
+    role?: string;
 }
 
 export interface UpdateUserRolesRequest {
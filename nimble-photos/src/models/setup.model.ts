@@ -0,0 +1,33 @@
+export interface SetupStatus {
+    hasAdmin: boolean;
+    hasStorage: boolean;
+    jwtSecretConfigured: boolean;
+    complete: boolean;
+    incompleteSteps: string[];
+}
+
+export interface SetupInitializeRequest {
+    admin: {
+        email: string;
+        password: string;
+        confirmPassword: string;
+        displayName: string;
+    };
+    storage: {
+        label: string;
+        path: string;
+        categoryTemplate?: string;
+    };
+    settings?: {
+        siteTitle?: string;
+        sitePublic?: boolean;
+        allowRegistration?: boolean;
+    };
+}
+
+export interface SetupInitializeResponse {
+    userId: string;
+    storageId: string;
+    accessToken: string;
+    refreshToken: string;
+}
@@ -3,7 +3,7 @@ import { computed, inject, Injectable, signal } from '@angular/core';
 import { Router } from '@angular/router';
 import { jwtDecode } from 'jwt-decode';
 import { catchError, finalize, map, Observable, shareReplay, switchMap, tap, throwError } from 'rxjs';
-import { LoginRequest, LoginResponse, RegisterRequest, RegistrationStatus } from '../models/auth.model';
+import { DeleteAccountRequest, LoginRequest, LoginResponse, RegisterRequest, RegistrationStatus } from '../models/auth.model';
 import { JwtClaims } from '../models/jwt-claims.model';
 import { User } from '../models/user.model';
 import { API_BASE_URL } from './api.config';
@@ -96,6 +96,13 @@ export class AuthService {
         }
     }
 
+    deleteAccount(request: Omit<DeleteAccountRequest, 'refreshToken'>): Observable<void> {
+        const payload: DeleteAccountRequest = { ...request, refreshToken: this.getRefreshToken() ?? undefined };
+        return this.http
+            .delete<void>(`${this.apiBase}/auth/me`, { body: payload })
+            .pipe(tap(() => this.clearLocalSession()));
+    }
+
     refreshAccessToken(): Observable<string> {
         const refreshToken = this.getRefreshToken();
         if (!refreshToken) {
@@ -124,6 +124,44 @@ export class PhotoService {
     });
   }
 
+  updatePhotoCaption(photoId: string, changes: { title?: string; caption?: string }): Observable<Photo> {
+    return this.http.put<Photo>(`${this.apiBase}/photos/${photoId}/caption`, changes);
+  }
+
+  updatePhotoMetadata(
+    photoId: string,
+    changes: { make?: string; model?: string; lensModel?: string; gpsLatitude?: number; gpsLongitude?: number },
+    writeToFile = false
+  ): Observable<{ metadata: PhotoMetadata; writtenToFile: boolean }> {
+    return this.http.put<{ metadata: PhotoMetadata; writtenToFile: boolean }>(
+      `${this.apiBase}/photos/${photoId}/metadata${writeToFile ? '?writeToFile=true' : ''}`,
+      changes
+    );
+  }
+
+  getPhotoStack(photoId: string): Observable<Photo[]> {
+    return this.http.get<Photo[]>(`${this.apiBase}/photos/${photoId}/stack`);
+  }
+
+  unstackPhoto(photoId: string): Observable<{ unstacked: number }> {
+    return this.http.delete<{ unstacked: number }>(`${this.apiBase}/photos/${photoId}/stack`);
+  }
+
+  downloadOriginal(photoId: string, stripGps?: boolean): Observable<Blob> {
+    const query = stripGps === undefined ? '' : `?stripGps=${stripGps}`;
+    return this.http.get(`${this.apiBase}/photos/${photoId}/original${query}`, { responseType: 'blob' });
+  }
+
+  batchEditPhotos(
+    photoIds: string[],
+    set: { caption?: string; dateTaken?: string; addTags?: string[]; removeTags?: string[] }
+  ): Observable<{ updated: number; results: { photoId: string; success: boolean; error?: string }[] }> {
+    return this.http.post<{ updated: number; results: { photoId: string; success: boolean; error?: string }[] }>(
+      `${this.apiBase}/photos/batch-edit`,
+      { photoIds, set }
+    );
+  }
+
   deletePhotos(photoIds: string[]): Observable<{ deleted: number }> {
     if (!photoIds.length) {
       return of({ deleted: 0 });
@@ -327,13 +365,16 @@ export class PhotoService {
     );
   }
 
-  addPhotosToAlbum(albumId: string, photoIds: string[]): Observable<{ updated: number }> {
-    return this.http.post<{ updated: number }>(`${this.apiBase}/albums/${albumId}/photos`, { photoIds });
+  addPhotosToAlbum(albumId: string, photoIds: string[], version = 1): Observable<{ updated: number; version: number }> {
+    return this.http.post<{ updated: number; version: number }>(`${this.apiBase}/albums/${albumId}/photos`, {
+      photoIds,
+      version
+    });
   }
 
-  removePhotosFromAlbum(albumId: string, photoIds: string[]): Observable<{ updated: number }> {
-    return this.http.delete<{ updated: number }>(`${this.apiBase}/albums/${albumId}/photos`, {
-      body: { photoIds }
+  removePhotosFromAlbum(albumId: string, photoIds: string[], version = 1): Observable<{ updated: number; version: number }> {
+    return this.http.delete<{ updated: number; version: number }>(`${this.apiBase}/albums/${albumId}/photos`, {
+      body: { photoIds, version }
     });
   }
 
@@ -407,6 +448,7 @@ export class PhotoService {
       thumbnailHash: dto.thumbnailHash ?? undefined,
       sortOrder: dto.sortOrder ?? 0,
       imageCount: dto.imageCount ?? undefined,
+      version: dto.version ?? undefined,
     };
   }
 
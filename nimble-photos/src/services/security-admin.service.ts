@@ -1,8 +1,9 @@
-import { HttpClient } from '@angular/common/http';
+import { HttpClient, HttpParams } from '@angular/common/http';
 import { Injectable } from '@angular/core';
-import { Observable } from 'rxjs';
+import { Observable, map } from 'rxjs';
 
-import { AdminDashboardUser, UpdateUserRolesRequest } from '../models/security-admin.model';
+import { PagedModel } from '../models/paged.response.model';
+import { AdminDashboardUser, AdminUserQuery, UpdateUserRolesRequest } from '../models/security-admin.model';
 import { API_BASE_URL } from './api.config';
 
 @Injectable({
@@ -13,8 +14,29 @@ export class SecurityAdminService {
 
     constructor(private readonly http: HttpClient) { }
 
-    getUsers(): Observable<AdminDashboardUser[]> {
-        return this.http.get<AdminDashboardUser[]>(`${this.apiBase}/admin/users`);
+    getUsers(query: AdminUserQuery = {}): Observable<AdminDashboardUser[]> {
+        return this.getUsersPage({ pageSize: 200, ...query }).pipe(map(page => page.items ?? []));
+    }
+
+    getUsersPage(query: AdminUserQuery = {}): Observable<PagedModel<AdminDashboardUser>> {
+        let params = new HttpParams();
+        if (query.page) {
+            params = params.set('page', query.page);
+        }
+        if (query.pageSize) {
+            params = params.set('pageSize', query.pageSize);
+        }
+        if (query.q) {
+            params = params.set('q', query.q);
+        }
+        if (query.role) {
+            params = params.set('role', query.role);
+        }
+        return this.http.get<PagedModel<AdminDashboardUser>>(`${this.apiBase}/admin/users`, { params });
+    }
+
+    getUser(userId: string): Observable<AdminDashboardUser> {
+        return this.http.get<AdminDashboardUser>(`${this.apiBase}/admin/users/${userId}`);
     }
 
     updateUserRoles(userId: string, payload: UpdateUserRolesRequest): Observable<AdminDashboardUser> {
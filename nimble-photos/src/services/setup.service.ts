@@ -0,0 +1,23 @@
+import { HttpClient } from '@angular/common/http';
+import { Injectable } from '@angular/core';
+import { Observable } from 'rxjs';
+
+import { SetupInitializeRequest, SetupInitializeResponse, SetupStatus } from '../models/setup.model';
+import { API_BASE_URL } from './api.config';
+
+@Injectable({
+    providedIn: 'root',
+})
+export class SetupService {
+    private readonly apiBase = API_BASE_URL;
+
+    constructor(private readonly http: HttpClient) { }
+
+    getStatus(): Observable<SetupStatus> {
+        return this.http.get<SetupStatus>(`${this.apiBase}/setup/status`);
+    }
+
+    initialize(request: SetupInitializeRequest): Observable<SetupInitializeResponse> {
+        return this.http.post<SetupInitializeResponse>(`${this.apiBase}/setup/initialize`, request);
+    }
+}
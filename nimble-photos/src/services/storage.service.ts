@@ -38,8 +38,8 @@ export class StorageService {
         return this.http.put<StorageLocation[]>(`${this.apiBase}/storage/locations/${id}/default`, {});
     }
 
-    refreshLocation(id: string): Observable<any> {
+    refreshLocation(id: string, rebuildStale = false): Observable<any> {
         logger.debug(`Refreshing storage location with id: ${id}`);
-        return this.http.post<any>(`${this.apiBase}/storage/scan`, { storageId: id });
+        return this.http.post<any>(`${this.apiBase}/storage/scan`, { storageId: id, rebuildStale });
     }
 }